@@ -0,0 +1,45 @@
+# VoiceCoach backend text - German
+
+## Microphone conflicts (device_conflict.rs)
+
+device-exclusive-renegotiated = Eine andere App hat die exklusive Kontrolle über dieses Mikrofon. VoiceCoach verwendet jetzt das gemeinsam genutzte Format des Geräts; für beste Ergebnisse deaktiviere „Anwendungen haben alleinige Kontrolle über dieses Gerät“ in den Soundeinstellungen des Geräts.
+device-exclusive = Eine andere App (oft eine DAW, ein Spiele-Chat oder ein Konferenztool) hat die exklusive Kontrolle über dieses Mikrofon. Schließe sie oder deaktiviere „Anwendungen haben alleinige Kontrolle über dieses Gerät“ in den Soundeinstellungen des Geräts und versuche es erneut.
+device-unavailable = Das Mikrofon ist nicht verfügbar. Prüfe, ob es angeschlossen und aktiviert ist, und versuche es erneut.
+device-format-renegotiated = Das Mikrofon hat das angeforderte Format abgelehnt, daher verwendet VoiceCoach stattdessen das bevorzugte Format des Geräts.
+device-open-failed = Das Mikrofon konnte nicht geöffnet werden. Prüfe die Einstellungen deines Eingabegeräts und versuche es erneut.
+
+## Rule-based coaching when Ollama is unavailable (ollama_integration.rs)
+
+coaching-price = Begegne Preisbedenken mit Fokus auf Nutzen und ROI. Frage: „Was würde diese Investition für Ihr Unternehmen bedeuten?“
+coaching-think-about-it = Dein Gegenüber braucht Zeit zum Nachdenken. Stelle eine kalibrierte Frage: „Was genau möchten Sie noch durchdenken?“
+coaching-not-interested = Erkenne die Haltung an und hake nach: „Verstehe. Bevor wir auflegen: Was müsste sich ändern, damit das für Sie wertvoll ist?“
+coaching-how = Dein Gegenüber sucht Informationen. Gib eine klare, knappe Antwort und prüfe, ob sie verstanden wurde.
+coaching-active-listening = Höre aktiv zu und stelle offene Fragen, um die Sichtweise deines Gegenübers besser zu verstehen.
+coaching-rule-based = Regelbasierter Vorschlag
+coaching-keep-listening = Weiter aktiv zuhören
+
+## Follow-up email drafted without the model (followup_email.rs)
+
+email-subject = Nachbereitung unseres Gesprächs
+email-subject-company = Nachbereitung unseres Gesprächs - { $company }
+email-greeting = Hallo { $name },
+email-greeting-anonymous = Hallo,
+email-thanks = vielen Dank, dass Sie sich heute Zeit für das Gespräch genommen haben.
+email-covered = Es war schön, über { $topics } zu sprechen.
+email-open-questions = { $count ->
+    [one] Auf die Frage, zu der wir nicht mehr gekommen sind, melde ich mich noch:
+   *[other] Auf die { $count } Fragen, zu denen wir nicht mehr gekommen sind, melde ich mich noch:
+}
+email-next-steps = Nächste Schritte:
+
+## Microphone level watch (mic_quality.rs)
+
+mic-clipping = Dein Mikrofon übersteuert (verzerrt). Halte es etwas weiter vom Mund entfernt oder senke die Eingangsverstärkung in den Soundeinstellungen.
+mic-clipping-adjusted = Dein Mikrofon hat übersteuert (verzerrt), deshalb hat VoiceCoach die Eingangsverstärkung gesenkt. Falls es weiter auftritt, halte das Mikrofon etwas weiter vom Mund entfernt.
+mic-quiet = Deine Stimme ist am Mikrofon sehr leise. Halte das Mikrofon näher an den Mund oder erhöhe die Eingangsverstärkung in den Soundeinstellungen.
+mic-quiet-adjusted = Deine Stimme war am Mikrofon sehr leise, deshalb hat VoiceCoach die Eingangsverstärkung erhöht. Falls es weiter auftritt, halte das Mikrofon näher an den Mund.
+mic-noisy = Die Hintergrundgeräusche sind fast so laut wie deine Stimme. Halte das Mikrofon näher an den Mund, richte es von Lärmquellen weg oder verwende ein Headset-Mikrofon.
+
+## Knowledge base answers (knowledge_answer.rs)
+
+answer-not-found = Dazu habe ich in der Wissensdatenbank keine Antwort gefunden.
@@ -0,0 +1,45 @@
+# VoiceCoach backend text - English (reference locale: every message lives here)
+
+## Microphone conflicts (device_conflict.rs)
+
+device-exclusive-renegotiated = Another app has exclusive control of this microphone. VoiceCoach switched to the device's shared format; for best results disable "Allow applications to take exclusive control" in the device's sound settings.
+device-exclusive = Another app (often a DAW, game chat or conferencing tool) has exclusive control of this microphone. Close it, or disable "Allow applications to take exclusive control" in the device's sound settings, then try again.
+device-unavailable = The microphone is unavailable. Check that it is plugged in and enabled, then try again.
+device-format-renegotiated = The microphone rejected the requested format, so VoiceCoach is using the device's preferred format instead.
+device-open-failed = The microphone could not be opened. Check your input device settings and try again.
+
+## Rule-based coaching when Ollama is unavailable (ollama_integration.rs)
+
+coaching-price = Address price concerns by focusing on value and ROI. Ask: 'What would making this investment mean for your business?'
+coaching-think-about-it = They need time to process. Use a calibrated question: 'What specifically would you like to think through?'
+coaching-not-interested = Acknowledge their position and explore: 'I understand. Before we end, what would have to change for this to be valuable to you?'
+coaching-how = They're seeking information. Provide a clear, concise answer and check understanding.
+coaching-active-listening = Listen actively and ask open-ended questions to understand their perspective better.
+coaching-rule-based = Rule-based suggestion
+coaching-keep-listening = Continue active listening
+
+## Follow-up email drafted without the model (followup_email.rs)
+
+email-subject = Following up on our call
+email-subject-company = Following up on our call - { $company }
+email-greeting = Hi { $name },
+email-greeting-anonymous = Hi,
+email-thanks = Thanks for taking the time to speak with me today.
+email-covered = It was great to talk through { $topics }.
+email-open-questions = { $count ->
+    [one] I'll get back to you on the question we didn't get to:
+   *[other] I'll get back to you on the { $count } questions we didn't get to:
+}
+email-next-steps = Next steps:
+
+## Microphone level watch (mic_quality.rs)
+
+mic-clipping = Your microphone is clipping (distorting). Move it a little further from your mouth or lower its input gain in the sound settings.
+mic-clipping-adjusted = Your microphone was clipping (distorting), so VoiceCoach lowered its input gain. If it continues, move the mic a little further from your mouth.
+mic-quiet = Your voice is very quiet on the microphone. Move the mic closer to your mouth or raise its input gain in the sound settings.
+mic-quiet-adjusted = Your voice was very quiet on the microphone, so VoiceCoach raised its input gain. If it continues, move the mic closer to your mouth.
+mic-noisy = Background noise is almost as loud as your voice. Move the mic closer to your mouth, point it away from noise sources, or use a headset microphone.
+
+## Knowledge base answers (knowledge_answer.rs)
+
+answer-not-found = I couldn't find an answer to that in the knowledge base.
@@ -0,0 +1,45 @@
+# VoiceCoach backend text - Spanish
+
+## Microphone conflicts (device_conflict.rs)
+
+device-exclusive-renegotiated = Otra aplicación tiene el control exclusivo de este micrófono. VoiceCoach ha pasado al formato compartido del dispositivo; para obtener los mejores resultados, desactiva «Permitir que las aplicaciones tomen el control exclusivo» en la configuración de sonido del dispositivo.
+device-exclusive = Otra aplicación (a menudo un DAW, un chat de juegos o una herramienta de videoconferencia) tiene el control exclusivo de este micrófono. Ciérrala o desactiva «Permitir que las aplicaciones tomen el control exclusivo» en la configuración de sonido del dispositivo y vuelve a intentarlo.
+device-unavailable = El micrófono no está disponible. Comprueba que está conectado y activado y vuelve a intentarlo.
+device-format-renegotiated = El micrófono rechazó el formato solicitado, así que VoiceCoach usa en su lugar el formato preferido del dispositivo.
+device-open-failed = No se pudo abrir el micrófono. Revisa la configuración del dispositivo de entrada y vuelve a intentarlo.
+
+## Rule-based coaching when Ollama is unavailable (ollama_integration.rs)
+
+coaching-price = Responde a las dudas sobre el precio centrándote en el valor y el retorno de la inversión. Pregunta: «¿Qué supondría esta inversión para su negocio?»
+coaching-think-about-it = Necesita tiempo para asimilarlo. Usa una pregunta calibrada: «¿Qué es exactamente lo que le gustaría pensar?»
+coaching-not-interested = Reconoce su postura y explora: «Lo entiendo. Antes de terminar, ¿qué tendría que cambiar para que esto le resultara útil?»
+coaching-how = Está buscando información. Da una respuesta clara y concisa y comprueba que se ha entendido.
+coaching-active-listening = Escucha activamente y haz preguntas abiertas para entender mejor su punto de vista.
+coaching-rule-based = Sugerencia basada en reglas
+coaching-keep-listening = Sigue escuchando activamente
+
+## Follow-up email drafted without the model (followup_email.rs)
+
+email-subject = Seguimiento de nuestra llamada
+email-subject-company = Seguimiento de nuestra llamada - { $company }
+email-greeting = Hola, { $name }:
+email-greeting-anonymous = Hola:
+email-thanks = Gracias por dedicarme su tiempo hoy.
+email-covered = Fue un placer hablar sobre { $topics }.
+email-open-questions = { $count ->
+    [one] Le responderé a la pregunta que no llegamos a tratar:
+   *[other] Le responderé a las { $count } preguntas que no llegamos a tratar:
+}
+email-next-steps = Próximos pasos:
+
+## Microphone level watch (mic_quality.rs)
+
+mic-clipping = Tu micrófono está saturando (distorsiona). Aléjalo un poco de la boca o baja su ganancia de entrada en la configuración de sonido.
+mic-clipping-adjusted = Tu micrófono estaba saturando (distorsionaba), así que VoiceCoach ha bajado su ganancia de entrada. Si continúa, aleja el micrófono un poco de la boca.
+mic-quiet = Tu voz llega muy baja al micrófono. Acerca el micrófono a la boca o sube su ganancia de entrada en la configuración de sonido.
+mic-quiet-adjusted = Tu voz llegaba muy baja al micrófono, así que VoiceCoach ha subido su ganancia de entrada. Si continúa, acerca el micrófono a la boca.
+mic-noisy = El ruido de fondo es casi tan fuerte como tu voz. Acerca el micrófono a la boca, oriéntalo lejos de las fuentes de ruido o usa un micrófono de auriculares.
+
+## Knowledge base answers (knowledge_answer.rs)
+
+answer-not-found = No he encontrado una respuesta a eso en la base de conocimiento.
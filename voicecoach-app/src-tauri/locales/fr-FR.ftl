@@ -0,0 +1,45 @@
+# VoiceCoach backend text - French
+
+## Microphone conflicts (device_conflict.rs)
+
+device-exclusive-renegotiated = Une autre application contrôle ce microphone en mode exclusif. VoiceCoach est passé au format partagé du périphérique ; pour de meilleurs résultats, désactivez « Autoriser les applications à prendre le contrôle exclusif de ce périphérique » dans les paramètres de son du périphérique.
+device-exclusive = Une autre application (souvent une STAN, un chat de jeu ou un outil de visioconférence) contrôle ce microphone en mode exclusif. Fermez-la ou désactivez « Autoriser les applications à prendre le contrôle exclusif de ce périphérique » dans les paramètres de son du périphérique, puis réessayez.
+device-unavailable = Le microphone n'est pas disponible. Vérifiez qu'il est branché et activé, puis réessayez.
+device-format-renegotiated = Le microphone a refusé le format demandé : VoiceCoach utilise à la place le format préféré du périphérique.
+device-open-failed = Impossible d'ouvrir le microphone. Vérifiez les paramètres de votre périphérique d'entrée, puis réessayez.
+
+## Rule-based coaching when Ollama is unavailable (ollama_integration.rs)
+
+coaching-price = Répondez aux objections sur le prix en mettant l'accent sur la valeur et le retour sur investissement. Demandez : « Que représenterait cet investissement pour votre entreprise ? »
+coaching-think-about-it = Votre interlocuteur a besoin de temps pour réfléchir. Posez une question calibrée : « Sur quel point précis souhaitez-vous réfléchir ? »
+coaching-not-interested = Prenez acte de sa position et creusez : « Je comprends. Avant de conclure, qu'est-ce qui devrait changer pour que cela ait de la valeur pour vous ? »
+coaching-how = Votre interlocuteur cherche des informations. Donnez une réponse claire et concise, puis vérifiez qu'elle a été comprise.
+coaching-active-listening = Écoutez activement et posez des questions ouvertes pour mieux comprendre son point de vue.
+coaching-rule-based = Suggestion basée sur des règles
+coaching-keep-listening = Continuer l'écoute active
+
+## Follow-up email drafted without the model (followup_email.rs)
+
+email-subject = Suite à notre appel
+email-subject-company = Suite à notre appel - { $company }
+email-greeting = Bonjour { $name },
+email-greeting-anonymous = Bonjour,
+email-thanks = Merci d'avoir pris le temps d'échanger avec moi aujourd'hui.
+email-covered = J'ai été ravi de parler de { $topics }.
+email-open-questions = { $count ->
+    [one] Je reviens vers vous concernant la question que nous n'avons pas pu aborder :
+   *[other] Je reviens vers vous concernant les { $count } questions que nous n'avons pas pu aborder :
+}
+email-next-steps = Prochaines étapes :
+
+## Microphone level watch (mic_quality.rs)
+
+mic-clipping = Votre micro sature (distorsion). Éloignez-le un peu de votre bouche ou baissez son gain d'entrée dans les paramètres audio.
+mic-clipping-adjusted = Votre micro saturait (distorsion), VoiceCoach a donc baissé son gain d'entrée. Si cela continue, éloignez un peu le micro de votre bouche.
+mic-quiet = Votre voix est très faible au micro. Rapprochez le micro de votre bouche ou augmentez son gain d'entrée dans les paramètres audio.
+mic-quiet-adjusted = Votre voix était très faible au micro, VoiceCoach a donc augmenté son gain d'entrée. Si cela continue, rapprochez le micro de votre bouche.
+mic-noisy = Le bruit de fond est presque aussi fort que votre voix. Rapprochez le micro de votre bouche, éloignez-le des sources de bruit ou utilisez un micro-casque.
+
+## Knowledge base answers (knowledge_answer.rs)
+
+answer-not-found = Je n'ai pas trouvé de réponse à cette question dans la base de connaissances.
@@ -0,0 +1,413 @@
+// Acceleration - GPU discovery and which Whisper model sizes this machine can run
+// Probed on first use (and again on request): CUDA through nvidia-smi (any OS; name,
+// total and free VRAM), DirectML on Windows 10 1903+ (DirectML.dll present, adapters
+// from Win32_VideoController - whose AdapterRAM tops out at 4 GB) and Metal on macOS
+// (system_profiler; Apple silicon shares system memory with the GPU, of which about
+// two thirds can be used for GPU work). Each Whisper size is matched with the fastest
+// backend it fits on - memory as whisper.cpp needs it, with headroom - and whether it
+// keeps up with a live call there; the CPU takes what fits in RAM, live only for the
+// sizes its cores and vector units decode in real time (hardware_profile). The
+// recommended model is the largest one that runs live. The Whisper backend settings
+// (model size and device, both automatic by default) are resolved against this report
+// by backend_choice: a configured device that is missing or too small for the model
+// falls back to the best one, with the reason.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::info;
+
+use crate::hardware_profile::HardwareProfile;
+
+// Share of unified memory a Mac lets the GPU work with
+const UNIFIED_GPU_SHARE: f64 = 2.0 / 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum AccelerationBackend {
+    Cuda,
+    Metal,
+    DirectMl,
+    Cpu,
+}
+
+// Fastest first
+const GPU_BACKENDS: [AccelerationBackend; 3] = [AccelerationBackend::Cuda, AccelerationBackend::Metal, AccelerationBackend::DirectMl];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum WhisperModelSize {
+    Tiny,
+    Base,
+    Small,
+    Medium,
+    Large,
+}
+
+const WHISPER_SIZES: [WhisperModelSize; 5] = [
+    WhisperModelSize::Tiny, WhisperModelSize::Base, WhisperModelSize::Small, WhisperModelSize::Medium, WhisperModelSize::Large,
+];
+
+impl WhisperModelSize {
+    /// Memory to load and run the model (whisper.cpp figures plus headroom)
+    fn required_mb(self) -> u64 {
+        match self {
+            WhisperModelSize::Tiny => 400,
+            WhisperModelSize::Base => 500,
+            WhisperModelSize::Small => 1_100,
+            WhisperModelSize::Medium => 2_600,
+            WhisperModelSize::Large => 4_700,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct GpuDevice {
+    pub backend: AccelerationBackend,
+    pub name: String,
+    pub vram_mb: Option<u64>,          // Usable by the GPU (a share of RAM where unified)
+    pub free_vram_mb: Option<u64>,     // Where the driver reports it
+}
+
+impl GpuDevice {
+    fn usable_mb(&self) -> Option<u64> {
+        self.free_vram_mb.or(self.vram_mb)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct WhisperFeasibility {
+    pub model: WhisperModelSize,
+    pub required_mb: u64,
+    pub backend: Option<AccelerationBackend>,  // Fastest it fits on; None = fits nowhere
+    pub realtime: bool,                        // Keeps up with a live call there
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct AccelerationReport {
+    pub backends: Vec<AccelerationBackend>,    // Available, fastest first; the CPU last
+    pub gpus: Vec<GpuDevice>,
+    pub models: Vec<WhisperFeasibility>,
+    pub recommended_model: WhisperModelSize,
+    pub recommended_backend: AccelerationBackend,
+    pub rationale: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct WhisperBackendSettings {
+    /// None = the recommended size
+    #[serde(default)]
+    pub model: Option<WhisperModelSize>,
+    /// None = the fastest device the model fits on
+    #[serde(default)]
+    pub device: Option<AccelerationBackend>,
+}
+
+// What the Whisper backend runs with
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct WhisperBackendChoice {
+    pub settings: WhisperBackendSettings,
+    pub model: WhisperModelSize,
+    pub device: AccelerationBackend,
+    pub realtime: bool,
+    pub fallback: Option<String>,    // Why the configured device isn't used
+}
+
+static REPORT: Lazy<Mutex<Option<AccelerationReport>>> = Lazy::new(|| Mutex::new(None));
+
+fn megabytes(value: &str) -> Option<u64> {
+    let mut parts = value.split_whitespace();
+    let number: f64 = parts.next()?.parse().ok()?;
+    let factor = match parts.next().map(|u| u.to_ascii_uppercase()) {
+        Some(unit) if unit == "GB" => 1024.0,
+        Some(unit) if unit == "MB" => 1.0,
+        None => 1.0,
+        _ => return None,
+    };
+    Some((number * factor) as u64)
+}
+
+/// `nvidia-smi --query-gpu=name,memory.total,memory.free --format=csv,noheader,nounits`
+fn parse_nvidia_smi(output: &str) -> Vec<GpuDevice> {
+    output.lines().filter_map(|line| {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() < 3 || fields[0].is_empty() {
+            return None;
+        }
+        Some(GpuDevice {
+            backend: AccelerationBackend::Cuda,
+            name: fields[0].to_string(),
+            vram_mb: megabytes(fields[1]),
+            free_vram_mb: megabytes(fields[2]),
+        })
+    }).collect()
+}
+
+/// `system_profiler SPDisplaysDataType`: the Metal-capable GPUs
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_system_profiler(output: &str, total_memory_mb: Option<u64>) -> Vec<GpuDevice> {
+    let mut gpus: Vec<(GpuDevice, bool)> = Vec::new();
+    for line in output.lines().map(str::trim) {
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        if key == "Chipset Model" {
+            // Apple silicon has no VRAM line: the GPU shares system memory
+            let unified = value.starts_with("Apple");
+            let vram_mb = unified.then(|| total_memory_mb.map(|mb| (mb as f64 * UNIFIED_GPU_SHARE) as u64)).flatten();
+            gpus.push((GpuDevice { backend: AccelerationBackend::Metal, name: value.to_string(), vram_mb, free_vram_mb: None }, unified));
+        } else if let Some((gpu, metal)) = gpus.last_mut() {
+            if key.starts_with("VRAM") {
+                gpu.vram_mb = megabytes(value).or(gpu.vram_mb);
+            } else if key.starts_with("Metal") {
+                *metal = !value.to_ascii_lowercase().contains("not supported");
+            }
+        }
+    }
+    gpus.into_iter().filter(|(_, metal)| *metal).map(|(gpu, _)| gpu).collect()
+}
+
+/// `Win32_VideoController` as "name|AdapterRAM bytes" lines
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_video_controllers(output: &str) -> Vec<GpuDevice> {
+    output.lines().filter_map(|line| {
+        let (name, bytes) = line.trim().split_once('|')?;
+        let name = name.trim();
+        if name.is_empty() || name.contains("Basic Display") || name.contains("Remote Display") {
+            return None;
+        }
+        Some(GpuDevice {
+            backend: AccelerationBackend::DirectMl,
+            name: name.to_string(),
+            vram_mb: bytes.trim().parse::<u64>().ok().filter(|b| *b > 0).map(|b| b / (1024 * 1024)),
+            free_vram_mb: None,
+        })
+    }).collect()
+}
+
+/// Whether the CPU decodes this size as fast as a call goes
+fn cpu_realtime(cpu: &HardwareProfile, model: WhisperModelSize) -> bool {
+    let has = |feature: &str| cpu.cpu_features.iter().any(|f| f == feature);
+    let simd = has("avx2") || has("avx512f") || has("neon");
+    match model {
+        WhisperModelSize::Tiny => simd || cpu.logical_cores >= 4,
+        WhisperModelSize::Base => simd && cpu.logical_cores >= 4,
+        WhisperModelSize::Small => simd && cpu.logical_cores >= 8,
+        WhisperModelSize::Medium | WhisperModelSize::Large => false,
+    }
+}
+
+/// Whether a model fits on a backend, and runs live there
+fn fits(cpu: &HardwareProfile, gpus: &[GpuDevice], backend: AccelerationBackend, model: WhisperModelSize) -> Option<bool> {
+    let required = model.required_mb();
+    match backend {
+        AccelerationBackend::Cpu => {
+            let memory = cpu.available_memory_mb.or(cpu.total_memory_mb);
+            memory.map_or(true, |mb| mb >= required).then(|| cpu_realtime(cpu, model))
+        }
+        // DirectML is a generic path: the large model is too slow on it for a live call
+        gpu_backend => gpus.iter()
+            .filter(|g| g.backend == gpu_backend)
+            .any(|g| g.usable_mb().map_or(false, |mb| mb >= required))
+            .then(|| !(gpu_backend == AccelerationBackend::DirectMl && model == WhisperModelSize::Large)),
+    }
+}
+
+fn assess(cpu: &HardwareProfile, gpus: Vec<GpuDevice>) -> AccelerationReport {
+    let mut backends: Vec<AccelerationBackend> = GPU_BACKENDS.iter().copied()
+        .filter(|b| gpus.iter().any(|g| g.backend == *b))
+        .collect();
+    backends.push(AccelerationBackend::Cpu);
+
+    let models: Vec<WhisperFeasibility> = WHISPER_SIZES.iter().map(|&model| {
+        // The first backend it runs live on, else the first it fits on
+        let options: Vec<(AccelerationBackend, bool)> = backends.iter()
+            .filter_map(|&b| fits(cpu, &gpus, b, model).map(|live| (b, live)))
+            .collect();
+        let best = options.iter().find(|(_, live)| *live).or_else(|| options.first());
+        WhisperFeasibility { model, required_mb: model.required_mb(), backend: best.map(|(b, _)| *b), realtime: best.map_or(false, |(_, live)| *live) }
+    }).collect();
+
+    let mut rationale: Vec<String> = gpus.iter().map(|g| match g.vram_mb {
+        Some(mb) => format!("{:?}: {} ({} MB)", g.backend, g.name, mb),
+        None => format!("{:?}: {} (memory unknown)", g.backend, g.name),
+    }).collect();
+    if gpus.is_empty() {
+        rationale.push("No GPU acceleration found: Whisper runs on the CPU".to_string());
+    }
+    let (recommended_model, recommended_backend) = match models.iter().rev().find(|m| m.realtime) {
+        Some(m) => {
+            rationale.push(format!("{:?} is the largest model that keeps up with a live call", m.model));
+            (m.model, m.backend.unwrap_or(AccelerationBackend::Cpu))
+        }
+        None => {
+            rationale.push("No model keeps up with a live call here: tiny is the least behind".to_string());
+            (WhisperModelSize::Tiny, AccelerationBackend::Cpu)
+        }
+    };
+    AccelerationReport { backends, gpus, models, recommended_model, recommended_backend, rationale }
+}
+
+/// Resolve the Whisper settings against what the machine has
+fn choose(settings: &WhisperBackendSettings, report: &AccelerationReport, cpu: &HardwareProfile) -> WhisperBackendChoice {
+    let model = settings.model.unwrap_or(report.recommended_model);
+    let best = report.models.iter().find(|m| m.model == model);
+    let auto = (best.and_then(|m| m.backend).unwrap_or(AccelerationBackend::Cpu), best.map_or(false, |m| m.realtime));
+    let ((device, realtime), fallback) = match settings.device {
+        None => (auto, None),
+        Some(device) if !report.backends.contains(&device) => (auto, Some(format!("No {:?} device on this machine", device))),
+        Some(device) => match fits(cpu, &report.gpus, device, model) {
+            Some(live) => ((device, live), None),
+            None => (auto, Some(format!("The {:?} model needs {} MB, more than {:?} has", model, model.required_mb(), device))),
+        },
+    };
+    WhisperBackendChoice { settings: settings.clone(), model, device, realtime, fallback }
+}
+
+/// Run a probe tool, its output if it succeeded
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = command.output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[allow(unused_mut, unused_variables)]
+fn probe(cpu: &HardwareProfile) -> Vec<GpuDevice> {
+    let mut gpus = run("nvidia-smi", &["--query-gpu=name,memory.total,memory.free", "--format=csv,noheader,nounits"])
+        .map(|out| parse_nvidia_smi(&out))
+        .unwrap_or_default();
+    #[cfg(windows)]
+    {
+        let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+        if std::path::Path::new(&system_root).join("System32").join("DirectML.dll").exists() {
+            let query = "Get-CimInstance Win32_VideoController | ForEach-Object { \"$($_.Name)|$($_.AdapterRAM)\" }";
+            let adapters = run("powershell", &["-NoProfile", "-Command", query]).map(|out| parse_video_controllers(&out)).unwrap_or_default();
+            // AdapterRAM stops at 4 GB: nvidia-smi knows better for the same card
+            gpus.extend(adapters.into_iter().map(|mut adapter| {
+                if let Some(cuda) = gpus.iter().find(|g| g.name == adapter.name) {
+                    adapter.vram_mb = cuda.vram_mb;
+                }
+                adapter
+            }).collect::<Vec<_>>());
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(out) = run("system_profiler", &["SPDisplaysDataType"]) {
+            gpus.extend(parse_system_profiler(&out, cpu.total_memory_mb));
+        }
+    }
+    gpus
+}
+
+/// This machine's acceleration report, probed on first use
+pub fn report() -> AccelerationReport {
+    let mut cached = REPORT.lock().unwrap();
+    if let Some(report) = cached.as_ref() {
+        return report.clone();
+    }
+    let cpu = crate::hardware_profile::profile();
+    let report = assess(&cpu, probe(&cpu));
+    info!("🎮 Acceleration: {:?}; Whisper {:?} on {:?} recommended",
+        report.backends, report.recommended_model, report.recommended_backend);
+    *cached = Some(report.clone());
+    report
+}
+
+/// Model and device the Whisper backend runs with
+pub fn backend_choice() -> WhisperBackendChoice {
+    choose(&crate::preferences::load().whisper_backend, &report(), &crate::hardware_profile::profile())
+}
+
+// ========== Tauri Commands ==========
+
+// GPU backends, VRAM and the Whisper sizes this machine runs (probed again with refresh)
+#[tauri::command]
+pub async fn detect_acceleration(refresh: Option<bool>) -> Result<AccelerationReport, String> {
+    tokio::task::spawn_blocking(move || {
+        if refresh.unwrap_or(false) {
+            *REPORT.lock().unwrap() = None;
+        }
+        report()
+    })
+        .await
+        .map_err(|e| format!("Acceleration probe failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_whisper_backend() -> Result<WhisperBackendChoice, String> {
+    tokio::task::spawn_blocking(backend_choice)
+        .await
+        .map_err(|e| format!("Acceleration probe failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_whisper_backend(settings: WhisperBackendSettings) -> Result<WhisperBackendChoice, String> {
+    crate::preferences::update(|p| p.whisper_backend = settings.clone())
+        .map_err(|e| e.to_string())?;
+    get_whisper_backend().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpu(features: &[&str], cores: usize, available: Option<u64>) -> HardwareProfile {
+        HardwareProfile {
+            arch: "x86_64".to_string(),
+            cpu_features: features.iter().map(|f| f.to_string()).collect(),
+            logical_cores: cores,
+            total_memory_mb: Some(16_384),
+            available_memory_mb: available,
+        }
+    }
+
+    #[test]
+    fn test_gpus_decide_the_whisper_model_and_device() {
+        let nvidia = parse_nvidia_smi("NVIDIA GeForce RTX 3060, 12288, 3000\nQuadro T1000, 4096, 3900\n");
+        assert_eq!(nvidia.len(), 2);
+        assert_eq!((nvidia[0].vram_mb, nvidia[0].usable_mb()), (Some(12_288), Some(3_000)));
+
+        let mac = "Graphics/Displays:\n\n    Apple M2:\n\n      Chipset Model: Apple M2\n      Type: GPU\n      Metal Support: Metal 3\n";
+        let metal = parse_system_profiler(mac, Some(16_384));
+        assert_eq!((metal.len(), metal[0].vram_mb), (1, Some(10_922)));
+        let old_mac = "      Chipset Model: Intel HD Graphics 3000\n      VRAM (Dynamic, Max): 512 MB\n      Metal Support: Not Supported\n";
+        assert!(parse_system_profiler(old_mac, Some(8_192)).is_empty());
+        let windows = parse_video_controllers("Microsoft Basic Display Adapter|0\nAMD Radeon RX 580|4293918720\n");
+        assert_eq!((windows.len(), windows[0].vram_mb), (1, Some(4_095)));
+
+        // 3 GB free on the 3060: medium on CUDA, large only on the CPU and not live
+        let workstation = cpu(&["avx2"], 8, Some(12_000));
+        let report = assess(&workstation, nvidia);
+        assert_eq!(report.backends, vec![AccelerationBackend::Cuda, AccelerationBackend::Cpu]);
+        assert_eq!((report.recommended_model, report.recommended_backend), (WhisperModelSize::Medium, AccelerationBackend::Cuda));
+        let large = &report.models[4];
+        assert_eq!((large.backend, large.realtime), (Some(AccelerationBackend::Cpu), false));
+
+        // A configured device the machine lacks, or too small for the model, falls back
+        let settings = WhisperBackendSettings { model: Some(WhisperModelSize::Large), device: Some(AccelerationBackend::Cuda) };
+        let choice = choose(&settings, &report, &workstation);
+        assert_eq!(choice.device, AccelerationBackend::Cpu);
+        assert!(choice.fallback.unwrap().contains("4700 MB"));
+        let metal_settings = WhisperBackendSettings { model: None, device: Some(AccelerationBackend::Metal) };
+        assert_eq!(choose(&metal_settings, &report, &workstation).device, AccelerationBackend::Cuda);
+        let cpu_settings = WhisperBackendSettings { model: Some(WhisperModelSize::Small), device: Some(AccelerationBackend::Cpu) };
+        let on_cpu = choose(&cpu_settings, &report, &workstation);
+        assert_eq!((on_cpu.device, on_cpu.realtime, on_cpu.fallback), (AccelerationBackend::Cpu, true, None));
+
+        // No GPU and an old CPU: tiny, and not live
+        let old = assess(&cpu(&[], 2, Some(1_000)), Vec::new());
+        assert_eq!((old.recommended_model, old.recommended_backend), (WhisperModelSize::Tiny, AccelerationBackend::Cpu));
+        assert!(old.models.iter().all(|m| !m.realtime));
+        assert_eq!(old.models[3].backend, None);
+    }
+}
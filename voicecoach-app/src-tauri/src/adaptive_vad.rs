@@ -0,0 +1,266 @@
+// Adaptive VAD - per-call voice activity threshold learned from the noise floor
+// A fixed threshold (configured, or from the level calibration wizard) misclassifies
+// speech on quiet mics and background noise in loud rooms. Each capture callback owns
+// an AdaptiveVad: 20ms frame levels are summarized per second (the second's quietest
+// tenth is its floor) and, once learn_seconds of audio have been heard, the noise
+// floor - a low percentile of the last WINDOW_SECS per-second floors, so speech
+// doesn't lift it - plus margin_db becomes the threshold, updated every second for the
+// rest of the call. Until then the fixed threshold applies. A new call (begin_call)
+// starts learning over. The learned values are published per source for the quality
+// report (get_mic_quality).
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use log::info;
+
+const FRAME_MS: usize = 20;
+// Seconds of per-second floors the noise floor is taken from
+const WINDOW_SECS: usize = 60;
+// Percentile of the per-second floors taken as the noise floor
+const FLOOR_PERCENTILE: f32 = 0.2;
+// Bounds on a learned threshold (RMS, full scale = 1.0)
+const MIN_THRESHOLD: f32 = 0.001;
+const MAX_THRESHOLD: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VadSource {
+    Microphone,
+    SystemAudio,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct AdaptiveVadSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Audio heard before the learned threshold replaces the fixed one
+    #[serde(default = "default_learn_seconds")]
+    pub learn_seconds: u32,
+    /// Threshold above the noise floor
+    #[serde(default = "default_margin_db")]
+    pub margin_db: f32,
+}
+
+fn default_true() -> bool { true }
+fn default_learn_seconds() -> u32 { 30 }
+fn default_margin_db() -> f32 { 9.0 }
+
+impl Default for AdaptiveVadSettings {
+    fn default() -> Self {
+        Self { enabled: true, learn_seconds: default_learn_seconds(), margin_db: default_margin_db() }
+    }
+}
+
+// Learned threshold of one source (quality report)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LearnedThreshold {
+    pub source: VadSource,
+    pub seconds_heard: u32,
+    pub noise_floor: Option<f32>,
+    pub threshold: Option<f32>,  // None while still learning
+}
+
+// Published per source: f32 bits (0 = none) and seconds heard
+struct Published {
+    noise_floor: AtomicU32,
+    threshold: AtomicU32,
+    seconds: AtomicU32,
+}
+
+impl Published {
+    const fn new() -> Self {
+        Self { noise_floor: AtomicU32::new(0), threshold: AtomicU32::new(0), seconds: AtomicU32::new(0) }
+    }
+
+    fn clear(&self) {
+        self.noise_floor.store(0, Ordering::Relaxed);
+        self.threshold.store(0, Ordering::Relaxed);
+        self.seconds.store(0, Ordering::Relaxed);
+    }
+}
+
+static MICROPHONE: Published = Published::new();
+static SYSTEM_AUDIO: Published = Published::new();
+// Bumped on every call start; estimators restart learning when they see a new value
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn published(source: VadSource) -> &'static Published {
+    match source {
+        VadSource::Microphone => &MICROPHONE,
+        VadSource::SystemAudio => &SYSTEM_AUDIO,
+    }
+}
+
+fn load_bits(bits: &AtomicU32) -> Option<f32> {
+    match bits.load(Ordering::Relaxed) {
+        0 => None,
+        bits => Some(f32::from_bits(bits)),
+    }
+}
+
+/// Threshold estimator owned by one capture callback
+pub struct AdaptiveVad {
+    source: VadSource,
+    settings: AdaptiveVadSettings,
+    frame_len: usize,
+    frame: (f32, usize),         // Sum of squares, samples of the frame in progress
+    second_frames: Vec<f32>,     // Frame RMS of the second in progress
+    floors: VecDeque<f32>,       // Per-second floors, last WINDOW_SECS
+    seconds: u32,
+    generation: u64,
+    threshold: Option<f32>,
+}
+
+impl AdaptiveVad {
+    /// Estimator with the stored settings (call when the stream is set up, not in the callback)
+    pub fn new(source: VadSource, sample_rate: u32) -> Self {
+        Self::with_settings(source, sample_rate, crate::preferences::load().adaptive_vad)
+    }
+
+    fn with_settings(source: VadSource, sample_rate: u32, settings: AdaptiveVadSettings) -> Self {
+        Self {
+            source,
+            settings,
+            frame_len: (sample_rate as usize * FRAME_MS / 1000).max(1),
+            frame: (0.0, 0),
+            second_frames: Vec::new(),
+            floors: VecDeque::new(),
+            seconds: 0,
+            generation: GENERATION.load(Ordering::Relaxed),
+            threshold: None,
+        }
+    }
+
+    fn restart(&mut self) {
+        self.frame = (0.0, 0);
+        self.second_frames.clear();
+        self.floors.clear();
+        self.seconds = 0;
+        self.threshold = None;
+        published(self.source).clear();
+    }
+
+    fn percentile(values: &mut [f32], p: f32) -> f32 {
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        values.get(((values.len().max(1) - 1) as f32 * p).round() as usize).copied().unwrap_or(0.0)
+    }
+
+    fn second_completed(&mut self) {
+        let floor = Self::percentile(&mut self.second_frames, 0.1);
+        self.second_frames.clear();
+        self.floors.push_back(floor);
+        if self.floors.len() > WINDOW_SECS {
+            self.floors.pop_front();
+        }
+        self.seconds += 1;
+
+        let mut floors: Vec<f32> = self.floors.iter().copied().collect();
+        let noise_floor = Self::percentile(&mut floors, FLOOR_PERCENTILE);
+        let published = published(self.source);
+        published.seconds.store(self.seconds, Ordering::Relaxed);
+        published.noise_floor.store(noise_floor.to_bits(), Ordering::Relaxed);
+        if self.seconds >= self.settings.learn_seconds {
+            let threshold = (noise_floor * 10f32.powf(self.settings.margin_db / 20.0)).clamp(MIN_THRESHOLD, MAX_THRESHOLD);
+            if self.threshold.is_none() {
+                info!("🎚️ {:?} VAD threshold learned: {:.4} (noise floor {:.4})", self.source, threshold, noise_floor);
+            }
+            self.threshold = Some(threshold);
+            published.threshold.store(threshold.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    /// Feed mono audio; the learned threshold (None while learning or disabled)
+    pub fn observe(&mut self, samples: &[f32]) -> Option<f32> {
+        if !self.settings.enabled {
+            return None;
+        }
+        let generation = GENERATION.load(Ordering::Relaxed);
+        if generation != self.generation {
+            self.generation = generation;
+            self.restart();
+        }
+        let frames_per_second = 1000 / FRAME_MS;
+        for &sample in samples {
+            self.frame.0 += sample * sample;
+            self.frame.1 += 1;
+            if self.frame.1 == self.frame_len {
+                self.second_frames.push((self.frame.0 / self.frame_len as f32).sqrt());
+                self.frame = (0.0, 0);
+                if self.second_frames.len() == frames_per_second {
+                    self.second_completed();
+                }
+            }
+        }
+        self.threshold
+    }
+}
+
+/// A call is starting: learn its thresholds from scratch
+pub fn begin_call() {
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+    MICROPHONE.clear();
+    SYSTEM_AUDIO.clear();
+}
+
+/// Learned thresholds of the sources heard this call
+pub fn learned_thresholds() -> Vec<LearnedThreshold> {
+    [VadSource::Microphone, VadSource::SystemAudio].into_iter()
+        .filter(|&source| published(source).seconds.load(Ordering::Relaxed) > 0)
+        .map(|source| {
+            let published = published(source);
+            LearnedThreshold {
+                source,
+                seconds_heard: published.seconds.load(Ordering::Relaxed),
+                noise_floor: load_bits(&published.noise_floor),
+                threshold: load_bits(&published.threshold),
+            }
+        })
+        .collect()
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_adaptive_vad_settings() -> Result<AdaptiveVadSettings, String> {
+    Ok(crate::preferences::load().adaptive_vad)
+}
+
+// Applies to streams started afterwards
+#[tauri::command]
+pub fn set_adaptive_vad_settings(settings: AdaptiveVadSettings) -> Result<AdaptiveVadSettings, String> {
+    if !(10..=300).contains(&settings.learn_seconds) {
+        return Err("learn_seconds must be between 10 and 300".to_string());
+    }
+    if !(0.0..=30.0).contains(&settings.margin_db) {
+        return Err("margin_db must be between 0 and 30".to_string());
+    }
+    crate::preferences::update(|p| p.adaptive_vad = settings.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_tracks_noise_floor_not_speech() {
+        let settings = AdaptiveVadSettings { learn_seconds: 10, margin_db: 6.0, ..Default::default() };
+        // 1 kHz: 20-sample frames; the system audio slot keeps the test off the mic's
+        let mut vad = AdaptiveVad::with_settings(VadSource::SystemAudio, 1_000, settings);
+        // Steady hum at 0.01 with loud speech in every other second
+        let second = |level: f32| -> Vec<f32> { (0..1_000).map(|i| if i % 2 == 0 { level } else { -level }).collect() };
+        for s in 0..9 {
+            assert_eq!(vad.observe(&second(if s % 2 == 0 { 0.3 } else { 0.01 })), None);
+        }
+        let threshold = vad.observe(&second(0.01)).unwrap();
+        assert!((threshold - 0.01 * 2.0).abs() < 0.001, "threshold {}", threshold);
+
+        // The room gets noisier: the threshold follows once quiet seconds dominate the window
+        for _ in 0..60 {
+            vad.observe(&second(0.04));
+        }
+        assert!((vad.observe(&second(0.04)).unwrap() - 0.08).abs() < 0.005);
+    }
+}
@@ -0,0 +1,259 @@
+// Analytics Export - per-session metrics across a date range, for BI tools
+// Sales ops pull team performance into their own tooling, so export_analytics writes
+// one row per session that started in the range: talk ratio, speaking rates,
+// objections, prospect questions, the recorded outcome, the session template and the
+// scores (checklist completion, the template's rubric, prompt ratings). Columns are built once and written either as CSV or
+// as a Parquet file with typed, nullable columns (metrics a session never got, e.g.
+// for a practice session without a call, are empty / null).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use log::info;
+
+use crate::session_store::{PromptRating, Session};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct AnalyticsExport {
+    pub path: String,
+    pub format: ExportFormat,
+    pub sessions: usize,
+}
+
+enum Values {
+    Text(Vec<Option<String>>),
+    Int(Vec<Option<i64>>),
+    Float(Vec<Option<f64>>),
+    Timestamp(Vec<Option<i64>>),  // Milliseconds since the epoch (UTC)
+}
+
+struct Column {
+    name: &'static str,
+    values: Values,
+}
+
+fn text(name: &'static str, sessions: &[Session], f: impl Fn(&Session) -> Option<String>) -> Column {
+    Column { name, values: Values::Text(sessions.iter().map(f).collect()) }
+}
+
+fn int(name: &'static str, sessions: &[Session], f: impl Fn(&Session) -> Option<i64>) -> Column {
+    Column { name, values: Values::Int(sessions.iter().map(f).collect()) }
+}
+
+fn float(name: &'static str, sessions: &[Session], f: impl Fn(&Session) -> Option<f64>) -> Column {
+    Column { name, values: Values::Float(sessions.iter().map(f).collect()) }
+}
+
+fn rated(session: &Session, rating: PromptRating) -> Option<i64> {
+    Some(session.prompts.iter().filter(|p| p.rating == Some(rating)).count() as i64)
+}
+
+fn columns(sessions: &[Session]) -> Vec<Column> {
+    let metric = |f: fn(&crate::call_analytics::CallMetrics) -> i64| move |s: &Session| s.metrics.as_ref().map(f);
+    vec![
+        text("session_id", sessions, |s| Some(s.id.clone())),
+        Column { name: "started_at", values: Values::Timestamp(sessions.iter().map(|s| Some(s.started_at as i64)).collect()) },
+        float("duration_seconds", sessions, |s| s.metrics.as_ref()
+            .map(|m| m.updated_at.saturating_sub(s.started_at) as f64 / 1000.0)),
+        text("company", sessions, |s| s.company.clone()),
+        text("outcome", sessions, |s| s.outcome.and_then(|o| serde_json::to_value(o).ok())
+            .and_then(|v| v.as_str().map(str::to_string))),
+        text("template", sessions, |s| s.template.clone()),
+        int("rep_words", sessions, metric(|m| m.talk_ratio.rep_words as i64)),
+        int("prospect_words", sessions, metric(|m| m.talk_ratio.prospect_words as i64)),
+        float("talk_ratio", sessions, |s| s.metrics.as_ref().map(|m| m.talk_ratio.rep_share as f64)),
+        float("rep_wpm", sessions, |s| s.metrics.as_ref().and_then(|m| m.rep_wpm).map(f64::from)),
+        float("prospect_wpm", sessions, |s| s.metrics.as_ref().and_then(|m| m.prospect_wpm).map(f64::from)),
+        int("objections", sessions, metric(|m| m.objections as i64)),
+        int("prospect_questions", sessions, metric(|m| m.prospect_questions as i64)),
+        int("unanswered_questions", sessions, metric(|m| m.unanswered_questions as i64)),
+        int("checklist_completed", sessions, metric(|m| m.checklist_completed as i64)),
+        int("checklist_total", sessions, metric(|m| m.checklist_total as i64)),
+        float("checklist_score", sessions, |s| s.metrics.as_ref()
+            .filter(|m| m.checklist_total > 0)
+            .map(|m| m.checklist_completed as f64 / m.checklist_total as f64)),
+        float("rubric_score", sessions, |s| s.metrics.as_ref()
+            .and_then(|m| crate::session_templates::rubric_score(&s.rubric, m))
+            .map(f64::from)),
+        int("prompts", sessions, |s| Some(s.prompts.len() as i64)),
+        int("helpful_prompts", sessions, |s| rated(s, PromptRating::Helpful)),
+        int("unhelpful_prompts", sessions, |s| rated(s, PromptRating::Unhelpful)),
+        int("topics", sessions, |s| Some(s.chapters.len() as i64)),
+    ]
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_cell(values: &Values, row: usize) -> String {
+    match values {
+        Values::Text(v) => v[row].as_deref().map(csv_field),
+        Values::Int(v) => v[row].map(|x| x.to_string()),
+        Values::Float(v) => v[row].map(|x| format!("{:.3}", x)),
+        Values::Timestamp(v) => v[row]
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+    }.unwrap_or_default()
+}
+
+fn render_csv(columns: &[Column], rows: usize) -> String {
+    let mut csv = columns.iter().map(|c| c.name).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+    for row in 0..rows {
+        let cells: Vec<String> = columns.iter().map(|c| csv_cell(&c.values, row)).collect();
+        csv.push_str(&cells.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+// Values present and the definition levels of a nullable column (a null is level 0
+// with no value written)
+fn split<T: Clone>(values: &[Option<T>]) -> (Vec<T>, Vec<i16>) {
+    let present = values.iter().flatten().cloned().collect();
+    let levels = values.iter().map(|v| v.is_some() as i16).collect();
+    (present, levels)
+}
+
+fn write_parquet(path: &Path, columns: &[Column]) -> Result<()> {
+    use parquet::basic::{Compression, LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+    use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::format::MilliSeconds;
+    use parquet::schema::types::Type;
+
+    let fields = columns.iter()
+        .map(|column| {
+            let (physical, logical) = match column.values {
+                Values::Text(_) => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+                Values::Int(_) => (PhysicalType::INT64, None),
+                Values::Float(_) => (PhysicalType::DOUBLE, None),
+                Values::Timestamp(_) => (PhysicalType::INT64, Some(LogicalType::Timestamp {
+                    is_adjusted_to_u_t_c: true,
+                    unit: TimeUnit::MILLIS(MilliSeconds {}),
+                })),
+            };
+            Type::primitive_type_builder(column.name, physical)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(logical)
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<parquet::errors::Result<Vec<_>>>()?;
+    let schema = Arc::new(Type::group_type_builder("session_metrics").with_fields(fields).build()?);
+    let properties = Arc::new(WriterProperties::builder().set_compression(Compression::SNAPPY).build());
+
+    let file = File::create(path).context(format!("Failed to create {:?}", path))?;
+    let mut writer = SerializedFileWriter::new(file, schema, properties)?;
+    let mut row_group = writer.next_row_group()?;
+    for column in columns {
+        let mut column_writer = row_group.next_column()?
+            .context("Parquet schema has fewer columns than the export")?;
+        match &column.values {
+            Values::Text(values) => {
+                let (present, levels) = split(values);
+                let present: Vec<ByteArray> = present.into_iter().map(|s| ByteArray::from(s.into_bytes())).collect();
+                column_writer.typed::<ByteArrayType>().write_batch(&present, Some(&levels), None)?;
+            }
+            Values::Int(values) | Values::Timestamp(values) => {
+                let (present, levels) = split(values);
+                column_writer.typed::<Int64Type>().write_batch(&present, Some(&levels), None)?;
+            }
+            Values::Float(values) => {
+                let (present, levels) = split(values);
+                column_writer.typed::<DoubleType>().write_batch(&present, Some(&levels), None)?;
+            }
+        }
+        column_writer.close()?;
+    }
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+fn export(path: &Path, format: ExportFormat, sessions: &[Session]) -> Result<()> {
+    let columns = columns(sessions);
+    match format {
+        ExportFormat::Csv => {
+            let mut file = File::create(path).context(format!("Failed to create {:?}", path))?;
+            file.write_all(render_csv(&columns, sessions.len()).as_bytes())?;
+            Ok(())
+        }
+        ExportFormat::Parquet => write_parquet(path, &columns),
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Sessions started in [from, to) (epoch ms; open-ended when None) to a CSV or Parquet file
+#[tauri::command]
+pub fn export_analytics(from: Option<u64>, to: Option<u64>, format: ExportFormat, path: String) -> Result<AnalyticsExport, String> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if from >= to {
+            return Err("The start of the range must be before its end".to_string());
+        }
+    }
+    let sessions: Vec<Session> = crate::session_store::all_sessions().into_iter()
+        .filter(|s| from.map_or(true, |f| s.started_at >= f) && to.map_or(true, |t| s.started_at < t))
+        .collect();
+    export(Path::new(&path), format, &sessions).map_err(|e| e.to_string())?;
+    info!("📊 Exported analytics for {} sessions to {} ({:?})", sessions.len(), path, format);
+    Ok(AnalyticsExport { path, format, sessions: sessions.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::call_analytics::CallMetrics;
+    use crate::session_store::CallOutcome;
+
+    fn session(id: &str, company: Option<&str>, metrics: Option<CallMetrics>) -> Session {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "started_at": 1_700_000_000_000u64,
+            "company": company,
+            "metrics": metrics,
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_export_quotes_text_and_leaves_missing_metrics_empty() {
+        let mut metrics = CallMetrics { objections: 2, checklist_completed: 1, checklist_total: 4, ..Default::default() };
+        metrics.updated_at = 1_700_000_090_000;
+        let mut called = session("20231114-221320", Some("Acme, \"Inc\""), Some(metrics));
+        called.outcome = Some(CallOutcome::FollowUp);
+        let practice = session("20231114-230000", None, None);
+
+        let sessions = vec![called, practice];
+        let csv = render_csv(&columns(&sessions), sessions.len());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("session_id,started_at,duration_seconds,company,outcome,"));
+        assert!(lines[1].starts_with("20231114-221320,2023-11-14T22:13:20Z,90.000,\"Acme, \"\"Inc\"\"\",follow_up,"));
+        assert!(lines[1].contains(",0.250,"));  // Checklist score
+        assert!(lines[2].starts_with("20231114-230000,2023-11-14T22:13:20Z,,,,,"));
+
+        let path = std::env::temp_dir().join("voicecoach_analytics_test.parquet");
+        export(&path, ExportFormat::Parquet, &sessions).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        use parquet::file::reader::FileReader;
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        assert_eq!(reader.metadata().file_metadata().schema_descr().num_columns(), columns(&sessions).len());
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,457 @@
+// App Audio - capture a single application's audio (per-process loopback)
+// System loopback records everything the machine plays: notification dings, music,
+// other tabs. Windows 10 2004+ can instead loop back one process tree via the
+// virtual "VAD\Process_Loopback" device (ActivateAudioInterfaceAsync with
+// AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK). capture_app_audio picks the
+// meeting app; transcription started with source "app_audio" then hears only it.
+// The whole process tree is included because browsers and Teams play audio from
+// helper processes. Other platforms report the mode as unavailable.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::info;
+
+/// Capture format requested from the loopback client (converted by Windows)
+pub const CAPTURE_SAMPLE_RATE: u32 = 16000;
+
+// Offered first by list_capture_apps
+const MEETING_APPS: &[&str] = &[
+    "zoom.exe", "ms-teams.exe", "teams.exe", "slack.exe", "webexmta.exe", "ciscocollabhost.exe",
+    "skype.exe", "discord.exe", "chrome.exe", "msedge.exe", "firefox.exe", "brave.exe",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, specta::Type)]
+pub struct AppAudioTarget {
+    pub pid: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+struct ProcessEntry {
+    pid: u32,
+    parent: u32,
+    name: String,
+}
+
+static TARGET: Lazy<Mutex<Option<AppAudioTarget>>> = Lazy::new(|| Mutex::new(None));
+
+/// Application selected with capture_app_audio, if any
+pub fn target() -> Option<AppAudioTarget> {
+    TARGET.lock().unwrap().clone()
+}
+
+fn matches_name(entry: &ProcessEntry, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    let exe = entry.name.to_lowercase();
+    exe == name || exe.trim_end_matches(".exe") == name
+}
+
+/// Processes named `name` whose parent is not also `name` (the roots of each app's tree)
+fn root_processes<'a>(processes: &'a [ProcessEntry], name: &str) -> Vec<&'a ProcessEntry> {
+    let matching: Vec<&ProcessEntry> = processes.iter().filter(|p| matches_name(p, name)).collect();
+    matching.iter()
+        .filter(|p| !matching.iter().any(|parent| parent.pid == p.parent && parent.pid != p.pid))
+        .copied()
+        .collect()
+}
+
+/// "zoom", "Zoom.exe" or a process id
+fn resolve(process: &str, processes: &[ProcessEntry]) -> Result<AppAudioTarget, String> {
+    if let Ok(pid) = process.trim().parse::<u32>() {
+        let entry = processes.iter().find(|p| p.pid == pid)
+            .ok_or_else(|| format!("No running process with id {}", pid))?;
+        return Ok(AppAudioTarget { pid, name: entry.name.clone() });
+    }
+    root_processes(processes, process).first()
+        .map(|p| AppAudioTarget { pid: p.pid, name: p.name.clone() })
+        .ok_or_else(|| format!("'{}' is not running", process.trim()))
+}
+
+#[cfg(windows)]
+fn running_processes() -> Vec<ProcessEntry> {
+    win::running_processes()
+}
+
+#[cfg(not(windows))]
+fn running_processes() -> Vec<ProcessEntry> {
+    Vec::new()
+}
+
+/// Loop back `pid` and its children as 16 kHz mono i16 on a capture thread until
+/// `on_samples` returns false. Returns once capture has started (or failed to).
+#[cfg(windows)]
+pub fn start_capture<F>(pid: u32, on_samples: F) -> Result<(), String>
+where
+    F: FnMut(&[i16]) -> bool + Send + 'static,
+{
+    win::start_capture(pid, on_samples)
+}
+
+#[cfg(not(windows))]
+pub fn start_capture<F>(_pid: u32, _on_samples: F) -> Result<(), String>
+where
+    F: FnMut(&[i16]) -> bool + Send + 'static,
+{
+    Err("Per-application capture requires Windows 10 version 2004 or later".to_string())
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+mod win {
+    // Raw COM: windows-sys has the types and entry points but no interface wrappers,
+    // so the few vtables used here are declared by hand (IUnknown methods first).
+    use super::{ProcessEntry, CAPTURE_SAMPLE_RATE};
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{mpsc, Mutex};
+    use std::time::Duration;
+    use windows_sys::core::{GUID, HRESULT, IUnknown_Vtbl, IID_IUnknown};
+    use windows_sys::Win32::Foundation::{CloseHandle, E_NOINTERFACE, INVALID_HANDLE_VALUE, S_OK};
+    use windows_sys::Win32::Media::Audio::{
+        ActivateAudioInterfaceAsync, AUDIOCLIENT_ACTIVATION_PARAMS, AUDIOCLIENT_ACTIVATION_PARAMS_0,
+        AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK, AUDIOCLIENT_PROCESS_LOOPBACK_PARAMS,
+        AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM,
+        AUDCLNT_STREAMFLAGS_EVENTCALLBACK, AUDCLNT_STREAMFLAGS_LOOPBACK,
+        AUDCLNT_STREAMFLAGS_SRC_DEFAULT_QUALITY, PROCESS_LOOPBACK_MODE_INCLUDE_TARGET_PROCESS_TREE,
+        VIRTUAL_AUDIO_DEVICE_PROCESS_LOOPBACK, WAVEFORMATEX, WAVE_FORMAT_PCM,
+    };
+    use windows_sys::Win32::System::Com::{CoInitializeEx, CoUninitialize, BLOB, COINIT_MULTITHREADED};
+    use windows_sys::Win32::System::Com::StructuredStorage::{PROPVARIANT, PROPVARIANT_0, PROPVARIANT_0_0, PROPVARIANT_0_0_0};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+    };
+    use windows_sys::Win32::System::Threading::{CreateEventW, WaitForSingleObject};
+    use windows_sys::Win32::System::Variant::VT_BLOB;
+
+    const IID_IAUDIOCLIENT: GUID = GUID::from_u128(0x1cb9ad4c_dbfa_4c32_b178_c2f568a703b2);
+    const IID_IAUDIOCAPTURECLIENT: GUID = GUID::from_u128(0xc8adbd64_e71e_48a0_a4de_185c395cd317);
+    const IID_ICOMPLETIONHANDLER: GUID = GUID::from_u128(0x41d949ab_9862_444a_80f6_c261334da5eb);
+    const IID_IAGILEOBJECT: GUID = GUID::from_u128(0x94ea2b94_e9cc_49e0_c0ff_ee64ca8f5b90);
+    const BUFFER_DURATION_HNS: i64 = 2_000_000;  // 200 ms
+    const ACTIVATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+    type Unused = usize;
+
+    #[repr(C)]
+    struct IAudioClientVtbl {
+        base: IUnknown_Vtbl,
+        Initialize: unsafe extern "system" fn(*mut c_void, i32, u32, i64, i64, *const WAVEFORMATEX, *const GUID) -> HRESULT,
+        GetBufferSize: Unused,
+        GetStreamLatency: Unused,
+        GetCurrentPadding: Unused,
+        IsFormatSupported: Unused,
+        GetMixFormat: Unused,
+        GetDevicePeriod: Unused,
+        Start: unsafe extern "system" fn(*mut c_void) -> HRESULT,
+        Stop: unsafe extern "system" fn(*mut c_void) -> HRESULT,
+        Reset: Unused,
+        SetEventHandle: unsafe extern "system" fn(*mut c_void, *mut c_void) -> HRESULT,
+        GetService: unsafe extern "system" fn(*mut c_void, *const GUID, *mut *mut c_void) -> HRESULT,
+    }
+
+    #[repr(C)]
+    struct IAudioCaptureClientVtbl {
+        base: IUnknown_Vtbl,
+        GetBuffer: unsafe extern "system" fn(*mut c_void, *mut *mut u8, *mut u32, *mut u32, *mut u64, *mut u64) -> HRESULT,
+        ReleaseBuffer: unsafe extern "system" fn(*mut c_void, u32) -> HRESULT,
+        GetNextPacketSize: unsafe extern "system" fn(*mut c_void, *mut u32) -> HRESULT,
+    }
+
+    #[repr(C)]
+    struct IActivateOperationVtbl {
+        base: IUnknown_Vtbl,
+        GetActivateResult: unsafe extern "system" fn(*mut c_void, *mut HRESULT, *mut *mut c_void) -> HRESULT,
+    }
+
+    #[repr(C)]
+    struct CompletionHandlerVtbl {
+        base: IUnknown_Vtbl,
+        ActivateCompleted: unsafe extern "system" fn(*mut c_void, *mut c_void) -> HRESULT,
+    }
+
+    // IActivateAudioInterfaceCompletionHandler (also agile: it is called on a worker thread)
+    #[repr(C)]
+    struct CompletionHandler {
+        vtbl: *const CompletionHandlerVtbl,
+        refs: AtomicU32,
+        done: Mutex<Option<mpsc::Sender<()>>>,
+    }
+
+    static COMPLETION_HANDLER_VTBL: CompletionHandlerVtbl = CompletionHandlerVtbl {
+        base: IUnknown_Vtbl { QueryInterface: handler_query_interface, AddRef: handler_add_ref, Release: handler_release },
+        ActivateCompleted: handler_activate_completed,
+    };
+
+    fn guid_eq(a: &GUID, b: &GUID) -> bool {
+        a.data1 == b.data1 && a.data2 == b.data2 && a.data3 == b.data3 && a.data4 == b.data4
+    }
+
+    unsafe extern "system" fn handler_query_interface(this: *mut c_void, iid: *const GUID, out: *mut *mut c_void) -> HRESULT {
+        let iid = &*iid;
+        if guid_eq(iid, &IID_IUnknown) || guid_eq(iid, &IID_ICOMPLETIONHANDLER) || guid_eq(iid, &IID_IAGILEOBJECT) {
+            handler_add_ref(this);
+            *out = this;
+            S_OK
+        } else {
+            *out = std::ptr::null_mut();
+            E_NOINTERFACE
+        }
+    }
+
+    unsafe extern "system" fn handler_add_ref(this: *mut c_void) -> u32 {
+        (*(this as *const CompletionHandler)).refs.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    unsafe extern "system" fn handler_release(this: *mut c_void) -> u32 {
+        let remaining = (*(this as *const CompletionHandler)).refs.fetch_sub(1, Ordering::SeqCst) - 1;
+        if remaining == 0 {
+            drop(Box::from_raw(this as *mut CompletionHandler));
+        }
+        remaining
+    }
+
+    unsafe extern "system" fn handler_activate_completed(this: *mut c_void, _operation: *mut c_void) -> HRESULT {
+        if let Some(done) = (*(this as *const CompletionHandler)).done.lock().unwrap().take() {
+            let _ = done.send(());
+        }
+        S_OK
+    }
+
+    unsafe fn vtbl<T>(object: *mut c_void) -> &'static T {
+        &**(object as *const *const T)
+    }
+
+    unsafe fn release(object: *mut c_void) {
+        if !object.is_null() {
+            (vtbl::<IUnknown_Vtbl>(object).Release)(object);
+        }
+    }
+
+    fn check(hr: HRESULT, what: &str) -> Result<(), String> {
+        if hr < 0 {
+            Err(format!("{} failed (HRESULT 0x{:08X})", what, hr as u32))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(super) fn running_processes() -> Vec<ProcessEntry> {
+        let mut processes = Vec::new();
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+            if snapshot == INVALID_HANDLE_VALUE {
+                return processes;
+            }
+            let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+            entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+            let mut more = Process32FirstW(snapshot, &mut entry) != 0;
+            while more {
+                let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+                processes.push(ProcessEntry {
+                    pid: entry.th32ProcessID,
+                    parent: entry.th32ParentProcessID,
+                    name: String::from_utf16_lossy(&entry.szExeFile[..len]),
+                });
+                more = Process32NextW(snapshot, &mut entry) != 0;
+            }
+            CloseHandle(snapshot);
+        }
+        processes
+    }
+
+    /// Activate an IAudioClient on the process loopback device for `pid`
+    unsafe fn activate_loopback_client(pid: u32) -> Result<*mut c_void, String> {
+        let mut params = AUDIOCLIENT_ACTIVATION_PARAMS {
+            ActivationType: AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK,
+            Anonymous: AUDIOCLIENT_ACTIVATION_PARAMS_0 {
+                ProcessLoopbackParams: AUDIOCLIENT_PROCESS_LOOPBACK_PARAMS {
+                    TargetProcessId: pid,
+                    ProcessLoopbackMode: PROCESS_LOOPBACK_MODE_INCLUDE_TARGET_PROCESS_TREE,
+                },
+            },
+        };
+        let mut variant: PROPVARIANT = std::mem::zeroed();
+        variant.Anonymous = PROPVARIANT_0 {
+            Anonymous: PROPVARIANT_0_0 {
+                vt: VT_BLOB,
+                wReserved1: 0,
+                wReserved2: 0,
+                wReserved3: 0,
+                Anonymous: PROPVARIANT_0_0_0 {
+                    blob: BLOB {
+                        cbSize: std::mem::size_of::<AUDIOCLIENT_ACTIVATION_PARAMS>() as u32,
+                        pBlobData: &mut params as *mut AUDIOCLIENT_ACTIVATION_PARAMS as *mut u8,
+                    },
+                },
+            },
+        };
+
+        let (done_tx, done_rx) = mpsc::channel();
+        let handler = Box::into_raw(Box::new(CompletionHandler {
+            vtbl: &COMPLETION_HANDLER_VTBL,
+            refs: AtomicU32::new(1),
+            done: Mutex::new(Some(done_tx)),
+        })) as *mut c_void;
+
+        let mut operation: *mut c_void = std::ptr::null_mut();
+        let hr = ActivateAudioInterfaceAsync(
+            VIRTUAL_AUDIO_DEVICE_PROCESS_LOOPBACK, &IID_IAUDIOCLIENT, &variant, handler, &mut operation);
+        let result = check(hr, "ActivateAudioInterfaceAsync")
+            .and_then(|_| done_rx.recv_timeout(ACTIVATION_TIMEOUT)
+                .map_err(|_| "Timed out activating process loopback".to_string()))
+            .and_then(|_| {
+                let mut activate_hr: HRESULT = S_OK;
+                let mut client: *mut c_void = std::ptr::null_mut();
+                check((vtbl::<IActivateOperationVtbl>(operation).GetActivateResult)(operation, &mut activate_hr, &mut client),
+                      "GetActivateResult")?;
+                check(activate_hr, "Process loopback activation")?;
+                Ok(client)
+            });
+        release(operation);
+        release(handler);
+        result
+    }
+
+    unsafe fn run_capture<F>(pid: u32, mut on_samples: F, started: mpsc::Sender<Result<(), String>>)
+    where
+        F: FnMut(&[i16]) -> bool,
+    {
+        let client = match activate_loopback_client(pid) {
+            Ok(client) => client,
+            Err(e) => {
+                let _ = started.send(Err(e));
+                return;
+            }
+        };
+        let client_vtbl = vtbl::<IAudioClientVtbl>(client);
+        let format = WAVEFORMATEX {
+            wFormatTag: WAVE_FORMAT_PCM as u16,
+            nChannels: 1,
+            nSamplesPerSec: CAPTURE_SAMPLE_RATE,
+            nAvgBytesPerSec: CAPTURE_SAMPLE_RATE * 2,
+            nBlockAlign: 2,
+            wBitsPerSample: 16,
+            cbSize: 0,
+        };
+        let flags = AUDCLNT_STREAMFLAGS_LOOPBACK | AUDCLNT_STREAMFLAGS_EVENTCALLBACK
+            | AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM | AUDCLNT_STREAMFLAGS_SRC_DEFAULT_QUALITY;
+        let event = CreateEventW(std::ptr::null(), 0, 0, std::ptr::null());
+        let mut capture: *mut c_void = std::ptr::null_mut();
+
+        let setup = check((client_vtbl.Initialize)(client, AUDCLNT_SHAREMODE_SHARED, flags, BUFFER_DURATION_HNS, 0, &format, std::ptr::null()),
+                          "IAudioClient::Initialize")
+            .and_then(|_| check((client_vtbl.SetEventHandle)(client, event), "IAudioClient::SetEventHandle"))
+            .and_then(|_| check((client_vtbl.GetService)(client, &IID_IAUDIOCAPTURECLIENT, &mut capture), "IAudioClient::GetService"))
+            .and_then(|_| check((client_vtbl.Start)(client), "IAudioClient::Start"));
+        let running = setup.is_ok();
+        let _ = started.send(setup);
+
+        if running {
+            let capture_vtbl = vtbl::<IAudioCaptureClientVtbl>(capture);
+            'capture: loop {
+                WaitForSingleObject(event, 200);
+                let mut packet: u32 = 0;
+                while (capture_vtbl.GetNextPacketSize)(capture, &mut packet) >= 0 && packet > 0 {
+                    let mut data: *mut u8 = std::ptr::null_mut();
+                    let mut frames: u32 = 0;
+                    let mut buffer_flags: u32 = 0;
+                    if (capture_vtbl.GetBuffer)(capture, &mut data, &mut frames, &mut buffer_flags,
+                                                std::ptr::null_mut(), std::ptr::null_mut()) < 0 {
+                        break 'capture;
+                    }
+                    let keep_going = if buffer_flags & AUDCLNT_BUFFERFLAGS_SILENT as u32 != 0 || data.is_null() {
+                        on_samples(&vec![0i16; frames as usize])
+                    } else {
+                        on_samples(std::slice::from_raw_parts(data as *const i16, frames as usize))
+                    };
+                    (capture_vtbl.ReleaseBuffer)(capture, frames);
+                    if !keep_going {
+                        break 'capture;
+                    }
+                }
+            }
+            (client_vtbl.Stop)(client);
+        }
+        release(capture);
+        release(client);
+        if !event.is_null() {
+            CloseHandle(event);
+        }
+    }
+
+    pub(super) fn start_capture<F>(pid: u32, on_samples: F) -> Result<(), String>
+    where
+        F: FnMut(&[i16]) -> bool + Send + 'static,
+    {
+        let (started_tx, started_rx) = mpsc::channel();
+        std::thread::spawn(move || unsafe {
+            let com = CoInitializeEx(std::ptr::null(), COINIT_MULTITHREADED as u32);
+            run_capture(pid, on_samples, started_tx);
+            if com >= 0 {
+                CoUninitialize();
+            }
+        });
+        started_rx.recv_timeout(ACTIVATION_TIMEOUT * 2)
+            .map_err(|_| "Process loopback capture did not start".to_string())?
+            .map_err(|e| format!("{} (per-application capture needs Windows 10 version 2004 or later)", e))
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Running meeting apps/browsers that can be captured (root process of each app)
+#[tauri::command]
+pub fn list_capture_apps() -> Result<Vec<AppAudioTarget>, String> {
+    let processes = running_processes();
+    Ok(MEETING_APPS.iter()
+        .flat_map(|name| root_processes(&processes, name))
+        .map(|p| AppAudioTarget { pid: p.pid, name: p.name.clone() })
+        .collect())
+}
+
+// Select the application whose audio is the prospect channel (None = back to system loopback)
+#[tauri::command]
+pub fn capture_app_audio(process: Option<String>) -> Result<Option<AppAudioTarget>, String> {
+    let target = match process {
+        Some(process) => {
+            if cfg!(not(windows)) {
+                return Err("Per-application capture requires Windows 10 version 2004 or later".to_string());
+            }
+            let target = resolve(&process, &running_processes())?;
+            info!("🎯 LED 9400: Capturing audio from {} (pid {}) only", target.name, target.pid);
+            Some(target)
+        }
+        None => {
+            info!("🎯 Per-application capture cleared");
+            None
+        }
+    };
+    *TARGET.lock().unwrap() = target.clone();
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, parent: u32, name: &str) -> ProcessEntry {
+        ProcessEntry { pid, parent, name: name.to_string() }
+    }
+
+    #[test]
+    fn test_resolve_picks_root_of_process_tree() {
+        let processes = vec![
+            entry(4, 0, "System"),
+            entry(100, 4, "explorer.exe"),
+            entry(200, 100, "chrome.exe"),
+            entry(201, 200, "chrome.exe"),
+            entry(202, 200, "chrome.exe"),
+            entry(300, 100, "Zoom.exe"),
+        ];
+        assert_eq!(resolve("chrome", &processes).unwrap().pid, 200);
+        assert_eq!(resolve("zoom.exe", &processes).unwrap(), AppAudioTarget { pid: 300, name: "Zoom.exe".to_string() });
+        assert_eq!(resolve("201", &processes).unwrap().pid, 201);
+        assert!(resolve("teams", &processes).is_err());
+    }
+}
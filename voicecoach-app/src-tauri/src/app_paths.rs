@@ -0,0 +1,53 @@
+// App Paths - where the app keeps its logs and data files
+// Directories are resolved from the app's own config (the bundle identifier in
+// tauri.conf.json), captured at startup before anything is logged or saved. Resolving
+// them from a default Config (empty identifier) would put every file straight into the
+// user's data root, next to other apps' files. Files the app saved in the data root
+// before (and the knowledge base folder) are moved into its own directory on first
+// start; only the voicecoach_* entries are, as the generic names there may belong to
+// other apps.
+
+use once_cell::sync::OnceCell;
+use std::path::PathBuf;
+
+static CONFIG: OnceCell<tauri::Config> = OnceCell::new();
+
+// Files and folders earlier versions kept directly in the data root
+const LEGACY_FILES: [&str; 4] = [
+    "voicecoach_credentials.json", "voicecoach_license.json", "voicecoach_preferences.json", "voicecoach_knowledge",
+];
+
+/// Capture the app's config and move legacy files into its data directory
+pub fn init(config: &tauri::Config) {
+    if CONFIG.set(config.clone()).is_err() {
+        return;
+    }
+    let (root, dir) = match (tauri::api::path::data_dir(), tauri::api::path::app_data_dir(config)) {
+        (Some(root), Some(dir)) if root != dir => (root, dir),
+        _ => return,
+    };
+    for file in LEGACY_FILES {
+        let (legacy, current) = (root.join(file), dir.join(file));
+        if legacy.exists() && !current.exists() {
+            let moved = std::fs::create_dir_all(&dir).and_then(|_| std::fs::rename(&legacy, &current));
+            // Logging isn't up yet
+            if let Err(e) = moved {
+                eprintln!("Failed to move {} to {}: {}", legacy.display(), current.display(), e);
+            }
+        }
+    }
+}
+
+/// The app's data directory (the working directory before init, e.g. in tests)
+pub fn data_dir() -> PathBuf {
+    CONFIG.get()
+        .and_then(tauri::api::path::app_data_dir)
+        .unwrap_or_else(|| PathBuf::from("./"))
+}
+
+/// The app's log directory
+pub fn log_dir() -> PathBuf {
+    CONFIG.get()
+        .and_then(tauri::api::path::app_log_dir)
+        .unwrap_or_else(|| PathBuf::from("./logs"))
+}
@@ -0,0 +1,382 @@
+// Audio Snippets - the raw audio behind transcript lines the engine wasn't sure of
+// A low-confidence line ("we'd need it by the fif- fifteenth?") is where a rep wants to
+// hear what was actually said before trusting, searching or quoting the transcript.
+// With retention on, the last seconds of each side's audio are kept in memory; when a
+// final line comes in whose mean word confidence is below the threshold, up to
+// snippet_seconds around its least certain words are written to snippets/ as a WAV
+// and attached to the line in its session (playable with play_recording). Retained
+// audio is capped in total: the oldest snippets are deleted once the cap is reached.
+// Correcting the line (correct_transcript_line) replaces its text and deletes its
+// snippet, and so does discarding it; a re-transcribed session drops all of them.
+// Off by default - this keeps call audio on disk without a recording being made.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn, error};
+use anyhow::{Result, Context};
+
+use crate::session_store::{TranscriptLine, TranscriptWord};
+
+const SNIPPETS_DIR: &str = "snippets";
+// Audio kept in memory per side: the longest snippet plus the engines' final latency
+const HISTORY_MS: u64 = 45_000;
+// Padding around the utterance so the first and last syllables are in the snippet
+const PAD_MS: u64 = 500;
+const MAX_SNIPPET_SECONDS: u32 = 30;
+const MAX_TOTAL_SECONDS: u32 = 3_600;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SnippetSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Longest snippet kept per line
+    #[serde(default = "default_snippet_seconds")]
+    pub snippet_seconds: u32,
+    /// Lines whose mean word confidence is below this keep their audio
+    #[serde(default = "default_below_confidence")]
+    pub below_confidence: f32,
+    /// Audio retained over all sessions; the oldest snippets go first
+    #[serde(default = "default_max_total_seconds")]
+    pub max_total_seconds: u32,
+}
+
+fn default_snippet_seconds() -> u32 { 10 }
+fn default_below_confidence() -> f32 { 0.6 }
+fn default_max_total_seconds() -> u32 { 600 }
+
+impl Default for SnippetSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snippet_seconds: default_snippet_seconds(),
+            below_confidence: default_below_confidence(),
+            max_total_seconds: default_max_total_seconds(),
+        }
+    }
+}
+
+/// Raw audio kept for a transcript line
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct AudioSnippet {
+    pub line: usize,                 // Index of the line in the session transcript
+    pub offset_ms: u64,              // Start of the audio, time since the call started
+    pub duration_ms: u64,
+    pub confidence: f32,             // Mean word confidence of the line
+    pub path: String,
+}
+
+/// Recent audio of one side, as it was fed to the engine
+#[derive(Default)]
+struct History {
+    sample_rate: u32,
+    samples: VecDeque<i16>,
+    end_ms: u64,                     // Capture time just after the last sample
+}
+
+impl History {
+    fn push(&mut self, samples: &[i16], sample_rate: u32, capture_ms: u64) {
+        if sample_rate != self.sample_rate {
+            self.samples.clear();
+            self.sample_rate = sample_rate;
+        }
+        self.samples.extend(samples.iter().copied());
+        let keep = (HISTORY_MS * sample_rate as u64 / 1000) as usize;
+        if self.samples.len() > keep {
+            let excess = self.samples.len() - keep;
+            self.samples.drain(..excess);
+        }
+        self.end_ms = capture_ms + samples.len() as u64 * 1000 / sample_rate.max(1) as u64;
+    }
+
+    /// Samples captured between `from_ms` and `to_ms`, with the capture time of the first
+    fn slice(&self, from_ms: u64, to_ms: u64) -> Option<(Vec<i16>, u64)> {
+        let rate = self.sample_rate as u64;
+        if rate == 0 {
+            return None;
+        }
+        let start_ms = self.end_ms.saturating_sub(self.samples.len() as u64 * 1000 / rate);
+        let from_ms = from_ms.max(start_ms);
+        let to_ms = to_ms.min(self.end_ms);
+        if to_ms <= from_ms {
+            return None;
+        }
+        let index = |ms: u64| (((ms - start_ms) * rate / 1000) as usize).min(self.samples.len());
+        let samples: Vec<i16> = self.samples.range(index(from_ms)..index(to_ms)).copied().collect();
+        (!samples.is_empty()).then(|| (samples, from_ms))
+    }
+}
+
+static REP: Lazy<Mutex<History>> = Lazy::new(|| Mutex::new(History::default()));
+static PROSPECT: Lazy<Mutex<History>> = Lazy::new(|| Mutex::new(History::default()));
+
+fn history(is_user: bool) -> &'static Mutex<History> {
+    if is_user { &REP } else { &PROSPECT }
+}
+
+/// Keeps a stream's audio for snippets
+pub struct SnippetRecorder {
+    is_user: bool,
+    sample_rate: u32,
+}
+
+/// Recorder for a capture stream (None when retention is off)
+pub fn recorder(is_user: bool, sample_rate: u32) -> Option<SnippetRecorder> {
+    if !crate::preferences::load().snippets.enabled {
+        return None;
+    }
+    *history(is_user).lock().unwrap() = History::default();
+    Some(SnippetRecorder { is_user, sample_rate })
+}
+
+impl SnippetRecorder {
+    /// Keep a mono buffer whose first sample was captured at `capture_ms`
+    pub fn record(&self, samples: &[i16], capture_ms: u64) {
+        // Audio thread: skip the buffer rather than wait on a snippet being cut
+        if let Ok(mut history) = history(self.is_user).try_lock() {
+            history.push(samples, self.sample_rate, capture_ms);
+        }
+    }
+}
+
+/// Mean confidence of the words that have one
+fn mean_confidence(words: &[TranscriptWord]) -> Option<f32> {
+    let scores: Vec<f32> = words.iter().filter_map(|w| w.confidence).collect();
+    (!scores.is_empty()).then(|| scores.iter().sum::<f32>() / scores.len() as f32)
+}
+
+/// Capture span to keep for a line: the utterance padded, or when that is longer than
+/// `max_ms`, `max_ms` centred on its uncertain words
+fn snippet_span(words: &[TranscriptWord], capture_ms: u64, speech_ms: u64, below: f32, max_ms: u64) -> (u64, u64) {
+    let from = capture_ms.saturating_sub(PAD_MS);
+    let to = words.iter().map(|w| w.end_ms).max().unwrap_or(0).max(capture_ms + speech_ms) + PAD_MS;
+    if to - from <= max_ms {
+        return (from, to);
+    }
+    let uncertain: Vec<&TranscriptWord> = words.iter().filter(|w| w.confidence.map_or(false, |c| c < below)).collect();
+    let centre = match (uncertain.first(), uncertain.last()) {
+        (Some(first), Some(last)) => (first.start_ms + last.end_ms) / 2,
+        _ => (from + to) / 2,
+    };
+    let start = centre.saturating_sub(max_ms / 2).max(from).min(to - max_ms);
+    (start, start + max_ms)
+}
+
+/// The audio of a final line to keep, if its words are uncertain enough (capture clock)
+pub struct Disputed {
+    from_ms: u64,
+    to_ms: u64,
+    confidence: f32,
+    is_user: bool,
+}
+
+/// Check a final line before it is recorded; words are on the capture clock
+pub fn disputed(words: &[TranscriptWord], is_user: bool, capture_ms: u64, speech_ms: u64) -> Option<Disputed> {
+    let confidence = mean_confidence(words)?;
+    if history(is_user).lock().unwrap().samples.is_empty() {
+        return None;  // No recorder on this side: retention is off
+    }
+    let settings = crate::preferences::load().snippets;
+    if !settings.enabled || confidence >= settings.below_confidence {
+        return None;
+    }
+    let (from_ms, to_ms) = snippet_span(words, capture_ms, speech_ms, settings.below_confidence, settings.snippet_seconds as u64 * 1000);
+    Some(Disputed { from_ms, to_ms, confidence, is_user })
+}
+
+/// Cut and attach the snippet of the line just recorded at `line` of the current session
+pub fn retain(line: usize, disputed: Disputed) {
+    let Disputed { from_ms, to_ms, confidence, is_user } = disputed;
+    std::thread::spawn(move || {
+        let session_id = match crate::session_store::current_session_id() {
+            Some(id) => id,
+            None => return,
+        };
+        let cut = {
+            let history = history(is_user).lock().unwrap();
+            history.slice(from_ms, to_ms).map(|(samples, start_ms)| (samples, start_ms, history.sample_rate))
+        };
+        let (samples, start_ms, sample_rate) = match cut {
+            Some(cut) => cut,
+            None => return,  // Older than the history (a very late final)
+        };
+        let path = snippets_dir().join(format!("{}_{}.wav", session_id, line));
+        let snippet = AudioSnippet {
+            line,
+            offset_ms: crate::session_store::offset_of(start_ms),
+            duration_ms: samples.len() as u64 * 1000 / sample_rate as u64,
+            confidence,
+            path: path.to_string_lossy().to_string(),
+        };
+        let kept = write_wav(&path, &samples, sample_rate)
+            .and_then(|_| crate::session_store::attach_snippet(&session_id, snippet.clone()));
+        match kept {
+            Ok(()) => info!("🎧 Kept {} ms of audio for line {} (confidence {:.2})", snippet.duration_ms, line, confidence),
+            Err(e) => {
+                error!("Failed to keep the audio of line {}: {}", line, e);
+                let _ = fs::remove_file(&path);
+                return;
+            }
+        }
+        enforce_cap(crate::preferences::load().snippets.max_total_seconds);
+    });
+}
+
+fn snippets_dir() -> PathBuf {
+    crate::app_paths::data_dir().join(SNIPPETS_DIR)
+}
+
+fn write_wav(path: &Path, samples: &[i16], sample_rate: u32) -> Result<()> {
+    fs::create_dir_all(snippets_dir())?;
+    let spec = hound::WavSpec { channels: 1, sample_rate, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut writer = hound::WavWriter::create(path, spec).context(format!("Failed to create snippet: {:?}", path))?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+/// A stored snippet file: (session id, line, duration)
+type StoredSnippet = (String, usize, u64);
+
+fn stored_snippets() -> Vec<StoredSnippet> {
+    let entries = match fs::read_dir(snippets_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    entries.filter_map(|entry| {
+        let path = entry.ok()?.path();
+        let (session_id, line) = path.file_stem()?.to_str()?.rsplit_once('_')?;
+        let reader = hound::WavReader::open(&path).ok()?;
+        let duration_ms = reader.duration() as u64 * 1000 / reader.spec().sample_rate.max(1) as u64;
+        Some((session_id.to_string(), line.parse().ok()?, duration_ms))
+    }).collect()
+}
+
+/// Snippets to delete, oldest first, to bring the total down to `max_ms` (session ids
+/// sort by start time)
+fn over_cap(mut stored: Vec<StoredSnippet>, max_ms: u64) -> Vec<StoredSnippet> {
+    stored.sort();
+    let mut total: u64 = stored.iter().map(|s| s.2).sum();
+    stored.into_iter().take_while(|s| {
+        let over = total > max_ms;
+        total -= s.2;
+        over
+    }).collect()
+}
+
+fn enforce_cap(max_total_seconds: u32) {
+    for (session_id, line, _) in over_cap(stored_snippets(), max_total_seconds as u64 * 1000) {
+        info!("🧹 Retained audio is over {} s: dropping line {} of session {}", max_total_seconds, line, session_id);
+        if let Err(e) = crate::session_store::detach_snippet(&session_id, line) {
+            warn!("⚠️ Snippet of a missing session {}: {}", session_id, e);
+            let _ = fs::remove_file(snippets_dir().join(format!("{}_{}.wav", session_id, line)));
+        }
+    }
+}
+
+/// Delete the files of snippets detached from their session
+pub fn discard(snippets: &[AudioSnippet]) {
+    for snippet in snippets {
+        if let Err(e) = fs::remove_file(&snippet.path) {
+            warn!("⚠️ Failed to delete snippet {}: {}", snippet.path, e);
+        }
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_snippet_settings() -> Result<SnippetSettings, String> {
+    Ok(crate::preferences::load().snippets)
+}
+
+// Applies from the next capture stream; a lower cap purges right away
+#[tauri::command]
+pub fn set_snippet_settings(settings: SnippetSettings) -> Result<SnippetSettings, String> {
+    if settings.snippet_seconds == 0 || settings.snippet_seconds > MAX_SNIPPET_SECONDS {
+        return Err(format!("snippet_seconds must be between 1 and {}", MAX_SNIPPET_SECONDS));
+    }
+    if settings.max_total_seconds < settings.snippet_seconds || settings.max_total_seconds > MAX_TOTAL_SECONDS {
+        return Err(format!("max_total_seconds must be between snippet_seconds and {}", MAX_TOTAL_SECONDS));
+    }
+    if !(0.0..=1.0).contains(&settings.below_confidence) {
+        return Err("below_confidence must be between 0 and 1".to_string());
+    }
+    crate::preferences::update(|p| p.snippets = settings.clone())
+        .map_err(|e| e.to_string())?;
+    enforce_cap(settings.max_total_seconds);
+    Ok(settings)
+}
+
+// Snippets of a session (the current one when no id is given)
+#[tauri::command]
+pub fn list_audio_snippets(session_id: Option<String>) -> Result<Vec<AudioSnippet>, String> {
+    crate::session_store::load_session(session_id).map(|s| s.snippets).map_err(|e| e.to_string())
+}
+
+// Replace the text of a line (the words no longer match it) and drop its snippet
+#[tauri::command]
+pub fn correct_transcript_line(session_id: Option<String>, line: usize, text: String) -> Result<TranscriptLine, String> {
+    if text.trim().is_empty() {
+        return Err("The corrected text is empty".to_string());
+    }
+    let session_id = match session_id.or_else(crate::session_store::current_session_id) {
+        Some(id) => id,
+        None => return Err("No session in progress".to_string()),
+    };
+    crate::session_store::correct_line(&session_id, line, text.trim()).map_err(|e| e.to_string())
+}
+
+// Delete the snippet of a line without correcting it
+#[tauri::command]
+pub fn discard_audio_snippet(session_id: Option<String>, line: usize) -> Result<(), String> {
+    let session_id = match session_id.or_else(crate::session_store::current_session_id) {
+        Some(id) => id,
+        None => return Err("No session in progress".to_string()),
+    };
+    crate::session_store::detach_snippet(&session_id, line).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(start_ms: u64, end_ms: u64, confidence: f32) -> TranscriptWord {
+        TranscriptWord { word: "w".to_string(), start_ms, end_ms, confidence: Some(confidence) }
+    }
+
+    #[test]
+    fn test_snippets_cover_uncertain_words_and_stay_under_the_cap() {
+        // 1 s buffers at 16 kHz from capture time 10 s: the last 45 s are kept
+        let mut history = History::default();
+        for second in 0..60u64 {
+            history.push(&vec![second as i16; 16_000], 16_000, 10_000 + second * 1000);
+        }
+        assert_eq!(history.samples.len(), 45 * 16_000);
+        let (samples, start_ms) = history.slice(60_000, 62_000).unwrap();
+        assert_eq!((samples.len(), start_ms, samples[0], samples[16_000]), (32_000, 60_000, 50, 51));
+        assert!(history.slice(0, 20_000).is_none());
+
+        // A short line is kept whole; a long one around its uncertain words
+        let words = vec![word(5_000, 5_400, 1.0), word(5_500, 6_000, 0.5)];
+        assert_eq!(snippet_span(&words, 5_000, 1_000, 0.6, 10_000), (4_500, 6_500));
+        let long = vec![word(0, 1_000, 0.9), word(18_000, 19_000, 0.2), word(29_000, 30_000, 0.9)];
+        assert_eq!(snippet_span(&long, 0, 30_000, 0.6, 10_000), (13_500, 23_500));
+        assert_eq!(mean_confidence(&words), Some(0.75));
+
+        // 25 s in four snippets under a 12 s cap: the two oldest go
+        let stored = vec![
+            ("20261016-120000".to_string(), 7, 5_000),
+            ("20261015-090000".to_string(), 12, 10_000),
+            ("20261016-120000".to_string(), 3, 5_000),
+            ("20261016-120000".to_string(), 9, 5_000),
+        ];
+        let dropped: Vec<(String, usize)> = over_cap(stored, 12_000).into_iter().map(|(id, line, _)| (id, line)).collect();
+        assert_eq!(dropped, vec![("20261015-090000".to_string(), 12), ("20261016-120000".to_string(), 3)]);
+    }
+}
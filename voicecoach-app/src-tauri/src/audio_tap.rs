@@ -0,0 +1,25 @@
+// Audio Tap Commands - the debug tee switched from the debug panel
+// The tee itself is in voicecoach-core (re-exported here under the old path).
+
+pub use voicecoach_core::audio_tap::*;
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn enable_audio_tap(
+    path: String,
+    file_seconds: Option<u32>,
+    max_files: Option<u32>,
+) -> Result<AudioTapStatus, String> {
+    enable(path, file_seconds, max_files)
+}
+
+#[tauri::command]
+pub fn disable_audio_tap() -> Result<AudioTapStatus, String> {
+    Ok(disable())
+}
+
+#[tauri::command]
+pub fn get_audio_tap_status() -> Result<AudioTapStatus, String> {
+    Ok(status())
+}
@@ -0,0 +1,312 @@
+// TypeScript Bindings - generated types for command arguments, results and event payloads
+// Every serde type that crosses the IPC boundary derives specta::Type and is listed
+// below; export writes them (and everything they reference) to src/types/bindings.ts,
+// which the UI imports instead of hand-written interfaces. Debug builds regenerate the
+// file on startup (so `tauri dev` keeps it current), and a test fails when the
+// committed file no longer matches the Rust types.
+
+use std::path::PathBuf;
+use specta::TypeCollection;
+use specta_typescript::{BigIntExportBehavior, Typescript};
+
+const HEADER: &str = "// Source: src-tauri/src/bindings.rs (regenerated by debug builds; `cargo test bindings` checks it)";
+
+fn types() -> TypeCollection {
+    let mut types = TypeCollection::default();
+    types
+        .register::<crate::AudioStatus>()
+        .register::<crate::AudioDevice>()
+        .register::<crate::AudioLevels>()
+        .register::<crate::PerformanceMetrics>()
+        .register::<crate::vosk_transcription::EndpointingSettings>()
+        .register::<crate::vosk_transcription::TranscriptionPayload>()
+        .register::<crate::vosk_transcription::StandbyStatus>()
+        .register::<crate::deepgram_transcription::TranscriptionPayload>()
+        .register::<crate::ws_watchdog::CloudReconnect>()
+        .register::<crate::breadcrumb_system::Breadcrumb>()
+        .register::<crate::breadcrumb_system::BreadcrumbQuery>()
+        .register::<crate::breadcrumb_system::BreadcrumbPage>()
+        .register::<crate::device_conflict::ConflictKind>()
+        .register::<crate::device_conflict::StreamConfigInfo>()
+        .register::<crate::device_conflict::DeviceConflictEvent>()
+        .register::<crate::device_selection::DeviceKind>()
+        .register::<crate::device_selection::RuleAction>()
+        .register::<crate::device_selection::DeviceRule>()
+        .register::<crate::device_selection::RankedDevice>()
+        .register::<crate::device_selection::DeviceSelectionEvent>()
+        .register::<crate::credentials::StoredKey>()
+        .register::<crate::credentials::TrustedSigner>()
+        .register::<crate::credentials::ExportKeyInfo>()
+        .register::<crate::export_security::ExportSecuritySettings>()
+        .register::<crate::export_security::ChunkEntry>()
+        .register::<crate::export_security::ExportManifest>()
+        .register::<crate::export_security::SealedExport>()
+        .register::<crate::export_security::ExportVerification>()
+        .register::<crate::level_calibration::LevelSource>()
+        .register::<crate::level_calibration::SourceCalibration>()
+        .register::<crate::level_calibration::LevelCalibration>()
+        .register::<crate::level_calibration::LevelProgress>()
+        .register::<crate::live_doc::LiveDocProvider>()
+        .register::<crate::live_doc::LiveDocSettings>()
+        .register::<crate::live_doc::LiveDocStatus>()
+        .register::<crate::profanity_filter::FilterMode>()
+        .register::<crate::profanity_filter::ProfanitySettings>()
+        .register::<crate::speakers::SpeakerInfo>()
+        .register::<crate::speakers::SpeakersUpdate>()
+        .register::<crate::license::Tier>()
+        .register::<crate::license::Feature>()
+        .register::<crate::license::LicenseState>()
+        .register::<crate::license::Activation>()
+        .register::<crate::license::LicenseStatus>()
+        .register::<crate::updates::UpdateSettings>()
+        .register::<crate::updates::Release>()
+        .register::<crate::updates::UpdateState>()
+        .register::<crate::updates::ChangelogEntry>()
+        .register::<crate::updates::UpdateStatus>()
+        .register::<crate::sales_stage::SalesStage>()
+        .register::<crate::sales_stage::StageVocabulary>()
+        .register::<crate::sales_stage::StageBiasSettings>()
+        .register::<crate::sales_stage::StageStatus>()
+        .register::<crate::memory_budget::MemoryBudget>()
+        .register::<crate::memory_budget::PressureLevel>()
+        .register::<crate::memory_budget::MemoryUsage>()
+        .register::<crate::live_listen::LiveListenStatus>()
+        .register::<crate::live_listen::ManagerSuggestion>()
+        .register::<crate::chaos::Fault>()
+        .register::<crate::chaos::FaultInjection>()
+        .register::<crate::chaos::FaultStatus>()
+        .register::<crate::punctuation::PunctuationSettings>()
+        .register::<crate::app_audio::AppAudioTarget>()
+        .register::<crate::session_store::PromptRating>()
+        .register::<crate::session_store::CallOutcome>()
+        .register::<crate::session_store::SessionPrompt>()
+        .register::<crate::session_store::Session>()
+        .register::<crate::telemetry::TelemetrySettings>()
+        .register::<crate::telemetry::TelemetryStatus>()
+        .register::<crate::read_aloud::ScriptedPeriod>()
+        .register::<crate::read_aloud::ReadAloudStatus>()
+        .register::<crate::cloud_usage::SilenceSkipSettings>()
+        .register::<crate::cloud_usage::UsagePeriod>()
+        .register::<crate::cloud_usage::UsageReport>()
+        .register::<crate::prospect_questions::ProspectQuestion>()
+        .register::<crate::followup_email::EmailFormat>()
+        .register::<crate::followup_email::EmailTemplateSettings>()
+        .register::<crate::followup_email::ProspectProfile>()
+        .register::<crate::followup_email::FollowupEmailDraft>()
+        .register::<crate::privacy::PrivacySettings>()
+        .register::<crate::privacy::CaptureState>()
+        .register::<crate::two_pass::Engine>()
+        .register::<crate::two_pass::TwoPassMode>()
+        .register::<crate::two_pass::ProvisionalSegment>()
+        .register::<crate::two_pass::Outcome>()
+        .register::<crate::two_pass::Reconciled>()
+        .register::<crate::prospect_brief::EnrichmentProvider>()
+        .register::<crate::prospect_brief::EnrichmentSettings>()
+        .register::<crate::prospect_brief::BriefSource>()
+        .register::<crate::prospect_brief::ProspectBrief>()
+        .register::<crate::topic_segmentation::TopicChapter>()
+        .register::<crate::document_processing::DocumentProcessingStats>()
+        .register::<crate::document_processing::KnowledgeSearchResult>()
+        .register::<crate::document_processing::CoachingSuggestion>()
+        .register::<crate::document_processing::KnowledgeValidation>()
+        .register::<crate::document_processing::UrlSource>()
+        .register::<crate::ollama_integration::CoachingSuggestion>()
+        .register::<crate::ollama_integration::CoachingHistoryEntry>()
+        .register::<crate::ollama_integration::PromptIncoming>()
+        .register::<crate::ollama_integration::PromptCancelled>()
+        .register::<crate::ollama_integration::KnowledgeDocument>()
+        .register::<crate::knowledge_base::KnowledgeDocument>()
+        .register::<crate::knowledge_base::KnowledgeCitation>()
+        .register::<crate::knowledge_base::KnowledgePassage>()
+        .register::<crate::knowledge_base::ProcessingStats>()
+        .register::<crate::knowledge_base::KnowledgeBaseStats>()
+        .register::<crate::file_transcription::FileTranscriptSegment>()
+        .register::<crate::file_transcription::FileTranscript>()
+        .register::<crate::audio_tap::AudioTapStatus>()
+        .register::<crate::obs_integration::ObsSettings>()
+        .register::<crate::obs_integration::ObsStatus>()
+        .register::<crate::logging::LogConfig>()
+        .register::<crate::call_analytics::ChecklistItemDef>()
+        .register::<crate::call_analytics::ChecklistItemStatus>()
+        .register::<crate::call_analytics::ChecklistStatus>()
+        .register::<crate::call_analytics::ChecklistItemCompleted>()
+        .register::<crate::call_analytics::TalkRatio>()
+        .register::<crate::call_analytics::CallMetrics>()
+        .register::<crate::call_analytics::CallSummary>()
+        .register::<crate::preferences::Preferences>()
+        .register::<crate::calibration::CalibrationSettings>()
+        .register::<crate::calibration::ChunkMeasurement>()
+        .register::<crate::calibration::CalibrationResult>()
+        .register::<crate::analytics_export::ExportFormat>()
+        .register::<crate::analytics_export::AnalyticsExport>()
+        .register::<crate::sidetone::SidetoneSettings>()
+        .register::<crate::sidetone::SidetoneStatus>()
+        .register::<crate::session_templates::RubricMetric>()
+        .register::<crate::session_templates::RubricCriterion>()
+        .register::<crate::session_templates::SessionTemplate>()
+        .register::<crate::session_templates::SessionTemplateSettings>()
+        .register::<crate::competitor_watch::Competitor>()
+        .register::<crate::competitor_watch::CompetitorWatchlist>()
+        .register::<crate::competitor_watch::CompetitorMention>()
+        .register::<crate::playback::PlaybackStatus>()
+        .register::<crate::session_store::TranscriptWord>()
+        .register::<crate::session_store::TranscriptLine>()
+        .register::<crate::transcript_search::TranscriptMatch>()
+        .register::<crate::i18n::LocaleStatus>()
+        .register::<crate::mic_quality::MicIssue>()
+        .register::<crate::mic_quality::MicQualitySettings>()
+        .register::<crate::mic_quality::MicLevels>()
+        .register::<crate::mic_quality::MicAdjustment>()
+        .register::<crate::mic_quality::MicQualityStatus>()
+        .register::<crate::coaching_cooldown::CoachingProfile>()
+        .register::<crate::control_interface::ControlInterfaceSettings>()
+        .register::<crate::control_interface::CallerInfo>()
+        .register::<crate::control_interface::ControlSessionStarted>()
+        .register::<crate::control_interface::ControlInterfaceStatus>()
+        .register::<crate::knowledge_answer::KnowledgeAnswer>()
+        .register::<crate::adaptive_vad::VadSource>()
+        .register::<crate::adaptive_vad::AdaptiveVadSettings>()
+        .register::<crate::adaptive_vad::LearnedThreshold>()
+        .register::<crate::session_store::Bookmark>()
+        .register::<crate::voice_commands::VoiceAction>()
+        .register::<crate::voice_commands::VoiceCommandSettings>()
+        .register::<crate::voice_commands::VoiceCommand>()
+        .register::<crate::voice_commands::VoiceCommandInfo>()
+        .register::<crate::team_report::TeamMember>()
+        .register::<crate::team_report::OutcomeCount>()
+        .register::<crate::team_report::RepScorecard>()
+        .register::<crate::team_report::TeamReport>()
+        .register::<crate::session_store::CoachNote>()
+        .register::<crate::voice_notes::VoiceNoteProgress>()
+        .register::<crate::hardware_profile::ModelSize>()
+        .register::<crate::hardware_profile::HardwareProfile>()
+        .register::<crate::hardware_profile::ModelDecision>()
+        .register::<crate::hardware_profile::EngineHealth>()
+        .register::<crate::one_party::ProspectLevel>()
+        .register::<crate::prospect_memory::ProspectMemory>()
+        .register::<crate::waveform::Waveform>()
+        .register::<crate::session_arbiter::ArbiterStatus>()
+        .register::<crate::session_arbiter::SessionTakeover>()
+        .register::<crate::whisper_channel::WhisperChannelSettings>()
+        .register::<crate::whisper_channel::OutputDeviceInfo>()
+        .register::<crate::startup::StartupOptions>()
+        .register::<crate::startup::StartupDeviceCheck>()
+        .register::<crate::session_store::ScratchNote>()
+        .register::<crate::scratchpad::TimelineKind>()
+        .register::<crate::scratchpad::TimelineEntry>()
+        .register::<crate::scratchpad::NotesExport>()
+        .register::<crate::rolling_summary::WindowSummary>()
+        .register::<crate::rolling_summary::RollingSummary>()
+        .register::<crate::catch_up::CatchUpTurn>()
+        .register::<crate::catch_up::CatchUp>()
+        .register::<crate::pipeline_stats::PipelineStream>()
+        .register::<crate::pipeline_stats::HistogramBucket>()
+        .register::<crate::pipeline_stats::HistogramSnapshot>()
+        .register::<crate::pipeline_stats::CallbackStats>()
+        .register::<crate::session_import::TranscriptFormat>()
+        .register::<crate::session_import::ImportedSession>()
+        .register::<crate::hold_detection::HoldDetectionSettings>()
+        .register::<crate::hold_detection::QuietKind>()
+        .register::<crate::hold_detection::QuietPeriod>()
+        .register::<crate::rule_harness::RuleEvaluation>()
+        .register::<crate::rule_harness::RuleTestReport>()
+        .register::<crate::transcript_quality::TranscriptQualitySettings>()
+        .register::<crate::transcript_quality::TranscriptQuality>()
+        .register::<crate::session_store::SessionListing>()
+        .register::<crate::peer_benchmark::BenchmarkSettings>()
+        .register::<crate::peer_benchmark::BenchmarkContribution>()
+        .register::<crate::peer_benchmark::BenchmarkPreview>()
+        .register::<crate::peer_benchmark::PeerStats>()
+        .register::<crate::peer_benchmark::MetricBenchmark>()
+        .register::<crate::peer_benchmark::PeerBenchmarks>()
+        .register::<crate::peer_benchmark::BenchmarkOptOut>()
+        .register::<crate::hardware_mute::HardwareMuteSettings>()
+        .register::<crate::hardware_mute::MutedInterval>()
+        .register::<crate::audio_snippets::SnippetSettings>()
+        .register::<crate::audio_snippets::AudioSnippet>()
+        .register::<crate::noise_suppression::NoiseProfile>()
+        .register::<crate::noise_suppression::NoiseSuppressionSettings>()
+        .register::<crate::no_coach_zones::NoCoachZone>()
+        .register::<crate::no_coach_zones::CallContext>()
+        .register::<crate::no_coach_zones::ZoneMatch>()
+        .register::<crate::no_coach_zones::NoCoachOverride>()
+        .register::<crate::transcript_stream::TranscriptStreamFormat>()
+        .register::<crate::transcript_stream::TranscriptStreamStatus>()
+        .register::<crate::window_layout::ScreenCorner>()
+        .register::<crate::window_layout::WindowPlacement>()
+        .register::<crate::acceleration::AccelerationBackend>()
+        .register::<crate::acceleration::WhisperModelSize>()
+        .register::<crate::acceleration::GpuDevice>()
+        .register::<crate::acceleration::WhisperFeasibility>()
+        .register::<crate::acceleration::AccelerationReport>()
+        .register::<crate::acceleration::WhisperBackendSettings>()
+        .register::<crate::acceleration::WhisperBackendChoice>()
+        .register::<crate::stage_bypass::PipelineStage>()
+        .register::<crate::similar_sessions::SimilarSession>()
+        .register::<crate::similar_sessions::SessionIndexStatus>()
+        .register::<crate::audio_clock::ClockAnchor>()
+        .register::<crate::settings_profile::SettingsProfileReport>()
+        .register::<crate::session_share::ShareOptions>()
+        .register::<crate::session_share::SharedSessionExport>()
+        .register::<crate::recognition_grammar::GrammarPurpose>()
+        .register::<crate::recognition_grammar::RecognitionGrammar>()
+        .register::<crate::recognition_grammar::GrammarResult>()
+        .register::<crate::transcript_plugins::TranscriptPluginSettings>()
+        .register::<crate::transcript_plugins::TranscriptPlugin>()
+        .register::<crate::experiments::ExperimentSettings>()
+        .register::<crate::experiments::ExperimentResults>()
+        .register::<crate::recording_conversion::SharingCopy>()
+        .register::<crate::recording_conversion::ConversionProgress>()
+        .register::<crate::transcript_confidence::TranscriptConfidence>()
+        .register::<crate::talk_over::TalkOverSettings>()
+        .register::<crate::talk_over::TalkOverNudge>()
+        .register::<crate::key_moments::KeyMoment>()
+        .register::<crate::sentences::Sentence>()
+        .register::<crate::call_recording::CallRecordingSettings>()
+        .register::<crate::call_recording::RecordingStorageWarning>()
+        .register::<crate::call_recording::RecordingStorage>()
+        .register::<crate::vosk_model::DecodingGraph>()
+        .register::<crate::vosk_model::ModelLayout>()
+        .register::<crate::model_benchmark::ClipBenchmark>()
+        .register::<crate::model_benchmark::ModelBenchmark>();
+    types
+}
+
+fn exporter() -> Typescript {
+    // u64 timestamps and counters arrive as JSON numbers, not BigInt
+    Typescript::default().header(HEADER).bigint(BigIntExportBehavior::Number)
+}
+
+pub fn bindings_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../src/types/bindings.ts")
+}
+
+/// Contents of bindings.ts for the current types
+pub fn render() -> Result<String, String> {
+    exporter().export(&types()).map_err(|e| format!("Failed to export TypeScript bindings: {}", e))
+}
+
+/// Regenerate src/types/bindings.ts (only written when it changed)
+pub fn export() -> Result<bool, String> {
+    let rendered = render()?;
+    let path = bindings_path();
+    if std::fs::read_to_string(&path).map_or(false, |current| current == rendered) {
+        return Ok(false);
+    }
+    std::fs::write(&path, rendered).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_committed_bindings_match_the_rust_types() {
+        let committed = std::fs::read_to_string(bindings_path()).unwrap_or_default();
+        assert!(
+            committed == render().unwrap(),
+            "src/types/bindings.ts is out of date - run a debug build (tauri dev) to regenerate it"
+        );
+    }
+}
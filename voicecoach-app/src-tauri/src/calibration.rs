@@ -0,0 +1,208 @@
+// Chunk-Size Calibration - measures Vosk decode throughput on this machine
+// Feeds synthetic speech-like audio through each available model at several
+// chunk durations, then picks the model + chunk size that keeps end-to-end
+// latency under the configured target. Results are stored in preferences.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use log::{info, warn};
+use vosk::{Model, Recognizer};
+
+use voicecoach_core::model_benchmark::synthetic_speech;
+
+const SAMPLE_RATE: u32 = 16000;
+const CALIBRATION_SECONDS: usize = 5;
+const CANDIDATE_CHUNK_MS: [u32; 5] = [100, 200, 250, 500, 1000];
+// Decoding must stay well ahead of real time or the buffer backs up
+const MAX_REAL_TIME_FACTOR: f32 = 0.7;
+
+// vosk-config "calibration" section
+#[derive(Serialize, Deserialize, Clone, Debug, specta::Type)]
+pub struct CalibrationSettings {
+    #[serde(default = "default_target_latency_ms")]
+    pub target_latency_ms: u32,
+    #[serde(default = "default_auto_calibrate")]
+    pub auto_calibrate_on_first_run: bool,
+}
+
+fn default_target_latency_ms() -> u32 { 600 }
+fn default_auto_calibrate() -> bool { true }
+
+impl Default for CalibrationSettings {
+    fn default() -> Self {
+        Self {
+            target_latency_ms: default_target_latency_ms(),
+            auto_calibrate_on_first_run: default_auto_calibrate(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ChunkMeasurement {
+    pub model: String,
+    pub chunk_ms: u32,
+    pub avg_decode_ms: f32,
+    pub p90_decode_ms: f32,
+    pub real_time_factor: f32,
+    pub estimated_latency_ms: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CalibrationResult {
+    pub model: String,              // "large" or "small"
+    pub model_path: String,
+    pub chunk_ms: u32,
+    pub chunk_samples: u32,         // chunk_ms expressed in 16kHz samples (cpal buffer size)
+    pub estimated_latency_ms: u32,
+    pub target_latency_ms: u32,
+    pub meets_target: bool,
+    pub measurements: Vec<ChunkMeasurement>,
+    pub calibrated_at: String,
+}
+
+fn measure_chunk(model: &Model, label: &str, audio: &[i16], chunk_ms: u32) -> Result<ChunkMeasurement> {
+    let mut recognizer = Recognizer::new(model, SAMPLE_RATE as f32)
+        .ok_or_else(|| anyhow!("Failed to create recognizer"))?;
+    let chunk_samples = (chunk_ms * SAMPLE_RATE / 1000) as usize;
+
+    let mut timings: Vec<f32> = Vec::new();
+    for chunk in audio.chunks(chunk_samples) {
+        let start = Instant::now();
+        recognizer.accept_waveform(chunk)
+            .map_err(|e| anyhow!("Decode failed: {:?}", e))?;
+        timings.push(start.elapsed().as_secs_f32() * 1000.0);
+    }
+    let _ = recognizer.final_result();
+
+    // First chunk includes graph warmup
+    if timings.len() > 1 {
+        timings.remove(0);
+    }
+    let avg = timings.iter().sum::<f32>() / timings.len().max(1) as f32;
+    timings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let p90 = timings.get(timings.len() * 9 / 10).copied().unwrap_or(avg);
+
+    Ok(ChunkMeasurement {
+        model: label.to_string(),
+        chunk_ms,
+        avg_decode_ms: avg,
+        p90_decode_ms: p90,
+        real_time_factor: avg / chunk_ms as f32,
+        // Worst case: a word spoken at the start of a chunk waits the whole chunk, then decodes
+        estimated_latency_ms: chunk_ms + p90.ceil() as u32,
+    })
+}
+
+/// Run calibration across the configured models.
+/// `preloaded` lets the app reuse its already-loaded model instead of loading it twice.
+pub fn calibrate(
+    large_model: &str,
+    small_model: &str,
+    target_latency_ms: u32,
+    preloaded: Option<(&str, Arc<Model>)>,
+) -> Result<CalibrationResult> {
+    info!("⏱️ LED 8400: Starting chunk-size calibration (target {}ms)", target_latency_ms);
+    let audio = synthetic_speech(CALIBRATION_SECONDS);
+
+    let mut measurements = Vec::new();
+    let mut best: Option<(String, String, ChunkMeasurement)> = None;
+    let mut fastest: Option<(String, String, ChunkMeasurement)> = None;
+
+    // Large model first: if it fits the budget we prefer its accuracy
+    for (label, path) in [("large", large_model), ("small", small_model)] {
+        if !Path::new(path).exists() {
+            info!("⏭️ LED 8401: Skipping {} model (not found at {})", label, path);
+            continue;
+        }
+
+        let model = match &preloaded {
+            Some((loaded_path, model)) if *loaded_path == path => model.clone(),
+            _ => match Model::new(path) {
+                Some(model) => Arc::new(model),
+                None => {
+                    warn!("⚠️ LED 8402: Failed to load {} model at {}", label, path);
+                    continue;
+                }
+            },
+        };
+
+        for chunk_ms in CANDIDATE_CHUNK_MS {
+            let measurement = measure_chunk(&model, label, &audio, chunk_ms)?;
+            info!("📏 LED 8403: {} model @ {}ms chunks: avg {:.1}ms, p90 {:.1}ms, RTF {:.2}, latency ~{}ms",
+                label, chunk_ms, measurement.avg_decode_ms, measurement.p90_decode_ms,
+                measurement.real_time_factor, measurement.estimated_latency_ms);
+
+            let keeps_up = measurement.real_time_factor <= MAX_REAL_TIME_FACTOR;
+            if keeps_up && measurement.estimated_latency_ms <= target_latency_ms {
+                // Chunks are ascending, so the last one within budget is the most accurate
+                best = Some((label.to_string(), path.to_string(), measurement.clone()));
+            }
+            if keeps_up && fastest.as_ref().map_or(true, |(_, _, f)| measurement.estimated_latency_ms < f.estimated_latency_ms) {
+                fastest = Some((label.to_string(), path.to_string(), measurement.clone()));
+            }
+            measurements.push(measurement);
+        }
+
+        if best.is_some() {
+            break;
+        }
+    }
+
+    let meets_target = best.is_some();
+    let (model, model_path, chosen) = best.or(fastest)
+        .ok_or_else(|| anyhow!("No Vosk model could keep up with real-time audio on this machine"))?;
+
+    if !meets_target {
+        warn!("⚠️ LED 8404: No configuration meets {}ms target, using lowest latency option", target_latency_ms);
+    }
+    info!("✅ LED 8405: Calibrated: {} model, {}ms chunks (~{}ms latency)", model, chosen.chunk_ms, chosen.estimated_latency_ms);
+
+    Ok(CalibrationResult {
+        model,
+        model_path,
+        chunk_ms: chosen.chunk_ms,
+        chunk_samples: chosen.chunk_ms * SAMPLE_RATE / 1000,
+        estimated_latency_ms: chosen.estimated_latency_ms,
+        target_latency_ms,
+        meets_target,
+        measurements,
+        calibrated_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Calibrate with vosk-config settings and store the result in preferences
+pub fn calibrate_and_store(target_override: Option<u32>, preloaded: Option<(&str, Arc<Model>)>) -> Result<CalibrationResult> {
+    let (large_model, small_model) = crate::vosk_transcription::configured_model_paths()?;
+    let settings = crate::vosk_transcription::configured_calibration();
+    let target = target_override.unwrap_or(settings.target_latency_ms);
+
+    let result = calibrate(&large_model, &small_model, target, preloaded)?;
+    crate::preferences::update(|p| p.calibration = Some(result.clone()))?;
+    Ok(result)
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub async fn run_calibration(
+    state: tauri::State<'_, crate::VoskAppState>,
+    target_latency_ms: Option<u32>,
+) -> Result<CalibrationResult, String> {
+    let (model_path, preloaded) = state.preloaded();
+
+    tokio::task::spawn_blocking(move || {
+        let preloaded = preloaded.map(|model| (model_path.as_str(), model));
+        calibrate_and_store(target_latency_ms, preloaded)
+    })
+        .await
+        .map_err(|e| format!("Calibration task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_calibration() -> Result<Option<CalibrationResult>, String> {
+    Ok(crate::preferences::load().calibration)
+}
@@ -0,0 +1,167 @@
+// Call Analytics Commands - call tracking wired to the app's sessions and coaching
+// The checklist, talk ratio and metrics are kept in voicecoach-core (re-exported here
+// under the old path). The app's adapter (AppEvents) is what core hands each final line
+// to: the session store, audio snippets and the coaching modules following the call,
+// with the checklist and stage settings read from preferences. begin_call starts both
+// halves of a new call; get_call_summary collects the call's progress.
+
+pub use voicecoach_core::call_analytics::*;
+pub(crate) use voicecoach_core::phrases::{count_phrases, find_phrase};
+
+use serde::Serialize;
+use log::warn;
+use voicecoach_core::call_analytics as analytics;
+use voicecoach_core::sales_stage::StageBiasSettings;
+
+use crate::event_sink::AppEvents;
+use crate::session_store::TranscriptWord;
+
+// End-of-call (or so-far) summary of the current call
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CallSummary {
+    pub checklist: ChecklistStatus,
+    pub talk_ratio: TalkRatio,
+    pub prospect_questions: Vec<crate::prospect_questions::ProspectQuestion>,
+    pub unanswered_questions: usize,
+    pub chapters: Vec<crate::topic_segmentation::TopicChapter>,
+    pub key_moments: Vec<crate::key_moments::KeyMoment>,  // Ranked
+}
+
+fn checklist_definitions() -> Vec<ChecklistItemDef> {
+    let preferences = crate::preferences::load();
+    // The active session template brings its own checklist
+    if let Some(template) = crate::session_templates::active_in(&preferences).filter(|t| !t.checklist.is_empty()) {
+        return template.checklist;
+    }
+    if preferences.checklist.is_empty() { default_checklist() } else { preferences.checklist }
+}
+
+fn save_metrics(metrics: CallMetrics) {
+    if let Err(e) = crate::session_store::attach_metrics(metrics) {
+        warn!("⚠️ Failed to save call metrics: {}", e);
+    }
+}
+
+impl CallObservers for AppEvents {
+    fn checklist(&self) -> Vec<ChecklistItemDef> {
+        checklist_definitions()
+    }
+
+    fn stage_bias(&self) -> StageBiasSettings {
+        crate::preferences::load().stage_bias
+    }
+
+    fn offset_of(&self, capture_ms: u64) -> u64 {
+        crate::session_store::offset_of(capture_ms)
+    }
+
+    fn record_line(&self, text: &str, is_user: bool, speech_ms: u64, capture_ms: u64, words: Vec<TranscriptWord>) -> Option<usize> {
+        // Keep the audio of a line the engine was unsure of for the rep to check
+        let disputed = crate::audio_snippets::disputed(&words, is_user, capture_ms, speech_ms);
+        let line = crate::session_store::record_line(capture_ms, is_user, text, words);
+        if let (Some(disputed), Some(line)) = (disputed, line) {
+            crate::audio_snippets::retain(line, disputed);
+        }
+        line
+    }
+
+    fn follow_line(&self, text: &str, is_user: bool) -> bool {
+        let scripted = crate::read_aloud::observe(&self.0, text, is_user);
+        crate::topic_segmentation::observe(&self.0, text);
+        crate::rolling_summary::observe(&self.0);
+        crate::competitor_watch::observe(&self.0, text, is_user);
+        if is_user {
+            crate::mic_quality::rep_spoke();
+        }
+        scripted
+    }
+
+    fn on_hold_at(&self, capture_ms: u64) -> bool {
+        crate::hold_detection::on_hold_at(capture_ms)
+    }
+
+    fn bookmark_objection(&self, line: usize) {
+        crate::session_store::bookmark_objection(line);
+    }
+
+    fn save_metrics(&self, metrics: CallMetrics) {
+        save_metrics(metrics);
+    }
+}
+
+/// Start of a new call: clear per-call progress and pick up checklist edits
+pub fn begin_call() {
+    if let Some(metrics) = analytics::begin_call(checklist_definitions()) {
+        save_metrics(metrics);
+    }
+    crate::transcript_sequencer::begin_call();
+    crate::read_aloud::begin_call();
+    crate::topic_segmentation::begin_call();
+    crate::competitor_watch::begin_call();
+    crate::coaching_cooldown::begin_call();
+    crate::adaptive_vad::begin_call();
+    crate::voice_commands::begin_call();
+    crate::prospect_memory::begin_call();
+    crate::rolling_summary::begin_call();
+    crate::hold_detection::begin_call();
+    crate::noise_suppression::begin_call();
+}
+
+/// Metrics of a finished transcript, with the app's checklist and stage settings
+/// (sessions imported by session_import)
+pub fn transcript_metrics(lines: &[(crate::session_store::TranscriptLine, u64)]) -> CallMetrics {
+    analytics::transcript_metrics(lines, checklist_definitions(), &crate::preferences::load().stage_bias)
+}
+
+/// Summary of the call so far
+pub fn call_summary() -> CallSummary {
+    let checklist = checklist_status(checklist_definitions);
+    let talk_ratio = talk_ratio(checklist_definitions);
+    let prospect_questions = crate::prospect_questions::questions();
+    let unanswered_questions = prospect_questions.iter().filter(|q| !q.answered).count();
+    let chapters = crate::topic_segmentation::chapters();
+    let key_moments = crate::key_moments::current();
+    CallSummary { checklist, talk_ratio, prospect_questions, unanswered_questions, chapters, key_moments }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_talk_ratio() -> Result<TalkRatio, String> {
+    Ok(talk_ratio(checklist_definitions))
+}
+
+#[tauri::command]
+pub fn get_call_summary() -> Result<CallSummary, String> {
+    Ok(call_summary())
+}
+
+#[tauri::command]
+pub fn get_checklist_status() -> Result<ChecklistStatus, String> {
+    Ok(checklist_status(checklist_definitions))
+}
+
+// Replace the checklist definition (persisted; resets current progress)
+#[tauri::command]
+pub fn set_checklist(items: Vec<ChecklistItemDef>) -> Result<ChecklistStatus, String> {
+    if items.iter().any(|i| i.id.trim().is_empty() || i.phrases.is_empty()) {
+        return Err("Every checklist item needs an id and at least one phrase".to_string());
+    }
+
+    crate::preferences::update(|p| p.checklist = items.clone())
+        .map_err(|e| e.to_string())?;
+    begin_call();
+    Ok(checklist_status(checklist_definitions))
+}
+
+#[tauri::command]
+pub fn reset_checklist() -> Result<ChecklistStatus, String> {
+    begin_call();
+    Ok(checklist_status(checklist_definitions))
+}
+
+// Manual tick/untick from the UI
+#[tauri::command]
+pub fn mark_checklist_item(id: String, completed: bool) -> Result<ChecklistStatus, String> {
+    mark_item(checklist_definitions, &id, completed)
+}
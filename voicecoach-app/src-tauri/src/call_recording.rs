@@ -0,0 +1,577 @@
+// Call Recording - the call's audio in a WAV linked to its session
+// With recording on, both capture streams hand the audio they feed the engine to a
+// writer thread, which mixes the sides on the capture clock into a mono WAV on the
+// session's time (second 0 is the start of the transcript, so playback and the waveform
+// line up with it) and links it to the session when the call stops. A full disk must
+// not lose the call silently: free space is checked before the recording starts and
+// every SPACE_CHECK_MS while it runs. Below twice the configured minimum the recording
+// continues at half the sample rate; below the minimum, or when a write fails, it
+// continues in the fallback directory. Either switch starts a new part, the parts are
+// joined when the call ends, and each is reported as a "recording_storage_warning" -
+// as is running out of places to write. How the recording ended up (complete,
+// degraded, cut short and by how much) is kept with the session. Off by default:
+// recording a call may need the other party's consent (one_party).
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+use anyhow::{Result, Context};
+
+const RECORDINGS_DIR: &str = "recordings";
+// 16-bit mono: 256 kbps, and 128 kbps under space pressure
+const FULL_RATE: u32 = 16_000;
+const REDUCED_RATE: u32 = 8_000;
+// Audio held back from the file so the later of the two sides can still be mixed in
+const MIX_LAG_MS: u64 = 1_000;
+const SPACE_CHECK_MS: u64 = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CallRecordingSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Where recordings are written (recordings/ in the app data directory by default)
+    #[serde(default)]
+    pub directory: Option<String>,
+    /// Where a recording continues when that directory is full or failing
+    #[serde(default)]
+    pub fallback_directory: Option<String>,
+    /// Free space a recording leaves on its disk
+    #[serde(default = "default_min_free_mb")]
+    pub min_free_mb: u64,
+}
+
+fn default_min_free_mb() -> u64 { 500 }
+
+impl Default for CallRecordingSettings {
+    fn default() -> Self {
+        Self { enabled: false, directory: None, fallback_directory: None, min_free_mb: default_min_free_mb() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingWarningKind {
+    BitrateReduced,  // Continuing at REDUCED_RATE
+    FailedOver,      // Continuing in the fallback directory
+    Stopped,         // Nowhere left to write: the rest of the call isn't recorded
+}
+
+// Payload of "recording_storage_warning", also kept with the session
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RecordingStorageWarning {
+    pub kind: RecordingWarningKind,
+    pub offset_ms: u64,              // Time since the call started
+    pub directory: Option<String>,   // Where the recording continues
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingState {
+    Complete,      // The whole call at full quality where it was meant to go
+    Degraded,      // The whole call, but (partly) at the reduced rate or in the fallback
+    Incomplete,    // Audio was lost to failed writes or a lack of space
+    NotRecorded,   // No directory had the space to start
+}
+
+// How a session's recording was stored
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RecordingStorage {
+    pub state: RecordingState,
+    pub sample_rate: u32,            // Of the recording (0 when nothing was recorded)
+    pub lost_ms: u64,                // Audio that couldn't be written
+    pub parts: Vec<String>,          // Files the recording stayed split across when joining failed
+    pub warnings: Vec<RecordingStorageWarning>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Space {
+    Ample,
+    Low,        // Under twice the minimum: reduce the rate
+    Exhausted,  // Under the minimum: move on
+}
+
+fn space(free_mb: Option<u64>, min_free_mb: u64) -> Space {
+    match free_mb {
+        // Unknown: keep writing, a failed write still fails over
+        None => Space::Ample,
+        Some(free) if free < min_free_mb => Space::Exhausted,
+        Some(free) if free < min_free_mb * 2 => Space::Low,
+        Some(_) => Space::Ample,
+    }
+}
+
+#[cfg(windows)]
+fn free_mb(dir: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available: u64 = 0;
+    if unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) } == 0 {
+        return None;
+    }
+    Some(available / (1024 * 1024))
+}
+
+#[cfg(unix)]
+fn free_mb(dir: &Path) -> Option<u64> {
+    // POSIX output: "Filesystem 1024-blocks Used Available Capacity Mounted-on"
+    let output = std::process::Command::new("df").arg("-Pk").arg(dir).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let available_kb: u64 = stdout.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available_kb / 1024)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn free_mb(_dir: &Path) -> Option<u64> {
+    None
+}
+
+fn default_directory() -> PathBuf {
+    crate::app_paths::data_dir().join(RECORDINGS_DIR)
+}
+
+/// Both sides mixed on the session's time at `rate`
+struct Mixer {
+    rate: u32,
+    written: u64,                    // Samples handed out so far
+    pending: VecDeque<i32>,          // Mixed samples from `written` on
+}
+
+impl Mixer {
+    fn new(rate: u32, from_ms: u64) -> Self {
+        Self { rate, written: from_ms * rate as u64 / 1000, pending: VecDeque::new() }
+    }
+
+    /// Add a side's buffer of `in_rate` audio starting at `offset_ms` (each output sample
+    /// the mean of the input it covers); audio older than what was handed out is dropped
+    fn add(&mut self, samples: &[i16], in_rate: u32, offset_ms: u64) {
+        let (rate, in_rate) = (self.rate as u64, in_rate.max(1) as u64);
+        let start = offset_ms * rate / 1000;
+        for i in 0..samples.len() as u64 * rate / in_rate {
+            let at = start + i;
+            if at < self.written {
+                continue;
+            }
+            let from = (i * in_rate / rate) as usize;
+            let to = (((i + 1) * in_rate / rate) as usize).max(from + 1).min(samples.len());
+            let mean = samples[from..to].iter().map(|&s| s as i32).sum::<i32>() / (to - from) as i32;
+            let index = (at - self.written) as usize;
+            if index >= self.pending.len() {
+                self.pending.resize(index + 1, 0);
+            }
+            self.pending[index] += mean;
+        }
+    }
+
+    fn end_ms(&self) -> u64 {
+        (self.written + self.pending.len() as u64) * 1000 / self.rate as u64
+    }
+
+    /// The mixed samples before `until_ms`
+    fn take(&mut self, until_ms: u64) -> Vec<i16> {
+        let until = (until_ms * self.rate as u64 / 1000).max(self.written);
+        let count = ((until - self.written) as usize).min(self.pending.len());
+        self.written += count as u64;
+        self.pending.drain(..count).map(|s| s.max(i16::MIN as i32).min(i16::MAX as i32) as i16).collect()
+    }
+}
+
+fn wav_spec(sample_rate: u32) -> hound::WavSpec {
+    hound::WavSpec { channels: 1, sample_rate, bits_per_sample: 16, sample_format: hound::SampleFormat::Int }
+}
+
+/// The file a recording is being written to
+struct Part {
+    path: PathBuf,
+    rate: u32,
+    start_ms: u64,
+    writer: hound::WavWriter<BufWriter<fs::File>>,
+}
+
+impl Part {
+    fn create(directory: &Path, session_id: &str, rate: u32, start_ms: u64) -> Result<Self> {
+        let path = (1..).map(|n| match n {
+            1 => directory.join(format!("{}.wav", session_id)),
+            n => directory.join(format!("{}-{}.wav", session_id, n)),
+        }).find(|path| !path.exists()).unwrap();
+        let writer = hound::WavWriter::create(&path, wav_spec(rate))
+            .context(format!("Failed to create recording: {:?}", path))?;
+        Ok(Self { path, rate, start_ms, writer })
+    }
+}
+
+/// A finished part: (file, sample rate, start on the session's time)
+type Written = (PathBuf, u32, u64);
+
+/// Join a recording's parts into one file at the lowest rate any of them used, in the
+/// directory of the last (the one that still had space)
+fn join(parts: &[Written], session_id: &str) -> Result<PathBuf> {
+    let rate = parts.iter().map(|p| p.1).min().unwrap_or(FULL_RATE);
+    let directory = parts.last().and_then(|p| p.0.parent()).unwrap_or_else(|| Path::new("."));
+    let joined = directory.join(format!("{}.joined.wav", session_id));
+    let mut writer = hound::WavWriter::create(&joined, wav_spec(rate))?;
+    let mut mixer = Mixer::new(rate, 0);
+    for (path, part_rate, start_ms) in parts {
+        let mut reader = hound::WavReader::open(path).context(format!("Failed to read recording part: {:?}", path))?;
+        let mut offset_ms = *start_ms;
+        let mut second = Vec::with_capacity(*part_rate as usize);
+        let mut samples = reader.samples::<i16>();
+        loop {
+            let sample = samples.next().transpose()?;
+            if let Some(sample) = sample {
+                second.push(sample);
+            }
+            if second.len() == *part_rate as usize || (sample.is_none() && !second.is_empty()) {
+                mixer.add(&second, *part_rate, offset_ms);
+                offset_ms += 1000;
+                second.clear();
+                for sample in mixer.take(mixer.end_ms()) {
+                    writer.write_sample(sample)?;
+                }
+            }
+            if sample.is_none() {
+                break;
+            }
+        }
+    }
+    writer.finalize()?;
+    for (path, _, _) in parts {
+        fs::remove_file(path).context(format!("Failed to remove recording part: {:?}", path))?;
+    }
+    let path = directory.join(format!("{}.wav", session_id));
+    fs::rename(&joined, &path)?;
+    Ok(path)
+}
+
+/// The recording of the call in progress (writer thread)
+struct Recording {
+    session_id: String,
+    min_free_mb: u64,
+    directories: Vec<PathBuf>,       // Preferred first, then the fallback
+    directory: usize,                // Index of the one in use
+    mixer: Mixer,
+    part: Option<Part>,              // None once there is nowhere left to write
+    parts: Vec<Written>,
+    reduced: bool,
+    failed_over: bool,
+    stopped: bool,
+    lost_ms: u64,
+    warnings: Vec<RecordingStorageWarning>,
+    checked_at: Instant,
+}
+
+impl Recording {
+    fn start(app: &AppHandle, session_id: String, settings: CallRecordingSettings) -> Self {
+        let mut directories = vec![settings.directory.as_ref().map_or_else(default_directory, PathBuf::from)];
+        directories.extend(settings.fallback_directory.as_ref().map(PathBuf::from));
+        let mut recording = Self {
+            session_id,
+            min_free_mb: settings.min_free_mb,
+            directories,
+            directory: 0,
+            mixer: Mixer::new(FULL_RATE, 0),
+            part: None,
+            parts: Vec::new(),
+            reduced: false,
+            failed_over: false,
+            stopped: false,
+            lost_ms: 0,
+            warnings: Vec::new(),
+            checked_at: Instant::now(),
+        };
+        recording.open(app, 0, Vec::new());
+        if let Some(part) = recording.part.as_ref() {
+            info!("⏺️ Recording session {} to {:?}", recording.session_id, part.path);
+        }
+        recording
+    }
+
+    fn warn(&mut self, app: &AppHandle, kind: RecordingWarningKind, message: String) {
+        warn!("⚠️ Recording of session {}: {}", self.session_id, message);
+        let warning = RecordingStorageWarning {
+            kind,
+            offset_ms: self.mixer.end_ms(),
+            directory: self.part.as_ref().and_then(|p| p.path.parent()).map(|d| d.to_string_lossy().to_string()),
+            message,
+        };
+        self.warnings.push(warning.clone());
+        if let Err(e) = app.emit_all("recording_storage_warning", warning) {
+            error!("Failed to emit recording_storage_warning: {:?}", e);
+        }
+    }
+
+    /// Continue in a new part in the first directory from `from` with the space for it;
+    /// `reasons` say why the directories before it were left
+    fn open(&mut self, app: &AppHandle, from: usize, mut reasons: Vec<String>) {
+        for index in from..self.directories.len() {
+            let directory = self.directories[index].clone();
+            if let Err(e) = fs::create_dir_all(&directory) {
+                reasons.push(format!("{} can't be created: {}", directory.display(), e));
+                continue;
+            }
+            let space = space(free_mb(&directory), self.min_free_mb);
+            if space == Space::Exhausted {
+                reasons.push(format!("less than {} MB free in {}", self.min_free_mb, directory.display()));
+                continue;
+            }
+            let rate = if self.reduced || space == Space::Low { REDUCED_RATE } else { FULL_RATE };
+            if rate != self.mixer.rate {
+                self.mixer = Mixer::new(rate, self.mixer.end_ms());
+            }
+            match Part::create(&directory, &self.session_id, rate, self.mixer.end_ms()) {
+                Ok(part) => self.part = Some(part),
+                Err(e) => {
+                    reasons.push(e.to_string());
+                    continue;
+                }
+            }
+            if index != self.directory || (index > 0 && !self.failed_over) {
+                self.directory = index;
+                self.failed_over = true;
+                let message = format!("Recording to {} ({})", directory.display(), reasons.join("; "));
+                self.warn(app, RecordingWarningKind::FailedOver, message);
+            }
+            if rate == REDUCED_RATE && !self.reduced {
+                self.reduced = true;
+                let message = format!("Recording at {} kHz to save space (less than {} MB free)", rate / 1000, self.min_free_mb * 2);
+                self.warn(app, RecordingWarningKind::BitrateReduced, message);
+            }
+            return;
+        }
+        self.stopped = true;
+        let message = format!("Not recording the rest of the call: {}", reasons.join("; "));
+        self.warn(app, RecordingWarningKind::Stopped, message);
+    }
+
+    fn close_part(&mut self) {
+        if let Some(Part { path, rate, start_ms, writer }) = self.part.take() {
+            if let Err(e) = writer.finalize() {
+                error!("Failed to finish recording part {:?}: {}", path, e);
+            }
+            self.parts.push((path, rate, start_ms));
+        }
+    }
+
+    fn write(&mut self, app: &AppHandle, samples: &[i16]) {
+        let lost_ms = samples.len() as u64 * 1000 / self.mixer.rate as u64;
+        let part = match self.part.as_mut() {
+            Some(part) => part,
+            None => {
+                self.lost_ms += lost_ms;
+                return;
+            }
+        };
+        if let Err(e) = samples.iter().try_for_each(|&s| part.writer.write_sample(s)) {
+            self.lost_ms += lost_ms;
+            let reason = format!("writing {:?} failed: {}", part.path, e);
+            self.close_part();
+            self.open(app, self.directory + 1, vec![reason]);
+        }
+    }
+
+    /// Move on when the disk in use runs low (everything mixed so far goes to the old part)
+    fn check_space(&mut self, app: &AppHandle) {
+        let free = free_mb(&self.directories[self.directory]);
+        let space = space(free, self.min_free_mb);
+        if space == Space::Ample || (space == Space::Low && self.reduced) {
+            return;
+        }
+        let mixed = self.mixer.take(self.mixer.end_ms());
+        self.write(app, &mixed);
+        if self.part.is_none() {
+            return;  // The write failed over already
+        }
+        self.close_part();
+        let reason = format!("less than {} MB free in {}", self.min_free_mb, self.directories[self.directory].display());
+        match space {
+            Space::Exhausted => self.open(app, self.directory + 1, vec![reason]),
+            _ => self.open(app, self.directory, Vec::new()),
+        }
+    }
+
+    fn add(&mut self, app: &AppHandle, samples: &[i16], sample_rate: u32, capture_ms: u64) {
+        self.mixer.add(samples, sample_rate, crate::session_store::offset_of(capture_ms));
+        let mixed = self.mixer.take(self.mixer.end_ms().saturating_sub(MIX_LAG_MS));
+        self.write(app, &mixed);
+        if self.part.is_some() && self.checked_at.elapsed() >= Duration::from_millis(SPACE_CHECK_MS) {
+            self.checked_at = Instant::now();
+            self.check_space(app);
+        }
+    }
+
+    fn finish(mut self, app: &AppHandle) {
+        let mixed = self.mixer.take(self.mixer.end_ms());
+        self.write(app, &mixed);
+        self.close_part();
+
+        let state = if self.parts.is_empty() {
+            RecordingState::NotRecorded
+        } else if self.stopped || self.lost_ms > 0 {
+            RecordingState::Incomplete
+        } else if self.reduced || self.failed_over {
+            RecordingState::Degraded
+        } else {
+            RecordingState::Complete
+        };
+        let first = self.parts.first().map(|p| p.0.clone());
+        let (path, parts) = match self.parts.len() {
+            0 | 1 => (first, Vec::new()),
+            _ => match join(&self.parts, &self.session_id) {
+                Ok(path) => (Some(path), Vec::new()),
+                Err(e) => {
+                    warn!("⚠️ Recording parts of session {} kept separate: {}", self.session_id, e);
+                    (first, self.parts.iter().map(|p| p.0.to_string_lossy().to_string()).collect())
+                }
+            },
+        };
+        let storage = RecordingStorage {
+            state,
+            sample_rate: self.parts.iter().map(|p| p.1).min().unwrap_or(0),
+            lost_ms: self.lost_ms,
+            parts,
+            warnings: self.warnings,
+        };
+        info!("⏹️ Recording of session {} finished ({:?}, {} ms lost)", self.session_id, storage.state, storage.lost_ms);
+        let path = path.map(|p| p.to_string_lossy().to_string());
+        if let Err(e) = crate::session_store::attach_recording(&self.session_id, path, storage) {
+            error!("Failed to link the recording of session {}: {}", self.session_id, e);
+        }
+    }
+}
+
+enum Message {
+    Audio { samples: Vec<i16>, sample_rate: u32, capture_ms: u64 },
+    End,
+}
+
+static WRITER: Lazy<Mutex<Option<Sender<Message>>>> = Lazy::new(|| Mutex::new(None));
+
+fn run(app: AppHandle, messages: Receiver<Message>) {
+    let mut recording: Option<Recording> = None;
+    // Buffers still in flight when the call stopped don't reopen its recording
+    let mut ended: Option<String> = None;
+    for message in messages.iter() {
+        match message {
+            Message::Audio { samples, sample_rate, capture_ms } => {
+                let session_id = match crate::session_store::current_session_id() {
+                    Some(id) => id,
+                    None => continue,
+                };
+                if ended.as_ref() == Some(&session_id) {
+                    continue;
+                }
+                if recording.as_ref().map_or(true, |r| r.session_id != session_id) {
+                    if let Some(finished) = recording.take() {
+                        finished.finish(&app);
+                    }
+                    recording = Some(Recording::start(&app, session_id, crate::preferences::load().call_recording));
+                }
+                if let Some(recording) = recording.as_mut() {
+                    recording.add(&app, &samples, sample_rate, capture_ms);
+                }
+            }
+            Message::End => {
+                if let Some(finished) = recording.take() {
+                    ended = Some(finished.session_id.clone());
+                    finished.finish(&app);
+                }
+            }
+        }
+    }
+}
+
+/// Feeds a capture stream's audio into the call recording
+pub struct CallRecorder {
+    sample_rate: u32,
+    messages: Sender<Message>,
+}
+
+/// Recorder for a capture stream (None when recording is off)
+pub fn recorder(app: &AppHandle, sample_rate: u32) -> Option<CallRecorder> {
+    if !crate::preferences::load().call_recording.enabled {
+        return None;
+    }
+    let messages = WRITER.lock().unwrap().get_or_insert_with(|| {
+        let (sender, receiver) = unbounded();
+        let app = app.clone();
+        std::thread::spawn(move || run(app, receiver));
+        sender
+    }).clone();
+    Some(CallRecorder { sample_rate, messages })
+}
+
+impl CallRecorder {
+    /// Record a mono buffer whose first sample was captured at `capture_ms`
+    pub fn record(&self, samples: &[i16], capture_ms: u64) {
+        // Unbounded: the audio thread never waits on the disk
+        let _ = self.messages.send(Message::Audio { samples: samples.to_vec(), sample_rate: self.sample_rate, capture_ms });
+    }
+}
+
+/// The call stopped: finish its recording and link it to the session
+pub fn end_call() {
+    if let Some(messages) = WRITER.lock().unwrap().as_ref() {
+        let _ = messages.send(Message::End);
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_call_recording_settings() -> Result<CallRecordingSettings, String> {
+    Ok(crate::preferences::load().call_recording)
+}
+
+// Applies to capture streams started from now on
+#[tauri::command]
+pub fn set_call_recording_settings(settings: CallRecordingSettings) -> Result<CallRecordingSettings, String> {
+    if settings.fallback_directory.is_some() && settings.fallback_directory == settings.directory {
+        return Err("The fallback directory must differ from the recording directory".to_string());
+    }
+    crate::preferences::update(|p| p.call_recording = settings.clone()).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+// How a session's recording was stored (the current one when no id is given)
+#[tauri::command]
+pub fn get_recording_storage(session_id: Option<String>) -> Result<Option<RecordingStorage>, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    Ok(session.recording_storage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sides_are_mixed_on_the_session_time() {
+        let mut mixer = Mixer::new(REDUCED_RATE, 0);
+        // 10 ms of the rep at 16 kHz, halved to 8 kHz by averaging pairs
+        let rep: Vec<i16> = (0..160).map(|i| if i % 2 == 0 { 100 } else { 300 }).collect();
+        mixer.add(&rep, FULL_RATE, 0);
+        // The prospect 5 ms in, already at 8 kHz
+        mixer.add(&[1_000; 80], REDUCED_RATE, 5);
+        assert_eq!(mixer.end_ms(), 15);
+
+        let mixed = mixer.take(10);
+        assert_eq!(mixed.len(), 80);
+        assert_eq!((mixed[0], mixed[39], mixed[40], mixed[79]), (200, 200, 1_200, 1_200));
+        // Too late for what was handed out: only the part after it is mixed
+        mixer.add(&[i16::MAX; 80], REDUCED_RATE, 5);
+        assert_eq!(mixer.take(15), vec![i16::MAX; 40]);
+
+        assert_eq!(space(Some(2_000), 500), Space::Ample);
+        assert_eq!(space(Some(900), 500), Space::Low);
+        assert_eq!(space(Some(100), 500), Space::Exhausted);
+        assert_eq!(space(None, 500), Space::Ample);
+    }
+}
@@ -0,0 +1,214 @@
+// Capture Host - the app around the core capture engines
+// Implements voicecoach-core's CaptureHost for the windows' event adapter: devices
+// from the device rules, streams in the privacy capture registry (with sidetone),
+// transcript clean-up, two-pass routing, and where released finals are published.
+// Each capture stream gets its detectors and recorders from `stream_audio`.
+
+use tauri::AppHandle;
+use vosk::{Model, Recognizer};
+
+use voicecoach_core::capture_host::{AppSamples, BuildStream, CaptureHost, Engine, StreamAudio};
+use voicecoach_core::transcript_sequencer::Release;
+
+use crate::event_sink::AppEvents;
+
+impl CaptureHost for AppEvents {
+    fn input_device(&self) -> Option<cpal::Device> {
+        crate::device_selection::select_input_device(&cpal::default_host())
+    }
+
+    fn system_audio_device(&self) -> Option<cpal::Device> {
+        crate::device_selection::system_audio_device(&cpal::default_host())
+    }
+
+    fn system_audio_config(&self, device: &cpal::Device) -> Result<cpal::SupportedStreamConfig, String> {
+        crate::device_selection::system_audio_config(device)
+    }
+
+    fn negotiate_input_config(&self, owner: &'static str, device: &cpal::Device, config: cpal::StreamConfig) -> Result<cpal::StreamConfig, String> {
+        crate::device_conflict::negotiate_input_config(&self.0, owner, device, config)
+    }
+
+    fn open_stream(&self, owner: &'static str, sample_rate: u32, mic: bool, build: BuildStream) -> Result<u64, String> {
+        crate::privacy::open_stream(owner, move || build().map(|stream| crate::sidetone::monitored(owner, stream, sample_rate, mic)))
+    }
+
+    fn close_streams(&self, owner: &'static str) {
+        crate::privacy::close_streams(Some(owner));
+    }
+
+    fn close_stream(&self, id: u64) -> bool {
+        crate::privacy::close_stream(id)
+    }
+
+    fn sidetone(&self, owner: &'static str, data: &[f32], channels: usize) {
+        crate::sidetone::feed(owner, data, channels);
+    }
+
+    fn start_app_capture(&self, pid: u32, on_samples: AppSamples) -> Result<(), String> {
+        crate::app_audio::start_capture(pid, on_samples)
+    }
+
+    fn stream_audio(&self, is_user: bool, sample_rate: u32, commands: Option<&Model>) -> Box<dyn StreamAudio> {
+        Box::new(AppStreamAudio::new(&self.0, is_user, sample_rate, commands))
+    }
+
+    fn wall_ms(&self, capture_ms: u64) -> u64 {
+        crate::session_store::wall_ms(capture_ms)
+    }
+
+    fn clean_text(&self, text: &str) -> String {
+        crate::profanity_filter::filter_transcript(text)
+    }
+
+    fn punctuate(&self, text: &str) -> String {
+        crate::punctuation::restore_transcript(text)
+    }
+
+    fn apply_plugins(&self, text: &str) -> String {
+        crate::transcript_plugins::apply(text)
+    }
+
+    fn transcribed(&self, engine: &str, latency_ms: u64) {
+        crate::telemetry::record_latency(crate::telemetry::Stage::Transcribe, engine, latency_ms as f64);
+        crate::telemetry::record_usage(engine);
+    }
+
+    fn engine_error(&self, engine: &str) {
+        crate::telemetry::record_error(engine);
+    }
+
+    fn recognition_grammar(&self) -> Option<Vec<String>> {
+        crate::recognition_grammar::vosk_grammar()
+    }
+
+    fn grammar_generation(&self) -> u64 {
+        crate::recognition_grammar::generation(&self.0)
+    }
+
+    fn grammar_finished(&self, text: &str, confidence: Option<f32>) {
+        crate::recognition_grammar::finished(&self.0, text, confidence);
+    }
+
+    fn emits_partials(&self, engine: Engine, is_user: bool) -> bool {
+        crate::two_pass::emits_partials(engine, is_user)
+    }
+
+    fn submit_final(&self, engine: Engine, is_user: bool, start_ms: u64, end_ms: u64, text: &str, release: Release) {
+        crate::two_pass::submit_final(&self.0, engine, is_user, start_ms, end_ms, text, release);
+    }
+
+    fn publish_final(&self, text: &str, is_user: bool, speaker: Option<&str>) {
+        crate::obs_integration::publish_caption(text);
+        match speaker {
+            Some(label) => crate::live_doc::queue_labeled_transcript(label, text),
+            None => crate::live_doc::queue_transcript(text, is_user),
+        }
+        crate::live_listen::publish_transcript(text, is_user, speaker);
+    }
+
+    fn observe_commands(&self, text: &str, is_user: bool) {
+        crate::voice_commands::observe_transcript(&self.0, text, is_user);
+    }
+
+    fn speaker_label(&self, speaker_id: u32, seconds: f32) -> String {
+        crate::speakers::label_segment(&self.0, speaker_id, seconds)
+    }
+
+    fn speaker_name(&self, speaker_id: u32) -> Option<String> {
+        crate::speakers::display_name(speaker_id)
+    }
+}
+
+/// One stream's processors, each built from preferences (None when turned off)
+struct AppStreamAudio {
+    app: AppHandle,
+    is_user: bool,
+    sample_rate: u32,
+    // Hold music and dead air on the prospect side
+    quiet: Option<crate::hold_detection::QuietDetector>,
+    // The rep's microphone muted on the device
+    mute: Option<crate::hardware_mute::MuteDetector>,
+    adaptive_vad: crate::adaptive_vad::AdaptiveVad,
+    // Either side's speech, for catching the rep talking over the prospect
+    talk_over: Option<crate::talk_over::TalkOverTracker>,
+    // Background noise removal on the rep's microphone
+    noise: Option<crate::noise_suppression::NoiseSuppressor>,
+    // Recent audio for the snippets of low-confidence lines
+    snippets: Option<crate::audio_snippets::SnippetRecorder>,
+    // Either side's audio for the call recording
+    recording: Option<crate::call_recording::CallRecorder>,
+    // Wake-word commands, on the engine's own model
+    commands: Option<Recognizer>,
+}
+
+impl AppStreamAudio {
+    fn new(app: &AppHandle, is_user: bool, sample_rate: u32, model: Option<&Model>) -> Self {
+        let vad_source = if is_user { crate::adaptive_vad::VadSource::Microphone } else { crate::adaptive_vad::VadSource::SystemAudio };
+        AppStreamAudio {
+            app: app.clone(),
+            is_user,
+            sample_rate,
+            quiet: if is_user { None } else { crate::hold_detection::detector(app, sample_rate) },
+            mute: if is_user { crate::hardware_mute::detector(app, sample_rate) } else { None },
+            adaptive_vad: crate::adaptive_vad::AdaptiveVad::new(vad_source, sample_rate),
+            talk_over: crate::talk_over::tracker(app, is_user, sample_rate),
+            noise: if is_user { crate::noise_suppression::suppressor(sample_rate) } else { None },
+            snippets: crate::audio_snippets::recorder(is_user, sample_rate),
+            recording: crate::call_recording::recorder(app, sample_rate),
+            commands: model.and_then(|model| crate::voice_commands::build_recognizer(model, sample_rate as f32)),
+        }
+    }
+}
+
+impl StreamAudio for AppStreamAudio {
+    fn gain(&self) -> f32 {
+        crate::mic_quality::gain_factor()
+    }
+
+    fn check_quality(&mut self, samples: &[f32]) {
+        if self.is_user {
+            crate::mic_quality::observe(&self.app, samples, self.sample_rate);
+        }
+    }
+
+    fn pausing(&mut self, samples: &[f32], capture_ms: u64) -> bool {
+        if let Some(detector) = self.quiet.as_mut() {
+            detector.observe(samples, capture_ms);
+        }
+        if let Some(detector) = self.mute.as_mut() {
+            detector.observe(samples, capture_ms);
+        }
+        self.quiet.as_ref().map_or(false, |d| d.pausing()) || self.mute.as_ref().map_or(false, |d| d.pausing())
+    }
+
+    fn denoised(&mut self, samples: &[f32]) -> Option<Vec<f32>> {
+        self.noise.as_mut().map(|suppressor| {
+            let mut cleaned = samples.to_vec();
+            suppressor.process(&mut cleaned);
+            cleaned
+        })
+    }
+
+    fn learn_threshold(&mut self, samples: &[f32]) -> Option<f32> {
+        self.adaptive_vad.observe(samples)
+    }
+
+    fn track_talk(&mut self, samples: &[f32], capture_ms: u64, threshold: Option<f32>) {
+        if let Some(tracker) = self.talk_over.as_mut() {
+            tracker.observe(samples, capture_ms, threshold);
+        }
+    }
+
+    fn record(&mut self, pcm: &[i16], capture_ms: u64) {
+        if let Some(recorder) = self.snippets.as_ref() {
+            recorder.record(pcm, capture_ms);
+        }
+        if let Some(recorder) = self.recording.as_ref() {
+            recorder.record(pcm, capture_ms);
+        }
+        if let Some(recognizer) = self.commands.as_mut() {
+            crate::voice_commands::accept(&self.app, recognizer, pcm);
+        }
+    }
+}
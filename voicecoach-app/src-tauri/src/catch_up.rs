@@ -0,0 +1,94 @@
+// Catch Up - where the call stands, for someone who missed the start
+// A manager joining a live-listen session late, or a rep back from muting to take a
+// note, needs the gist and the exact last words. catch_me_up returns the rolling
+// summary of everything before the last few exchanges (rolling_summary, so it takes
+// seconds even on long calls) followed by those exchanges verbatim, consecutive lines
+// of one side merged into a turn. Listeners request it over the live-listen socket
+// ({"type": "catch_me_up"}) and get it back as a "catch_up" message.
+
+use serde::{Deserialize, Serialize};
+
+use crate::session_store::TranscriptLine;
+
+const DEFAULT_EXCHANGES: usize = 3;
+const MAX_EXCHANGES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CatchUpTurn {
+    pub is_user: bool,               // The rep's side
+    pub offset_ms: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CatchUp {
+    pub session_id: String,
+    pub elapsed_ms: u64,             // Call time at the last line
+    pub summary: Option<String>,     // Of the call before `recent` (None when nothing came before)
+    pub summarized_by: Option<String>, // "ollama" or "excerpts"
+    pub recent: Vec<CatchUpTurn>,
+}
+
+/// The last `exchanges` exchanges (two turns each) of the transcript, oldest first
+fn recent_turns(transcript: &[TranscriptLine], exchanges: usize) -> Vec<CatchUpTurn> {
+    let mut turns: Vec<CatchUpTurn> = Vec::new();
+    for line in transcript.iter().rev() {
+        if let Some(turn) = turns.last_mut().filter(|turn| turn.is_user == line.is_user) {
+            turn.text = format!("{} {}", line.text, turn.text);
+            turn.offset_ms = line.offset_ms;
+            continue;
+        }
+        if turns.len() == exchanges * 2 {
+            break;
+        }
+        turns.push(CatchUpTurn { is_user: line.is_user, offset_ms: line.offset_ms, text: line.text.clone() });
+    }
+    turns.reverse();
+    turns
+}
+
+// ========== Tauri Commands ==========
+
+// The call so far: summary up to the last `exchanges` exchanges (3 by default), then
+// those exchanges verbatim
+#[tauri::command]
+pub async fn catch_me_up(exchanges: Option<u32>) -> Result<CatchUp, String> {
+    let exchanges = exchanges.map_or(DEFAULT_EXCHANGES, |n| n as usize).clamp(1, MAX_EXCHANGES);
+    let (session_id, recent, elapsed_ms) = crate::session_store::with_current(|s| {
+        (s.id.clone(), recent_turns(&s.transcript, exchanges), s.transcript.last().map_or(0, |l| l.offset_ms))
+    }).ok_or("No call in progress")?;
+    let summary = match recent.first() {
+        Some(first) if first.offset_ms > 0 => Some(crate::rolling_summary::summary_so_far(Some(first.offset_ms)).await?),
+        _ => None,
+    };
+    Ok(CatchUp {
+        session_id,
+        elapsed_ms,
+        summary: summary.as_ref().map(|s| s.summary.clone()),
+        summarized_by: summary.map(|s| s.summarized_by),
+        recent,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_turns_merge_lines_of_one_side() {
+        let line = |offset_ms: u64, is_user: bool, text: &str| TranscriptLine { offset_ms, is_user, text: text.to_string(), words: Vec::new() };
+        let transcript = vec![
+            line(0, true, "Hi, thanks for joining."),
+            line(2_000, false, "Sure."),
+            line(4_000, true, "So about pricing."),
+            line(6_000, true, "It's per seat."),
+            line(9_000, false, "How much per seat?"),
+        ];
+        let turns = recent_turns(&transcript, 1);
+        assert_eq!(turns.len(), 2);
+        assert_eq!((turns[0].offset_ms, turns[0].text.as_str()), (4_000, "So about pricing. It's per seat."));
+        assert!(!turns[1].is_user);
+        assert_eq!(recent_turns(&transcript, 5).len(), 4);
+        assert!(recent_turns(&[], 3).is_empty());
+    }
+}
@@ -0,0 +1,33 @@
+// Chaos Commands - arming fault injection from the debug panel
+// The faults and the `inject` hooks are in voicecoach-core (re-exported here under the
+// old path); the app's `chaos` feature turns on the core's.
+
+pub use voicecoach_core::chaos::*;
+
+// ========== Tauri Commands ==========
+
+// Replace the armed faults (an empty list clears them) and reset the counters
+#[tauri::command]
+pub fn set_fault_injection(faults: Vec<FaultInjection>) -> Result<FaultStatus, String> {
+    #[cfg(feature = "chaos")]
+    {
+        arm(faults)
+    }
+    #[cfg(not(feature = "chaos"))]
+    {
+        let _ = faults;
+        Err("Fault injection requires a build with the `chaos` feature".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn get_fault_injection() -> Result<FaultStatus, String> {
+    #[cfg(feature = "chaos")]
+    {
+        Ok(status())
+    }
+    #[cfg(not(feature = "chaos"))]
+    {
+        Ok(FaultStatus { available: false, armed: Vec::new(), triggered: Vec::new() })
+    }
+}
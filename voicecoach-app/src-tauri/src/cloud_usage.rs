@@ -0,0 +1,26 @@
+// Cloud Usage Commands - the usage report and silence skipping settings
+// The silence gate, timeline and usage counters are in voicecoach-core (re-exported
+// here under the old path); the settings are stored in preferences.
+
+pub use voicecoach_core::cloud_usage::*;
+
+use log::info;
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_usage_report() -> Result<UsageReport, String> {
+    Ok(report(crate::preferences::load().silence_skipping.enabled))
+}
+
+// Applies to the next cloud session
+#[tauri::command]
+pub fn set_silence_skipping(settings: SilenceSkipSettings) -> Result<SilenceSkipSettings, String> {
+    if settings.threshold.map_or(false, |t| !(0.0..1.0).contains(&t)) {
+        return Err("threshold must be between 0 and 1".to_string());
+    }
+    crate::preferences::update(|p| p.silence_skipping = settings.clone())
+        .map_err(|e| e.to_string())?;
+    info!("🔇 Cloud silence skipping {}", if settings.enabled { "enabled" } else { "disabled" });
+    Ok(settings)
+}
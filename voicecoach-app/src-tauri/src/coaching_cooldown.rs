@@ -0,0 +1,261 @@
+// Coaching Cooldown - keeps the same advice from firing over and over
+// Every coaching request passes two gates configured in the coaching profile:
+// - before generation, global rate limits (a minimum gap between prompts and a cap
+//   per minute) turn requests away without spending a model call;
+// - after generation, the rule that produced the suggestion ("fallback:price",
+//   "ollama:sales_coach_json") must be out of its cooldown, and the suggestion must
+//   not say the same thing as one shown in the last dedup_window_secs: its embedding
+//   (Ollama embedding model, or hashed bag-of-words vectors when Ollama is unavailable,
+//   as in topic segmentation) is compared with theirs by cosine similarity.
+// Suppressed requests return an error naming the reason, like coaching paused during
+// read-aloud. The rep can also pause prompts altogether ("coach, pause") until resumed.
+// Shown prompts are forgotten, and a pause lifted, when a call starts.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::info;
+
+use crate::ollama_integration::OllamaCoachingService;
+use crate::topic_segmentation::{cosine, lexical_vector, EMBEDDING_MODEL};
+
+const RATE_WINDOW_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CoachingProfile {
+    /// Seconds before the same rule may prompt again
+    #[serde(default = "default_rule_cooldown_secs")]
+    pub rule_cooldown_secs: u32,
+    /// Per-rule overrides of rule_cooldown_secs, e.g. "fallback:price" = 120
+    #[serde(default)]
+    pub rule_cooldowns: HashMap<String, u32>,
+    /// Shortest gap between any two prompts
+    #[serde(default = "default_min_gap_secs")]
+    pub min_gap_secs: u32,
+    #[serde(default = "default_max_prompts_per_minute")]
+    pub max_prompts_per_minute: u32,
+    #[serde(default = "default_dedup_window_secs")]
+    pub dedup_window_secs: u32,
+    /// Cosine similarity at which two suggestions count as the same advice
+    #[serde(default = "default_dedup_similarity")]
+    pub dedup_similarity: f32,
+}
+
+fn default_rule_cooldown_secs() -> u32 { 60 }
+fn default_min_gap_secs() -> u32 { 10 }
+fn default_max_prompts_per_minute() -> u32 { 4 }
+fn default_dedup_window_secs() -> u32 { 300 }
+fn default_dedup_similarity() -> f32 { 0.92 }
+
+impl Default for CoachingProfile {
+    fn default() -> Self {
+        Self {
+            rule_cooldown_secs: default_rule_cooldown_secs(),
+            rule_cooldowns: HashMap::new(),
+            min_gap_secs: default_min_gap_secs(),
+            max_prompts_per_minute: default_max_prompts_per_minute(),
+            dedup_window_secs: default_dedup_window_secs(),
+            dedup_similarity: default_dedup_similarity(),
+        }
+    }
+}
+
+impl CoachingProfile {
+    fn cooldown_ms(&self, rule: &str) -> u64 {
+        self.rule_cooldowns.get(rule).copied().unwrap_or(self.rule_cooldown_secs) as u64 * 1000
+    }
+}
+
+struct ShownPrompt {
+    at: u64,
+    rule: String,
+    lexical: Vec<f32>,
+    embedding: Option<Vec<f32>>,
+}
+
+#[derive(Default)]
+struct Cooldowns {
+    shown: VecDeque<ShownPrompt>,  // Oldest first
+}
+
+impl Cooldowns {
+    /// Why a new prompt may not be generated at `now` (global rate limits)
+    fn rate_limited(&self, profile: &CoachingProfile, now: u64) -> Option<String> {
+        if let Some(last) = self.shown.back() {
+            if now.saturating_sub(last.at) < profile.min_gap_secs as u64 * 1000 {
+                return Some(format!("less than {}s since the last prompt", profile.min_gap_secs));
+            }
+        }
+        let recent = self.shown.iter().filter(|p| now.saturating_sub(p.at) < RATE_WINDOW_MS).count();
+        if recent >= profile.max_prompts_per_minute as usize {
+            return Some(format!("{} prompts in the last minute", recent));
+        }
+        None
+    }
+
+    /// Why a suggestion from `rule` may not be shown at `now` (rule cooldown, duplicate)
+    fn suppressed(&self, profile: &CoachingProfile, now: u64, rule: &str, lexical: &[f32], embedding: Option<&[f32]>) -> Option<String> {
+        if let Some(last) = self.shown.iter().rev().find(|p| p.rule == rule) {
+            if now.saturating_sub(last.at) < profile.cooldown_ms(rule) {
+                return Some(format!("rule {} is cooling down", rule));
+            }
+        }
+        let window_ms = profile.dedup_window_secs as u64 * 1000;
+        self.shown.iter()
+            .filter(|p| now.saturating_sub(p.at) < window_ms)
+            .map(|p| match (embedding, &p.embedding) {
+                (Some(a), Some(b)) if a.len() == b.len() => cosine(a, b),
+                _ => cosine(lexical, &p.lexical),
+            })
+            .find(|&similarity| similarity >= profile.dedup_similarity)
+            .map(|similarity| format!("a near-identical prompt was shown recently (similarity {:.2})", similarity))
+    }
+
+    fn record(&mut self, profile: &CoachingProfile, prompt: ShownPrompt) {
+        // Keep what any cooldown, the rate window or dedup may still look at
+        let keep_ms = (profile.dedup_window_secs as u64 * 1000)
+            .max(RATE_WINDOW_MS)
+            .max(profile.rule_cooldowns.values().copied().max().unwrap_or(0).max(profile.rule_cooldown_secs) as u64 * 1000);
+        let now = prompt.at;
+        self.shown.push_back(prompt);
+        while self.shown.front().map_or(false, |p| now.saturating_sub(p.at) >= keep_ms) {
+            self.shown.pop_front();
+        }
+    }
+}
+
+static COOLDOWNS: Lazy<Mutex<Cooldowns>> = Lazy::new(|| Mutex::new(Cooldowns::default()));
+// Prompts paused by the rep
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Forget the previous call's prompts (and a pause it left on)
+pub fn begin_call() {
+    COOLDOWNS.lock().unwrap().shown.clear();
+    PAUSED.store(false, Ordering::Relaxed);
+}
+
+/// Hold back coaching prompts until resumed (voice command), or resume them
+pub fn set_paused(paused: bool) {
+    PAUSED.store(paused, Ordering::Relaxed);
+    info!("{} Coaching prompts {}", if paused { "⏸️" } else { "▶️" }, if paused { "paused" } else { "resumed" });
+}
+
+/// Gate before generating: Err(reason) when the global rate limits are reached
+pub fn check_rate() -> Result<(), String> {
+    if PAUSED.load(Ordering::Relaxed) {
+        return Err("Coaching paused by voice command".to_string());
+    }
+    let profile = crate::experiments::coaching_profile();
+    match COOLDOWNS.lock().unwrap().rate_limited(&profile, now_ms()) {
+        Some(reason) => {
+            info!("🔕 Coaching prompt skipped: {}", reason);
+            Err(format!("Coaching rate-limited: {}", reason))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Gate after generating: records the suggestion as shown, or Err(reason) when its
+/// rule is cooling down or it repeats a recent prompt
+pub async fn admit(service: &OllamaCoachingService, ollama_available: bool, rule: &str, suggestion: &str) -> Result<(), String> {
+    let profile = crate::experiments::coaching_profile();
+    let lexical = lexical_vector(suggestion);
+    let embedding = if ollama_available {
+        service.embed(EMBEDDING_MODEL, suggestion).await.ok()
+    } else {
+        None
+    };
+
+    let now = now_ms();
+    let mut cooldowns = COOLDOWNS.lock().unwrap();
+    if let Some(reason) = cooldowns.suppressed(&profile, now, rule, &lexical, embedding.as_deref()) {
+        info!("🔕 Coaching prompt suppressed: {}", reason);
+        return Err(format!("Coaching suppressed: {}", reason));
+    }
+    cooldowns.record(&profile, ShownPrompt { at: now, rule: rule.to_string(), lexical, embedding });
+    Ok(())
+}
+
+/// Both gates on a simulated clock and without embeddings (as when Ollama is down),
+/// for replaying a transcript through a profile
+#[derive(Default)]
+pub struct ReplayGates {
+    cooldowns: Cooldowns,
+}
+
+impl ReplayGates {
+    /// Offer a suggestion from `rule` at `at_ms`: recorded as shown, or Err(reason)
+    pub fn offer(&mut self, profile: &CoachingProfile, at_ms: u64, rule: &str, suggestion: &str) -> Result<(), String> {
+        if let Some(reason) = self.cooldowns.rate_limited(profile, at_ms) {
+            return Err(reason);
+        }
+        let lexical = lexical_vector(suggestion);
+        if let Some(reason) = self.cooldowns.suppressed(profile, at_ms, rule, &lexical, None) {
+            return Err(reason);
+        }
+        self.cooldowns.record(profile, ShownPrompt { at: at_ms, rule: rule.to_string(), lexical, embedding: None });
+        Ok(())
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_coaching_profile() -> Result<CoachingProfile, String> {
+    Ok(crate::preferences::load().coaching_profile)
+}
+
+#[tauri::command]
+pub fn set_coaching_profile(profile: CoachingProfile) -> Result<CoachingProfile, String> {
+    if !(0.0..=1.0).contains(&profile.dedup_similarity) {
+        return Err("dedup_similarity must be between 0 and 1".to_string());
+    }
+    if profile.max_prompts_per_minute == 0 {
+        return Err("max_prompts_per_minute must be at least 1".to_string());
+    }
+    crate::preferences::update(|p| p.coaching_profile = profile.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(at: u64, rule: &str, text: &str) -> ShownPrompt {
+        ShownPrompt { at, rule: rule.to_string(), lexical: lexical_vector(text), embedding: None }
+    }
+
+    #[test]
+    fn test_rate_limits_rule_cooldowns_and_duplicates() {
+        let mut profile = CoachingProfile::default();
+        profile.rule_cooldowns.insert("fallback:price".to_string(), 120);
+        let mut cooldowns = Cooldowns::default();
+        let price = "Address price concerns by focusing on value and ROI.";
+        cooldowns.record(&profile, shown(0, "fallback:price", price));
+
+        // Minimum gap, then the per-minute cap
+        assert!(cooldowns.rate_limited(&profile, 5_000).is_some());
+        assert!(cooldowns.rate_limited(&profile, 15_000).is_none());
+        for at in [15_000, 30_000, 45_000] {
+            cooldowns.record(&profile, shown(at, "ollama:sales_coach_json", &format!("Ask about their timeline {}", at)));
+        }
+        assert!(cooldowns.rate_limited(&profile, 58_000).unwrap().contains("4 prompts"));
+
+        // The price rule's own cooldown outlasts the default one
+        let other = lexical_vector("Summarize the next steps and confirm the date.");
+        assert!(cooldowns.suppressed(&profile, 90_000, "fallback:price", &other, None).unwrap().contains("cooling down"));
+        assert!(cooldowns.suppressed(&profile, 130_000, "fallback:price", &other, None).is_none());
+
+        // Same advice from another rule within five minutes is a duplicate, not after
+        let reworded = lexical_vector("Focusing on value and ROI: address price concerns.");
+        assert!(cooldowns.suppressed(&profile, 200_000, "ollama:sales_coach_json", &reworded, None).unwrap().contains("near-identical"));
+        assert!(cooldowns.suppressed(&profile, 310_000, "ollama:sales_coach_json", &reworded, None).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+// Competitor Watch - flags competitor mentions by the prospect, with battlecards
+// Final prospect lines are matched against the keyword watchlist (competitor names
+// and aliases, whole words). On a match the competitor's battlecard is pulled from the
+// knowledge base (the document named in the watchlist, or a battlecard document whose
+// filename mentions the competitor) and a "competitor_mentioned" event carries its top
+// counter-positioning points: bullets under counter/positioning/"how to win" style
+// headings first, any bullets otherwise. A competitor is flagged again only after
+// MENTION_COOLDOWN_MS so a long comparison doesn't flood the UI. The rep can also ask
+// for a battlecard on demand (voice_commands).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+const MENTION_COOLDOWN_MS: u64 = 120_000;
+// Headings whose bullets are counter-positioning points (lowercase substrings)
+const COUNTER_HEADINGS: &[&str] = &[
+    "counter", "position", "why us", "how to win", "talk track", "landmine", "objection", "differentiat",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Competitor {
+    pub name: String,
+    /// Other ways the competitor is referred to (product names, abbreviations)
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Knowledge base document with the battlecard; None = found by name
+    #[serde(default)]
+    pub battlecard: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CompetitorWatchlist {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub competitors: Vec<Competitor>,
+    /// Counter-positioning points sent with a mention
+    #[serde(default = "default_max_points")]
+    pub max_points: usize,
+}
+
+fn default_true() -> bool { true }
+fn default_max_points() -> usize { 3 }
+
+impl Default for CompetitorWatchlist {
+    fn default() -> Self {
+        Self { enabled: true, competitors: Vec::new(), max_points: default_max_points() }
+    }
+}
+
+// Payload of "competitor_mentioned"
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CompetitorMention {
+    pub competitor: String,
+    pub matched: String,             // Name or alias that was heard
+    pub transcript: String,
+    pub timestamp: u64,
+    pub battlecard: Option<String>,  // Document the points came from
+    pub points: Vec<String>,
+}
+
+struct WatchState {
+    watchlist: CompetitorWatchlist,  // Cached for the call (checked on every prospect line)
+    flagged_at: HashMap<String, u64>,
+    mentions: Vec<CompetitorMention>,
+}
+
+static STATE: Lazy<Mutex<Option<WatchState>>> = Lazy::new(|| Mutex::new(None));
+
+fn with_state<T>(f: impl FnOnce(&mut WatchState) -> T) -> T {
+    let mut guard = STATE.lock().unwrap();
+    let state = guard.get_or_insert_with(|| WatchState {
+        watchlist: crate::preferences::load().competitors,
+        flagged_at: HashMap::new(),
+        mentions: Vec::new(),
+    });
+    f(state)
+}
+
+/// Start of a new call: forget mentions and pick up watchlist edits
+pub fn begin_call() {
+    *STATE.lock().unwrap() = None;
+}
+
+/// Text of a bullet line ("- ", "* ", "• ", "1. ", "2) "), None for other lines
+fn bullet_text(line: &str) -> Option<String> {
+    let rest = ["- ", "* ", "• "].iter().find_map(|marker| line.strip_prefix(marker))
+        .or_else(|| {
+            let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+            let marker = line[digits..].chars().next()?;
+            (digits > 0 && (marker == '.' || marker == ')')).then(|| &line[digits + 1..])
+        })?;
+    let point = rest.trim();
+    (!point.is_empty()).then(|| point.to_string())
+}
+
+/// Top counter-positioning points of a battlecard
+fn counter_points(content: &str, max: usize) -> Vec<String> {
+    let mut heading = String::new();
+    let (mut preferred, mut other) = (Vec::new(), Vec::new());
+    for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match bullet_text(line) {
+            Some(point) if COUNTER_HEADINGS.iter().any(|h| heading.contains(h)) => preferred.push(point),
+            Some(point) => other.push(point),
+            None if line.starts_with('#') || (line.ends_with(':') && line.split_whitespace().count() <= 8) => {
+                heading = line.to_lowercase();
+            }
+            None => {}
+        }
+    }
+    let mut points = if preferred.is_empty() { other } else { preferred };
+    if points.is_empty() {
+        // Prose battlecard: its first substantial sentences
+        points = content.split_terminator(['.', '!', '?'])
+            .map(|s| s.trim().to_string())
+            .filter(|s| s.split_whitespace().count() >= 4)
+            .collect();
+    }
+    points.truncate(max);
+    points
+}
+
+/// Competitors of the watchlist named in `text`, with the name or alias heard
+pub(crate) fn mentioned(text: &str, watchlist: &CompetitorWatchlist) -> Vec<(Competitor, String)> {
+    watchlist.competitors.iter()
+        .filter_map(|competitor| {
+            let names: Vec<String> = std::iter::once(competitor.name.clone()).chain(competitor.aliases.iter().cloned()).collect();
+            crate::call_analytics::find_phrase(text, &names).map(|matched| (competitor.clone(), matched.to_string()))
+        })
+        .collect()
+}
+
+/// Feed a final transcript line; prospect mentions of watched competitors are flagged
+pub fn observe(app: &AppHandle, text: &str, is_user: bool) {
+    if is_user {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let (hits, max_points) = with_state(|state| {
+        if !state.watchlist.enabled {
+            return (Vec::new(), 0);
+        }
+        let hits: Vec<(Competitor, String)> = mentioned(text, &state.watchlist).into_iter()
+            .filter(|(competitor, _)| state.flagged_at.get(&competitor.name).map_or(true, |&at| now.saturating_sub(at) >= MENTION_COOLDOWN_MS))
+            .collect();
+        for (competitor, _) in &hits {
+            state.flagged_at.insert(competitor.name.clone(), now);
+        }
+        (hits, state.watchlist.max_points)
+    });
+
+    for (competitor, matched) in hits {
+        let card = crate::knowledge_base::battlecard(&competitor.name, competitor.battlecard.as_deref());
+        let mention = CompetitorMention {
+            competitor: competitor.name.clone(),
+            matched,
+            transcript: text.to_string(),
+            timestamp: now,
+            points: card.as_ref().map_or_else(Vec::new, |(_, content)| counter_points(content, max_points)),
+            battlecard: card.map(|(filename, _)| filename),
+        };
+        info!("⚔️ Competitor mentioned: {} ({} points from {})", mention.competitor, mention.points.len(),
+            mention.battlecard.as_deref().unwrap_or("no battlecard"));
+        with_state(|state| state.mentions.push(mention.clone()));
+        if let Err(e) = app.emit_all("competitor_mentioned", mention) {
+            error!("Failed to emit competitor_mentioned: {:?}", e);
+        }
+    }
+}
+
+/// Battlecard requested by the rep: the named competitor, or the last one mentioned
+pub fn battlecard_for(name: Option<&str>) -> Option<CompetitorMention> {
+    let (competitor, max_points, last) = with_state(|state| {
+        let competitor = name.and_then(|name| state.watchlist.competitors.iter().find(|c| c.name == name).cloned());
+        (competitor, state.watchlist.max_points, state.mentions.last().cloned())
+    });
+    let competitor = match (competitor, name) {
+        (Some(competitor), _) => competitor,
+        (None, None) => return last,
+        (None, Some(_)) => return None,
+    };
+    let card = crate::knowledge_base::battlecard(&competitor.name, competitor.battlecard.as_deref());
+    Some(CompetitorMention {
+        matched: competitor.name.clone(),
+        competitor: competitor.name,
+        transcript: String::new(),
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        points: card.as_ref().map_or_else(Vec::new, |(_, content)| counter_points(content, max_points)),
+        battlecard: card.map(|(filename, _)| filename),
+    })
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_competitor_watchlist() -> Result<CompetitorWatchlist, String> {
+    Ok(crate::preferences::load().competitors)
+}
+
+#[tauri::command]
+pub fn set_competitor_watchlist(watchlist: CompetitorWatchlist) -> Result<CompetitorWatchlist, String> {
+    if watchlist.competitors.iter().any(|c| c.name.trim().is_empty()) {
+        return Err("Every competitor needs a name".to_string());
+    }
+    crate::preferences::update(|p| p.competitors = watchlist.clone())
+        .map_err(|e| e.to_string())?;
+    with_state(|state| state.watchlist = watchlist.clone());
+    Ok(watchlist)
+}
+
+// Competitor mentions flagged during the current call
+#[tauri::command]
+pub fn get_competitor_mentions() -> Result<Vec<CompetitorMention>, String> {
+    Ok(with_state(|state| state.mentions.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mentions_match_aliases_and_points_prefer_counter_sections() {
+        let watchlist = CompetitorWatchlist {
+            competitors: vec![Competitor { name: "Gong".to_string(), aliases: vec!["gong io".to_string()], battlecard: None }],
+            ..Default::default()
+        };
+        let hits = mentioned("We're also looking at Gong.io right now", &watchlist);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].1, "Gong");
+        assert!(mentioned("the gongs rang", &watchlist).is_empty());
+
+        let card = "# Gong battlecard\nOverview:\n- Founded 2015\n- Call recording\n\n## How to win\n1. Real-time coaching, not post-call\n2) Runs locally - no recordings leave the laptop\n- Flat pricing\n";
+        assert_eq!(counter_points(card, 2), vec!["Real-time coaching, not post-call", "Runs locally - no recordings leave the laptop"]);
+        assert_eq!(counter_points("Overview:\n- Founded 2015", 3), vec!["Founded 2015"]);
+    }
+}
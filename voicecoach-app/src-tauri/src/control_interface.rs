@@ -0,0 +1,375 @@
+// Control Interface - lets a call-center dialer drive VoiceCoach (screen-pop integration)
+// Opt-in. A small HTTP endpoint on localhost only accepts JSON commands posted to
+// /command with "Authorization: Bearer <token>"; the token is generated when the
+// interface is first enabled and shown in settings for the dialer's configuration.
+//   {"command": "start_session", "caller": {"name": ..., "phone": ..., "company": ...}, "template_id": ...}
+//   {"command": "end_session"}
+//   {"command": "status"}
+// start_session starts a session (with the given template, else the active one),
+// stores the caller with it, names the company for the prospect brief and emits
+// "control_session_started" so the window pops up and starts capture; end_session
+// emits "control_session_ended" for the window to stop it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use log::{info, warn, error};
+
+const DEFAULT_PORT: u16 = 47822;
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+const READ_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ControlInterfaceSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub token: Option<String>,  // Generated when first enabled
+}
+
+fn default_port() -> u16 { DEFAULT_PORT }
+
+impl Default for ControlInterfaceSettings {
+    fn default() -> Self {
+        Self { enabled: false, port: DEFAULT_PORT, token: None }
+    }
+}
+
+/// Caller metadata a dialer passes with start_session
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct CallerInfo {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub company: Option<String>,
+    #[serde(default)]
+    pub crm_id: Option<String>,      // Contact/lead id in the dialer's CRM
+    #[serde(default)]
+    pub campaign: Option<String>,
+    #[serde(default)]
+    pub extra: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum ControlCommand {
+    StartSession {
+        #[serde(default)]
+        caller: CallerInfo,
+        #[serde(default)]
+        template_id: Option<String>,
+    },
+    EndSession,
+    Status,
+}
+
+// Payload of "control_session_started"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ControlSessionStarted {
+    pub session_id: Option<String>,
+    pub caller: CallerInfo,
+    pub template_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ControlInterfaceStatus {
+    pub settings: ControlInterfaceSettings,
+    pub running: bool,
+    pub url: Option<String>,
+    pub last_command_at: Option<u64>,
+}
+
+struct Server {
+    port: u16,
+    task: tokio::task::JoinHandle<()>,
+}
+
+static SERVER: Lazy<Mutex<Option<Server>>> = Lazy::new(|| Mutex::new(None));
+static LAST_COMMAND_AT: Lazy<Mutex<Option<u64>>> = Lazy::new(|| Mutex::new(None));
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    fn content_length(&self) -> usize {
+        self.header("content-length").and_then(|v| v.parse().ok()).unwrap_or(0)
+    }
+}
+
+/// Request line and headers (the text before the blank line)
+fn parse_head(head: &str) -> Option<HttpRequest> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    Some(HttpRequest { method, path, headers })
+}
+
+/// Whether the Authorization header carries the bearer token (compared in constant time)
+fn token_matches(presented: Option<&str>, token: &str) -> bool {
+    presented.and_then(|v| v.strip_prefix("Bearer "))
+        .map_or(false, |presented| crate::credentials::secrets_equal(presented.trim(), token))
+}
+
+fn start_session(app: &AppHandle, caller: CallerInfo, template_id: Option<String>) -> Result<serde_json::Value, String> {
+    // No template named: keep the active one
+    if template_id.is_some() {
+        crate::session_templates::start_session_from_template(template_id.clone())?;
+    } else {
+        crate::call_analytics::begin_call();
+        crate::session_store::begin_session();
+    }
+    let session_id = crate::session_store::current_session_id();
+    if let Some(id) = &session_id {
+        crate::session_store::set_caller(id, &caller);
+    }
+    crate::no_coach_zones::set_caller(&caller);
+    if let Some(company) = caller.company.clone().filter(|c| !c.trim().is_empty()) {
+        crate::prospect_brief::set_session_company(app.clone(), company)?;
+    }
+    info!("📞 Dialer started session {} for {}", session_id.as_deref().unwrap_or("?"),
+        caller.name.as_deref().or(caller.phone.as_deref()).unwrap_or("an unknown caller"));
+
+    let started = ControlSessionStarted { session_id: session_id.clone(), caller, template_id };
+    if let Err(e) = app.emit_all("control_session_started", started) {
+        error!("Failed to emit control_session_started: {:?}", e);
+    }
+    // Screen-pop: bring the window forward for the rep
+    if let Some(window) = app.get_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    Ok(serde_json::json!({ "ok": true, "session_id": session_id }))
+}
+
+fn execute(app: &AppHandle, body: &[u8]) -> Result<serde_json::Value, String> {
+    let command: ControlCommand = serde_json::from_slice(body).map_err(|e| format!("Invalid command: {}", e))?;
+    *LAST_COMMAND_AT.lock().unwrap() = Some(now_ms());
+    match command {
+        ControlCommand::StartSession { caller, template_id } => start_session(app, caller, template_id),
+        ControlCommand::EndSession => {
+            let session_id = crate::session_store::current_session_id();
+            info!("📞 Dialer ended session {}", session_id.as_deref().unwrap_or("?"));
+            crate::no_coach_zones::call_ended();
+            if let Err(e) = app.emit_all("control_session_ended", session_id.clone()) {
+                error!("Failed to emit control_session_ended: {:?}", e);
+            }
+            Ok(serde_json::json!({ "ok": true, "session_id": session_id }))
+        }
+        ControlCommand::Status => Ok(serde_json::json!({
+            "ok": true,
+            "session_id": crate::session_store::current_session_id(),
+            "version": env!("CARGO_PKG_VERSION"),
+        })),
+    }
+}
+
+async fn respond(stream: &mut tokio::net::TcpStream, status: u16, body: serde_json::Value) {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Payload Too Large",
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, body.len(), body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+fn error_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "ok": false, "error": message })
+}
+
+/// Read one request (head and body) from the connection
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Result<(HttpRequest, Vec<u8>), u16> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            let request = parse_head(&String::from_utf8_lossy(&buffer[..end])).ok_or(400u16)?;
+            // A huge Content-Length must not wrap around the size check
+            let total = (end + 4).checked_add(request.content_length())
+                .filter(|total| *total <= MAX_REQUEST_BYTES)
+                .ok_or(413u16)?;
+            while buffer.len() < total {
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return Err(400),
+                    Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                }
+            }
+            return Ok((request, buffer[end + 4..total].to_vec()));
+        }
+        if buffer.len() > MAX_REQUEST_BYTES {
+            return Err(413);
+        }
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return Err(400),
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    }
+}
+
+async fn handle_connection(app: AppHandle, mut stream: tokio::net::TcpStream, token: String) {
+    let timeout = std::time::Duration::from_secs(READ_TIMEOUT_SECS);
+    let (request, body) = match tokio::time::timeout(timeout, read_request(&mut stream)).await {
+        Ok(Ok(request)) => request,
+        Ok(Err(status)) => return respond(&mut stream, status, error_body("Malformed request")).await,
+        Err(_) => return,
+    };
+    if !token_matches(request.header("authorization"), &token) {
+        warn!("⚠️ Control interface: rejected {} {} without a valid token", request.method, request.path);
+        return respond(&mut stream, 401, error_body("Invalid or missing bearer token")).await;
+    }
+    if request.path != "/command" {
+        return respond(&mut stream, 404, error_body("Commands are posted to /command")).await;
+    }
+    if request.method != "POST" {
+        return respond(&mut stream, 405, error_body("Use POST")).await;
+    }
+    match execute(&app, &body) {
+        Ok(result) => respond(&mut stream, 200, result).await,
+        Err(e) => respond(&mut stream, 400, error_body(&e)).await,
+    }
+}
+
+async fn serve(app: AppHandle, listener: tokio::net::TcpListener, token: String) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(handle_connection(app.clone(), stream, token.clone()));
+    }
+}
+
+fn stop_server() {
+    if let Some(server) = SERVER.lock().unwrap().take() {
+        server.task.abort();
+        info!("📞 Control interface stopped");
+    }
+}
+
+/// Start (or restart) the endpoint per the settings; stops it when disabled
+async fn apply(app: AppHandle, settings: &ControlInterfaceSettings) -> Result<(), String> {
+    stop_server();
+    let token = match (&settings.enabled, &settings.token) {
+        (true, Some(token)) => token.clone(),
+        _ => return Ok(()),
+    };
+    // Loopback only: the dialer runs on the rep's machine
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", settings.port)).await
+        .map_err(|e| format!("Failed to listen on port {}: {}", settings.port, e))?;
+    let task = tokio::spawn(serve(app, listener, token));
+    *SERVER.lock().unwrap() = Some(Server { port: settings.port, task });
+    info!("📞 Control interface listening on 127.0.0.1:{}", settings.port);
+    Ok(())
+}
+
+/// Open the endpoint at startup when enabled
+pub fn start_control_interface(app: AppHandle) {
+    let settings = crate::preferences::load().control_interface;
+    if settings.enabled {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = apply(app, &settings).await {
+                error!("❌ Control interface: {}", e);
+            }
+        });
+    }
+}
+
+fn status() -> ControlInterfaceStatus {
+    let port = SERVER.lock().unwrap().as_ref().map(|s| s.port);
+    ControlInterfaceStatus {
+        settings: crate::preferences::load().control_interface,
+        running: port.is_some(),
+        url: port.map(|port| format!("http://127.0.0.1:{}/command", port)),
+        last_command_at: *LAST_COMMAND_AT.lock().unwrap(),
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_control_interface() -> Result<ControlInterfaceStatus, String> {
+    Ok(status())
+}
+
+// Enable/disable the dialer endpoint (a token is generated the first time it is enabled)
+#[tauri::command]
+pub async fn set_control_interface(app: AppHandle, enabled: bool, port: Option<u16>) -> Result<ControlInterfaceStatus, String> {
+    let preferences = crate::preferences::update(|p| {
+        let settings = &mut p.control_interface;
+        settings.enabled = enabled;
+        settings.port = port.unwrap_or(settings.port);
+        if enabled && settings.token.is_none() {
+            settings.token = Some(crate::live_listen::new_token());
+        }
+    }).map_err(|e| e.to_string())?;
+    apply(app, &preferences.control_interface).await?;
+    Ok(status())
+}
+
+// Replace the token (dialers configured with the old one are locked out)
+#[tauri::command]
+pub async fn regenerate_control_token(app: AppHandle) -> Result<ControlInterfaceStatus, String> {
+    let preferences = crate::preferences::update(|p| p.control_interface.token = Some(crate::live_listen::new_token()))
+        .map_err(|e| e.to_string())?;
+    apply(app, &preferences.control_interface).await?;
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_head_and_token_are_checked() {
+        let request = parse_head("POST /command HTTP/1.1\r\nHost: 127.0.0.1\r\nauthorization: Bearer abc123\r\nContent-Length: 27").unwrap();
+        assert_eq!((request.method.as_str(), request.path.as_str(), request.content_length()), ("POST", "/command", 27));
+        assert!(token_matches(request.header("Authorization"), "abc123"));
+        assert!(!token_matches(request.header("Authorization"), "abc124"));
+        assert!(!token_matches(Some("abc123"), "abc123"));
+        assert!(!token_matches(None, "abc123"));
+
+        let command: ControlCommand = serde_json::from_str(r#"{"command":"start_session","caller":{"name":"Dana","phone":"+15550100"}}"#).unwrap();
+        assert!(matches!(command, ControlCommand::StartSession { caller, template_id: None } if caller.name.as_deref() == Some("Dana")));
+        assert!(serde_json::from_str::<ControlCommand>(r#"{"command":"delete_everything"}"#).is_err());
+    }
+
+    #[test]
+    fn test_oversized_content_length_is_refused() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let status = runtime.block_on(async {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let mut client = tokio::net::TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+            client.write_all(format!("POST /command HTTP/1.1\r\nContent-Length: {}\r\n\r\n", usize::MAX).as_bytes()).await.unwrap();
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await.err()
+        });
+        assert_eq!(status, Some(413));
+    }
+}
@@ -0,0 +1,289 @@
+// Credentials - local key material that must never end up in preferences/exports
+// Kept in its own file in the app data directory (owner-only on Unix) so the
+// preferences JSON can be shared or backed up freely. Holds the keys used to sign
+// and encrypt exported transcripts and access tokens for third-party integrations.
+
+use anyhow::{Result, Context, anyhow};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+const CREDENTIALS_FILE: &str = "voicecoach_credentials.json";
+
+// Symmetric export key; the first entry is used for new exports, older and
+// imported keys are kept so received/previous bundles can still be decrypted
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct StoredKey {
+    pub id: String,
+    pub key: String,  // base64
+    pub created_at: u64,
+    #[serde(default)]
+    pub imported: bool,
+}
+
+// Public key of a colleague/system whose signed exports we accept
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TrustedSigner {
+    pub name: String,
+    pub public_key: String,  // base64
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Credentials {
+    #[serde(default)]
+    export_signing_key: Option<String>,  // base64 ed25519 seed
+    #[serde(default)]
+    export_encryption_keys: Vec<StoredKey>,
+    #[serde(default)]
+    trusted_signers: Vec<TrustedSigner>,
+    #[serde(default)]
+    integration_tokens: BTreeMap<String, String>,  // e.g. "notion" -> API token
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ExportKeyInfo {
+    pub public_key: String,
+    pub signer_fingerprint: String,
+    pub encryption_key_id: String,
+    pub decryption_key_ids: Vec<String>,
+    pub trusted_signers: Vec<TrustedSigner>,
+}
+
+static CREDENTIALS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn credentials_path() -> PathBuf {
+    crate::app_paths::data_dir().join(CREDENTIALS_FILE)
+}
+
+fn read_from_disk() -> Credentials {
+    let path = credentials_path();
+    match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("⚠️ Credentials file {:?} is invalid, ignoring it: {}", path, e);
+            Credentials::default()
+        }),
+        Err(_) => Credentials::default(),
+    }
+}
+
+fn write_to_disk(credentials: &Credentials) -> Result<()> {
+    let path = credentials_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(credentials)?;
+    fs::write(&path, json).context(format!("Failed to write credentials: {:?}", path))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+fn with_credentials<T>(f: impl FnOnce(&mut Credentials) -> Result<T>) -> Result<T> {
+    let _guard = CREDENTIALS_LOCK.lock().unwrap();
+    let mut credentials = read_from_disk();
+    let before = serde_json::to_string(&credentials)?;
+    let result = f(&mut credentials)?;
+    if serde_json::to_string(&credentials)? != before {
+        write_to_disk(&credentials)?;
+    }
+    Ok(result)
+}
+
+/// Compare a presented token with a secret without stopping at the first difference,
+/// so the time taken doesn't tell how much of it was right
+pub fn secrets_equal(presented: &str, secret: &str) -> bool {
+    presented.len() == secret.len()
+        && presented.bytes().zip(secret.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Short hex id for a key: first 8 bytes of its SHA256
+pub fn fingerprint(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn random_bytes() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; 32]> {
+    BASE64.decode(encoded.trim())
+        .map_err(|e| anyhow!("Invalid base64 key: {}", e))?
+        .try_into()
+        .map_err(|_| anyhow!("Key must be 32 bytes"))
+}
+
+fn new_encryption_key(imported: Option<[u8; 32]>) -> StoredKey {
+    let key = imported.unwrap_or_else(random_bytes);
+    StoredKey {
+        id: fingerprint(&key),
+        key: BASE64.encode(key),
+        created_at: chrono::Utc::now().timestamp_millis() as u64,
+        imported: imported.is_some(),
+    }
+}
+
+fn ensure_signing_key(credentials: &mut Credentials) -> Result<SigningKey> {
+    if let Some(seed) = &credentials.export_signing_key {
+        return Ok(SigningKey::from_bytes(&decode_key(seed)?));
+    }
+    let key = SigningKey::from_bytes(&random_bytes());
+    credentials.export_signing_key = Some(BASE64.encode(key.to_bytes()));
+    info!("🔑 Generated export signing key {}", fingerprint(key.verifying_key().as_bytes()));
+    Ok(key)
+}
+
+fn ensure_encryption_key(credentials: &mut Credentials) -> StoredKey {
+    if credentials.export_encryption_keys.is_empty() {
+        let key = new_encryption_key(None);
+        info!("🔑 Generated export encryption key {}", key.id);
+        credentials.export_encryption_keys.push(key);
+    }
+    credentials.export_encryption_keys[0].clone()
+}
+
+/// Ed25519 key used to sign exports (created on first use)
+pub fn export_signing_key() -> Result<SigningKey> {
+    with_credentials(ensure_signing_key)
+}
+
+/// Active export encryption key as (id, key), created on first use
+pub fn export_encryption_key() -> Result<(String, [u8; 32])> {
+    with_credentials(|c| {
+        let key = ensure_encryption_key(c);
+        Ok((key.id, decode_key(&key.key)?))
+    })
+}
+
+/// Look up any known encryption key by id (active, rotated-out or imported)
+pub fn find_encryption_key(id: &str) -> Option<[u8; 32]> {
+    let _guard = CREDENTIALS_LOCK.lock().unwrap();
+    read_from_disk().export_encryption_keys.iter()
+        .find(|k| k.id == id)
+        .and_then(|k| decode_key(&k.key).ok())
+}
+
+/// Name of the trusted signer with this public key, if any (our own key counts)
+pub fn trusted_signer_name(public_key: &VerifyingKey) -> Option<String> {
+    let _guard = CREDENTIALS_LOCK.lock().unwrap();
+    let credentials = read_from_disk();
+    let encoded = BASE64.encode(public_key.as_bytes());
+
+    let own = credentials.export_signing_key.as_deref()
+        .and_then(|seed| decode_key(seed).ok())
+        .map(|seed| SigningKey::from_bytes(&seed).verifying_key());
+    if own.as_ref() == Some(public_key) {
+        return Some("this device".to_string());
+    }
+    credentials.trusted_signers.iter()
+        .find(|s| s.public_key == encoded)
+        .map(|s| s.name.clone())
+}
+
+/// Access token stored for an integration (None if never configured)
+pub fn integration_token(name: &str) -> Option<String> {
+    let _guard = CREDENTIALS_LOCK.lock().unwrap();
+    read_from_disk().integration_tokens.get(name).cloned()
+}
+
+/// Store (Some) or forget (None) an integration's access token
+pub fn set_integration_token(name: &str, token: Option<String>) -> Result<()> {
+    with_credentials(|c| {
+        match token.filter(|t| !t.trim().is_empty()) {
+            Some(token) => c.integration_tokens.insert(name.to_string(), token.trim().to_string()),
+            None => c.integration_tokens.remove(name),
+        };
+        Ok(())
+    })
+}
+
+fn key_info(credentials: &mut Credentials) -> Result<ExportKeyInfo> {
+    let signing = ensure_signing_key(credentials)?;
+    let active = ensure_encryption_key(credentials);
+    Ok(ExportKeyInfo {
+        public_key: BASE64.encode(signing.verifying_key().as_bytes()),
+        signer_fingerprint: fingerprint(signing.verifying_key().as_bytes()),
+        encryption_key_id: active.id,
+        decryption_key_ids: credentials.export_encryption_keys.iter().map(|k| k.id.clone()).collect(),
+        trusted_signers: credentials.trusted_signers.clone(),
+    })
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_export_key_info() -> Result<ExportKeyInfo, String> {
+    with_credentials(key_info).map_err(|e| e.to_string())
+}
+
+// New signing and/or encryption key; old encryption keys stay available for decryption
+#[tauri::command]
+pub fn rotate_export_keys(signing: bool, encryption: bool) -> Result<ExportKeyInfo, String> {
+    with_credentials(|c| {
+        if signing {
+            c.export_signing_key = None;
+        }
+        if encryption {
+            c.export_encryption_keys.insert(0, new_encryption_key(None));
+        }
+        info!("🔄 Rotated export keys (signing: {}, encryption: {})", signing, encryption);
+        key_info(c)
+    }).map_err(|e| e.to_string())
+}
+
+// Base64 of the active encryption key, to hand to a reviewer out-of-band
+#[tauri::command]
+pub fn share_export_encryption_key() -> Result<String, String> {
+    with_credentials(|c| Ok(ensure_encryption_key(c).key)).map_err(|e| e.to_string())
+}
+
+// Add someone else's encryption key so their bundles can be decrypted here
+#[tauri::command]
+pub fn import_export_encryption_key(key: String) -> Result<ExportKeyInfo, String> {
+    with_credentials(|c| {
+        let bytes = decode_key(&key)?;
+        let id = fingerprint(&bytes);
+        if !c.export_encryption_keys.iter().any(|k| k.id == id) {
+            c.export_encryption_keys.push(new_encryption_key(Some(bytes)));
+            info!("🔑 Imported export encryption key {}", id);
+        }
+        key_info(c)
+    }).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn trust_export_signer(name: String, public_key: String) -> Result<ExportKeyInfo, String> {
+    with_credentials(|c| {
+        let bytes = decode_key(&public_key)?;
+        VerifyingKey::from_bytes(&bytes).map_err(|e| anyhow!("Invalid ed25519 public key: {}", e))?;
+        c.trusted_signers.retain(|s| s.public_key != BASE64.encode(bytes));
+        c.trusted_signers.push(TrustedSigner {
+            name,
+            public_key: BASE64.encode(bytes),
+            fingerprint: fingerprint(&bytes),
+        });
+        key_info(c)
+    }).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn untrust_export_signer(fingerprint: String) -> Result<ExportKeyInfo, String> {
+    with_credentials(|c| {
+        c.trusted_signers.retain(|s| s.fingerprint != fingerprint);
+        key_info(c)
+    }).map_err(|e| e.to_string())
+}
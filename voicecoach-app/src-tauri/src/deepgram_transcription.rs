@@ -1,210 +1,149 @@
-// Deepgram Real-time Transcription for VoiceCoach
-// WebKit-quality cloud transcription with ultra-low latency
+// Deepgram Commands - starting and stopping cloud transcription from the windows
+// The connection, capture stream and results watchdog are in voicecoach-core
+// (re-exported here under the old path). Here the app decides whether a source may be
+// transcribed (license, privacy, no-coach zones, the session arbiter) and passes in
+// its calibration and silence skipping settings. In one-party consent mode a prospect
+// source (loopback or app audio) is only level-metered: no connection is made.
+
+pub use voicecoach_core::deepgram_transcription::*;
 
-use tokio_tungstenite::{connect_async, tungstenite::Message};
 use futures_util::{StreamExt, SinkExt};
-use serde::{Deserialize, Serialize};
-use serde_json;
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use tokio_tungstenite::connect_async;
+use cpal::traits::DeviceTrait;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use tauri::{AppHandle, Manager};
 use log::{info, error, warn};
-use anyhow::{Result, anyhow};
 use std::sync::atomic::{AtomicBool, Ordering};
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct TranscriptionPayload {
-    pub text: String,
-    pub is_final: bool,
-    pub timestamp: u64,
-    pub is_user: bool,
-}
+use crate::event_sink::AppEvents;
 
-#[derive(Deserialize, Debug)]
-struct DeepgramResponse {
-    channel: Option<Channel>,
-    is_final: Option<bool>,
-    speech_final: Option<bool>,
-}
+// The prospect channel is being metered instead (one-party consent)
+static METERING: AtomicBool = AtomicBool::new(false);
 
-#[derive(Deserialize, Debug)]
-struct Channel {
-    alternatives: Vec<Alternative>,
+/// One-party consent: meter the prospect source instead of transcribing it
+fn start_prospect_meter(app: AppHandle, app_target: Option<crate::app_audio::AppAudioTarget>) -> Result<String, String> {
+    METERING.store(true, Ordering::Relaxed);
+    if let Some(target) = app_target {
+        let mut meter = crate::one_party::ProspectMeter::new(app, crate::app_audio::CAPTURE_SAMPLE_RATE);
+        let started = crate::app_audio::start_capture(target.pid, move |samples| {
+            if !METERING.load(Ordering::Relaxed) {
+                return false;
+            }
+            meter.observe_i16(samples);
+            true
+        });
+        if let Err(e) = started {
+            METERING.store(false, Ordering::Relaxed);
+            return Err(e);
+        }
+        info!("🔒 Metering audio from {} (pid {}) only - one-party consent", target.name, target.pid);
+        return Ok("Prospect audio is metered only (one-party consent)".into());
+    }
+
+    let device = crate::device_selection::system_audio_device(&cpal::default_host())
+        .ok_or("No system audio (loopback/monitor) device available")?;
+    let supported = crate::device_selection::system_audio_config(&device)?;
+    let sample_format = supported.sample_format();
+    let mut config: cpal::StreamConfig = supported.into();
+    config.buffer_size = cpal::BufferSize::Default;
+    let input_channels = config.channels as usize;
+    let sample_rate = config.sample_rate.0;
+    let mut meter = crate::one_party::ProspectMeter::new(app, sample_rate);
+    info!("🔒 Metering {} only - one-party consent", device.name().unwrap_or_default());
+    crate::privacy::open_stream(STREAM_OWNER, move || crate::sample_format::build_input_stream(
+        &device,
+        &config,
+        sample_format,
+        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+            let _timing = crate::pipeline_stats::time_callback(crate::pipeline_stats::PipelineStream::ProspectMeter, data.len() / input_channels.max(1), sample_rate);
+            if input_channels > 1 {
+                meter.observe(&crate::device_conflict::downmix_to_mono(data, input_channels));
+            } else {
+                meter.observe(data);
+            }
+        },
+        |err| error!("Prospect meter stream error: {:?}", err),
+    ).map_err(|e| format!("Failed to build audio stream: {}", e))).map_err(|e| {
+        METERING.store(false, Ordering::Relaxed);
+        e
+    })?;
+    Ok("Prospect audio is metered only (one-party consent)".into())
 }
 
-#[derive(Deserialize, Debug)]
-struct Alternative {
-    transcript: String,
-    confidence: f32,
+/// Stop streaming and close the capture stream (app audio capture ends on its next buffer)
+pub fn close_capture() {
+    stop();
+    METERING.store(false, Ordering::Relaxed);
+    crate::privacy::close_streams(Some(STREAM_OWNER));
+    crate::call_recording::end_call();
 }
 
-// Global connection state
-static IS_RUNNING: AtomicBool = AtomicBool::new(false);
+// ========== Tauri Commands ==========
 
 // Start Deepgram real-time transcription
 #[tauri::command]
 pub async fn start_deepgram_transcription(
     app: AppHandle,
     api_key: String,
+    source: Option<String>,   // "microphone" (default), "system_audio" (loopback) or "app_audio" (see capture_app_audio)
+    diarize: Option<bool>,    // Separate "Prospect A" / "Prospect B" on multi-party calls
 ) -> Result<String, String> {
-    if IS_RUNNING.load(Ordering::Relaxed) {
+    crate::license::require_feature(crate::license::Feature::CloudEngines)?;
+    crate::privacy::ensure_capture_allowed(&app)?;
+    crate::no_coach_zones::ensure_allowed(&app)?;
+    let mut system_audio = source.as_deref() == Some("system_audio");
+    let app_target = if source.as_deref() == Some("app_audio") {
+        Some(crate::app_audio::target().ok_or("Select an application with capture_app_audio first")?)
+    } else {
+        None
+    };
+    let diarize = diarize.unwrap_or(false);
+    let claim = crate::session_arbiter::claim(crate::two_pass::Engine::Deepgram)?;
+    if is_running() || METERING.load(Ordering::Relaxed) {
         return Ok("Transcription already running".into());
     }
     
     info!("Starting Deepgram real-time transcription...");
     
-    // Deepgram WebSocket URL with parameters for best quality
-    let ws_url = format!(
-        "wss://api.deepgram.com/v1/listen?\
-        encoding=linear16&\
-        sample_rate=16000&\
-        channels=1&\
-        punctuate=true&\
-        interim_results=true&\
-        endpointing=300&\
-        vad_turnoff=500"
-    );
-    
-    // Create connection with auth
-    let request = http::Request::builder()
-        .uri(&ws_url)
-        .header("Authorization", format!("Token {}", api_key))
-        .header("Sec-WebSocket-Protocol", "websocket")
-        .header("Sec-WebSocket-Version", "13")
-        .body(())
-        .map_err(|e| format!("Failed to build request: {}", e))?;
-    
-    // Connect to WebSocket
-    let (ws_stream, _) = connect_async(request)
-        .await
-        .map_err(|e| format!("Failed to connect to Deepgram: {}. Check your API key.", e))?;
-    
-    info!("✅ Connected to Deepgram WebSocket");
-    IS_RUNNING.store(true, Ordering::Relaxed);
-    
-    let (ws_sender, mut ws_receiver) = ws_stream.split();
-    let ws_sender = Arc::new(Mutex::new(ws_sender));
-    
-    // Setup audio capture
-    let host = cpal::default_host();
-    let device = host.default_input_device()
-        .ok_or("No input device available")?;
-    
-    info!("Using audio device: {}", device.name().unwrap_or_default());
-    
-    // 16kHz mono for Deepgram
-    let config = cpal::StreamConfig {
-        channels: 1,
-        sample_rate: cpal::SampleRate(16000),
-        buffer_size: cpal::BufferSize::Fixed(1600), // 100ms chunks for low latency
+    // No loopback device: keep the call going on the microphone only
+    if system_audio && crate::device_selection::system_audio_device(&cpal::default_host()).is_none() {
+        warn!("⚠️ No system audio device available, falling back to microphone only");
+        let _ = app.emit_all("audio_source_fallback", serde_json::json!({
+            "requested": "system_audio",
+            "using": "microphone",
+        }));
+        system_audio = false;
+    }
+    if (system_audio || app_target.is_some()) && !crate::one_party::allows(false) {
+        return start_prospect_meter(app, app_target);
+    }
+    crate::call_analytics::begin_call();
+    crate::speakers::begin_call();
+    crate::session_store::begin_session();
+    
+    let source = match app_target {
+        Some(target) => Source::App { pid: target.pid, name: target.name, sample_rate: crate::app_audio::CAPTURE_SAMPLE_RATE },
+        None if system_audio => Source::SystemAudio,
+        None => Source::Microphone,
     };
-    
-    // Clone for audio callback
-    let ws_sender_clone = ws_sender.clone();
-    
-    // Build audio stream
-    let stream = device.build_input_stream(
-        &config,
-        move |data: &[f32], _: &cpal::InputCallbackInfo| {
-            if !IS_RUNNING.load(Ordering::Relaxed) {
-                return;
-            }
-            
-            // Convert f32 to i16 (LINEAR16 format)
-            let i16_data: Vec<i16> = data.iter()
-                .map(|&sample| {
-                    let clamped = sample.max(-1.0).min(1.0);
-                    (clamped * 32767.0) as i16
-                })
-                .collect();
-            
-            // Convert to bytes
-            let bytes: Vec<u8> = i16_data.iter()
-                .flat_map(|&sample| sample.to_le_bytes())
-                .collect();
-            
-            // Send to Deepgram
-            let sender = ws_sender_clone.clone();
-            tokio::spawn(async move {
-                let mut sender = sender.lock().await;
-                if let Err(e) = sender.send(Message::Binary(bytes)).await {
-                    error!("Failed to send audio to Deepgram: {}", e);
-                }
-            });
-        },
-        |err| {
-            error!("Audio stream error: {:?}", err);
-        },
-        None
-    ).map_err(|e| format!("Failed to build audio stream: {}", e))?;
-    
-    stream.play().map_err(|e| format!("Failed to start audio stream: {}", e))?;
-    
-    // Handle incoming transcriptions
-    let app_for_receiver = app.clone();
-    tokio::spawn(async move {
-        let mut last_transcript = String::new();
-        
-        while let Some(msg) = ws_receiver.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    if let Ok(response) = serde_json::from_str::<DeepgramResponse>(&text) {
-                        if let Some(channel) = response.channel {
-                            if let Some(alt) = channel.alternatives.first() {
-                                let transcript = &alt.transcript;
-                                
-                                // Skip empty transcripts
-                                if transcript.is_empty() {
-                                    continue;
-                                }
-                                
-                                // Determine if this is final or interim
-                                let is_final = response.is_final.unwrap_or(false) || 
-                                              response.speech_final.unwrap_or(false);
-                                
-                                // Only emit if text changed (avoid duplicates)
-                                if transcript != &last_transcript {
-                                    info!("{} transcript: {} (confidence: {:.2})", 
-                                        if is_final { "Final" } else { "Interim" },
-                                        transcript, alt.confidence);
-                                    
-                                    let payload = TranscriptionPayload {
-                                        text: transcript.clone(),
-                                        is_final,
-                                        timestamp: chrono::Utc::now().timestamp_millis() as u64,
-                                        is_user: true,
-                                    };
-                                    
-                                    let _ = app_for_receiver.emit_all("voice_transcription", payload);
-                                    last_transcript = transcript.clone();
-                                }
-                                
-                                // Clear last transcript on final to prepare for next utterance
-                                if is_final {
-                                    last_transcript.clear();
-                                }
-                            }
-                        }
-                    }
-                },
-                Ok(Message::Close(_)) => {
-                    info!("WebSocket connection closed");
-                    break;
-                },
-                Err(e) => {
-                    error!("WebSocket error: {}", e);
-                    break;
-                },
-                _ => {}
-            }
-        }
-        
-        IS_RUNNING.store(false, Ordering::Relaxed);
-    });
-    
-    // Keep stream alive
-    std::mem::forget(stream);
+    // The source's calibrated silence threshold and the calibrated input gain
+    let preferences = crate::preferences::load();
+    let calibration = if matches!(source, Source::Microphone) {
+        preferences.level_calibration.microphone
+    } else {
+        preferences.level_calibration.system_audio
+    };
+    let settings = StreamSettings {
+        api_key,
+        source,
+        diarize,
+        silence_skipping: preferences.silence_skipping,
+        vad_threshold: calibration.map(|c| c.vad_threshold),
+        mic_gain: crate::level_calibration::microphone_calibration().map_or(1.0, |levels| levels.gain),
+    };
+    let events = Arc::new(AppEvents(app.clone()));
+    start(events.clone(), events, settings).await?;
+    claim.commit();
     
     Ok("Deepgram transcription started successfully".into())
 }
@@ -213,14 +152,14 @@ pub async fn start_deepgram_transcription(
 #[tauri::command]
 pub async fn stop_deepgram_transcription() -> Result<String, String> {
     info!("Stopping Deepgram transcription...");
-    IS_RUNNING.store(false, Ordering::Relaxed);
+    close_capture();
     Ok("Deepgram transcription stopped".into())
 }
 
 // Get status
 #[tauri::command]
 pub async fn get_deepgram_status() -> Result<bool, String> {
-    Ok(IS_RUNNING.load(Ordering::Relaxed))
+    Ok(is_running())
 }
 
 // Test Deepgram connection
@@ -0,0 +1,153 @@
+// Audio Device Conflict Handling
+// When another app holds the mic in exclusive mode (WASAPI exclusive, ALSA hw:
+// device busy, CoreAudio hog mode) stream creation fails with a cryptic backend
+// error. This probes the requested config, recognizes the conflict, falls back
+// to the device's preferred shared-mode format and tells the UI what happened.
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+// Downmixing is shared with the core capture engines
+pub use voicecoach_core::sample_format::downmix_to_mono;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    ExclusiveModeConflict,  // Another app owns the device
+    DeviceUnavailable,      // Unplugged / disabled
+    FormatNotSupported,     // Device rejects the requested format
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct StreamConfigInfo {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub buffer_size: Option<u32>,
+}
+
+impl From<&cpal::StreamConfig> for StreamConfigInfo {
+    fn from(config: &cpal::StreamConfig) -> Self {
+        Self {
+            sample_rate: config.sample_rate.0,
+            channels: config.channels,
+            buffer_size: match config.buffer_size {
+                cpal::BufferSize::Fixed(size) => Some(size),
+                cpal::BufferSize::Default => None,
+            },
+        }
+    }
+}
+
+// Payload of the "device_conflict" event
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct DeviceConflictEvent {
+    pub engine: String,
+    pub device: String,
+    pub kind: ConflictKind,
+    pub requested: StreamConfigInfo,
+    pub error: String,
+    pub renegotiated: bool,
+    pub fallback: Option<StreamConfigInfo>,
+    pub guidance: String,
+}
+
+/// Recognize exclusive-mode conflicts from the backend error text
+pub fn classify(err: &cpal::BuildStreamError) -> ConflictKind {
+    match err {
+        cpal::BuildStreamError::DeviceNotAvailable => ConflictKind::DeviceUnavailable,
+        cpal::BuildStreamError::StreamConfigNotSupported => ConflictKind::FormatNotSupported,
+        cpal::BuildStreamError::BackendSpecific { err } => {
+            let description = err.description.to_lowercase();
+            let busy_markers = [
+                "0x8889000a",              // WASAPI AUDCLNT_E_DEVICE_IN_USE
+                "device in use",
+                "already in use",
+                "device or resource busy", // ALSA EBUSY
+                "!hog",                    // CoreAudio kAudioDevicePermissionsError (hog mode)
+                "560492391",
+            ];
+            if busy_markers.iter().any(|marker| description.contains(marker)) {
+                ConflictKind::ExclusiveModeConflict
+            } else if description.contains("0x88890004") || description.contains("no such device") {
+                // AUDCLNT_E_DEVICE_INVALIDATED / ENODEV
+                ConflictKind::DeviceUnavailable
+            } else {
+                ConflictKind::Other
+            }
+        }
+        _ => ConflictKind::Other,
+    }
+}
+
+fn guidance_for(kind: ConflictKind, renegotiated: bool) -> String {
+    crate::i18n::text(match (kind, renegotiated) {
+        (ConflictKind::ExclusiveModeConflict, true) => "device-exclusive-renegotiated",
+        (ConflictKind::ExclusiveModeConflict, false) => "device-exclusive",
+        (ConflictKind::DeviceUnavailable, _) => "device-unavailable",
+        (ConflictKind::FormatNotSupported, true) | (ConflictKind::Other, true) => "device-format-renegotiated",
+        _ => "device-open-failed",
+    })
+}
+
+/// Try opening the device with a no-op callback to see whether a config is accepted
+fn probe(device: &cpal::Device, config: &cpal::StreamConfig) -> Result<(), cpal::BuildStreamError> {
+    let format = crate::sample_format::input_format(device);
+    let stream = crate::sample_format::build_input_stream(device, config, format, |_: &[f32], _: &_| {}, |_| {})?;
+    let _ = stream.pause();
+    Ok(())
+}
+
+/// Return a config the device will accept: the requested one if possible, otherwise
+/// the device's preferred shared-mode format. Emits "device_conflict" when falling back
+/// or giving up. Callers must handle the returned channel count / sample rate.
+pub fn negotiate_input_config(
+    app: &AppHandle,
+    engine: &str,
+    device: &cpal::Device,
+    requested: cpal::StreamConfig,
+) -> Result<cpal::StreamConfig, String> {
+    let first_error = match probe(device, &requested) {
+        Ok(()) => return Ok(requested),
+        Err(e) => e,
+    };
+
+    let device_name = device.name().unwrap_or_default();
+    let kind = classify(&first_error);
+    warn!("⚠️ LED 8500: {} could not open '{}' at {:?}: {} ({:?})", engine, device_name, requested, first_error, kind);
+
+    // Shared-mode renegotiation: the default input config is the device's mix format
+    let fallback = device.default_input_config()
+        .map(|c| cpal::StreamConfig {
+            channels: c.channels(),
+            sample_rate: c.sample_rate(),
+            buffer_size: cpal::BufferSize::Default,
+        })
+        .ok()
+        .filter(|c| c.channels != requested.channels || c.sample_rate != requested.sample_rate || requested.buffer_size != cpal::BufferSize::Default)
+        .filter(|c| probe(device, c).is_ok());
+
+    let event = DeviceConflictEvent {
+        engine: engine.to_string(),
+        device: device_name,
+        kind,
+        requested: StreamConfigInfo::from(&requested),
+        error: first_error.to_string(),
+        renegotiated: fallback.is_some(),
+        fallback: fallback.as_ref().map(StreamConfigInfo::from),
+        guidance: guidance_for(kind, fallback.is_some()),
+    };
+    if let Err(e) = app.emit_all("device_conflict", event.clone()) {
+        error!("❌ LED 8502: Failed to emit device_conflict: {:?}", e);
+    }
+
+    match fallback {
+        Some(config) => {
+            info!("✅ LED 8501: Renegotiated shared-mode format: {} Hz, {} channels", config.sample_rate.0, config.channels);
+            Ok(config)
+        }
+        None => Err(format!("{} ({})", event.guidance, event.error)),
+    }
+}
@@ -0,0 +1,260 @@
+// Input Device Selection - ranked preference rules instead of the OS default
+// Windows happily switches the default mic to a webcam or a Bluetooth hands-free
+// profile (8kHz, awful for recognition). Rules like "prefer USB headset, never
+// webcam" are kept in preferences and applied at startup and after hot-plug.
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use log::{info, warn};
+
+const HOTPLUG_POLL_SECS: u64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceKind {
+    Usb,
+    Headset,
+    Webcam,
+    Bluetooth,
+    BluetoothHandsFree,
+    BuiltIn,
+    Virtual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Prefer,
+    Avoid,  // Only used when nothing better is available
+    Never,
+}
+
+// One ranking rule; matches on a detected device kind or a name substring
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct DeviceRule {
+    #[serde(default)]
+    pub kind: Option<DeviceKind>,
+    #[serde(default)]
+    pub name_contains: Option<String>,
+    pub action: RuleAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RankedDevice {
+    pub name: String,
+    pub kinds: Vec<DeviceKind>,
+    pub score: i32,
+    pub excluded: bool,
+    pub is_system_default: bool,
+    pub selected: bool,
+    pub matched_rules: Vec<usize>,
+}
+
+// Payload of the "input_device_selected" event (startup + hot-plug)
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct DeviceSelectionEvent {
+    pub device: Option<String>,
+    pub previous: Option<String>,
+    pub reason: String,
+}
+
+pub fn default_rules() -> Vec<DeviceRule> {
+    let rule = |kind, action| DeviceRule { kind: Some(kind), name_contains: None, action };
+    vec![
+        rule(DeviceKind::Headset, RuleAction::Prefer),
+        rule(DeviceKind::Usb, RuleAction::Prefer),
+        rule(DeviceKind::Webcam, RuleAction::Never),
+        rule(DeviceKind::BluetoothHandsFree, RuleAction::Never),
+        rule(DeviceKind::Virtual, RuleAction::Avoid),
+    ]
+}
+
+fn current_rules() -> Vec<DeviceRule> {
+    crate::preferences::load().device_rules.unwrap_or_else(default_rules)
+}
+
+/// Best-effort device classification from the name the OS reports
+pub fn classify_device(name: &str) -> Vec<DeviceKind> {
+    let name = name.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| name.contains(n));
+    let mut kinds = Vec::new();
+
+    if has(&["usb"]) { kinds.push(DeviceKind::Usb); }
+    if has(&["headset", "headphone", "earphone"]) { kinds.push(DeviceKind::Headset); }
+    if has(&["webcam", "camera", "c920", "c922", "brio", "facecam", "lifecam"]) { kinds.push(DeviceKind::Webcam); }
+    if has(&["hands-free", "handsfree", "hands free", "hfp", "headset gateway"]) {
+        kinds.push(DeviceKind::BluetoothHandsFree);
+    }
+    if has(&["bluetooth", "airpods", " bt ", "wh-1000", "buds"]) || kinds.contains(&DeviceKind::BluetoothHandsFree) {
+        kinds.push(DeviceKind::Bluetooth);
+    }
+    if has(&["built-in", "internal", "realtek", "microphone array", "macbook"]) { kinds.push(DeviceKind::BuiltIn); }
+    if has(&["virtual", "voicemeeter", "cable output", "stereo mix", "loopback", "blackhole", "monitor of"]) {
+        kinds.push(DeviceKind::Virtual);
+    }
+    kinds
+}
+
+fn rule_matches(rule: &DeviceRule, name: &str, kinds: &[DeviceKind]) -> bool {
+    let kind_ok = rule.kind.map_or(true, |k| kinds.contains(&k));
+    let name_ok = rule.name_contains.as_ref()
+        .map_or(true, |needle| name.to_lowercase().contains(&needle.to_lowercase()));
+    (rule.kind.is_some() || rule.name_contains.is_some()) && kind_ok && name_ok
+}
+
+/// Score devices against the rules. Earlier rules weigh more; Never excludes.
+pub fn rank_devices(names: &[String], default_name: Option<&str>, rules: &[DeviceRule]) -> Vec<RankedDevice> {
+    let mut ranked: Vec<RankedDevice> = names.iter().map(|name| {
+        let kinds = classify_device(name);
+        let mut score = 0;
+        let mut excluded = false;
+        let mut matched_rules = Vec::new();
+
+        for (index, rule) in rules.iter().enumerate() {
+            if !rule_matches(rule, name, &kinds) {
+                continue;
+            }
+            matched_rules.push(index);
+            let weight = (rules.len() - index) as i32 * 10;
+            match rule.action {
+                RuleAction::Prefer => score += weight,
+                RuleAction::Avoid => score -= weight,
+                RuleAction::Never => excluded = true,
+            }
+        }
+
+        let is_system_default = default_name == Some(name.as_str());
+        if is_system_default {
+            score += 1;  // Tie-breaker only
+        }
+
+        RankedDevice { name: name.clone(), kinds, score, excluded, is_system_default, selected: false, matched_rules }
+    }).collect();
+
+    ranked.sort_by(|a, b| a.excluded.cmp(&b.excluded).then(b.score.cmp(&a.score)));
+    if let Some(best) = ranked.iter_mut().find(|d| !d.excluded) {
+        best.selected = true;
+    }
+    ranked
+}
+
+fn list_input_devices(host: &cpal::Host) -> (Vec<cpal::Device>, Option<String>) {
+    let devices: Vec<cpal::Device> = host.input_devices()
+        .map(|iter| iter.collect())
+        .unwrap_or_default();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    (devices, default_name)
+}
+
+/// Pick the input device according to the preference rules.
+/// Falls back to the system default if every device is excluded or listing fails.
+pub fn select_input_device(host: &cpal::Host) -> Option<cpal::Device> {
+    if crate::chaos::inject(crate::chaos::Fault::DeviceDisappearance) {
+        return None;
+    }
+    let (devices, default_name) = list_input_devices(host);
+    let names: Vec<String> = devices.iter().map(|d| d.name().unwrap_or_default()).collect();
+    let ranked = rank_devices(&names, default_name.as_deref(), &current_rules());
+
+    let chosen = ranked.iter().find(|d| d.selected)
+        .and_then(|best| devices.into_iter().find(|d| d.name().ok().as_deref() == Some(best.name.as_str())));
+
+    match chosen {
+        Some(device) => {
+            info!("🎧 Selected input device by preference rules: {}", device.name().unwrap_or_default());
+            Some(device)
+        }
+        None => {
+            warn!("⚠️ No input device passes the preference rules, using system default");
+            host.default_input_device()
+        }
+    }
+}
+
+/// Loopback capture of system audio: WASAPI records output devices directly,
+/// other platforms expose a monitor/"stereo mix" input device
+pub fn system_audio_device(host: &cpal::Host) -> Option<cpal::Device> {
+    if crate::chaos::inject(crate::chaos::Fault::DeviceDisappearance) {
+        return None;
+    }
+    if cfg!(target_os = "windows") {
+        return host.default_output_device();
+    }
+    host.input_devices().ok()?.find(|d| {
+        d.name().map_or(false, |name| classify_device(&name).contains(&DeviceKind::Virtual))
+    })
+}
+
+/// Stream format for a device returned by system_audio_device (loopback uses the output mix format)
+pub fn system_audio_config(device: &cpal::Device) -> Result<cpal::SupportedStreamConfig, String> {
+    if cfg!(target_os = "windows") {
+        device.default_output_config()
+    } else {
+        device.default_input_config()
+    }.map_err(|e| format!("Failed to get device config: {}", e))
+}
+
+fn preferred_device_name() -> Option<String> {
+    let host = cpal::default_host();
+    let (devices, default_name) = list_input_devices(&host);
+    let names: Vec<String> = devices.iter().map(|d| d.name().unwrap_or_default()).collect();
+    rank_devices(&names, default_name.as_deref(), &current_rules())
+        .into_iter()
+        .find(|d| d.selected)
+        .map(|d| d.name)
+}
+
+/// Poll for hot-plug changes and announce when the preferred device changes.
+/// The frontend restarts transcription on "input_device_selected".
+pub fn start_hotplug_watcher(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut previous = preferred_device_name();
+        let _ = app.emit_all("input_device_selected", DeviceSelectionEvent {
+            device: previous.clone(),
+            previous: None,
+            reason: "startup".to_string(),
+        });
+
+        loop {
+            std::thread::sleep(Duration::from_secs(HOTPLUG_POLL_SECS));
+            let current = preferred_device_name();
+            if current != previous {
+                info!("🔌 Preferred input device changed: {:?} -> {:?}", previous, current);
+                let _ = app.emit_all("input_device_selected", DeviceSelectionEvent {
+                    device: current.clone(),
+                    previous: previous.clone(),
+                    reason: "hotplug".to_string(),
+                });
+                previous = current;
+            }
+        }
+    });
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_device_rules() -> Result<Vec<DeviceRule>, String> {
+    Ok(current_rules())
+}
+
+#[tauri::command]
+pub fn set_device_rules(rules: Vec<DeviceRule>) -> Result<Vec<RankedDevice>, String> {
+    if rules.iter().any(|r| r.kind.is_none() && r.name_contains.as_deref().map_or(true, |n| n.trim().is_empty())) {
+        return Err("Each rule needs a device kind or a name to match".to_string());
+    }
+    crate::preferences::update(|p| p.device_rules = Some(rules))
+        .map_err(|e| e.to_string())?;
+    get_ranked_devices()
+}
+
+// All input devices in preference order, with the one that would be used marked
+#[tauri::command]
+pub fn get_ranked_devices() -> Result<Vec<RankedDevice>, String> {
+    let host = cpal::default_host();
+    let (devices, default_name) = list_input_devices(&host);
+    let names: Vec<String> = devices.iter().map(|d| d.name().unwrap_or_default()).collect();
+    Ok(rank_devices(&names, default_name.as_deref(), &current_rules()))
+}
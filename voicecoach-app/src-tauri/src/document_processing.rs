@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 use log::{info, error};
+use crate::knowledge_base::KnowledgeBaseStats;
 
 // LED breadcrumb trail for Rust operations
 // Uses console output for debugging - Rust logs will be prefixed with [TAURI] in frontend
@@ -97,7 +98,7 @@ impl RustBreadcrumbTrail {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct DocumentProcessingStats {
     pub total_documents: usize,
     pub total_chunks: usize,
@@ -106,7 +107,7 @@ pub struct DocumentProcessingStats {
     pub knowledge_base_size: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
 pub struct KnowledgeSearchResult {
     pub content: String,
     pub similarity_score: f64,
@@ -114,7 +115,18 @@ pub struct KnowledgeSearchResult {
     pub metadata: std::collections::HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// Result of the integration script's "validate" command
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct KnowledgeValidation {
+    pub is_valid: bool,
+    #[serde(default)]
+    pub errors: Vec<String>,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+#[specta(rename = "DocumentCoachingSuggestion")]
 pub struct CoachingSuggestion {
     pub suggestion_type: String,
     pub confidence: f64,
@@ -354,7 +366,7 @@ pub async fn get_coaching_suggestions(
 
 // Tauri command for validating knowledge base integrity
 #[tauri::command]
-pub async fn validate_knowledge_base() -> Result<serde_json::Value, String> {
+pub async fn validate_knowledge_base() -> Result<KnowledgeValidation, String> {
     let trail = RustBreadcrumbTrail::new("TauriKnowledgeValidation");
     
     // LED 201: Tauri command invocation start
@@ -394,7 +406,7 @@ pub async fn validate_knowledge_base() -> Result<serde_json::Value, String> {
     trail.light(510, "DATA_PROCESSING_START", Some("parsing validation results JSON"));
     
     let result_str = String::from_utf8_lossy(&output.stdout);
-    let validation_result: serde_json::Value = serde_json::from_str(&result_str).map_err(|e| {
+    let validation_result: KnowledgeValidation = serde_json::from_str(&result_str).map_err(|e| {
         trail.fail(510, "DATA_PROCESSING_FAILED", &format!("JSON parse failed: {}", e));
         format!("Failed to parse validation results: {}", e)
     })?;
@@ -404,7 +416,7 @@ pub async fn validate_knowledge_base() -> Result<serde_json::Value, String> {
     
     // LED 560: Health check completion
     trail.light(560, "KNOWLEDGE_BASE_HEALTH_CHECK_COMPLETE", 
-        Some(&format!("validation_status: {}", validation_result.get("is_valid").unwrap_or(&serde_json::Value::Bool(false)))));
+        Some(&format!("validation_status: {}", validation_result.is_valid)));
     
     // LED 202: Tauri command completion
     trail.light(202, "VALIDATE_KNOWLEDGE_BASE_COMMAND_COMPLETE", None);
@@ -414,7 +426,7 @@ pub async fn validate_knowledge_base() -> Result<serde_json::Value, String> {
 
 // Tauri command for getting knowledge base statistics
 #[tauri::command]
-pub async fn get_knowledge_base_stats() -> Result<serde_json::Value, String> {
+pub async fn get_knowledge_base_stats() -> Result<KnowledgeBaseStats, String> {
     let trail = RustBreadcrumbTrail::new("TauriKnowledgeStats");
     
     // LED 201: Tauri command invocation start
@@ -454,7 +466,7 @@ pub async fn get_knowledge_base_stats() -> Result<serde_json::Value, String> {
     trail.light(510, "DATA_PROCESSING_START", Some("parsing stats JSON"));
     
     let result_str = String::from_utf8_lossy(&output.stdout);
-    let stats: serde_json::Value = serde_json::from_str(&result_str).map_err(|e| {
+    let stats: KnowledgeBaseStats = serde_json::from_str(&result_str).map_err(|e| {
         trail.fail(510, "DATA_PROCESSING_FAILED", &format!("JSON parse failed: {}", e));
         format!("Failed to parse knowledge stats: {}", e)
     })?;
@@ -468,6 +480,234 @@ pub async fn get_knowledge_base_stats() -> Result<serde_json::Value, String> {
     Ok(stats)
 }
 
+// ========== URL / Web Page Ingestion ==========
+
+// Web pages tracked for scheduled refresh (persisted in preferences)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct UrlSource {
+    pub url: String,
+    pub title: String,
+    pub refresh_hours: Option<u32>,  // None = ingest once, never refresh
+    pub last_fetched: i64,
+    pub last_error: Option<String>,
+}
+
+// How often the scheduler looks for sources that are due
+const URL_REFRESH_CHECK_SECS: u64 = 15 * 60;
+const URL_FETCH_TIMEOUT_SECS: u64 = 30;
+
+// Elements that never hold article text
+const NON_CONTENT_TAGS: [&str; 10] = ["script", "style", "noscript", "nav", "header", "footer", "aside", "form", "svg", "iframe"];
+// Elements whose text forms a readable block
+const BLOCK_TAGS: [&str; 12] = ["h1", "h2", "h3", "h4", "h5", "h6", "p", "li", "blockquote", "pre", "td", "th"];
+
+fn has_ancestor_in(element: &scraper::ElementRef, tags: &[&str], stop_at: &scraper::ElementRef) -> bool {
+    for ancestor in element.ancestors().filter_map(scraper::ElementRef::wrap) {
+        if ancestor.id() == stop_at.id() {
+            return false;
+        }
+        if tags.contains(&ancestor.value().name()) {
+            return true;
+        }
+    }
+    false
+}
+
+fn paragraph_text_len(container: &scraper::ElementRef, paragraphs: &scraper::Selector) -> usize {
+    container.select(paragraphs)
+        .filter(|p| !has_ancestor_in(p, &NON_CONTENT_TAGS, container))
+        .map(|p| p.text().map(str::len).sum::<usize>())
+        .sum()
+}
+
+/// Readability-style extraction: pick the container holding the most paragraph text
+/// (preferring <article>/<main>), then collect its headings, paragraphs and list items.
+pub fn extract_readable_text(html: &str) -> (String, String) {
+    let document = scraper::Html::parse_document(html);
+    let selector = |css: &str| scraper::Selector::parse(css).expect("static selector");
+
+    let title = document.select(&selector("title"))
+        .next()
+        .map(|t| t.text().collect::<String>().trim().to_string())
+        .unwrap_or_default();
+
+    let paragraphs = selector("p");
+    let root = document.root_element();
+    let preferred = document.select(&selector("article, main, [role=main]"))
+        .max_by_key(|el| paragraph_text_len(el, &paragraphs));
+    let container = match preferred.filter(|el| paragraph_text_len(el, &paragraphs) > 0) {
+        Some(el) => el,
+        None => document.select(&selector("div, section, body"))
+            .max_by_key(|el| {
+                // Favor tight containers: paragraph text counts, everything else is noise
+                let total: usize = el.text().map(str::len).sum();
+                let para = paragraph_text_len(el, &paragraphs);
+                (para * 2).saturating_sub(total / 2)
+            })
+            .unwrap_or(root),
+    };
+
+    let blocks = selector(&BLOCK_TAGS.join(", "));
+    let mut lines: Vec<String> = Vec::new();
+    for block in container.select(&blocks) {
+        // Skip boilerplate and blocks nested in another block (already captured)
+        if has_ancestor_in(&block, &NON_CONTENT_TAGS, &root) || has_ancestor_in(&block, &BLOCK_TAGS, &container) {
+            continue;
+        }
+        let text = block.text().collect::<Vec<_>>().join(" ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            continue;
+        }
+        match block.value().name() {
+            "li" => lines.push(format!("- {}", text)),
+            name if name.starts_with('h') && name.len() == 2 => lines.push(format!("\n{}", text)),
+            _ => lines.push(text),
+        }
+    }
+
+    (title, lines.join("\n").trim().to_string())
+}
+
+/// Fetch a page and reduce it to readable text (blocking - call off the async runtime)
+fn fetch_readable_page(url: &str) -> Result<(String, String), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(URL_FETCH_TIMEOUT_SECS))
+        .user_agent("VoiceCoach/0.1 (knowledge import)")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client.get(url).send()
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Fetching {} returned HTTP {}", url, response.status()));
+    }
+    let html = response.text()
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?;
+
+    let (title, text) = extract_readable_text(&html);
+    if text.len() < 100 {
+        return Err(format!("No readable content found at {}", url));
+    }
+    let title = if title.is_empty() { url.to_string() } else { title };
+    Ok((title, text))
+}
+
+/// Fetch + index one URL and record it in the source list
+fn ingest_url_blocking(url: &str, refresh_hours: Option<u32>) -> Result<crate::knowledge_base::KnowledgeDocument, String> {
+    let trail = RustBreadcrumbTrail::new("UrlIngestion");
+    trail.light(230, "URL_FETCH_START", Some(url));
+
+    let fetched = fetch_readable_page(url);
+    let now = chrono::Utc::now().timestamp();
+    let (title, result) = match fetched {
+        Ok((title, text)) => {
+            trail.light(231, "URL_FETCH_COMPLETE", Some(&format!("{} chars", text.len())));
+            let doc = crate::knowledge_base::add_web_document(url, &title, text)
+                .map_err(|e| e.to_string());
+            (title, doc)
+        }
+        Err(e) => {
+            trail.fail(231, "URL_FETCH_FAILED", &e);
+            (url.to_string(), Err(e))
+        }
+    };
+
+    let last_error = result.as_ref().err().cloned();
+    crate::preferences::update(|p| {
+        match p.url_sources.iter_mut().find(|s| s.url == url) {
+            Some(source) => {
+                source.last_fetched = now;
+                source.last_error = last_error;
+                if result.is_ok() {
+                    source.title = title;
+                }
+            }
+            None if result.is_ok() => p.url_sources.push(UrlSource {
+                url: url.to_string(),
+                title,
+                refresh_hours,
+                last_fetched: now,
+                last_error: None,
+            }),
+            None => {}
+        }
+    }).map_err(|e| e.to_string())?;
+
+    result
+}
+
+fn is_due(source: &UrlSource, now: i64) -> bool {
+    match source.refresh_hours {
+        Some(hours) => now - source.last_fetched >= hours as i64 * 3600,
+        None => false,
+    }
+}
+
+/// Background thread that re-ingests URL sources when their refresh interval elapses
+pub fn start_url_refresh_scheduler() {
+    std::thread::spawn(|| loop {
+        let now = chrono::Utc::now().timestamp();
+        let due: Vec<UrlSource> = crate::preferences::load().url_sources
+            .into_iter()
+            .filter(|s| is_due(s, now))
+            .collect();
+
+        for source in due {
+            info!("🔄 Refreshing URL source: {}", source.url);
+            if let Err(e) = ingest_url_blocking(&source.url, source.refresh_hours) {
+                error!("URL refresh failed for {}: {}", source.url, e);
+            }
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(URL_REFRESH_CHECK_SECS));
+    });
+}
+
+// Tauri command for importing a web page into the knowledge base
+#[tauri::command]
+pub async fn ingest_url(url: String, refresh_hours: Option<u32>) -> Result<crate::knowledge_base::KnowledgeDocument, String> {
+    let url = url.trim().to_string();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("URL must start with http:// or https://".to_string());
+    }
+    if refresh_hours == Some(0) {
+        return Err("refresh_hours must be at least 1".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let doc = ingest_url_blocking(&url, refresh_hours)?;
+        // Re-ingesting an existing source updates its schedule too
+        crate::preferences::update(|p| {
+            if let Some(source) = p.url_sources.iter_mut().find(|s| s.url == url) {
+                source.refresh_hours = refresh_hours;
+            }
+        }).map_err(|e| e.to_string())?;
+        Ok(doc)
+    })
+        .await
+        .map_err(|e| format!("URL ingestion task failed: {}", e))?
+}
+
+#[tauri::command]
+pub fn list_url_sources() -> Result<Vec<UrlSource>, String> {
+    Ok(crate::preferences::load().url_sources)
+}
+
+// Stop tracking a URL and drop its content from the knowledge base
+#[tauri::command]
+pub fn remove_url_source(url: String) -> Result<bool, String> {
+    let mut removed = false;
+    crate::preferences::update(|p| {
+        let before = p.url_sources.len();
+        p.url_sources.retain(|s| s.url != url);
+        removed = p.url_sources.len() < before;
+    }).map_err(|e| e.to_string())?;
+
+    crate::knowledge_base::remove_web_document(&url).map_err(|e| e.to_string())?;
+    Ok(removed)
+}
+
 // Helper function to get Python script path
 fn get_python_script_path(script_name: &str) -> Result<PathBuf, String> {
     // Try to find the Python script in the voice_transcription_app_stability_02 directory
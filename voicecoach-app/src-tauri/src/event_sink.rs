@@ -0,0 +1,17 @@
+// Event Sink - core events delivered to the app's windows
+// voicecoach-core reports progress to an EventSink instead of an AppHandle; this
+// wraps the app's handle so those events reach every window exactly as the app's own
+// emit_all events do.
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use voicecoach_core::events::EventSink;
+
+pub struct AppEvents(pub AppHandle);
+
+impl EventSink for AppEvents {
+    fn emit_json(&self, event: &str, payload: Value) -> Result<(), String> {
+        self.0.emit_all(event, payload).map_err(|e| e.to_string())
+    }
+}
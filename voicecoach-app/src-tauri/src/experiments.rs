@@ -0,0 +1,301 @@
+// Experiments - A/B tests of coaching strategies
+// Enablement teams tuning the coach want to know whether a change helps before rolling
+// it out. An experiment has variants, each a rule set: a coaching profile (cooldowns and
+// rate limits; none = this install's) and a coaching focus added to every coaching
+// prompt after the session template's. While an experiment is running, every session
+// that starts is assigned one of its variants at random (by weight) and coached with
+// it; the assignment is stored with the session. get_experiment_results compares the
+// variants on what the sessions recorded afterwards: how reps rated the prompts and how
+// the calls ended, with a z-score of each variant against the first one (the control)
+// so a difference can be told from noise. One experiment runs at a time.
+
+use serde::{Deserialize, Serialize};
+use log::info;
+
+use crate::coaching_cooldown::CoachingProfile;
+use crate::session_store::{CallOutcome, PromptRating, Session};
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ExperimentVariant {
+    pub id: String,
+    pub name: String,
+    /// Share of sessions, relative to the other variants
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    /// Cooldowns and rate limits; None = the install's coaching profile
+    #[serde(default)]
+    pub coaching_profile: Option<CoachingProfile>,
+    /// Guidance added to every coaching prompt
+    #[serde(default)]
+    pub coaching_focus: Option<String>,
+}
+
+fn default_weight() -> f32 { 1.0 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Experiment {
+    pub id: String,
+    pub name: String,
+    pub variants: Vec<ExperimentVariant>,  // The first is the control
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct ExperimentSettings {
+    #[serde(default)]
+    pub experiments: Vec<Experiment>,
+    /// Experiment new sessions are assigned to
+    #[serde(default)]
+    pub running: Option<String>,
+}
+
+/// The variant a session was coached with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct ExperimentAssignment {
+    pub experiment_id: String,
+    pub variant_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct VariantResults {
+    pub variant_id: String,
+    pub name: String,
+    pub sessions: usize,
+    pub prompts: usize,
+    pub prompts_per_session: f32,
+    pub rated_prompts: usize,
+    pub helpful_prompts: usize,
+    pub helpful_rate: Option<f32>,    // Helpful share of rated prompts
+    pub outcomes: usize,              // Sessions with an outcome set
+    pub won: usize,
+    pub lost: usize,
+    pub follow_up: usize,
+    pub no_decision: usize,
+    pub win_rate: Option<f32>,        // Won share of sessions with an outcome
+    pub helpful_rate_z: Option<f32>,  // Against the control; |z| > 1.96 is unlikely to be noise
+    pub win_rate_z: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ExperimentResults {
+    pub experiment_id: String,
+    pub name: String,
+    pub running: bool,
+    pub variants: Vec<VariantResults>,
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// The variant at `roll` (0-1) along the weights
+fn pick_variant(variants: &[ExperimentVariant], roll: f32) -> Option<&ExperimentVariant> {
+    let total: f32 = variants.iter().map(|v| v.weight).sum();
+    let mut threshold = roll * total;
+    for variant in variants {
+        if threshold < variant.weight {
+            return Some(variant);
+        }
+        threshold -= variant.weight;
+    }
+    variants.last()
+}
+
+/// Assignment for a session that is starting (None when no experiment runs)
+pub fn assign() -> Option<ExperimentAssignment> {
+    let settings = crate::preferences::load().experiments;
+    let running = settings.running.as_ref()?;
+    let experiment = settings.experiments.iter().find(|e| &e.id == running)?;
+    let variant = pick_variant(&experiment.variants, rand::random::<f32>())?;
+    info!("🧪 Session assigned to variant '{}' of experiment '{}'", variant.id, experiment.id);
+    Some(ExperimentAssignment { experiment_id: experiment.id.clone(), variant_id: variant.id.clone() })
+}
+
+/// Variant of the session in progress
+fn current_variant() -> Option<ExperimentVariant> {
+    let assignment = crate::session_store::with_current(|s| s.experiment.clone()).flatten()?;
+    crate::preferences::load().experiments.experiments.into_iter()
+        .find(|e| e.id == assignment.experiment_id)?
+        .variants.into_iter()
+        .find(|v| v.id == assignment.variant_id)
+}
+
+/// Coaching profile for the session in progress: its variant's, else the install's
+pub fn coaching_profile() -> CoachingProfile {
+    current_variant().and_then(|v| v.coaching_profile)
+        .unwrap_or_else(|| crate::preferences::load().coaching_profile)
+}
+
+/// Coaching focus of the session's variant
+pub fn coaching_focus() -> Option<String> {
+    current_variant().and_then(|v| v.coaching_focus)
+}
+
+fn rate(hits: usize, total: usize) -> Option<f32> {
+    (total > 0).then(|| hits as f32 / total as f32)
+}
+
+/// Two-proportion z-score of (hits, total) against the control's
+fn z_score(hits: usize, total: usize, control_hits: usize, control_total: usize) -> Option<f32> {
+    if total == 0 || control_total == 0 {
+        return None;
+    }
+    let pooled = (hits + control_hits) as f32 / (total + control_total) as f32;
+    let error = (pooled * (1.0 - pooled) * (1.0 / total as f32 + 1.0 / control_total as f32)).sqrt();
+    (error > 0.0).then(|| (hits as f32 / total as f32 - control_hits as f32 / control_total as f32) / error)
+}
+
+fn variant_results(variant: &ExperimentVariant, sessions: &[&Session]) -> VariantResults {
+    let prompts: Vec<_> = sessions.iter().flat_map(|s| &s.prompts).collect();
+    let rated = prompts.iter().filter(|p| p.rating.is_some()).count();
+    let helpful = prompts.iter().filter(|p| p.rating == Some(PromptRating::Helpful)).count();
+    let outcome_count = |outcome: CallOutcome| sessions.iter().filter(|s| s.outcome == Some(outcome)).count();
+    let outcomes = sessions.iter().filter(|s| s.outcome.is_some()).count();
+    let won = outcome_count(CallOutcome::Won);
+    VariantResults {
+        variant_id: variant.id.clone(),
+        name: variant.name.clone(),
+        sessions: sessions.len(),
+        prompts: prompts.len(),
+        prompts_per_session: if sessions.is_empty() { 0.0 } else { prompts.len() as f32 / sessions.len() as f32 },
+        rated_prompts: rated,
+        helpful_prompts: helpful,
+        helpful_rate: rate(helpful, rated),
+        outcomes,
+        won,
+        lost: outcome_count(CallOutcome::Lost),
+        follow_up: outcome_count(CallOutcome::FollowUp),
+        no_decision: outcome_count(CallOutcome::NoDecision),
+        win_rate: rate(won, outcomes),
+        helpful_rate_z: None,
+        win_rate_z: None,
+    }
+}
+
+fn results(experiment: &Experiment, running: bool, sessions: &[Session]) -> ExperimentResults {
+    let mut variants: Vec<VariantResults> = experiment.variants.iter().map(|variant| {
+        let assigned: Vec<&Session> = sessions.iter()
+            .filter(|s| s.experiment.as_ref().map_or(false, |a| a.experiment_id == experiment.id && a.variant_id == variant.id))
+            .collect();
+        variant_results(variant, &assigned)
+    }).collect();
+
+    if let Some(control) = variants.first().cloned() {
+        for variant in variants.iter_mut().skip(1) {
+            variant.helpful_rate_z = z_score(variant.helpful_prompts, variant.rated_prompts, control.helpful_prompts, control.rated_prompts);
+            variant.win_rate_z = z_score(variant.won, variant.outcomes, control.won, control.outcomes);
+        }
+    }
+    ExperimentResults { experiment_id: experiment.id.clone(), name: experiment.name.clone(), running, variants }
+}
+
+fn validate(experiment: &Experiment) -> Result<(), String> {
+    let valid_id = |id: &str| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_id(&experiment.id) || experiment.variants.iter().any(|v| !valid_id(&v.id)) {
+        return Err("Experiment and variant ids use letters, digits, '-' and '_'".to_string());
+    }
+    if experiment.variants.len() < 2 {
+        return Err("An experiment needs at least two variants".to_string());
+    }
+    let mut ids: Vec<&str> = experiment.variants.iter().map(|v| v.id.as_str()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.len() != experiment.variants.len() {
+        return Err("Variant ids must be unique".to_string());
+    }
+    if experiment.variants.iter().any(|v| v.weight <= 0.0) {
+        return Err("Variant weights must be positive".to_string());
+    }
+    Ok(())
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn list_experiments() -> Result<ExperimentSettings, String> {
+    Ok(crate::preferences::load().experiments)
+}
+
+// Create an experiment, or replace the one with the same id (its sessions keep their
+// assignments; results follow the variant ids)
+#[tauri::command]
+pub fn save_experiment(experiment: Experiment) -> Result<ExperimentSettings, String> {
+    validate(&experiment)?;
+    let preferences = crate::preferences::update(|p| {
+        let experiments = &mut p.experiments.experiments;
+        match experiments.iter_mut().find(|e| e.id == experiment.id) {
+            Some(existing) => *existing = Experiment { created_at: existing.created_at, ..experiment.clone() },
+            None => experiments.push(Experiment { created_at: now_ms(), ..experiment.clone() }),
+        }
+    }).map_err(|e| e.to_string())?;
+    info!("🧪 Experiment '{}' saved ({} variants)", experiment.id, experiment.variants.len());
+    Ok(preferences.experiments)
+}
+
+#[tauri::command]
+pub fn delete_experiment(id: String) -> Result<ExperimentSettings, String> {
+    let preferences = crate::preferences::update(|p| {
+        p.experiments.experiments.retain(|e| e.id != id);
+        if p.experiments.running.as_deref() == Some(id.as_str()) {
+            p.experiments.running = None;
+        }
+    }).map_err(|e| e.to_string())?;
+    Ok(preferences.experiments)
+}
+
+// Assign the sessions that start from now on to this experiment (None = stop)
+#[tauri::command]
+pub fn set_running_experiment(id: Option<String>) -> Result<ExperimentSettings, String> {
+    if let Some(id) = &id {
+        if !crate::preferences::load().experiments.experiments.iter().any(|e| &e.id == id) {
+            return Err(format!("Unknown experiment: {}", id));
+        }
+    }
+    let preferences = crate::preferences::update(|p| p.experiments.running = id.clone())
+        .map_err(|e| e.to_string())?;
+    info!("🧪 Running experiment: {}", id.as_deref().unwrap_or("none"));
+    Ok(preferences.experiments)
+}
+
+// How each variant's sessions did: prompt ratings and call outcomes
+#[tauri::command]
+pub fn get_experiment_results(experiment_id: String) -> Result<ExperimentResults, String> {
+    let settings = crate::preferences::load().experiments;
+    let experiment = settings.experiments.iter().find(|e| e.id == experiment_id)
+        .ok_or_else(|| format!("Unknown experiment: {}", experiment_id))?;
+    let running = settings.running.as_deref() == Some(experiment_id.as_str());
+    Ok(results(experiment, running, &crate::session_store::all_sessions()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(id: &str, weight: f32) -> ExperimentVariant {
+        ExperimentVariant { id: id.to_string(), name: id.to_string(), weight, coaching_profile: None, coaching_focus: None }
+    }
+
+    #[test]
+    fn test_variants_are_picked_by_weight_and_compared_with_the_control() {
+        let variants = vec![variant("control", 1.0), variant("terse", 3.0)];
+        assert_eq!(pick_variant(&variants, 0.0).unwrap().id, "control");
+        assert_eq!(pick_variant(&variants, 0.24).unwrap().id, "control");
+        assert_eq!(pick_variant(&variants, 0.26).unwrap().id, "terse");
+        assert_eq!(pick_variant(&variants, 1.0).unwrap().id, "terse");
+
+        // 20 of 100 vs 40 of 100 helpful is a real difference; 1 of 2 vs 2 of 3 is not
+        assert!(z_score(40, 100, 20, 100).unwrap() > 1.96);
+        assert!(z_score(2, 3, 1, 2).unwrap().abs() < 1.96);
+        assert_eq!(z_score(1, 0, 1, 2), None);
+
+        let experiment = Experiment { id: "focus".to_string(), name: "Focus".to_string(), variants, created_at: 0 };
+        assert!(validate(&experiment).is_ok());
+        let mut session: Session = serde_json::from_value(serde_json::json!({ "id": "s1", "started_at": 0, "outcome": "won" })).unwrap();
+        session.experiment = Some(ExperimentAssignment { experiment_id: "focus".to_string(), variant_id: "terse".to_string() });
+        let report = results(&experiment, true, &[session]);
+        assert_eq!((report.variants[0].sessions, report.variants[1].sessions), (0, 1));
+        assert_eq!(report.variants[1].win_rate, Some(1.0));
+        assert_eq!(report.variants[1].win_rate_z, None);
+    }
+}
@@ -0,0 +1,412 @@
+// Export Security - tamper-evident (and optionally encrypted) export bundles
+// Wraps an exported transcript/bundle file into a .vcx bundle: the content is split
+// into fixed-size chunks, each chunk optionally encrypted with ChaCha20-Poly1305
+// (chunk index bound as associated data so chunks can't be reordered), and a
+// manifest of per-chunk SHA256 hashes is signed with the device's ed25519 key.
+// verify_export reports exactly which chunks were altered. Only a bundle signed by a
+// trusted signer is valid (authentic); an unsigned encrypted bundle whose chunks all
+// decrypt and match the manifest is intact, and its content can still be recovered
+// with the key, but it is reported as unsigned.
+
+use anyhow::{Result, Context, anyhow, bail};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+
+const BUNDLE_FORMAT: &str = "voicecoach-export-v1";
+const BUNDLE_EXTENSION: &str = "vcx";
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// Defaults applied when seal_export is called without explicit flags
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct ExportSecuritySettings {
+    pub sign: bool,
+    pub encrypt: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ChunkEntry {
+    pub index: usize,
+    pub sha256: String,  // Hash of the stored (possibly encrypted) chunk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ExportManifest {
+    pub file_name: String,
+    pub created_at: u64,
+    pub total_bytes: u64,
+    pub chunk_size: usize,
+    pub plaintext_sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer_public_key: Option<String>,
+    pub chunks: Vec<ChunkEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportBundle {
+    format: String,
+    manifest: ExportManifest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    chunks: Vec<String>,  // base64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SealedExport {
+    pub bundle_path: String,
+    pub signed: bool,
+    pub encrypted: bool,
+    pub chunks: usize,
+    pub signer_fingerprint: Option<String>,
+    pub encryption_key_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ExportVerification {
+    pub valid: bool,
+    pub signed: bool,
+    pub signature_valid: Option<bool>,
+    pub signer_fingerprint: Option<String>,
+    pub trusted_signer: Option<String>,  // None = unsigned or unknown key (not valid)
+    pub encrypted: bool,
+    pub intact: bool,                    // Every chunk and the reassembled content check out
+    pub chunks_total: usize,
+    pub tampered_chunks: Vec<usize>,
+    pub decrypted_to: Option<String>,
+    pub problems: Vec<String>,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn chunk_aad(index: usize) -> [u8; 8] {
+    (index as u64).to_le_bytes()
+}
+
+fn manifest_bytes(manifest: &ExportManifest) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(manifest)?)
+}
+
+fn default_bundle_path(input: &Path) -> PathBuf {
+    let mut name = input.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", BUNDLE_EXTENSION));
+    input.with_file_name(name)
+}
+
+/// Build a bundle from raw export bytes with the device's keys
+fn seal(file_name: &str, data: &[u8], sign: bool, encrypt: bool) -> Result<ExportBundle> {
+    let encryption = if encrypt { Some(crate::credentials::export_encryption_key()?) } else { None };
+    let signing_key = if sign { Some(crate::credentials::export_signing_key()?) } else { None };
+    seal_with(file_name, data, signing_key, encryption)
+}
+
+/// Build a bundle, signed with `signing_key` and encrypted with `encryption` (id, key) when given
+fn seal_with(file_name: &str, data: &[u8], signing_key: Option<SigningKey>, encryption: Option<(String, [u8; 32])>) -> Result<ExportBundle> {
+    let cipher = encryption.as_ref().map(|(_, key)| ChaCha20Poly1305::new(Key::from_slice(key)));
+
+    let mut entries = Vec::new();
+    let mut stored = Vec::new();
+    for (index, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
+        let (bytes, nonce) = match &cipher {
+            Some(cipher) => {
+                let mut nonce = [0u8; 12];
+                rand::thread_rng().fill_bytes(&mut nonce);
+                let aad = chunk_aad(index);
+                let sealed = cipher.encrypt(Nonce::from_slice(&nonce), Payload { msg: chunk, aad: &aad })
+                    .map_err(|_| anyhow!("Encryption failed for chunk {}", index))?;
+                (sealed, Some(BASE64.encode(nonce)))
+            }
+            None => (chunk.to_vec(), None),
+        };
+        entries.push(ChunkEntry { index, sha256: sha256_hex(&bytes), nonce });
+        stored.push(BASE64.encode(bytes));
+    }
+
+    let manifest = ExportManifest {
+        file_name: file_name.to_string(),
+        created_at: chrono::Utc::now().timestamp_millis() as u64,
+        total_bytes: data.len() as u64,
+        chunk_size: CHUNK_SIZE,
+        plaintext_sha256: sha256_hex(data),
+        encryption_key_id: encryption.map(|(id, _)| id),
+        signer_public_key: signing_key.as_ref().map(|k| BASE64.encode(k.verifying_key().as_bytes())),
+        chunks: entries,
+    };
+    let signature = match &signing_key {
+        Some(key) => Some(BASE64.encode(key.sign(&manifest_bytes(&manifest)?).to_bytes())),
+        None => None,
+    };
+
+    Ok(ExportBundle { format: BUNDLE_FORMAT.to_string(), manifest, signature, chunks: stored })
+}
+
+fn check_signature(bundle: &ExportBundle) -> Result<VerifyingKey> {
+    let public_key = bundle.manifest.signer_public_key.as_deref()
+        .ok_or_else(|| anyhow!("Bundle has a signature but no signer key"))?;
+    let public_key: [u8; 32] = BASE64.decode(public_key)?.try_into()
+        .map_err(|_| anyhow!("Signer key must be 32 bytes"))?;
+    let public_key = VerifyingKey::from_bytes(&public_key)?;
+
+    let signature = BASE64.decode(bundle.signature.as_deref().unwrap_or_default())?;
+    let signature = Signature::from_slice(&signature)?;
+    public_key.verify(&manifest_bytes(&bundle.manifest)?, &signature)?;
+    Ok(public_key)
+}
+
+/// Check signature + chunk hashes against the device's keys, and reassemble the
+/// plaintext when possible
+fn verify(bundle: &ExportBundle) -> (ExportVerification, Option<Vec<u8>>) {
+    verify_with(bundle, crate::credentials::find_encryption_key, crate::credentials::trusted_signer_name)
+}
+
+fn verify_with(
+    bundle: &ExportBundle,
+    find_key: impl Fn(&str) -> Option<[u8; 32]>,
+    trusted_signer: impl Fn(&VerifyingKey) -> Option<String>,
+) -> (ExportVerification, Option<Vec<u8>>) {
+    let manifest = &bundle.manifest;
+    let mut report = ExportVerification {
+        valid: false,
+        signed: bundle.signature.is_some(),
+        signature_valid: None,
+        signer_fingerprint: None,
+        trusted_signer: None,
+        encrypted: manifest.encryption_key_id.is_some(),
+        intact: false,
+        chunks_total: manifest.chunks.len(),
+        tampered_chunks: Vec::new(),
+        decrypted_to: None,
+        problems: Vec::new(),
+    };
+
+    if bundle.format != BUNDLE_FORMAT {
+        report.problems.push(format!("Unsupported bundle format '{}'", bundle.format));
+        return (report, None);
+    }
+
+    if !report.signed {
+        report.problems.push("Bundle is not signed, so its origin can't be verified".to_string());
+    } else {
+        match check_signature(bundle) {
+            Ok(public_key) => {
+                report.signature_valid = Some(true);
+                report.signer_fingerprint = Some(crate::credentials::fingerprint(public_key.as_bytes()));
+                report.trusted_signer = trusted_signer(&public_key);
+                if report.trusted_signer.is_none() {
+                    report.problems.push("Signature is valid but the signer is not in your trusted list".to_string());
+                }
+            }
+            Err(e) => {
+                report.signature_valid = Some(false);
+                report.problems.push(format!("Manifest signature is invalid: {}", e));
+            }
+        }
+    }
+
+    if bundle.chunks.len() != manifest.chunks.len() {
+        report.problems.push(format!("Manifest lists {} chunks but bundle contains {}", manifest.chunks.len(), bundle.chunks.len()));
+    }
+
+    let cipher = manifest.encryption_key_id.as_deref().and_then(|id| {
+        let key = find_key(id);
+        if key.is_none() {
+            report.problems.push(format!("Encryption key {} is not available on this device", id));
+        }
+        key.map(|k| ChaCha20Poly1305::new(Key::from_slice(&k)))
+    });
+
+    let mut plaintext = Vec::with_capacity(manifest.total_bytes as usize);
+    let mut readable = !report.encrypted || cipher.is_some();
+    for (index, entry) in manifest.chunks.iter().enumerate() {
+        let stored = match bundle.chunks.get(index).map(|c| BASE64.decode(c)) {
+            Some(Ok(bytes)) if entry.index == index && sha256_hex(&bytes) == entry.sha256 => bytes,
+            _ => {
+                report.tampered_chunks.push(index);
+                readable = false;
+                continue;
+            }
+        };
+        if !readable {
+            continue;
+        }
+        match &cipher {
+            Some(cipher) => {
+                let nonce = entry.nonce.as_deref().and_then(|n| BASE64.decode(n).ok()).filter(|n| n.len() == 12);
+                let aad = chunk_aad(index);
+                match nonce.and_then(|n| cipher.decrypt(Nonce::from_slice(&n), Payload { msg: &stored, aad: &aad }).ok()) {
+                    Some(chunk) => plaintext.extend_from_slice(&chunk),
+                    None => {
+                        report.tampered_chunks.push(index);
+                        readable = false;
+                    }
+                }
+            }
+            None => plaintext.extend_from_slice(&stored),
+        }
+    }
+
+    if !report.tampered_chunks.is_empty() {
+        report.problems.push(format!("{} chunk(s) failed integrity checks", report.tampered_chunks.len()));
+    }
+    let plaintext = if readable && sha256_hex(&plaintext) == manifest.plaintext_sha256 {
+        Some(plaintext)
+    } else {
+        if readable {
+            report.problems.push("Reassembled content does not match the manifest hash".to_string());
+        }
+        None
+    };
+
+    report.intact = report.tampered_chunks.is_empty()
+        && bundle.chunks.len() == manifest.chunks.len()
+        && plaintext.is_some();
+    // Anyone can recompute the hashes of an edited bundle (and re-sign it with their own
+    // key), so only a valid signature from a trusted signer makes it tamper-evident
+    report.valid = report.signature_valid == Some(true)
+        && report.trusted_signer.is_some()
+        && report.tampered_chunks.is_empty()
+        && bundle.chunks.len() == manifest.chunks.len()
+        && (plaintext.is_some() || (report.encrypted && cipher.is_none()));
+    (report, plaintext)
+}
+
+/// Whether the recovered content may be written out: the bundle is valid, or it is
+/// unsigned but encrypted with one of our keys and every chunk decrypted and checked out
+fn recoverable(report: &ExportVerification) -> bool {
+    report.intact && (report.valid || (!report.signed && report.encrypted))
+}
+
+fn seal_file(input: &Path, output: &Path, sign: bool, encrypt: bool) -> Result<SealedExport> {
+    if !sign && !encrypt {
+        bail!("Nothing to do: enable signing and/or encryption");
+    }
+    let data = fs::read(input).context(format!("Failed to read export: {:?}", input))?;
+    let file_name = input.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let bundle = seal(&file_name, &data, sign, encrypt)?;
+
+    fs::write(output, serde_json::to_vec_pretty(&bundle)?)
+        .context(format!("Failed to write bundle: {:?}", output))?;
+    info!("🔏 LED 8600: Sealed {:?} -> {:?} ({} chunks, signed: {}, encrypted: {})",
+          input, output, bundle.chunks.len(), sign, encrypt);
+
+    Ok(SealedExport {
+        bundle_path: output.to_string_lossy().to_string(),
+        signed: sign,
+        encrypted: encrypt,
+        chunks: bundle.chunks.len(),
+        signer_fingerprint: bundle.manifest.signer_public_key.as_deref()
+            .and_then(|k| BASE64.decode(k).ok())
+            .map(|k| crate::credentials::fingerprint(&k)),
+        encryption_key_id: bundle.manifest.encryption_key_id,
+    })
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_export_security() -> Result<ExportSecuritySettings, String> {
+    Ok(crate::preferences::load().export_security)
+}
+
+#[tauri::command]
+pub fn set_export_security(settings: ExportSecuritySettings) -> Result<ExportSecuritySettings, String> {
+    crate::preferences::update(|p| p.export_security = settings.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+// Wrap an exported file into a signed and/or encrypted .vcx bundle
+#[tauri::command]
+pub fn seal_export(
+    input_path: String,
+    output_path: Option<String>,
+    sign: Option<bool>,
+    encrypt: Option<bool>,
+) -> Result<SealedExport, String> {
+    let defaults = crate::preferences::load().export_security;
+    let input = PathBuf::from(&input_path);
+    let output = output_path.map(PathBuf::from).unwrap_or_else(|| default_bundle_path(&input));
+
+    seal_file(&input, &output, sign.unwrap_or(defaults.sign), encrypt.unwrap_or(defaults.encrypt))
+        .map_err(|e| e.to_string())
+}
+
+// Validate a received bundle; writes the recovered content when decrypt_to is given and it
+// checks out (signed by a trusted signer, or unsigned but encrypted with one of our keys,
+// with no tampered chunks either way)
+#[tauri::command]
+pub fn verify_export(path: String, decrypt_to: Option<String>) -> Result<ExportVerification, String> {
+    let contents = fs::read_to_string(&path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    let bundle: ExportBundle = serde_json::from_str(&contents)
+        .map_err(|e| format!("Not a VoiceCoach export bundle: {}", e))?;
+
+    let (mut report, plaintext) = verify(&bundle);
+    if report.valid {
+        info!("✅ LED 8601: Export bundle {} verified ({} chunks)", path, report.chunks_total);
+    } else if recoverable(&report) {
+        info!("🔓 LED 8603: Export bundle {} is unsigned but intact ({} chunks)", path, report.chunks_total);
+    } else {
+        warn!("⚠️ LED 8602: Export bundle {} failed verification: {:?}", path, report.problems);
+    }
+
+    if let (Some(target), Some(content)) = (decrypt_to, plaintext) {
+        if recoverable(&report) {
+            fs::write(&target, content).map_err(|e| format!("Failed to write {}: {}", target, e))?;
+            report.decrypted_to = Some(target);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [9; 32];
+
+    fn our_key(id: &str) -> Option<[u8; 32]> {
+        (id == "k1").then_some(KEY)
+    }
+
+    #[test]
+    fn test_encrypt_only_bundles_round_trip_as_unsigned() {
+        let data: Vec<u8> = (0..CHUNK_SIZE * 2 + 100).map(|i| (i % 251) as u8).collect();
+        let bundle = seal_with("call.json", &data, None, Some(("k1".to_string(), KEY))).unwrap();
+
+        let (report, plaintext) = verify_with(&bundle, our_key, |_| None);
+        assert!(report.intact && !report.valid && !report.signed);
+        assert!(recoverable(&report));
+        assert_eq!(plaintext.as_deref(), Some(data.as_slice()));
+
+        // Without the key nothing is recovered
+        let (report, _) = verify_with(&bundle, |_| None, |_| None);
+        assert!(!report.intact && !recoverable(&report));
+
+        // A flipped chunk fails decryption
+        let mut tampered = bundle.clone();
+        let mut chunk = BASE64.decode(&tampered.chunks[1]).unwrap();
+        chunk[0] ^= 1;
+        tampered.chunks[1] = BASE64.encode(&chunk);
+        tampered.manifest.chunks[1].sha256 = sha256_hex(&chunk);
+        let (report, _) = verify_with(&tampered, our_key, |_| None);
+        assert_eq!(report.tampered_chunks, vec![1]);
+        assert!(!recoverable(&report));
+
+        // Signed by a trusted signer it is valid
+        let signed = seal_with("call.json", &data, Some(SigningKey::from_bytes(&[7; 32])), Some(("k1".to_string(), KEY))).unwrap();
+        let (report, _) = verify_with(&signed, our_key, |_| Some("this device".to_string()));
+        assert!(report.valid && recoverable(&report));
+    }
+}
@@ -0,0 +1,289 @@
+// Follow-up Email Drafts - turns the call summary into an email the rep can paste
+// The draft is written by the local Ollama model from the call summary (checklist,
+// prospect questions), the action items coaching suggested during the call and an
+// optional prospect profile. Tone, sign-off, length and extra instructions come from
+// a template stored in preferences (overridable per draft). When the model is not
+// available a plain template draft is assembled from the same facts.
+
+use serde::{Deserialize, Serialize};
+use log::{info, warn};
+
+use crate::call_analytics::CallSummary;
+use crate::ollama_integration::OllamaCoachingService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailFormat {
+    Markdown,
+    Plaintext,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct EmailTemplateSettings {
+    #[serde(default = "default_tone")]
+    pub tone: String,
+    #[serde(default)]
+    pub sender_name: Option<String>,
+    #[serde(default = "default_sign_off")]
+    pub sign_off: String,
+    #[serde(default = "default_format")]
+    pub format: EmailFormat,
+    #[serde(default = "default_max_words")]
+    pub max_words: u32,
+    /// Extra guidance for the model (e.g. "always offer two meeting slots")
+    #[serde(default)]
+    pub instructions: Option<String>,
+}
+
+fn default_tone() -> String { "warm and professional".to_string() }
+fn default_sign_off() -> String { "Best regards".to_string() }
+fn default_format() -> EmailFormat { EmailFormat::Markdown }
+fn default_max_words() -> u32 { 200 }
+
+impl Default for EmailTemplateSettings {
+    fn default() -> Self {
+        Self {
+            tone: default_tone(),
+            sender_name: None,
+            sign_off: default_sign_off(),
+            format: default_format(),
+            max_words: default_max_words(),
+            instructions: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct ProspectProfile {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub company: Option<String>,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct FollowupEmailDraft {
+    pub subject: String,
+    pub body: String,
+    pub format: EmailFormat,
+    pub source: String,  // "ollama" or "template"
+}
+
+/// Facts the draft is written from
+struct CallFacts {
+    covered: Vec<String>,
+    open_items: Vec<String>,
+    answered: Vec<String>,
+    unanswered: Vec<String>,
+    action_items: Vec<String>,
+}
+
+impl CallFacts {
+    fn new(summary: &CallSummary, action_items: Vec<String>) -> Self {
+        let (covered, open_items) = summary.checklist.items.iter()
+            .partition::<Vec<_>, _>(|item| item.completed);
+        let (answered, unanswered) = summary.prospect_questions.iter()
+            .partition::<Vec<_>, _>(|q| q.answered);
+        Self {
+            covered: covered.into_iter().map(|i| i.label.clone()).collect(),
+            open_items: open_items.into_iter().map(|i| i.label.clone()).collect(),
+            answered: answered.into_iter().map(|q| q.text.clone()).collect(),
+            unanswered: unanswered.into_iter().map(|q| q.text.clone()).collect(),
+            action_items,
+        }
+    }
+}
+
+fn bullet_list(title: &str, items: &[String], out: &mut String) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(&format!("{}:\n", title));
+    for item in items {
+        out.push_str(&format!("- {}\n", item));
+    }
+    out.push('\n');
+}
+
+fn build_prompt(facts: &CallFacts, prospect: &ProspectProfile, template: &EmailTemplateSettings) -> String {
+    let mut prompt = String::from("You are a sales rep writing a follow-up email after a call.\n\n");
+
+    let mut profile = Vec::new();
+    if let Some(name) = &prospect.name { profile.push(format!("Name: {}", name)); }
+    if let Some(role) = &prospect.role { profile.push(format!("Role: {}", role)); }
+    if let Some(company) = &prospect.company { profile.push(format!("Company: {}", company)); }
+    if let Some(notes) = &prospect.notes { profile.push(format!("Notes: {}", notes)); }
+    bullet_list("PROSPECT", &profile, &mut prompt);
+
+    bullet_list("TOPICS COVERED", &facts.covered, &mut prompt);
+    bullet_list("NOT YET COVERED", &facts.open_items, &mut prompt);
+    bullet_list("QUESTIONS THE PROSPECT ASKED (answered on the call)", &facts.answered, &mut prompt);
+    bullet_list("QUESTIONS STILL OPEN (answer or promise an answer)", &facts.unanswered, &mut prompt);
+    bullet_list("AGREED NEXT STEPS / ACTION ITEMS", &facts.action_items, &mut prompt);
+
+    prompt.push_str(&format!("Write the email in a {} tone, at most {} words.\n", template.tone, template.max_words));
+    prompt.push_str(match template.format {
+        EmailFormat::Markdown => "Use Markdown (short paragraphs, bullet lists for next steps).\n",
+        EmailFormat::Plaintext => "Use plain text only, no Markdown.\n",
+    });
+    let signature = match &template.sender_name {
+        Some(name) => format!("{},\n{}", template.sign_off, name),
+        None => format!("{},", template.sign_off),
+    };
+    prompt.push_str(&format!("End with:\n{}\n", signature));
+    if let Some(instructions) = &template.instructions {
+        prompt.push_str(&format!("{}\n", instructions));
+    }
+    if let Some(language) = crate::i18n::language_instruction() {
+        prompt.push_str(&format!("{}\n", language));
+    }
+    prompt.push_str("Start your reply with a line \"Subject: ...\", then a blank line, then the email body.\n");
+    prompt
+}
+
+/// Split a model reply into (subject, body)
+fn parse_draft(reply: &str, fallback_subject: &str) -> (String, String) {
+    let reply = reply.trim();
+    let mut lines = reply.lines();
+    match lines.next().and_then(|l| l.trim().strip_prefix("Subject:")) {
+        Some(subject) => {
+            let body = lines.collect::<Vec<_>>().join("\n");
+            (subject.trim().to_string(), body.trim().to_string())
+        }
+        None => (fallback_subject.to_string(), reply.to_string()),
+    }
+}
+
+fn default_subject(prospect: &ProspectProfile) -> String {
+    match &prospect.company {
+        Some(company) => crate::i18n::text_with("email-subject-company", &[("company", company.as_str().into())]),
+        None => crate::i18n::text("email-subject"),
+    }
+}
+
+/// Draft assembled without the model
+fn template_draft(facts: &CallFacts, prospect: &ProspectProfile, template: &EmailTemplateSettings) -> String {
+    let bullet = match template.format {
+        EmailFormat::Markdown => "- ",
+        EmailFormat::Plaintext => "  * ",
+    };
+    let mut body = match &prospect.name {
+        Some(name) => crate::i18n::text_with("email-greeting", &[("name", name.as_str().into())]),
+        None => crate::i18n::text("email-greeting-anonymous"),
+    };
+    body.push_str("\n\n");
+    body.push_str(&crate::i18n::text("email-thanks"));
+    if !facts.covered.is_empty() {
+        let topics = facts.covered.join(", ").to_lowercase();
+        body.push(' ');
+        body.push_str(&crate::i18n::text_with("email-covered", &[("topics", topics.into())]));
+    }
+    body.push_str("\n\n");
+    if !facts.unanswered.is_empty() {
+        body.push_str(&crate::i18n::text_with("email-open-questions", &[("count", facts.unanswered.len().into())]));
+        body.push('\n');
+        for question in &facts.unanswered {
+            body.push_str(&format!("{}{}\n", bullet, question));
+        }
+        body.push('\n');
+    }
+    if !facts.action_items.is_empty() {
+        body.push_str(&crate::i18n::text("email-next-steps"));
+        body.push('\n');
+        for item in &facts.action_items {
+            body.push_str(&format!("{}{}\n", bullet, item));
+        }
+        body.push('\n');
+    }
+    body.push_str(&format!("{},\n", template.sign_off));
+    if let Some(name) = &template.sender_name {
+        body.push_str(name);
+        body.push('\n');
+    }
+    body
+}
+
+// ========== Tauri Commands ==========
+
+// Follow-up email draft for the current call (`template` overrides the stored one)
+#[tauri::command]
+pub async fn draft_followup_email(
+    prospect: Option<ProspectProfile>,
+    template: Option<EmailTemplateSettings>,
+) -> Result<FollowupEmailDraft, String> {
+    let prospect = prospect.unwrap_or_default();
+    let template = template.unwrap_or_else(|| crate::preferences::load().followup_email);
+    let since = crate::session_store::current_started_at().unwrap_or(0);
+    let facts = CallFacts::new(&crate::call_analytics::call_summary(), crate::ollama_integration::action_items_since(since));
+    let subject = default_subject(&prospect);
+
+    let service = OllamaCoachingService::new();
+    if service.check_availability().await.unwrap_or(false) {
+        let num_predict = (template.max_words * 2 + 50) as i32;
+        match service.complete(build_prompt(&facts, &prospect, &template), 0.5, num_predict, 60).await {
+            Ok(reply) => {
+                let (subject, body) = parse_draft(&reply, &subject);
+                info!("✉️ Follow-up email drafted ({} chars)", body.len());
+                return Ok(FollowupEmailDraft { subject, body, format: template.format, source: "ollama".to_string() });
+            }
+            Err(e) => {
+                warn!("⚠️ Email draft generation failed, using template: {}", e);
+                crate::telemetry::record_error("ollama");
+            }
+        }
+    }
+
+    Ok(FollowupEmailDraft {
+        subject,
+        body: template_draft(&facts, &prospect, &template),
+        format: template.format,
+        source: "template".to_string(),
+    })
+}
+
+#[tauri::command]
+pub fn get_email_template() -> Result<EmailTemplateSettings, String> {
+    Ok(crate::preferences::load().followup_email)
+}
+
+#[tauri::command]
+pub fn set_email_template(settings: EmailTemplateSettings) -> Result<EmailTemplateSettings, String> {
+    if settings.max_words == 0 {
+        return Err("max_words must be at least 1".to_string());
+    }
+    crate::preferences::update(|p| p.followup_email = settings.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_reply_and_template_draft() {
+        let (subject, body) = parse_draft("Subject: Great speaking today\n\nHi Dana,\nThanks!", "fallback");
+        assert_eq!(subject, "Great speaking today");
+        assert_eq!(body, "Hi Dana,\nThanks!");
+        assert_eq!(parse_draft("Hi Dana", "fallback").0, "fallback");
+
+        let facts = CallFacts {
+            covered: vec!["Budget discussed".to_string()],
+            open_items: vec![],
+            answered: vec![],
+            unanswered: vec!["Do you integrate with Salesforce?".to_string()],
+            action_items: vec!["Send the pricing sheet".to_string()],
+        };
+        let prospect = ProspectProfile { name: Some("Dana".to_string()), ..Default::default() };
+        let template = EmailTemplateSettings { sender_name: Some("Sam".to_string()), ..Default::default() };
+        let draft = template_draft(&facts, &prospect, &template);
+        assert!(draft.starts_with("Hi Dana,"));
+        assert!(draft.contains("- Do you integrate with Salesforce?"));
+        assert!(draft.contains("- Send the pricing sheet"));
+        assert!(draft.ends_with("Best regards,\nSam\n"));
+    }
+}
@@ -0,0 +1,222 @@
+// Hardware Mute - the headset's mute button, seen from the microphone signal
+// A rep who mutes on the headset (or the Windows mixer) keeps "talking" to VoiceCoach:
+// the stream stays open and delivers silence, the recognizer idles on it and the UI
+// looks live. Mute buttons and muted endpoints don't deliver a quiet room, they deliver
+// digital silence - samples that are exactly (or all but) constant, where an open mic
+// always carries a noise floor of some -70 dBFS or more. So the rep's audio is checked
+// in 20 ms frames: min_mute_ms of digitally silent frames is a mute, a few live frames
+// end it. While muted the rep's audio is not transcribed (Vosk isn't fed, Deepgram
+// isn't streamed), the UI gets "mic_mute_state" to show a "you are muted" indicator,
+// and the muted interval goes into the session for the review timeline.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+const FRAME_MS: u64 = 20;
+// Peak-to-peak span below which a frame is digital silence (~ -80 dBFS)
+const SILENT_SPAN: f32 = 2e-4;
+// Live frames that end a mute (60 ms: a click or the first syllable)
+const UNMUTE_FRAMES: u32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct HardwareMuteSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Stop transcribing the rep while muted
+    #[serde(default = "default_true")]
+    pub pause_transcription: bool,
+    /// Digital silence that counts as a mute (stream starts and dropouts are shorter)
+    #[serde(default = "default_min_mute_ms")]
+    pub min_mute_ms: u32,
+}
+
+fn default_true() -> bool { true }
+fn default_min_mute_ms() -> u32 { 1_500 }
+
+impl Default for HardwareMuteSettings {
+    fn default() -> Self {
+        Self { enabled: true, pause_transcription: true, min_mute_ms: default_min_mute_ms() }
+    }
+}
+
+/// A muted interval (payload of "mic_mute_state" too: no end yet = muted now)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct MutedInterval {
+    pub start_ms: u64,               // Time since the call started
+    pub end_ms: Option<u64>,
+}
+
+// Capture time the ongoing mute started, if muted
+static MUTED_SINCE: Lazy<Mutex<Option<u64>>> = Lazy::new(|| Mutex::new(None));
+
+/// Whether a frame is digital silence
+fn digitally_silent(frame: &[f32]) -> bool {
+    let (min, max) = frame.iter().fold((f32::MAX, f32::MIN), |(min, max), &s| (min.min(s), max.max(s)));
+    max - min < SILENT_SPAN
+}
+
+/// Mute state over a run of frames: returns (start, end) when a mute starts (no end)
+/// or ends
+#[derive(Default)]
+struct Tracker {
+    silent_since: Option<u64>,
+    muted_since: Option<u64>,
+    live_frames: u32,
+}
+
+impl Tracker {
+    fn frame(&mut self, silent: bool, at_ms: u64, min_mute_ms: u64) -> Option<(u64, Option<u64>)> {
+        if silent {
+            self.live_frames = 0;
+            let since = *self.silent_since.get_or_insert(at_ms);
+            if self.muted_since.is_none() && at_ms + FRAME_MS - since >= min_mute_ms {
+                self.muted_since = Some(since);
+                return Some((since, None));
+            }
+            return None;
+        }
+        self.silent_since = None;
+        let since = self.muted_since?;
+        self.live_frames += 1;
+        if self.live_frames < UNMUTE_FRAMES {
+            return None;
+        }
+        self.muted_since = None;
+        self.live_frames = 0;
+        Some((since, Some(at_ms - (UNMUTE_FRAMES as u64 - 1) * FRAME_MS)))
+    }
+}
+
+pub struct MuteDetector {
+    app: AppHandle,
+    settings: HardwareMuteSettings,
+    frame_len: usize,
+    frame: Vec<f32>,
+    frame_start_ms: Option<u64>,
+    tracker: Tracker,
+}
+
+/// Detector for the rep's microphone stream (None when detection is off)
+pub fn detector(app: &AppHandle, sample_rate: u32) -> Option<MuteDetector> {
+    let settings = crate::preferences::load().hardware_mute;
+    if !settings.enabled {
+        return None;
+    }
+    let frame_len = (sample_rate as u64 * FRAME_MS / 1000).max(1) as usize;
+    Some(MuteDetector { app: app.clone(), settings, frame_len, frame: Vec::with_capacity(frame_len), frame_start_ms: None, tracker: Tracker::default() })
+}
+
+impl MuteDetector {
+    /// Check a mono buffer whose first sample was captured at `capture_ms`
+    pub fn observe(&mut self, samples: &[f32], capture_ms: u64) {
+        let sample_ms = |i: usize| capture_ms + i as u64 * FRAME_MS / self.frame_len as u64;
+        for (i, &sample) in samples.iter().enumerate() {
+            let at = *self.frame_start_ms.get_or_insert_with(|| sample_ms(i));
+            self.frame.push(sample);
+            if self.frame.len() < self.frame_len {
+                continue;
+            }
+            let silent = digitally_silent(&self.frame);
+            self.frame.clear();
+            self.frame_start_ms = None;
+            if let Some((start, end)) = self.tracker.frame(silent, at, self.settings.min_mute_ms as u64) {
+                transition(self.app.clone(), start, end);
+            }
+        }
+    }
+
+    /// Whether the rep's audio captured now should not be transcribed
+    pub fn pausing(&self) -> bool {
+        self.settings.pause_transcription && self.tracker.muted_since.is_some()
+    }
+}
+
+impl Drop for MuteDetector {
+    // The stream stopped: a mute in progress ends where the audio did
+    fn drop(&mut self) {
+        if let Some(start) = self.tracker.muted_since.take() {
+            transition(self.app.clone(), start, Some(crate::transcript_sequencer::capture_ms()));
+        }
+    }
+}
+
+/// A mute started or ended (off the audio thread: this writes the session)
+fn transition(app: AppHandle, start_capture_ms: u64, end_capture_ms: Option<u64>) {
+    std::thread::spawn(move || {
+        *MUTED_SINCE.lock().unwrap() = end_capture_ms.is_none().then(|| start_capture_ms);
+        let interval = MutedInterval {
+            start_ms: crate::session_store::offset_of(start_capture_ms),
+            end_ms: end_capture_ms.map(crate::session_store::offset_of),
+        };
+        match interval.end_ms {
+            None => info!("🔇 Microphone muted on the device since {} s", interval.start_ms / 1000),
+            Some(end_ms) => {
+                info!("🎙️ Microphone unmuted after {} s", end_ms.saturating_sub(interval.start_ms) / 1000);
+                if let Err(e) = crate::session_store::add_muted_interval(interval.clone()) {
+                    error!("Failed to record the muted interval: {}", e);
+                }
+            }
+        }
+        if let Err(e) = app.emit_all("mic_mute_state", interval) {
+            error!("Failed to emit mic_mute_state: {:?}", e);
+        }
+    });
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_hardware_mute() -> Result<HardwareMuteSettings, String> {
+    Ok(crate::preferences::load().hardware_mute)
+}
+
+// Applies from the next microphone stream
+#[tauri::command]
+pub fn set_hardware_mute(settings: HardwareMuteSettings) -> Result<HardwareMuteSettings, String> {
+    if settings.min_mute_ms < FRAME_MS as u32 * 10 {
+        return Err(format!("min_mute_ms must be at least {}", FRAME_MS * 10));
+    }
+    crate::preferences::update(|p| p.hardware_mute = settings.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+// The ongoing mute, if the microphone is muted on the device now
+#[tauri::command]
+pub fn get_mic_mute_state() -> Result<Option<MutedInterval>, String> {
+    Ok(MUTED_SINCE.lock().unwrap().map(|start| MutedInterval { start_ms: crate::session_store::offset_of(start), end_ms: None }))
+}
+
+// Muted intervals of a session (the current one when no id is given)
+#[tauri::command]
+pub fn get_muted_intervals(session_id: Option<String>) -> Result<Vec<MutedInterval>, String> {
+    crate::session_store::load_session(session_id).map(|s| s.muted_intervals).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digital_silence_starts_and_live_audio_ends_a_mute() {
+        assert!(digitally_silent(&[0.0; 320]));
+        assert!(digitally_silent(&[0.01; 320]));  // Constant DC offset
+        let room: Vec<f32> = (0..320).map(|i| 0.001 * ((i * 7919 % 13) as f32 - 6.0) / 6.0).collect();
+        assert!(!digitally_silent(&room));
+
+        let mut tracker = Tracker::default();
+        let mut events = Vec::new();
+        let mut at = 0;
+        // 1 s of room noise, a 1 s dropout (too short), 3 s muted, then speech
+        for silent in (0..50).map(|_| false).chain((0..50).map(|_| true)).chain((0..10).map(|_| false))
+            .chain((0..150).map(|_| true)).chain((0..10).map(|_| false)) {
+            events.extend(tracker.frame(silent, at, 1_500));
+            at += FRAME_MS;
+        }
+        assert_eq!(events, vec![(2_200, None), (2_200, Some(5_200))]);
+        assert!(tracker.muted_since.is_none());
+    }
+}
@@ -0,0 +1,394 @@
+// Hold Detection - on-hold music and dead air on the prospect channel
+// The prospect's audio is analysed in frames: level, and spectral flatness (geometric
+// over arithmetic mean of the power spectrum - near 0 for tonal sound, about 0.56 for
+// noise). A one-second block is music-like when it is loud almost throughout, tonal,
+// and steady in level (speech pauses between words and varies syllable by syllable),
+// and dead air when it is quiet almost throughout. A run of such blocks longer than
+// the configured minimum becomes a hold or dead-air period: the UI is told
+// ("quiet_period"), the period goes into the session (and the review timeline), cloud
+// streaming is paused while it lasts (cloud_usage counts the audio as skipped), and
+// prospect words transcribed during hold music - announcements, lyrics - are taken
+// out of the talk ratio (call_analytics). Dead air carries no words, so it never
+// counted towards the ratio.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+// Analysis frame (samples at the capture rate; a power of two for the FFT)
+const FRAME: usize = 1024;
+const BLOCK_MS: u64 = 1_000;
+// Share of frames above the speech threshold for a block to count as music / dead air
+const MUSIC_LOUD_SHARE: f32 = 0.9;
+const DEAD_AIR_LOUD_SHARE: f32 = 0.05;
+// Mean flatness of the loud frames below which a block is tonal
+const MUSIC_MAX_FLATNESS: f32 = 0.3;
+// Frame level variation (standard deviation / mean of RMS) below which a block is steady
+const MUSIC_MAX_LEVEL_VARIATION: f32 = 0.35;
+// Other blocks a period survives (someone speaking over the hold music)
+const MAX_MISSES: u32 = 1;
+// Threshold when the system audio has no level calibration (RMS, full scale = 1.0)
+const DEFAULT_THRESHOLD: f32 = 0.005;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct HoldDetectionSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Stop streaming to cloud engines during hold music and dead air
+    #[serde(default = "default_true")]
+    pub pause_cloud: bool,
+    #[serde(default = "default_min_hold_seconds")]
+    pub min_hold_seconds: u32,
+    #[serde(default = "default_min_dead_air_seconds")]
+    pub min_dead_air_seconds: u32,
+}
+
+fn default_true() -> bool { true }
+fn default_min_hold_seconds() -> u32 { 8 }
+fn default_min_dead_air_seconds() -> u32 { 20 }
+
+impl Default for HoldDetectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pause_cloud: true,
+            min_hold_seconds: default_min_hold_seconds(),
+            min_dead_air_seconds: default_min_dead_air_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum QuietKind {
+    Hold,
+    DeadAir,
+}
+
+/// A hold or dead-air period (payload of "quiet_period" too: no end yet = it started)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct QuietPeriod {
+    pub kind: QuietKind,
+    pub start_ms: u64,               // Time since the call started
+    pub end_ms: Option<u64>,
+}
+
+// A period on the capture clock: kind, start, end (None while ongoing)
+type CapturePeriod = (QuietKind, u64, Option<u64>);
+
+// Periods of the current call, for the talk ratio
+static CALL_PERIODS: Lazy<Mutex<Vec<CapturePeriod>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// In-place radix-2 FFT (`re.len()` a power of two)
+pub(crate) fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let (tr, ti) = (re[b] * cos - im[b] * sin, re[b] * sin + im[b] * cos);
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Spectral flatness of a windowed frame (0 = a pure tone, ~0.56 = white noise)
+fn spectral_flatness(re: &[f32], im: &[f32]) -> f32 {
+    const EPSILON: f32 = 1e-10;
+    let bins = &re[1..re.len() / 2];
+    let powers = bins.iter().zip(&im[1..]).map(|(r, i)| r * r + i * i + EPSILON);
+    let (log_sum, sum) = powers.fold((0.0f32, 0.0f32), |(l, s), p| (l + p.ln(), s + p));
+    let n = bins.len() as f32;
+    (log_sum / n).exp() / (sum / n)
+}
+
+/// What a block of (RMS, flatness) frames sounds like
+fn classify(frames: &[(f32, f32)], threshold: f32) -> Option<QuietKind> {
+    if frames.is_empty() {
+        return None;
+    }
+    let loud: Vec<&(f32, f32)> = frames.iter().filter(|(rms, _)| *rms >= threshold).collect();
+    let loud_share = loud.len() as f32 / frames.len() as f32;
+    if loud_share <= DEAD_AIR_LOUD_SHARE {
+        return Some(QuietKind::DeadAir);
+    }
+    if loud_share < MUSIC_LOUD_SHARE {
+        return None;
+    }
+    let n = loud.len() as f32;
+    let flatness = loud.iter().map(|(_, f)| f).sum::<f32>() / n;
+    let mean_rms = loud.iter().map(|(rms, _)| rms).sum::<f32>() / n;
+    let deviation = (loud.iter().map(|(rms, _)| (rms - mean_rms).powi(2)).sum::<f32>() / n).sqrt();
+    (flatness < MUSIC_MAX_FLATNESS && deviation / mean_rms < MUSIC_MAX_LEVEL_VARIATION).then(|| QuietKind::Hold)
+}
+
+/// A run of music-like or silent blocks, and whether it has become a period
+struct Run {
+    kind: QuietKind,
+    start_capture_ms: u64,
+    end_capture_ms: u64,
+    misses: u32,
+    confirmed: bool,
+}
+
+/// Watches one prospect capture stream (created per stream by `detector`)
+pub struct QuietDetector {
+    app: AppHandle,
+    settings: HoldDetectionSettings,
+    threshold: f32,
+    frames_per_block: usize,
+    window: Vec<f32>,
+    re: Vec<f32>,
+    im: Vec<f32>,
+    filled: usize,
+    block: Vec<(f32, f32)>,
+    block_start_ms: Option<u64>,
+    run: Option<Run>,
+}
+
+/// A detector for the prospect stream (None when detection is off)
+pub fn detector(app: &AppHandle, sample_rate: u32) -> Option<QuietDetector> {
+    let preferences = crate::preferences::load();
+    let settings = preferences.hold_detection;
+    if !settings.enabled {
+        return None;
+    }
+    let threshold = preferences.level_calibration.system_audio.map_or(DEFAULT_THRESHOLD, |c| c.vad_threshold);
+    let window = (0..FRAME)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME - 1) as f32).cos())
+        .collect();
+    Some(QuietDetector {
+        app: app.clone(),
+        settings,
+        threshold,
+        frames_per_block: ((sample_rate as u64 * BLOCK_MS / 1000) as usize / FRAME).max(1),
+        window,
+        re: vec![0.0; FRAME],
+        im: vec![0.0; FRAME],
+        filled: 0,
+        block: Vec::with_capacity(64),
+        block_start_ms: None,
+        run: None,
+    })
+}
+
+impl QuietDetector {
+    /// Analyse a mono buffer whose first sample was captured at `capture_ms`
+    pub fn observe(&mut self, samples: &[f32], capture_ms: u64) {
+        self.block_start_ms.get_or_insert(capture_ms);
+        for &sample in samples {
+            self.re[self.filled] = sample;
+            self.filled += 1;
+            if self.filled < FRAME {
+                continue;
+            }
+            self.filled = 0;
+            let rms = (self.re.iter().map(|s| s * s).sum::<f32>() / FRAME as f32).sqrt();
+            for (value, weight) in self.re.iter_mut().zip(&self.window) {
+                *value *= weight;
+            }
+            self.im.iter_mut().for_each(|v| *v = 0.0);
+            fft(&mut self.re, &mut self.im);
+            self.block.push((rms, spectral_flatness(&self.re, &self.im)));
+            if self.block.len() >= self.frames_per_block {
+                let kind = classify(&self.block, self.threshold);
+                let start = self.block_start_ms.replace(capture_ms).unwrap_or(capture_ms);
+                self.block.clear();
+                self.end_block(kind, start, capture_ms);
+            }
+        }
+    }
+
+    /// Whether cloud streaming should pause for the audio being captured now
+    pub fn pausing(&self) -> bool {
+        self.settings.pause_cloud && self.run.as_ref().map_or(false, |run| run.confirmed && run.misses == 0)
+    }
+
+    fn min_ms(&self, kind: QuietKind) -> u64 {
+        match kind {
+            QuietKind::Hold => self.settings.min_hold_seconds as u64 * 1000,
+            QuietKind::DeadAir => self.settings.min_dead_air_seconds as u64 * 1000,
+        }
+    }
+
+    fn end_block(&mut self, kind: Option<QuietKind>, start_ms: u64, end_ms: u64) {
+        let min_ms = kind.map(|k| self.min_ms(k));
+        match self.run.as_mut() {
+            Some(run) if Some(run.kind) == kind => {
+                run.end_capture_ms = end_ms;
+                run.misses = 0;
+                if !run.confirmed && end_ms.saturating_sub(run.start_capture_ms) >= min_ms.unwrap_or(u64::MAX) {
+                    run.confirmed = true;
+                    transition(self.app.clone(), run.kind, run.start_capture_ms, None);
+                }
+                return;
+            }
+            Some(run) if run.confirmed && run.misses < MAX_MISSES => {
+                run.misses += 1;
+                return;
+            }
+            _ => {}
+        }
+        self.close();
+        self.run = kind.map(|kind| Run { kind, start_capture_ms: start_ms, end_capture_ms: end_ms, misses: 0, confirmed: false });
+    }
+
+    fn close(&mut self) {
+        if let Some(run) = self.run.take().filter(|run| run.confirmed) {
+            transition(self.app.clone(), run.kind, run.start_capture_ms, Some(run.end_capture_ms));
+        }
+    }
+}
+
+impl Drop for QuietDetector {
+    // The stream stopped: a period in progress ends where the audio did
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// A period started or ended (off the audio thread: this writes the session)
+fn transition(app: AppHandle, kind: QuietKind, start_capture_ms: u64, end_capture_ms: Option<u64>) {
+    std::thread::spawn(move || {
+        {
+            let mut periods = CALL_PERIODS.lock().unwrap();
+            match periods.iter_mut().find(|p| p.0 == kind && p.1 == start_capture_ms) {
+                Some(period) => period.2 = end_capture_ms,
+                None => periods.push((kind, start_capture_ms, end_capture_ms)),
+            }
+        }
+        let period = QuietPeriod {
+            kind,
+            start_ms: crate::session_store::offset_of(start_capture_ms),
+            end_ms: end_capture_ms.map(crate::session_store::offset_of),
+        };
+        match period.end_ms {
+            None => {
+                info!("⏸️ Prospect {} since {} s", if kind == QuietKind::Hold { "on hold" } else { "dead air" }, period.start_ms / 1000);
+                if kind == QuietKind::Hold {
+                    crate::call_analytics::exclude_prospect_talk(start_capture_ms);
+                }
+            }
+            Some(end_ms) => {
+                info!("▶️ Prospect {} ended after {} s", if kind == QuietKind::Hold { "hold" } else { "dead air" }, end_ms.saturating_sub(period.start_ms) / 1000);
+                if let Err(e) = crate::session_store::add_quiet_period(period.clone()) {
+                    error!("Failed to record the quiet period: {}", e);
+                }
+            }
+        }
+        if let Err(e) = app.emit_all("quiet_period", period) {
+            error!("Failed to emit quiet_period: {:?}", e);
+        }
+    });
+}
+
+/// Start of a new call
+pub fn begin_call() {
+    CALL_PERIODS.lock().unwrap().clear();
+}
+
+/// Whether prospect speech captured at `capture_ms` fell in hold music
+pub fn on_hold_at(capture_ms: u64) -> bool {
+    CALL_PERIODS.lock().unwrap().iter()
+        .any(|&(kind, start, end)| kind == QuietKind::Hold && capture_ms >= start && end.map_or(true, |end| capture_ms <= end))
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_hold_detection() -> Result<HoldDetectionSettings, String> {
+    Ok(crate::preferences::load().hold_detection)
+}
+
+// Applies from the next prospect stream
+#[tauri::command]
+pub fn set_hold_detection(settings: HoldDetectionSettings) -> Result<HoldDetectionSettings, String> {
+    if settings.min_hold_seconds == 0 || settings.min_dead_air_seconds == 0 {
+        return Err("Minimum durations must be at least one second".to_string());
+    }
+    crate::preferences::update(|p| p.hold_detection = settings.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+// Hold and dead-air periods of a session (the current one when no id is given),
+// including one still in progress
+#[tauri::command]
+pub fn get_quiet_periods(session_id: Option<String>) -> Result<Vec<QuietPeriod>, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    let mut periods = session.quiet_periods;
+    if crate::session_store::current_session_id().as_deref() == Some(session.id.as_str()) {
+        let ongoing = CALL_PERIODS.lock().unwrap().iter()
+            .filter(|p| p.2.is_none())
+            .map(|&(kind, start, _)| QuietPeriod { kind, start_ms: crate::session_store::offset_of(start), end_ms: None })
+            .collect::<Vec<_>>();
+        periods.extend(ongoing);
+    }
+    Ok(periods)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(signal: impl Fn(usize) -> f32) -> Vec<(f32, f32)> {
+        let window: Vec<f32> = (0..FRAME)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME - 1) as f32).cos())
+            .collect();
+        (0..16).map(|frame| {
+            let samples: Vec<f32> = (0..FRAME).map(|i| signal(frame * FRAME + i)).collect();
+            let rms = (samples.iter().map(|s| s * s).sum::<f32>() / FRAME as f32).sqrt();
+            let mut re: Vec<f32> = samples.iter().zip(&window).map(|(s, w)| s * w).collect();
+            let mut im = vec![0.0; FRAME];
+            fft(&mut re, &mut im);
+            (rms, spectral_flatness(&re, &im))
+        }).collect()
+    }
+
+    #[test]
+    fn test_blocks_classify_as_music_dead_air_or_neither() {
+        let rate = 16_000.0;
+        let tone = |i: usize| {
+            let t = i as f32 / rate;
+            0.1 * (2.0 * std::f32::consts::PI * 440.0 * t).sin() + 0.05 * (2.0 * std::f32::consts::PI * 660.0 * t).sin()
+        };
+        // Deterministic white noise
+        let noise = |i: usize| {
+            let mut x = (i as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+            x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            ((x ^ (x >> 31)) >> 40) as f32 / (1u64 << 24) as f32 * 0.2 - 0.1
+        };
+        // Speech-like: voiced bursts at a syllable rate with pauses between them
+        let speech = |i: usize| if (i / 2_000) % 2 == 0 { tone(i) * (1.0 + (i % 2_000) as f32 / 500.0) } else { 0.0 };
+
+        assert_eq!(classify(&block(tone), 0.005), Some(QuietKind::Hold));
+        assert_eq!(classify(&block(|_| 0.0), 0.005), Some(QuietKind::DeadAir));
+        assert_eq!(classify(&block(noise), 0.005), None);
+        assert_eq!(classify(&block(speech), 0.005), None);
+        assert!(block(noise).iter().all(|(_, flatness)| *flatness > MUSIC_MAX_FLATNESS));
+    }
+}
@@ -0,0 +1,237 @@
+// i18n - localized backend text (Fluent)
+// User-facing text the backend writes itself - rule-based coaching prompts, microphone
+// guidance, template follow-up emails - is looked up by message id in the Fluent
+// resources under locales/ (compiled in). The locale comes from preferences, or the
+// system UI language when unset; a requested locale without translations falls back
+// to one of the same language, then to en-US, and a message missing from a
+// translation falls back to its en-US text. Numbers passed as arguments are formatted
+// with the locale's separators. Text written by the Ollama model is requested in the
+// locale's language instead (language_instruction).
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::types::FluentNumber;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use unic_langid::LanguageIdentifier;
+use log::{info, warn};
+
+const DEFAULT_LOCALE: &str = "en-US";
+
+struct Locale {
+    id: &'static str,
+    language: &'static str,  // Name used when asking the model for this language
+    resource: &'static str,
+}
+
+const LOCALES: &[Locale] = &[
+    Locale { id: "en-US", language: "English", resource: include_str!("../locales/en-US.ftl") },
+    Locale { id: "de-DE", language: "German", resource: include_str!("../locales/de-DE.ftl") },
+    Locale { id: "es-ES", language: "Spanish", resource: include_str!("../locales/es-ES.ftl") },
+    Locale { id: "fr-FR", language: "French", resource: include_str!("../locales/fr-FR.ftl") },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LocaleStatus {
+    pub locale: String,              // Locale in use
+    pub preference: Option<String>,  // None = follow the system language
+    pub system: Option<String>,
+    pub available: Vec<String>,
+}
+
+type Bundle = FluentBundle<FluentResource>;
+
+static BUNDLES: Lazy<HashMap<&'static str, Bundle>> = Lazy::new(|| {
+    LOCALES.iter().map(|locale| (locale.id, build_bundle(locale))).collect()
+});
+// Resolved once and on set_locale (preferences are read from disk)
+static ACTIVE: Lazy<Mutex<&'static str>> = Lazy::new(|| Mutex::new(resolve(crate::preferences::load().locale.as_deref())));
+
+/// Format a number with the given separators, honoring Fluent's fraction digit options
+fn format_number(number: &FluentNumber, group: &str, decimal: char) -> String {
+    let options = &number.options;
+    let min_fraction = options.minimum_fraction_digits.unwrap_or(0);
+    let max_fraction = options.maximum_fraction_digits.unwrap_or(3).max(min_fraction);
+    let fixed = format!("{:.*}", max_fraction, number.value.abs());
+    let (integer, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut fraction = fraction.trim_end_matches('0').to_string();
+    while fraction.len() < min_fraction {
+        fraction.push('0');
+    }
+
+    let mut out = String::new();
+    if number.value < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        out.push('-');
+    }
+    for (i, digit) in integer.chars().enumerate() {
+        if options.use_grouping && i > 0 && (integer.len() - i) % 3 == 0 {
+            out.push_str(group);
+        }
+        out.push(digit);
+    }
+    if !fraction.is_empty() {
+        out.push(decimal);
+        out.push_str(&fraction);
+    }
+    out
+}
+
+// Bundle formatters (plain fn pointers, so one per separator convention)
+fn format_point<M>(value: &FluentValue, _: &M) -> Option<String> {
+    match value {
+        FluentValue::Number(number) => Some(format_number(number, ",", '.')),
+        _ => None,
+    }
+}
+
+fn format_comma<M>(value: &FluentValue, _: &M) -> Option<String> {
+    match value {
+        FluentValue::Number(number) => Some(format_number(number, ".", ',')),
+        _ => None,
+    }
+}
+
+fn format_space_comma<M>(value: &FluentValue, _: &M) -> Option<String> {
+    match value {
+        FluentValue::Number(number) => Some(format_number(number, "\u{202f}", ',')),
+        _ => None,
+    }
+}
+
+fn build_bundle(locale: &Locale) -> Bundle {
+    let langid: LanguageIdentifier = locale.id.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Text goes into plain-text UI, emails and logs: no bidi isolation marks
+    bundle.set_use_isolating(false);
+    bundle.set_formatter(Some(match locale.id {
+        "fr-FR" => format_space_comma,
+        "de-DE" | "es-ES" => format_comma,
+        _ => format_point,
+    }));
+    let resource = FluentResource::try_new(locale.resource.to_string()).unwrap_or_else(|(resource, errors)| {
+        warn!("⚠️ {} translations have syntax errors: {:?}", locale.id, errors);
+        resource
+    });
+    if let Err(errors) = bundle.add_resource(resource) {
+        warn!("⚠️ {} translations have duplicate messages: {:?}", locale.id, errors);
+    }
+    bundle
+}
+
+/// Best available locale for a requested one (the system language when None)
+fn resolve(requested: Option<&str>) -> &'static str {
+    let requested = requested.map(str::to_string).or_else(sys_locale::get_locale).unwrap_or_default().replace('_', "-");
+    let language = requested.split('-').next().unwrap_or_default();
+    LOCALES.iter()
+        .find(|l| l.id.eq_ignore_ascii_case(&requested))
+        .or_else(|| LOCALES.iter().find(|l| l.id.split('-').next().map_or(false, |lang| lang.eq_ignore_ascii_case(language))))
+        .map_or(DEFAULT_LOCALE, |l| l.id)
+}
+
+fn active() -> &'static str {
+    *ACTIVE.lock().unwrap()
+}
+
+fn format_in(locale: &str, id: &str, args: &FluentArgs) -> Option<String> {
+    let bundle = BUNDLES.get(locale)?;
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(args), &mut errors);
+    if !errors.is_empty() {
+        warn!("⚠️ Formatting {} ({}): {:?}", id, locale, errors);
+    }
+    Some(text.into_owned())
+}
+
+/// Localized text of message `id`
+pub fn text(id: &str) -> String {
+    text_with(id, &[])
+}
+
+/// Localized text of message `id` with its arguments (strings or numbers)
+pub fn text_with(id: &str, args: &[(&str, FluentValue)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    format_in(active(), id, &fluent_args)
+        .or_else(|| format_in(DEFAULT_LOCALE, id, &fluent_args))
+        .unwrap_or_else(|| {
+            warn!("⚠️ No translation for message {}", id);
+            id.to_string()
+        })
+}
+
+/// Instruction asking the model to write in the locale's language (None for English)
+pub fn language_instruction() -> Option<String> {
+    LOCALES.iter()
+        .find(|l| l.id == active() && l.id != DEFAULT_LOCALE)
+        .map(|l| format!("Write your answer in {}.", l.language))
+}
+
+fn status() -> LocaleStatus {
+    LocaleStatus {
+        locale: active().to_string(),
+        preference: crate::preferences::load().locale,
+        system: sys_locale::get_locale(),
+        available: LOCALES.iter().map(|l| l.id.to_string()).collect(),
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_locale() -> Result<LocaleStatus, String> {
+    Ok(status())
+}
+
+// Locale for backend text (None = follow the system language); e.g. "de-DE" or "de"
+#[tauri::command]
+pub fn set_locale(locale: Option<String>) -> Result<LocaleStatus, String> {
+    let resolved = resolve(locale.as_deref());
+    if let Some(requested) = &locale {
+        let language = requested.split(['-', '_']).next().unwrap_or_default();
+        if !resolved.split('-').next().map_or(false, |lang| lang.eq_ignore_ascii_case(language)) {
+            return Err(format!("No translations for {} (available: {})", requested,
+                LOCALES.iter().map(|l| l.id).collect::<Vec<_>>().join(", ")));
+        }
+    }
+    crate::preferences::update(|p| p.locale = locale.clone())
+        .map_err(|e| e.to_string())?;
+    *ACTIVE.lock().unwrap() = resolved;
+    info!("🌐 Backend text locale: {}", resolved);
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translations_cover_the_reference_locale_and_format_numbers() {
+        // Message ids of the reference resource ("id = ..." lines)
+        let ids: Vec<&str> = LOCALES[0].resource.lines()
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .filter(|id| id.chars().all(|c| c.is_ascii_lowercase() || c == '-'))
+            .collect();
+        assert!(ids.len() > 10);
+        for locale in LOCALES {
+            assert!(FluentResource::try_new(locale.resource.to_string()).is_ok(), "{} has syntax errors", locale.id);
+            let missing: Vec<&&str> = ids.iter().filter(|id| !BUNDLES[locale.id].has_message(id)).collect();
+            assert!(missing.is_empty(), "{} lacks {:?}", locale.id, missing);
+        }
+
+        assert_eq!(resolve(Some("de_AT")), "de-DE");
+        assert_eq!(resolve(Some("ja-JP")), DEFAULT_LOCALE);
+        let mut args = FluentArgs::new();
+        args.set("count", 3);
+        assert_eq!(format_in("de-DE", "email-open-questions", &args).unwrap(),
+            "Auf die 3 Fragen, zu denen wir nicht mehr gekommen sind, melde ich mich noch:");
+        args.set("count", 1);
+        assert!(format_in("en-US", "email-open-questions", &args).unwrap().contains("the question we"));
+        assert_eq!(format_number(&FluentNumber::from(-1234567.25), ".", ','), "-1.234.567,25");
+        assert_eq!(format_number(&FluentNumber::from(950), ",", '.'), "950");
+    }
+}
@@ -0,0 +1,231 @@
+// Key Moments - the few lines of a call worth jumping to
+// A manager reviewing a call, or a rep writing it up, wants the turning points rather
+// than the whole transcript: when pricing first came up, when the prospect named who
+// decides, when they committed, where the conversation soured, when a competitor was
+// brought up. These are found in the transcript by combining cue phrases with the
+// objection detector (sales_stage), the keyword watchlist (competitor_watch) and a
+// per-line prospect sentiment score - positive and negative wording, with objection
+// cues counted as negative - kept over the last few prospect lines: a spike is the
+// score dropping to NEGATIVE_SPIKE, and another is only taken once the score has
+// recovered. Each moment is ranked by how much it matters to the deal (a commitment
+// over a pricing mention; deeper spikes higher) and surfaced in the call summary and
+// the review timeline.
+
+use serde::{Deserialize, Serialize};
+
+use crate::competitor_watch::CompetitorWatchlist;
+use crate::session_store::TranscriptLine;
+
+// Prospect lines the sentiment score is kept over
+const SENTIMENT_WINDOW: usize = 3;
+// Score over the window that is a negative spike; recovered at 0 or above
+const NEGATIVE_SPIKE: i32 = -3;
+
+const PRICING_CUES: &[&str] = &[
+    "price", "pricing", "cost", "costs", "how much", "per seat", "per user", "per month", "per year",
+    "discount", "quote", "budget", "license fee", "subscription fee",
+];
+const DECISION_MAKER_CUES: &[&str] = &[
+    "decision maker", "i make the decision", "i make the call", "final say", "sign off", "signs off",
+    "my boss", "my manager", "our cfo", "our ceo", "our cto", "the board", "procurement", "run it by", "run this by",
+];
+const COMMITMENT_CUES: &[&str] = &[
+    "let's do it", "let's move forward", "move forward with", "send over the contract", "send me the contract",
+    "ready to sign", "i'll sign", "we'll sign", "we're in", "count us in", "we'll take it", "let's get started",
+];
+const NEGATIONS: &[&str] = &["not", "don't", "can't", "won't", "isn't", "aren't", "never", "no"];
+const POSITIVE_WORDS: &[&str] = &[
+    "great", "love", "perfect", "excellent", "awesome", "excited", "impressive", "helpful",
+    "fantastic", "makes sense", "sounds good", "exactly what", "really like",
+];
+const NEGATIVE_WORDS: &[&str] = &[
+    "frustrated", "frustrating", "disappointed", "annoyed", "angry", "unhappy", "not happy", "terrible",
+    "awful", "horrible", "worst", "ridiculous", "waste of time", "not interested", "no way", "hate",
+    "doesn't work", "broken", "worried",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyMomentKind {
+    PricingMentioned,
+    DecisionMaker,
+    VerbalCommitment,
+    NegativeSpike,
+    CompetitorMentioned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct KeyMoment {
+    pub kind: KeyMomentKind,
+    pub label: String,
+    pub line: usize,                 // Index of the transcript line
+    pub offset_ms: u64,              // Time since the call started
+    pub is_user: bool,
+    pub text: String,
+    pub score: f32,                  // 0-1, how much it matters; the list is sorted by it
+}
+
+/// Phrases and watchlist the transcript is matched against (loaded once per transcript)
+struct Cues {
+    pricing: Vec<String>,
+    decision_maker: Vec<String>,
+    commitment: Vec<String>,
+    negations: Vec<String>,
+    positive: Vec<String>,
+    negative: Vec<String>,
+    objections: Vec<String>,
+    watchlist: CompetitorWatchlist,
+}
+
+fn phrases(list: &[&str]) -> Vec<String> {
+    list.iter().map(|p| p.to_string()).collect()
+}
+
+impl Cues {
+    fn new(objections: Vec<String>, watchlist: CompetitorWatchlist) -> Self {
+        Self {
+            pricing: phrases(PRICING_CUES),
+            decision_maker: phrases(DECISION_MAKER_CUES),
+            commitment: phrases(COMMITMENT_CUES),
+            negations: phrases(NEGATIONS),
+            positive: phrases(POSITIVE_WORDS),
+            negative: phrases(NEGATIVE_WORDS),
+            objections,
+            watchlist,
+        }
+    }
+
+    fn load() -> Self {
+        Self::new(crate::sales_stage::objection_cues(), crate::preferences::load().competitors)
+    }
+
+    /// Sentiment of a prospect line: positive wording up, negative wording and objections down
+    fn sentiment(&self, text: &str) -> i32 {
+        let count = |list: &[String]| crate::call_analytics::count_phrases(text, list) as i32;
+        count(&self.positive) - count(&self.negative) - count(&self.objections).min(1)
+    }
+
+    /// A commitment, not a question about one or a refusal
+    fn commits(&self, text: &str) -> bool {
+        crate::call_analytics::find_phrase(text, &self.commitment).is_some()
+            && !text.trim_end().ends_with('?')
+            && crate::call_analytics::find_phrase(text, &self.negations).is_none()
+    }
+}
+
+fn moment(kind: KeyMomentKind, label: String, index: usize, line: &TranscriptLine, score: f32) -> KeyMoment {
+    KeyMoment { kind, label, line: index, offset_ms: line.offset_ms, is_user: line.is_user, text: line.text.clone(), score }
+}
+
+fn detect(transcript: &[TranscriptLine], cues: &Cues) -> Vec<KeyMoment> {
+    let mut moments = Vec::new();
+    let first = |moments: &[KeyMoment], kind: KeyMomentKind| !moments.iter().any(|m| m.kind == kind);
+    let mut competitors_seen: Vec<String> = Vec::new();
+    let mut window: Vec<i32> = Vec::new();
+    let mut spiking = false;
+
+    for (index, line) in transcript.iter().enumerate() {
+        if first(&moments, KeyMomentKind::PricingMentioned) && crate::call_analytics::find_phrase(&line.text, &cues.pricing).is_some() {
+            moments.push(moment(KeyMomentKind::PricingMentioned, "Pricing first mentioned".to_string(), index, line, 0.7));
+        }
+        if line.is_user {
+            continue;
+        }
+        if first(&moments, KeyMomentKind::DecisionMaker) && crate::call_analytics::find_phrase(&line.text, &cues.decision_maker).is_some() {
+            moments.push(moment(KeyMomentKind::DecisionMaker, "Decision-maker identified".to_string(), index, line, 0.8));
+        }
+        if first(&moments, KeyMomentKind::VerbalCommitment) && cues.commits(&line.text) {
+            moments.push(moment(KeyMomentKind::VerbalCommitment, "Verbal commitment".to_string(), index, line, 1.0));
+        }
+        if cues.watchlist.enabled {
+            for (competitor, _) in crate::competitor_watch::mentioned(&line.text, &cues.watchlist) {
+                if !competitors_seen.contains(&competitor.name) {
+                    moments.push(moment(KeyMomentKind::CompetitorMentioned, format!("Competitor mentioned: {}", competitor.name), index, line, 0.5));
+                    competitors_seen.push(competitor.name);
+                }
+            }
+        }
+
+        window.push(cues.sentiment(&line.text));
+        if window.len() > SENTIMENT_WINDOW {
+            window.remove(0);
+        }
+        let score: i32 = window.iter().sum();
+        if !spiking && score <= NEGATIVE_SPIKE {
+            spiking = true;
+            // Deeper drops rank higher
+            let depth = (NEGATIVE_SPIKE - score) as f32 * 0.1;
+            moments.push(moment(KeyMomentKind::NegativeSpike, "Negative sentiment spike".to_string(), index, line, (0.6 + depth).min(0.95)));
+        } else if spiking && score >= 0 {
+            spiking = false;
+        }
+    }
+
+    // Highest first; the earlier of equals
+    moments.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal).then(a.line.cmp(&b.line)));
+    moments
+}
+
+/// Key moments of a transcript, ranked
+pub fn key_moments(transcript: &[TranscriptLine]) -> Vec<KeyMoment> {
+    detect(transcript, &Cues::load())
+}
+
+/// Key moments of the call so far
+pub fn current() -> Vec<KeyMoment> {
+    crate::session_store::with_current(|s| s.transcript.clone())
+        .map_or_else(Vec::new, |transcript| key_moments(&transcript))
+}
+
+// ========== Tauri Commands ==========
+
+// Ranked key moments of a session (the current one when no id is given)
+#[tauri::command]
+pub fn get_key_moments(session_id: Option<String>) -> Result<Vec<KeyMoment>, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    Ok(key_moments(&session.transcript))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::competitor_watch::Competitor;
+
+    fn line(offset_s: u64, is_user: bool, text: &str) -> TranscriptLine {
+        TranscriptLine { offset_ms: offset_s * 1000, is_user, text: text.to_string(), words: Vec::new() }
+    }
+
+    #[test]
+    fn test_moments_are_found_and_ranked() {
+        let transcript = vec![
+            line(0, true, "Thanks for taking the call"),
+            line(10, false, "We looked at Globex last year"),
+            line(20, true, "Our pricing is per seat"),
+            line(30, false, "That's too expensive, honestly I'm frustrated"),
+            line(40, false, "This is a waste of time"),
+            line(50, true, "Let me show you the ROI"),
+            line(60, false, "Could we move forward with a pilot?"),
+            line(70, false, "My boss has the final say"),
+            line(80, false, "Okay, that makes sense. Let's do it"),
+        ];
+        let watchlist = CompetitorWatchlist {
+            competitors: vec![Competitor { name: "Globex".to_string(), aliases: Vec::new(), battlecard: None }],
+            ..Default::default()
+        };
+        let cues = Cues::new(phrases(&["too expensive", "not sure"]), watchlist);
+        let moments = detect(&transcript, &cues);
+
+        let found: Vec<(KeyMomentKind, usize)> = moments.iter().map(|m| (m.kind, m.line)).collect();
+        assert_eq!(found, vec![
+            (KeyMomentKind::VerbalCommitment, 8),
+            (KeyMomentKind::DecisionMaker, 7),
+            (KeyMomentKind::PricingMentioned, 2),
+            (KeyMomentKind::NegativeSpike, 4),
+            (KeyMomentKind::CompetitorMentioned, 1),
+        ]);
+        assert_eq!(moments[4].label, "Competitor mentioned: Globex");
+        // The question about moving forward is not a commitment
+        assert_eq!(moments[0].offset_ms, 80_000);
+        assert!((moments[3].score - 0.6).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,229 @@
+// Knowledge Answer - direct answers to questions from the knowledge base
+// answer_question retrieves the best-matching passages (knowledge_base search on the
+// question's keywords) and has the local LLM write a short answer from them alone,
+// citing passages as [1], [2]. The budget is strict: passages are added until the
+// context budget is spent (the last one cut to fit) and the reply is capped at the
+// answer budget. When Ollama is unavailable, or fails, the answer is extractive: the
+// passage sentences sharing the most keywords with the question, in passage order,
+// up to the answer budget. Citations are the passages the answer actually used.
+
+use serde::{Deserialize, Serialize};
+use log::{info, warn};
+
+use crate::knowledge_base::KnowledgeCitation;
+use crate::ollama_integration::OllamaCoachingService;
+
+const DEFAULT_SOURCES: usize = 4;
+const CONTEXT_TOKENS: usize = 1200;
+const DEFAULT_ANSWER_TOKENS: usize = 150;
+const MAX_ANSWER_TOKENS: usize = 400;
+// Rough English average, used for every budget
+const CHARS_PER_TOKEN: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct KnowledgeAnswer {
+    pub question: String,
+    pub answer: String,
+    pub citations: Vec<KnowledgeCitation>,  // Passages the answer cites, as numbered in it
+    pub source: String,                     // "ollama", "extractive" or "none"
+}
+
+struct Passage {
+    text: String,
+    citation: KnowledgeCitation,
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// Passages that fit the context budget (the last one cut to the remainder)
+fn within_budget(hits: Vec<(String, KnowledgeCitation)>, budget_tokens: usize) -> Vec<Passage> {
+    let mut remaining = budget_tokens * CHARS_PER_TOKEN;
+    let mut passages = Vec::new();
+    for (text, citation) in hits {
+        if remaining == 0 {
+            break;
+        }
+        let text = truncate_chars(&text, remaining).to_string();
+        remaining -= text.chars().count();
+        passages.push(Passage { text, citation });
+    }
+    passages
+}
+
+fn build_prompt(question: &str, passages: &[Passage], answer_tokens: usize) -> String {
+    let mut prompt = String::from("Answer the question using only the numbered passages below.\n\n");
+    for (i, passage) in passages.iter().enumerate() {
+        prompt.push_str(&format!("[{}] {}\n\n", i + 1, passage.text));
+    }
+    prompt.push_str(&format!("QUESTION:\n{}\n\n", question));
+    prompt.push_str(&format!(
+        "Answer in at most {} words. Cite the passages you use like [1]. \
+         If the passages do not answer the question, say so in one sentence.\n",
+        answer_tokens * 3 / 4
+    ));
+    if let Some(language) = crate::i18n::language_instruction() {
+        prompt.push_str(&format!("{}\n", language));
+    }
+    prompt
+}
+
+/// Passage numbers (1-based) cited in an answer, in order of first citation
+fn cited_numbers(answer: &str, passages: usize) -> Vec<usize> {
+    let mut cited = Vec::new();
+    for part in answer.split('[').skip(1) {
+        let number = part.split(']').next().and_then(|n| n.trim().parse::<usize>().ok());
+        if let Some(n) = number.filter(|n| (1..=passages).contains(n)) {
+            if !cited.contains(&n) {
+                cited.push(n);
+            }
+        }
+    }
+    cited
+}
+
+/// Renumber the answer's citations to match the cited passages only
+fn renumber(answer: &str, cited: &[usize]) -> String {
+    let mut out = answer.to_string();
+    for (new, old) in cited.iter().enumerate() {
+        out = out.replace(&format!("[{}]", old), &format!("[#{}]", new + 1));
+    }
+    out.replace("[#", "[")
+}
+
+/// Offline answer: the sentences sharing most keywords with the question
+fn extractive_answer(question: &str, passages: &[Passage], answer_tokens: usize) -> (String, Vec<usize>) {
+    let terms = crate::topic_segmentation::keywords(question);
+    // (passage, position, score, sentence)
+    let mut sentences: Vec<(usize, usize, usize, &str)> = Vec::new();
+    for (p, passage) in passages.iter().enumerate() {
+        for (position, sentence) in passage.text.split_inclusive(['.', '!', '?', '\n']).enumerate() {
+            let sentence = sentence.trim();
+            let words = crate::topic_segmentation::keywords(sentence);
+            let score = terms.iter().filter(|t| words.contains(t)).count();
+            if score > 0 {
+                sentences.push((p, position, score, sentence));
+            }
+        }
+    }
+    sentences.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)).then(a.1.cmp(&b.1)));
+
+    let mut budget = answer_tokens * CHARS_PER_TOKEN;
+    let mut chosen: Vec<(usize, usize, &str)> = Vec::new();
+    for (p, position, _, sentence) in sentences {
+        if sentence.chars().count() > budget {
+            continue;
+        }
+        budget -= sentence.chars().count();
+        chosen.push((p, position, sentence));
+    }
+    chosen.sort();
+
+    let mut cited: Vec<usize> = Vec::new();
+    let mut answer = Vec::new();
+    for (p, _, sentence) in chosen {
+        if !cited.contains(&(p + 1)) {
+            cited.push(p + 1);
+        }
+        let number = cited.iter().position(|&n| n == p + 1).unwrap_or(0) + 1;
+        answer.push(format!("{} [{}]", sentence, number));
+    }
+    (answer.join(" "), cited)
+}
+
+fn answer(question: String, answer: String, passages: &[Passage], cited: &[usize], source: &str) -> KnowledgeAnswer {
+    KnowledgeAnswer {
+        question,
+        answer,
+        citations: cited.iter().filter_map(|n| passages.get(n - 1)).map(|p| p.citation.clone()).collect(),
+        source: source.to_string(),
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Answer a question from the knowledge base, with citations (extractive without Ollama)
+#[tauri::command]
+pub async fn answer_question(
+    question: String,
+    max_sources: Option<usize>,
+    max_answer_tokens: Option<usize>,
+) -> Result<KnowledgeAnswer, String> {
+    let question = question.trim().to_string();
+    let terms = crate::topic_segmentation::keywords(&question);
+    if terms.is_empty() {
+        return Err("Ask a question with at least one keyword".to_string());
+    }
+    let answer_tokens = max_answer_tokens.unwrap_or(DEFAULT_ANSWER_TOKENS).clamp(20, MAX_ANSWER_TOKENS);
+    let hits = crate::knowledge_base::search_passages(&terms.join(" "), max_sources.unwrap_or(DEFAULT_SOURCES))
+        .map_err(|e| e.to_string())?;
+    let passages = within_budget(hits, CONTEXT_TOKENS);
+    if passages.is_empty() {
+        return Ok(answer(question, crate::i18n::text("answer-not-found"), &[], &[], "none"));
+    }
+
+    let service = OllamaCoachingService::new();
+    if service.check_availability().await.unwrap_or(false) {
+        let prompt = build_prompt(&question, &passages, answer_tokens);
+        match service.complete(prompt, 0.2, answer_tokens as i32, 30).await {
+            Ok(reply) => {
+                let reply = reply.trim();
+                let cited = cited_numbers(reply, passages.len());
+                info!("📖 Answered from {} of {} passages", cited.len(), passages.len());
+                return Ok(answer(question, renumber(reply, &cited), &passages, &cited, "ollama"));
+            }
+            Err(e) => {
+                warn!("⚠️ Answer synthesis failed, answering extractively: {}", e);
+                crate::telemetry::record_error("ollama");
+            }
+        }
+    }
+
+    let (text, cited) = extractive_answer(&question, &passages, answer_tokens);
+    if text.is_empty() {
+        return Ok(answer(question, crate::i18n::text("answer-not-found"), &[], &[], "none"));
+    }
+    Ok(answer(question, text, &passages, &cited, "extractive"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passage(document: &str, text: &str) -> (String, KnowledgeCitation) {
+        let citation = KnowledgeCitation {
+            document: document.to_string(),
+            section: None,
+            chunk_index: 0,
+            similarity: 1.0,
+            excerpt: String::new(),
+            source_url: None,
+        };
+        (text.to_string(), citation)
+    }
+
+    #[test]
+    fn test_extractive_answer_and_citation_numbering() {
+        let passages = within_budget(vec![
+            passage("pricing.md", "Our list price is per seat. Annual contracts get a discount of 15 percent."),
+            passage("onboarding.md", "Onboarding takes two weeks. A discount requires manager approval for contracts under a year."),
+        ], CONTEXT_TOKENS);
+        let (text, cited) = extractive_answer("What discount do annual contracts get?", &passages, 150);
+        assert_eq!(text, "Annual contracts get a discount of 15 percent. [1] A discount requires manager approval for contracts under a year. [2]");
+        assert_eq!(cited, vec![1, 2]);
+
+        // A budget of one sentence keeps the best match only
+        let (text, _) = extractive_answer("What discount do annual contracts get?", &passages, 12);
+        assert_eq!(text, "Annual contracts get a discount of 15 percent. [1]");
+
+        // Citations renumbered to the passages used; out-of-range numbers ignored
+        let cited = cited_numbers("Approval is needed [3], see also [3] and [9].", 3);
+        assert_eq!(cited, vec![3]);
+        assert_eq!(renumber("Approval is needed [3].", &cited), "Approval is needed [1].");
+        assert_eq!(within_budget(vec![passage("a", &"x".repeat(5000))], 100)[0].text.len(), 400);
+    }
+}
@@ -0,0 +1,243 @@
+// Level Calibration Wizard - per-source gain and VAD threshold from real recordings
+// Records ~10s of the user speaking into the mic and ~10s of typical system audio
+// (call audio, loopback capture), measures noise floor / speech level / clipping
+// and derives a mixer gain and VAD threshold for each source. Results are stored
+// in preferences; the mic values are applied when the next transcription starts.
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+const DEFAULT_SECONDS: u32 = 10;
+const FRAME_MS: u32 = 20;
+const PROGRESS_INTERVAL_MS: u64 = 250;
+// Normalized speech level to aim for (~ -20 dBFS RMS)
+const TARGET_SPEECH_RMS: f32 = 0.1;
+const MIN_GAIN: f32 = 0.25;
+const MAX_GAIN: f32 = 8.0;
+const CLIP_LEVEL: f32 = 0.99;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelSource {
+    Microphone,
+    SystemAudio,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SourceCalibration {
+    pub device: String,
+    pub noise_floor: f32,     // RMS, 10th percentile of 20ms frames
+    pub speech_level: f32,    // RMS, 90th percentile of 20ms frames
+    pub peak: f32,
+    pub clipped_ratio: f32,   // Fraction of samples at full scale
+    pub gain: f32,            // Recommended mixer gain
+    pub vad_threshold: f32,   // RMS threshold after gain is applied
+    pub warnings: Vec<String>,
+    pub calibrated_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct LevelCalibration {
+    #[serde(default)]
+    pub microphone: Option<SourceCalibration>,
+    #[serde(default)]
+    pub system_audio: Option<SourceCalibration>,
+}
+
+// Payload of "level_calibration_progress" (drives the wizard's level meter)
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct LevelProgress {
+    pub source: LevelSource,
+    pub elapsed_ms: u64,
+    pub total_ms: u64,
+    pub rms: f32,
+}
+
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let index = ((sorted.len() - 1) as f32 * p).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+/// Derive gain + VAD threshold from per-frame RMS values and raw sample stats
+fn recommend(device: &str, frame_rms: &[f32], peak: f32, clipped_ratio: f32) -> SourceCalibration {
+    let mut sorted = frame_rms.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let noise_floor = percentile(&sorted, 0.1);
+    let speech_level = percentile(&sorted, 0.9);
+    let mut warnings = Vec::new();
+
+    let mut gain = if speech_level > 0.0 { TARGET_SPEECH_RMS / speech_level } else { MAX_GAIN };
+    // Never push the loudest peak past full scale
+    if peak > 0.0 {
+        gain = gain.min(CLIP_LEVEL / peak);
+    }
+    gain = gain.clamp(MIN_GAIN, MAX_GAIN);
+
+    if clipped_ratio > 0.001 {
+        warnings.push("Input is clipping - lower the device volume in your OS sound settings".to_string());
+    }
+    if speech_level < noise_floor * 2.0 {
+        warnings.push("Speech is barely louder than the background noise - move closer or reduce noise".to_string());
+    }
+    if speech_level < 0.003 {
+        warnings.push("Almost no signal was captured - check the device is the right one and not muted".to_string());
+    }
+
+    // Threshold between noise floor and speech (geometric mean), at least 2x the floor
+    let vad_threshold = ((noise_floor * speech_level).sqrt().max(noise_floor * 2.0) * gain).clamp(0.001, 0.05);
+
+    SourceCalibration {
+        device: device.to_string(),
+        noise_floor,
+        speech_level,
+        peak,
+        clipped_ratio,
+        gain,
+        vad_threshold,
+        warnings,
+        calibrated_at: chrono::Utc::now().timestamp_millis() as u64,
+    }
+}
+
+fn source_config(device: &cpal::Device, source: LevelSource) -> Result<cpal::SupportedStreamConfig, String> {
+    match source {
+        LevelSource::Microphone => device.default_input_config()
+            .map_err(|e| format!("Failed to get device config: {}", e)),
+        LevelSource::SystemAudio => crate::device_selection::system_audio_config(device),
+    }
+}
+
+fn record_source(app: &AppHandle, source: LevelSource, seconds: u32) -> Result<SourceCalibration, String> {
+    let host = cpal::default_host();
+    let device = match source {
+        LevelSource::Microphone => crate::device_selection::select_input_device(&host),
+        LevelSource::SystemAudio => crate::device_selection::system_audio_device(&host),
+    }.ok_or_else(|| match source {
+        LevelSource::Microphone => "No input device available".to_string(),
+        LevelSource::SystemAudio => "No system audio (loopback/monitor) device available".to_string(),
+    })?;
+    let device_name = device.name().unwrap_or_default();
+    let supported = source_config(&device, source)?;
+    let channels = supported.channels() as usize;
+    let frame_samples = (supported.sample_rate().0 * FRAME_MS / 1000) as usize;
+    let sample_format = supported.sample_format();
+    let config: cpal::StreamConfig = supported.into();
+    info!("🎚️ LED 8700: Level calibration of {:?} on '{}' for {}s", source, device_name, seconds);
+
+    // (per-frame RMS, peak, clipped samples, total samples, partial frame)
+    let stats = Arc::new(Mutex::new((Vec::<f32>::new(), 0.0f32, 0usize, 0usize, Vec::<f32>::new())));
+    let stats_clone = stats.clone();
+    let stream = crate::sample_format::build_input_stream(
+        &device,
+        &config,
+        sample_format,
+        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+            let mono = if channels > 1 { crate::device_conflict::downmix_to_mono(data, channels) } else { data.to_vec() };
+            let mut guard = stats_clone.lock().unwrap();
+            let (frames, peak, clipped, total, pending) = &mut *guard;
+            for &sample in &mono {
+                let level = sample.abs();
+                *peak = peak.max(level);
+                if level >= CLIP_LEVEL {
+                    *clipped += 1;
+                }
+                *total += 1;
+                pending.push(sample);
+                if pending.len() >= frame_samples {
+                    frames.push((pending.iter().map(|s| s * s).sum::<f32>() / pending.len() as f32).sqrt());
+                    pending.clear();
+                }
+            }
+        },
+        |err| error!("❌ LED 8702: Level calibration stream error: {:?}", err),
+    ).map_err(|e| format!("Failed to open '{}': {}", device_name, e))?;
+    stream.play().map_err(|e| format!("Failed to start stream: {}", e))?;
+
+    let total_ms = seconds as u64 * 1000;
+    let started = Instant::now();
+    while (started.elapsed().as_millis() as u64) < total_ms {
+        std::thread::sleep(Duration::from_millis(PROGRESS_INTERVAL_MS));
+        let rms = stats.lock().unwrap().0.last().copied().unwrap_or(0.0);
+        let _ = app.emit_all("level_calibration_progress", LevelProgress {
+            source,
+            elapsed_ms: (started.elapsed().as_millis() as u64).min(total_ms),
+            total_ms,
+            rms,
+        });
+    }
+    drop(stream);
+
+    let (frames, peak, clipped, total, _) = stats.lock().unwrap().clone();
+    if frames.is_empty() {
+        return Err(format!("No audio received from '{}'", device_name));
+    }
+    let result = recommend(&device_name, &frames, peak, clipped as f32 / total.max(1) as f32);
+    info!("✅ LED 8701: {:?} calibrated: gain {:.2}, VAD threshold {:.4} (noise {:.4}, speech {:.4})",
+          source, result.gain, result.vad_threshold, result.noise_floor, result.speech_level);
+    if !result.warnings.is_empty() {
+        warn!("⚠️ Level calibration warnings: {:?}", result.warnings);
+    }
+    Ok(result)
+}
+
+/// Stored mic calibration, if the wizard has been run
+pub fn microphone_calibration() -> Option<SourceCalibration> {
+    crate::preferences::load().level_calibration.microphone
+}
+
+// ========== Tauri Commands ==========
+
+// One wizard step: record `seconds` (default 10) of the source, then persist the result
+#[tauri::command]
+pub async fn calibrate_source_levels(app: AppHandle, source: LevelSource, seconds: Option<u32>) -> Result<SourceCalibration, String> {
+    if crate::privacy::is_muted() {
+        return Err("Capture is muted (privacy mode)".to_string());
+    }
+    let seconds = seconds.unwrap_or(DEFAULT_SECONDS).clamp(3, 30);
+    let result = tokio::task::spawn_blocking(move || record_source(&app, source, seconds))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let stored = result.clone();
+    crate::preferences::update(|p| match source {
+        LevelSource::Microphone => p.level_calibration.microphone = Some(stored),
+        LevelSource::SystemAudio => p.level_calibration.system_audio = Some(stored),
+    }).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_level_calibration() -> Result<LevelCalibration, String> {
+    Ok(crate::preferences::load().level_calibration)
+}
+
+#[tauri::command]
+pub fn reset_level_calibration() -> Result<LevelCalibration, String> {
+    crate::preferences::update(|p| p.level_calibration = LevelCalibration::default())
+        .map(|p| p.level_calibration)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_mic_gets_boosted_and_threshold_sits_above_noise() {
+        // 30% background at 0.002, 70% speech around 0.02
+        let frames: Vec<f32> = (0..500).map(|i| if i % 10 < 3 { 0.002 } else { 0.02 }).collect();
+        let result = recommend("Test Mic", &frames, 0.08, 0.0);
+
+        assert!((result.gain - 5.0).abs() < 0.01);
+        assert!(result.vad_threshold > result.noise_floor * result.gain);
+        assert!(result.vad_threshold < result.speech_level * result.gain);
+        assert!(result.warnings.is_empty());
+    }
+}
@@ -0,0 +1,553 @@
+// License - trial period, key activation and paid-tier feature gating
+// A fresh install gets an offline trial with every feature enabled. After that
+// the app keeps working locally (Vosk, Ollama, knowledge base) and the cloud /
+// integration features need an activated license. Activation binds a key to a
+// machine fingerprint on the licensing server; the result is re-validated daily
+// and a grace period keeps paid features working while the server is unreachable.
+// The key itself lives in the credentials store, the rest in its own JSON file.
+// That file is writable by the user, so nothing in it is taken on trust: the tier,
+// expiry, machine and validation time of an activation are claims signed by the
+// licensing server (its public key is baked into the build via
+// VOICECOACH_LICENSE_PUBKEY), and so is the trial start the server recorded for the
+// machine, which replaces the local one once fetched - deleting the file doesn't
+// start a new trial. Claims dated in the future and activations whose key is no
+// longer stored are not honored.
+
+use anyhow::{Result, Context, anyhow, bail};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn, error};
+
+const LICENSE_FILE: &str = "voicecoach_license.json";
+const LICENSE_KEY_NAME: &str = "license_key";  // Entry in the credentials store
+const DEFAULT_LICENSE_SERVER: &str = "https://licensing.voicecoach.app/v1";
+// Set by release builds; without it no activation can be verified
+const LICENSE_SERVER_PUBLIC_KEY: Option<&str> = option_env!("VOICECOACH_LICENSE_PUBKEY");
+
+const TRIAL_DAYS: i64 = 14;
+const GRACE_DAYS: i64 = 7;          // Offline allowance after the last successful validation
+const REVALIDATE_HOURS: i64 = 24;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const CLOCK_SKEW_MS: i64 = 5 * 60 * 1000;  // Allowed lead of the server's clock over ours
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Tier {
+    Free,
+    Pro,
+    Team,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    CloudEngines,  // Deepgram and other hosted transcription
+    LiveDocs,      // Streaming calls to Google Docs / Notion
+    CrmSync,
+}
+
+impl Feature {
+    const ALL: [Feature; 3] = [Feature::CloudEngines, Feature::LiveDocs, Feature::CrmSync];
+
+    fn name(self) -> &'static str {
+        match self {
+            Feature::CloudEngines => "Cloud transcription engines",
+            Feature::LiveDocs => "Live document streaming",
+            Feature::CrmSync => "CRM sync",
+        }
+    }
+}
+
+impl Tier {
+    fn includes(self, _feature: Feature) -> bool {
+        // Every paid tier currently unlocks everything; Team adds seats server-side
+        !matches!(self, Tier::Free)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseState {
+    Trial,
+    TrialExpired,
+    Active,
+    Grace,     // Server unreachable, last validation older than REVALIDATE_HOURS
+    Expired,   // Subscription ended or grace period used up
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Activation {
+    pub key_hint: String,  // Last 4 characters, for display
+    pub activated_at: i64,
+    #[serde(default)]
+    pub claims: String,     // base64 ActivationClaims as signed by the licensing server
+    #[serde(default)]
+    pub signature: String,  // base64 ed25519 signature over the claims
+}
+
+// What the licensing server vouches for when it activates or re-validates a key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ActivationClaims {
+    tier: Tier,
+    #[serde(default)]
+    licensee: Option<String>,
+    #[serde(default)]
+    expires_at: Option<i64>,  // ms; None = perpetual
+    machine_id: String,
+    validated_at: i64,
+}
+
+// What it vouches for when asked about a machine's trial
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrialClaims {
+    machine_id: String,
+    trial_started_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LicenseRecord {
+    trial_started_at: i64,
+    machine_id: String,
+    #[serde(default)]
+    activation: Option<Activation>,
+    #[serde(default)]
+    trial_confirmed: bool,  // trial_started_at came from the server's signed claims
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct LicenseStatus {
+    pub state: LicenseState,
+    pub tier: Tier,
+    pub features: Vec<Feature>,  // Currently enabled gated features
+    pub trial_days_left: Option<i64>,
+    pub grace_days_left: Option<i64>,
+    pub expires_at: Option<i64>,
+    pub licensee: Option<String>,
+    pub key_hint: Option<String>,
+    pub machine_id: String,
+    pub message: Option<String>,
+}
+
+// Request/response of the licensing server (/activate, /validate and /trial)
+#[derive(Debug, Serialize)]
+struct ServerRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license_key: Option<&'a str>,
+    machine_id: &'a str,
+    app_version: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerResponse {
+    valid: bool,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    claims: Option<String>,     // base64 ActivationClaims (TrialClaims from /trial)
+    #[serde(default)]
+    signature: Option<String>,
+}
+
+static LICENSE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+// Last server message (e.g. why a key was rejected), shown in the status
+static LAST_MESSAGE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+fn license_path() -> PathBuf {
+    crate::app_paths::data_dir().join(LICENSE_FILE)
+}
+
+fn license_server() -> String {
+    std::env::var("VOICECOACH_LICENSE_SERVER").unwrap_or_else(|_| DEFAULT_LICENSE_SERVER.to_string())
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Stable per-machine id: hash of OS machine id, host name and user
+pub fn machine_fingerprint() -> String {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"].iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let host = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .or_else(|_| fs::read_to_string("/etc/hostname"))
+        .unwrap_or_default();
+    let user = std::env::var("USERNAME").or_else(|_| std::env::var("USER")).unwrap_or_default();
+    let source = format!("{}|{}|{}|{}|{}", machine_id.trim(), host.trim(), user, std::env::consts::OS, std::env::consts::ARCH);
+    crate::credentials::fingerprint(source.as_bytes())
+}
+
+fn server_key() -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = BASE64.decode(LICENSE_SERVER_PUBLIC_KEY?.trim()).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+/// Decode `claims` once the server's signature over them checks out
+fn verify_claims<T: DeserializeOwned>(claims: &str, signature: &str, server_key: Option<&VerifyingKey>) -> Result<T> {
+    let server_key = server_key.ok_or_else(|| anyhow!("This build has no licensing server key"))?;
+    let claims = BASE64.decode(claims.trim())?;
+    let signature = Signature::from_slice(&BASE64.decode(signature.trim())?)?;
+    server_key.verify(&claims, &signature)
+        .map_err(|_| anyhow!("License signature is invalid"))?;
+    Ok(serde_json::from_slice(&claims)?)
+}
+
+/// The claims of `activation` if the server signed them for this machine, dated no later than `now`
+fn verified_activation(activation: &Activation, machine_id: &str, now: i64, server_key: Option<&VerifyingKey>) -> Result<ActivationClaims> {
+    let claims: ActivationClaims = verify_claims(&activation.claims, &activation.signature, server_key)?;
+    if claims.machine_id != machine_id {
+        bail!("License was activated for a different machine");
+    }
+    if claims.validated_at > now + CLOCK_SKEW_MS {
+        bail!("License validation is dated in the future");
+    }
+    Ok(claims)
+}
+
+fn read_from_disk() -> Option<LicenseRecord> {
+    let contents = fs::read_to_string(license_path()).ok()?;
+    serde_json::from_str(&contents).map_err(|e| warn!("⚠️ License file is invalid: {}", e)).ok()
+}
+
+fn write_to_disk(record: &LicenseRecord) -> Result<()> {
+    let path = license_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(record)?)
+        .context(format!("Failed to write license file: {:?}", path))
+}
+
+/// Load the license record, starting the trial on first run. An activation copied
+/// from another machine is dropped (the trial start is kept).
+fn load_record() -> LicenseRecord {
+    let _guard = LICENSE_LOCK.lock().unwrap();
+    let machine_id = machine_fingerprint();
+    let (mut record, mut changed) = match read_from_disk() {
+        Some(record) => (record, false),
+        None => {
+            info!("🎟️ LED 9000: Starting {}-day trial", TRIAL_DAYS);
+            (LicenseRecord { trial_started_at: now_ms(), machine_id: machine_id.clone(), activation: None, trial_confirmed: false }, true)
+        }
+    };
+    if record.machine_id != machine_id {
+        warn!("⚠️ LED 9001: License was activated on a different machine, re-activation required");
+        record.machine_id = machine_id;
+        record.activation = None;
+        record.trial_confirmed = false;
+        changed = true;
+    }
+    if changed {
+        if let Err(e) = write_to_disk(&record) {
+            error!("❌ LED 9002: Failed to save license state: {}", e);
+        }
+    }
+    record
+}
+
+fn save_activation(activation: Option<Activation>) -> Result<()> {
+    let mut record = load_record();
+    let _guard = LICENSE_LOCK.lock().unwrap();
+    record.activation = activation;
+    write_to_disk(&record)
+}
+
+fn save_trial_start(trial_started_at: i64) -> Result<()> {
+    let mut record = load_record();
+    let _guard = LICENSE_LOCK.lock().unwrap();
+    record.trial_started_at = trial_started_at;
+    record.trial_confirmed = true;
+    write_to_disk(&record)
+}
+
+fn days_left(until_ms: i64, now: i64) -> i64 {
+    ((until_ms - now) as f64 / DAY_MS as f64).ceil().max(0.0) as i64
+}
+
+/// Pure evaluation of a record at `now` (no I/O); activations not signed by
+/// `server_key` count as no activation
+fn evaluate(record: &LicenseRecord, now: i64, server_key: Option<&VerifyingKey>) -> LicenseStatus {
+    let trial_end = record.trial_started_at + TRIAL_DAYS * DAY_MS;
+    // A trial that "starts" in the future is a moved clock or an edited file
+    let trial_valid = record.trial_started_at <= now + CLOCK_SKEW_MS;
+    let mut message = LAST_MESSAGE.lock().unwrap().clone();
+    let activation = record.activation.as_ref().and_then(|a| {
+        match verified_activation(a, &record.machine_id, now, server_key) {
+            Ok(claims) => Some((a, claims)),
+            Err(e) => {
+                message = Some(format!("License activation could not be verified ({}). Activate your license key again.", e));
+                None
+            }
+        }
+    });
+
+    let (state, tier, grace_days_left) = match &activation {
+        Some((_, c)) if c.expires_at.map_or(false, |expires| expires <= now) => (LicenseState::Expired, Tier::Free, None),
+        Some((_, c)) if now - c.validated_at <= REVALIDATE_HOURS * 60 * 60 * 1000 => (LicenseState::Active, c.tier, None),
+        Some((_, c)) => {
+            let grace_end = c.validated_at + (GRACE_DAYS * DAY_MS);
+            if now < grace_end {
+                (LicenseState::Grace, c.tier, Some(days_left(grace_end, now)))
+            } else {
+                (LicenseState::Expired, Tier::Free, None)
+            }
+        }
+        None if trial_valid && now < trial_end => (LicenseState::Trial, Tier::Free, None),
+        None => (LicenseState::TrialExpired, Tier::Free, None),
+    };
+
+    let features = Feature::ALL.iter().copied()
+        .filter(|f| state == LicenseState::Trial || tier.includes(*f))
+        .collect();
+
+    LicenseStatus {
+        state,
+        tier,
+        features,
+        trial_days_left: activation.is_none().then(|| if trial_valid { days_left(trial_end, now) } else { 0 }),
+        grace_days_left,
+        expires_at: activation.as_ref().and_then(|(_, c)| c.expires_at),
+        licensee: activation.as_ref().and_then(|(_, c)| c.licensee.clone()),
+        key_hint: activation.as_ref().map(|(a, _)| a.key_hint.clone()),
+        machine_id: record.machine_id.clone(),
+        message,
+    }
+}
+
+/// Current status without contacting the server
+pub fn status() -> LicenseStatus {
+    let mut record = load_record();
+    // An activation is only honored while the key it was made with is stored
+    if record.activation.is_some() && crate::credentials::integration_token(LICENSE_KEY_NAME).is_none() {
+        record.activation = None;
+    }
+    evaluate(&record, now_ms(), server_key().as_ref())
+}
+
+/// Err with a user-facing message unless `feature` is enabled by the trial or license
+pub fn require_feature(feature: Feature) -> Result<(), String> {
+    let status = status();
+    if status.features.contains(&feature) {
+        return Ok(());
+    }
+    let reason = match status.state {
+        LicenseState::TrialExpired => "the trial has ended",
+        LicenseState::Expired => "the license has expired",
+        _ => "it is not included in your plan",
+    };
+    Err(format!("{} requires a paid license ({}). Activate a license key in Settings.", feature.name(), reason))
+}
+
+async fn call_server(endpoint: &str, license_key: Option<&str>, machine_id: &str) -> Result<ServerResponse> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/{}", license_server(), endpoint))
+        .timeout(std::time::Duration::from_secs(15))
+        .json(&ServerRequest { license_key, machine_id, app_version: env!("CARGO_PKG_VERSION") })
+        .send()
+        .await?;
+    if response.status().is_server_error() {
+        bail!("Licensing server error {}", response.status());
+    }
+    Ok(response.json().await?)
+}
+
+/// The activation the server granted, once its signed claims check out for this machine
+fn activation_from(response: &ServerResponse, license_key: &str, machine_id: &str, activated_at: i64) -> Result<Activation> {
+    if !response.valid {
+        return Err(anyhow!(response.message.clone().unwrap_or_else(|| "License key was rejected".to_string())));
+    }
+    let key_hint: String = license_key.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    let activation = Activation {
+        key_hint,
+        activated_at,
+        claims: response.claims.clone().unwrap_or_default(),
+        signature: response.signature.clone().unwrap_or_default(),
+    };
+    verified_activation(&activation, machine_id, now_ms(), server_key().as_ref())
+        .context("Licensing server response could not be verified")?;
+    Ok(activation)
+}
+
+/// Replace the local trial start with the one the server recorded for this machine
+/// (once; network failures leave the local one until the next status check)
+async fn confirm_trial_if_needed() {
+    let record = load_record();
+    if record.trial_confirmed || record.activation.is_some() {
+        return;
+    }
+    let response = match call_server("trial", None, &record.machine_id).await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("⚠️ LED 9004: Trial confirmation unavailable: {}", e);
+            return;
+        }
+    };
+    let claims = verify_claims::<TrialClaims>(
+        response.claims.as_deref().unwrap_or_default(),
+        response.signature.as_deref().unwrap_or_default(),
+        server_key().as_ref(),
+    );
+    match claims {
+        Ok(claims) if claims.machine_id == record.machine_id && claims.trial_started_at <= now_ms() + CLOCK_SKEW_MS => {
+            if let Err(e) = save_trial_start(claims.trial_started_at) {
+                error!("❌ LED 9002: Failed to save license state: {}", e);
+            }
+        }
+        Ok(_) => warn!("⚠️ LED 9003: Licensing server sent a trial for another machine or time"),
+        Err(e) => warn!("⚠️ LED 9003: Trial confirmation could not be verified: {}", e),
+    }
+}
+
+/// Re-validate an activation older than REVALIDATE_HOURS (or one that doesn't verify).
+/// Network failures are ignored (grace period applies); an explicit rejection, or no
+/// stored key to re-validate with, removes the activation.
+async fn revalidate_if_due() {
+    let record = load_record();
+    let now = now_ms();
+    let activation = match record.activation.clone() {
+        Some(activation) => activation,
+        None => return,
+    };
+    let due = verified_activation(&activation, &record.machine_id, now, server_key().as_ref())
+        .map_or(true, |claims| now - claims.validated_at > REVALIDATE_HOURS * 60 * 60 * 1000);
+    if !due {
+        return;
+    }
+    let key = match crate::credentials::integration_token(LICENSE_KEY_NAME) {
+        Some(key) => key,
+        None => {
+            warn!("⚠️ LED 9003: License key is no longer stored, removing the activation");
+            if let Err(e) = save_activation(None) {
+                error!("❌ LED 9002: Failed to save license state: {}", e);
+            }
+            return;
+        }
+    };
+
+    match call_server("validate", Some(&key), &record.machine_id).await {
+        Ok(response) => {
+            let result = activation_from(&response, &key, &record.machine_id, activation.activated_at);
+            *LAST_MESSAGE.lock().unwrap() = response.message.clone();
+            let saved = match result {
+                Ok(renewed) => save_activation(Some(renewed)),
+                Err(e) => {
+                    warn!("⚠️ LED 9003: License no longer valid: {}", e);
+                    *LAST_MESSAGE.lock().unwrap() = Some(e.to_string());
+                    save_activation(None)
+                }
+            };
+            if let Err(e) = saved {
+                error!("❌ LED 9002: Failed to save license state: {}", e);
+            }
+        }
+        Err(e) => warn!("⚠️ LED 9004: License validation unavailable, using grace period: {}", e),
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub async fn activate_license(license_key: String) -> Result<LicenseStatus, String> {
+    let license_key = license_key.trim().to_string();
+    if license_key.is_empty() {
+        return Err("License key is required".to_string());
+    }
+    let machine_id = load_record().machine_id;
+    let response = call_server("activate", Some(&license_key), &machine_id).await
+        .map_err(|e| format!("Could not reach the licensing server: {}", e))?;
+    *LAST_MESSAGE.lock().unwrap() = response.message.clone();
+
+    let activation = activation_from(&response, &license_key, &machine_id, now_ms()).map_err(|e| e.to_string())?;
+    let key_hint = activation.key_hint.clone();
+    crate::credentials::set_integration_token(LICENSE_KEY_NAME, Some(license_key))
+        .map_err(|e| e.to_string())?;
+    save_activation(Some(activation)).map_err(|e| e.to_string())?;
+    let status = status();
+    info!("🎟️ LED 9005: License activated ({:?}, key …{})", status.tier, key_hint);
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_license_status() -> Result<LicenseStatus, String> {
+    confirm_trial_if_needed().await;
+    revalidate_if_due().await;
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn server() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn record(activation: Option<Activation>) -> LicenseRecord {
+        LicenseRecord { trial_started_at: 0, machine_id: "m".to_string(), activation, trial_confirmed: true }
+    }
+
+    fn signed(key: &SigningKey, machine_id: &str, validated_at: i64) -> Activation {
+        let claims = serde_json::to_vec(&ActivationClaims {
+            tier: Tier::Pro,
+            licensee: None,
+            expires_at: None,
+            machine_id: machine_id.to_string(),
+            validated_at,
+        }).unwrap();
+        Activation {
+            key_hint: "ABCD".to_string(),
+            activated_at: validated_at,
+            claims: BASE64.encode(&claims),
+            signature: BASE64.encode(key.sign(&claims).to_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_trial_then_activation_grace_and_expiry() {
+        let key = server().verifying_key();
+        let trial = evaluate(&record(None), 3 * DAY_MS, Some(&key));
+        assert_eq!(trial.state, LicenseState::Trial);
+        assert_eq!(trial.trial_days_left, Some(TRIAL_DAYS - 3));
+        assert_eq!(trial.features.len(), Feature::ALL.len());
+
+        let ended = evaluate(&record(None), (TRIAL_DAYS + 1) * DAY_MS, Some(&key));
+        assert_eq!(ended.state, LicenseState::TrialExpired);
+        assert!(ended.features.is_empty());
+
+        let paid = record(Some(signed(&server(), "m", 20 * DAY_MS)));
+        assert_eq!(evaluate(&paid, 20 * DAY_MS + 1, Some(&key)).state, LicenseState::Active);
+        let offline = evaluate(&paid, 23 * DAY_MS, Some(&key));
+        assert_eq!(offline.state, LicenseState::Grace);
+        assert!(offline.features.contains(&Feature::CloudEngines));
+        assert_eq!(evaluate(&paid, (21 + GRACE_DAYS) * DAY_MS, Some(&key)).state, LicenseState::Expired);
+    }
+
+    #[test]
+    fn test_forged_activations_and_future_dates_are_not_honored() {
+        let key = server().verifying_key();
+        let now = 20 * DAY_MS;
+        let forged = record(Some(signed(&SigningKey::from_bytes(&[8; 32]), "m", now)));
+        assert_eq!(evaluate(&forged, now, Some(&key)).state, LicenseState::TrialExpired);
+        let copied = record(Some(signed(&server(), "other-machine", now)));
+        assert_eq!(evaluate(&copied, now, Some(&key)).state, LicenseState::TrialExpired);
+        let future = record(Some(signed(&server(), "m", now + 365 * DAY_MS)));
+        assert_eq!(evaluate(&future, now, Some(&key)).state, LicenseState::TrialExpired);
+        // Without the server's key (a build without VOICECOACH_LICENSE_PUBKEY) nothing verifies
+        let genuine = record(Some(signed(&server(), "m", now)));
+        assert_eq!(evaluate(&genuine, now, None).state, LicenseState::TrialExpired);
+
+        let mut moved_trial = record(None);
+        moved_trial.trial_started_at = now + 30 * DAY_MS;
+        let status = evaluate(&moved_trial, now, Some(&key));
+        assert_eq!(status.state, LicenseState::TrialExpired);
+        assert_eq!(status.trial_days_left, Some(0));
+    }
+}
@@ -0,0 +1,293 @@
+// Live Document Streaming - append the call to a shared Google Doc or Notion page
+// Final transcript segments and coaching notes are queued from the transcription
+// threads and flushed in batches (~10s) by a background task, so a colleague can
+// follow a call remotely without screen sharing. Access tokens live in the
+// credentials store; only the document target is kept in preferences.
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn, error};
+
+const DEFAULT_BATCH_SECONDS: u64 = 10;
+// Lines kept while the document is unreachable; oldest are dropped beyond this
+const MAX_QUEUED_LINES: usize = 500;
+// Notion limits: 2000 chars per rich text object, 100 blocks per append
+const NOTION_MAX_TEXT: usize = 2000;
+const NOTION_MAX_BLOCKS: usize = 100;
+const NOTION_VERSION: &str = "2022-06-28";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveDocProvider {
+    GoogleDocs,
+    Notion,
+}
+
+impl LiveDocProvider {
+    fn token_name(self) -> &'static str {
+        match self {
+            LiveDocProvider::GoogleDocs => "google_docs",
+            LiveDocProvider::Notion => "notion",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LiveDocSettings {
+    pub provider: LiveDocProvider,
+    /// Google Doc ID, or the Notion page/block ID to append under
+    pub document_id: String,
+    #[serde(default = "default_batch_seconds")]
+    pub batch_seconds: u64,
+    #[serde(default = "default_true")]
+    pub include_coaching: bool,
+}
+
+fn default_batch_seconds() -> u64 { DEFAULT_BATCH_SECONDS }
+fn default_true() -> bool { true }
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct LiveDocStatus {
+    pub configured: bool,
+    pub streaming: bool,
+    pub provider: Option<LiveDocProvider>,
+    pub document_id: Option<String>,
+    pub queued_lines: usize,
+    pub sent_lines: u32,
+    pub last_error: Option<String>,
+}
+
+static STREAMING: AtomicBool = AtomicBool::new(false);
+static SESSION: AtomicU32 = AtomicU32::new(0);
+static SENT_LINES: AtomicU32 = AtomicU32::new(0);
+static QUEUE: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static LAST_ERROR: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+static INCLUDE_COACHING: AtomicBool = AtomicBool::new(true);
+
+fn enqueue(line: String) {
+    if !STREAMING.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut queue = QUEUE.lock().unwrap();
+    queue.push(line);
+    if queue.len() > MAX_QUEUED_LINES {
+        let excess = queue.len() - MAX_QUEUED_LINES;
+        queue.drain(..excess);
+    }
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%H:%M:%S").to_string()
+}
+
+/// Size of the pending lines (memory budget)
+pub fn queue_bytes() -> usize {
+    QUEUE.lock().unwrap().iter().map(|line| line.len()).sum()
+}
+
+/// Drop the oldest pending lines until the queue fits `max_bytes`; returns bytes freed
+pub fn trim_queue(max_bytes: usize) -> usize {
+    let mut queue = QUEUE.lock().unwrap();
+    let mut total: usize = queue.iter().map(|line| line.len()).sum();
+    let mut excess = 0;
+    while total > max_bytes && excess < queue.len() {
+        total -= queue[excess].len();
+        excess += 1;
+    }
+    let freed = queue.drain(..excess).map(|line| line.len()).sum();
+    if excess > 0 {
+        warn!("⚠️ LED 8803: Dropped {} queued live doc lines (memory budget)", excess);
+    }
+    freed
+}
+
+/// Queue a final transcript segment (no-op unless streaming)
+pub fn queue_transcript(text: &str, is_user: bool) {
+    queue_labeled_transcript(if is_user { "You" } else { "Prospect" }, text);
+}
+
+/// Queue a final segment from a named/diarized speaker ("Prospect B", "Dana")
+pub fn queue_labeled_transcript(speaker: &str, text: &str) {
+    enqueue(format!("[{}] {}: {}", timestamp(), speaker, text.trim()));
+}
+
+/// Queue a coaching note (no-op unless streaming with include_coaching)
+pub fn queue_coaching_note(text: &str) {
+    if INCLUDE_COACHING.load(Ordering::Relaxed) {
+        enqueue(format!("[{}] 💡 Coach: {}", timestamp(), text.trim()));
+    }
+}
+
+fn access_token(provider: LiveDocProvider) -> Result<String> {
+    crate::credentials::integration_token(provider.token_name())
+        .ok_or_else(|| anyhow!("No access token configured for {:?}", provider))
+}
+
+async fn append_google_doc(client: &reqwest::Client, token: &str, document_id: &str, lines: &[String]) -> Result<()> {
+    let mut text = lines.join("\n");
+    text.push('\n');
+    let body = serde_json::json!({
+        "requests": [{ "insertText": { "endOfSegmentLocation": {}, "text": text } }]
+    });
+    let response = client
+        .post(format!("https://docs.googleapis.com/v1/documents/{}:batchUpdate", document_id))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        bail!("Google Docs returned {}: {}", response.status(), response.text().await.unwrap_or_default());
+    }
+    Ok(())
+}
+
+fn notion_paragraph(line: &str) -> serde_json::Value {
+    let content: String = line.chars().take(NOTION_MAX_TEXT).collect();
+    serde_json::json!({
+        "object": "block",
+        "type": "paragraph",
+        "paragraph": { "rich_text": [{ "type": "text", "text": { "content": content } }] }
+    })
+}
+
+async fn append_notion(client: &reqwest::Client, token: &str, block_id: &str, lines: &[String]) -> Result<()> {
+    for batch in lines.chunks(NOTION_MAX_BLOCKS) {
+        let children: Vec<_> = batch.iter().map(|line| notion_paragraph(line)).collect();
+        let response = client
+            .patch(format!("https://api.notion.com/v1/blocks/{}/children", block_id))
+            .bearer_auth(token)
+            .header("Notion-Version", NOTION_VERSION)
+            .json(&serde_json::json!({ "children": children }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!("Notion returned {}: {}", response.status(), response.text().await.unwrap_or_default());
+        }
+    }
+    Ok(())
+}
+
+async fn append_lines(client: &reqwest::Client, settings: &LiveDocSettings, lines: &[String]) -> Result<()> {
+    let token = access_token(settings.provider)?;
+    match settings.provider {
+        LiveDocProvider::GoogleDocs => append_google_doc(client, &token, &settings.document_id, lines).await,
+        LiveDocProvider::Notion => append_notion(client, &token, &settings.document_id, lines).await,
+    }
+}
+
+async fn flush(client: &reqwest::Client, settings: &LiveDocSettings) {
+    let lines: Vec<String> = std::mem::take(&mut *QUEUE.lock().unwrap());
+    if lines.is_empty() {
+        return;
+    }
+    match append_lines(client, settings, &lines).await {
+        Ok(()) => {
+            SENT_LINES.fetch_add(lines.len() as u32, Ordering::Relaxed);
+            *LAST_ERROR.lock().unwrap() = None;
+        }
+        Err(e) => {
+            error!("❌ LED 8802: Live doc append failed ({} lines requeued): {}", lines.len(), e);
+            *LAST_ERROR.lock().unwrap() = Some(e.to_string());
+            // Put the batch back in front of anything queued meanwhile
+            let mut queue = QUEUE.lock().unwrap();
+            let newer = std::mem::replace(&mut *queue, lines);
+            queue.extend(newer);
+            if queue.len() > MAX_QUEUED_LINES {
+                let excess = queue.len() - MAX_QUEUED_LINES;
+                queue.drain(..excess);
+            }
+        }
+    }
+}
+
+fn configured_settings() -> Result<LiveDocSettings> {
+    crate::preferences::load().live_doc
+        .ok_or_else(|| anyhow!("Live document streaming is not configured"))
+}
+
+fn status() -> LiveDocStatus {
+    let settings = crate::preferences::load().live_doc;
+    LiveDocStatus {
+        configured: settings.as_ref().map_or(false, |s| access_token(s.provider).is_ok()),
+        streaming: STREAMING.load(Ordering::Relaxed),
+        provider: settings.as_ref().map(|s| s.provider),
+        document_id: settings.map(|s| s.document_id),
+        queued_lines: QUEUE.lock().unwrap().len(),
+        sent_lines: SENT_LINES.load(Ordering::Relaxed),
+        last_error: LAST_ERROR.lock().unwrap().clone(),
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Save the target document; the token goes to the credentials store (None keeps the current one)
+#[tauri::command]
+pub fn configure_live_doc(settings: LiveDocSettings, access_token: Option<String>) -> Result<LiveDocStatus, String> {
+    if settings.document_id.trim().is_empty() {
+        return Err("A document ID is required".to_string());
+    }
+    if let Some(token) = access_token {
+        crate::credentials::set_integration_token(settings.provider.token_name(), Some(token))
+            .map_err(|e| e.to_string())?;
+    }
+    crate::preferences::update(|p| p.live_doc = Some(settings))
+        .map_err(|e| e.to_string())?;
+    Ok(status())
+}
+
+#[tauri::command]
+pub async fn start_live_doc_stream() -> Result<LiveDocStatus, String> {
+    crate::license::require_feature(crate::license::Feature::LiveDocs)?;
+    let settings = configured_settings().map_err(|e| e.to_string())?;
+    access_token(settings.provider).map_err(|e| e.to_string())?;
+
+    let session = SESSION.fetch_add(1, Ordering::SeqCst) + 1;
+    QUEUE.lock().unwrap().clear();
+    SENT_LINES.store(0, Ordering::Relaxed);
+    *LAST_ERROR.lock().unwrap() = None;
+    INCLUDE_COACHING.store(settings.include_coaching, Ordering::Relaxed);
+    STREAMING.store(true, Ordering::SeqCst);
+    enqueue(format!("— VoiceCoach call started {} —", chrono::Local::now().format("%Y-%m-%d %H:%M")));
+    info!("📝 LED 8800: Streaming call to {:?} document {} every {}s", settings.provider, settings.document_id, settings.batch_seconds);
+
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let interval = tokio::time::Duration::from_secs(settings.batch_seconds.max(2));
+        while STREAMING.load(Ordering::SeqCst) && SESSION.load(Ordering::SeqCst) == session {
+            tokio::time::sleep(interval).await;
+            flush(&client, &settings).await;
+        }
+        // Final flush so the tail of the call isn't lost
+        if SESSION.load(Ordering::SeqCst) == session {
+            flush(&client, &settings).await;
+            info!("📝 LED 8801: Live doc stream stopped ({} lines sent)", SENT_LINES.load(Ordering::Relaxed));
+        }
+    });
+    Ok(status())
+}
+
+#[tauri::command]
+pub fn stop_live_doc_stream() -> Result<LiveDocStatus, String> {
+    if STREAMING.swap(false, Ordering::SeqCst) {
+        enqueue_final_marker();
+    }
+    Ok(status())
+}
+
+fn enqueue_final_marker() {
+    let mut queue = QUEUE.lock().unwrap();
+    queue.push(format!("— Call ended {} —", chrono::Local::now().format("%H:%M")));
+    if queue.len() > MAX_QUEUED_LINES {
+        warn!("⚠️ Live doc queue over capacity at stop, dropping oldest lines");
+        let excess = queue.len() - MAX_QUEUED_LINES;
+        queue.drain(..excess);
+    }
+}
+
+#[tauri::command]
+pub fn get_live_doc_status() -> Result<LiveDocStatus, String> {
+    Ok(status())
+}
@@ -0,0 +1,375 @@
+// Live Listen - read-only remote view of the rep's call for a coach/manager
+// Opt-in per call with explicit rep consent. A session token is generated and a
+// WebSocket endpoint is opened on the local network (or the rep connects out to
+// a relay server); listeners presenting the token receive final transcripts and
+// coaching events. Listeners cannot control anything - they may send a private
+// suggestion, which is shown on the rep's overlay only, and ask to be caught up on a
+// call they joined late (the reply goes to the asking socket only).
+
+use futures_util::{SinkExt, StreamExt};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+use log::{info, warn, error};
+
+const DEFAULT_PORT: u16 = 47821;
+const EVENT_BUFFER: usize = 256;
+const MAX_SUGGESTION_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct LiveListenStatus {
+    pub active: bool,
+    pub join_url: Option<String>,   // ws://<lan-ip>:<port>/?token=...
+    pub token: Option<String>,
+    pub relay_url: Option<String>,
+    pub relay_connected: bool,
+    pub listeners: u32,
+    pub started_at: Option<u64>,
+}
+
+// Payload of "manager_suggestion"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ManagerSuggestion {
+    pub text: String,
+    pub from: Option<String>,
+    pub received_at: u64,
+}
+
+// The messages listeners may send
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ListenerMessage {
+    Suggestion { text: String, #[serde(default)] from: Option<String> },
+    CatchMeUp { #[serde(default)] exchanges: Option<u32> },
+}
+
+struct ListenSession {
+    token: String,
+    events: broadcast::Sender<String>,
+    join_url: Option<String>,
+    relay_url: Option<String>,
+    relay_connected: bool,
+    started_at: u64,
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+static SESSION: Lazy<Mutex<Option<ListenSession>>> = Lazy::new(|| Mutex::new(None));
+static LISTENERS: AtomicU32 = AtomicU32::new(0);
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+pub(crate) fn new_token() -> String {
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// LAN address other machines can reach (no packets are sent)
+fn local_ip() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+fn token_from_query(query: Option<&str>) -> Option<String> {
+    query?.split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .map(|token| token.to_string())
+}
+
+fn status() -> LiveListenStatus {
+    let session = SESSION.lock().unwrap();
+    LiveListenStatus {
+        active: session.is_some(),
+        join_url: session.as_ref().and_then(|s| s.join_url.clone()),
+        token: session.as_ref().map(|s| s.token.clone()),
+        relay_url: session.as_ref().and_then(|s| s.relay_url.clone()),
+        relay_connected: session.as_ref().map_or(false, |s| s.relay_connected),
+        listeners: LISTENERS.load(Ordering::Relaxed),
+        started_at: session.as_ref().map(|s| s.started_at),
+    }
+}
+
+fn emit_status(app: &AppHandle) {
+    if let Err(e) = app.emit_all("live_listen_status", status()) {
+        error!("❌ LED 9303: Failed to emit live_listen_status: {:?}", e);
+    }
+}
+
+fn publish(event: serde_json::Value) {
+    if let Some(session) = SESSION.lock().unwrap().as_ref() {
+        // No receivers is fine - nobody is listening yet
+        let _ = session.events.send(event.to_string());
+    }
+}
+
+/// Share a final transcript line with listeners (no-op unless a session is active)
+pub fn publish_transcript(text: &str, is_user: bool, speaker: Option<&str>) {
+    publish(serde_json::json!({
+        "type": "transcript",
+        "text": text,
+        "is_user": is_user,
+        "speaker": speaker,
+        "timestamp": now_ms(),
+    }));
+}
+
+/// Share a coaching suggestion with listeners (no-op unless a session is active)
+pub fn publish_coaching(suggestion: &crate::ollama_integration::CoachingSuggestion) {
+    publish(serde_json::json!({
+        "type": "coaching",
+        "suggestion": suggestion,
+        "timestamp": now_ms(),
+    }));
+}
+
+/// Act on a listener message; returns the reply for that listener, if any
+async fn handle_listener_message(app: &AppHandle, text: &str) -> Option<String> {
+    match serde_json::from_str::<ListenerMessage>(text) {
+        Ok(ListenerMessage::Suggestion { text, from }) => {
+            let text: String = text.trim().chars().take(MAX_SUGGESTION_CHARS).collect();
+            if text.is_empty() {
+                return None;
+            }
+            info!("📨 LED 9302: Private suggestion from {}", from.as_deref().unwrap_or("manager"));
+            let suggestion = ManagerSuggestion { text, from, received_at: now_ms() };
+            crate::whisper_channel::manager_cue();
+            if let Err(e) = app.emit_all("manager_suggestion", suggestion) {
+                error!("❌ LED 9303: Failed to emit manager_suggestion: {:?}", e);
+            }
+            None
+        }
+        Ok(ListenerMessage::CatchMeUp { exchanges }) => {
+            info!("📨 LED 9302: Listener asked to be caught up");
+            let reply = match crate::catch_up::catch_me_up(exchanges).await {
+                Ok(catch_up) => serde_json::json!({ "type": "catch_up", "catch_up": catch_up, "timestamp": now_ms() }),
+                Err(e) => serde_json::json!({ "type": "catch_up", "error": e, "timestamp": now_ms() }),
+            };
+            Some(reply.to_string())
+        }
+        Err(_) => {
+            warn!("⚠️ Ignoring unsupported live listen message (listeners are read-only)");
+            None
+        }
+    }
+}
+
+/// Pump events to one socket and suggestions back until either side closes
+async fn serve_socket<S>(app: AppHandle, socket: tokio_tungstenite::WebSocketStream<S>, mut events: broadcast::Receiver<String>)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let (mut sink, mut source) = socket.split();
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if sink.send(Message::Text(event)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("⚠️ Live listen socket lagged, {} events skipped", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    let _ = sink.send(Message::Close(None)).await;
+                    break;
+                }
+            },
+            incoming = source.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(reply) = handle_listener_message(&app, &text).await {
+                        if sink.send(Message::Text(reply)).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// WebSocket handshake that only lets in listeners presenting the session token
+async fn accept_listener(stream: tokio::net::TcpStream, token: &str)
+    -> Result<tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>, tokio_tungstenite::tungstenite::Error> {
+    // The handshake callback signature is fixed by tungstenite
+    #[allow(clippy::result_large_err)]
+    let check_token = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        // Reachable from the whole LAN: compared in constant time
+        let presented = token_from_query(request.uri().query());
+        if presented.map_or(false, |presented| crate::credentials::secrets_equal(&presented, token)) {
+            Ok(response)
+        } else {
+            let mut denied = ErrorResponse::new(Some("Invalid or missing session token".to_string()));
+            *denied.status_mut() = http::StatusCode::UNAUTHORIZED;
+            Err(denied)
+        }
+    };
+    tokio_tungstenite::accept_hdr_async(stream, check_token).await
+}
+
+async fn accept_listeners(app: AppHandle, listener: tokio::net::TcpListener, token: String, events: broadcast::Sender<String>) {
+    while let Ok((stream, peer)) = listener.accept().await {
+        let app = app.clone();
+        let token = token.clone();
+        let events = events.subscribe();
+        tokio::spawn(async move {
+            let socket = match accept_listener(stream, &token).await {
+                Ok(socket) => socket,
+                Err(e) => {
+                    warn!("⚠️ LED 9301: Rejected live listen connection from {}: {}", peer, e);
+                    return;
+                }
+            };
+            LISTENERS.fetch_add(1, Ordering::Relaxed);
+            info!("👂 LED 9300: Listener joined from {}", peer);
+            emit_status(&app);
+            serve_socket(app.clone(), socket, events).await;
+            LISTENERS.fetch_sub(1, Ordering::Relaxed);
+            info!("👂 Listener from {} left", peer);
+            emit_status(&app);
+        });
+    }
+}
+
+/// Rep side of the relay: one outbound socket the relay fans out to listeners
+async fn connect_relay(app: AppHandle, relay_url: String, token: String, events: broadcast::Receiver<String>) {
+    let separator = if relay_url.contains('?') { '&' } else { '?' };
+    let url = format!("{}{}role=rep&token={}", relay_url, separator, token);
+    match tokio_tungstenite::connect_async(url.as_str()).await {
+        Ok((socket, _)) => {
+            info!("👂 LED 9300: Connected to live listen relay {}", relay_url);
+            set_relay_connected(&app, true);
+            serve_socket(app.clone(), socket, events).await;
+            set_relay_connected(&app, false);
+        }
+        Err(e) => error!("❌ LED 9301: Failed to connect to relay {}: {}", relay_url, e),
+    }
+}
+
+fn set_relay_connected(app: &AppHandle, connected: bool) {
+    if let Some(session) = SESSION.lock().unwrap().as_mut() {
+        session.relay_connected = connected;
+    }
+    emit_status(app);
+}
+
+// ========== Tauri Commands ==========
+
+// Open a listen session for this call. `consent` must come from an explicit rep action.
+#[tauri::command]
+pub async fn start_live_listen(
+    app: AppHandle,
+    consent: bool,
+    port: Option<u16>,
+    relay_url: Option<String>,
+    lan: Option<bool>,
+) -> Result<LiveListenStatus, String> {
+    if !consent {
+        return Err("Live listen requires the rep's explicit consent".to_string());
+    }
+    if SESSION.lock().unwrap().is_some() {
+        return Ok(status());
+    }
+    let lan = lan.unwrap_or(relay_url.is_none());
+    if !lan && relay_url.is_none() {
+        return Err("Enable LAN listening or provide a relay URL".to_string());
+    }
+    if let Some(url) = &relay_url {
+        if !url.starts_with("ws://") && !url.starts_with("wss://") {
+            return Err("Relay URL must start with ws:// or wss://".to_string());
+        }
+    }
+
+    let token = new_token();
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    let mut tasks = Vec::new();
+    let mut join_url = None;
+
+    if lan {
+        let port = port.unwrap_or(DEFAULT_PORT);
+        let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await
+            .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+        let host = local_ip().map(|ip| ip.to_string()).unwrap_or_else(|| "localhost".to_string());
+        join_url = Some(format!("ws://{}:{}/?token={}", host, port, token));
+        tasks.push(tokio::spawn(accept_listeners(app.clone(), listener, token.clone(), events.clone())));
+    }
+    if let Some(url) = relay_url.clone() {
+        tasks.push(tokio::spawn(connect_relay(app.clone(), url, token.clone(), events.subscribe())));
+    }
+
+    LISTENERS.store(0, Ordering::Relaxed);
+    *SESSION.lock().unwrap() = Some(ListenSession {
+        token,
+        events,
+        join_url,
+        relay_url,
+        relay_connected: false,
+        started_at: now_ms(),
+        tasks,
+    });
+    info!("👂 LED 9300: Live listen session started (rep consented)");
+    emit_status(&app);
+    Ok(status())
+}
+
+// End the session: listeners are disconnected and the token stops working
+#[tauri::command]
+pub fn stop_live_listen(app: AppHandle) -> Result<LiveListenStatus, String> {
+    if let Some(session) = SESSION.lock().unwrap().take() {
+        for task in session.tasks {
+            task.abort();
+        }
+        // Dropping the sender closes every listener socket
+        drop(session.events);
+        info!("👂 Live listen session ended");
+    }
+    emit_status(&app);
+    Ok(status())
+}
+
+#[tauri::command]
+pub fn get_live_listen_status() -> Result<LiveListenStatus, String> {
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_is_read_from_query() {
+        assert_eq!(token_from_query(Some("role=manager&token=abc123")).as_deref(), Some("abc123"));
+        assert_eq!(token_from_query(Some("role=manager")), None);
+        assert_eq!(token_from_query(None), None);
+    }
+
+    #[test]
+    fn test_only_listeners_with_the_token_are_let_in() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let joins = |query: &'static str| runtime.block_on(async move {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("ws://{}/{}", listener.local_addr().unwrap(), query);
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                accept_listener(stream, "abc123").await.is_ok()
+            });
+            let client = tokio_tungstenite::connect_async(url).await.is_ok();
+            (server.await.unwrap(), client)
+        });
+        assert_eq!(joins("?role=manager&token=abc123"), (true, true));
+        assert_eq!(joins("?token=abc124"), (false, false));
+        assert_eq!(joins("?token=abc1234"), (false, false));
+        assert_eq!(joins(""), (false, false));
+    }
+}
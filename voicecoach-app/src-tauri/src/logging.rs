@@ -0,0 +1,210 @@
+// Logging - reloadable per-module log levels plus a size-rotated log file
+// Replaces the one-shot env_logger setup so audio issues can be diagnosed
+// without restarting the app with RUST_LOG. Existing log:: macros are bridged
+// into tracing, so no call sites need to change.
+
+use anyhow::{Result, Context};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+const LOG_FILE_NAME: &str = "voicecoach.log";
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_LOG_FILES: u32 = 5;
+const DEFAULT_LEVEL: &str = "info";
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LogConfig {
+    pub default_level: String,
+    /// Per-target overrides, e.g. "voicecoach::vosk_transcription" -> "debug"
+    pub modules: BTreeMap<String, String>,
+    pub log_file: Option<String>,
+}
+
+/// Size-based rotation: voicecoach.log -> voicecoach.log.1 -> ... -> voicecoach.log.N
+struct RollingLogFile {
+    path: PathBuf,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RollingLogFile {
+    fn open(path: PathBuf) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .context(format!("Failed to create log directory: {:?}", dir))?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)
+            .context(format!("Failed to open log file: {:?}", path))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self { path, file: Some(file), size })
+    }
+
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        PathBuf::from(format!("{}.{}", self.path.display(), index))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+
+        let _ = fs::remove_file(self.rotated_path(MAX_LOG_FILES));
+        for index in (1..MAX_LOG_FILES).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))?;
+
+        self.file = Some(OpenOptions::new().create(true).append(true).open(&self.path)?);
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RollingLogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size + buf.len() as u64 > MAX_LOG_BYTES && self.size > 0 {
+            self.rotate()?;
+        }
+        let file = self.file.as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "log file not open"))?;
+        let written = file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+struct LogState {
+    base_directives: String,
+    modules: BTreeMap<String, String>,
+    log_file: Option<PathBuf>,
+}
+
+impl LogState {
+    fn directives(&self) -> String {
+        let mut directives = vec![self.base_directives.clone()];
+        directives.extend(self.modules.iter().map(|(module, level)| format!("{}={}", module, level)));
+        directives.join(",")
+    }
+
+    fn to_config(&self) -> LogConfig {
+        LogConfig {
+            default_level: self.base_directives.clone(),
+            modules: self.modules.clone(),
+            log_file: self.log_file.as_ref().map(|p| p.to_string_lossy().to_string()),
+        }
+    }
+}
+
+static FILTER_HANDLE: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+static LOG_STATE: Lazy<Mutex<LogState>> = Lazy::new(|| Mutex::new(LogState {
+    base_directives: DEFAULT_LEVEL.to_string(),
+    modules: BTreeMap::new(),
+    log_file: None,
+}));
+
+fn log_directory() -> PathBuf {
+    crate::app_paths::log_dir()
+}
+
+/// Install the global logger. Honors RUST_LOG for the starting filter.
+pub fn init() {
+    let base_directives = std::env::var("RUST_LOG")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_LEVEL.to_string());
+
+    let filter = EnvFilter::try_new(&base_directives)
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LEVEL));
+    let (filter_layer, handle) = reload::Layer::new(filter);
+
+    let log_path = log_directory().join(LOG_FILE_NAME);
+    let (file_layer, log_file) = match RollingLogFile::open(log_path.clone()) {
+        Ok(file) => (
+            Some(fmt::layer().with_ansi(false).with_writer(Mutex::new(file))),
+            Some(log_path),
+        ),
+        Err(e) => {
+            eprintln!("⚠️ Log file disabled: {}", e);
+            (None, None)
+        }
+    };
+
+    // Bridge log:: macros (all existing modules) into tracing
+    if let Err(e) = tracing_log::LogTracer::init() {
+        eprintln!("⚠️ Failed to bridge log records into tracing: {}", e);
+    }
+
+    let subscriber = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(fmt::layer().with_writer(io::stderr))
+        .with(file_layer);
+
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("⚠️ Logger already initialized: {}", e);
+        return;
+    }
+
+    let _ = FILTER_HANDLE.set(handle);
+    let mut state = LOG_STATE.lock().unwrap();
+    state.base_directives = base_directives;
+    state.log_file = log_file;
+}
+
+fn apply(state: &LogState) -> Result<()> {
+    let handle = FILTER_HANDLE.get().context("Logger not initialized")?;
+    let filter = EnvFilter::try_new(state.directives())
+        .context("Invalid log filter")?;
+    handle.reload(filter).context("Failed to reload log filter")?;
+    Ok(())
+}
+
+// ========== Tauri Commands ==========
+
+// Change the level for one module/target (or the default when module is None).
+// Use level "inherit" to drop a module override.
+#[tauri::command]
+pub fn set_log_level(module: Option<String>, level: String) -> Result<LogConfig, String> {
+    let level = level.trim().to_lowercase();
+    let mut state = LOG_STATE.lock().unwrap();
+
+    match module.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()) {
+        Some(module) if level == "inherit" => {
+            state.modules.remove(&module);
+        }
+        Some(module) => {
+            level.parse::<LevelFilter>().map_err(|_| format!("Unknown log level '{}'", level))?;
+            state.modules.insert(module, level);
+        }
+        None => {
+            level.parse::<LevelFilter>().map_err(|_| format!("Unknown log level '{}'", level))?;
+            state.base_directives = level;
+        }
+    }
+
+    apply(&state).map_err(|e| e.to_string())?;
+    log::info!("🔧 Log filter now: {}", state.directives());
+    Ok(state.to_config())
+}
+
+#[tauri::command]
+pub fn get_log_config() -> Result<LogConfig, String> {
+    Ok(LOG_STATE.lock().unwrap().to_config())
+}
@@ -11,7 +11,9 @@ use std::sync::{Arc, Mutex};
 mod vosk_transcription;
 use vosk_transcription::{
     start_vosk_transcription, stop_vosk_transcription, 
-    get_vosk_status, test_vosk, initialize_vosk_model
+    get_vosk_status, test_vosk, initialize_vosk_model,
+    set_endpointing, get_endpointing,
+    enter_transcription_standby, exit_transcription_standby, get_standby_status
 };
 
 
@@ -22,8 +24,352 @@ use deepgram_transcription::{
     get_deepgram_status, test_deepgram
 };
 
+// Half-open detection for the cloud streaming connection
+use voicecoach_core::ws_watchdog;
+
 // Breadcrumb system for debugging
 mod breadcrumb_system;
+use breadcrumb_system::{get_breadcrumbs, get_breadcrumb_components};
+
+// Exclusive-mode device conflict detection + shared-mode fallback
+mod device_conflict;
+
+// Ranked input device selection rules (prefer headset, never webcam, ...)
+mod device_selection;
+use device_selection::{get_device_rules, set_device_rules, get_ranked_devices};
+
+// Local key material (export signing/encryption keys, trusted signers)
+mod credentials;
+use credentials::{get_export_key_info, rotate_export_keys, share_export_encryption_key, import_export_encryption_key, trust_export_signer, untrust_export_signer};
+
+// Signed / encrypted export bundles
+mod export_security;
+use export_security::{get_export_security, set_export_security, seal_export, verify_export};
+
+// Per-source level calibration wizard (mixer gain + VAD threshold)
+mod level_calibration;
+use level_calibration::{calibrate_source_levels, get_level_calibration, reset_level_calibration};
+
+// Stream transcript + coaching notes into a shared Google Doc / Notion page
+mod live_doc;
+use live_doc::{configure_live_doc, start_live_doc_stream, stop_live_doc_stream, get_live_doc_status};
+
+// Optional profanity masking/tagging of transcripts before they leave the backend
+mod profanity_filter;
+use profanity_filter::{get_profanity_filter, set_profanity_filter, apply_profanity_filter};
+
+// Diarized speaker labels + participant count for multi-party calls
+mod speakers;
+use speakers::{get_speakers, rename_speaker};
+
+// Trial period, license activation and paid-tier feature gating
+mod license;
+use license::{activate_license, get_license_status};
+
+// Signed release manifest checks with staged rollout
+mod updates;
+use updates::{check_for_updates, defer_update, set_update_auto_check};
+
+// Sales stage detection + stage vocabulary biasing for the recognizers
+mod sales_stage;
+use sales_stage::{get_sales_stage, set_sales_stage, get_stage_bias_settings, set_stage_bias_settings};
+
+// Memory budgets with pressure-based degradation
+mod memory_budget;
+use memory_budget::{get_memory_usage, set_memory_budget};
+
+// Read-only remote live listen for coaches/managers
+mod live_listen;
+use live_listen::{start_live_listen, stop_live_listen, get_live_listen_status};
+
+// Fault injection for error-recovery testing (armed only with the `chaos` feature)
+mod chaos;
+use chaos::{set_fault_injection, get_fault_injection};
+
+// Punctuation/capitalization restoration for Vosk output
+mod punctuation;
+use punctuation::{get_punctuation_settings, set_punctuation_settings};
+
+// Per-application loopback capture (Windows 10 2004+)
+mod app_audio;
+use app_audio::{list_capture_apps, capture_app_audio};
+
+// Capture-order release of final transcript segments
+mod transcript_sequencer;
+
+// Per-call session records (coaching prompt history for post-call review)
+mod session_store;
+use session_store::{get_session_prompts, rate_session_prompt, set_session_outcome, add_session_bookmark, get_session_bookmarks, set_session_recording, list_sessions};
+
+// OpenTelemetry metrics/trace export (vosk-config "telemetry" section)
+mod telemetry;
+use telemetry::get_telemetry_status;
+
+// Read-aloud detection against "script" knowledge documents (pauses coaching)
+mod read_aloud;
+use read_aloud::get_read_aloud_status;
+
+// Cloud engine silence skipping and usage/savings report
+mod cloud_usage;
+use cloud_usage::{get_usage_report, set_silence_skipping};
+
+// Vosk recognizer pool (parallel decoding of file chunks)
+use voicecoach_core::recognizer_pool;
+
+// Prospect question log (asked / answered)
+mod prospect_questions;
+use prospect_questions::get_prospect_questions;
+
+// Follow-up email drafts from the call summary
+mod followup_email;
+use followup_email::{draft_followup_email, get_email_template, set_email_template};
+
+// Privacy mute (closes every capture stream, tray indicator)
+mod privacy;
+use privacy::{set_privacy_mute, get_capture_state};
+
+// Two-pass transcription (local partials, cloud finals)
+mod two_pass;
+use two_pass::{get_two_pass_mode, set_two_pass_mode, start_two_pass_transcription};
+
+// Prospect company research brief attached to the session
+mod prospect_brief;
+use prospect_brief::{set_session_company, get_session_brief, get_enrichment_settings, set_enrichment_settings};
+
+// Topic chapters of the call transcript
+mod topic_segmentation;
+use topic_segmentation::{segment_topics, get_topic_chapters};
+
+// Generated TypeScript types for the command payloads
+mod bindings;
+
+// Cross-session analytics export (CSV/Parquet)
+mod analytics_export;
+use analytics_export::export_analytics;
+
+// Mic monitoring (sidetone) for headset users
+mod sidetone;
+use sidetone::{get_sidetone, set_sidetone};
+
+// Session templates per call type (checklist, coaching focus, knowledge, rubric)
+mod session_templates;
+use session_templates::{list_session_templates, get_active_session_template, save_session_template, delete_session_template, start_session_from_template};
+
+// Competitor mentions with battlecard points
+mod competitor_watch;
+use competitor_watch::{get_competitor_watchlist, set_competitor_watchlist, get_competitor_mentions};
+
+// Time-stretched playback for session review
+mod playback;
+use playback::{play_recording, set_playback_paused, seek_playback, set_playback_rate, stop_playback, get_playback_status};
+
+// Word-level transcript search
+mod transcript_search;
+use transcript_search::search_in_session;
+
+// Localized backend text
+mod i18n;
+use i18n::{get_locale, set_locale};
+
+// Live mic clipping / SNR watch
+mod mic_quality;
+use mic_quality::{get_mic_quality, set_mic_quality};
+
+// Coaching prompt cooldowns, rate limits and deduplication
+mod coaching_cooldown;
+use coaching_cooldown::{get_coaching_profile, set_coaching_profile};
+
+// Local HTTP control interface for dialer screen-pops
+mod control_interface;
+use control_interface::{get_control_interface, set_control_interface, regenerate_control_token};
+
+// Knowledge base question answering
+mod knowledge_answer;
+use knowledge_answer::answer_question;
+
+// Crash-safe transcript journal (no commands; replayed at startup)
+mod transcript_journal;
+
+// Per-call VAD threshold learned from the noise floor
+mod adaptive_vad;
+use adaptive_vad::{get_adaptive_vad_settings, set_adaptive_vad_settings};
+
+// Hands-free voice commands (wake word + phrase)
+mod voice_commands;
+use voice_commands::{get_voice_commands, set_voice_command_settings};
+
+// Coaching program reports across reps
+mod team_report;
+use team_report::generate_team_report;
+
+// Post-call voice notes
+mod voice_notes;
+use voice_notes::{record_voice_note, stop_voice_note, get_session_notes};
+
+// CPU/RAM-based default model and decoder threads (get_engine_health)
+use voicecoach_core::hardware_profile;
+
+// One-party consent: prospect channel metered, never transcribed
+mod one_party;
+use one_party::{get_one_party_consent, set_one_party_consent};
+
+// Relationship memory across calls with the same prospect
+mod prospect_memory;
+use prospect_memory::get_prospect_memory;
+
+// Waveform peaks of session recordings for the playback UI
+mod waveform;
+use waveform::get_waveform;
+
+// One session at a time: start arbitration and takeover
+mod session_arbiter;
+use session_arbiter::{get_session_arbiter_status, force_start_session};
+
+// Coach audio on a second output device (whisper channel)
+mod whisper_channel;
+use whisper_channel::{list_output_devices, get_whisper_channel, set_whisper_channel, test_whisper_channel};
+
+// Startup behavior: tray start, standby, device check, launch at login
+mod startup;
+use startup::{get_startup_options, set_startup_options, check_startup_devices};
+
+// Notes typed during a call, pinned to the transcript
+mod scratchpad;
+use scratchpad::{add_scratch_note, update_scratch_note, delete_scratch_note, get_scratch_notes, get_notes_timeline, export_scratch_notes};
+
+// Incremental summary of long calls, final call summary
+mod rolling_summary;
+use rolling_summary::{get_rolling_summary, summarize_call};
+
+// Catch-up for late joiners: summary so far plus the last exchanges
+mod catch_up;
+use catch_up::catch_me_up;
+
+// Audio callback timing histograms (duration, jitter, overruns)
+mod pipeline_stats;
+use pipeline_stats::{get_audio_pipeline_stats, reset_audio_pipeline_stats};
+
+// Import of calls recorded with other tools (WAV + transcript)
+mod session_import;
+use session_import::import_external_session;
+
+// On-hold music and dead-air detection on the prospect channel
+mod hold_detection;
+use hold_detection::{get_hold_detection, set_hold_detection, get_quiet_periods};
+
+// Coaching rule test harness (fixtures replayed through the rules)
+mod rule_harness;
+use rule_harness::test_rules_against_session;
+
+// Transcript quality scores and re-transcription with the large model
+mod transcript_quality;
+use transcript_quality::{get_transcript_quality_settings, set_transcript_quality_settings, get_transcript_quality, retranscribe_session};
+
+// Opt-in anonymized peer benchmarks (team and industry)
+mod peer_benchmark;
+use peer_benchmark::{get_benchmark_settings, preview_benchmark_contribution, opt_in_to_benchmarks, opt_out_of_benchmarks, get_peer_benchmarks};
+
+// Headset / device mute detected from the microphone signal
+mod hardware_mute;
+use hardware_mute::{get_hardware_mute, set_hardware_mute, get_mic_mute_state, get_muted_intervals};
+
+// Raw audio kept for low-confidence lines until they are corrected
+mod audio_snippets;
+use audio_snippets::{get_snippet_settings, set_snippet_settings, list_audio_snippets, correct_transcript_line, discard_audio_snippet};
+
+// Spectral subtraction of a captured background noise profile on the mic
+mod noise_suppression;
+use noise_suppression::{capture_noise_profile, get_noise_suppression, set_noise_suppression, clear_noise_profile, set_session_noise_suppression, get_session_noise_suppression};
+
+// Calls never recorded or coached (calendar, contact or time rules) unless overridden
+mod no_coach_zones;
+use no_coach_zones::{get_no_coach_zones, set_no_coach_zones, set_call_context, check_no_coach_zone, override_no_coach_zone, get_no_coach_audit};
+
+// Final transcript lines appended to a text / JSON Lines file during the call
+mod transcript_stream;
+use transcript_stream::{stream_transcript_to_file, stop_transcript_stream, get_transcript_streams};
+
+// Window positions remembered per monitor arrangement, snap-to-corner
+mod window_layout;
+use window_layout::{snap_window_to_corner, reset_overlay_layout};
+
+// GPU discovery (CUDA / DirectML / Metal) and the Whisper model and device it allows
+mod acceleration;
+use acceleration::{detect_acceleration, get_whisper_backend, set_whisper_backend};
+
+// Runtime bypass of single audio pipeline stages, for support to bisect failures
+mod stage_bypass;
+use stage_bypass::{set_stage_bypass, get_stage_bypasses, clear_stage_bypasses};
+
+// Vector index of session summaries for finding similar past calls
+mod similar_sessions;
+use similar_sessions::{find_similar_sessions, index_past_sessions};
+
+// Sample-counting capture clock that session times and event timestamps are kept on
+use voicecoach_core::audio_clock;
+
+// Signed settings profiles for fleet deployment (export/import, machine-wide default)
+mod settings_profile;
+use settings_profile::{export_settings_profile, import_settings_profile, get_default_profile_path};
+
+// Name pseudonymization and contact redaction for shared transcripts
+use voicecoach_core::pii_redaction;
+
+// Sessions shared for training libraries (pseudonymized, voice anonymized)
+mod session_share;
+use session_share::export_shared_session;
+
+// Grammar-constrained recognition (wake word commands, practice drills)
+mod recognition_grammar;
+use recognition_grammar::{set_recognition_grammar, clear_recognition_grammar, get_recognition_grammar};
+
+// WASM plugins that rewrite final transcript segments
+mod transcript_plugins;
+use transcript_plugins::{list_transcript_plugins, install_transcript_plugin, set_transcript_plugins, test_transcript_plugin};
+
+// A/B experiments on coaching rule sets
+mod experiments;
+use experiments::{list_experiments, save_experiment, delete_experiment, set_running_experiment, get_experiment_results};
+
+// Device sample formats converted to and from f32
+use voicecoach_core::sample_format;
+
+// MP3/Opus sharing copies of call recordings
+mod recording_conversion;
+use recording_conversion::convert_recording;
+
+// Per-line and per-word transcript confidence for the heatmap
+mod transcript_confidence;
+use transcript_confidence::get_transcript_confidence;
+
+// Talk-over detection and nudges
+mod talk_over;
+use talk_over::{get_talk_over_settings, set_talk_over_settings, get_talk_over_count};
+
+// Ranked key moments of a call (pricing, decision-maker, commitment, negative spikes)
+mod key_moments;
+use key_moments::get_key_moments;
+
+// voicecoach-core events delivered to the windows
+mod event_sink;
+
+// Devices, capture registry and transcript routing for the core capture engines
+mod capture_host;
+
+// Sentence-level records of the punctuated transcript for analytics
+mod sentences;
+use sentences::get_transcript_sentences;
+
+// Call recording with disk-space checks, fallback directory and bitrate reduction
+mod call_recording;
+use call_recording::{get_call_recording_settings, set_call_recording_settings, get_recording_storage};
+
+// Installed Vosk model layouts (static, pruned, quantized) and checked loading
+use voicecoach_core::vosk_model;
+
+// Model benchmark (RTF, memory, accuracy) for the small-vs-large choice
+mod model_benchmark;
+use model_benchmark::benchmark_model;
 
 // Microphone test module
 mod test_mic;
@@ -35,14 +381,16 @@ use document_processing::{
     process_documents, search_knowledge_base, 
     validate_knowledge_base, get_knowledge_base_stats, 
     initialize_document_processing,
-    get_coaching_suggestions
+    get_coaching_suggestions,
+    ingest_url, list_url_sources, remove_url_source
 };
 
 // Ollama AI coaching integration
 mod ollama_integration;
 use ollama_integration::{
     generate_ai_coaching, check_ollama_status,
-    load_knowledge_base, save_knowledge_base
+    load_knowledge_base, save_knowledge_base,
+    get_coaching_history
 };
 
 // Claude API integration (via OpenRouter - backend only!)
@@ -56,17 +404,59 @@ mod knowledge_base;
 use knowledge_base::{
     initialize_knowledge_base as init_kb,
     process_single_file, process_documents_batch,
-    search_knowledge, get_kb_stats, get_all_documents,
+    search_knowledge, get_kb_stats, get_all_documents, get_knowledge_passage,
+    search_knowledge_with_citations,
     add_document_to_kb, remove_document_from_kb,
     clear_knowledge_base, process_text_content,
     select_files, select_directory
 };
 
+// File-based transcription (shared with the CLI binary)
+use voicecoach_core::file_transcription;
+
+// Debug audio tap (tees engine input to rotating WAV files)
+mod audio_tap;
+use audio_tap::{enable_audio_tap, disable_audio_tap, get_audio_tap_status};
+
+// OBS integration (captions + coaching tips via obs-websocket 5.x)
+mod obs_integration;
+use obs_integration::{connect_obs, disconnect_obs, get_obs_status, test_obs_connection, set_obs_text};
+
+// App data and log directories from the app's config
+mod app_paths;
+
+// Reloadable logging (per-module levels + rotating log file)
+mod logging;
+use logging::{set_log_level, get_log_config};
+
+// Transcript-driven call analytics (checklist tracking)
+mod call_analytics;
+use call_analytics::{get_checklist_status, set_checklist, reset_checklist, mark_checklist_item, get_talk_ratio, get_call_summary};
+
+// Persistent user preferences (app data dir)
+mod preferences;
+
+// Chunk-size / model calibration for this machine's CPU headroom
+mod calibration;
+use calibration::{run_calibration, get_calibration};
+
 // App state to hold preloaded Vosk model for <1s startup
 // Using Arc to share the model across threads
 pub struct VoskAppState {
-    pub model: Arc<Option<Arc<vosk::Model>>>,
-    pub model_path: Arc<String>,
+    pub model: Arc<Mutex<Option<Arc<vosk::Model>>>>,
+    pub model_path: Arc<Mutex<String>>,  // Replaced by the small model under memory pressure
+}
+
+impl VoskAppState {
+    /// (model path, preloaded model if any)
+    pub fn preloaded(&self) -> (String, Option<Arc<vosk::Model>>) {
+        (self.model_path.lock().unwrap().clone(), self.model.lock().unwrap().clone())
+    }
+
+    pub fn replace_model(&self, model_path: &str, model: Option<Arc<vosk::Model>>) {
+        *self.model_path.lock().unwrap() = model_path.to_string();
+        *self.model.lock().unwrap() = model;
+    }
 }
 
 // Enhanced initialization with both transcription and RAG
@@ -94,11 +484,13 @@ async fn initialize_app() -> Result<String, String> {
         if let Ok(config) = serde_json::from_str::<serde_json::Value>(&clean_json) {
             let large = config["model_paths"]["large_model"].as_str().unwrap_or("");
             let small = config["model_paths"]["small_model"].as_str().unwrap_or("");
+            let calibrated = preferences::load().calibration.map(|c| c.model_path);
             
-            if std::path::Path::new(large).exists() {
-                large.to_string()
-            } else if std::path::Path::new(small).exists() {
-                small.to_string()
+            if let Some(path) = calibrated.filter(|p| std::path::Path::new(p).exists()) {
+                info!("✅ Using calibrated model: {}", path);
+                path
+            } else if let Some(path) = hardware_profile::default_model_path(large, small) {
+                path
             } else {
                 "../models/vosk-model-small-en-us-0.15".to_string()
             }
@@ -133,7 +525,7 @@ async fn initialize_app() -> Result<String, String> {
     
     // Initialize knowledge base manager
     info!("🧠 Initializing knowledge base manager...");
-    match init_kb() {
+    match init_kb(&app_paths::data_dir()) {
         Ok(_) => {
             info!("✅ Knowledge base manager initialized successfully");
         }
@@ -152,48 +544,85 @@ async fn initialize_voicecoach() -> Result<String, String> {
     initialize_app().await
 }
 
+// Payloads of the audio status commands below (exported to the UI by bindings.rs)
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct AudioStatus {
+    pub is_recording: bool,
+    pub is_processing: bool,
+    pub audio_level: f32,
+    pub prospect_level: f32,
+    pub status: String,  // "Recording" or "Stopped"
+    pub timestamp: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub buffer_size: u32,
+}
+
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct AudioDevice {
+    pub name: String,
+    pub is_input: bool,
+    pub is_default: bool,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct AudioLevels {
+    pub user: f32,      // 0-100
+    pub prospect: f32,  // 0-100
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub struct PerformanceMetrics {
+    pub average_latency_ms: f64,
+    pub uptime_seconds: u64,
+    pub total_transcriptions: u32,
+    pub status: String,
+    pub target_latency_ms: u32,
+}
+
 // Audio status
 #[tauri::command]
-async fn get_audio_status() -> Result<serde_json::Value, String> {
+async fn get_audio_status() -> Result<AudioStatus, String> {
     let is_recording = get_vosk_status().await.unwrap_or(false);
     
-    Ok(serde_json::json!({
-        "is_recording": is_recording,
-        "is_processing": false,
-        "audio_level": 0.0,
-        "prospect_level": 0.0,
-        "status": if is_recording { "Recording" } else { "Stopped" },
-        "timestamp": chrono::Utc::now().timestamp_millis(),
-        "sample_rate": 16000,
-        "channels": 1,
-        "buffer_size": 800
-    }))
+    Ok(AudioStatus {
+        is_recording,
+        is_processing: false,
+        audio_level: 0.0,
+        prospect_level: 0.0,
+        status: if is_recording { "Recording" } else { "Stopped" }.to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        sample_rate: 16000,
+        channels: 1,
+        buffer_size: 800,
+    })
 }
 
 // Audio devices
 #[tauri::command]
-async fn get_audio_devices() -> Result<Vec<serde_json::Value>, String> {
+async fn get_audio_devices() -> Result<Vec<AudioDevice>, String> {
     Ok(vec![
-        serde_json::json!({
-            "name": "Default Microphone", 
-            "is_input": true, 
-            "is_default": true,
-            "sample_rate": 16000,
-            "channels": 1
-        })
+        AudioDevice {
+            name: "Default Microphone".to_string(),
+            is_input: true,
+            is_default: true,
+            sample_rate: 16000,
+            channels: 1,
+        }
     ])
 }
 
 // Audio levels
 #[tauri::command]
-async fn get_audio_levels() -> Result<serde_json::Value, String> {
-    Ok(serde_json::json!({
-        "input_level": 0.0,
-        "output_level": 0.0,
-        "peak_input": 0.0,
-        "peak_output": 0.0,
-        "timestamp": chrono::Utc::now().timestamp_millis()
-    }))
+async fn get_audio_levels() -> Result<AudioLevels, String> {
+    Ok(AudioLevels {
+        user: 0.0,
+        prospect: 0.0,
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+    })
 }
 
 // Start recording (maps to regular Vosk)
@@ -214,7 +643,7 @@ async fn stop_recording() -> Result<String, String> {
 
 // Get performance metrics
 #[tauri::command]
-async fn get_performance_metrics() -> Result<serde_json::Value, String> {
+async fn get_performance_metrics() -> Result<PerformanceMetrics, String> {
     use std::time::SystemTime;
     
     // Calculate uptime
@@ -231,13 +660,13 @@ async fn get_performance_metrics() -> Result<serde_json::Value, String> {
     // Simple transcription count based on recording status
     let total_transcriptions = if is_recording { 1 } else { 0 };
     
-    Ok(serde_json::json!({
-        "average_latency_ms": 45.0,  // Typical Vosk latency
-        "uptime_seconds": uptime_seconds,
-        "total_transcriptions": total_transcriptions,
-        "status": "Performance tracking active",
-        "target_latency_ms": 100
-    }))
+    Ok(PerformanceMetrics {
+        average_latency_ms: 45.0,  // Typical Vosk latency
+        uptime_seconds,
+        total_transcriptions,
+        status: "Performance tracking active".to_string(),
+        target_latency_ms: 100,
+    })
 }
 
 // CRITICAL: RAG Knowledge retrieval command (was missing!)
@@ -247,23 +676,14 @@ async fn retrieve_coaching_knowledge(
     stage: String,
     _topics: Vec<String>,
     max_results: i32
-) -> Result<Vec<serde_json::Value>, String> {
+) -> Result<Vec<document_processing::KnowledgeSearchResult>, String> {
     info!("Retrieving coaching knowledge for query: {} (stage: {})", query, stage);
     
     // Use local knowledge base search
     match search_knowledge_base(query, Some(max_results as usize), Some(stage)).await {
         Ok(results) => {
             info!("Retrieved {} knowledge items from local knowledge base", results.len());
-            // Convert KnowledgeSearchResult to serde_json::Value
-            let json_results: Vec<serde_json::Value> = results.into_iter()
-                .map(|result| serde_json::json!({
-                    "content": result.content,
-                    "similarity_score": result.similarity_score,
-                    "source_document": result.source_document,
-                    "metadata": result.metadata
-                }))
-                .collect();
-            Ok(json_results)
+            Ok(results)
         }
         Err(e) => {
             error!("Local knowledge retrieval failed: {}", e);
@@ -272,6 +692,41 @@ async fn retrieve_coaching_knowledge(
     }
 }
 
+// Transcribe a WAV file from disk using the preloaded model, decoding chunks in parallel
+#[tauri::command]
+async fn transcribe_audio_file(
+    state: tauri::State<'_, VoskAppState>,
+    file_path: String,
+) -> Result<file_transcription::FileTranscript, String> {
+    info!("📤 LED 8100: transcribe_audio_file requested for {}", file_path);
+
+    let model = match state.preloaded() {
+        (_, Some(model)) => model,
+        (model_path, None) => Arc::new(vosk_model::load(&model_path).map_err(|e| e.to_string())?.0),
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let pool = recognizer_pool::RecognizerPool::new(
+            model, file_transcription::resolve_pool_size(), file_transcription::VOSK_SAMPLE_RATE as f32);
+        file_transcription::transcribe_wav_file(&pool, &file_path)
+    })
+        .await
+        .map_err(|e| format!("Transcription task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+// Hardware profile, the model choice it led to and the model in use
+#[tauri::command]
+fn get_engine_health(state: tauri::State<'_, VoskAppState>) -> Result<hardware_profile::EngineHealth, String> {
+    let (model_path, preloaded) = state.preloaded();
+    Ok(hardware_profile::EngineHealth {
+        hardware: hardware_profile::profile(),
+        decision: hardware_profile::decision(),
+        model_path,
+        model_loaded: preloaded.is_some(),
+        memory_fallback: memory_budget::small_model_active(),
+    })
+}
 
 fn create_system_tray() -> SystemTray {
     let quit = CustomMenuItem::new("quit".to_string(), "Quit VoiceCoach");
@@ -279,6 +734,7 @@ fn create_system_tray() -> SystemTray {
     
     let tray_menu = SystemTrayMenu::new()
         .add_item(show)
+        .add_item(privacy::tray_menu_item())
         .add_native_item(SystemTrayMenuItem::Separator)
         .add_item(quit);
 
@@ -300,6 +756,7 @@ fn handle_system_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
                         let _ = window.set_focus();
                     }
                 }
+                id if privacy::is_tray_item(id) => privacy::toggle(app),
                 _ => {}
             }
         }
@@ -308,9 +765,23 @@ fn handle_system_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
 }
 
 fn main() {
-    env_logger::init();
+    // Data and log paths come from the app's config, so capture it before anything is saved
+    let context = tauri::generate_context!();
+    app_paths::init(context.config());
+    logging::init();
     info!("Starting VoiceCoach with Vosk transcription + RAG knowledge system...");
 
+    // Keep the UI's generated command types in sync during development
+    #[cfg(debug_assertions)]
+    match bindings::export() {
+        Ok(true) => info!("📝 TypeScript bindings regenerated: {:?}", bindings::bindings_path()),
+        Ok(false) => {}
+        Err(e) => warn!("⚠️ {}", e),
+    }
+
+    // First run: settings profile deployed machine-wide by IT (before the config is read)
+    settings_profile::apply_machine_default();
+
     // PRELOAD VOSK MODEL AT STARTUP FOR <1s RESPONSE TIME
     info!("⚡ Preloading Vosk model at startup for fast response...");
     
@@ -331,13 +802,14 @@ fn main() {
         if let Ok(config) = serde_json::from_str::<serde_json::Value>(&clean_json) {
             let large = config["model_paths"]["large_model"].as_str().unwrap_or("");
             let small = config["model_paths"]["small_model"].as_str().unwrap_or("");
+            let calibrated = preferences::load().calibration.map(|c| c.model_path);
             
-            if std::path::Path::new(large).exists() {
-                info!("✅ Using large model: {}", large);
-                large.to_string()
-            } else if std::path::Path::new(small).exists() {
-                info!("✅ Using small model: {}", small);
-                small.to_string()
+            if let Some(path) = calibrated.filter(|p| std::path::Path::new(p).exists()) {
+                info!("✅ Using calibrated model: {}", path);
+                path
+            } else if let Some(path) = hardware_profile::default_model_path(large, small) {
+                info!("✅ Using {} model for this hardware: {}", if path == large { "large" } else { "small" }, path);
+                path
             } else {
                 warn!("⚠️ No model found, using default path");
                 "../models/vosk-model-small-en-us-0.15".to_string()
@@ -351,29 +823,27 @@ fn main() {
     
     // Preload the model
     let preload_start = std::time::Instant::now();
-    let preloaded_model = if std::path::Path::new(&model_path).exists() {
-        match vosk::Model::new(&model_path) {
-            Some(model) => {
-                let load_time = preload_start.elapsed();
-                info!("⚡ Vosk model preloaded in {:.2}s", load_time.as_secs_f32());
-                Some(model)
-            }
-            None => {
-                error!("❌ Failed to preload Vosk model");
-                None
-            }
+    let preloaded_model = match vosk_model::load(&model_path) {
+        Ok((model, _)) => {
+            let load_time = preload_start.elapsed();
+            info!("⚡ Vosk model preloaded in {:.2}s", load_time.as_secs_f32());
+            Some(model)
+        }
+        Err(e) => {
+            error!("❌ Failed to preload Vosk model: {}", e);
+            None
         }
-    } else {
-        error!("❌ Vosk model path not found: {}", model_path);
-        None
     };
     
     // Create app state with preloaded model
     let app_state = VoskAppState {
-        model: Arc::new(preloaded_model.map(Arc::new)),
-        model_path: Arc::new(model_path),
+        model: Arc::new(Mutex::new(preloaded_model.map(Arc::new))),
+        model_path: Arc::new(Mutex::new(model_path)),
     };
 
+    // Keep the configured tray icon so it can be restored after a privacy mute
+    privacy::remember_tray_icon(context.system_tray_icon().cloned());
+    
     tauri::Builder::default()
         .manage(app_state)  // Add app state to Tauri
         .system_tray(create_system_tray())
@@ -383,13 +853,60 @@ fn main() {
             
             if let Some(window) = app.get_window("main") {
                 let _ = window.set_title("VoiceCoach - AI Sales Coaching");
-                let _ = window.show();  // Make sure window is visible
-                let _ = window.set_focus();  // Bring to front
                 
                 #[cfg(debug_assertions)]
                 window.open_devtools();
             }
             
+            // Startup options: show the window or stay in the tray, warm standby, device check
+            let (model_path, _) = app.state::<VoskAppState>().preloaded();
+            startup::apply(&app.handle(), model_path);
+            
+            // Follow device preference rules across hot-plug events
+            device_selection::start_hotplug_watcher(app.handle());
+            
+            // Restore window positions for the connected monitors, follow docking changes
+            window_layout::start(app.handle());
+            
+            // Re-ingest web page knowledge sources on their refresh schedule
+            document_processing::start_url_refresh_scheduler();
+            
+            // Periodic release manifest check ("update_status" events)
+            updates::start_update_checker(app.handle());
+            
+            // Enforce memory budgets, degrade under pressure ("memory_pressure" events)
+            memory_budget::start_memory_monitor(app.handle());
+            
+            // OTLP metrics export for fleet monitoring (only when enabled in config)
+            telemetry::start_exporter();
+            
+            // Dialer control endpoint on localhost (only when enabled)
+            control_interface::start_control_interface(app.handle());
+            
+            // Replay transcript journals a crash left behind, then keep compacting
+            let recovered = session_store::recover_journals();
+            if !recovered.is_empty() {
+                let _ = app.emit_all("transcripts_recovered", recovered);
+            }
+            transcript_journal::start_compactor();
+            
+            // First run: calibrate chunk size in the background (applies to the next stream/launch)
+            if preferences::load().calibration.is_none()
+                && vosk_transcription::configured_calibration().auto_calibrate_on_first_run
+            {
+                let handle = app.handle();
+                let (model_path, preloaded) = app.state::<VoskAppState>().preloaded();
+                std::thread::spawn(move || {
+                    let preloaded = preloaded.map(|model| (model_path.as_str(), model));
+                    match calibration::calibrate_and_store(None, preloaded) {
+                        Ok(result) => {
+                            let _ = handle.emit_all("calibration_complete", result);
+                        }
+                        Err(e) => warn!("⚠️ First-run calibration failed: {}", e),
+                    }
+                });
+            }
+            
             info!("VoiceCoach setup completed");
             Ok(())
         })
@@ -408,6 +925,9 @@ fn main() {
             stop_vosk_transcription,
             get_vosk_status,
             test_vosk,
+            transcribe_audio_file,
+            set_endpointing,
+            get_endpointing,
             
             
             // Deepgram cloud transcription (WebKit-quality)
@@ -434,6 +954,7 @@ fn main() {
             check_ollama_status,
             load_knowledge_base,
             save_knowledge_base,
+            get_coaching_history,
             
             // Claude API integration (backend only!)
             ask_claude,
@@ -443,8 +964,10 @@ fn main() {
             process_single_file,
             process_documents_batch,
             search_knowledge,
+            search_knowledge_with_citations,
             get_kb_stats,
             get_all_documents,
+            get_knowledge_passage,
             add_document_to_kb,
             remove_document_from_kb,
             clear_knowledge_base,
@@ -453,8 +976,325 @@ fn main() {
             select_directory,
             
             // Microphone test
-            test_microphone_access
+            test_microphone_access,
+            
+            // Debug audio tap
+            enable_audio_tap,
+            disable_audio_tap,
+            get_audio_tap_status,
+            // OBS integration
+            connect_obs,
+            disconnect_obs,
+            get_obs_status,
+            test_obs_connection,
+            set_obs_text,
+            // Logging controls
+            set_log_level,
+            get_log_config,
+            // Calibration
+            run_calibration,
+            get_calibration,
+            // Web page knowledge import
+            ingest_url,
+            list_url_sources,
+            remove_url_source,
+            // Call checklist
+            get_checklist_status,
+            set_checklist,
+            reset_checklist,
+            mark_checklist_item,
+            get_talk_ratio,
+            get_call_summary,
+            // Input device preferences
+            get_device_rules,
+            set_device_rules,
+            get_ranked_devices,
+            // Export signing and encryption
+            get_export_key_info,
+            rotate_export_keys,
+            share_export_encryption_key,
+            import_export_encryption_key,
+            trust_export_signer,
+            untrust_export_signer,
+            get_export_security,
+            set_export_security,
+            seal_export,
+            verify_export,
+            // Warm standby (instant call start)
+            enter_transcription_standby,
+            exit_transcription_standby,
+            get_standby_status,
+            // Level calibration wizard
+            calibrate_source_levels,
+            get_level_calibration,
+            reset_level_calibration,
+            // Live document streaming
+            configure_live_doc,
+            start_live_doc_stream,
+            stop_live_doc_stream,
+            get_live_doc_status,
+            // Profanity filter
+            get_profanity_filter,
+            set_profanity_filter,
+            apply_profanity_filter,
+            // Multi-participant speakers
+            get_speakers,
+            rename_speaker,
+            // License
+            activate_license,
+            get_license_status,
+            // Updates
+            check_for_updates,
+            defer_update,
+            set_update_auto_check,
+            // Sales stage vocabulary biasing
+            get_sales_stage,
+            set_sales_stage,
+            get_stage_bias_settings,
+            set_stage_bias_settings,
+            // Memory budget
+            get_memory_usage,
+            set_memory_budget,
+            // Live listen
+            start_live_listen,
+            stop_live_listen,
+            get_live_listen_status,
+            // Fault injection
+            set_fault_injection,
+            get_fault_injection,
+            // Punctuation restoration
+            get_punctuation_settings,
+            set_punctuation_settings,
+            // Per-application capture
+            list_capture_apps,
+            capture_app_audio,
+            // Session store
+            get_session_prompts,
+            rate_session_prompt,
+            // Telemetry
+            get_telemetry_status,
+            // Read-aloud detection
+            get_read_aloud_status,
+            // Cloud usage
+            get_usage_report,
+            set_silence_skipping,
+            // Prospect questions
+            get_prospect_questions,
+            // Follow-up email
+            draft_followup_email,
+            get_email_template,
+            set_email_template,
+            // Privacy mute
+            set_privacy_mute,
+            get_capture_state,
+            // Two-pass transcription
+            get_two_pass_mode,
+            set_two_pass_mode,
+            start_two_pass_transcription,
+            // Prospect brief
+            set_session_company,
+            get_session_brief,
+            get_enrichment_settings,
+            set_enrichment_settings,
+            // Topic chapters
+            segment_topics,
+            get_topic_chapters,
+            // Analytics export
+            set_session_outcome,
+            export_analytics,
+            // Sidetone
+            get_sidetone,
+            set_sidetone,
+            // Session templates
+            list_session_templates,
+            get_active_session_template,
+            save_session_template,
+            delete_session_template,
+            start_session_from_template,
+            // Competitor watch
+            get_competitor_watchlist,
+            set_competitor_watchlist,
+            get_competitor_mentions,
+            // Playback
+            play_recording,
+            set_playback_paused,
+            seek_playback,
+            set_playback_rate,
+            stop_playback,
+            get_playback_status,
+            // Transcript search
+            search_in_session,
+            // Locale
+            get_locale,
+            set_locale,
+            // Mic quality
+            get_mic_quality,
+            set_mic_quality,
+            // Coaching profile
+            get_coaching_profile,
+            set_coaching_profile,
+            // Dialer control interface
+            get_control_interface,
+            set_control_interface,
+            regenerate_control_token,
+            // Knowledge answers
+            answer_question,
+            // Adaptive VAD
+            get_adaptive_vad_settings,
+            set_adaptive_vad_settings,
+            // Voice commands and bookmarks
+            get_voice_commands,
+            set_voice_command_settings,
+            add_session_bookmark,
+            get_session_bookmarks,
+            // Team reports
+            generate_team_report,
+            // Voice notes
+            record_voice_note,
+            stop_voice_note,
+            get_session_notes,
+            // Engine health
+            get_engine_health,
+            // One-party consent
+            get_one_party_consent,
+            set_one_party_consent,
+            // Prospect memory
+            get_prospect_memory,
+            // Recordings and waveforms
+            set_session_recording,
+            get_waveform,
+            // Session arbiter
+            get_session_arbiter_status,
+            force_start_session,
+            // Whisper channel
+            list_output_devices,
+            get_whisper_channel,
+            set_whisper_channel,
+            test_whisper_channel,
+            // Startup options
+            get_startup_options,
+            set_startup_options,
+            check_startup_devices,
+            // Scratchpad notes
+            add_scratch_note,
+            update_scratch_note,
+            delete_scratch_note,
+            get_scratch_notes,
+            get_notes_timeline,
+            export_scratch_notes,
+            // Rolling summary
+            get_rolling_summary,
+            summarize_call,
+            catch_me_up,
+            // Audio pipeline stats
+            get_audio_pipeline_stats,
+            reset_audio_pipeline_stats,
+            // Session import
+            import_external_session,
+            // Hold detection
+            get_hold_detection,
+            set_hold_detection,
+            get_quiet_periods,
+            // Rule harness
+            test_rules_against_session,
+            // Transcript quality
+            list_sessions,
+            get_transcript_quality_settings,
+            set_transcript_quality_settings,
+            get_transcript_quality,
+            retranscribe_session,
+            // Peer benchmarks
+            get_benchmark_settings,
+            preview_benchmark_contribution,
+            opt_in_to_benchmarks,
+            opt_out_of_benchmarks,
+            get_peer_benchmarks,
+            // Hardware mute
+            get_hardware_mute,
+            set_hardware_mute,
+            get_mic_mute_state,
+            get_muted_intervals,
+            // Audio snippets
+            get_snippet_settings,
+            set_snippet_settings,
+            list_audio_snippets,
+            correct_transcript_line,
+            discard_audio_snippet,
+            // Noise suppression
+            capture_noise_profile,
+            get_noise_suppression,
+            set_noise_suppression,
+            clear_noise_profile,
+            set_session_noise_suppression,
+            get_session_noise_suppression,
+            // No-coach zones
+            get_no_coach_zones,
+            set_no_coach_zones,
+            set_call_context,
+            check_no_coach_zone,
+            override_no_coach_zone,
+            get_no_coach_audit,
+            // Transcript streaming to a file
+            stream_transcript_to_file,
+            stop_transcript_stream,
+            get_transcript_streams,
+            // Window layout across monitor arrangements
+            snap_window_to_corner,
+            reset_overlay_layout,
+            // GPU acceleration and Whisper backend device
+            detect_acceleration,
+            get_whisper_backend,
+            set_whisper_backend,
+            // Breadcrumb queries for the debug panel
+            get_breadcrumbs,
+            get_breadcrumb_components,
+            // Pipeline stage bypass (debugging)
+            set_stage_bypass,
+            get_stage_bypasses,
+            clear_stage_bypasses,
+            // Similar past sessions
+            find_similar_sessions,
+            index_past_sessions,
+            // Settings profiles (fleet deployment)
+            export_settings_profile,
+            import_settings_profile,
+            get_default_profile_path,
+            // Anonymized session sharing
+            export_shared_session,
+            // Recognition grammar mode
+            set_recognition_grammar,
+            clear_recognition_grammar,
+            get_recognition_grammar,
+            // Transcript plugins
+            list_transcript_plugins,
+            install_transcript_plugin,
+            set_transcript_plugins,
+            test_transcript_plugin,
+            // Coaching experiments
+            list_experiments,
+            save_experiment,
+            delete_experiment,
+            set_running_experiment,
+            get_experiment_results,
+            // Recording sharing copies
+            convert_recording,
+            // Transcript confidence heatmap
+            get_transcript_confidence,
+            // Talk-over nudges
+            get_talk_over_settings,
+            set_talk_over_settings,
+            get_talk_over_count,
+            // Key moments
+            get_key_moments,
+            // Transcript sentences
+            get_transcript_sentences,
+            // Call recording
+            get_call_recording_settings,
+            set_call_recording_settings,
+            get_recording_storage,
+            // Model benchmark
+            benchmark_model
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
\ No newline at end of file
@@ -0,0 +1,220 @@
+// Memory Budget - hard caps for in-memory buffers and pressure-based degradation
+// Each growable store has a budget: the Vosk audio buffer, the transcript cache
+// (coaching history + pending live-doc lines) and the knowledge index. A monitor
+// thread enforces the caps and compares the process RSS against the overall cap:
+// under pressure it shrinks buffers to half budget and unloads the knowledge index
+// (reloaded from disk on next use); at the cap it also swaps the preloaded Vosk
+// model for the small model (used by the next stream).
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+const MONITOR_INTERVAL_SECS: u64 = 5;
+const KB: usize = 1024;
+const MB: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct MemoryBudget {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Whole-process cap (resident memory)
+    #[serde(default = "default_process_cap_mb")]
+    pub process_cap_mb: u64,
+    #[serde(default = "default_ring_buffer_kb")]
+    pub ring_buffer_kb: usize,
+    #[serde(default = "default_transcript_cache_kb")]
+    pub transcript_cache_kb: usize,
+    #[serde(default = "default_knowledge_index_mb")]
+    pub knowledge_index_mb: usize,
+    /// Fraction of process_cap_mb at which degradation starts
+    #[serde(default = "default_pressure_ratio")]
+    pub pressure_ratio: f32,
+    #[serde(default = "default_true")]
+    pub fallback_to_small_model: bool,
+}
+
+fn default_true() -> bool { true }
+fn default_process_cap_mb() -> u64 { 2048 }
+fn default_ring_buffer_kb() -> usize { 2048 }
+fn default_transcript_cache_kb() -> usize { 1024 }
+fn default_knowledge_index_mb() -> usize { 128 }
+fn default_pressure_ratio() -> f32 { 0.85 }
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            process_cap_mb: default_process_cap_mb(),
+            ring_buffer_kb: default_ring_buffer_kb(),
+            transcript_cache_kb: default_transcript_cache_kb(),
+            knowledge_index_mb: default_knowledge_index_mb(),
+            pressure_ratio: default_pressure_ratio(),
+            fallback_to_small_model: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PressureLevel {
+    Normal,
+    Elevated,  // Above pressure_ratio of the cap
+    Critical,  // At or above the cap
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct MemoryUsage {
+    pub process_rss_bytes: Option<u64>,  // None where the platform reading is unavailable
+    pub ring_buffer_bytes: usize,
+    pub transcript_cache_bytes: usize,
+    pub knowledge_index_bytes: usize,
+    pub level: PressureLevel,
+    pub small_model_active: bool,
+    pub actions: Vec<String>,  // Degradation steps taken on the last check
+    pub budget: MemoryBudget,
+}
+
+static LEVEL: Lazy<Mutex<PressureLevel>> = Lazy::new(|| Mutex::new(PressureLevel::Normal));
+static SMALL_MODEL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn budget() -> MemoryBudget {
+    crate::preferences::load().memory_budget
+}
+
+/// True once pressure handling switched to the small model
+pub fn small_model_active() -> bool {
+    SMALL_MODEL_ACTIVE.load(Ordering::Relaxed)
+}
+
+fn level_for(rss: Option<u64>, budget: &MemoryBudget) -> PressureLevel {
+    let cap = budget.process_cap_mb * MB as u64;
+    match rss {
+        Some(rss) if rss >= cap => PressureLevel::Critical,
+        Some(rss) if rss as f64 >= cap as f64 * budget.pressure_ratio as f64 => PressureLevel::Elevated,
+        _ => PressureLevel::Normal,
+    }
+}
+
+/// Enforce the caps and degrade according to pressure; returns the actions taken
+fn enforce(app: &AppHandle, budget: &MemoryBudget, level: PressureLevel) -> Vec<String> {
+    let mut actions = Vec::new();
+    // Under pressure buffers are shrunk to half their budget
+    let divisor = if level == PressureLevel::Normal { 1 } else { 2 };
+
+    let dropped = crate::vosk_transcription::trim_audio_buffer(budget.ring_buffer_kb * KB / divisor);
+    if dropped > 0 {
+        actions.push(format!("trimmed audio buffer by {} KB", dropped / KB));
+    }
+    let cache_limit = budget.transcript_cache_kb * KB / divisor;
+    let dropped = crate::ollama_integration::trim_coaching_history(cache_limit / 2)
+        + crate::live_doc::trim_queue(cache_limit / 2);
+    if dropped > 0 {
+        actions.push(format!("evicted {} KB of transcript cache", dropped / KB));
+    }
+
+    if level != PressureLevel::Normal && crate::knowledge_base::unload_index() {
+        actions.push("unloaded knowledge index (reloads on demand)".to_string());
+    }
+    if level == PressureLevel::Critical && budget.fallback_to_small_model && !small_model_active() {
+        match crate::vosk_transcription::switch_to_small_model(app) {
+            Ok(path) => {
+                SMALL_MODEL_ACTIVE.store(true, Ordering::Relaxed);
+                actions.push(format!("switched to small model {} for the next stream", path));
+            }
+            Err(e) => warn!("⚠️ LED 9202: Small model fallback unavailable: {}", e),
+        }
+    }
+    actions
+}
+
+fn usage(level: PressureLevel, actions: Vec<String>, budget: MemoryBudget) -> MemoryUsage {
+    MemoryUsage {
+        process_rss_bytes: crate::hardware_profile::process_rss_bytes(),
+        ring_buffer_bytes: crate::vosk_transcription::audio_buffer_bytes(),
+        transcript_cache_bytes: crate::ollama_integration::coaching_history_bytes() + crate::live_doc::queue_bytes(),
+        knowledge_index_bytes: crate::knowledge_base::index_bytes(),
+        level,
+        small_model_active: small_model_active(),
+        actions,
+        budget,
+    }
+}
+
+// The knowledge index cap is enforced by the knowledge base when documents are added
+fn apply_index_limit(budget: &MemoryBudget) {
+    crate::knowledge_base::set_index_limit(if budget.enabled { budget.knowledge_index_mb * MB } else { 0 });
+}
+
+fn check(app: &AppHandle) -> MemoryUsage {
+    let budget = budget();
+    apply_index_limit(&budget);
+    if !budget.enabled {
+        return usage(PressureLevel::Normal, Vec::new(), budget);
+    }
+    let level = level_for(crate::hardware_profile::process_rss_bytes(), &budget);
+    let actions = enforce(app, &budget, level);
+
+    let previous = std::mem::replace(&mut *LEVEL.lock().unwrap(), level);
+    let usage = usage(level, actions, budget);
+    if level != previous || !usage.actions.is_empty() {
+        match level {
+            PressureLevel::Normal if previous != level => info!("🧠 LED 9200: Memory pressure {:?} -> Normal", previous),
+            PressureLevel::Normal => info!("🧠 LED 9200: Memory caps enforced: {}", usage.actions.join(", ")),
+            _ => warn!("🧠 LED 9201: Memory pressure {:?}: {}", level, usage.actions.join(", ")),
+        }
+        if let Err(e) = app.emit_all("memory_pressure", usage.clone()) {
+            error!("❌ LED 9203: Failed to emit memory_pressure: {:?}", e);
+        }
+    }
+    usage
+}
+
+/// Periodic enforcement (caps always, degradation when the process nears its cap)
+pub fn start_memory_monitor(app: AppHandle) {
+    apply_index_limit(&budget());
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(MONITOR_INTERVAL_SECS));
+        check(&app);
+    });
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_memory_usage() -> Result<MemoryUsage, String> {
+    let level = *LEVEL.lock().unwrap();
+    Ok(usage(level, Vec::new(), budget()))
+}
+
+#[tauri::command]
+pub fn set_memory_budget(app: AppHandle, budget: MemoryBudget) -> Result<MemoryUsage, String> {
+    if budget.process_cap_mb < 256 {
+        return Err("process_cap_mb must be at least 256".to_string());
+    }
+    if !(0.5..=1.0).contains(&budget.pressure_ratio) {
+        return Err("pressure_ratio must be between 0.5 and 1.0".to_string());
+    }
+    crate::preferences::update(|p| p.memory_budget = budget.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(check(&app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pressure_levels_follow_process_cap() {
+        let budget = MemoryBudget { process_cap_mb: 1000, pressure_ratio: 0.8, ..MemoryBudget::default() };
+        let mb = MB as u64;
+        assert_eq!(level_for(Some(700 * mb), &budget), PressureLevel::Normal);
+        assert_eq!(level_for(Some(850 * mb), &budget), PressureLevel::Elevated);
+        assert_eq!(level_for(Some(1000 * mb), &budget), PressureLevel::Critical);
+        assert_eq!(level_for(None, &budget), PressureLevel::Normal);
+    }
+}
@@ -0,0 +1,352 @@
+// Mic Quality - live clipping / signal-to-noise watch on the rep's microphone
+// Both engines feed the mic audio they send for transcription (after input gain).
+// Each second is summarized (clipped samples, 20ms frame levels) and the last
+// persist_seconds (30s) are judged together: clipping in a quarter of those seconds,
+// or - while the rep has been speaking (final mic transcripts in the window) - a very
+// quiet voice or speech barely above the background noise (low SNR), raises a
+// "mic_adjustment_suggested" event with localized advice. A problem is suggested once,
+// then again every REPEAT_MS while it persists. With auto_adjust_gain the live input
+// gain is also stepped down on clipping and up on a quiet voice (bounded, on top of
+// the level calibration gain), and the window restarts to judge the new level.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+use crate::adaptive_vad::LearnedThreshold;
+
+const FRAME_MS: usize = 20;
+const CLIP_LEVEL: f32 = 0.99;
+// A second "clips" when this share of its samples is at full scale
+const CLIPPED_SECOND_RATIO: f32 = 0.001;
+// Clipping is persistent when this share of the window's seconds clip
+const CLIPPED_SHARE: f32 = 0.25;
+// Speech level (RMS, 90th percentile) below which the voice is too quiet (~ -40 dBFS)
+const QUIET_SPEECH_RMS: f32 = 0.01;
+const REPEAT_MS: u64 = 120_000;
+// Live gain adjustment: step per suggestion and bounds
+const GAIN_STEP: f32 = 1.4;
+const MIN_GAIN_FACTOR: f32 = 0.25;
+const MAX_GAIN_FACTOR: f32 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum MicIssue {
+    Clipping,
+    QuietVoice,
+    Noisy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct MicQualitySettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Step the live input gain on clipping / a quiet voice
+    #[serde(default)]
+    pub auto_adjust_gain: bool,
+    #[serde(default = "default_min_snr_db")]
+    pub min_snr_db: f32,
+    /// How long a problem must last before it is suggested
+    #[serde(default = "default_persist_seconds")]
+    pub persist_seconds: u32,
+}
+
+fn default_true() -> bool { true }
+fn default_min_snr_db() -> f32 { 10.0 }
+fn default_persist_seconds() -> u32 { 30 }
+
+impl Default for MicQualitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_adjust_gain: false,
+            min_snr_db: default_min_snr_db(),
+            persist_seconds: default_persist_seconds(),
+        }
+    }
+}
+
+// Levels of the current window
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, specta::Type)]
+pub struct MicLevels {
+    pub seconds: u32,         // Audio in the window
+    pub clipped_share: f32,   // Share of its seconds that clipped
+    pub noise_floor: f32,     // RMS, 10th percentile of 20ms frames
+    pub speech_level: f32,    // RMS, 90th percentile
+    pub snr_db: f32,
+}
+
+// Payload of "mic_adjustment_suggested"
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct MicAdjustment {
+    pub issue: MicIssue,
+    pub advice: String,
+    pub levels: MicLevels,
+    pub gain_factor: f32,     // Live gain adjustment in effect (1.0 = none)
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct MicQualityStatus {
+    pub settings: MicQualitySettings,
+    pub levels: Option<MicLevels>,
+    pub gain_factor: f32,
+    pub vad_thresholds: Vec<LearnedThreshold>,  // Learned this call (adaptive_vad)
+}
+
+struct Second {
+    clipped: bool,
+}
+
+struct Monitor {
+    settings: MicQualitySettings,
+    sample_rate: u32,
+    frame: (f32, usize),             // Sum of squares, samples of the frame in progress
+    second: (usize, usize),          // Clipped, total samples of the second in progress
+    frames: VecDeque<f32>,           // Frame RMS over the window
+    seconds: VecDeque<Second>,
+    suggested_at: HashMap<MicIssue, u64>,
+}
+
+impl Monitor {
+    fn new(settings: MicQualitySettings, sample_rate: u32) -> Self {
+        Self {
+            settings,
+            sample_rate,
+            frame: (0.0, 0),
+            second: (0, 0),
+            frames: VecDeque::new(),
+            seconds: VecDeque::new(),
+            suggested_at: HashMap::new(),
+        }
+    }
+
+    fn restart_window(&mut self) {
+        self.frames.clear();
+        self.seconds.clear();
+    }
+
+    fn levels(&self) -> MicLevels {
+        let mut sorted: Vec<f32> = self.frames.iter().copied().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let percentile = |p: f32| sorted.get(((sorted.len().max(1) - 1) as f32 * p).round() as usize).copied().unwrap_or(0.0);
+        let (noise_floor, speech_level) = (percentile(0.1), percentile(0.9));
+        let clipped = self.seconds.iter().filter(|s| s.clipped).count();
+        MicLevels {
+            seconds: self.seconds.len() as u32,
+            clipped_share: clipped as f32 / self.seconds.len().max(1) as f32,
+            noise_floor,
+            speech_level,
+            snr_db: 20.0 * (speech_level.max(1e-6) / noise_floor.max(1e-6)).log10(),
+        }
+    }
+
+    /// Problems of a full window (the rep `speaking` in it enables the level checks)
+    fn issues(&self, levels: &MicLevels, speaking: bool) -> Vec<MicIssue> {
+        let mut issues = Vec::new();
+        if levels.clipped_share >= CLIPPED_SHARE {
+            issues.push(MicIssue::Clipping);
+        }
+        if speaking && levels.speech_level < QUIET_SPEECH_RMS {
+            issues.push(MicIssue::QuietVoice);
+        } else if speaking && levels.snr_db < self.settings.min_snr_db {
+            issues.push(MicIssue::Noisy);
+        }
+        issues
+    }
+
+    /// Add mic audio; returns the window's levels and problems each time a full
+    /// window completes another second
+    fn push(&mut self, samples: &[f32], speaking: bool) -> Option<(MicLevels, Vec<MicIssue>)> {
+        let frame_len = (self.sample_rate as usize * FRAME_MS / 1000).max(1);
+        let window = self.settings.persist_seconds.max(1) as usize;
+        let mut judged = None;
+        for &sample in samples {
+            self.frame.0 += sample * sample;
+            self.frame.1 += 1;
+            self.second.0 += (sample.abs() >= CLIP_LEVEL) as usize;
+            self.second.1 += 1;
+            if self.frame.1 == frame_len {
+                self.frames.push_back((self.frame.0 / frame_len as f32).sqrt());
+                if self.frames.len() > window * 1000 / FRAME_MS {
+                    self.frames.pop_front();
+                }
+                self.frame = (0.0, 0);
+            }
+            if self.second.1 == self.sample_rate as usize {
+                let clipped = self.second.0 as f32 / self.second.1 as f32 >= CLIPPED_SECOND_RATIO;
+                self.seconds.push_back(Second { clipped });
+                if self.seconds.len() > window {
+                    self.seconds.pop_front();
+                }
+                self.second = (0, 0);
+                if self.seconds.len() == window {
+                    let levels = self.levels();
+                    judged = Some((levels, self.issues(&levels, speaking)));
+                }
+            }
+        }
+        judged
+    }
+}
+
+static STATE: Lazy<Mutex<Option<Monitor>>> = Lazy::new(|| Mutex::new(None));
+// Live gain adjustment (f32 bits), read by the capture callbacks
+static GAIN_FACTOR: AtomicU32 = AtomicU32::new(0x3f80_0000);  // 1.0
+// When the rep's last final transcript arrived
+static REP_SPOKE_AT: AtomicU64 = AtomicU64::new(0);
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Multiplier for the mic input gain (1.0 unless auto_adjust_gain stepped it)
+pub fn gain_factor() -> f32 {
+    f32::from_bits(GAIN_FACTOR.load(Ordering::Relaxed))
+}
+
+/// A final transcript of the rep arrived (the level checks only run while the rep talks)
+pub fn rep_spoke() {
+    REP_SPOKE_AT.store(now_ms(), Ordering::Relaxed);
+}
+
+fn advice(issue: MicIssue, adjusted: bool) -> String {
+    crate::i18n::text(match (issue, adjusted) {
+        (MicIssue::Clipping, false) => "mic-clipping",
+        (MicIssue::Clipping, true) => "mic-clipping-adjusted",
+        (MicIssue::QuietVoice, false) => "mic-quiet",
+        (MicIssue::QuietVoice, true) => "mic-quiet-adjusted",
+        (MicIssue::Noisy, _) => "mic-noisy",
+    })
+}
+
+/// Step the live gain for an issue; the new factor when it changed
+fn adjust_gain(issue: MicIssue) -> Option<f32> {
+    let current = gain_factor();
+    let adjusted = match issue {
+        MicIssue::Clipping => current / GAIN_STEP,
+        MicIssue::QuietVoice => current * GAIN_STEP,
+        MicIssue::Noisy => return None,  // Gain raises the noise with the voice
+    }.clamp(MIN_GAIN_FACTOR, MAX_GAIN_FACTOR);
+    if (adjusted - current).abs() < f32::EPSILON {
+        return None;
+    }
+    GAIN_FACTOR.store(adjusted.to_bits(), Ordering::Relaxed);
+    Some(adjusted)
+}
+
+/// Capture callback: mic audio as sent for transcription (mono, after input gain)
+pub fn observe(app: &AppHandle, samples: &[f32], sample_rate: u32) {
+    // Never block the audio thread on the lock
+    let mut guard = match STATE.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+    if guard.as_ref().map_or(true, |m| m.sample_rate != sample_rate) {
+        *guard = Some(Monitor::new(crate::preferences::load().mic_quality, sample_rate));
+    }
+    let monitor = match guard.as_mut() {
+        Some(monitor) if monitor.settings.enabled => monitor,
+        _ => return,
+    };
+    let now = now_ms();
+    let window_ms = monitor.settings.persist_seconds as u64 * 1000;
+    let speaking = now.saturating_sub(REP_SPOKE_AT.load(Ordering::Relaxed)) < window_ms;
+    let (levels, issues) = match monitor.push(samples, speaking) {
+        Some(judged) => judged,
+        None => return,
+    };
+    monitor.suggested_at.retain(|issue, _| issues.contains(issue));
+
+    for issue in issues {
+        if monitor.suggested_at.get(&issue).map_or(false, |&at| now.saturating_sub(at) < REPEAT_MS) {
+            continue;
+        }
+        monitor.suggested_at.insert(issue, now);
+        let adjusted = if monitor.settings.auto_adjust_gain { adjust_gain(issue) } else { None };
+        if adjusted.is_some() {
+            monitor.restart_window();
+        }
+        let suggestion = MicAdjustment {
+            issue,
+            advice: advice(issue, adjusted.is_some()),
+            levels,
+            gain_factor: gain_factor(),
+            timestamp: now,
+        };
+        info!("🎙️ Mic {:?}: clipped {:.0}% of seconds, SNR {:.1} dB, speech {:.4}{}", issue,
+            levels.clipped_share * 100.0, levels.snr_db, levels.speech_level,
+            adjusted.map_or(String::new(), |factor| format!(", gain x{:.2}", factor)));
+        if let Err(e) = app.emit_all("mic_adjustment_suggested", suggestion) {
+            error!("Failed to emit mic_adjustment_suggested: {:?}", e);
+        }
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_mic_quality() -> Result<MicQualityStatus, String> {
+    let state = STATE.lock().unwrap();
+    Ok(MicQualityStatus {
+        settings: crate::preferences::load().mic_quality,
+        levels: state.as_ref().filter(|m| !m.seconds.is_empty()).map(Monitor::levels),
+        gain_factor: gain_factor(),
+        vad_thresholds: crate::adaptive_vad::learned_thresholds(),
+    })
+}
+
+// Applies at once; turning auto_adjust_gain off restores the unadjusted gain
+#[tauri::command]
+pub fn set_mic_quality(settings: MicQualitySettings) -> Result<MicQualityStatus, String> {
+    if settings.persist_seconds < 5 {
+        return Err("persist_seconds must be at least 5".to_string());
+    }
+    crate::preferences::update(|p| p.mic_quality = settings.clone())
+        .map_err(|e| e.to_string())?;
+    if !settings.auto_adjust_gain {
+        GAIN_FACTOR.store(1.0f32.to_bits(), Ordering::Relaxed);
+    }
+    if let Some(monitor) = STATE.lock().unwrap().as_mut() {
+        monitor.settings = settings;
+        monitor.restart_window();
+    }
+    get_mic_quality()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(seconds: usize, amplitude: f32) -> Vec<f32> {
+        (0..seconds * 1_000)
+            .map(|i| (2.0 * std::f32::consts::PI * 200.0 * i as f32 / 1_000.0).sin() * amplitude)
+            .collect()
+    }
+
+    #[test]
+    fn test_persistent_clipping_and_low_snr_are_flagged_after_the_window() {
+        let settings = MicQualitySettings { persist_seconds: 10, ..Default::default() };
+        // 1 kHz keeps the test small: 20-sample frames, 1000-sample seconds
+        let mut monitor = Monitor::new(settings.clone(), 1_000);
+
+        // Overdriven voice: nothing is judged until the window is full
+        let loud: Vec<f32> = tone(10, 1.5).iter().map(|s| s.clamp(-1.0, 1.0)).collect();
+        assert!(monitor.push(&loud[..9_000], false).is_none());
+        let (levels, issues) = monitor.push(&loud[9_000..], false).unwrap();
+        assert_eq!(issues, vec![MicIssue::Clipping]);
+        assert!(levels.clipped_share > 0.99);
+
+        // Speech 6 dB over a constant hum: noisy only while the rep is speaking
+        let mut monitor = Monitor::new(settings, 1_000);
+        let mut audio = tone(5, 0.1);
+        audio.extend(tone(5, 0.05));
+        assert_eq!(monitor.push(&audio, true).unwrap().1, vec![MicIssue::Noisy]);
+        monitor.restart_window();
+        assert_eq!(monitor.push(&audio, false).unwrap().1, vec![]);
+    }
+}
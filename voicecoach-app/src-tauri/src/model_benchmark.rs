@@ -0,0 +1,38 @@
+// Model Benchmark Command - measure an installed Vosk model on this machine
+// The benchmark is in voicecoach-core (re-exported here); the command runs it off the
+// async runtime and reports progress to the windows. Without a test set of its own the
+// reference clips bundled under resources/benchmark are used.
+
+pub use voicecoach_core::model_benchmark::*;
+
+use tauri::AppHandle;
+use log::{error, warn};
+
+use crate::event_sink::AppEvents;
+
+// Bundled reference clips (<name>.wav + <name>.txt), listed under bundle.resources
+const BUNDLED_TEST_SET: &str = "resources/benchmark";
+
+fn bundled_test_set(app: &AppHandle) -> Option<String> {
+    let dir = app.path_resolver().resolve_resource(BUNDLED_TEST_SET).filter(|dir| dir.is_dir());
+    if dir.is_none() {
+        warn!("⚠️ Bundled benchmark test set not found - falling back to synthetic speech");
+    }
+    dir.map(|dir| dir.to_string_lossy().to_string())
+}
+
+// ========== Tauri Commands ==========
+
+// RTF, memory footprint and accuracy of the model at `model_path`, on `test_set` (a
+// folder of <name>.wav + <name>.txt clips; the bundled reference clips by default)
+#[tauri::command]
+pub async fn benchmark_model(app: AppHandle, model_path: String, test_set: Option<String>) -> Result<ModelBenchmark, String> {
+    let test_set = test_set.or_else(|| bundled_test_set(&app));
+    tokio::task::spawn_blocking(move || benchmark(&AppEvents(app), &model_path, test_set.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| {
+            error!("❌ Model benchmark failed: {}", e);
+            e.to_string()
+        })
+}
@@ -0,0 +1,346 @@
+// No-Coach Zones - calls that are never recorded or coached without a deliberate override
+// Some calls should not be transcribed at all: HR meetings, a one-on-one with the
+// manager, the weekly legal review, a particular customer under NDA. A zone names them
+// by calendar event title keywords, by contact (name, phone, company, CRM id or
+// attendee) or by time window (weekdays and a local time range; a range may wrap past
+// midnight). The upcoming call is described with set_call_context (the calendar event
+// the frontend knows about) or by the dialer's start_session (the caller, forgotten at
+// its end_session). Both
+// transcription engines check the zones before any audio is captured: a match refuses
+// to start and emits "no_coach_zone_blocked". The rep can still go ahead by confirming
+// with override_no_coach_zone and a reason - that allows one start within
+// OVERRIDE_TTL_MS and appends an entry to the audit log (no_coach_audit.jsonl), which
+// managers can read with get_no_coach_audit.
+
+use anyhow::{Result, Context};
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+use crate::control_interface::CallerInfo;
+
+const AUDIT_FILE: &str = "no_coach_audit.jsonl";
+// An override allows one start this soon after it was confirmed
+const OVERRIDE_TTL_MS: u64 = 5 * 60 * 1000;
+// Phone numbers compare by their last digits (country and trunk prefixes vary)
+const PHONE_DIGITS: usize = 9;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TimeWindow {
+    /// Days the window applies, 0 = Monday; every day when empty
+    #[serde(default)]
+    pub days: Vec<u8>,
+    pub start: String,               // "HH:MM", local time
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct NoCoachZone {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Words or phrases in a calendar event title (whole words, case-insensitive)
+    #[serde(default)]
+    pub calendar_keywords: Vec<String>,
+    /// Names, phone numbers, companies, CRM ids or e-mail addresses
+    #[serde(default)]
+    pub contacts: Vec<String>,
+    #[serde(default)]
+    pub windows: Vec<TimeWindow>,
+}
+
+fn default_true() -> bool { true }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct CalendarEvent {
+    pub title: String,
+    #[serde(default)]
+    pub attendees: Vec<String>,
+}
+
+/// What is known about the call about to start
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct CallContext {
+    #[serde(default)]
+    pub calendar_event: Option<CalendarEvent>,
+    #[serde(default)]
+    pub contact: Option<CallerInfo>,
+}
+
+// Payload of "no_coach_zone_blocked" and result of check_no_coach_zone
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ZoneMatch {
+    pub zone_id: String,
+    pub zone_name: String,
+    pub matched: String,             // e.g. "calendar event \"HR sync\""
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct NoCoachOverride {
+    pub at: u64,
+    pub zone_id: String,
+    pub zone_name: String,
+    pub matched: String,
+    pub reason: String,
+    pub context: CallContext,
+}
+
+static CONTEXT: Lazy<Mutex<CallContext>> = Lazy::new(|| Mutex::new(CallContext::default()));
+// Zone overridden and when, until a start uses it
+static OVERRIDE: Lazy<Mutex<Option<(String, u64)>>> = Lazy::new(|| Mutex::new(None));
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+fn phone_tail(value: &str) -> Option<String> {
+    let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+    (digits.len() >= 7).then(|| digits[digits.len().saturating_sub(PHONE_DIGITS)..].to_string())
+}
+
+fn same_contact(entry: &str, value: &str) -> bool {
+    let (entry, value) = (entry.trim(), value.trim());
+    if entry.is_empty() || value.is_empty() {
+        return false;
+    }
+    match (phone_tail(entry), phone_tail(value)) {
+        (Some(a), Some(b)) => a == b,
+        _ => entry.eq_ignore_ascii_case(value),
+    }
+}
+
+impl TimeWindow {
+    fn contains(&self, at: NaiveDateTime) -> bool {
+        let (start, end) = match (parse_time(&self.start), parse_time(&self.end)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return false,
+        };
+        let time = NaiveTime::from_hms_opt(at.hour(), at.minute(), 0).unwrap_or(start);
+        // A window past midnight belongs to the day it started on
+        let (inside, day) = if start <= end {
+            (time >= start && time < end, at.weekday())
+        } else if time >= start {
+            (true, at.weekday())
+        } else {
+            (time < end, at.weekday().pred())
+        };
+        inside && (self.days.is_empty() || self.days.contains(&(day.num_days_from_monday() as u8)))
+    }
+}
+
+impl NoCoachZone {
+    /// What of the call puts it in this zone
+    fn matches(&self, context: &CallContext, at: NaiveDateTime) -> Option<String> {
+        if let Some(event) = &context.calendar_event {
+            if crate::call_analytics::find_phrase(&event.title, &self.calendar_keywords).is_some() {
+                return Some(format!("calendar event \"{}\"", event.title));
+            }
+            if let Some(attendee) = event.attendees.iter().find(|a| self.contacts.iter().any(|c| same_contact(c, a))) {
+                return Some(format!("attendee {}", attendee));
+            }
+        }
+        if let Some(caller) = &context.contact {
+            let fields = [&caller.name, &caller.phone, &caller.company, &caller.crm_id];
+            if let Some(value) = fields.iter().filter_map(|f| f.as_deref()).find(|v| self.contacts.iter().any(|c| same_contact(c, v))) {
+                return Some(format!("contact {}", value));
+            }
+        }
+        self.windows.iter().find(|w| w.contains(at))
+            .map(|w| format!("time window {}-{}", w.start, w.end))
+    }
+}
+
+/// First enabled zone the call falls in
+fn matching_zone(zones: &[NoCoachZone], context: &CallContext, at: NaiveDateTime) -> Option<ZoneMatch> {
+    zones.iter().filter(|z| z.enabled).find_map(|zone| {
+        zone.matches(context, at).map(|matched| ZoneMatch { zone_id: zone.id.clone(), zone_name: zone.name.clone(), matched })
+    })
+}
+
+fn current_match() -> Option<ZoneMatch> {
+    let zones = crate::preferences::load().no_coach_zones;
+    if zones.is_empty() {
+        return None;
+    }
+    let context = CONTEXT.lock().unwrap().clone();
+    matching_zone(&zones, &context, chrono::Local::now().naive_local())
+}
+
+/// The dialer started a session for this caller
+pub fn set_caller(caller: &CallerInfo) {
+    CONTEXT.lock().unwrap().contact = Some(caller.clone());
+}
+
+/// The dialer ended its session: the next call is someone else
+pub fn call_ended() {
+    *CONTEXT.lock().unwrap() = CallContext::default();
+}
+
+/// Refuse to start capture for a call in a no-coach zone, unless it was just overridden
+pub fn ensure_allowed(app: &AppHandle) -> Result<(), String> {
+    let zone = match current_match() {
+        Some(zone) => zone,
+        None => return Ok(()),
+    };
+    let mut overridden = OVERRIDE.lock().unwrap();
+    if let Some((zone_id, at)) = overridden.as_ref() {
+        if *zone_id == zone.zone_id && now_ms().saturating_sub(*at) <= OVERRIDE_TTL_MS {
+            info!("⚠️ Starting in no-coach zone \"{}\" (overridden)", zone.zone_name);
+            *overridden = None;
+            return Ok(());
+        }
+    }
+    info!("🚫 Not starting: {} is in no-coach zone \"{}\"", zone.matched, zone.zone_name);
+    let message = format!("No-coach zone \"{}\" ({}) - confirm with override_no_coach_zone to start anyway", zone.zone_name, zone.matched);
+    if let Err(e) = app.emit_all("no_coach_zone_blocked", zone) {
+        error!("Failed to emit no_coach_zone_blocked: {:?}", e);
+    }
+    Err(message)
+}
+
+fn audit_path() -> PathBuf {
+    crate::app_paths::data_dir().join(AUDIT_FILE)
+}
+
+fn append_audit(entry: &NoCoachOverride) -> Result<()> {
+    let path = audit_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)
+        .context(format!("Failed to open the no-coach audit log {:?}", path))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    file.sync_data()?;
+    Ok(())
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_no_coach_zones() -> Result<Vec<NoCoachZone>, String> {
+    Ok(crate::preferences::load().no_coach_zones)
+}
+
+#[tauri::command]
+pub fn set_no_coach_zones(zones: Vec<NoCoachZone>) -> Result<Vec<NoCoachZone>, String> {
+    for zone in &zones {
+        if zone.id.trim().is_empty() || zones.iter().filter(|z| z.id == zone.id).count() > 1 {
+            return Err(format!("Zone \"{}\" needs a unique id", zone.name));
+        }
+        for window in &zone.windows {
+            if parse_time(&window.start).is_none() || parse_time(&window.end).is_none() {
+                return Err(format!("Zone \"{}\": times are HH:MM", zone.name));
+            }
+            if window.days.iter().any(|&d| d > 6) {
+                return Err(format!("Zone \"{}\": days are 0 (Monday) to 6", zone.name));
+            }
+        }
+    }
+    crate::preferences::update(|p| p.no_coach_zones = zones.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(zones)
+}
+
+// Describe the call about to start (None clears it)
+#[tauri::command]
+pub fn set_call_context(context: Option<CallContext>) -> Result<Option<ZoneMatch>, String> {
+    *CONTEXT.lock().unwrap() = context.unwrap_or_default();
+    Ok(current_match())
+}
+
+// The zone the upcoming call is in, if any
+#[tauri::command]
+pub fn check_no_coach_zone() -> Result<Option<ZoneMatch>, String> {
+    Ok(current_match())
+}
+
+// The rep confirmed starting in a zone: the next start within 5 minutes goes ahead
+#[tauri::command]
+pub fn override_no_coach_zone(zone_id: String, reason: String) -> Result<NoCoachOverride, String> {
+    if reason.trim().is_empty() {
+        return Err("Give a reason for the override".to_string());
+    }
+    let zone = current_match().filter(|z| z.zone_id == zone_id)
+        .ok_or_else(|| format!("The upcoming call is not in zone {}", zone_id))?;
+    let entry = NoCoachOverride {
+        at: now_ms(),
+        zone_id: zone.zone_id,
+        zone_name: zone.zone_name,
+        matched: zone.matched,
+        reason: reason.trim().to_string(),
+        context: CONTEXT.lock().unwrap().clone(),
+    };
+    append_audit(&entry).map_err(|e| e.to_string())?;
+    warn!("⚠️ No-coach zone \"{}\" overridden: {}", entry.zone_name, entry.reason);
+    *OVERRIDE.lock().unwrap() = Some((entry.zone_id.clone(), entry.at));
+    Ok(entry)
+}
+
+// Every override, oldest first
+#[tauri::command]
+pub fn get_no_coach_audit() -> Result<Vec<NoCoachOverride>, String> {
+    let contents = match fs::read_to_string(audit_path()) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(Vec::new()),
+    };
+    Ok(contents.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_zones_match_by_calendar_contact_and_window() {
+        let zones = vec![
+            NoCoachZone {
+                id: "hr".to_string(), name: "HR".to_string(), enabled: true,
+                calendar_keywords: vec!["HR".to_string(), "performance review".to_string()],
+                contacts: vec!["+1 (555) 010-0199".to_string(), "legal@acme.com".to_string()],
+                windows: Vec::new(),
+            },
+            NoCoachZone {
+                id: "late".to_string(), name: "Night shift".to_string(), enabled: true,
+                calendar_keywords: Vec::new(), contacts: Vec::new(),
+                // Fridays 22:00 to Saturday 02:00
+                windows: vec![TimeWindow { days: vec![4], start: "22:00".to_string(), end: "02:00".to_string() }],
+            },
+        ];
+        let monday_noon = at("2026-10-12 12:00");
+        let event = |title: &str, attendees: &[&str]| CallContext {
+            calendar_event: Some(CalendarEvent { title: title.to_string(), attendees: attendees.iter().map(|a| a.to_string()).collect() }),
+            contact: None,
+        };
+
+        assert_eq!(matching_zone(&zones, &event("Quarterly hr sync", &[]), monday_noon).unwrap().zone_id, "hr");
+        assert!(matching_zone(&zones, &event("Chrome demo for Acme", &[]), monday_noon).is_none());
+        assert_eq!(matching_zone(&zones, &event("Demo", &["LEGAL@acme.com"]), monday_noon).unwrap().matched, "attendee LEGAL@acme.com");
+        let dialed = CallContext { calendar_event: None, contact: Some(CallerInfo { phone: Some("5550100199".to_string()), ..Default::default() }) };
+        assert_eq!(matching_zone(&zones, &dialed, monday_noon).unwrap().matched, "contact 5550100199");
+
+        // The window runs past midnight on the day it started
+        let nothing = CallContext::default();
+        assert_eq!(matching_zone(&zones, &nothing, at("2026-10-16 23:30")).unwrap().zone_id, "late");
+        assert!(matching_zone(&zones, &nothing, at("2026-10-17 01:15")).is_some());
+        assert!(matching_zone(&zones, &nothing, at("2026-10-17 23:30")).is_none());
+        assert!(matching_zone(&zones, &nothing, at("2026-10-16 21:59")).is_none());
+
+        let disabled: Vec<NoCoachZone> = zones.into_iter().map(|z| NoCoachZone { enabled: false, ..z }).collect();
+        assert!(matching_zone(&disabled, &event("HR", &[]), monday_noon).is_none());
+    }
+}
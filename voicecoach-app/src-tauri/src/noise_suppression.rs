@@ -0,0 +1,353 @@
+// Noise Suppression - spectral subtraction of a captured background noise profile
+// Server-room fans, air conditioning and open-office hum are steady enough to learn:
+// capture_noise_profile records a few seconds of the room with the rep silent and keeps
+// the noise spectrum of its quietest frames (the rep breathing or a chair creaking
+// doesn't make it in). With a profile, the rep's microphone is cleaned before it is
+// transcribed: 32 ms overlapping frames, each bin reduced by over_subtraction times the
+// profile's magnitude and kept above spectral_floor of its own so the remaining noise
+// doesn't turn into "musical" chirps. It costs one frame of latency. The detectors
+// that read the raw signal (mic quality, headset mute) still hear the microphone as
+// it is. On for each new call when enabled; set_session_noise_suppression switches it
+// for the call in progress only. One profile is kept, with the device it was captured
+// on; capture a new one after moving desks or changing headsets.
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use log::{info, error};
+
+use crate::hold_detection::fft;
+
+const DEFAULT_SECONDS: u32 = 5;
+// Analysis frame length, rounded up to a power of two for the FFT
+const FRAME_MS: u32 = 32;
+// Share of the captured frames (the quietest) that make up the profile
+const QUIET_SHARE: f32 = 0.5;
+// Quieter than this is a muted or disconnected microphone, not a room (RMS)
+const MIN_NOISE_RMS: f32 = 1e-5;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct NoiseProfile {
+    pub device: String,
+    pub bin_hz: f32,                 // Frequency step of `power`
+    pub power: Vec<f32>,             // Mean noise power per bin, normalized by frame length
+    pub noise_rms: f32,
+    pub captured_at: u64,
+}
+
+impl NoiseProfile {
+    /// Noise power at a frequency (linear between bins)
+    fn power_at(&self, hz: f32) -> f32 {
+        let position = hz / self.bin_hz;
+        let below = position.floor() as usize;
+        if below + 1 >= self.power.len() {
+            return self.power.last().copied().unwrap_or(0.0);
+        }
+        let frac = position - below as f32;
+        self.power[below] * (1.0 - frac) + self.power[below + 1] * frac
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct NoiseSuppressionSettings {
+    /// Suppress noise on new calls (needs a profile)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Multiple of the noise magnitude subtracted from each bin
+    #[serde(default = "default_over_subtraction")]
+    pub over_subtraction: f32,
+    /// Share of a bin's magnitude always kept
+    #[serde(default = "default_spectral_floor")]
+    pub spectral_floor: f32,
+    #[serde(default)]
+    pub profile: Option<NoiseProfile>,
+}
+
+fn default_over_subtraction() -> f32 { 2.0 }
+fn default_spectral_floor() -> f32 { 0.05 }
+
+impl Default for NoiseSuppressionSettings {
+    fn default() -> Self {
+        Self { enabled: false, over_subtraction: default_over_subtraction(), spectral_floor: default_spectral_floor(), profile: None }
+    }
+}
+
+// Suppression for the call in progress (reset from the settings at each call)
+static SESSION_ENABLED: AtomicBool = AtomicBool::new(false);
+
+fn frame_len(sample_rate: u32) -> usize {
+    ((sample_rate * FRAME_MS / 1000) as usize).next_power_of_two()
+}
+
+/// Periodic Hann window: at half-frame hops the windows sum to one
+fn hann(n: usize) -> Vec<f32> {
+    (0..n).map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / n as f32).cos()).collect()
+}
+
+/// Profile of the quietest frames of a recording of the room
+fn build_profile(device: &str, samples: &[f32], sample_rate: u32) -> Result<NoiseProfile, String> {
+    let n = frame_len(sample_rate);
+    let window = hann(n);
+    let mut frames: Vec<(f32, Vec<f32>)> = samples.windows(n).step_by(n / 2).map(|frame| {
+        let rms = (frame.iter().map(|s| s * s).sum::<f32>() / n as f32).sqrt();
+        let mut re: Vec<f32> = frame.iter().zip(&window).map(|(s, w)| s * w).collect();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im);
+        (rms, (0..=n / 2).map(|k| (re[k] * re[k] + im[k] * im[k]) / n as f32).collect())
+    }).collect();
+    if frames.len() < 10 {
+        return Err("Not enough audio for a noise profile".to_string());
+    }
+    frames.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    frames.truncate(((frames.len() as f32 * QUIET_SHARE) as usize).max(1));
+    let noise_rms = frames.iter().map(|f| f.0).sum::<f32>() / frames.len() as f32;
+    if noise_rms < MIN_NOISE_RMS {
+        return Err("No background noise was heard - is the microphone muted?".to_string());
+    }
+    let mut power = vec![0.0; n / 2 + 1];
+    for (_, spectrum) in &frames {
+        for (total, p) in power.iter_mut().zip(spectrum) {
+            *total += p / frames.len() as f32;
+        }
+    }
+    Ok(NoiseProfile {
+        device: device.to_string(),
+        bin_hz: sample_rate as f32 / n as f32,
+        power,
+        noise_rms,
+        captured_at: chrono::Utc::now().timestamp_millis() as u64,
+    })
+}
+
+/// Streaming spectral subtraction for one capture stream
+pub struct NoiseSuppressor {
+    frame_len: usize,
+    window: Vec<f32>,
+    noise: Vec<f32>,                 // Noise magnitude per bin at this stream's rate
+    over_subtraction: f32,
+    spectral_floor: f32,
+    input: Vec<f32>,
+    overlap: Vec<f32>,
+    output: VecDeque<f32>,
+}
+
+/// Suppressor for the rep's microphone stream (None without a profile)
+pub fn suppressor(sample_rate: u32) -> Option<NoiseSuppressor> {
+    let settings = crate::preferences::load().noise_suppression;
+    let profile = settings.profile?;
+    let n = frame_len(sample_rate);
+    let noise = (0..=n / 2)
+        .map(|k| (profile.power_at(k as f32 * sample_rate as f32 / n as f32) * n as f32).sqrt())
+        .collect();
+    Some(NoiseSuppressor {
+        frame_len: n,
+        window: hann(n),
+        noise,
+        over_subtraction: settings.over_subtraction,
+        spectral_floor: settings.spectral_floor,
+        input: Vec::with_capacity(2 * n),
+        overlap: vec![0.0; n],
+        output: VecDeque::from(vec![0.0; n]),
+    })
+}
+
+impl NoiseSuppressor {
+    /// Clean a mono buffer in place (one frame behind), unless switched off for this call
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if !SESSION_ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        let n = self.frame_len;
+        self.input.extend_from_slice(samples);
+        while self.input.len() >= n {
+            self.subtract_frame();
+            self.input.drain(..n / 2);
+        }
+        for sample in samples.iter_mut() {
+            *sample = self.output.pop_front().unwrap_or(0.0);
+        }
+    }
+
+    fn subtract_frame(&mut self) {
+        let n = self.frame_len;
+        let mut re: Vec<f32> = self.input[..n].iter().zip(&self.window).map(|(s, w)| s * w).collect();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im);
+        for k in 0..n {
+            let bin = if k <= n / 2 { k } else { n - k };
+            let magnitude = (re[k] * re[k] + im[k] * im[k]).sqrt();
+            if magnitude > 0.0 {
+                let kept = (magnitude - self.over_subtraction * self.noise[bin]).max(self.spectral_floor * magnitude);
+                re[k] *= kept / magnitude;
+                im[k] *= kept / magnitude;
+            }
+        }
+        // Inverse transform through the forward one on the conjugate
+        im.iter_mut().for_each(|v| *v = -*v);
+        fft(&mut re, &mut im);
+        for (acc, value) in self.overlap.iter_mut().zip(&re) {
+            *acc += value / n as f32;
+        }
+        self.output.extend(self.overlap.drain(..n / 2));
+        self.overlap.resize(n, 0.0);
+    }
+}
+
+/// Start of a new call: suppression back to the setting
+pub fn begin_call() {
+    let settings = crate::preferences::load().noise_suppression;
+    SESSION_ENABLED.store(settings.enabled && settings.profile.is_some(), Ordering::Relaxed);
+}
+
+fn record_room(seconds: u32) -> Result<NoiseProfile, String> {
+    let device = crate::device_selection::select_input_device(&cpal::default_host())
+        .ok_or("No input device available")?;
+    let device_name = device.name().unwrap_or_default();
+    let supported = device.default_input_config().map_err(|e| format!("Failed to get device config: {}", e))?;
+    let channels = supported.channels() as usize;
+    let sample_rate = supported.sample_rate().0;
+    let sample_format = supported.sample_format();
+    let config: cpal::StreamConfig = supported.into();
+    info!("🤫 Capturing the noise profile of '{}' for {}s", device_name, seconds);
+
+    let recorded = Arc::new(Mutex::new(Vec::<f32>::new()));
+    let sink = recorded.clone();
+    let stream = crate::sample_format::build_input_stream(
+        &device,
+        &config,
+        sample_format,
+        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+            let mono = if channels > 1 { crate::device_conflict::downmix_to_mono(data, channels) } else { data.to_vec() };
+            sink.lock().unwrap().extend(mono);
+        },
+        |err| error!("❌ Noise profile stream error: {:?}", err),
+    ).map_err(|e| format!("Failed to open '{}': {}", device_name, e))?;
+    stream.play().map_err(|e| format!("Failed to start stream: {}", e))?;
+    std::thread::sleep(Duration::from_secs(seconds as u64));
+    drop(stream);
+
+    let samples = recorded.lock().unwrap().clone();
+    let profile = build_profile(&device_name, &samples, sample_rate)?;
+    info!("✅ Noise profile of '{}': noise floor {:.5} RMS", device_name, profile.noise_rms);
+    Ok(profile)
+}
+
+// ========== Tauri Commands ==========
+
+// Record `seconds` (default 5) of the room with the rep silent and suppress that noise
+// from now on (the call in progress picks it up with its next microphone stream)
+#[tauri::command]
+pub async fn capture_noise_profile(seconds: Option<u32>) -> Result<NoiseProfile, String> {
+    if crate::privacy::is_muted() {
+        return Err("Capture is muted (privacy mode)".to_string());
+    }
+    let seconds = seconds.unwrap_or(DEFAULT_SECONDS).clamp(2, 30);
+    let profile = tokio::task::spawn_blocking(move || record_room(seconds))
+        .await
+        .map_err(|e| e.to_string())??;
+    let stored = profile.clone();
+    crate::preferences::update(|p| {
+        p.noise_suppression.profile = Some(stored);
+        p.noise_suppression.enabled = true;
+    }).map_err(|e| e.to_string())?;
+    SESSION_ENABLED.store(true, Ordering::Relaxed);
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn get_noise_suppression() -> Result<NoiseSuppressionSettings, String> {
+    Ok(crate::preferences::load().noise_suppression)
+}
+
+// The stored profile is kept (capture_noise_profile replaces it, clear_noise_profile drops it)
+#[tauri::command]
+pub fn set_noise_suppression(enabled: bool, over_subtraction: f32, spectral_floor: f32) -> Result<NoiseSuppressionSettings, String> {
+    if !(1.0..=4.0).contains(&over_subtraction) {
+        return Err("over_subtraction must be between 1 and 4".to_string());
+    }
+    if !(0.0..=0.5).contains(&spectral_floor) {
+        return Err("spectral_floor must be between 0 and 0.5".to_string());
+    }
+    crate::preferences::update(|p| {
+        p.noise_suppression.enabled = enabled;
+        p.noise_suppression.over_subtraction = over_subtraction;
+        p.noise_suppression.spectral_floor = spectral_floor;
+    }).map(|p| p.noise_suppression).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn clear_noise_profile() -> Result<NoiseSuppressionSettings, String> {
+    SESSION_ENABLED.store(false, Ordering::Relaxed);
+    crate::preferences::update(|p| p.noise_suppression.profile = None)
+        .map(|p| p.noise_suppression)
+        .map_err(|e| e.to_string())
+}
+
+// Switch suppression for the call in progress only
+#[tauri::command]
+pub fn set_session_noise_suppression(enabled: bool) -> Result<bool, String> {
+    if enabled && crate::preferences::load().noise_suppression.profile.is_none() {
+        return Err("Capture a noise profile first".to_string());
+    }
+    SESSION_ENABLED.store(enabled, Ordering::Relaxed);
+    info!("🤫 Noise suppression {} for this call", if enabled { "on" } else { "off" });
+    Ok(enabled)
+}
+
+#[tauri::command]
+pub fn get_session_noise_suppression() -> Result<bool, String> {
+    Ok(SESSION_ENABLED.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(len: usize, seed: u32) -> Vec<f32> {
+        let mut state = seed;
+        (0..len).map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            0.01 * ((state >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0)
+        }).collect()
+    }
+
+    fn energy(samples: &[f32]) -> f32 {
+        samples.iter().map(|s| s * s).sum()
+    }
+
+    #[test]
+    fn test_profiled_noise_is_removed_and_speech_band_tone_kept() {
+        let rate = 16_000;
+        let profile = build_profile("test", &noise(5 * rate as usize, 1), rate).unwrap();
+        assert_eq!(profile.power.len(), 257);
+        assert!(build_profile("test", &vec![0.0; 5 * rate as usize], rate).is_err());
+
+        let n = frame_len(rate);
+        let noise_mag = (0..=n / 2).map(|k| (profile.power_at(k as f32 * profile.bin_hz) * n as f32).sqrt()).collect();
+        let mut suppressor = NoiseSuppressor {
+            frame_len: n, window: hann(n), noise: noise_mag, over_subtraction: 2.0, spectral_floor: 0.05,
+            input: Vec::new(), overlap: vec![0.0; n], output: VecDeque::from(vec![0.0; n]),
+        };
+        SESSION_ENABLED.store(true, Ordering::Relaxed);
+        let mut run = |input: &[f32]| -> Vec<f32> {
+            let mut out = input.to_vec();
+            out.chunks_mut(160).for_each(|chunk| suppressor.process(chunk));
+            out
+        };
+
+        // The same room, later: over 15 dB quieter once the first frames are through
+        let room = noise(3 * rate as usize, 7);
+        let cleaned = run(&room);
+        assert!(energy(&cleaned[2 * n..]) < energy(&room[2 * n..]) * 0.03);
+
+        // A 1 kHz tone in that noise comes through (one frame late)
+        let tone: Vec<f32> = (0..3 * rate as usize).map(|i| 0.3 * (2.0 * std::f32::consts::PI * 1_000.0 * i as f32 / rate as f32).sin()).collect();
+        let noisy: Vec<f32> = tone.iter().zip(noise(tone.len(), 11)).map(|(t, n)| t + n).collect();
+        let cleaned = run(&noisy);
+        let ratio = energy(&cleaned[2 * n..]) / energy(&tone[n..tone.len() - n]);
+        assert!((0.85..1.1).contains(&ratio), "{}", ratio);
+    }
+}
@@ -0,0 +1,330 @@
+// OBS Integration - pushes live captions and coaching tips into OBS text sources
+// Speaks the obs-websocket 5.x protocol (OBS 28+ ships it built in, default port 4455)
+// so sales trainers streaming mock calls can show what the coach sees on stream
+
+use anyhow::{Result, anyhow, Context};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use futures_util::{SinkExt, StreamExt};
+use log::{info, warn, error};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type ObsSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// obs-websocket 5.x opcodes
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+const RPC_VERSION: u64 = 1;
+
+// Keep the caption source readable on stream
+const MAX_CAPTION_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ObsSettings {
+    #[serde(default = "default_host")]
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Name of the OBS text source that receives live captions
+    #[serde(default = "default_caption_source")]
+    pub caption_source: String,
+    /// Name of the OBS text source that receives the current coaching tip
+    #[serde(default = "default_tip_source")]
+    pub tip_source: String,
+}
+
+fn default_host() -> String { "localhost".to_string() }
+fn default_port() -> u16 { 4455 }
+fn default_caption_source() -> String { "VoiceCoach Captions".to_string() }
+fn default_tip_source() -> String { "VoiceCoach Tip".to_string() }
+
+impl Default for ObsSettings {
+    fn default() -> Self {
+        Self {
+            host: default_host(),
+            port: default_port(),
+            password: None,
+            caption_source: default_caption_source(),
+            tip_source: default_tip_source(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ObsStatus {
+    pub connected: bool,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub caption_source: Option<String>,
+    pub tip_source: Option<String>,
+}
+
+enum ObsUpdate {
+    SetText { source: String, text: String },
+    Shutdown,
+}
+
+static OBS_CONNECTED: AtomicBool = AtomicBool::new(false);
+static OBS_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+static OBS_SENDER: Lazy<Mutex<Option<UnboundedSender<ObsUpdate>>>> = Lazy::new(|| Mutex::new(None));
+// Id of the connection OBS_SENDER belongs to (changed under the OBS_SENDER lock)
+static OBS_CONNECTION: AtomicU64 = AtomicU64::new(0);
+static OBS_SETTINGS: Lazy<Mutex<Option<ObsSettings>>> = Lazy::new(|| Mutex::new(None));
+
+/// obs-websocket auth: base64(sha256(base64(sha256(password + salt)) + challenge))
+fn auth_string(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{}{}", password, salt).as_bytes()));
+    BASE64.encode(Sha256::digest(format!("{}{}", secret, challenge).as_bytes()))
+}
+
+fn next_request_id() -> String {
+    format!("voicecoach-{}", OBS_REQUEST_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+fn request_message(request_type: &str, request_data: Value) -> Message {
+    Message::Text(json!({
+        "op": OP_REQUEST,
+        "d": {
+            "requestType": request_type,
+            "requestId": next_request_id(),
+            "requestData": request_data,
+        }
+    }).to_string())
+}
+
+/// Read messages until one with the given opcode arrives
+async fn wait_for_op(socket: &mut ObsSocket, op: u64) -> Result<Value> {
+    while let Some(msg) = socket.next().await {
+        match msg? {
+            Message::Text(text) => {
+                let value: Value = serde_json::from_str(&text)?;
+                if value["op"].as_u64() == Some(op) {
+                    return Ok(value["d"].clone());
+                }
+            }
+            Message::Close(frame) => {
+                let reason = frame
+                    .map(|f| format!("{} ({})", f.reason, u16::from(f.code)))
+                    .unwrap_or_default();
+                return Err(anyhow!("OBS closed the connection: {}", reason));
+            }
+            _ => {}
+        }
+    }
+    Err(anyhow!("OBS connection ended during handshake"))
+}
+
+/// Connect and complete the Hello/Identify handshake
+async fn connect_and_identify(settings: &ObsSettings) -> Result<ObsSocket> {
+    let url = format!("ws://{}:{}", settings.host, settings.port);
+    let (mut socket, _) = connect_async(url.as_str())
+        .await
+        .context(format!("Failed to connect to OBS at {}. Is obs-websocket enabled?", url))?;
+
+    let hello = wait_for_op(&mut socket, OP_HELLO).await?;
+
+    let mut identify = json!({ "rpcVersion": RPC_VERSION, "eventSubscriptions": 0 });
+    if let Some(auth) = hello.get("authentication") {
+        let password = settings.password.as_deref()
+            .ok_or_else(|| anyhow!("OBS requires a password but none was configured"))?;
+        let challenge = auth["challenge"].as_str().unwrap_or_default();
+        let salt = auth["salt"].as_str().unwrap_or_default();
+        identify["authentication"] = json!(auth_string(password, salt, challenge));
+    }
+
+    socket.send(Message::Text(json!({ "op": OP_IDENTIFY, "d": identify }).to_string())).await?;
+    wait_for_op(&mut socket, OP_IDENTIFIED).await
+        .context("OBS rejected the identify request (wrong password?)")?;
+
+    Ok(socket)
+}
+
+/// Background task owning the socket; drains updates until shutdown or disconnect
+async fn run_connection(socket: ObsSocket, mut updates: mpsc::UnboundedReceiver<ObsUpdate>, connection: u64) {
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Some(ObsUpdate::SetText { source, text }) => {
+                    let request = request_message("SetInputSettings", json!({
+                        "inputName": source,
+                        "inputSettings": { "text": text },
+                        "overlay": true,
+                    }));
+                    if let Err(e) = ws_sender.send(request).await {
+                        error!("❌ LED 8304: Failed to send text to OBS: {}", e);
+                        break;
+                    }
+                }
+                Some(ObsUpdate::Shutdown) | None => {
+                    let _ = ws_sender.close().await;
+                    break;
+                }
+            },
+            msg = ws_receiver.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    if let Ok(value) = serde_json::from_str::<Value>(&text) {
+                        let status = &value["d"]["requestStatus"];
+                        if value["op"].as_u64() == Some(OP_REQUEST_RESPONSE) && status["result"] == json!(false) {
+                            warn!("⚠️ LED 8305: OBS request failed: {}", status["comment"].as_str().unwrap_or("unknown error"));
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    warn!("⚠️ LED 8306: OBS closed the connection");
+                    break;
+                }
+                Some(Err(e)) => {
+                    error!("❌ LED 8306: OBS connection error: {}", e);
+                    break;
+                }
+                _ => {}
+            },
+        }
+    }
+
+    // A reconnect may have installed a newer connection by now; leave that one alone
+    let mut sender = OBS_SENDER.lock().unwrap();
+    if OBS_CONNECTION.load(Ordering::SeqCst) == connection {
+        sender.take();
+        OBS_CONNECTED.store(false, Ordering::Relaxed);
+    }
+    info!("🔌 LED 8307: OBS connection task finished");
+}
+
+fn push_text(source_of: impl Fn(&ObsSettings) -> String, text: &str) {
+    if !OBS_CONNECTED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let source = match OBS_SETTINGS.lock().unwrap().as_ref() {
+        Some(settings) => source_of(settings),
+        None => return,
+    };
+
+    if let Some(sender) = OBS_SENDER.lock().unwrap().as_ref() {
+        let _ = sender.send(ObsUpdate::SetText { source, text: text.to_string() });
+    }
+}
+
+/// Push a final transcript line to the caption source (no-op when not connected).
+/// Safe to call from audio threads - the socket lives on its own task.
+pub fn publish_caption(text: &str) {
+    let chars: Vec<char> = text.chars().collect();
+    let start = chars.len().saturating_sub(MAX_CAPTION_CHARS);
+    let caption: String = chars[start..].iter().collect();
+    push_text(|s| s.caption_source.clone(), &caption);
+}
+
+/// Push the current coaching tip to the tip source (no-op when not connected)
+pub fn publish_coaching_tip(text: &str) {
+    push_text(|s| s.tip_source.clone(), text);
+}
+
+fn current_status() -> ObsStatus {
+    let settings = OBS_SETTINGS.lock().unwrap().clone();
+    ObsStatus {
+        connected: OBS_CONNECTED.load(Ordering::Relaxed),
+        host: settings.as_ref().map(|s| s.host.clone()),
+        port: settings.as_ref().map(|s| s.port),
+        caption_source: settings.as_ref().map(|s| s.caption_source.clone()),
+        tip_source: settings.map(|s| s.tip_source),
+    }
+}
+
+fn shutdown_connection() {
+    if let Some(sender) = OBS_SENDER.lock().unwrap().take() {
+        let _ = sender.send(ObsUpdate::Shutdown);
+    }
+    OBS_CONNECTED.store(false, Ordering::Relaxed);
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub async fn connect_obs(settings: Option<ObsSettings>) -> Result<ObsStatus, String> {
+    let settings = settings.unwrap_or_default();
+    info!("🎬 LED 8300: Connecting to OBS at {}:{}", settings.host, settings.port);
+
+    shutdown_connection();
+
+    let socket = connect_and_identify(&settings).await.map_err(|e| {
+        error!("❌ LED 8301: OBS connection failed: {}", e);
+        e.to_string()
+    })?;
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    *OBS_SETTINGS.lock().unwrap() = Some(settings);
+    let connection = {
+        let mut sender = OBS_SENDER.lock().unwrap();
+        *sender = Some(tx);
+        OBS_CONNECTED.store(true, Ordering::Relaxed);
+        OBS_CONNECTION.fetch_add(1, Ordering::SeqCst) + 1
+    };
+    tokio::spawn(run_connection(socket, rx, connection));
+
+    info!("✅ LED 8302: Connected to OBS");
+    Ok(current_status())
+}
+
+#[tauri::command]
+pub fn disconnect_obs() -> Result<ObsStatus, String> {
+    info!("🎬 LED 8303: Disconnecting from OBS");
+    shutdown_connection();
+    Ok(current_status())
+}
+
+#[tauri::command]
+pub fn get_obs_status() -> Result<ObsStatus, String> {
+    Ok(current_status())
+}
+
+// Test connection settings without keeping the connection open
+#[tauri::command]
+pub async fn test_obs_connection(settings: Option<ObsSettings>) -> Result<String, String> {
+    let settings = settings.unwrap_or_default();
+    info!("🔍 Testing OBS connection at {}:{}", settings.host, settings.port);
+
+    let result: Result<String> = async {
+        let mut socket = connect_and_identify(&settings).await?;
+        socket.send(request_message("GetVersion", json!({}))).await?;
+        let response = wait_for_op(&mut socket, OP_REQUEST_RESPONSE).await?;
+        let _ = socket.close(None).await;
+
+        Ok(format!(
+            "Connected to OBS {} (obs-websocket {})",
+            response["responseData"]["obsVersion"].as_str().unwrap_or("unknown"),
+            response["responseData"]["obsWebSocketVersion"].as_str().unwrap_or("unknown"),
+        ))
+    }.await;
+
+    result.map_err(|e| e.to_string())
+}
+
+// Manually push text to the caption or tip source (e.g. from the UI)
+#[tauri::command]
+pub fn set_obs_text(target: String, text: String) -> Result<(), String> {
+    if !OBS_CONNECTED.load(Ordering::Relaxed) {
+        return Err("Not connected to OBS".into());
+    }
+
+    match target.as_str() {
+        "caption" => publish_caption(&text),
+        "tip" => publish_coaching_tip(&text),
+        other => return Err(format!("Unknown OBS target '{}' (expected 'caption' or 'tip')", other)),
+    }
+    Ok(())
+}
@@ -1,11 +1,27 @@
 // Ollama AI Coaching Integration Module
 // Provides real-time AI coaching suggestions using local Ollama LLM
+// A prompt arrives in two phases: "prompt_incoming" (category and urgency, emitted as
+// soon as the trigger passes the rate limit) so the UI can show a placeholder, then
+// "coaching_suggestion" carrying the same preview id once the content is ready, or
+// "prompt_cancelled" when the prompt is suppressed after all.
 
 use anyhow::{Result, Context};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use log::{info, warn, error};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::Instant;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+
+use crate::knowledge_base::{citation_for, KnowledgeCitation};
+
+// Recent suggestions with their citations (prompt history for click-through)
+const MAX_COACHING_HISTORY: usize = 200;
+// Name of the prompt built by build_coaching_prompt (recorded with each session prompt)
+const COACHING_TEMPLATE: &str = "sales_coach_json";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OllamaRequest {
@@ -32,15 +48,56 @@ pub struct OllamaResponse {
     pub eval_count: Option<i32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct CoachingSuggestion {
     pub suggestion: String,
     pub confidence: f32,
     pub reasoning: Option<String>,
     pub action_items: Vec<String>,
+    /// Knowledge passages the suggestion was grounded on (empty when no RAG content was used)
+    #[serde(default)]
+    pub citations: Vec<KnowledgeCitation>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CoachingHistoryEntry {
+    pub timestamp: u64,
+    pub transcription: String,
+    pub suggestion: CoachingSuggestion,
+    pub source: String,  // "ollama" or "fallback"
+    #[serde(default)]
+    pub preview_id: Option<u64>,  // Of the "prompt_incoming" announcing it
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptUrgency {
+    Low,
+    Medium,
+    High,
+}
+
+// Payload of "prompt_incoming"
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PromptIncoming {
+    pub id: u64,
+    pub category: String,            // "objection" or the fallback rule ("price", "how", ...)
+    pub urgency: PromptUrgency,
+    pub timestamp: u64,
+}
+
+// Payload of "prompt_cancelled"
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PromptCancelled {
+    pub id: u64,
+    pub reason: String,
+}
+
+static COACHING_HISTORY: Lazy<Mutex<VecDeque<CoachingHistoryEntry>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+static NEXT_PREVIEW_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+#[specta(rename = "StoredKnowledgeDocument")]
 pub struct KnowledgeDocument {
     pub filename: String,
     pub content: String,
@@ -97,9 +154,12 @@ impl OllamaCoachingService {
     ) -> Result<CoachingSuggestion> {
         info!("🎯 LED 6100: Starting Ollama coaching generation");
         let start_time = Instant::now();
+        if crate::chaos::inject(crate::chaos::Fault::NetworkTimeout) {
+            anyhow::bail!("Ollama request timed out");
+        }
 
         // Build optimized prompt with knowledge base context
-        let prompt = self.build_coaching_prompt(transcription, knowledge_base, context)?;
+        let (prompt, mut citations) = self.build_coaching_prompt(transcription, knowledge_base, context)?;
         
         info!("📊 LED 6101: Prompt built, size: {} chars", prompt.len());
 
@@ -111,16 +171,31 @@ impl OllamaCoachingService {
         } else {
             prompt
         };
+        // Only cite passages that survived compression
+        citations.retain(|c| final_prompt.contains(c.excerpt.trim_end_matches('…')));
+
+        let response = self.complete(final_prompt, 0.3, 300, 30).await?;
+
+        info!("✅ LED 6120: Ollama response received in {:?}", start_time.elapsed());
+
+        // Parse the response into coaching suggestion
+        let mut suggestion = self.parse_coaching_response(&response)?;
+        suggestion.citations = citations;
+        
+        info!("🎯 LED 6130: Coaching suggestion generated successfully");
+        Ok(suggestion)
+    }
 
-        // Create Ollama request
+    /// Run a prompt through the model and return the raw completion
+    pub async fn complete(&self, prompt: String, temperature: f32, num_predict: i32, timeout_secs: u64) -> Result<String> {
         let request = OllamaRequest {
             model: self.model.clone(),
-            prompt: final_prompt,
+            prompt,
             stream: false,
             options: OllamaOptions {
-                temperature: 0.3,
+                temperature,
                 top_p: 0.9,
-                num_predict: 300,
+                num_predict,
             },
         };
 
@@ -131,7 +206,7 @@ impl OllamaCoachingService {
         let response = self.client
             .post(&url)
             .json(&request)
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(std::time::Duration::from_secs(timeout_secs))
             .send()
             .await
             .context("Failed to send request to Ollama")?;
@@ -143,14 +218,30 @@ impl OllamaCoachingService {
 
         let ollama_response: OllamaResponse = response.json().await
             .context("Failed to parse Ollama response")?;
+        Ok(ollama_response.response)
+    }
 
-        info!("✅ LED 6120: Ollama response received in {:?}", start_time.elapsed());
+    /// Embedding vector for `text` from an Ollama embedding model
+    pub async fn embed(&self, model: &str, text: &str) -> Result<Vec<f32>> {
+        #[derive(Deserialize)]
+        struct EmbeddingResponse {
+            embedding: Vec<f32>,
+        }
 
-        // Parse the response into coaching suggestion
-        let suggestion = self.parse_coaching_response(&ollama_response.response)?;
-        
-        info!("🎯 LED 6130: Coaching suggestion generated successfully");
-        Ok(suggestion)
+        let url = format!("{}/api/embeddings", self.base_url);
+        let response = self.client
+            .post(&url)
+            .json(&json!({ "model": model, "prompt": text }))
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await
+            .context("Failed to send embedding request to Ollama")?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Ollama embedding request failed: {}", response.status()));
+        }
+        let embedding: EmbeddingResponse = response.json().await
+            .context("Failed to parse Ollama embedding response")?;
+        Ok(embedding.embedding)
     }
 
     /// Build optimized prompt for coaching
@@ -159,24 +250,40 @@ impl OllamaCoachingService {
         transcription: &str,
         knowledge_base: Option<Vec<KnowledgeDocument>>,
         context: Option<String>,
-    ) -> Result<String> {
+    ) -> Result<(String, Vec<KnowledgeCitation>)> {
         let mut prompt = String::new();
+        let mut citations = Vec::new();
 
         // Add role and context
         prompt.push_str("You are an expert sales coach providing real-time guidance.\n\n");
 
+        // Focus of the active session template (call type)
+        if let Some(focus) = crate::session_templates::coaching_focus() {
+            prompt.push_str(&format!("COACHING FOCUS:\n{}\n\n", focus));
+        }
+        // Strategy of the session's experiment variant
+        if let Some(focus) = crate::experiments::coaching_focus() {
+            prompt.push_str(&format!("COACHING STRATEGY:\n{}\n\n", focus));
+        }
+
         // Add knowledge base context if available
         if let Some(docs) = knowledge_base {
             prompt.push_str("KEY SALES PRINCIPLES:\n");
             
             // Extract most relevant chunks (limit to prevent token overflow)
             let relevant_chunks = self.extract_relevant_chunks(&docs, transcription, 3);
-            for chunk in relevant_chunks {
+            for (chunk, citation) in relevant_chunks {
                 prompt.push_str(&format!("- {}\n", chunk));
+                citations.push(citation);
             }
             prompt.push_str("\n");
         }
 
+        // What earlier calls with this prospect left open
+        if let Some(memory) = crate::prospect_memory::prompt_context() {
+            prompt.push_str(&format!("RELATIONSHIP MEMORY:\n{}\n", memory));
+        }
+
         // Add conversation context if available
         if let Some(ctx) = context {
             prompt.push_str(&format!("CONVERSATION CONTEXT:\n{}\n\n", ctx));
@@ -187,28 +294,35 @@ impl OllamaCoachingService {
 
         // Add instruction
         prompt.push_str("Based on the sales principles and current statement, provide a brief, actionable coaching suggestion.\n");
+        if let Some(language) = crate::i18n::language_instruction() {
+            prompt.push_str(&format!("{}\n", language));
+        }
         prompt.push_str("Respond in JSON format:\n");
         prompt.push_str(r#"{"suggestion": "your advice", "confidence": 0.0-1.0, "action_items": ["item1", "item2"]}"#);
 
-        Ok(prompt)
+        Ok((prompt, citations))
     }
 
-    /// Extract most relevant chunks from knowledge base
+    /// Extract most relevant chunks from knowledge base, with their citations
     fn extract_relevant_chunks(
         &self,
         docs: &[KnowledgeDocument],
         query: &str,
         max_chunks: usize,
-    ) -> Vec<String> {
-        let mut relevant_chunks = Vec::new();
+    ) -> Vec<(String, KnowledgeCitation)> {
+        let mut relevant_chunks: Vec<(String, KnowledgeCitation)> = Vec::new();
         let query_lower = query.to_lowercase();
+        let cite = |doc: &KnowledgeDocument, index: usize, chunk: &String| {
+            let similarity = Self::keyword_similarity(&query_lower, &chunk.to_lowercase());
+            (chunk.clone(), citation_for(&doc.filename, &doc.content, None, index, chunk, similarity))
+        };
 
         // Simple keyword matching (could be enhanced with embeddings)
         for doc in docs {
-            for chunk in &doc.chunks {
+            for (index, chunk) in doc.chunks.iter().enumerate() {
                 if chunk.to_lowercase().contains(&query_lower) ||
                    self.has_relevant_keywords(chunk, &query_lower) {
-                    relevant_chunks.push(chunk.clone());
+                    relevant_chunks.push(cite(doc, index, chunk));
                     if relevant_chunks.len() >= max_chunks {
                         return relevant_chunks;
                     }
@@ -219,9 +333,9 @@ impl OllamaCoachingService {
         // If not enough relevant chunks found, add some general ones
         if relevant_chunks.len() < max_chunks {
             for doc in docs {
-                for chunk in doc.chunks.iter().take(max_chunks - relevant_chunks.len()) {
-                    if !relevant_chunks.contains(chunk) {
-                        relevant_chunks.push(chunk.clone());
+                for (index, chunk) in doc.chunks.iter().enumerate().take(max_chunks - relevant_chunks.len()) {
+                    if !relevant_chunks.iter().any(|(existing, _)| existing == chunk) {
+                        relevant_chunks.push(cite(doc, index, chunk));
                     }
                 }
                 if relevant_chunks.len() >= max_chunks {
@@ -233,6 +347,15 @@ impl OllamaCoachingService {
         relevant_chunks
     }
 
+    /// Fraction of query words present in the chunk (same measure as knowledge base search)
+    fn keyword_similarity(query: &str, text: &str) -> f32 {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            return 0.0;
+        }
+        words.iter().filter(|w| text.contains(*w)).count() as f32 / words.len() as f32
+    }
+
     /// Check if chunk contains relevant keywords
     fn has_relevant_keywords(&self, chunk: &str, query: &str) -> bool {
         // Sales-related keywords to check
@@ -299,61 +422,209 @@ impl OllamaCoachingService {
             confidence: 0.7,
             reasoning: None,
             action_items: vec![],
+            citations: vec![],
         })
     }
 
-    /// Generate fallback coaching without Ollama
-    pub fn generate_fallback_coaching(&self, transcription: &str) -> CoachingSuggestion {
-        info!("🔄 LED 6200: Using rule-based fallback coaching");
-
-        let suggestion = if transcription.to_lowercase().contains("price") {
-            "Address price concerns by focusing on value and ROI. Ask: 'What would making this investment mean for your business?'"
+    /// Fallback rule matching a statement: (rule id, suggestion message id in i18n)
+    pub fn fallback_rule(transcription: &str) -> (&'static str, &'static str) {
+        if transcription.to_lowercase().contains("price") {
+            ("price", "coaching-price")
         } else if transcription.to_lowercase().contains("think about it") {
-            "They need time to process. Use a calibrated question: 'What specifically would you like to think through?'"
+            ("think_about_it", "coaching-think-about-it")
         } else if transcription.to_lowercase().contains("not interested") {
-            "Acknowledge their position and explore: 'I understand. Before we end, what would have to change for this to be valuable to you?'"
+            ("not_interested", "coaching-not-interested")
         } else if transcription.to_lowercase().contains("how") {
-            "They're seeking information. Provide a clear, concise answer and check understanding."
+            ("how", "coaching-how")
         } else {
-            "Listen actively and ask open-ended questions to understand their perspective better."
-        };
+            ("active_listening", "coaching-active-listening")
+        }
+    }
+
+    /// Generate fallback coaching without Ollama
+    pub fn generate_fallback_coaching(&self, transcription: &str) -> CoachingSuggestion {
+        info!("🔄 LED 6200: Using rule-based fallback coaching");
+
+        let (_, suggestion) = Self::fallback_rule(transcription);
 
         CoachingSuggestion {
-            suggestion: suggestion.to_string(),
+            suggestion: crate::i18n::text(suggestion),
             confidence: 0.5,
-            reasoning: Some("Rule-based suggestion".to_string()),
-            action_items: vec!["Continue active listening".to_string()],
+            reasoning: Some(crate::i18n::text("coaching-rule-based")),
+            action_items: vec![crate::i18n::text("coaching-keep-listening")],
+            citations: vec![],
         }
     }
 }
 
+/// Category and urgency of the prompt a statement will get, known before any generation
+fn preview(transcription: &str) -> (String, PromptUrgency) {
+    if crate::sales_stage::is_objection(transcription) {
+        return ("objection".to_string(), PromptUrgency::High);
+    }
+    let (rule, _) = OllamaCoachingService::fallback_rule(transcription);
+    let urgency = match rule {
+        "price" | "not_interested" => PromptUrgency::High,
+        "think_about_it" | "how" => PromptUrgency::Medium,
+        _ => PromptUrgency::Low,
+    };
+    (rule.to_string(), urgency)
+}
+
+/// Announce a prompt that is being generated; returns its preview id
+fn announce_prompt(app: &AppHandle, transcription: &str) -> u64 {
+    let id = NEXT_PREVIEW_ID.fetch_add(1, Ordering::Relaxed);
+    let (category, urgency) = preview(transcription);
+    crate::whisper_channel::prompt_cue(urgency);
+    let incoming = PromptIncoming { id, category, urgency, timestamp: chrono::Utc::now().timestamp_millis() as u64 };
+    if let Err(e) = app.emit_all("prompt_incoming", incoming) {
+        error!("Failed to emit prompt_incoming: {:?}", e);
+    }
+    id
+}
+
+/// Withdraw an announced prompt that won't be shown
+fn cancel_prompt(app: &AppHandle, id: u64, reason: &str) {
+    if let Err(e) = app.emit_all("prompt_cancelled", PromptCancelled { id, reason: reason.to_string() }) {
+        error!("Failed to emit prompt_cancelled: {:?}", e);
+    }
+}
+
 // Tauri command to generate coaching
 #[tauri::command]
 pub async fn generate_ai_coaching(
+    app: AppHandle,
     transcription: String,
     knowledge_base: Option<Vec<KnowledgeDocument>>,
     context: Option<String>,
 ) -> Result<CoachingSuggestion, String> {
+    // No prompts while the rep reads a disclosure or demo script verbatim
+    if crate::read_aloud::is_active() {
+        return Err("Coaching paused while a script is being read".to_string());
+    }
+    crate::coaching_cooldown::check_rate()?;
+    let preview_id = announce_prompt(&app, &transcription);
     let service = OllamaCoachingService::new();
+    let started = Instant::now();
     
     // Check if Ollama is available
     let ollama_available = service.check_availability().await
         .unwrap_or(false);
 
-    if ollama_available {
+    let prompt_context = context.clone();
+    // Only the active session template's knowledge workspace
+    let knowledge_base = knowledge_base.map(crate::session_templates::filter_knowledge);
+    let (suggestion, source) = if ollama_available {
         // Try to generate with Ollama
         match service.generate_coaching(&transcription, knowledge_base, context).await {
-            Ok(suggestion) => Ok(suggestion),
+            Ok(suggestion) => (suggestion, "ollama"),
             Err(e) => {
                 error!("Ollama generation failed: {}", e);
+                crate::telemetry::record_error("ollama");
                 // Fall back to rule-based
-                Ok(service.generate_fallback_coaching(&transcription))
+                (service.generate_fallback_coaching(&transcription), "fallback")
             }
         }
     } else {
         // Use fallback if Ollama not available
-        Ok(service.generate_fallback_coaching(&transcription))
+        (service.generate_fallback_coaching(&transcription), "fallback")
+    };
+
+    // Persist with the rule/template that produced it (post-call prompt review)
+    let rule = if source == "ollama" {
+        format!("ollama:{}", COACHING_TEMPLATE)
+    } else {
+        format!("fallback:{}", OllamaCoachingService::fallback_rule(&transcription).0)
+    };
+    // Not shown while its rule cools down or when it repeats a recent prompt
+    if let Err(e) = crate::coaching_cooldown::admit(&service, ollama_available, &rule, &suggestion.suggestion).await {
+        cancel_prompt(&app, preview_id, &e);
+        return Err(e);
+    }
+    crate::session_store::record_prompt(&transcription, prompt_context, &rule, &suggestion);
+    let elapsed = started.elapsed();
+    crate::telemetry::record_latency(crate::telemetry::Stage::Coach, source, elapsed.as_secs_f64() * 1000.0);
+    crate::telemetry::record_usage(source);
+    crate::telemetry::record_span("coach", elapsed, &[("source", source), ("rule", &rule)], !(ollama_available && source == "fallback"));
+
+    // Record with citations and notify listeners (click-through to source passages)
+    let entry = CoachingHistoryEntry {
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        transcription,
+        suggestion: suggestion.clone(),
+        source: source.to_string(),
+        preview_id: Some(preview_id),
+    };
+    {
+        let mut history = COACHING_HISTORY.lock().unwrap();
+        history.push_back(entry.clone());
+        while history.len() > MAX_COACHING_HISTORY {
+            history.pop_front();
+        }
     }
+    if let Err(e) = app.emit_all("coaching_suggestion", entry) {
+        error!("Failed to emit coaching_suggestion: {:?}", e);
+    }
+
+    // Mirror the current tip to OBS for streamed sessions (no-op when not connected)
+    crate::obs_integration::publish_coaching_tip(&suggestion.suggestion);
+    crate::live_doc::queue_coaching_note(&suggestion.suggestion);
+    crate::live_listen::publish_coaching(&suggestion);
+    Ok(suggestion)
+}
+
+fn history_entry_bytes(entry: &CoachingHistoryEntry) -> usize {
+    entry.transcription.len() + entry.suggestion.suggestion.len()
+        + entry.suggestion.reasoning.as_ref().map_or(0, |r| r.len())
+        + entry.suggestion.action_items.iter().map(|a| a.len()).sum::<usize>()
+        + entry.suggestion.citations.iter().map(|c| c.excerpt.len() + c.document.len()).sum::<usize>()
+}
+
+/// Approximate size of the coaching history (memory budget)
+pub fn coaching_history_bytes() -> usize {
+    COACHING_HISTORY.lock().unwrap().iter().map(history_entry_bytes).sum()
+}
+
+/// Drop the oldest entries until the history fits `max_bytes`; returns bytes freed
+pub fn trim_coaching_history(max_bytes: usize) -> usize {
+    let mut history = COACHING_HISTORY.lock().unwrap();
+    let mut total: usize = history.iter().map(history_entry_bytes).sum();
+    let mut freed = 0;
+    while total > max_bytes {
+        match history.pop_front() {
+            Some(entry) => {
+                let size = history_entry_bytes(&entry);
+                total -= size;
+                freed += size;
+            }
+            None => break,
+        }
+    }
+    if freed > 0 {
+        history.shrink_to_fit();
+    }
+    freed
+}
+
+/// Distinct action items suggested since `since_ms`, oldest first
+pub fn action_items_since(since_ms: u64) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    for entry in COACHING_HISTORY.lock().unwrap().iter().filter(|e| e.timestamp >= since_ms) {
+        for item in &entry.suggestion.action_items {
+            if !items.iter().any(|i| i.eq_ignore_ascii_case(item)) {
+                items.push(item.clone());
+            }
+        }
+    }
+    items
+}
+
+// Most recent coaching suggestions (newest last) with their knowledge citations
+#[tauri::command]
+pub fn get_coaching_history(limit: Option<usize>) -> Result<Vec<CoachingHistoryEntry>, String> {
+    let history = COACHING_HISTORY.lock().unwrap();
+    let skip = history.len().saturating_sub(limit.unwrap_or(MAX_COACHING_HISTORY));
+    Ok(history.iter().skip(skip).cloned().collect())
 }
 
 // Tauri command to check Ollama availability
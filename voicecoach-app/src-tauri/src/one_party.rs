@@ -0,0 +1,118 @@
+// One-Party Consent - only the rep's side of the call is transcribed and stored
+// For jurisdictions where the other party may not be recorded. With the mode on, a
+// prospect channel (system audio or per-application loopback) is routed to a level
+// meter instead of a transcription engine: nothing is sent to a cloud engine, tapped
+// to disk or kept - the meter reports the channel's level and whether the prospect is
+// speaking ("prospect_level") and drops the samples. As a second barrier, a prospect
+// final that still reaches the pipeline is discarded before the sequencer, so it never
+// reaches the session, the journal or coaching. Turning the mode on ends a prospect
+// transcription that is already running.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+use crate::adaptive_vad::{AdaptiveVad, VadSource};
+use crate::audio_clock::SampleClock;
+
+const METER_INTERVAL_MS: u64 = 100;
+// Speaking threshold until adaptive_vad has learned one (RMS, full scale = 1.0)
+const DEFAULT_SPEECH_RMS: f32 = 0.01;
+
+// Payload of "prospect_level"
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProspectLevel {
+    pub rms: f32,
+    pub speaking: bool,
+    pub speech_ms: u64,    // Prospect speaking time since the meter started
+    pub timestamp: u64,
+}
+
+// Cached preference (checked on every prospect final)
+static ENABLED: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(crate::preferences::load().privacy.one_party_consent));
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Whether a channel may be transcribed and stored
+pub fn allows(is_user: bool) -> bool {
+    is_user || !enabled()
+}
+
+/// Level meter owned by a prospect capture callback
+pub struct ProspectMeter {
+    app: AppHandle,
+    sample_rate: u32,
+    vad: AdaptiveVad,
+    sum_squares: f32,
+    samples: usize,
+    speech_ms: u64,
+    talk_over: Option<crate::talk_over::TalkOverTracker>,
+    clock: SampleClock,
+}
+
+impl ProspectMeter {
+    pub fn new(app: AppHandle, sample_rate: u32) -> Self {
+        let talk_over = crate::talk_over::tracker(&app, false, sample_rate);
+        Self { app, sample_rate, vad: AdaptiveVad::new(VadSource::SystemAudio, sample_rate), sum_squares: 0.0, samples: 0, speech_ms: 0, talk_over, clock: SampleClock::new(sample_rate) }
+    }
+
+    /// Meter mono audio; the samples are not kept
+    pub fn observe(&mut self, samples: &[f32]) {
+        let learned = self.vad.observe(samples);
+        let threshold = learned.unwrap_or(DEFAULT_SPEECH_RMS);
+        // The prospect still speaks over the rep (talk_over) when only metered
+        let capture_ms = self.clock.stamp(crate::transcript_sequencer::capture_ms(), samples.len());
+        if let Some(tracker) = self.talk_over.as_mut() {
+            tracker.observe(samples, capture_ms, learned);
+        }
+        let interval = (self.sample_rate as u64 * METER_INTERVAL_MS / 1000) as usize;
+        for &sample in samples {
+            self.sum_squares += sample * sample;
+            self.samples += 1;
+            if self.samples < interval {
+                continue;
+            }
+            let rms = (self.sum_squares / self.samples as f32).sqrt();
+            let speaking = rms > threshold;
+            if speaking {
+                self.speech_ms += METER_INTERVAL_MS;
+            }
+            self.sum_squares = 0.0;
+            self.samples = 0;
+            let level = ProspectLevel { rms, speaking, speech_ms: self.speech_ms, timestamp: chrono::Utc::now().timestamp_millis() as u64 };
+            if let Err(e) = self.app.emit_all("prospect_level", level) {
+                error!("Failed to emit prospect_level: {:?}", e);
+            }
+        }
+    }
+
+    pub fn observe_i16(&mut self, samples: &[i16]) {
+        let samples: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        self.observe(&samples);
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_one_party_consent() -> Result<bool, String> {
+    Ok(enabled())
+}
+
+// Turning the mode on stops a prospect transcription in progress
+#[tauri::command]
+pub fn set_one_party_consent(enabled: bool) -> Result<bool, String> {
+    crate::preferences::update(|p| p.privacy.one_party_consent = enabled)
+        .map_err(|e| e.to_string())?;
+    ENABLED.store(enabled, Ordering::Relaxed);
+    info!("🔒 One-party consent mode {}", if enabled { "on: prospect audio is metered only" } else { "off" });
+    if enabled && crate::deepgram_transcription::transcribing_prospect() {
+        warn!("⚠️ Stopping the prospect transcription in progress (one-party consent)");
+        crate::deepgram_transcription::close_capture();
+    }
+    Ok(enabled)
+}
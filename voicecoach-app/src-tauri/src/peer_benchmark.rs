@@ -0,0 +1,406 @@
+// Peer Benchmark - opt-in anonymized metrics, compared with the team and industry
+// Reps want to know whether a 60% talk ratio is normal. Reps who opt in contribute a
+// single aggregate of their recent calls, at most once a day, and get medians and
+// quartiles of their team, their industry and everyone back. The contribution is
+// redacted on this machine before anything leaves it:
+// - only per-call averages over the last PERIOD_DAYS (talk ratio, prospect questions
+//   and objections per call, rep words per minute), rounded, and the call count
+//   rounded down to a multiple of CALL_COUNT_STEP;
+// - nothing until MIN_CALLS calls, so one call can't be singled out;
+// - no text, names, companies, session ids or times; the industry is one of a fixed
+//   list, the team code is sent hashed, and the contributor id is random (not derived
+//   from the machine or the license) and thrown away on opt-out.
+// preview_benchmark_contribution shows the exact payload whether opted in or not.
+// Opting out stops uploads, forgets the id, team and industry, and asks the server to
+// delete what the id contributed.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use log::{info, warn};
+
+use crate::session_store::Session;
+
+const DEFAULT_ENDPOINT: &str = "https://benchmarks.voicecoach.app/v1";
+const SCHEMA: u32 = 1;
+const PERIOD_DAYS: u64 = 30;
+const MIN_CALLS: usize = 5;
+const CALL_COUNT_STEP: usize = 5;
+// Peer groups smaller than this are not shown (the server applies the same floor)
+const MIN_PEERS: u32 = 5;
+const UPLOAD_INTERVAL_MS: u64 = 24 * 60 * 60 * 1000;
+// Hashed with the team code so the server can't look codes up in a plain SHA-256 table
+const TEAM_SALT: &str = "voicecoach-benchmark-team";
+
+pub const INDUSTRIES: [&str; 10] = [
+    "software", "financial_services", "insurance", "healthcare", "manufacturing",
+    "real_estate", "telecom", "education", "professional_services", "other",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct BenchmarkSettings {
+    #[serde(default)]
+    pub opted_in: bool,
+    /// One of INDUSTRIES
+    #[serde(default)]
+    pub industry: Option<String>,
+    /// Shared within a team for team benchmarks; only its hash is sent
+    #[serde(default)]
+    pub team_code: Option<String>,
+    /// Random id of this contributor, created on opt-in
+    #[serde(default)]
+    pub contributor_id: Option<String>,
+    #[serde(default)]
+    pub last_upload_at: Option<u64>,
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+}
+
+fn default_endpoint() -> String { DEFAULT_ENDPOINT.to_string() }
+
+impl Default for BenchmarkSettings {
+    fn default() -> Self {
+        Self {
+            opted_in: false,
+            industry: None,
+            team_code: None,
+            contributor_id: None,
+            last_upload_at: None,
+            endpoint: default_endpoint(),
+        }
+    }
+}
+
+// Everything an upload contains
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct BenchmarkContribution {
+    pub schema: u32,
+    pub contributor_id: String,
+    pub industry: Option<String>,
+    pub team: Option<String>,        // Salted SHA-256 of the team code
+    pub period_days: u32,
+    pub calls: u32,                  // Rounded down to a multiple of 5
+    pub talk_ratio: Option<f32>,     // Rep share of words
+    pub prospect_questions_per_call: Option<f32>,
+    pub objections_per_call: Option<f32>,
+    pub rep_wpm: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct BenchmarkPreview {
+    pub opted_in: bool,
+    pub contribution: Option<BenchmarkContribution>,
+    pub payload: Option<String>,     // The JSON body exactly as it would be sent
+    pub withheld_reason: Option<String>, // Why nothing would be sent now
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PeerStats {
+    pub contributors: u32,
+    pub p25: f32,
+    pub median: f32,
+    pub p75: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct MetricBenchmark {
+    pub metric: String,              // Field name in BenchmarkContribution
+    pub yours: Option<f32>,
+    pub team: Option<PeerStats>,
+    pub industry: Option<PeerStats>,
+    pub everyone: Option<PeerStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PeerBenchmarks {
+    pub fetched_at: u64,
+    pub metrics: Vec<MetricBenchmark>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct BenchmarkOptOut {
+    pub deleted_remotely: bool,      // The server confirmed deleting the contributions
+    pub message: Option<String>,
+}
+
+// Server reply to GET /benchmarks: metric name -> stats, per peer group
+#[derive(Debug, Default, Deserialize)]
+struct ServerBenchmarks {
+    #[serde(default)]
+    team: HashMap<String, PeerStats>,
+    #[serde(default)]
+    industry: HashMap<String, PeerStats>,
+    #[serde(default)]
+    all: HashMap<String, PeerStats>,
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+fn round_to(value: f32, decimals: i32) -> f32 {
+    let scale = 10f32.powi(decimals);
+    (value * scale).round() / scale
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f32)
+}
+
+fn team_hash(code: &str) -> String {
+    let digest = Sha256::digest(format!("{}:{}", TEAM_SALT, code.trim().to_lowercase()).as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The redacted aggregate of the calls of the last PERIOD_DAYS, or why there is none
+fn contribution(sessions: &[Session], settings: &BenchmarkSettings, contributor_id: &str, now: u64) -> Result<BenchmarkContribution, String> {
+    let since = now.saturating_sub(PERIOD_DAYS * 24 * 60 * 60 * 1000);
+    let calls: Vec<&crate::call_analytics::CallMetrics> = sessions.iter()
+        .filter(|s| s.started_at >= since)
+        .filter_map(|s| s.metrics.as_ref())
+        .filter(|m| m.talk_ratio.rep_words + m.talk_ratio.prospect_words > 0)
+        .collect();
+    if calls.len() < MIN_CALLS {
+        return Err(format!("{} calls in the last {} days; contributions start at {}", calls.len(), PERIOD_DAYS, MIN_CALLS));
+    }
+    let per_call = |count: fn(&crate::call_analytics::CallMetrics) -> usize| {
+        Some(round_to(calls.iter().map(|&m| count(m)).sum::<usize>() as f32 / calls.len() as f32, 1))
+    };
+    Ok(BenchmarkContribution {
+        schema: SCHEMA,
+        contributor_id: contributor_id.to_string(),
+        industry: settings.industry.clone().filter(|i| INDUSTRIES.contains(&i.as_str())),
+        team: settings.team_code.as_deref().filter(|c| !c.trim().is_empty()).map(team_hash),
+        period_days: PERIOD_DAYS as u32,
+        calls: (calls.len() / CALL_COUNT_STEP * CALL_COUNT_STEP) as u32,
+        talk_ratio: mean(calls.iter().map(|m| m.talk_ratio.rep_share)).map(|r| round_to(r, 2)),
+        prospect_questions_per_call: per_call(|m| m.prospect_questions),
+        objections_per_call: per_call(|m| m.objections),
+        rep_wpm: mean(calls.iter().filter_map(|m| m.rep_wpm))
+            .map(|wpm| (wpm.clamp(0.0, 400.0) / 5.0).round() * 5.0),
+    })
+}
+
+/// Per metric: the contribution's value next to the peer groups large enough to show
+fn compare(yours: Option<&BenchmarkContribution>, server: &ServerBenchmarks) -> Vec<MetricBenchmark> {
+    let group = |stats: &HashMap<String, PeerStats>, metric: &str| {
+        stats.get(metric).filter(|s| s.contributors >= MIN_PEERS).cloned()
+    };
+    let metrics: [(&str, Option<f32>); 4] = [
+        ("talk_ratio", yours.and_then(|c| c.talk_ratio)),
+        ("prospect_questions_per_call", yours.and_then(|c| c.prospect_questions_per_call)),
+        ("objections_per_call", yours.and_then(|c| c.objections_per_call)),
+        ("rep_wpm", yours.and_then(|c| c.rep_wpm)),
+    ];
+    metrics.iter().map(|&(metric, value)| MetricBenchmark {
+        metric: metric.to_string(),
+        yours: value,
+        team: group(&server.team, metric),
+        industry: group(&server.industry, metric),
+        everyone: group(&server.all, metric),
+    }).collect()
+}
+
+async fn upload(settings: &BenchmarkSettings, contribution: &BenchmarkContribution) -> Result<()> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/contributions", settings.endpoint.trim_end_matches('/')))
+        .timeout(std::time::Duration::from_secs(15))
+        .json(contribution)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        bail!("Benchmark server returned {}", response.status());
+    }
+    crate::preferences::update(|p| p.benchmarks.last_upload_at = Some(now_ms()))?;
+    info!("📊 Benchmark contribution uploaded ({} calls)", contribution.calls);
+    Ok(())
+}
+
+/// Upload this period's contribution when opted in and the last one is a day old
+async fn upload_if_due() -> Result<()> {
+    let settings = crate::preferences::load().benchmarks;
+    let contributor_id = match (&settings.contributor_id, settings.opted_in) {
+        (Some(id), true) => id.clone(),
+        _ => return Ok(()),
+    };
+    if settings.last_upload_at.map_or(false, |at| now_ms().saturating_sub(at) < UPLOAD_INTERVAL_MS) {
+        return Ok(());
+    }
+    match contribution(&crate::session_store::all_sessions(), &settings, &contributor_id, now_ms()) {
+        Ok(contribution) => upload(&settings, &contribution).await,
+        Err(reason) => {
+            info!("📊 No benchmark contribution yet: {}", reason);
+            Ok(())
+        }
+    }
+}
+
+/// A call ended: contribute in the background if due
+pub fn call_finished() {
+    if !crate::preferences::load().benchmarks.opted_in {
+        return;
+    }
+    tauri::async_runtime::spawn(async {
+        if let Err(e) = upload_if_due().await {
+            warn!("⚠️ Benchmark upload failed: {}", e);
+        }
+    });
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_benchmark_settings() -> Result<BenchmarkSettings, String> {
+    Ok(crate::preferences::load().benchmarks)
+}
+
+// Exactly what would be uploaded now (also before opting in)
+#[tauri::command]
+pub fn preview_benchmark_contribution() -> Result<BenchmarkPreview, String> {
+    let settings = crate::preferences::load().benchmarks;
+    // Before opting in there is no id yet; the preview shows where it goes
+    let contributor_id = settings.contributor_id.clone().unwrap_or_else(|| "<assigned on opt-in>".to_string());
+    match contribution(&crate::session_store::all_sessions(), &settings, &contributor_id, now_ms()) {
+        Ok(contribution) => Ok(BenchmarkPreview {
+            opted_in: settings.opted_in,
+            payload: Some(serde_json::to_string_pretty(&contribution).map_err(|e| e.to_string())?),
+            contribution: Some(contribution),
+            withheld_reason: None,
+        }),
+        Err(reason) => Ok(BenchmarkPreview { opted_in: settings.opted_in, contribution: None, payload: None, withheld_reason: Some(reason) }),
+    }
+}
+
+#[tauri::command]
+pub fn opt_in_to_benchmarks(industry: Option<String>, team_code: Option<String>) -> Result<BenchmarkSettings, String> {
+    if let Some(industry) = &industry {
+        if !INDUSTRIES.contains(&industry.as_str()) {
+            return Err(format!("Unknown industry {} (expected one of {})", industry, INDUSTRIES.join(", ")));
+        }
+    }
+    let preferences = crate::preferences::update(|p| {
+        let benchmarks = &mut p.benchmarks;
+        benchmarks.opted_in = true;
+        benchmarks.industry = industry;
+        benchmarks.team_code = team_code.filter(|c| !c.trim().is_empty());
+        if benchmarks.contributor_id.is_none() {
+            benchmarks.contributor_id = Some((0..16).map(|_| format!("{:02x}", rand::random::<u8>())).collect());
+        }
+    }).map_err(|e| e.to_string())?;
+    info!("📊 Opted in to peer benchmarks");
+    Ok(preferences.benchmarks)
+}
+
+// Stop contributing, forget the id, team and industry, and ask the server to delete
+// this contributor's data
+#[tauri::command]
+pub async fn opt_out_of_benchmarks() -> Result<BenchmarkOptOut, String> {
+    let previous = crate::preferences::load().benchmarks;
+    crate::preferences::update(|p| p.benchmarks = BenchmarkSettings { endpoint: previous.endpoint.clone(), ..Default::default() })
+        .map_err(|e| e.to_string())?;
+    info!("📊 Opted out of peer benchmarks");
+
+    let contributor_id = match previous.contributor_id {
+        Some(id) => id,
+        None => return Ok(BenchmarkOptOut { deleted_remotely: true, message: None }),
+    };
+    let result = reqwest::Client::new()
+        .delete(format!("{}/contributions/{}", previous.endpoint.trim_end_matches('/'), contributor_id))
+        .timeout(std::time::Duration::from_secs(15))
+        .send()
+        .await;
+    Ok(match result {
+        Ok(response) if response.status().is_success() => BenchmarkOptOut { deleted_remotely: true, message: None },
+        Ok(response) => BenchmarkOptOut { deleted_remotely: false, message: Some(format!("Benchmark server returned {}", response.status())) },
+        Err(e) => BenchmarkOptOut {
+            deleted_remotely: false,
+            message: Some(format!("Could not reach the benchmark server ({}); contributions expire after {} days", e, PERIOD_DAYS)),
+        },
+    })
+}
+
+// Team, industry and overall benchmarks next to this rep's numbers (opted in only)
+#[tauri::command]
+pub async fn get_peer_benchmarks() -> Result<PeerBenchmarks, String> {
+    let settings = crate::preferences::load().benchmarks;
+    let contributor_id = match (&settings.contributor_id, settings.opted_in) {
+        (Some(id), true) => id.clone(),
+        _ => return Err("Peer benchmarks are available after opting in".to_string()),
+    };
+    if let Err(e) = upload_if_due().await {
+        warn!("⚠️ Benchmark upload failed: {}", e);
+    }
+    let mut query = vec![("contributor_id", contributor_id.clone())];
+    if let Some(team) = settings.team_code.as_deref().filter(|c| !c.trim().is_empty()) {
+        query.push(("team", team_hash(team)));
+    }
+    if let Some(industry) = &settings.industry {
+        query.push(("industry", industry.clone()));
+    }
+    let response = reqwest::Client::new()
+        .get(format!("{}/benchmarks", settings.endpoint.trim_end_matches('/')))
+        .query(&query)
+        .timeout(std::time::Duration::from_secs(15))
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach the benchmark server: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Benchmark server returned {}", response.status()));
+    }
+    let server: ServerBenchmarks = response.json().await.map_err(|e| format!("Invalid benchmark reply: {}", e))?;
+    let yours = contribution(&crate::session_store::all_sessions(), &settings, &contributor_id, now_ms()).ok();
+    Ok(PeerBenchmarks { fetched_at: now_ms(), metrics: compare(yours.as_ref(), &server) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::call_analytics::{CallMetrics, TalkRatio};
+
+    fn call(started_at: u64, rep_share: f32, questions: usize) -> Session {
+        let mut session: Session = serde_json::from_value(serde_json::json!({
+            "id": "20231114-221320",
+            "started_at": started_at,
+            "company": "Globex Corporation",
+        })).unwrap();
+        session.metrics = Some(CallMetrics {
+            talk_ratio: TalkRatio { rep_words: 600, prospect_words: 400, rep_share, ..Default::default() },
+            rep_wpm: Some(152.3),
+            prospect_questions: questions,
+            ..Default::default()
+        });
+        session
+    }
+
+    #[test]
+    fn test_contribution_is_aggregated_rounded_and_redacted() {
+        let now = 1_700_000_000_000u64;
+        let day = 24 * 60 * 60 * 1000;
+        let settings = BenchmarkSettings { industry: Some("software".to_string()), team_code: Some("Sales-East".to_string()), ..Default::default() };
+        let mut sessions: Vec<Session> = (0..4).map(|i| call(now - i * day, 0.61, 3)).collect();
+        sessions.push(call(now - 40 * day, 0.9, 0));  // Outside the period
+        assert!(contribution(&sessions, &settings, "id", now).unwrap_err().contains("4 calls"));
+
+        sessions.extend((0..3).map(|i| call(now - i * day, 0.55, 4)));
+        let c = contribution(&sessions, &settings, "id", now).unwrap();
+        assert_eq!((c.calls, c.talk_ratio, c.prospect_questions_per_call, c.rep_wpm), (5, Some(0.58), Some(3.4), Some(150.0)));
+        assert_eq!(c.team.as_deref(), Some(team_hash("sales-east ").as_str()));
+        let payload = serde_json::to_string(&c).unwrap();
+        assert!(!payload.contains("Globex") && !payload.contains("20231114") && !payload.contains("Sales-East"));
+
+        let unknown = BenchmarkSettings { industry: Some("Acme Corp".to_string()), ..Default::default() };
+        assert_eq!(contribution(&sessions, &unknown, "id", now).unwrap().industry, None);
+
+        // Peer groups below the floor are hidden
+        let stats = |contributors| PeerStats { contributors, p25: 0.4, median: 0.5, p75: 0.6 };
+        let server = ServerBenchmarks {
+            team: HashMap::from([("talk_ratio".to_string(), stats(3))]),
+            all: HashMap::from([("talk_ratio".to_string(), stats(800))]),
+            ..Default::default()
+        };
+        let talk = &compare(Some(&c), &server)[0];
+        assert_eq!((talk.yours, talk.team.is_none(), talk.everyone.as_ref().map(|s| s.contributors)), (Some(0.58), true, Some(800)));
+    }
+}
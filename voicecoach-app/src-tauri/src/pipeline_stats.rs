@@ -0,0 +1,19 @@
+// Pipeline Stats Commands - audio callback timing for glitch reports
+// The histograms are kept in voicecoach-core (re-exported here under the old path).
+
+pub use voicecoach_core::pipeline_stats::*;
+
+// ========== Tauri Commands ==========
+
+// Callback timing of every audio stream since launch (or the last reset); streams
+// that never ran have zero callbacks
+#[tauri::command]
+pub fn get_audio_pipeline_stats() -> Result<Vec<CallbackStats>, String> {
+    Ok(snapshot())
+}
+
+#[tauri::command]
+pub fn reset_audio_pipeline_stats() -> Result<(), String> {
+    reset();
+    Ok(())
+}
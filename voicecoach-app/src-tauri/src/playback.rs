@@ -0,0 +1,398 @@
+// Playback - session review of call recordings at 0.5x-2x
+// play_recording opens a WAV file (a call recording, an audio tap or an imported file)
+// on the default output device. Speed changes go through a WSOLA time-stretch
+// (waveform-similarity overlap-add): the recording is cut into overlapping windows that
+// are re-spaced for the new speed, each shifted by up to SEARCH_MS to where it lines up
+// best with the audio before it, so voices keep their pitch at 1.5x instead of turning
+// into chipmunks. While a recording is loaded, "playback_position" events report the
+// position on the recording's own clock (independent of the rate) so the review screen
+// can highlight the transcript line being heard - transcript segments, session prompt
+// offsets and topic chapters use the same clock. The chosen rate is remembered.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+const MIN_RATE: f32 = 0.5;
+const MAX_RATE: f32 = 2.0;
+// Stretch window (two hops, 50% overlap) and how far a window may move to line up
+const WINDOW_MS: usize = 40;
+const SEARCH_MS: usize = 10;
+const POSITION_INTERVAL_MS: u64 = 100;
+
+// Payload of "playback_position"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct PlaybackStatus {
+    pub path: Option<String>,
+    pub playing: bool,       // False when paused, finished or nothing is loaded
+    pub position_ms: u64,    // Recording time, whatever the rate
+    pub duration_ms: u64,
+    pub rate: f32,
+}
+
+/// WSOLA time-stretch over a mono recording
+struct Stretcher {
+    window: Vec<f32>,          // Periodic Hann: overlapping halves add up to exactly 1
+    hop: usize,
+    search: usize,
+    position: f64,             // Recording sample where the next window nominally starts
+    previous: Option<usize>,   // Start of the last window taken
+    overlap: Vec<f32>,         // Second half of the last window, added to the next
+}
+
+fn sample_at(samples: &[f32], index: usize) -> f32 {
+    samples.get(index).copied().unwrap_or(0.0)
+}
+
+impl Stretcher {
+    fn new(sample_rate: u32, position: usize) -> Self {
+        let hop = (sample_rate as usize * WINDOW_MS / 2000).max(1);
+        Self {
+            window: (0..2 * hop)
+                .map(|i| 0.5 - 0.5 * (std::f32::consts::PI * i as f32 / hop as f32).cos())
+                .collect(),
+            hop,
+            search: sample_rate as usize * SEARCH_MS / 1000,
+            position: position as f64,
+            previous: None,
+            overlap: vec![0.0; hop],
+        }
+    }
+
+    /// Start near `nominal` whose first hop best matches what naturally followed the
+    /// previous window (normalized cross-correlation; `nominal` wins ties)
+    fn best_start(&self, samples: &[f32], nominal: usize) -> usize {
+        let target = match self.previous {
+            Some(previous) => previous + self.hop,
+            None => return nominal,
+        };
+        let score = |start: usize| {
+            let (mut dot, mut energy) = (0.0f32, 0.0f32);
+            for i in 0..self.hop {
+                let sample = sample_at(samples, start + i);
+                dot += sample * sample_at(samples, target + i);
+                energy += sample * sample;
+            }
+            dot / (energy.sqrt() + 1e-6)
+        };
+        let mut best = (nominal, score(nominal));
+        for start in nominal.saturating_sub(self.search)..=nominal + self.search {
+            let candidate = score(start);
+            if candidate > best.1 {
+                best = (start, candidate);
+            }
+        }
+        best.0
+    }
+
+    /// Next hop of stretched audio; None once the recording is used up
+    fn next_hop(&mut self, samples: &[f32], rate: f64) -> Option<Vec<f32>> {
+        let nominal = self.position.round() as usize;
+        if nominal >= samples.len() {
+            return None;
+        }
+        // At 1x the natural continuation is the nominal window: no search needed
+        let start = if rate == 1.0 { nominal } else { self.best_start(samples, nominal) };
+        let mut hop = Vec::with_capacity(self.hop);
+        for (i, weight) in self.window.iter().enumerate() {
+            let sample = sample_at(samples, start + i) * weight;
+            if i < self.hop {
+                hop.push(self.overlap[i] + sample);
+            } else {
+                self.overlap[i - self.hop] = sample;
+            }
+        }
+        self.previous = Some(start);
+        self.position += self.hop as f64 * rate;
+        Some(hop)
+    }
+}
+
+struct Player {
+    path: String,
+    recording: Vec<f32>,        // Mono
+    sample_rate: u32,
+    stretcher: Stretcher,
+    pending: VecDeque<f32>,     // Stretched audio not played yet (recording rate)
+    heard: f64,                 // Recording samples played so far
+    rate: f64,
+    paused: bool,
+    finished: bool,
+    // Linear resampling from the recording rate to the output rate
+    step: f64,
+    phase: f64,
+    previous: f32,
+    next: f32,
+}
+
+impl Player {
+    fn new(path: String, recording: Vec<f32>, sample_rate: u32, rate: f32) -> Self {
+        Self {
+            path,
+            recording,
+            sample_rate,
+            stretcher: Stretcher::new(sample_rate, 0),
+            pending: VecDeque::new(),
+            heard: 0.0,
+            rate: rate as f64,
+            paused: false,
+            finished: false,
+            step: 1.0,
+            phase: 0.0,
+            previous: 0.0,
+            next: 0.0,
+        }
+    }
+
+    fn pull(&mut self) -> f32 {
+        if self.pending.is_empty() {
+            match self.stretcher.next_hop(&self.recording, self.rate) {
+                Some(hop) => self.pending.extend(hop),
+                None => {
+                    self.finished = true;
+                    return 0.0;
+                }
+            }
+        }
+        self.heard = (self.heard + self.rate).min(self.recording.len() as f64);
+        self.pending.pop_front().unwrap_or(0.0)
+    }
+
+    /// Fill interleaved output frames (every channel gets the mono recording)
+    fn fill(&mut self, output: &mut [f32], channels: usize) {
+        for frame in output.chunks_mut(channels.max(1)) {
+            if self.paused || self.finished {
+                frame.fill(0.0);
+                continue;
+            }
+            while self.phase >= 1.0 {
+                self.previous = self.next;
+                self.next = self.pull();
+                self.phase -= 1.0;
+            }
+            let sample = self.previous + (self.next - self.previous) * self.phase as f32;
+            frame.fill(sample.clamp(-1.0, 1.0));
+            self.phase += self.step;
+        }
+    }
+
+    fn seek(&mut self, position_ms: u64) {
+        let sample = ((position_ms * self.sample_rate as u64 / 1000) as usize).min(self.recording.len());
+        self.stretcher = Stretcher::new(self.sample_rate, sample);
+        self.pending.clear();
+        self.heard = sample as f64;
+        self.finished = sample >= self.recording.len();
+    }
+
+    fn to_ms(&self, samples: f64) -> u64 {
+        (samples * 1000.0 / self.sample_rate as f64) as u64
+    }
+
+    fn status(&self) -> PlaybackStatus {
+        PlaybackStatus {
+            path: Some(self.path.clone()),
+            playing: !self.paused && !self.finished,
+            position_ms: self.to_ms(self.heard),
+            duration_ms: self.to_ms(self.recording.len() as f64),
+            rate: self.rate as f32,
+        }
+    }
+}
+
+struct Playback {
+    player: Arc<Mutex<Player>>,
+    close: mpsc::Sender<()>,
+    keeper: JoinHandle<()>,
+}
+
+static PLAYBACK: Lazy<Mutex<Option<Playback>>> = Lazy::new(|| Mutex::new(None));
+
+fn saved_rate() -> f32 {
+    crate::preferences::load().playback_rate.unwrap_or(1.0).clamp(MIN_RATE, MAX_RATE)
+}
+
+fn build_output(device: &cpal::Device, config: &cpal::StreamConfig, format: cpal::SampleFormat, player: Arc<Mutex<Player>>) -> Result<cpal::Stream, String> {
+    let channels = config.channels as usize;
+    crate::sample_format::build_output_stream(
+        device,
+        config,
+        format,
+        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+            // A command holding the player costs one buffer of silence, never a blocked callback
+            if let Ok(mut player) = player.try_lock() {
+                player.fill(data, channels);
+            }
+        },
+        |err| error!("Playback output error: {:?}", err),
+    ).map_err(|e| format!("Failed to build playback output: {}", e))
+}
+
+fn open_output(player: &Arc<Mutex<Player>>) -> Result<cpal::Stream, String> {
+    let device = cpal::default_host().default_output_device().ok_or("No output device available")?;
+    let supported = device.default_output_config().map_err(|e| format!("Failed to get output config: {}", e))?;
+    let config: cpal::StreamConfig = supported.config();
+    {
+        let mut player = player.lock().unwrap();
+        player.step = player.sample_rate as f64 / config.sample_rate.0 as f64;
+    }
+    let stream = build_output(&device, &config, supported.sample_format(), player.clone())?;
+    stream.play().map_err(|e| format!("Failed to start playback: {}", e))?;
+    info!("🔊 Playback on {} ({} Hz)", device.name().unwrap_or_default(), config.sample_rate.0);
+    Ok(stream)
+}
+
+/// Hold the output stream on its own thread (cpal streams can't move between threads)
+/// and report the position until closed
+fn start_keeper(app: AppHandle, player: Arc<Mutex<Player>>) -> Result<(mpsc::Sender<()>, JoinHandle<()>), String> {
+    let (started_tx, started_rx) = mpsc::channel();
+    let (close_tx, close_rx) = mpsc::channel::<()>();
+    let keeper = std::thread::Builder::new()
+        .name("playback".to_string())
+        .spawn(move || {
+            let stream = match open_output(&player) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let _ = started_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = started_tx.send(Ok(()));
+            let mut reported: Option<PlaybackStatus> = None;
+            while let Err(RecvTimeoutError::Timeout) = close_rx.recv_timeout(Duration::from_millis(POSITION_INTERVAL_MS)) {
+                let status = player.lock().unwrap().status();
+                if reported.as_ref() != Some(&status) {
+                    if let Err(e) = app.emit_all("playback_position", status.clone()) {
+                        error!("Failed to emit playback_position: {:?}", e);
+                    }
+                    reported = Some(status);
+                }
+            }
+            drop(stream);
+        })
+        .map_err(|e| format!("Failed to spawn playback thread: {}", e))?;
+    started_rx.recv().map_err(|_| "Playback thread exited before starting".to_string())??;
+    Ok((close_tx, keeper))
+}
+
+fn stop_current() {
+    let playback = PLAYBACK.lock().unwrap().take();
+    if let Some(playback) = playback {
+        let _ = playback.close.send(());
+        let _ = playback.keeper.join();
+        info!("⏹️ Playback stopped");
+    }
+}
+
+fn with_player(f: impl FnOnce(&mut Player)) -> Result<PlaybackStatus, String> {
+    let playback = PLAYBACK.lock().unwrap();
+    let playback = playback.as_ref().ok_or("No recording is loaded")?;
+    let mut player = playback.player.lock().unwrap();
+    f(&mut player);
+    Ok(player.status())
+}
+
+fn idle_status() -> PlaybackStatus {
+    PlaybackStatus { path: None, playing: false, position_ms: 0, duration_ms: 0, rate: saved_rate() }
+}
+
+// ========== Tauri Commands ==========
+
+// Load a WAV recording and play it from `position_ms` (the start when None) at the saved rate
+#[tauri::command]
+pub async fn play_recording(app: AppHandle, path: String, position_ms: Option<u64>) -> Result<PlaybackStatus, String> {
+    stop_current();
+    let file = path.clone();
+    let (recording, sample_rate) = tokio::task::spawn_blocking(move || crate::file_transcription::read_wav_mono(&file))
+        .await
+        .map_err(|e| format!("Loading the recording failed: {}", e))?
+        .map_err(|e| e.to_string())?;
+    if recording.is_empty() {
+        return Err(format!("{} has no audio", path));
+    }
+
+    let mut player = Player::new(path.clone(), recording, sample_rate, saved_rate());
+    if let Some(position_ms) = position_ms {
+        player.seek(position_ms);
+    }
+    let player = Arc::new(Mutex::new(player));
+    let (close, keeper) = start_keeper(app, player.clone())?;
+    let status = player.lock().unwrap().status();
+    info!("▶️ Playing {} at {:.2}x ({} ms)", path, status.rate, status.duration_ms);
+    *PLAYBACK.lock().unwrap() = Some(Playback { player, close, keeper });
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn set_playback_paused(paused: bool) -> Result<PlaybackStatus, String> {
+    with_player(|player| player.paused = paused)
+}
+
+#[tauri::command]
+pub fn seek_playback(position_ms: u64) -> Result<PlaybackStatus, String> {
+    with_player(|player| player.seek(position_ms))
+}
+
+// Speed from 0.5x to 2x, pitch preserved; applies at once and to later recordings
+#[tauri::command]
+pub fn set_playback_rate(rate: f32) -> Result<PlaybackStatus, String> {
+    if !(MIN_RATE..=MAX_RATE).contains(&rate) {
+        return Err(format!("rate must be between {} and {}", MIN_RATE, MAX_RATE));
+    }
+    crate::preferences::update(|p| p.playback_rate = Some(rate))
+        .map_err(|e| e.to_string())?;
+    info!("⏩ Playback rate {:.2}x", rate);
+    Ok(with_player(|player| player.rate = rate as f64).unwrap_or_else(|_| idle_status()))
+}
+
+#[tauri::command]
+pub fn stop_playback() -> Result<PlaybackStatus, String> {
+    stop_current();
+    Ok(idle_status())
+}
+
+#[tauri::command]
+pub fn get_playback_status() -> Result<PlaybackStatus, String> {
+    Ok(with_player(|_| {}).unwrap_or_else(|_| idle_status()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stretch(samples: &[f32], rate: f64) -> Vec<f32> {
+        let mut stretcher = Stretcher::new(16_000, 0);
+        std::iter::from_fn(|| stretcher.next_hop(samples, rate)).flatten().collect()
+    }
+
+    // Upward zero crossings per second
+    fn frequency(samples: &[f32]) -> f32 {
+        let crossings = samples.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        crossings as f32 * 16_000.0 / samples.len() as f32
+    }
+
+    #[test]
+    fn test_stretch_keeps_pitch_and_scales_duration() {
+        let tone: Vec<f32> = (0..16_000)
+            .map(|i| (2.0 * std::f32::consts::PI * 200.0 * i as f32 / 16_000.0).sin() * 0.5)
+            .collect();
+
+        // 1x reproduces the recording after the first hop's fade-in
+        let same = stretch(&tone, 1.0);
+        assert!(same[320..16_000].iter().zip(&tone[320..]).all(|(a, b)| (a - b).abs() < 1e-5));
+
+        for rate in [1.5, 0.5] {
+            let stretched = stretch(&tone, rate);
+            let expected = 16_000.0 / rate as f32;
+            assert!((stretched.len() as f32 - expected).abs() < 700.0, "{}x gave {} samples", rate, stretched.len());
+            let body = &stretched[640..stretched.len() - 640];
+            assert!((frequency(body) - 200.0).abs() < 10.0, "{}x plays at {} Hz", rate, frequency(body));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+// User Preferences - small JSON store in the app data directory
+// Holds per-install state (calibration results, URL knowledge sources etc.) that
+// should survive restarts but doesn't belong in vosk-config.jsonc, which is shared.
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+use crate::acceleration::WhisperBackendSettings;
+use crate::adaptive_vad::AdaptiveVadSettings;
+use crate::audio_snippets::SnippetSettings;
+use crate::calibration::CalibrationResult;
+use crate::call_analytics::ChecklistItemDef;
+use crate::call_recording::CallRecordingSettings;
+use crate::cloud_usage::SilenceSkipSettings;
+use crate::coaching_cooldown::CoachingProfile;
+use crate::competitor_watch::CompetitorWatchlist;
+use crate::control_interface::ControlInterfaceSettings;
+use crate::device_selection::DeviceRule;
+use crate::document_processing::UrlSource;
+use crate::experiments::ExperimentSettings;
+use crate::export_security::ExportSecuritySettings;
+use crate::followup_email::EmailTemplateSettings;
+use crate::hardware_mute::HardwareMuteSettings;
+use crate::hold_detection::HoldDetectionSettings;
+use crate::level_calibration::LevelCalibration;
+use crate::live_doc::LiveDocSettings;
+use crate::memory_budget::MemoryBudget;
+use crate::mic_quality::MicQualitySettings;
+use crate::no_coach_zones::NoCoachZone;
+use crate::noise_suppression::NoiseSuppressionSettings;
+use crate::peer_benchmark::BenchmarkSettings;
+use crate::privacy::PrivacySettings;
+use crate::prospect_brief::EnrichmentSettings;
+use crate::profanity_filter::ProfanitySettings;
+use crate::punctuation::PunctuationSettings;
+use crate::sales_stage::StageBiasSettings;
+use crate::session_templates::SessionTemplateSettings;
+use crate::sidetone::SidetoneSettings;
+use crate::startup::StartupOptions;
+use crate::talk_over::TalkOverSettings;
+use crate::transcript_plugins::TranscriptPluginSettings;
+use crate::transcript_quality::TranscriptQualitySettings;
+use crate::two_pass::TwoPassMode;
+use crate::updates::UpdateSettings;
+use crate::voice_commands::VoiceCommandSettings;
+use crate::window_layout::WindowPlacement;
+use crate::whisper_channel::WhisperChannelSettings;
+
+const PREFERENCES_FILE: &str = "voicecoach_preferences.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct Preferences {
+    #[serde(default)]
+    pub calibration: Option<CalibrationResult>,
+    #[serde(default)]
+    pub url_sources: Vec<UrlSource>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItemDef>,  // Empty = built-in default checklist
+    #[serde(default)]
+    pub device_rules: Option<Vec<DeviceRule>>,  // None = built-in default rules
+    #[serde(default)]
+    pub export_security: ExportSecuritySettings,
+    #[serde(default)]
+    pub level_calibration: LevelCalibration,
+    #[serde(default)]
+    pub live_doc: Option<LiveDocSettings>,
+    #[serde(default)]
+    pub profanity_filter: ProfanitySettings,
+    #[serde(default)]
+    pub updates: UpdateSettings,
+    #[serde(default)]
+    pub stage_bias: StageBiasSettings,
+    #[serde(default)]
+    pub memory_budget: MemoryBudget,
+    #[serde(default)]
+    pub punctuation: PunctuationSettings,
+    #[serde(default)]
+    pub silence_skipping: SilenceSkipSettings,
+    #[serde(default)]
+    pub followup_email: EmailTemplateSettings,
+    #[serde(default)]
+    pub privacy: PrivacySettings,
+    #[serde(default)]
+    pub two_pass: TwoPassMode,
+    #[serde(default)]
+    pub enrichment: EnrichmentSettings,
+    #[serde(default)]
+    pub sidetone: SidetoneSettings,
+    #[serde(default)]
+    pub session_templates: SessionTemplateSettings,
+    #[serde(default)]
+    pub competitors: CompetitorWatchlist,
+    #[serde(default)]
+    pub playback_rate: Option<f32>,  // Review speed (None = 1x)
+    #[serde(default)]
+    pub locale: Option<String>,  // Backend text locale (None = system language)
+    #[serde(default)]
+    pub mic_quality: MicQualitySettings,
+    #[serde(default)]
+    pub coaching_profile: CoachingProfile,
+    #[serde(default)]
+    pub control_interface: ControlInterfaceSettings,
+    #[serde(default)]
+    pub adaptive_vad: AdaptiveVadSettings,
+    #[serde(default)]
+    pub voice_commands: VoiceCommandSettings,
+    #[serde(default)]
+    pub whisper_channel: WhisperChannelSettings,
+    #[serde(default)]
+    pub startup: StartupOptions,
+    #[serde(default)]
+    pub hold_detection: HoldDetectionSettings,
+    #[serde(default)]
+    pub transcript_quality: TranscriptQualitySettings,
+    #[serde(default)]
+    pub benchmarks: BenchmarkSettings,
+    #[serde(default)]
+    pub hardware_mute: HardwareMuteSettings,
+    #[serde(default)]
+    pub snippets: SnippetSettings,
+    #[serde(default)]
+    pub noise_suppression: NoiseSuppressionSettings,
+    #[serde(default)]
+    pub no_coach_zones: Vec<NoCoachZone>,
+    #[serde(default)]
+    pub window_layouts: Vec<WindowPlacement>,
+    #[serde(default)]
+    pub whisper_backend: WhisperBackendSettings,
+    #[serde(default)]
+    pub transcript_plugins: TranscriptPluginSettings,
+    #[serde(default)]
+    pub experiments: ExperimentSettings,
+    #[serde(default)]
+    pub talk_over: TalkOverSettings,
+    #[serde(default)]
+    pub call_recording: CallRecordingSettings,
+}
+
+// Serializes read-modify-write cycles across commands
+static PREFERENCES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn preferences_path() -> PathBuf {
+    crate::app_paths::data_dir().join(PREFERENCES_FILE)
+}
+
+fn read_from_disk() -> Preferences {
+    let path = preferences_path();
+    match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("⚠️ Preferences file {:?} is invalid, using defaults: {}", path, e);
+            Preferences::default()
+        }),
+        Err(_) => Preferences::default(),
+    }
+}
+
+fn write_to_disk(preferences: &Preferences) -> Result<()> {
+    let path = preferences_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(preferences)?;
+    fs::write(&path, json).context(format!("Failed to write preferences: {:?}", path))?;
+    info!("💾 Preferences saved to {:?}", path);
+    Ok(())
+}
+
+/// Whether preferences were ever saved on this machine (false on first run)
+pub fn stored() -> bool {
+    preferences_path().exists()
+}
+
+/// Load preferences (defaults if the file is missing or unreadable)
+pub fn load() -> Preferences {
+    let _guard = PREFERENCES_LOCK.lock().unwrap();
+    read_from_disk()
+}
+
+/// Apply a change and persist it, returning the updated preferences
+pub fn update<F: FnOnce(&mut Preferences)>(change: F) -> Result<Preferences> {
+    let _guard = PREFERENCES_LOCK.lock().unwrap();
+    let mut preferences = read_from_disk();
+    change(&mut preferences);
+    write_to_disk(&preferences)?;
+    Ok(preferences)
+}
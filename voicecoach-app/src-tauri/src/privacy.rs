@@ -0,0 +1,308 @@
+// Privacy Mute - system-wide capture kill switch
+// Every capture stream is opened through open_stream, which builds it on a keeper
+// thread that owns it until it is closed (cpal streams can't move between threads,
+// so they used to be leaked and only gated). Muting ends transcription and standby,
+// closes and joins every keeper thread, flips the tray icon and emits a
+// "capture_state" event whose open_streams count comes from the registry after the
+// streams were dropped. While muted, starting capture fails unless auto re-arm is
+// enabled, in which case the next session start unmutes. One-party consent mode (the
+// prospect channel metered, never transcribed) lives in one_party.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread::JoinHandle;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+const MUTED_ERROR: &str = "Capture is muted (privacy mode)";
+const TRAY_ITEM: &str = "privacy";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct PrivacySettings {
+    /// Unmute automatically when the next transcription session starts
+    #[serde(default)]
+    pub auto_rearm: bool,
+    /// Transcribe and store only the rep's side (one_party)
+    #[serde(default)]
+    pub one_party_consent: bool,
+}
+
+// Payload of "capture_state" and result of the privacy commands
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CaptureState {
+    pub muted: bool,
+    pub auto_rearm: bool,
+    pub open_streams: usize,     // Capture streams currently held open
+    pub streams: Vec<String>,    // Owner of each open stream
+    pub changed_at: Option<u64>,
+}
+
+/// A capture stream that starts once built on its keeper thread
+pub trait CaptureStream {
+    fn start(&self) -> Result<(), String>;
+}
+
+impl CaptureStream for cpal::Stream {
+    fn start(&self) -> Result<(), String> {
+        use cpal::traits::StreamTrait;
+        self.play().map_err(|e| format!("Failed to start stream: {}", e))
+    }
+}
+
+struct HeldStream {
+    id: u64,
+    owner: &'static str,
+    close: mpsc::Sender<()>,
+    keeper: JoinHandle<()>,
+}
+
+static STREAMS: Lazy<Mutex<Vec<HeldStream>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static NEXT_STREAM_ID: AtomicU64 = AtomicU64::new(1);
+static MUTED: AtomicBool = AtomicBool::new(false);
+static CHANGED_AT: AtomicU64 = AtomicU64::new(0);
+// Tray icon from tauri.conf.json, restored on unmute
+static TRAY_ICON: Lazy<Mutex<Option<tauri::Icon>>> = Lazy::new(|| Mutex::new(None));
+
+/// Build and start a stream on its own keeper thread, which holds it until closed
+pub fn open_stream<S, F>(owner: &'static str, build: F) -> Result<u64, String>
+where
+    S: CaptureStream + 'static,
+    F: FnOnce() -> Result<S, String> + Send + 'static,
+{
+    if is_muted() {
+        return Err(MUTED_ERROR.to_string());
+    }
+    let (started_tx, started_rx) = mpsc::channel();
+    let (close_tx, close_rx) = mpsc::channel::<()>();
+    let keeper = std::thread::Builder::new()
+        .name(format!("capture-{}", owner))
+        .spawn(move || {
+            let stream = match build().and_then(|stream| stream.start().map(|_| stream)) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let _ = started_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = started_tx.send(Ok(()));
+            // Close requested (or the registry entry was dropped)
+            let _ = close_rx.recv();
+            drop(stream);
+        })
+        .map_err(|e| format!("Failed to spawn capture thread: {}", e))?;
+    started_rx.recv().map_err(|_| "Capture thread exited before starting".to_string())??;
+
+    // A mute that came in while the stream was being built has already closed the
+    // registry without it; muting sets MUTED before it takes the lock
+    let mut streams = STREAMS.lock().unwrap();
+    if is_muted() {
+        drop(streams);
+        let _ = close_tx.send(());
+        if keeper.join().is_err() {
+            warn!("⚠️ Capture thread for a stream muted while opening ({}) panicked", owner);
+        }
+        return Err(MUTED_ERROR.to_string());
+    }
+    let id = NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed);
+    streams.push(HeldStream { id, owner, close: close_tx, keeper });
+    drop(streams);
+    info!("🎙️ Capture stream {} opened ({})", id, owner);
+    Ok(id)
+}
+
+/// Close the streams of `owner` (all when None) and wait until they are dropped
+pub fn close_streams(owner: Option<&str>) -> usize {
+    close_where(|s| owner.map_or(true, |o| s.owner == o))
+}
+
+/// Close the stream `id` (as returned by open_stream) and wait until it is dropped
+pub fn close_stream(id: u64) -> bool {
+    close_where(|s| s.id == id) > 0
+}
+
+fn close_where(matches: impl Fn(&HeldStream) -> bool) -> usize {
+    let closing: Vec<HeldStream> = {
+        let mut streams = STREAMS.lock().unwrap();
+        let (closing, kept) = streams.drain(..).partition(|s| matches(s));
+        *streams = kept;
+        closing
+    };
+    let count = closing.len();
+    for stream in closing {
+        let _ = stream.close.send(());
+        if stream.keeper.join().is_err() {
+            warn!("⚠️ Capture thread for stream {} ({}) panicked", stream.id, stream.owner);
+        }
+        info!("🔌 Capture stream {} closed ({})", stream.id, stream.owner);
+    }
+    count
+}
+
+pub fn is_muted() -> bool {
+    MUTED.load(Ordering::SeqCst)
+}
+
+/// Gate for session starts: Err while muted, unless auto re-arm unmutes first
+pub fn ensure_capture_allowed(app: &AppHandle) -> Result<(), String> {
+    if !is_muted() {
+        return Ok(());
+    }
+    if crate::preferences::load().privacy.auto_rearm {
+        info!("🔓 Session starting - privacy mute auto re-armed");
+        set_muted(app, false);
+        return Ok(());
+    }
+    Err(MUTED_ERROR.to_string())
+}
+
+fn capture_state() -> CaptureState {
+    let streams = STREAMS.lock().unwrap();
+    let changed_at = CHANGED_AT.load(Ordering::Relaxed);
+    CaptureState {
+        muted: is_muted(),
+        auto_rearm: crate::preferences::load().privacy.auto_rearm,
+        open_streams: streams.len(),
+        streams: streams.iter().map(|s| s.owner.to_string()).collect(),
+        changed_at: Some(changed_at).filter(|&t| t > 0),
+    }
+}
+
+/// Remember the configured tray icon so unmuting can put it back
+pub fn remember_tray_icon(icon: Option<tauri::Icon>) {
+    *TRAY_ICON.lock().unwrap() = icon;
+}
+
+/// 32x32 grey microphone disc with a red slash
+fn muted_icon() -> tauri::Icon {
+    const SIZE: u32 = 32;
+    let mut rgba = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let (dx, dy) = (x as f32 - 15.5, y as f32 - 15.5);
+            let on_slash = (x as i32 - y as i32).abs() <= 2 && (4..28).contains(&x);
+            let in_disc = dx * dx + dy * dy <= 15.0 * 15.0;
+            let pixel = if on_slash {
+                [220, 38, 38, 255]
+            } else if in_disc {
+                [110, 110, 110, 255]
+            } else {
+                [0, 0, 0, 0]
+            };
+            rgba.extend_from_slice(&pixel);
+        }
+    }
+    tauri::Icon::Rgba { rgba, width: SIZE, height: SIZE }
+}
+
+fn update_tray(app: &AppHandle, muted: bool) {
+    let tray = app.tray_handle();
+    let icon = if muted { Some(muted_icon()) } else { TRAY_ICON.lock().unwrap().clone() };
+    if let Some(icon) = icon {
+        if let Err(e) = tray.set_icon(icon) {
+            warn!("⚠️ Failed to update tray icon: {:?}", e);
+        }
+    }
+    let _ = tray.set_tooltip(if muted { "VoiceCoach - capture muted" } else { "VoiceCoach" });
+    let _ = tray.get_item(TRAY_ITEM).set_title(if muted { "Resume capture" } else { "Mute capture (privacy)" });
+}
+
+fn set_muted(app: &AppHandle, muted: bool) -> CaptureState {
+    MUTED.store(muted, Ordering::SeqCst);
+    CHANGED_AT.store(chrono::Utc::now().timestamp_millis() as u64, Ordering::Relaxed);
+    if muted {
+        // Stop the engines first so nothing reopens a stream, then close what is left
+        crate::vosk_transcription::close_capture(app);
+        crate::deepgram_transcription::close_capture();
+        let closed = close_streams(None);
+        info!("🔇 Privacy mute on - {} capture stream(s) closed", closed);
+    } else {
+        info!("🎙️ Privacy mute off");
+    }
+    update_tray(app, muted);
+
+    let state = capture_state();
+    if muted && state.open_streams > 0 {
+        error!("❌ {} capture stream(s) still open after privacy mute", state.open_streams);
+    }
+    if let Err(e) = app.emit_all("capture_state", state.clone()) {
+        error!("Failed to emit capture_state: {:?}", e);
+    }
+    state
+}
+
+/// Tray menu toggle
+pub fn toggle(app: &AppHandle) {
+    set_muted(app, !is_muted());
+}
+
+/// Tray menu item for the toggle
+pub fn tray_menu_item() -> tauri::CustomMenuItem {
+    tauri::CustomMenuItem::new(TRAY_ITEM.to_string(), "Mute capture (privacy)")
+}
+
+pub fn is_tray_item(id: &str) -> bool {
+    id == TRAY_ITEM
+}
+
+// ========== Tauri Commands ==========
+
+// Mute/unmute all capture (`auto_rearm` is stored when given)
+#[tauri::command]
+pub fn set_privacy_mute(app: AppHandle, muted: bool, auto_rearm: Option<bool>) -> Result<CaptureState, String> {
+    if let Some(auto_rearm) = auto_rearm {
+        crate::preferences::update(|p| p.privacy.auto_rearm = auto_rearm)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(set_muted(&app, muted))
+}
+
+#[tauri::command]
+pub fn get_capture_state() -> Result<CaptureState, String> {
+    Ok(capture_state())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    struct FakeStream(Arc<AtomicBool>);
+
+    impl CaptureStream for FakeStream {
+        fn start(&self) -> Result<(), String> {
+            self.0.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    impl Drop for FakeStream {
+        fn drop(&mut self) {
+            self.0.store(false, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_closing_drops_the_stream_before_returning() {
+        let live = Arc::new(AtomicBool::new(false));
+        let flag = live.clone();
+        open_stream("privacy-test", move || Ok(FakeStream(flag))).unwrap();
+        assert!(live.load(Ordering::SeqCst));
+
+        assert!(open_stream("privacy-test", || Err::<FakeStream, _>("no device".to_string())).is_err());
+        assert_eq!(close_streams(Some("privacy-test")), 1);
+        assert!(!live.load(Ordering::SeqCst));
+
+        // Muted while the stream was being built: it is closed, never registered
+        let flag = live.clone();
+        let opened = open_stream("privacy-test", move || {
+            MUTED.store(true, Ordering::SeqCst);
+            Ok(FakeStream(flag))
+        });
+        MUTED.store(false, Ordering::SeqCst);
+        assert_eq!(opened, Err(MUTED_ERROR.to_string()));
+        assert!(!live.load(Ordering::SeqCst));
+        assert_eq!(close_streams(Some("privacy-test")), 0);
+    }
+}
@@ -0,0 +1,175 @@
+// Profanity Filter - optional masking/tagging of transcript text
+// Applied where transcripts leave the engines (before frontend emission), so the
+// UI, OBS captions, live documents and anything exported from the UI all see the
+// filtered text. Settings and the allowlist are stored in preferences, i.e. per
+// install/workspace.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::info;
+
+// Matched as word prefixes ("fucking", "shitty", "bitches")
+const PROFANE_ROOTS: &[&str] = &[
+    "fuck", "motherfuck", "shit", "bullshit", "bitch", "cunt", "bastard",
+    "asshole", "dickhead", "wank", "douche",
+];
+// Matched as whole words only (prefix matching would hit "assume", "cockpit", ...)
+const PROFANE_WORDS: &[&str] = &[
+    "ass", "arse", "damn", "goddamn", "dick", "dicks", "piss", "pissed", "crap", "cock",
+    "cocks", "prick", "twat", "slut", "whore", "bollocks", "jackass", "dumbass",
+];
+
+const TAG_TEXT: &str = "[profanity]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterMode {
+    Mask,  // "f***"
+    Tag,   // "[profanity]"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProfanitySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_mode")]
+    pub mode: FilterMode,
+    /// Words never filtered for this workspace (e.g. "damn", product names)
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    /// Additional words to filter (whole-word match)
+    #[serde(default)]
+    pub extra_words: Vec<String>,
+}
+
+fn default_mode() -> FilterMode { FilterMode::Mask }
+
+impl Default for ProfanitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: default_mode(),
+            allowlist: Vec::new(),
+            extra_words: Vec::new(),
+        }
+    }
+}
+
+// Cached copy of the preferences entry (transcripts are filtered on audio threads)
+static SETTINGS: Lazy<Mutex<Option<ProfanitySettings>>> = Lazy::new(|| Mutex::new(None));
+
+fn current_settings() -> ProfanitySettings {
+    SETTINGS.lock().unwrap()
+        .get_or_insert_with(|| crate::preferences::load().profanity_filter)
+        .clone()
+}
+
+fn is_profane(word: &str, settings: &ProfanitySettings) -> bool {
+    let word = word.to_lowercase();
+    let word = word.trim_matches('\'');
+    if settings.allowlist.iter().any(|allowed| allowed.trim().eq_ignore_ascii_case(word)) {
+        return false;
+    }
+    PROFANE_ROOTS.iter().any(|root| word.starts_with(root))
+        || PROFANE_WORDS.contains(&word)
+        || settings.extra_words.iter().any(|extra| extra.trim().eq_ignore_ascii_case(word))
+}
+
+fn replacement(word: &str, mode: FilterMode) -> String {
+    match mode {
+        FilterMode::Mask => {
+            let mut chars = word.chars();
+            let first = chars.next().map(String::from).unwrap_or_default();
+            first + &"*".repeat(chars.count())
+        }
+        FilterMode::Tag => TAG_TEXT.to_string(),
+    }
+}
+
+/// Filter `text` with explicit settings; punctuation and spacing are preserved
+pub fn filter_with(text: &str, settings: &ProfanitySettings) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, output: &mut String| {
+        if !word.is_empty() {
+            if is_profane(word, settings) {
+                output.push_str(&replacement(word, settings.mode));
+            } else {
+                output.push_str(word);
+            }
+            word.clear();
+        }
+    };
+
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '\'' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut output);
+            output.push(c);
+        }
+    }
+    flush(&mut word, &mut output);
+    output
+}
+
+/// Filter transcript text with the stored settings (returns the input unchanged when disabled)
+pub fn filter_transcript(text: &str) -> String {
+    let settings = current_settings();
+    if settings.enabled {
+        filter_with(text, &settings)
+    } else {
+        text.to_string()
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_profanity_filter() -> Result<ProfanitySettings, String> {
+    Ok(current_settings())
+}
+
+#[tauri::command]
+pub fn set_profanity_filter(settings: ProfanitySettings) -> Result<ProfanitySettings, String> {
+    crate::preferences::update(|p| p.profanity_filter = settings.clone())
+        .map_err(|e| e.to_string())?;
+    *SETTINGS.lock().unwrap() = Some(settings.clone());
+    info!("🧼 Profanity filter {} ({:?}, {} allowlisted)",
+          if settings.enabled { "enabled" } else { "disabled" }, settings.mode, settings.allowlist.len());
+    Ok(settings)
+}
+
+// Filter arbitrary text (e.g. transcripts the UI assembles for export)
+#[tauri::command]
+pub fn apply_profanity_filter(text: String) -> Result<String, String> {
+    Ok(filter_transcript(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(mode: FilterMode, allowlist: &[&str]) -> ProfanitySettings {
+        ProfanitySettings {
+            enabled: true,
+            mode,
+            allowlist: allowlist.iter().map(|w| w.to_string()).collect(),
+            extra_words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_mask_preserves_punctuation_and_spares_innocent_words() {
+        let filtered = filter_with("Well, shit. I assume the fucking demo crashed?", &settings(FilterMode::Mask, &[]));
+        assert_eq!(filtered, "Well, s***. I assume the f****** demo crashed?");
+    }
+
+    #[test]
+    fn test_tag_mode_and_allowlist() {
+        let filtered = filter_with("damn that crap price", &settings(FilterMode::Tag, &["Damn"]));
+        assert_eq!(filtered, "damn that [profanity] price");
+    }
+}
@@ -0,0 +1,262 @@
+// Prospect Brief - company research attached to a session before the call
+// set_session_company names the prospect's company for the session in progress or,
+// if none is running, for the next one to start. Each configured enrichment provider
+// (public JSON endpoints such as Wikipedia's page summary or a web search API) is
+// queried with the company name, the text it returns is summarized by the local LLM
+// into a short brief, and the brief is stored with the session. "session_brief_ready"
+// fires when it is available; get_session_brief returns it afterwards.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+use crate::ollama_integration::OllamaCoachingService;
+
+const PROVIDER_TIMEOUT_SECS: u64 = 10;
+// Per-provider text passed to the summarizer (and kept as the source excerpt)
+const MAX_SOURCE_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct EnrichmentProvider {
+    pub name: String,
+    /// Request URL; "{company}" is replaced with the URL-encoded company name
+    pub url: String,
+    /// JSON pointer to the text in the response (e.g. "/extract"); None = whole body
+    #[serde(default)]
+    pub text_pointer: Option<String>,
+    /// Extra request headers (e.g. a search API key)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct EnrichmentSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_providers")]
+    pub providers: Vec<EnrichmentProvider>,
+}
+
+fn default_true() -> bool { true }
+
+fn default_providers() -> Vec<EnrichmentProvider> {
+    vec![
+        EnrichmentProvider {
+            name: "wikipedia".to_string(),
+            url: "https://en.wikipedia.org/api/rest_v1/page/summary/{company}".to_string(),
+            text_pointer: Some("/extract".to_string()),
+            headers: HashMap::new(),
+        },
+        EnrichmentProvider {
+            name: "duckduckgo".to_string(),
+            url: "https://api.duckduckgo.com/?q={company}&format=json&no_html=1".to_string(),
+            text_pointer: Some("/AbstractText".to_string()),
+            headers: HashMap::new(),
+        },
+    ]
+}
+
+impl Default for EnrichmentSettings {
+    fn default() -> Self {
+        Self { enabled: true, providers: default_providers() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct BriefSource {
+    pub provider: String,
+    pub url: String,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProspectBrief {
+    pub company: String,
+    pub summary: String,
+    pub sources: Vec<BriefSource>,
+    pub generated_at: u64,
+    pub summarized_by: String,  // "ollama" or "excerpts" (LLM unavailable)
+}
+
+// Company named before the session started: (company, app for the ready event)
+static PENDING: Lazy<Mutex<Option<(String, AppHandle)>>> = Lazy::new(|| Mutex::new(None));
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Text of a provider response ("" when the pointer doesn't resolve to a string)
+fn extract_text(body: &str, pointer: Option<&str>) -> String {
+    match pointer {
+        Some(pointer) => serde_json::from_str::<serde_json::Value>(body).ok()
+            .and_then(|json| json.pointer(pointer).and_then(|v| v.as_str()).map(str::to_string))
+            .unwrap_or_default(),
+        None => body.to_string(),
+    }
+    .trim()
+    .to_string()
+}
+
+async fn query_provider(client: &reqwest::Client, provider: &EnrichmentProvider, company: &str) -> anyhow::Result<BriefSource> {
+    let url = provider.url.replace("{company}", &url_encode(company));
+    let mut request = client.get(&url)
+        .timeout(Duration::from_secs(PROVIDER_TIMEOUT_SECS))
+        .header("User-Agent", "VoiceCoach");
+    for (name, value) in &provider.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = request.send().await?.error_for_status()?;
+    let text = extract_text(&response.text().await?, provider.text_pointer.as_deref());
+    Ok(BriefSource { provider: provider.name.clone(), url, excerpt: truncate(&text, MAX_SOURCE_CHARS) })
+}
+
+fn summary_prompt(company: &str, sources: &[BriefSource]) -> String {
+    let mut prompt = format!("You are preparing a sales rep for a call with {}.\n\nRESEARCH:\n", company);
+    for source in sources {
+        prompt.push_str(&format!("[{}] {}\n\n", source.provider, source.excerpt));
+    }
+    prompt.push_str("Write a prospect brief of at most 120 words: what the company does, its size and market, \
+        and two or three angles or questions the rep could open with. Use only the research above; \
+        say so if it is thin.\n");
+    prompt
+}
+
+async fn build_brief(company: &str) -> ProspectBrief {
+    let settings = crate::preferences::load().enrichment;
+    let client = reqwest::Client::new();
+    let mut sources = Vec::new();
+    for provider in &settings.providers {
+        match query_provider(&client, provider, company).await {
+            Ok(source) if !source.excerpt.is_empty() => sources.push(source),
+            Ok(_) => info!("🔎 {} has nothing on {}", provider.name, company),
+            Err(e) => warn!("⚠️ Enrichment provider {} failed: {}", provider.name, e),
+        }
+    }
+
+    let service = OllamaCoachingService::new();
+    let summarized = if sources.is_empty() || !service.check_availability().await.unwrap_or(false) {
+        None
+    } else {
+        match service.complete(summary_prompt(company, &sources), 0.3, 300, 60).await {
+            Ok(summary) => Some(summary.trim().to_string()),
+            Err(e) => {
+                warn!("⚠️ Brief summarization failed: {}", e);
+                crate::telemetry::record_error("ollama");
+                None
+            }
+        }
+    };
+    let (summary, summarized_by) = match summarized {
+        Some(summary) => (summary, "ollama"),
+        None if sources.is_empty() => (format!("No public information found for {}.", company), "excerpts"),
+        None => (sources.iter().map(|s| truncate(&s.excerpt, 400)).collect::<Vec<_>>().join("\n\n"), "excerpts"),
+    };
+    ProspectBrief {
+        company: company.to_string(),
+        summary,
+        sources,
+        generated_at: chrono::Utc::now().timestamp_millis() as u64,
+        summarized_by: summarized_by.to_string(),
+    }
+}
+
+/// Research `company` in the background and attach the brief to `session_id`
+fn spawn_enrichment(app: AppHandle, session_id: String, company: String) {
+    if !crate::preferences::load().enrichment.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        info!("🔎 Researching {} for session {}", company, session_id);
+        let brief = build_brief(&company).await;
+        if let Err(e) = crate::session_store::attach_brief(&session_id, brief.clone()) {
+            error!("❌ Failed to store prospect brief: {}", e);
+            return;
+        }
+        if let Err(e) = app.emit_all("session_brief_ready", serde_json::json!({ "session_id": session_id, "brief": brief })) {
+            error!("Failed to emit session_brief_ready: {:?}", e);
+        }
+    });
+}
+
+/// A session just started: research the company named for it, if any
+pub fn session_started(session_id: &str) {
+    if let Some((company, app)) = PENDING.lock().unwrap().take() {
+        crate::session_store::set_company(session_id, &company);
+        spawn_enrichment(app, session_id.to_string(), company);
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Name the prospect's company for the session in progress (or the next one to start)
+#[tauri::command]
+pub fn set_session_company(app: AppHandle, company: String) -> Result<Option<String>, String> {
+    let company = company.trim().to_string();
+    if company.is_empty() {
+        return Err("Company name is empty".to_string());
+    }
+    match crate::session_store::current_session_id() {
+        Some(session_id) => {
+            crate::session_store::set_company(&session_id, &company);
+            spawn_enrichment(app, session_id.clone(), company);
+            Ok(Some(session_id))
+        }
+        None => {
+            *PENDING.lock().unwrap() = Some((company, app));
+            Ok(None)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_session_brief(session_id: Option<String>) -> Result<Option<ProspectBrief>, String> {
+    crate::session_store::session_brief(session_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_enrichment_settings() -> Result<EnrichmentSettings, String> {
+    Ok(crate::preferences::load().enrichment)
+}
+
+#[tauri::command]
+pub fn set_enrichment_settings(settings: EnrichmentSettings) -> Result<EnrichmentSettings, String> {
+    if let Some(provider) = settings.providers.iter().find(|p| !p.url.starts_with("https://") && !p.url.starts_with("http://")) {
+        return Err(format!("Provider {} needs an http(s) URL", provider.name));
+    }
+    crate::preferences::update(|p| p.enrichment = settings.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_urls_and_text_extraction() {
+        assert_eq!(url_encode("Acme & Sons, Inc."), "Acme%20%26%20Sons%2C%20Inc.");
+
+        let body = r#"{"title": "Acme", "extract": " Acme Corp makes anvils. "}"#;
+        assert_eq!(extract_text(body, Some("/extract")), "Acme Corp makes anvils.");
+        assert_eq!(extract_text(body, Some("/missing")), "");
+        assert_eq!(extract_text("plain text", None), "plain text");
+        assert_eq!(truncate("abcdef", 3), "abc…");
+    }
+}
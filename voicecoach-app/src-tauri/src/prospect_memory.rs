@@ -0,0 +1,293 @@
+// Prospect Memory - what earlier calls with the same prospect left behind
+// A session is linked to a prospect by the dialer's caller record (CRM id, phone) or
+// the company set for it. Earlier sessions that share any of these make up the
+// relationship memory of the call: the objections the prospect raised, what the rep
+// promised (sending material, following up, checking something), the preferences the
+// prospect stated, and how those calls ended and what they covered (topic chapters).
+// Only the most recent calls count and each list keeps its newest entries, so the
+// memory rolls forward with the relationship. It is built once per call (again when
+// the link changes), added to the coaching prompt as RELATIONSHIP MEMORY and returned
+// by get_prospect_memory.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::info;
+
+use crate::control_interface::CallerInfo;
+use crate::session_store::{CallOutcome, Session};
+
+// Earlier calls the memory is built from (the most recent ones)
+const MAX_CALLS: usize = 5;
+// Entries kept per list, newest first
+const MAX_ITEMS: usize = 5;
+const MAX_ITEM_CHARS: usize = 160;
+// A phone number shorter than this is an extension, not a prospect
+const MIN_PHONE_DIGITS: usize = 7;
+
+// Rep lines committing to something after the call
+const PROMISE_CUES: &[&str] = &[
+    "i'll send", "i will send", "we'll send", "we will send",
+    "i'll follow up", "i will follow up", "we'll follow up", "we will follow up",
+    "i'll get back to you", "i will get back to you", "we'll get back to you",
+    "i'll email", "i will email", "i'll call you", "i will call you",
+    "i'll check", "i will check", "let me check", "i'll find out", "i promise",
+];
+
+// Prospect lines stating what they want or need
+const PREFERENCE_CUES: &[&str] = &[
+    "we prefer", "i prefer", "we'd prefer", "we would prefer", "we'd rather", "we would rather",
+    "we need", "we don't want", "we do not want", "important to us", "important for us",
+    "has to be", "must have", "we only",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryKind {
+    Objection,
+    Promise,
+    Preference,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct MemoryItem {
+    pub kind: MemoryKind,
+    pub text: String,
+    pub session_id: String,
+    pub said_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PastCall {
+    pub session_id: String,
+    pub started_at: u64,
+    pub outcome: Option<CallOutcome>,
+    pub topics: Vec<String>,          // Chapter labels
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProspectMemory {
+    pub prospect: String,             // Caller name or company
+    pub identifiers: Vec<String>,     // "crm:…", "phone:…", "company:…"
+    pub calls: Vec<PastCall>,         // Newest first
+    pub objections: Vec<MemoryItem>,
+    pub promises: Vec<MemoryItem>,
+    pub preferences: Vec<MemoryItem>,
+}
+
+// Memory of the current call with the identifiers it was built for
+type BuiltMemory = (Vec<String>, Option<ProspectMemory>);
+static CURRENT: Lazy<Mutex<Option<BuiltMemory>>> = Lazy::new(|| Mutex::new(None));
+
+fn simplify(text: &str) -> String {
+    text.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+/// What links a session to a prospect: CRM id, phone number, company
+fn identifiers(company: Option<&str>, caller: Option<&CallerInfo>) -> Vec<String> {
+    let mut ids = Vec::new();
+    if let Some(crm_id) = caller.and_then(|c| c.crm_id.as_deref()).map(str::trim).filter(|id| !id.is_empty()) {
+        ids.push(format!("crm:{}", crm_id));
+    }
+    let phone: String = caller.and_then(|c| c.phone.as_deref()).unwrap_or_default()
+        .chars().filter(|c| c.is_ascii_digit()).collect();
+    if phone.len() >= MIN_PHONE_DIGITS {
+        ids.push(format!("phone:{}", phone));
+    }
+    let company = company.or_else(|| caller.and_then(|c| c.company.as_deref())).map(simplify).unwrap_or_default();
+    if !company.is_empty() {
+        ids.push(format!("company:{}", company));
+    }
+    ids
+}
+
+fn session_identifiers(session: &Session) -> Vec<String> {
+    identifiers(session.company.as_deref(), session.caller.as_ref())
+}
+
+fn display_name(session: &Session) -> String {
+    let caller = session.caller.as_ref();
+    caller.and_then(|c| c.name.clone())
+        .or_else(|| session.company.clone())
+        .or_else(|| caller.and_then(|c| c.company.clone()))
+        .unwrap_or_else(|| "this prospect".to_string())
+}
+
+fn item(kind: MemoryKind, text: &str, session: &Session, offset_ms: u64) -> MemoryItem {
+    let mut text = text.trim().to_string();
+    if text.chars().count() > MAX_ITEM_CHARS {
+        text = format!("{}…", text.chars().take(MAX_ITEM_CHARS).collect::<String>());
+    }
+    MemoryItem { kind, text, session_id: session.id.clone(), said_at: session.started_at + offset_ms }
+}
+
+/// Add an entry unless the list is full or already holds the same words
+fn remember(list: &mut Vec<MemoryItem>, entry: MemoryItem) {
+    if list.len() < MAX_ITEMS && !list.iter().any(|e| simplify(&e.text) == simplify(&entry.text)) {
+        list.push(entry);
+    }
+}
+
+/// Memory of `current` from the earlier sessions linked to the same prospect
+fn build(current: &Session, sessions: &[Session]) -> Option<ProspectMemory> {
+    let ids = session_identifiers(current);
+    if ids.is_empty() {
+        return None;
+    }
+    let promise_cues: Vec<String> = PROMISE_CUES.iter().map(|c| c.to_string()).collect();
+    let preference_cues: Vec<String> = PREFERENCE_CUES.iter().map(|c| c.to_string()).collect();
+    let mut memory = ProspectMemory {
+        prospect: display_name(current),
+        identifiers: ids.clone(),
+        calls: Vec::new(),
+        objections: Vec::new(),
+        promises: Vec::new(),
+        preferences: Vec::new(),
+    };
+
+    let earlier = sessions.iter()
+        .rev()
+        .filter(|s| s.id != current.id && s.started_at < current.started_at)
+        .filter(|s| session_identifiers(s).iter().any(|id| ids.contains(id)))
+        .take(MAX_CALLS);
+    for session in earlier {
+        memory.calls.push(PastCall {
+            session_id: session.id.clone(),
+            started_at: session.started_at,
+            outcome: session.outcome,
+            topics: session.chapters.iter().map(|c| c.label.clone()).collect(),
+        });
+        for line in session.transcript.iter().rev() {
+            if line.is_user {
+                if crate::call_analytics::find_phrase(&line.text, &promise_cues).is_some() {
+                    remember(&mut memory.promises, item(MemoryKind::Promise, &line.text, session, line.offset_ms));
+                }
+            } else if crate::sales_stage::is_objection(&line.text) {
+                remember(&mut memory.objections, item(MemoryKind::Objection, &line.text, session, line.offset_ms));
+            } else if crate::call_analytics::find_phrase(&line.text, &preference_cues).is_some() {
+                remember(&mut memory.preferences, item(MemoryKind::Preference, &line.text, session, line.offset_ms));
+            }
+        }
+    }
+    if memory.calls.is_empty() {
+        return None;
+    }
+    Some(memory)
+}
+
+fn date(ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map_or(String::new(), |t| t.format("%Y-%m-%d").to_string())
+}
+
+/// The memory as a prompt section
+fn render(memory: &ProspectMemory) -> String {
+    let mut text = format!("Earlier calls with {}:\n", memory.prospect);
+    for call in &memory.calls {
+        let outcome = call.outcome
+            .and_then(|o| serde_json::to_value(o).ok())
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_else(|| "no outcome recorded".to_string());
+        let topics = if call.topics.is_empty() { String::new() } else { format!(", covered {}", call.topics.join(", ")) };
+        text.push_str(&format!("- {}: {}{}\n", date(call.started_at), outcome, topics));
+    }
+    for (title, items) in [
+        ("Objections they raised", &memory.objections),
+        ("Promises the rep made", &memory.promises),
+        ("Preferences they stated", &memory.preferences),
+    ] {
+        if items.is_empty() {
+            continue;
+        }
+        text.push_str(&format!("{}:\n", title));
+        for item in items {
+            text.push_str(&format!("- \"{}\" ({})\n", item.text, date(item.said_at)));
+        }
+    }
+    text
+}
+
+/// Start of a new call: the memory is rebuilt for its prospect
+pub fn begin_call() {
+    *CURRENT.lock().unwrap() = None;
+}
+
+/// Memory of the call in progress (built on first use and when its link changes)
+fn current_memory() -> Option<ProspectMemory> {
+    let ids = crate::session_store::with_current(session_identifiers)?;
+    if ids.is_empty() {
+        return None;
+    }
+    let mut current = CURRENT.lock().unwrap();
+    if let Some((built_for, memory)) = current.as_ref() {
+        if *built_for == ids {
+            return memory.clone();
+        }
+    }
+    let session = crate::session_store::load_session(None).ok()?;
+    let memory = build(&session, &crate::session_store::all_sessions());
+    if let Some(memory) = &memory {
+        info!("🤝 Relationship memory for {}: {} earlier calls, {} objections, {} promises, {} preferences",
+            memory.prospect, memory.calls.len(), memory.objections.len(), memory.promises.len(), memory.preferences.len());
+    }
+    *current = Some((ids, memory.clone()));
+    memory
+}
+
+/// RELATIONSHIP MEMORY section for the coaching prompt, when the prospect was called before
+pub fn prompt_context() -> Option<String> {
+    current_memory().map(|memory| render(&memory))
+}
+
+// ========== Tauri Commands ==========
+
+// Relationship memory of a session (the current one when no id is given); None when the
+// session isn't linked to a prospect or there were no earlier calls
+#[tauri::command]
+pub fn get_prospect_memory(session_id: Option<String>) -> Result<Option<ProspectMemory>, String> {
+    let current_id = crate::session_store::current_session_id();
+    if session_id.is_none() || session_id == current_id {
+        return Ok(current_memory());
+    }
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    Ok(build(&session, &crate::session_store::all_sessions()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, started_at: u64, company: &str, lines: &[(bool, &str)]) -> Session {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "started_at": started_at,
+            "company": company,
+            "outcome": "follow_up",
+            "transcript": lines.iter().enumerate()
+                .map(|(i, (is_user, text))| serde_json::json!({ "offset_ms": i as u64 * 1000, "is_user": is_user, "text": text }))
+                .collect::<Vec<_>>(),
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_memory_collects_promises_and_preferences_from_the_same_prospect() {
+        let sessions = vec![
+            session("a", 1_000, "Acme Corp", &[(false, "We need SSO before anything else"), (true, "I'll send the security whitepaper tomorrow")]),
+            session("b", 2_000, "Globex", &[(true, "I'll send the contract")]),
+            session("c", 3_000, "ACME corp.", &[(true, "I will send the security whitepaper tomorrow."), (false, "Sounds good")]),
+        ];
+        let current = session("d", 4_000, "acme corp", &[]);
+        let memory = build(&current, &sessions).unwrap();
+        assert_eq!(memory.identifiers, vec!["company:acmecorp".to_string()]);
+        assert_eq!(memory.calls.iter().map(|c| c.session_id.as_str()).collect::<Vec<_>>(), vec!["c", "a"]);
+        assert_eq!(memory.promises.len(), 2);
+        assert_eq!(memory.promises[0].session_id, "c");
+        assert_eq!(memory.preferences[0].text, "We need SSO before anything else");
+
+        let context = render(&memory);
+        assert!(context.starts_with("Earlier calls with acme corp:\n"));
+        assert!(context.contains("Preferences they stated:\n- \"We need SSO before anything else\""));
+
+        assert!(build(&session("e", 5_000, "Initech", &[]), &sessions).is_none());
+    }
+}
@@ -0,0 +1,12 @@
+// Prospect Questions Commands - the call's question log for the UI
+// Detection and answer matching are in voicecoach-core (re-exported here under the old
+// path).
+
+pub use voicecoach_core::prospect_questions::*;
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_prospect_questions() -> Result<Vec<ProspectQuestion>, String> {
+    Ok(questions())
+}
@@ -0,0 +1,313 @@
+// Punctuation - restores capitalization and punctuation in Vosk final segments
+// Vosk emits lowercase, unpunctuated text. Final segments are restored before they
+// are filtered and emitted, so the UI, OBS captions, live documents and analytics
+// all see readable sentences. The built-in rules (sentence case, "I", weekdays and
+// months, commas after leading interjections and before "but", question marks for
+// interrogative openings) cost microseconds. Builds with the `onnx-punctuation`
+// feature can instead run a token-classification ONNX model, which is noticeably
+// better on long segments but adds inference latency - hence the toggles.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::info;
+#[cfg(feature = "onnx-punctuation")]
+use log::warn;
+
+// A segment opening with one of these (after any interjections) is a question
+const QUESTION_STARTERS: &[&str] = &[
+    "what", "why", "how", "when", "where", "who", "whom", "whose", "which",
+    "can", "could", "would", "will", "do", "does", "did", "is", "are", "was", "were",
+    "should", "shall", "have", "has", "am", "isn't", "aren't", "don't", "doesn't",
+    "didn't", "won't", "wouldn't", "can't", "couldn't", "shouldn't",
+];
+const INTERJECTIONS: &[&str] = &[
+    "okay", "ok", "so", "well", "yeah", "yes", "no", "right", "actually", "anyway",
+    "alright", "sure", "hmm", "um", "uh", "oh",
+];
+// A leading interjection only takes a comma before these ("No, I don't" but not "No problem")
+const CLAUSE_OPENERS: &[&str] = &[
+    "i", "i'm", "i'll", "i've", "i'd", "we", "you", "they", "he", "she", "it", "that",
+    "this", "there", "let's", "let",
+];
+const ALWAYS_CAPITALIZED: &[&str] = &[
+    "i", "i'm", "i'll", "i've", "i'd",
+    "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday",
+    "january", "february", "april", "june", "july", "august", "september", "october",
+    "november", "december",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PunctuationSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Use the ONNX model instead of the rules (needs the `onnx-punctuation` build)
+    #[serde(default)]
+    pub use_model: bool,
+    /// Directory holding model.onnx, vocab.txt (WordPiece) and labels.json
+    #[serde(default)]
+    pub model_dir: Option<String>,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for PunctuationSettings {
+    fn default() -> Self {
+        Self { enabled: true, use_model: false, model_dir: None }
+    }
+}
+
+// Cached copy of the preferences entry (segments are restored on audio threads)
+static SETTINGS: Lazy<Mutex<Option<PunctuationSettings>>> = Lazy::new(|| Mutex::new(None));
+
+fn current_settings() -> PunctuationSettings {
+    SETTINGS.lock().unwrap()
+        .get_or_insert_with(|| crate::preferences::load().punctuation)
+        .clone()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn ends_with_punctuation(word: &str) -> bool {
+    word.chars().last().map_or(false, |c| !c.is_alphanumeric() && c != '\'')
+}
+
+/// Rule-based restoration of one final segment
+pub fn punctuate_with_rules(text: &str) -> String {
+    let lower: Vec<String> = text.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if lower.is_empty() {
+        return String::new();
+    }
+    let mut words: Vec<String> = text.split_whitespace()
+        .zip(&lower)
+        .map(|(word, lower)| if ALWAYS_CAPITALIZED.contains(&lower.as_str()) { capitalize(word) } else { word.to_string() })
+        .collect();
+
+    // "okay so what ..." -> "Okay, so what ..." (comma after the first interjection only)
+    let leading = lower.iter().take_while(|w| INTERJECTIONS.contains(&w.as_str())).count();
+    if leading > 0 && leading < lower.len() {
+        let next = lower[leading].as_str();
+        if CLAUSE_OPENERS.contains(&next) || QUESTION_STARTERS.contains(&next) {
+            words[0].push(',');
+        }
+    }
+    for i in 1..words.len() {
+        if lower[i] == "but" && !ends_with_punctuation(&words[i - 1]) {
+            words[i - 1].push(',');
+        }
+    }
+
+    let question = lower.get(leading).map_or(false, |w| QUESTION_STARTERS.contains(&w.as_str()));
+    words[0] = capitalize(&words[0]);
+    let mut restored = words.join(" ");
+    if !ends_with_punctuation(&restored) {
+        restored.push(if question { '?' } else { '.' });
+    }
+    restored
+}
+
+#[cfg(feature = "onnx-punctuation")]
+mod model {
+    use anyhow::{Result, anyhow};
+    use ort::session::Session;
+    use ort::value::Tensor;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    // Words per inference call (keeps WordPiece sequences well under 512 tokens)
+    const CHUNK_WORDS: usize = 120;
+
+    /// One output class: punctuation to append and whether the word is capitalized
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct Label {
+        #[serde(default)]
+        pub punct: String,
+        #[serde(default)]
+        pub upper: bool,
+    }
+
+    pub struct PunctuationModel {
+        session: Session,
+        vocab: HashMap<String, i64>,
+        labels: Vec<Label>,
+        cls: i64,
+        sep: i64,
+        unk: i64,
+    }
+
+    impl PunctuationModel {
+        pub fn load(dir: &Path) -> Result<Self> {
+            let session = Session::builder()?.commit_from_file(dir.join("model.onnx"))?;
+            let vocab: HashMap<String, i64> = std::fs::read_to_string(dir.join("vocab.txt"))?
+                .lines()
+                .enumerate()
+                .map(|(id, token)| (token.to_string(), id as i64))
+                .collect();
+            let labels: Vec<Label> = serde_json::from_str(&std::fs::read_to_string(dir.join("labels.json"))?)?;
+            let special = |token: &str| vocab.get(token).copied()
+                .ok_or_else(|| anyhow!("vocab.txt has no {} token", token));
+            let (cls, sep, unk) = (special("[CLS]")?, special("[SEP]")?, special("[UNK]")?);
+            Ok(Self { session, vocab, labels, cls, sep, unk })
+        }
+
+        /// Greedy longest-match WordPiece
+        fn wordpiece(&self, word: &str) -> Vec<i64> {
+            let chars: Vec<char> = word.chars().collect();
+            let mut pieces = Vec::new();
+            let mut start = 0;
+            while start < chars.len() {
+                let mut end = chars.len();
+                let mut found = None;
+                while end > start {
+                    let piece: String = chars[start..end].iter().collect();
+                    let piece = if start > 0 { format!("##{}", piece) } else { piece };
+                    if let Some(&id) = self.vocab.get(&piece) {
+                        found = Some(id);
+                        break;
+                    }
+                    end -= 1;
+                }
+                match found {
+                    Some(id) => pieces.push(id),
+                    None => return vec![self.unk],
+                }
+                start = end;
+            }
+            pieces
+        }
+
+        fn classify(&self, words: &[&str]) -> Result<Vec<Label>> {
+            let mut ids = vec![self.cls];
+            let mut first_piece = Vec::with_capacity(words.len());
+            for word in words {
+                first_piece.push(ids.len());
+                ids.extend(self.wordpiece(&word.to_lowercase()));
+            }
+            ids.push(self.sep);
+            let len = ids.len();
+
+            let input_ids = Tensor::from_array(([1usize, len], ids))?;
+            let attention_mask = Tensor::from_array(([1usize, len], vec![1i64; len]))?;
+            let outputs = self.session.run(ort::inputs![
+                "input_ids" => input_ids,
+                "attention_mask" => attention_mask,
+            ]?)?;
+            let (shape, logits) = outputs[0].try_extract_raw_tensor::<f32>()?;
+            let classes = *shape.last().ok_or_else(|| anyhow!("Model output has no class dimension"))? as usize;
+
+            first_piece.iter().map(|&index| {
+                let row = &logits[index * classes..(index + 1) * classes];
+                let best = row.iter().enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map(|(class, _)| class)
+                    .unwrap_or(0);
+                self.labels.get(best).cloned().ok_or_else(|| anyhow!("labels.json has no class {}", best))
+            }).collect()
+        }
+
+        pub fn punctuate(&self, text: &str) -> Result<String> {
+            let words: Vec<&str> = text.split_whitespace().collect();
+            let mut restored = Vec::with_capacity(words.len());
+            for chunk in words.chunks(CHUNK_WORDS) {
+                for (word, label) in chunk.iter().zip(self.classify(chunk)?) {
+                    let word = if label.upper { super::capitalize(word) } else { word.to_string() };
+                    restored.push(word + &label.punct);
+                }
+            }
+            Ok(restored.join(" "))
+        }
+    }
+}
+
+#[cfg(feature = "onnx-punctuation")]
+static MODEL: Lazy<Mutex<Option<(String, std::sync::Arc<model::PunctuationModel>)>>> = Lazy::new(|| Mutex::new(None));
+
+#[cfg(feature = "onnx-punctuation")]
+fn loaded_model(dir: &str) -> anyhow::Result<std::sync::Arc<model::PunctuationModel>> {
+    let mut cached = MODEL.lock().unwrap();
+    if let Some((loaded_dir, model)) = cached.as_ref() {
+        if loaded_dir == dir {
+            return Ok(model.clone());
+        }
+    }
+    let model = std::sync::Arc::new(model::PunctuationModel::load(std::path::Path::new(dir))?);
+    info!("✏️ Punctuation model loaded from {}", dir);
+    *cached = Some((dir.to_string(), model.clone()));
+    Ok(model)
+}
+
+#[cfg(feature = "onnx-punctuation")]
+fn punctuate_with_model(text: &str, settings: &PunctuationSettings) -> Option<String> {
+    let dir = settings.model_dir.as_deref()?;
+    match loaded_model(dir).and_then(|model| model.punctuate(text)) {
+        Ok(restored) => Some(restored),
+        Err(e) => {
+            warn!("⚠️ Punctuation model failed, using rules: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "onnx-punctuation"))]
+fn punctuate_with_model(_text: &str, _settings: &PunctuationSettings) -> Option<String> {
+    None
+}
+
+/// Restore a final transcript segment with the stored settings (unchanged when disabled)
+pub fn restore_transcript(text: &str) -> String {
+    let settings = current_settings();
+    if !settings.enabled {
+        return text.to_string();
+    }
+    let restored = if settings.use_model { punctuate_with_model(text, &settings) } else { None };
+    restored.unwrap_or_else(|| punctuate_with_rules(text))
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_punctuation_settings() -> Result<PunctuationSettings, String> {
+    Ok(current_settings())
+}
+
+#[tauri::command]
+pub fn set_punctuation_settings(settings: PunctuationSettings) -> Result<PunctuationSettings, String> {
+    if settings.use_model {
+        if cfg!(not(feature = "onnx-punctuation")) {
+            return Err("This build has no ONNX punctuation support".to_string());
+        }
+        match settings.model_dir.as_deref() {
+            Some(dir) if std::path::Path::new(dir).join("model.onnx").exists() => {}
+            _ => return Err("model_dir must contain model.onnx, vocab.txt and labels.json".to_string()),
+        }
+    }
+    crate::preferences::update(|p| p.punctuation = settings.clone())
+        .map_err(|e| e.to_string())?;
+    *SETTINGS.lock().unwrap() = Some(settings.clone());
+    info!("✏️ Punctuation restoration {} ({})",
+          if settings.enabled { "enabled" } else { "disabled" },
+          if settings.use_model { "model" } else { "rules" });
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_restore_case_commas_and_questions() {
+        assert_eq!(punctuate_with_rules("okay so what do you think about the pricing"),
+                   "Okay, so what do you think about the pricing?");
+        assert_eq!(punctuate_with_rules("no i think we can start on monday but i'll check"),
+                   "No, I think we can start on Monday, but I'll check.");
+        assert_eq!(punctuate_with_rules("no problem"), "No problem.");
+        assert_eq!(punctuate_with_rules("   "), "");
+    }
+}
@@ -0,0 +1,154 @@
+// Read-Aloud Detection - recognizes when the rep is reading a script verbatim
+// Legal disclosures and demo scripts are stored in the knowledge base as "script"
+// documents. Each final rep segment is compared to them by word-trigram overlap;
+// while the rep is reading, coaching prompts are suppressed and the segments are
+// left out of the talk-ratio counts. "read_aloud_status" events mark the start and
+// end of each scripted section.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+// Segments shorter than this don't change the current state
+const MIN_WORDS: usize = 6;
+// Share of a segment's trigrams found in a script for it to count as read aloud
+const MATCH_THRESHOLD: f32 = 0.6;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ScriptedPeriod {
+    pub document: String,
+    pub started_at: u64,
+    pub ended_at: Option<u64>,  // None while still reading
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ReadAloudStatus {
+    pub active: bool,
+    pub document: Option<String>,
+    pub periods: Vec<ScriptedPeriod>,
+}
+
+static PERIODS: Lazy<Mutex<Vec<ScriptedPeriod>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| w.chars().filter(|c| c.is_alphanumeric() || *c == '\'').collect::<String>().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn trigrams(words: &[String]) -> HashSet<String> {
+    words.windows(3).map(|w| w.join(" ")).collect()
+}
+
+/// Best-matching script for a segment: (document, share of the segment's trigrams it contains)
+fn best_match(segment: &[String], scripts: &[(String, String)]) -> Option<(String, f32)> {
+    let grams = trigrams(segment);
+    if grams.is_empty() {
+        return None;
+    }
+    scripts.iter()
+        .map(|(name, content)| {
+            let script = trigrams(&words(content));
+            let shared = grams.iter().filter(|g| script.contains(*g)).count();
+            (name.clone(), shared as f32 / grams.len() as f32)
+        })
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+fn status() -> ReadAloudStatus {
+    let periods = PERIODS.lock().unwrap().clone();
+    let current = periods.last().filter(|p| p.ended_at.is_none());
+    ReadAloudStatus {
+        active: current.is_some(),
+        document: current.map(|p| p.document.clone()),
+        periods,
+    }
+}
+
+/// True while the rep is reading a script (coaching is suppressed)
+pub fn is_active() -> bool {
+    PERIODS.lock().unwrap().last().map_or(false, |p| p.ended_at.is_none())
+}
+
+/// Start of a new call
+pub fn begin_call() {
+    PERIODS.lock().unwrap().clear();
+}
+
+/// Classify a final segment; true if it belongs to a scripted section
+pub fn observe(app: &AppHandle, text: &str, is_user: bool) -> bool {
+    if !is_user {
+        return false;
+    }
+    let segment = words(text);
+    if segment.len() < MIN_WORDS {
+        return is_active();
+    }
+    let matched = best_match(&segment, &crate::knowledge_base::script_documents())
+        .filter(|(_, similarity)| *similarity >= MATCH_THRESHOLD);
+
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let changed = {
+        let mut periods = PERIODS.lock().unwrap();
+        let reading = periods.last().filter(|p| p.ended_at.is_none()).map(|p| p.document.clone());
+        match (reading, matched) {
+            (None, Some((document, similarity))) => {
+                info!("📜 Rep is reading '{}' ({:.0}% match), coaching paused", document, similarity * 100.0);
+                periods.push(ScriptedPeriod { document, started_at: now, ended_at: None });
+                true
+            }
+            (Some(current), Some((document, _))) if current != document => {
+                if let Some(last) = periods.last_mut() {
+                    last.ended_at = Some(now);
+                }
+                periods.push(ScriptedPeriod { document, started_at: now, ended_at: None });
+                true
+            }
+            (Some(_), None) => {
+                info!("📜 Rep stopped reading, coaching resumed");
+                if let Some(last) = periods.last_mut() {
+                    last.ended_at = Some(now);
+                }
+                true
+            }
+            _ => false,
+        }
+    };
+    if changed {
+        if let Err(e) = app.emit_all("read_aloud_status", status()) {
+            error!("Failed to emit read_aloud_status: {:?}", e);
+        }
+    }
+    is_active()
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_read_aloud_status() -> Result<ReadAloudStatus, String> {
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbatim_reading_matches_script_but_paraphrase_does_not() {
+        let scripts = vec![(
+            "recording_disclosure".to_string(),
+            "This call may be recorded for quality and training purposes. By continuing you consent to the recording.".to_string(),
+        )];
+        let read = words("this call may be recorded for quality and training purposes by continuing");
+        let (document, similarity) = best_match(&read, &scripts).unwrap();
+        assert_eq!(document, "recording_disclosure");
+        assert!(similarity >= MATCH_THRESHOLD);
+
+        let paraphrase = words("just so you know we record these calls to train the team");
+        assert!(best_match(&paraphrase, &scripts).unwrap().1 < MATCH_THRESHOLD);
+    }
+}
@@ -0,0 +1,243 @@
+// Recognition Grammar - Vosk restricted to the phrases a moment expects
+// Open dictation gets short, out-of-context utterances wrong ("bookmark that" comes back
+// as "book market"); limited to a handful of phrases (plus [unk] for anything else), Vosk
+// only has to pick one and is right nearly every time. set_recognition_grammar switches
+// the transcription recognizer to a grammar for a purpose: after the wake word on its own,
+// the rep's next utterance is recognized against the command phrases (voice_commands);
+// a practice drill asks for the lines the rep should say and scores what comes back. The
+// grammar lasts a number of utterances (one by default) or until it times out, then the
+// recognizer switches back to open dictation - or the sales stage's biased grammar - by
+// itself. What is recognized under a grammar is not transcript: it is emitted as
+// "grammar_result" and handed to the purpose's handler. Every switch is reported as
+// "recognition_grammar_changed" (null payload when dictation is back). Only Vosk has a
+// local recognizer to constrain; Deepgram streams ignore the mode.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+const DEFAULT_UTTERANCES: u32 = 1;
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const MAX_TIMEOUT_SECS: u64 = 300;
+// Larger grammars lose the accuracy the mode is for
+const MAX_PHRASES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum GrammarPurpose {
+    VoiceCommand,   // The utterance after the wake word
+    Practice,       // Practice drill: the lines the rep should say
+    Custom,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct RecognitionGrammar {
+    pub phrases: Vec<String>,
+    pub purpose: GrammarPurpose,
+    pub utterances_left: u32,
+    pub expires_at: u64,
+}
+
+// Payload of "grammar_result"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct GrammarResult {
+    pub purpose: GrammarPurpose,
+    pub text: String,                // As recognized, [unk] dropped
+    pub matched: Option<String>,     // The phrase it was, if any
+    pub confidence: Option<f32>,     // Mean word confidence (Vosk word timing on)
+    pub timestamp: u64,
+}
+
+static ACTIVE: Lazy<Mutex<Option<RecognitionGrammar>>> = Lazy::new(|| Mutex::new(None));
+// Bumped on every switch; the audio callback rebuilds its recognizer when it changes
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Lowercase words without punctuation, [unk] dropped - how Vosk writes grammar phrases
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '\'' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .filter(|w| *w != "unk")
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn grammar(phrases: &[String], purpose: GrammarPurpose, utterances: u32, timeout_secs: u64, now: u64) -> Result<RecognitionGrammar, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for phrase in phrases.iter().map(|p| normalize(p)).filter(|p| !p.is_empty()) {
+        if !normalized.contains(&phrase) {
+            normalized.push(phrase);
+        }
+    }
+    if normalized.is_empty() {
+        return Err("A recognition grammar needs at least one phrase".to_string());
+    }
+    if normalized.len() > MAX_PHRASES {
+        return Err(format!("A recognition grammar is limited to {} phrases", MAX_PHRASES));
+    }
+    Ok(RecognitionGrammar {
+        phrases: normalized,
+        purpose,
+        utterances_left: utterances.max(1),
+        expires_at: now + timeout_secs.clamp(1, MAX_TIMEOUT_SECS) * 1000,
+    })
+}
+
+/// The phrase a recognized text is, if any
+fn matched_phrase(grammar: &RecognitionGrammar, text: &str) -> Option<String> {
+    let text = normalize(text);
+    grammar.phrases.iter().find(|p| **p == text).cloned()
+}
+
+/// Count one utterance; false once the grammar is used up
+fn consume(grammar: &mut RecognitionGrammar) -> bool {
+    grammar.utterances_left = grammar.utterances_left.saturating_sub(1);
+    grammar.utterances_left > 0
+}
+
+fn switched(app: &AppHandle, grammar: Option<RecognitionGrammar>) {
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+    match &grammar {
+        Some(g) => info!("🎯 Recognition grammar on: {:?}, {} phrases", g.purpose, g.phrases.len()),
+        None => info!("🎯 Recognition grammar off, back to open dictation"),
+    }
+    if let Err(e) = app.emit_all("recognition_grammar_changed", grammar) {
+        error!("Failed to emit recognition_grammar_changed: {:?}", e);
+    }
+}
+
+/// Restrict recognition to `phrases` for the next `utterances` (or `timeout_secs`)
+pub fn activate(app: &AppHandle, phrases: &[String], purpose: GrammarPurpose, utterances: u32, timeout_secs: u64) -> Result<RecognitionGrammar, String> {
+    let grammar = grammar(phrases, purpose, utterances, timeout_secs, now_ms())?;
+    *ACTIVE.lock().unwrap() = Some(grammar.clone());
+    switched(app, Some(grammar.clone()));
+    Ok(grammar)
+}
+
+/// Back to open dictation
+pub fn deactivate(app: &AppHandle) {
+    if ACTIVE.lock().unwrap().take().is_some() {
+        switched(app, None);
+    }
+}
+
+/// Current switch generation (an expired grammar is dropped first)
+pub fn generation(app: &AppHandle) -> u64 {
+    let expired = ACTIVE.lock().unwrap().as_ref().map_or(false, |g| now_ms() >= g.expires_at);
+    if expired {
+        deactivate(app);
+    }
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// Phrases for a Vosk recognizer (with [unk] for anything else), when a grammar is on
+pub fn vosk_grammar() -> Option<Vec<String>> {
+    ACTIVE.lock().unwrap().as_ref().map(|g| {
+        let mut phrases = g.phrases.clone();
+        phrases.push("[unk]".to_string());
+        phrases
+    })
+}
+
+/// An utterance recognized under the grammar: report it, hand it to the purpose,
+/// and switch back to dictation once the grammar is used up
+pub fn finished(app: &AppHandle, text: &str, confidence: Option<f32>) {
+    let (result, used_up) = {
+        let mut active = ACTIVE.lock().unwrap();
+        let grammar = match active.as_mut() {
+            Some(grammar) => grammar,
+            // Expired while the rep was speaking
+            None => return,
+        };
+        let result = GrammarResult {
+            purpose: grammar.purpose,
+            text: normalize(text),
+            matched: matched_phrase(grammar, text),
+            confidence,
+            timestamp: now_ms(),
+        };
+        let used_up = !consume(grammar);
+        if used_up {
+            *active = None;
+        }
+        (result, used_up)
+    };
+    if used_up {
+        switched(app, None);
+    }
+
+    info!("🎯 Grammar result ({:?}): '{}' matched {:?}", result.purpose, result.text, result.matched);
+    if result.purpose == GrammarPurpose::VoiceCommand {
+        if let Some(phrase) = &result.matched {
+            crate::voice_commands::handle_after_wake(app, phrase);
+        }
+    }
+    if let Err(e) = app.emit_all("grammar_result", result) {
+        error!("Failed to emit grammar_result: {:?}", e);
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Recognize only these phrases for the next utterances (default 1) or until the
+// timeout (default 10 s); then open dictation resumes by itself
+#[tauri::command]
+pub fn set_recognition_grammar(
+    app: AppHandle,
+    phrases: Vec<String>,
+    purpose: Option<GrammarPurpose>,
+    utterances: Option<u32>,
+    timeout_secs: Option<u64>,
+) -> Result<RecognitionGrammar, String> {
+    activate(
+        &app,
+        &phrases,
+        purpose.unwrap_or(GrammarPurpose::Custom),
+        utterances.unwrap_or(DEFAULT_UTTERANCES),
+        timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS),
+    )
+}
+
+#[tauri::command]
+pub fn clear_recognition_grammar(app: AppHandle) -> Result<(), String> {
+    deactivate(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_recognition_grammar() -> Result<Option<RecognitionGrammar>, String> {
+    Ok(ACTIVE.lock().unwrap().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grammar_is_normalized_and_used_up() {
+        let phrases = vec!["Bookmark that.".to_string(), "bookmark that".to_string(), "  ".to_string(), "What's your timeline?".to_string()];
+        let mut g = grammar(&phrases, GrammarPurpose::Practice, 2, 10, 1_000).unwrap();
+        assert_eq!(g.phrases, vec!["bookmark that", "what's your timeline"]);
+        assert_eq!(g.expires_at, 11_000);
+
+        assert_eq!(matched_phrase(&g, "[unk] What's your timeline"), Some("what's your timeline".to_string()));
+        assert_eq!(matched_phrase(&g, "[unk]"), None);
+
+        assert!(consume(&mut g));
+        assert!(!consume(&mut g));
+
+        assert!(grammar(&["?!".to_string()], GrammarPurpose::Custom, 1, 10, 0).is_err());
+        // Zero utterances still listens once; the timeout is bounded
+        let g = grammar(&phrases, GrammarPurpose::Custom, 0, 0, 0).unwrap();
+        assert_eq!((g.utterances_left, g.expires_at), (1, 1_000));
+    }
+}
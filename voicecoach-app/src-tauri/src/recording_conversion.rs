@@ -0,0 +1,35 @@
+// Recording Conversion Command - sharing copies of a session's recording
+// The encoding is in voicecoach-core (re-exported here); the command finds the
+// session's recording and reports progress to the windows.
+
+pub use voicecoach_core::recording_conversion::*;
+
+use std::path::Path;
+use tauri::AppHandle;
+use log::error;
+
+use crate::event_sink::AppEvents;
+
+// ========== Tauri Commands ==========
+
+// Write an MP3/Opus sharing copy of a session's recording beside the original
+// (the current session when no id is given; medium quality by default)
+#[tauri::command]
+pub async fn convert_recording(
+    app: AppHandle,
+    session_id: Option<String>,
+    format: SharingFormat,
+    quality: Option<SharingQuality>,
+) -> Result<SharingCopy, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    let recording = session.recording.ok_or("This session has no recording")?;
+    let quality = quality.unwrap_or(SharingQuality::Medium);
+    let session_id = session.id;
+    tokio::task::spawn_blocking(move || convert(&AppEvents(app), &session_id, Path::new(&recording), format, quality))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| {
+            error!("❌ Recording conversion failed: {}", e);
+            e.to_string()
+        })
+}
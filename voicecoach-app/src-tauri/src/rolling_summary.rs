@@ -0,0 +1,355 @@
+// Rolling Summary - an incremental summary of long calls, kept up to date as they go
+// Summarizing an hour of transcript at the end of the call takes the local LLM
+// minutes, so once a call passes 30 minutes its transcript is summarized in 5-minute
+// windows in the background (map) and each window summary is folded into the running
+// summary of the call (reduce). Windows hold the sentences (sentences.rs) that start in
+// them, so a long turn is cut between sentences rather than wherever the engine ended
+// a line. Every window is read once; the final summary and a mid-call "catch me up"
+// then only have to fold in the sentences since the last window, which takes seconds.
+// When Ollama is unavailable the windows are summarized by their most informative
+// sentences instead. Updates are emitted as "rolling_summary";
+// summarize_call stores the final summary with the session and indexes it for
+// find_similar_sessions.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+use crate::ollama_integration::OllamaCoachingService;
+use crate::sentences::Sentence;
+use crate::session_store::TranscriptLine;
+
+// Calls shorter than this are summarized in one pass at the end
+const ROLLING_AFTER_MS: u64 = 30 * 60_000;
+const WINDOW_MS: u64 = 5 * 60_000;
+const WINDOW_WORDS: usize = 60;
+const SUMMARY_WORDS: usize = 150;
+// Without the LLM: sentences kept per window, and the length the summary is held to
+const EXCERPT_SENTENCES: usize = 3;
+const MAX_EXCERPT_SUMMARY_CHARS: usize = 2_000;
+const LLM_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct WindowSummary {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RollingSummary {
+    pub session_id: String,
+    pub summary: String,
+    pub covered_until_ms: u64,       // Transcript time the summary includes
+    pub windows: Vec<WindowSummary>,
+    pub summarized_by: String,       // "ollama" or "excerpts" (LLM unavailable)
+    pub updated_at: u64,
+}
+
+#[derive(Default, Clone)]
+struct Rolling {
+    session_id: Option<String>,
+    summary: String,
+    covered_until_ms: u64,
+    windows: Vec<WindowSummary>,
+    excerpts: bool,                  // Some window was summarized without the LLM
+}
+
+impl Rolling {
+    fn snapshot(&self, session_id: &str) -> RollingSummary {
+        RollingSummary {
+            session_id: session_id.to_string(),
+            summary: self.summary.clone(),
+            covered_until_ms: self.covered_until_ms,
+            windows: self.windows.clone(),
+            summarized_by: if self.excerpts { "excerpts" } else { "ollama" }.to_string(),
+            updated_at: chrono::Utc::now().timestamp_millis() as u64,
+        }
+    }
+}
+
+static ROLLING: Lazy<Mutex<Rolling>> = Lazy::new(|| Mutex::new(Rolling::default()));
+// Bumped per call so a background pass of the previous call doesn't write into this one
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static ROLLING_UP: AtomicBool = AtomicBool::new(false);
+
+/// Windows [start, end) of `window_ms` from `from_ms` that the transcript has passed;
+/// with `partial`, also the unfinished last one
+fn windows(sentences: &[Sentence], from_ms: u64, window_ms: u64, partial: bool) -> Vec<(u64, u64)> {
+    let last = match sentences.last() {
+        Some(sentence) => sentence.start_ms,
+        None => return Vec::new(),
+    };
+    let mut bounds = Vec::new();
+    let mut start = from_ms;
+    while last >= start + window_ms {
+        bounds.push((start, start + window_ms));
+        start += window_ms;
+    }
+    if partial && last >= start {
+        bounds.push((start, last + 1));
+    }
+    bounds
+}
+
+fn sentences_in(sentences: &[Sentence], start_ms: u64, end_ms: u64) -> Vec<Sentence> {
+    sentences.iter().filter(|s| s.start_ms >= start_ms && s.start_ms < end_ms).cloned().collect()
+}
+
+fn speaker(sentence: &Sentence) -> &'static str {
+    if sentence.is_user { "Rep" } else { "Prospect" }
+}
+
+fn minute(ms: u64) -> u64 {
+    ms / 60_000
+}
+
+/// The sentences with the most content words, in call order
+fn excerpt(sentences: &[Sentence]) -> String {
+    let mut ranked: Vec<(usize, usize)> = sentences.iter().enumerate()
+        .map(|(i, s)| (i, crate::topic_segmentation::keywords(&s.text).len()))
+        .collect();
+    ranked.sort_by_key(|r| std::cmp::Reverse(r.1));
+    let mut chosen: Vec<usize> = ranked.into_iter().take(EXCERPT_SENTENCES).map(|(i, _)| i).collect();
+    chosen.sort_unstable();
+    chosen.iter().map(|&i| format!("{}: {}", speaker(&sentences[i]), sentences[i].text)).collect::<Vec<_>>().join(" / ")
+}
+
+/// Keep the end of an excerpt summary within MAX_EXCERPT_SUMMARY_CHARS, at a line break
+fn keep_tail(text: String) -> String {
+    if text.chars().count() <= MAX_EXCERPT_SUMMARY_CHARS {
+        return text;
+    }
+    let skip = text.chars().count() - MAX_EXCERPT_SUMMARY_CHARS;
+    let tail: String = text.chars().skip(skip).collect();
+    match tail.find('\n') {
+        Some(at) => tail[at + 1..].to_string(),
+        None => tail,
+    }
+}
+
+/// Summary of one stretch of the call; None when the LLM failed (the caller falls back)
+async fn map(service: &OllamaCoachingService, sentences: &[Sentence], start_ms: u64, end_ms: u64) -> Option<String> {
+    let mut prompt = format!("Below is minutes {}-{} of a sales call.\n\nTRANSCRIPT:\n", minute(start_ms), minute(end_ms).max(minute(start_ms) + 1));
+    for sentence in sentences {
+        prompt.push_str(&format!("{}: {}\n", speaker(sentence), sentence.text));
+    }
+    prompt.push_str(&format!("\nSummarize this part in at most {} words: what was discussed, objections raised, \
+        and anything either side agreed or committed to. No preamble.\n", WINDOW_WORDS));
+    match service.complete(prompt, 0.2, 200, LLM_TIMEOUT_SECS).await {
+        Ok(summary) => Some(summary.trim().to_string()),
+        Err(e) => {
+            warn!("⚠️ Window summarization failed: {}", e);
+            crate::telemetry::record_error("ollama");
+            None
+        }
+    }
+}
+
+/// Fold the summary of the next stretch into the summary so far
+async fn reduce(service: &OllamaCoachingService, summary: &str, addition: &str) -> Option<String> {
+    if summary.is_empty() {
+        return Some(addition.to_string());
+    }
+    let prompt = format!("SUMMARY OF THE SALES CALL SO FAR:\n{}\n\nWHAT HAPPENED NEXT:\n{}\n\n\
+        Write the updated summary of the whole call in at most {} words. Keep open objections, \
+        commitments and next steps; drop what was resolved. No preamble.\n", summary, addition, SUMMARY_WORDS);
+    match service.complete(prompt, 0.2, 300, LLM_TIMEOUT_SECS).await {
+        Ok(summary) => Some(summary.trim().to_string()),
+        Err(e) => {
+            warn!("⚠️ Summary update failed: {}", e);
+            crate::telemetry::record_error("ollama");
+            None
+        }
+    }
+}
+
+/// Summarize the sentences of [start_ms, end_ms) and fold them into `rolling`
+async fn fold(service: Option<&OllamaCoachingService>, rolling: &mut Rolling, sentences: &[Sentence], start_ms: u64, end_ms: u64) {
+    rolling.covered_until_ms = end_ms;
+    if sentences.is_empty() {
+        return;
+    }
+    let window = match service {
+        Some(service) => map(service, sentences, start_ms, end_ms).await,
+        None => None,
+    };
+    let (window, by_llm) = match window {
+        Some(window) => (window, true),
+        None => (excerpt(sentences), false),
+    };
+    let folded = match (by_llm, service) {
+        (true, Some(service)) => reduce(service, &rolling.summary, &window).await,
+        _ => None,
+    };
+    rolling.summary = match folded {
+        Some(summary) => summary,
+        None => {
+            rolling.excerpts = true;
+            let line = format!("[{}-{} min] {}", minute(start_ms), minute(end_ms), window);
+            keep_tail(if rolling.summary.is_empty() { line } else { format!("{}\n{}", rolling.summary, line) })
+        }
+    };
+    rolling.windows.push(WindowSummary { start_ms, end_ms, summary: window });
+}
+
+async fn available_service() -> Option<OllamaCoachingService> {
+    let service = OllamaCoachingService::new();
+    service.check_availability().await.unwrap_or(false).then(|| service)
+}
+
+/// Summarize the finished windows of the call in progress, one at a time
+async fn roll(app: AppHandle, generation: u64) {
+    let service = available_service().await;
+    loop {
+        let covered = ROLLING.lock().unwrap().covered_until_ms;
+        let next = crate::session_store::current_session_id().and_then(|session_id| {
+            crate::sentences::with_current(|sentences| {
+                windows(sentences, covered, WINDOW_MS, false).first()
+                    .map(|&(start, end)| (session_id, start, end, sentences_in(sentences, start, end)))
+            })
+        });
+        let (session_id, start, end, sentences) = match next {
+            Some(next) => next,
+            None => return,
+        };
+
+        // Work on a copy so commands can read the summary meanwhile
+        let mut rolling = ROLLING.lock().unwrap().clone();
+        rolling.session_id = Some(session_id.clone());
+        fold(service.as_ref(), &mut rolling, &sentences, start, end).await;
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            return;  // A new call started meanwhile
+        }
+        info!("🧾 Rolling summary covers {} min of session {}", minute(end), session_id);
+        let snapshot = rolling.snapshot(&session_id);
+        *ROLLING.lock().unwrap() = rolling;
+        if let Err(e) = app.emit_all("rolling_summary", snapshot) {
+            error!("Failed to emit rolling_summary: {:?}", e);
+        }
+    }
+}
+
+/// Start of a new call
+pub fn begin_call() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    *ROLLING.lock().unwrap() = Rolling::default();
+}
+
+/// A final line was added to the session in progress: summarize the next window in the
+/// background once the call is long enough and a window is complete
+pub fn observe(app: &AppHandle) {
+    let last = crate::sentences::with_current(|sentences| sentences.last().map(|s| s.start_ms));
+    let due = last.map_or(false, |last| {
+        last >= ROLLING_AFTER_MS && last >= ROLLING.lock().unwrap().covered_until_ms + WINDOW_MS
+    });
+    if !due || ROLLING_UP.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    let generation = GENERATION.load(Ordering::SeqCst);
+    tauri::async_runtime::spawn(async move {
+        roll(app, generation).await;
+        ROLLING_UP.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Summary of the call in progress up to `until_ms` (its last sentence by default): the
+/// rolling summary with the sentences since its last window folded in
+pub async fn summary_so_far(until_ms: Option<u64>) -> Result<RollingSummary, String> {
+    let session_id = crate::session_store::current_session_id().ok_or("No session in progress")?;
+    let sentences = crate::sentences::with_current(|sentences| sentences.to_vec());
+    let mut rolling = ROLLING.lock().unwrap().clone();
+    rolling.session_id = Some(session_id.clone());
+    let start = rolling.covered_until_ms;
+    let end = until_ms.or_else(|| sentences.last().map(|s| s.start_ms + 1));
+    if let Some(end) = end.filter(|&end| end > start) {
+        let service = available_service().await;
+        fold(service.as_ref(), &mut rolling, &sentences_in(&sentences, start, end), start, end).await;
+    }
+    Ok(rolling.snapshot(&session_id))
+}
+
+/// Summary of a stored session's whole transcript, window by window
+async fn summarize_transcript(session_id: &str, transcript: &[TranscriptLine]) -> RollingSummary {
+    let service = available_service().await;
+    let mut rolling = Rolling::default();
+    let sentences = crate::sentences::segment(transcript);
+    for (start, end) in windows(&sentences, 0, WINDOW_MS, true) {
+        fold(service.as_ref(), &mut rolling, &sentences_in(&sentences, start, end), start, end).await;
+    }
+    rolling.snapshot(session_id)
+}
+
+// ========== Tauri Commands ==========
+
+// The rolling summary of the call in progress as it stands (no LLM work)
+#[tauri::command]
+pub fn get_rolling_summary() -> Result<Option<RollingSummary>, String> {
+    let session_id = match crate::session_store::current_session_id() {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+    let rolling = ROLLING.lock().unwrap();
+    Ok(rolling.session_id.is_some().then(|| rolling.snapshot(&session_id)))
+}
+
+// Final summary of a session (the current one when no id is given), stored with it;
+// a stored session that was summarized before returns that summary
+#[tauri::command]
+pub async fn summarize_call(session_id: Option<String>) -> Result<RollingSummary, String> {
+    let current_id = crate::session_store::current_session_id();
+    let summary = if session_id.is_none() || session_id == current_id {
+        summary_so_far(None).await?
+    } else {
+        let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+        if let Some(summary) = session.summary {
+            return Ok(summary);
+        }
+        if session.transcript.is_empty() {
+            return Err(format!("Session {} has no transcript to summarize", session.id));
+        }
+        summarize_transcript(&session.id, &session.transcript).await
+    };
+    crate::session_store::attach_summary(&summary.session_id, summary.clone()).map_err(|e| e.to_string())?;
+    info!("🧾 Session {} summarized ({} min, {})", summary.session_id, minute(summary.covered_until_ms), summary.summarized_by);
+    let session_id = summary.session_id.clone();
+    tauri::async_runtime::spawn(async move { crate::similar_sessions::index_session(&session_id).await });
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(start_ms: u64, is_user: bool, text: &str) -> Sentence {
+        Sentence { line: 0, is_user, text: text.to_string(), start_ms, end_ms: start_ms, words: text.split_whitespace().count() }
+    }
+
+    #[test]
+    fn test_windows_close_as_the_transcript_passes_them() {
+        let sentences = vec![sentence(0, true, "hi"), sentence(4_000, false, "hello"), sentence(11_500, true, "so")];
+        assert_eq!(windows(&sentences, 0, 5_000, false), vec![(0, 5_000), (5_000, 10_000)]);
+        assert_eq!(windows(&sentences, 10_000, 5_000, false), vec![]);
+        assert_eq!(windows(&sentences, 10_000, 5_000, true), vec![(10_000, 11_501)]);
+        assert_eq!(sentences_in(&sentences, 0, 5_000).len(), 2);
+
+        // A line running past the end of a window is cut between its sentences
+        let line = TranscriptLine { offset_ms: 3_500, is_user: false, text: "Two words. Then four more words.".to_string(), words: Vec::new() };
+        let split = crate::sentences::segment(&[line]);
+        assert_eq!(sentences_in(&split, 0, 4_000).len(), 1);
+        assert_eq!(sentences_in(&split, 4_000, 8_000)[0].text, "Then four more words.");
+
+        // Without the LLM a window keeps its most informative sentences, in call order
+        let sentences = vec![
+            sentence(0, true, "ok"),
+            sentence(1, false, "our procurement process requires security review approval"),
+            sentence(2, true, "yes"),
+            sentence(3, false, "budget cycle starts january"),
+        ];
+        assert_eq!(excerpt(&sentences), "Rep: ok / Prospect: our procurement process requires security review approval / Prospect: budget cycle starts january");
+    }
+}
@@ -0,0 +1,168 @@
+// Rule Harness - replays transcripts through the coaching rules and checks what fires
+// A rule author changing a trigger word or a cooldown wants to know which prompts a
+// real call would have produced, and to pin that down. A fixture is a JSON file with
+// a transcript, an optional coaching profile and the prompts expected to fire:
+//   {"name": "...", "profile": {...}, "transcript": [...],
+//    "expect": [{"rule": "fallback:price", "at_ms": 12000, "tolerance_ms": 2000}],
+//    "exact": true}
+// Each prospect line is a coaching request: the rule that answers it (the same
+// fallback rules as without Ollama) goes through the cooldown gates on the
+// transcript's clock, embeddings left out. Every expected prompt must fire within its
+// tolerance; with "exact", nothing else may fire. test_rules_against_session runs a
+// fixture against a stored session (or the fixture's own transcript), or just lists
+// what would fire; the fixtures shipped in rule_fixtures/ run with cargo test.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::coaching_cooldown::{CoachingProfile, ReplayGates};
+use crate::ollama_integration::OllamaCoachingService;
+use crate::session_store::TranscriptLine;
+
+const DEFAULT_TOLERANCE_MS: u64 = 1_000;
+
+#[derive(Debug, Clone, Deserialize)]
+struct ExpectedPrompt {
+    rule: String,
+    at_ms: u64,
+    #[serde(default = "default_tolerance_ms")]
+    tolerance_ms: u64,
+}
+
+fn default_tolerance_ms() -> u64 { DEFAULT_TOLERANCE_MS }
+
+#[derive(Debug, Clone, Deserialize)]
+struct RuleFixture {
+    name: String,
+    #[serde(default)]
+    profile: Option<CoachingProfile>,  // The default profile when missing
+    #[serde(default)]
+    transcript: Vec<TranscriptLine>,   // Empty = run against a session
+    #[serde(default)]
+    expect: Vec<ExpectedPrompt>,
+    #[serde(default)]
+    exact: bool,                       // Prompts not listed in `expect` fail the fixture
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RuleEvaluation {
+    pub offset_ms: u64,
+    pub text: String,                // The prospect line that asked for coaching
+    pub rule: String,                // e.g. "fallback:price"
+    pub fired: bool,
+    pub suppressed_by: Option<String>, // Why the gates held it back
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RuleTestReport {
+    pub name: String,
+    pub evaluations: Vec<RuleEvaluation>,
+    pub failures: Vec<String>,
+    pub passed: bool,
+}
+
+fn load_fixture(path: &Path) -> Result<RuleFixture, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("Cannot read fixture {:?}: {}", path, e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Fixture {:?} is invalid: {}", path, e))
+}
+
+/// Every coaching request of the transcript with the rule answering it and whether it fired
+fn replay(profile: &CoachingProfile, transcript: &[TranscriptLine]) -> Vec<RuleEvaluation> {
+    let mut gates = ReplayGates::default();
+    transcript.iter().filter(|line| !line.is_user).map(|line| {
+        let (rule, message) = OllamaCoachingService::fallback_rule(&line.text);
+        let rule = format!("fallback:{}", rule);
+        let outcome = gates.offer(profile, line.offset_ms, &rule, &crate::i18n::text(message));
+        RuleEvaluation {
+            offset_ms: line.offset_ms,
+            text: line.text.clone(),
+            rule,
+            fired: outcome.is_ok(),
+            suppressed_by: outcome.err(),
+        }
+    }).collect()
+}
+
+/// Expectations the evaluations don't meet
+fn check(evaluations: &[RuleEvaluation], expect: &[ExpectedPrompt], exact: bool) -> Vec<String> {
+    let fired: Vec<&RuleEvaluation> = evaluations.iter().filter(|e| e.fired).collect();
+    let mut matched = vec![false; fired.len()];
+    let mut failures = Vec::new();
+    for expected in expect {
+        let hit = fired.iter().enumerate().position(|(i, e)| {
+            !matched[i] && e.rule == expected.rule && e.offset_ms.abs_diff(expected.at_ms) <= expected.tolerance_ms
+        });
+        match hit {
+            Some(i) => matched[i] = true,
+            None => failures.push(format!("expected {} at {:.1}s (±{:.1}s), did not fire",
+                expected.rule, expected.at_ms as f64 / 1000.0, expected.tolerance_ms as f64 / 1000.0)),
+        }
+    }
+    if exact {
+        for (e, _) in fired.iter().zip(&matched).filter(|(_, &m)| !m) {
+            failures.push(format!("unexpected {} at {:.1}s", e.rule, e.offset_ms as f64 / 1000.0));
+        }
+    }
+    failures
+}
+
+fn run(name: String, profile: &CoachingProfile, transcript: &[TranscriptLine], expect: &[ExpectedPrompt], exact: bool) -> RuleTestReport {
+    let evaluations = replay(profile, transcript);
+    let failures = check(&evaluations, expect, exact);
+    RuleTestReport { name, passed: failures.is_empty(), evaluations, failures }
+}
+
+// ========== Tauri Commands ==========
+
+// Replay a session (the current one when no id is given) through the coaching rules.
+// With a fixture, its expectations are checked and its profile used; its own
+// transcript replaces the session's when no session id is given.
+#[tauri::command]
+pub fn test_rules_against_session(session_id: Option<String>, fixture_path: Option<String>) -> Result<RuleTestReport, String> {
+    let fixture = fixture_path.map(|path| load_fixture(Path::new(&path))).transpose()?;
+    let profile = fixture.as_ref().and_then(|f| f.profile.clone())
+        .unwrap_or_else(|| crate::preferences::load().coaching_profile);
+    match fixture {
+        Some(fixture) if session_id.is_none() && !fixture.transcript.is_empty() => {
+            Ok(run(fixture.name, &profile, &fixture.transcript, &fixture.expect, fixture.exact))
+        }
+        fixture => {
+            let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+            Ok(match fixture {
+                Some(fixture) => run(fixture.name, &profile, &session.transcript, &fixture.expect, fixture.exact),
+                None => run(session.id, &profile, &session.transcript, &[], false),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_rule_fixtures_pass() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("rule_fixtures");
+        let mut fixtures = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let fixture = load_fixture(&path).unwrap();
+            let report = run(fixture.name, &fixture.profile.unwrap_or_default(), &fixture.transcript, &fixture.expect, fixture.exact);
+            assert!(report.passed, "{:?}: {:?}", path, report.failures);
+            fixtures += 1;
+        }
+        assert!(fixtures > 0);
+
+        // A prompt held back by its cooldown is reported, and expecting it fails
+        let line = |offset_ms: u64, text: &str| TranscriptLine { offset_ms, is_user: false, text: text.to_string(), words: Vec::new() };
+        let transcript = vec![line(5_000, "The price is steep."), line(30_000, "Again, the price.")];
+        let expect = vec![ExpectedPrompt { rule: "fallback:price".to_string(), at_ms: 30_000, tolerance_ms: 0 }];
+        let report = run("cooldown".to_string(), &CoachingProfile::default(), &transcript, &expect, true);
+        assert!(report.evaluations[1].suppressed_by.as_deref().unwrap().contains("cooling down"));
+        assert_eq!(report.failures.len(), 2);
+        assert!(report.failures[1].starts_with("unexpected fallback:price at 5.0s"));
+    }
+}
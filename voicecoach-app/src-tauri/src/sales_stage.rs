@@ -0,0 +1,59 @@
+// Sales Stage Commands - stage detection wired to the app's settings and windows
+// Detection and the stage vocabularies are in voicecoach-core (re-exported here under
+// the old path); the functions below read the bias settings from preferences (the
+// capture engines get them through CaptureHost), and the commands expose and set the
+// current stage and settings.
+
+pub use voicecoach_core::sales_stage::*;
+
+use tauri::AppHandle;
+use voicecoach_core::sales_stage as detection;
+
+use crate::event_sink::AppEvents;
+
+fn settings() -> StageBiasSettings {
+    crate::preferences::load().stage_bias
+}
+
+/// Cue phrases of the objection-handling stage
+pub fn objection_cues() -> Vec<String> {
+    detection::objection_cues(&settings())
+}
+
+/// Whether a line carries objection-handling cues (counted per call by call_analytics)
+pub fn is_objection(text: &str) -> bool {
+    detection::is_objection(text, &settings())
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_sales_stage() -> Result<StageStatus, String> {
+    Ok(status(&settings(), None))
+}
+
+// Manual override (e.g. the rep jumps straight to pricing); detection continues from here
+#[tauri::command]
+pub fn set_sales_stage(app: AppHandle, stage: SalesStage) -> Result<StageStatus, String> {
+    Ok(set_stage(&AppEvents(app), stage, &settings()))
+}
+
+#[tauri::command]
+pub fn get_stage_bias_settings() -> Result<StageBiasSettings, String> {
+    let mut settings = settings();
+    if settings.stages.is_empty() {
+        settings.stages = default_vocabularies();
+    }
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn set_stage_bias_settings(settings: StageBiasSettings) -> Result<StageBiasSettings, String> {
+    if !(0.0..=10.0).contains(&settings.keyword_boost) {
+        return Err("keyword_boost must be between 0 and 10".to_string());
+    }
+    crate::preferences::update(|p| p.stage_bias = settings.clone())
+        .map_err(|e| e.to_string())?;
+    settings_changed();
+    Ok(settings)
+}
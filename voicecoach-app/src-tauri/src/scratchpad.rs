@@ -0,0 +1,226 @@
+// Scratchpad - notes typed during a call, pinned to the transcript
+// Each note is stamped with the live transcript position (the session's capture
+// clock, the same one transcript lines use) when it is typed, and kept with the
+// session. Review reads the call as one timeline with the notes between the lines
+// that were being spoken, and the notes export as text in which every note follows a
+// short excerpt of the transcript leading up to it (overlapping excerpts are merged),
+// ready to paste into a CRM or share with a manager. The review timeline also marks
+// when the prospect was on hold or in dead air (hold_detection) and when the rep's
+// microphone was muted (hardware_mute), and the call's key moments (key_moments).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use log::info;
+
+use crate::hold_detection::QuietKind;
+use crate::key_moments::KeyMomentKind;
+use crate::session_store::{ScratchNote, Session, TranscriptLine};
+
+const MAX_NOTE_CHARS: usize = 2_000;
+// Transcript lines before / after each note in an export
+const DEFAULT_CONTEXT_LINES: usize = 2;
+const MAX_CONTEXT_LINES: usize = 20;
+const LINES_AFTER: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    Transcript,
+    Note,
+    Hold,
+    DeadAir,
+    Muted,
+    KeyMoment,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TimelineEntry {
+    pub kind: TimelineKind,
+    pub offset_ms: u64,
+    pub text: String,
+    pub is_user: Option<bool>,       // Transcript lines: the rep's side
+    pub note_id: Option<u32>,
+    pub end_ms: Option<u64>,         // Hold, dead-air and muted periods
+    #[serde(default)]
+    pub key_moment: Option<KeyMomentKind>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct NotesExport {
+    pub session_id: String,
+    pub path: Option<String>,        // Written when a path was given
+    pub text: String,
+    pub notes: usize,
+}
+
+/// Transcript lines and notes in call order (a note comes after a line at the same time)
+fn timeline(transcript: &[TranscriptLine], notes: &[ScratchNote]) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = transcript.iter()
+        .map(|line| TimelineEntry { kind: TimelineKind::Transcript, offset_ms: line.offset_ms, text: line.text.clone(), is_user: Some(line.is_user), note_id: None, end_ms: None, key_moment: None })
+        .chain(notes.iter().map(|note| TimelineEntry { kind: TimelineKind::Note, offset_ms: note.offset_ms, text: note.text.clone(), is_user: None, note_id: Some(note.id), end_ms: None, key_moment: None }))
+        .collect();
+    // Stable: lines and notes each keep their own order
+    entries.sort_by_key(|e| (e.offset_ms, e.kind == TimelineKind::Note));
+    entries
+}
+
+fn clock(offset_ms: u64) -> String {
+    let seconds = offset_ms / 1000;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// The notes as text, each after the `context_lines` transcript lines before it
+fn render_export(session: &Session, context_lines: usize) -> String {
+    let entries = timeline(&session.transcript, &session.scratchpad);
+    // Positions of the transcript lines each note pulls into the export
+    let mut keep = BTreeSet::new();
+    for (position, entry) in entries.iter().enumerate() {
+        if entry.kind != TimelineKind::Note {
+            continue;
+        }
+        keep.insert(position);
+        let lines_before = entries[..position].iter().enumerate().rev()
+            .filter(|(_, e)| e.kind == TimelineKind::Transcript)
+            .take(context_lines);
+        let lines_after = entries.iter().enumerate().skip(position + 1)
+            .filter(|(_, e)| e.kind == TimelineKind::Transcript)
+            .take(LINES_AFTER);
+        keep.extend(lines_before.chain(lines_after).map(|(p, _)| p));
+    }
+
+    let started = chrono::DateTime::from_timestamp_millis(session.started_at as i64)
+        .map_or(String::new(), |t| t.format(" (%Y-%m-%d %H:%M UTC)").to_string());
+    let mut text = format!("Call notes - session {}{}\n", session.id, started);
+    let mut previous = None;
+    for position in keep {
+        if previous.map_or(true, |p| p + 1 != position) {
+            text.push_str("\n…\n");
+        }
+        previous = Some(position);
+        let entry = &entries[position];
+        match entry.kind {
+            TimelineKind::Note => text.push_str(&format!("  >> NOTE [{}] {}\n", clock(entry.offset_ms), entry.text)),
+            TimelineKind::Transcript => {
+                let speaker = if entry.is_user == Some(true) { "Rep" } else { "Prospect" };
+                text.push_str(&format!("[{}] {}: {}\n", clock(entry.offset_ms), speaker, entry.text));
+            }
+            TimelineKind::Hold | TimelineKind::DeadAir | TimelineKind::Muted | TimelineKind::KeyMoment => text.push_str(&format!("  -- [{}] {}\n", clock(entry.offset_ms), entry.text)),
+        }
+    }
+    text
+}
+
+fn check_text(text: &str) -> Result<&str, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("The note is empty".to_string());
+    }
+    if text.chars().count() > MAX_NOTE_CHARS {
+        return Err(format!("Notes are limited to {} characters", MAX_NOTE_CHARS));
+    }
+    Ok(text)
+}
+
+fn session_id_or_current(session_id: Option<String>) -> Result<String, String> {
+    session_id.or_else(crate::session_store::current_session_id).ok_or_else(|| "No session in progress".to_string())
+}
+
+// ========== Tauri Commands ==========
+
+// Note on the call in progress, at the current transcript position
+#[tauri::command]
+pub fn add_scratch_note(text: String) -> Result<ScratchNote, String> {
+    let note = crate::session_store::add_scratch_note(check_text(&text)?).map_err(|e| e.to_string())?;
+    info!("📝 Scratchpad note {} at {}", note.id, clock(note.offset_ms));
+    Ok(note)
+}
+
+#[tauri::command]
+pub fn update_scratch_note(session_id: Option<String>, note_id: u32, text: String) -> Result<ScratchNote, String> {
+    let id = session_id_or_current(session_id)?;
+    crate::session_store::edit_scratch_note(&id, note_id, Some(check_text(&text)?))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No note {} in session {}", note_id, id))
+}
+
+#[tauri::command]
+pub fn delete_scratch_note(session_id: Option<String>, note_id: u32) -> Result<(), String> {
+    let id = session_id_or_current(session_id)?;
+    crate::session_store::edit_scratch_note(&id, note_id, None).map(|_| ()).map_err(|e| e.to_string())
+}
+
+// Scratchpad notes of a session (the current one when no id is given)
+#[tauri::command]
+pub fn get_scratch_notes(session_id: Option<String>) -> Result<Vec<ScratchNote>, String> {
+    crate::session_store::load_session(session_id).map(|s| s.scratchpad).map_err(|e| e.to_string())
+}
+
+// Transcript, notes, hold / dead-air / muted periods and key moments of a session
+// merged in call order
+#[tauri::command]
+pub fn get_notes_timeline(session_id: Option<String>) -> Result<Vec<TimelineEntry>, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    let mut entries = timeline(&session.transcript, &session.scratchpad);
+    entries.extend(session.quiet_periods.iter().map(|period| {
+        let (kind, label) = match period.kind {
+            QuietKind::Hold => (TimelineKind::Hold, "On hold"),
+            QuietKind::DeadAir => (TimelineKind::DeadAir, "Dead air"),
+        };
+        let length = period.end_ms.map_or(String::new(), |end| format!(" ({})", clock(end.saturating_sub(period.start_ms))));
+        TimelineEntry { kind, offset_ms: period.start_ms, text: format!("{}{}", label, length), is_user: Some(false), note_id: None, end_ms: period.end_ms, key_moment: None }
+    }));
+    entries.extend(session.muted_intervals.iter().map(|interval| {
+        let length = interval.end_ms.map_or(String::new(), |end| format!(" ({})", clock(end.saturating_sub(interval.start_ms))));
+        TimelineEntry { kind: TimelineKind::Muted, offset_ms: interval.start_ms, text: format!("Rep muted{}", length), is_user: Some(true), note_id: None, end_ms: interval.end_ms, key_moment: None }
+    }));
+    entries.extend(crate::key_moments::key_moments(&session.transcript).into_iter().map(|moment| {
+        TimelineEntry { kind: TimelineKind::KeyMoment, offset_ms: moment.offset_ms, text: moment.label, is_user: Some(moment.is_user), note_id: None, end_ms: None, key_moment: Some(moment.kind) }
+    }));
+    entries.sort_by_key(|e| e.offset_ms);
+    Ok(entries)
+}
+
+// Notes interleaved with transcript excerpts (`context_lines` lines before each note,
+// 2 by default), written to `path` when one is given
+#[tauri::command]
+pub fn export_scratch_notes(session_id: Option<String>, path: Option<String>, context_lines: Option<u32>) -> Result<NotesExport, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    if session.scratchpad.is_empty() {
+        return Err(format!("Session {} has no notes", session.id));
+    }
+    let context_lines = context_lines.map_or(DEFAULT_CONTEXT_LINES, |n| n as usize).min(MAX_CONTEXT_LINES);
+    let text = render_export(&session, context_lines);
+    if let Some(path) = &path {
+        std::fs::write(path, &text).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        info!("📝 Exported {} notes of session {} to {}", session.scratchpad.len(), session.id, path);
+    }
+    Ok(NotesExport { session_id: session.id, path, text, notes: session.scratchpad.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notes_interleave_with_the_lines_around_them() {
+        let session: Session = serde_json::from_value(serde_json::json!({
+            "id": "20260101-090000",
+            "started_at": 0,
+            "transcript": (0..8).map(|i| serde_json::json!({ "offset_ms": i * 10_000, "is_user": i % 2 == 0, "text": format!("line {}", i) })).collect::<Vec<_>>(),
+            "scratchpad": [
+                { "id": 0, "offset_ms": 15_000, "text": "budget is fixed", "created_at": 0 },
+                { "id": 1, "offset_ms": 70_000, "text": "send pricing", "created_at": 0 },
+            ],
+        })).unwrap();
+
+        let entries = timeline(&session.transcript, &session.scratchpad);
+        assert_eq!(entries.len(), 10);
+        assert_eq!((entries[2].kind, entries[2].note_id), (TimelineKind::Note, Some(0)));
+        // A note typed at the start of a line comes after it
+        assert_eq!(entries[9].note_id, Some(1));
+
+        let text = render_export(&session, 1);
+        assert!(text.contains("[00:10] Prospect: line 1\n  >> NOTE [00:15] budget is fixed\n[00:20] Rep: line 2\n\n…\n[01:10] Prospect: line 7\n  >> NOTE [01:10] send pricing\n"));
+        assert!(!text.contains("line 4"));
+    }
+}
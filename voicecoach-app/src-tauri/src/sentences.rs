@@ -0,0 +1,14 @@
+// Sentences Commands - the session transcript as timed sentences
+// Splitting and timing are in voicecoach-core (re-exported here under the old path);
+// the command segments a stored session's transcript.
+
+pub use voicecoach_core::sentences::*;
+
+// ========== Tauri Commands ==========
+
+// Sentences of a session's transcript (the current one when no id is given)
+#[tauri::command]
+pub fn get_transcript_sentences(session_id: Option<String>) -> Result<Vec<Sentence>, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    Ok(segment(&session.transcript))
+}
@@ -0,0 +1,218 @@
+// Session Arbiter - one call session at a time
+// Starting transcription begins a session, and a double-click, the hotkey and the tray
+// firing together used to start two: the second start superseded the first stream
+// halfway through its setup and left an empty session behind. A start now claims the
+// arbiter for the length of its setup and holds the session once the engine is live.
+// A competing start is refused with an error that begins with a code the UI can
+// match: SESSION_STARTING while another start is still setting up, SESSION_ACTIVE
+// while a session is running. force_start_session is the explicit takeover: it stops
+// the running engine, writes out the previous session and starts the new one.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+use crate::two_pass::Engine;
+
+// How long a takeover waits for a start that is still setting up
+const TAKEOVER_WAIT_MS: u64 = 5_000;
+const TAKEOVER_POLL_MS: u64 = 100;
+
+fn name(engine: Engine) -> &'static str {
+    match engine {
+        Engine::Vosk => "vosk",
+        Engine::Deepgram => "deepgram",
+    }
+}
+
+fn is_running(engine: Engine) -> bool {
+    match engine {
+        Engine::Vosk => crate::vosk_transcription::is_running(),
+        Engine::Deepgram => crate::deepgram_transcription::is_running(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ArbiterState {
+    Idle,
+    Starting,
+    Active,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ArbiterStatus {
+    pub state: ArbiterState,
+    pub engine: Option<Engine>,
+    pub session_id: Option<String>,
+    pub since: Option<u64>,
+}
+
+// Payload of "session_takeover"
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SessionTakeover {
+    pub previous_session_id: Option<String>,
+    pub previous_engine: Engine,
+    pub engine: Engine,
+}
+
+#[derive(Debug, Clone)]
+enum Slot {
+    Idle,
+    Starting { engine: Engine, since: u64 },
+    Active { engine: Engine, session_id: Option<String>, since: u64 },
+}
+
+static SLOT: Lazy<Mutex<Slot>> = Lazy::new(|| Mutex::new(Slot::Idle));
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// The slot as it stands; a session whose engine has stopped no longer holds it
+fn current(slot: &mut Slot) -> Slot {
+    if let Slot::Active { engine, .. } = slot {
+        if !is_running(*engine) {
+            *slot = Slot::Idle;
+        }
+    }
+    slot.clone()
+}
+
+/// A start in progress; dropping it without `commit` (the start failed) frees the arbiter
+pub struct StartClaim {
+    engine: Engine,
+    committed: bool,
+}
+
+impl StartClaim {
+    /// The engine is live: the session now holds the arbiter
+    pub fn commit(mut self) {
+        self.committed = true;
+        let session_id = crate::session_store::current_session_id();
+        *SLOT.lock().unwrap() = Slot::Active { engine: self.engine, session_id, since: now_ms() };
+    }
+}
+
+impl Drop for StartClaim {
+    fn drop(&mut self) {
+        if !self.committed {
+            *SLOT.lock().unwrap() = Slot::Idle;
+        }
+    }
+}
+
+/// Claim the arbiter for starting a session with `engine`, unless another start or
+/// session holds it
+pub fn claim(engine: Engine) -> Result<StartClaim, String> {
+    let mut slot = SLOT.lock().unwrap();
+    match current(&mut slot) {
+        Slot::Idle => {}
+        Slot::Starting { engine: other, .. } => {
+            warn!("⚠️ {} start refused: a {} session is starting", name(engine), name(other));
+            return Err(format!("SESSION_STARTING: A {} session is already starting", name(other)));
+        }
+        Slot::Active { engine: other, session_id, .. } => {
+            warn!("⚠️ {} start refused: session {:?} is running", name(engine), session_id);
+            return Err(format!("SESSION_ACTIVE: Session {} ({}) is in progress. Stop it first or use force_start_session",
+                session_id.as_deref().unwrap_or("?"), name(other)));
+        }
+    }
+    *slot = Slot::Starting { engine, since: now_ms() };
+    Ok(StartClaim { engine, committed: false })
+}
+
+pub fn status() -> ArbiterStatus {
+    match current(&mut SLOT.lock().unwrap()) {
+        Slot::Idle => ArbiterStatus { state: ArbiterState::Idle, engine: None, session_id: None, since: None },
+        Slot::Starting { engine, since } => ArbiterStatus { state: ArbiterState::Starting, engine: Some(engine), session_id: None, since: Some(since) },
+        Slot::Active { engine, session_id, since } => ArbiterStatus { state: ArbiterState::Active, engine: Some(engine), session_id, since: Some(since) },
+    }
+}
+
+/// Stop the running session's engine and write the session out
+async fn finalize(engine: Engine) -> Result<(), String> {
+    match engine {
+        Engine::Vosk => {
+            crate::vosk_transcription::stop_vosk_transcription().await?;
+        }
+        Engine::Deepgram => crate::deepgram_transcription::close_capture(),
+    }
+    *SLOT.lock().unwrap() = Slot::Idle;
+    crate::session_store::write_current().map_err(|e| format!("Failed to save the previous session: {}", e))
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_session_arbiter_status() -> Result<ArbiterStatus, String> {
+    Ok(status())
+}
+
+// Take over: end the session in progress (waiting briefly for one that is still
+// starting), then start a new one on `engine`. Vosk needs model_path, Deepgram api_key
+// (source and diarize as for start_deepgram_transcription).
+#[tauri::command]
+pub async fn force_start_session(
+    app: AppHandle,
+    engine: Engine,
+    model_path: Option<String>,
+    api_key: Option<String>,
+    source: Option<String>,
+    diarize: Option<bool>,
+) -> Result<String, String> {
+    let mut waited = 0;
+    while status().state == ArbiterState::Starting {
+        if waited >= TAKEOVER_WAIT_MS {
+            return Err("SESSION_STARTING: The session being started did not finish starting".to_string());
+        }
+        tokio::time::sleep(Duration::from_millis(TAKEOVER_POLL_MS)).await;
+        waited += TAKEOVER_POLL_MS;
+    }
+
+    let previous = status();
+    if let (ArbiterState::Active, Some(previous_engine)) = (previous.state, previous.engine) {
+        info!("🔀 Taking over session {:?} ({}) for a new {} session", previous.session_id, name(previous_engine), name(engine));
+        finalize(previous_engine).await?;
+        let takeover = SessionTakeover { previous_session_id: previous.session_id, previous_engine, engine };
+        if let Err(e) = app.emit_all("session_takeover", takeover) {
+            error!("Failed to emit session_takeover: {:?}", e);
+        }
+    }
+
+    match engine {
+        Engine::Vosk => {
+            let model_path = model_path.ok_or("A Vosk session needs model_path")?;
+            crate::vosk_transcription::start_vosk_transcription(app, model_path).await
+        }
+        Engine::Deepgram => {
+            let api_key = api_key.ok_or("A Deepgram session needs api_key")?;
+            crate::deepgram_transcription::start_deepgram_transcription(app, api_key, source, diarize).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_start_is_refused_until_the_first_fails_or_its_engine_stops() {
+        let first = claim(Engine::Vosk).unwrap();
+        let refused = claim(Engine::Deepgram).err().unwrap();
+        assert!(refused.starts_with("SESSION_STARTING: A vosk session"));
+        assert_eq!(status().state, ArbiterState::Starting);
+
+        // The first start failed
+        drop(first);
+        assert_eq!(status().state, ArbiterState::Idle);
+
+        // Committed, but the engine isn't running (it stopped): the slot is free again
+        claim(Engine::Deepgram).unwrap().commit();
+        assert_eq!(status().state, ArbiterState::Idle);
+        drop(claim(Engine::Vosk).unwrap());
+    }
+}
@@ -0,0 +1,407 @@
+// Session Import - calls recorded with other tools, brought into VoiceCoach
+// Consultants often have a WAV recording and a transcript from another tool (a
+// meeting recorder, a captioning service). import_external_session turns the pair
+// into a regular session: the transcript is parsed (plain text with timestamps, JSON
+// segments, or WebVTT - SRT differs only in its header and decimal comma), speakers
+// are split into the rep's side and the prospect's, the call metrics are computed
+// from the lines as the live call would have (call_analytics), and the recording is
+// linked for playback. From there the session shows up in analytics exports, team
+// reports, transcript search and summaries; optionally the transcript is also added
+// to the knowledge base so coaching can draw on it.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use log::{info, warn};
+
+use crate::session_store::TranscriptLine;
+
+// Knowledge base document type of imported call transcripts
+const CALL_TRANSCRIPT_DOC_TYPE: &str = "call_transcript";
+// Speaker labels taken as the rep when no rep speaker is given
+const REP_LABELS: &[&str] = &["rep", "me", "you", "agent", "sales", "salesperson", "host"];
+// Pace used to place lines when the transcript has no timestamps at all
+const ESTIMATED_WPM: u64 = 150;
+// Longest "Name:" prefix read as a speaker label
+const MAX_SPEAKER_CHARS: usize = 40;
+// Transcript running past the recording by more than this is reported
+const DURATION_TOLERANCE_MS: u64 = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    Text,
+    Json,
+    Vtt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ImportedSession {
+    pub session_id: String,
+    pub lines: usize,
+    pub duration_ms: u64,
+    pub speakers: Vec<String>,       // As labelled in the transcript, in order of appearance
+    pub rep_speaker: Option<String>, // The one taken as the rep
+    pub recording: Option<String>,
+    pub knowledge_document: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// A transcript line as read from the file
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ParsedLine {
+    offset_ms: Option<u64>,
+    end_ms: Option<u64>,
+    speaker: Option<String>,
+    is_user: Option<bool>,           // VoiceCoach's own JSON says so directly
+    text: String,
+}
+
+fn format_for(path: &str) -> Result<TranscriptFormat, String> {
+    let extension = Path::new(path).extension().map(|e| e.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("txt") | Some("text") | Some("md") => Ok(TranscriptFormat::Text),
+        Some("json") => Ok(TranscriptFormat::Json),
+        Some("vtt") | Some("srt") => Ok(TranscriptFormat::Vtt),
+        _ => Err(format!("Can't tell the transcript format of {}; pass text, json or vtt", path)),
+    }
+}
+
+/// "1:02:03.5", "02:03", "02:03,500" (SRT) as milliseconds
+fn parse_clock(text: &str) -> Option<u64> {
+    let parts: Vec<&str> = text.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+    let (whole, fraction) = match parts[parts.len() - 1].split_once(['.', ',']) {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (parts[parts.len() - 1], None),
+    };
+    let mut units = parts[..parts.len() - 1].to_vec();
+    units.push(whole);
+    if units.iter().any(|u| u.is_empty() || !u.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    let seconds = units.iter().fold(0u64, |total, u| total * 60 + u.parse::<u64>().unwrap_or(0));
+    let millis = match fraction {
+        Some(f) if !f.is_empty() && f.chars().all(|c| c.is_ascii_digit()) => {
+            format!("{:0<3}", &f[..f.len().min(3)]).parse::<u64>().unwrap_or(0)
+        }
+        Some(_) => return None,
+        None => 0,
+    };
+    Some(seconds * 1000 + millis)
+}
+
+/// "Name: text" split into the speaker and the text
+fn split_speaker(text: &str) -> (Option<String>, &str) {
+    if let Some((label, rest)) = text.split_once(':') {
+        let label = label.trim();
+        let plausible = !label.is_empty()
+            && label.chars().count() <= MAX_SPEAKER_CHARS
+            && label.split_whitespace().count() <= 4
+            && !label.chars().any(|c| matches!(c, '.' | '?' | '!' | ',' | '"'));
+        if plausible {
+            return (Some(label.to_string()), rest.trim());
+        }
+    }
+    (None, text.trim())
+}
+
+/// Plain text: "[00:01:23] Name: text", "00:01:23 text", or a "Name 00:01:23" header
+/// line above the text; lines without a timestamp or speaker continue the one before
+fn parse_text(content: &str) -> Vec<ParsedLine> {
+    let mut lines: Vec<ParsedLine> = Vec::new();
+    // Speaker and time of a header line, for the text below it
+    let mut header: Option<(Option<String>, u64)> = None;
+    for raw in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let bracketed = raw.strip_prefix('[').or_else(|| raw.strip_prefix('('))
+            .and_then(|rest| rest.split_once([']', ')']))
+            .and_then(|(inside, rest)| Some((parse_clock(inside.split_whitespace().next()?)?, rest)));
+        let leading = bracketed.or_else(|| {
+            let (first, rest) = raw.split_once(char::is_whitespace).unwrap_or((raw, ""));
+            parse_clock(first).map(|ms| (ms, rest))
+        });
+        if let Some((offset_ms, rest)) = leading {
+            let rest = rest.trim_start_matches(|c: char| c == '-' || c.is_whitespace());
+            if rest.is_empty() {
+                header = Some((None, offset_ms));
+                continue;
+            }
+            let (speaker, text) = split_speaker(rest);
+            lines.push(ParsedLine { offset_ms: Some(offset_ms), speaker, text: text.to_string(), ..Default::default() });
+            header = None;
+            continue;
+        }
+        // "Name 00:01:23" (meeting recorders put the speaker and time above the text)
+        let trailing = raw.rsplit_once(char::is_whitespace)
+            .filter(|(name, _)| name.split_whitespace().all(|w| w.starts_with(|c: char| c.is_uppercase() || c.is_ascii_digit())))
+            .and_then(|(name, last)| Some((name.trim(), parse_clock(last)?)));
+        if let Some((name, offset_ms)) = trailing {
+            header = Some((Some(name.to_string()), offset_ms));
+            continue;
+        }
+        let (speaker, text) = split_speaker(raw);
+        let pending = header.take();
+        if pending.is_none() && speaker.is_none() {
+            if let Some(previous) = lines.last_mut() {
+                previous.text.push(' ');
+                previous.text.push_str(text);
+                continue;
+            }
+        }
+        let (header_speaker, offset_ms) = pending.map_or((None, None), |(speaker, ms)| (speaker, Some(ms)));
+        lines.push(ParsedLine { offset_ms, speaker: speaker.or(header_speaker), text: text.to_string(), ..Default::default() });
+    }
+    lines
+}
+
+/// WebVTT / SRT cues; the speaker from a <v Name> tag or a "Name:" prefix
+fn parse_vtt(content: &str) -> Vec<ParsedLine> {
+    let mut lines = Vec::new();
+    let normalized = content.replace("\r\n", "\n");
+    for block in normalized.split("\n\n") {
+        let mut block_lines = block.lines().map(str::trim).skip_while(|l| !l.contains("-->"));
+        let timing = match block_lines.next() {
+            Some(timing) => timing,
+            None => continue,  // Header, NOTE, STYLE and REGION blocks
+        };
+        let (start, end) = timing.split_once("-->").unwrap_or((timing, ""));
+        let raw_text = block_lines.filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" ");
+        let voice = raw_text.find("<v").and_then(|at| {
+            let tag = &raw_text[at + 2..];
+            let name = &tag[..tag.find('>')?];
+            // <v.loud Name> carries classes before the name
+            let name = name.trim_start_matches(|c: char| c != ' ').trim();
+            (!name.is_empty()).then(|| name.to_string())
+        });
+        let mut text = String::new();
+        let mut in_tag = false;
+        for c in raw_text.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' => in_tag = false,
+                c if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+        let (prefix, text) = split_speaker(&text);
+        if text.is_empty() {
+            continue;
+        }
+        lines.push(ParsedLine {
+            offset_ms: parse_clock(start.trim()),
+            end_ms: end.split_whitespace().next().and_then(parse_clock),
+            speaker: voice.or(prefix),
+            is_user: None,
+            text: text.to_string(),
+        });
+    }
+    lines
+}
+
+/// JSON segments: an array, or one under "segments" / "utterances" / "transcript" /
+/// "lines". Times are "start"/"end" in seconds or "start_ms"/"end_ms"/"offset_ms" in
+/// milliseconds (VoiceCoach's own transcripts and file transcriptions read as-is)
+fn parse_json(content: &str) -> Result<Vec<ParsedLine>, String> {
+    let value: serde_json::Value = serde_json::from_str(content).map_err(|e| format!("Invalid JSON transcript: {}", e))?;
+    let segments = match &value {
+        serde_json::Value::Array(segments) => segments,
+        serde_json::Value::Object(object) => ["segments", "utterances", "transcript", "lines"].iter()
+            .find_map(|key| object.get(*key)?.as_array())
+            .ok_or("The JSON transcript has no segments, utterances, transcript or lines array")?,
+        _ => return Err("The JSON transcript is neither an array nor an object".to_string()),
+    };
+    let millis = |segment: &serde_json::Value, ms_keys: &[&str], seconds_key: &str| {
+        ms_keys.iter().find_map(|key| segment.get(*key)?.as_u64())
+            .or_else(|| segment.get(seconds_key)?.as_f64().map(|s| (s.max(0.0) * 1000.0).round() as u64))
+    };
+    Ok(segments.iter()
+        .filter_map(|segment| {
+            let text = segment.get("text").or_else(|| segment.get("transcript"))?.as_str()?.trim();
+            let speaker = ["speaker", "speaker_label", "speaker_name"].iter()
+                .find_map(|key| match segment.get(*key)? {
+                    serde_json::Value::String(name) => Some(name.clone()),
+                    serde_json::Value::Number(n) => Some(format!("Speaker {}", n)),
+                    _ => None,
+                });
+            (!text.is_empty()).then(|| ParsedLine {
+                offset_ms: millis(segment, &["offset_ms", "start_ms"], "start"),
+                end_ms: millis(segment, &["end_ms"], "end"),
+                speaker,
+                is_user: segment.get("is_user").and_then(|v| v.as_bool()),
+                text: text.to_string(),
+            })
+        })
+        .collect())
+}
+
+// Lines with their speaking time, the speakers, and the one taken as the rep
+type Attributed = (Vec<(TranscriptLine, u64)>, Vec<String>, Option<String>);
+
+/// Lines on the call clock with the rep's side marked, each with its speaking time
+/// where the transcript gave an end time; also the speakers and the one taken as the rep
+fn to_transcript(mut parsed: Vec<ParsedLine>, rep_speaker: Option<&str>) -> Result<Attributed, String> {
+    let mut speakers: Vec<String> = Vec::new();
+    for speaker in parsed.iter().filter_map(|l| l.speaker.as_ref()) {
+        if !speakers.iter().any(|s| s.eq_ignore_ascii_case(speaker)) {
+            speakers.push(speaker.clone());
+        }
+    }
+    let rep = match rep_speaker {
+        Some(wanted) => Some(speakers.iter().find(|s| s.eq_ignore_ascii_case(wanted)).cloned()
+            .ok_or_else(|| format!("No speaker {} in the transcript (speakers: {})", wanted, speakers.join(", ")))?),
+        None => speakers.iter().find(|s| REP_LABELS.contains(&s.to_lowercase().as_str()))
+            .or_else(|| speakers.first())
+            .cloned(),
+    };
+
+    // Untimed transcripts are laid out at a speaking pace
+    if parsed.iter().all(|l| l.offset_ms.is_none()) {
+        let mut offset_ms = 0;
+        for line in parsed.iter_mut() {
+            let speech_ms = line.text.split_whitespace().count() as u64 * 60_000 / ESTIMATED_WPM;
+            line.offset_ms = Some(offset_ms);
+            line.end_ms = Some(offset_ms + speech_ms);
+            offset_ms += speech_ms;
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut offset_ms, mut is_user) = (0, true);
+    for line in parsed {
+        offset_ms = line.offset_ms.unwrap_or(offset_ms);
+        // Unlabelled lines stay with the side before them
+        is_user = line.is_user
+            .or_else(|| line.speaker.as_ref().map(|s| rep.as_ref().map_or(false, |r| r.eq_ignore_ascii_case(s))))
+            .unwrap_or(is_user);
+        let speech_ms = line.end_ms.map_or(0, |end| end.saturating_sub(offset_ms));
+        lines.push((TranscriptLine { offset_ms, is_user, text: line.text, words: Vec::new() }, speech_ms));
+    }
+    lines.sort_by_key(|(line, _)| line.offset_ms);
+    Ok((lines, speakers, rep))
+}
+
+/// Length of a WAV recording
+fn recording_duration_ms(path: &str) -> Result<u64, String> {
+    let reader = hound::WavReader::open(path).map_err(|e| format!("{} is not a readable WAV file: {}", path, e))?;
+    let spec = reader.spec();
+    Ok(reader.duration() as u64 * 1000 / spec.sample_rate.max(1) as u64)
+}
+
+// ========== Tauri Commands ==========
+
+// Import a call recorded elsewhere: a WAV recording (optional) and its transcript
+// (format from the extension when not given). `rep_speaker` names the rep's label in
+// the transcript; otherwise a label like "Rep" or "Me", else the first speaker. The
+// session starts at the file's modification time less the call's length.
+#[tauri::command]
+pub fn import_external_session(
+    audio_path: Option<String>,
+    transcript_path: String,
+    format: Option<TranscriptFormat>,
+    rep_speaker: Option<String>,
+    add_to_knowledge_base: Option<bool>,
+) -> Result<ImportedSession, String> {
+    let format = match format {
+        Some(format) => format,
+        None => format_for(&transcript_path)?,
+    };
+    let content = std::fs::read_to_string(&transcript_path)
+        .map_err(|e| format!("Failed to read {}: {}", transcript_path, e))?;
+    let parsed = match format {
+        TranscriptFormat::Text => parse_text(&content),
+        TranscriptFormat::Json => parse_json(&content)?,
+        TranscriptFormat::Vtt => parse_vtt(&content),
+    };
+    if parsed.is_empty() {
+        return Err(format!("No transcript lines found in {}", transcript_path));
+    }
+    let (lines, speakers, rep) = to_transcript(parsed, rep_speaker.as_deref())?;
+
+    let mut warnings = Vec::new();
+    if speakers.len() < 2 {
+        warnings.push("The transcript doesn't tell two speakers apart; talk ratio and question tracking will be off".to_string());
+    }
+    let transcript_ms = lines.iter().map(|(line, speech_ms)| line.offset_ms + speech_ms).max().unwrap_or(0);
+    let recording_ms = audio_path.as_deref().map(recording_duration_ms).transpose()?;
+    if let Some(recording_ms) = recording_ms {
+        if transcript_ms > recording_ms + DURATION_TOLERANCE_MS {
+            warnings.push(format!("The transcript runs {} s past the end of the recording", (transcript_ms - recording_ms) / 1000));
+        }
+    }
+    let duration_ms = recording_ms.unwrap_or(transcript_ms);
+    let modified_ms = std::fs::metadata(audio_path.as_deref().unwrap_or(&transcript_path))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or_else(|| chrono::Utc::now().timestamp_millis() as u64, |d| d.as_millis() as u64);
+    let started_at = modified_ms.saturating_sub(duration_ms);
+
+    let metrics = crate::call_analytics::transcript_metrics(&lines);
+    let transcript: Vec<TranscriptLine> = lines.into_iter().map(|(line, _)| line).collect();
+    let session = crate::session_store::import_session(started_at, transcript, audio_path.clone(), &transcript_path, metrics)
+        .map_err(|e| e.to_string())?;
+
+    let knowledge_document = if add_to_knowledge_base.unwrap_or(false) {
+        let text = session.transcript.iter()
+            .map(|line| format!("{}: {}", if line.is_user { "Rep" } else { "Prospect" }, line.text))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let filename = format!("call-{}.txt", session.id);
+        match crate::knowledge_base::process_text_content(filename.clone(), text, Some(CALL_TRANSCRIPT_DOC_TYPE.to_string())) {
+            Ok(_) => Some(filename),
+            Err(e) => {
+                warn!("⚠️ Imported session {} not added to the knowledge base: {}", session.id, e);
+                warnings.push(format!("Not added to the knowledge base: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    crate::transcript_quality::call_finished(&session.id);
+    info!("📥 Imported {} lines ({} speakers) from {} as session {}", session.transcript.len(), speakers.len(), transcript_path, session.id);
+    Ok(ImportedSession {
+        session_id: session.id,
+        lines: session.transcript.len(),
+        duration_ms,
+        speakers,
+        rep_speaker: rep,
+        recording: audio_path,
+        knowledge_document,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_formats_parse_to_the_same_call() {
+        let text = "[00:00:01] Rep: Hi, thanks for taking the call.\n[00:00:04] Dana: Sure, what's this about?\nI only have a minute.\n";
+        let vtt = "WEBVTT\n\nNOTE exported\n\n1\n00:00:01.000 --> 00:00:03.500\n<v Rep>Hi, thanks for taking the call.</v>\n\n00:00:04.000 --> 00:00:07.000\n<v Dana>Sure, what's this about?\nI only have a minute.</v>\n";
+        let json = r#"{"segments": [
+            {"start": 1.0, "end": 3.5, "speaker": "Rep", "text": "Hi, thanks for taking the call."},
+            {"start": 4.0, "end": 7.0, "speaker": "Dana", "text": "Sure, what's this about? I only have a minute."}
+        ]}"#;
+        for parsed in [parse_text(text), parse_vtt(vtt), parse_json(json).unwrap()] {
+            let (lines, speakers, rep) = to_transcript(parsed, None).unwrap();
+            assert_eq!(speakers, vec!["Rep".to_string(), "Dana".to_string()]);
+            assert_eq!(rep.as_deref(), Some("Rep"));
+            assert_eq!(lines.len(), 2);
+            assert!(lines[0].0.is_user && !lines[1].0.is_user);
+            assert_eq!((lines[0].0.offset_ms, lines[1].0.offset_ms), (1_000, 4_000));
+            assert_eq!(lines[1].0.text, "Sure, what's this about? I only have a minute.");
+        }
+
+        // Header lines, SRT times and a named rep
+        let recorder = "Alex Kim  0:00\nHello there.\nDana  1:02:03\nHi.\n";
+        let (lines, _, rep) = to_transcript(parse_text(recorder), Some("dana")).unwrap();
+        assert_eq!(rep.as_deref(), Some("Dana"));
+        assert_eq!((lines[1].0.offset_ms, lines[1].0.is_user), (3_723_000, true));
+        assert_eq!(parse_clock("00:01:02,250"), Some(62_250));
+        assert!(to_transcript(parse_text(text), Some("Sam")).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+// Session Share - sessions exported for training libraries, without the prospect
+// A good call makes a good training example, but it names the prospect and carries
+// their voice. export_shared_session writes a session to a folder (session.json and,
+// when it has one, recording.wav) with the identifying parts taken out: the dialer's
+// caller record, the research brief, coach notes and retained snippets are left
+// behind; every text in the session has its names pseudonymized and its email
+// addresses and phone numbers replaced (pii_redaction); and the recording is voice
+// anonymized by shifting pitch and formants together (a granular pitch shift that
+// keeps the timing, so transcript offsets still match the audio). The folder can be
+// sealed like any other export (export_security).
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+use std::path::Path;
+use log::info;
+
+use crate::pii_redaction::{EntityKind, Pseudonymizer};
+use crate::session_store::Session;
+
+const SESSION_FILE: &str = "session.json";
+const AUDIO_FILE: &str = "recording.wav";
+const GRAIN_MS: u32 = 40;
+// Frames read and shifted at a time
+const BLOCK_FRAMES: usize = 16_384;
+const MAX_SHIFT_SEMITONES: f32 = 12.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(default)]
+pub struct ShareOptions {
+    pub pseudonymize: bool,
+    pub names: Vec<String>,          // More people to alias (e.g. the rep)
+    pub include_audio: bool,
+    pub anonymize_voice: bool,
+    pub pitch_semitones: f32,        // Negative = lower voice
+}
+
+impl Default for ShareOptions {
+    fn default() -> Self {
+        Self { pseudonymize: true, names: Vec::new(), include_audio: true, anonymize_voice: true, pitch_semitones: -4.0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SharedSessionExport {
+    pub session_id: String,
+    pub session_file: String,
+    pub audio_file: Option<String>,
+    pub pseudonyms: usize,           // People and companies aliased
+    pub voice_shift_semitones: Option<f32>,
+}
+
+/// Granular pitch shifter: each Hann-windowed grain (50% overlap) is read from its
+/// own position in the input at `ratio` speed, which scales pitch and formants while
+/// the output keeps the input's length. Fed block by block.
+struct PitchShifter {
+    ratio: f32,
+    window: Vec<f32>,
+    hop: usize,
+    input: Vec<f32>,
+    input_start: usize,              // Absolute index of input[0]
+    output: Vec<f32>,                // Overlap-add accumulator from output_start
+    weights: Vec<f32>,
+    output_start: usize,
+    next_grain: usize,               // Absolute start of the next grain
+}
+
+impl PitchShifter {
+    fn new(sample_rate: u32, semitones: f32) -> Self {
+        let grain = (sample_rate * GRAIN_MS / 1000).max(64) as usize & !1;
+        let window = (0..grain).map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / grain as f32).cos()).collect();
+        Self {
+            ratio: 2f32.powf(semitones / 12.0),
+            window,
+            hop: grain / 2,
+            input: Vec::new(),
+            input_start: 0,
+            output: Vec::new(),
+            weights: Vec::new(),
+            output_start: 0,
+            next_grain: 0,
+        }
+    }
+
+    fn input_end(&self) -> usize {
+        self.input_start + self.input.len()
+    }
+
+    // Input read at a fractional absolute position (silence past the end)
+    fn read(&self, position: f32) -> f32 {
+        let index = position.floor() as usize;
+        let fraction = position - index as f32;
+        let at = |i: usize| i.checked_sub(self.input_start).and_then(|i| self.input.get(i)).copied().unwrap_or(0.0);
+        at(index) * (1.0 - fraction) + at(index + 1) * fraction
+    }
+
+    fn render_grain(&mut self) {
+        let start = self.next_grain;
+        let needed = start + self.window.len() - self.output_start;
+        if self.output.len() < needed {
+            self.output.resize(needed, 0.0);
+            self.weights.resize(needed, 0.0);
+        }
+        for i in 0..self.window.len() {
+            let sample = self.read(start as f32 + i as f32 * self.ratio);
+            let slot = start + i - self.output_start;
+            self.output[slot] += sample * self.window[i];
+            self.weights[slot] += self.window[i];
+        }
+        self.next_grain += self.hop;
+    }
+
+    // Output before the next grain is complete
+    fn drain(&mut self, until: usize) -> Vec<f32> {
+        let ready = until.saturating_sub(self.output_start).min(self.output.len());
+        let done: Vec<f32> = self.output.drain(..ready).zip(self.weights.drain(..ready))
+            .map(|(sample, weight)| if weight > 1e-3 { sample / weight } else { 0.0 })
+            .collect();
+        self.output_start += ready;
+        // Grains still to come read from their own start onwards
+        let unused = self.next_grain.saturating_sub(self.input_start).min(self.input.len());
+        self.input.drain(..unused);
+        self.input_start += unused;
+        done
+    }
+
+    fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.input.extend_from_slice(samples);
+        let reach = (self.window.len() as f32 * self.ratio).ceil() as usize + 2;
+        while self.next_grain + reach <= self.input_end() {
+            self.render_grain();
+        }
+        self.drain(self.next_grain)
+    }
+
+    fn finish(&mut self) -> Vec<f32> {
+        let end = self.input_end();
+        while self.next_grain < end {
+            self.render_grain();
+        }
+        self.drain(end)
+    }
+}
+
+/// Copy a WAV file with every channel pitch shifted (16-bit output)
+fn anonymize_voice(input: &Path, output: &Path, semitones: f32) -> Result<()> {
+    let mut reader = hound::WavReader::open(input).context(format!("Cannot read {}", input.display()))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    let samples: Box<dyn Iterator<Item = f32>> = match spec.sample_format {
+        hound::SampleFormat::Float => Box::new(reader.samples::<f32>().map_while(|s| s.ok())),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            Box::new(reader.samples::<i32>().map_while(|s| s.ok()).map(move |v| v as f32 / scale))
+        }
+    };
+    let out_spec = hound::WavSpec { bits_per_sample: 16, sample_format: hound::SampleFormat::Int, ..spec };
+    let mut writer = hound::WavWriter::create(output, out_spec).context(format!("Cannot write {}", output.display()))?;
+    let mut shifters: Vec<PitchShifter> = (0..channels).map(|_| PitchShifter::new(spec.sample_rate, semitones)).collect();
+
+    let mut samples = samples.peekable();
+    while samples.peek().is_some() {
+        let block: Vec<f32> = samples.by_ref().take(BLOCK_FRAMES * channels).collect();
+        let outputs: Vec<Vec<f32>> = shifters.iter_mut().enumerate()
+            .map(|(channel, shifter)| shifter.process(&block.iter().skip(channel).step_by(channels).copied().collect::<Vec<f32>>()))
+            .collect();
+        write_frames(&mut writer, &outputs)?;
+    }
+    let rest: Vec<Vec<f32>> = shifters.iter_mut().map(PitchShifter::finish).collect();
+    write_frames(&mut writer, &rest)?;
+    writer.finalize()?;
+    Ok(())
+}
+
+// Interleave the channels' output into the file
+fn write_frames(writer: &mut hound::WavWriter<std::io::BufWriter<std::fs::File>>, outputs: &[Vec<f32>]) -> Result<()> {
+    let frames = outputs.iter().map(|o| o.len()).min().unwrap_or(0);
+    for frame in 0..frames {
+        for output in outputs {
+            writer.write_sample((output[frame].clamp(-1.0, 1.0) * 32767.0) as i16)?;
+        }
+    }
+    Ok(())
+}
+
+fn pseudonymizer(session: &Session, names: &[String]) -> Pseudonymizer {
+    let mut pseudonyms = Pseudonymizer::new();
+    if let Some(caller) = &session.caller {
+        if let Some(name) = &caller.name {
+            pseudonyms.add(name, EntityKind::Person);
+        }
+        if let Some(company) = &caller.company {
+            pseudonyms.add(company, EntityKind::Company);
+        }
+    }
+    for company in session.company.iter().chain(session.brief.as_ref().map(|b| &b.company)) {
+        pseudonyms.add(company, EntityKind::Company);
+    }
+    for name in names {
+        pseudonyms.add(name, EntityKind::Person);
+    }
+    pseudonyms
+}
+
+fn export(session: Session, dir: &Path, options: &ShareOptions) -> Result<SharedSessionExport> {
+    std::fs::create_dir_all(dir).context(format!("Cannot create {}", dir.display()))?;
+    let audio_file = match (&session.recording, options.include_audio) {
+        (Some(recording), true) => {
+            let path = dir.join(AUDIO_FILE);
+            if options.anonymize_voice {
+                anonymize_voice(Path::new(recording), &path, options.pitch_semitones)?;
+            } else {
+                std::fs::copy(recording, &path).context(format!("Cannot copy {}", recording))?;
+            }
+            Some(path)
+        }
+        _ => None,
+    };
+
+    let voice_shift_semitones = (audio_file.is_some() && options.anonymize_voice).then(|| options.pitch_semitones);
+    let pseudonyms = pseudonymizer(&session, &options.names);
+    let shared = Session {
+        caller: None,
+        brief: None,
+        notes: Vec::new(),
+        snippets: Vec::new(),
+        imported_from: None,
+        recording: audio_file.as_ref().map(|_| AUDIO_FILE.to_string()),
+        ..session
+    };
+    let mut value = serde_json::to_value(&shared)?;
+    if options.pseudonymize {
+        pseudonyms.apply_to_value(&mut value);
+        value["id"] = serde_json::Value::String(shared.id.clone());
+        value["recording"] = serde_json::to_value(&shared.recording)?;
+    }
+    let session_file = dir.join(SESSION_FILE);
+    std::fs::write(&session_file, serde_json::to_string_pretty(&value)?).context(format!("Cannot write {}", session_file.display()))?;
+
+    Ok(SharedSessionExport {
+        session_id: shared.id,
+        session_file: session_file.display().to_string(),
+        audio_file: audio_file.map(|p| p.display().to_string()),
+        pseudonyms: if options.pseudonymize { pseudonyms.entities() } else { 0 },
+        voice_shift_semitones,
+    })
+}
+
+// ========== Tauri Commands ==========
+
+// Write a session (the current one when no id is given) to the folder `path` for a
+// training library, pseudonymized and voice anonymized unless the options say otherwise
+#[tauri::command]
+pub async fn export_shared_session(session_id: Option<String>, path: String, options: Option<ShareOptions>) -> Result<SharedSessionExport, String> {
+    let options = options.unwrap_or_default();
+    if options.anonymize_voice && !(-MAX_SHIFT_SEMITONES..=MAX_SHIFT_SEMITONES).contains(&options.pitch_semitones) {
+        return Err(format!("The pitch shift must be within ±{} semitones", MAX_SHIFT_SEMITONES));
+    }
+    if options.anonymize_voice && options.pitch_semitones.abs() < 1.0 {
+        return Err("A pitch shift under a semitone does not disguise a voice".to_string());
+    }
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    let shared = tokio::task::spawn_blocking(move || export(session, Path::new(&path), &options))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    info!("📦 Session {} shared to {} ({} pseudonyms{})", shared.session_id, shared.session_file, shared.pseudonyms,
+        shared.voice_shift_semitones.map_or(String::new(), |s| format!(", voice shifted {:+} semitones", s)));
+    Ok(shared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count()
+    }
+
+    #[test]
+    fn test_pitch_shift_scales_frequency_and_keeps_length() {
+        let rate = 16_000;
+        let tone: Vec<f32> = (0..rate).map(|i| (2.0 * PI * 200.0 * i as f32 / rate as f32).sin() * 0.5).collect();
+        let mut shifter = PitchShifter::new(rate as u32, 12.0);
+        let mut shifted: Vec<f32> = tone.chunks(1_000).flat_map(|block| shifter.process(block)).collect();
+        shifted.extend(shifter.finish());
+        assert_eq!(shifted.len(), tone.len());
+
+        // An octave up: twice the zero crossings (measured away from the edges)
+        let middle = &shifted[2_000..14_000];
+        let ratio = zero_crossings(middle) as f32 / zero_crossings(&tone[2_000..14_000]) as f32;
+        assert!((ratio - 2.0).abs() < 0.15, "crossing ratio {}", ratio);
+    }
+}
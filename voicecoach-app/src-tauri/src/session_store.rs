@@ -0,0 +1,883 @@
+// Session Store - per-call records in the app data directory
+// Each call (started by either transcription engine) gets a session file under
+// sessions/<id>.json. Every coaching prompt shown during the call is appended with
+// the statement that triggered it, the rule or prompt template that produced it and
+// when it appeared, so post-call review can replay which guidance the rep saw.
+// Reps can rate prompts during or after the call. A session can also carry the
+// prospect's company and the research brief prepared for it (prospect_brief), the
+// topic chapters the transcript was segmented into (topic_segmentation), the call's
+// metrics (call_analytics), the outcome the rep recorded for it, the session
+// template it was started from with that template's rubric (session_templates), and
+// the final transcript lines with word timings where the engine gave them (searched
+// by transcript_search). Lines are kept in memory and written with the metrics
+// snapshots rather than on every line; transcript_journal keeps them crash-safe in
+// between and is replayed into the sessions on startup. Bookmarks mark moments of the
+// call for review - added by the rep (button or voice command) and automatically where
+// a coaching prompt appeared or the prospect raised an objection; coach notes are the
+// transcribed voice notes recorded after the call (voice_notes); scratchpad notes are
+// typed during the call at the live transcript position (scratchpad). A WAV recording
+// of the call can be linked to the session for playback and its waveform (waveform);
+// one made by the app (call_recording) notes how it was stored.
+// The final summary of the call is stored once generated (rolling_summary). Sessions
+// can also be imported from recordings and transcripts made with other tools
+// (session_import); those keep the transcript file they came from. Periods the
+// prospect spent on hold or in dead air are kept for the review timeline
+// (hold_detection), and so are the intervals the rep's microphone was muted on the
+// device (hardware_mute). list_sessions shows every session with its transcript quality; a
+// poor transcript may be replaced by a re-transcription of the recording
+// (transcript_quality). Lines the engine was unsure of can keep a snippet of their
+// audio until they are corrected (audio_snippets). Final lines can also be streamed to
+// a file as they are recorded (transcript_stream). Every offset is on the session's
+// audio clock (audio_clock); the wall-clock time of the start is anchored once with it.
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+use crate::audio_clock::ClockAnchor;
+use crate::audio_snippets::AudioSnippet;
+use crate::call_analytics::CallMetrics;
+use crate::call_recording::RecordingStorage;
+use crate::control_interface::CallerInfo;
+use crate::experiments::ExperimentAssignment;
+use crate::hardware_mute::MutedInterval;
+use crate::hold_detection::QuietPeriod;
+use crate::ollama_integration::CoachingSuggestion;
+use crate::prospect_brief::ProspectBrief;
+use crate::rolling_summary::RollingSummary;
+use crate::session_templates::RubricCriterion;
+use crate::transcript_quality::TranscriptQuality;
+use crate::topic_segmentation::TopicChapter;
+use crate::transcript_journal::JournalEntry;
+
+pub use voicecoach_core::transcript::{TranscriptLine, TranscriptWord};
+
+const SESSIONS_DIR: &str = "sessions";
+// A bookmark goes to the start of the last line when it began this recently
+const BOOKMARK_LOOKBACK_MS: u64 = 15_000;
+// Automatic bookmarks are labelled with this much of the prompt or objection
+const AUTO_BOOKMARK_LABEL_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptRating {
+    Helpful,
+    Unhelpful,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CallOutcome {
+    Won,
+    Lost,
+    FollowUp,
+    NoDecision,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SessionPrompt {
+    pub id: u32,
+    pub timestamp: u64,
+    pub offset_ms: u64,              // Time since the call started
+    pub trigger: String,             // Statement that triggered the prompt
+    #[serde(default)]
+    pub context: Option<String>,     // Conversation context passed to the generator
+    pub rule: String,                // "ollama:<template>" or "fallback:<rule>"
+    pub suggestion: CoachingSuggestion,
+    #[serde(default)]
+    pub rating: Option<PromptRating>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SessionListing {
+    pub id: String,
+    pub started_at: u64,
+    pub company: Option<String>,
+    pub outcome: Option<CallOutcome>,
+    pub duration_ms: u64,            // To the start of the last line
+    pub lines: usize,
+    pub has_recording: bool,
+    pub imported: bool,
+    pub quality: Option<TranscriptQuality>, // None without transcript
+    pub retranscribe_suggested: bool, // Poor, with a recording, not re-transcribed yet
+    pub retranscribe_queued: bool,
+    pub retranscribed_with: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Bookmark {
+    pub id: u32,
+    pub offset_ms: u64,              // Time since the call started
+    pub label: Option<String>,
+    pub created_at: u64,
+    pub source: String,              // "manual", "voice", "prompt" or "objection"
+    #[serde(default)]
+    pub prompt_id: Option<u32>,      // The coaching prompt a "prompt" bookmark marks
+}
+
+/// A transcribed post-call voice note
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CoachNote {
+    pub id: u32,
+    pub recorded_at: u64,
+    pub duration_ms: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ScratchNote {
+    pub id: u32,
+    pub offset_ms: u64,              // Transcript position when it was typed
+    pub text: String,
+    pub created_at: u64,
+    #[serde(default)]
+    pub edited_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Session {
+    pub id: String,
+    pub started_at: u64,
+    #[serde(default)]
+    pub prompts: Vec<SessionPrompt>,
+    #[serde(default)]
+    pub company: Option<String>,
+    #[serde(default)]
+    pub brief: Option<ProspectBrief>,
+    #[serde(default)]
+    pub chapters: Vec<TopicChapter>,
+    #[serde(default)]
+    pub metrics: Option<CallMetrics>,
+    #[serde(default)]
+    pub outcome: Option<CallOutcome>,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub rubric: Vec<RubricCriterion>,
+    #[serde(default)]
+    pub transcript: Vec<TranscriptLine>,
+    #[serde(default)]
+    pub caller: Option<CallerInfo>,  // From the dialer that started the session
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+    #[serde(default)]
+    pub notes: Vec<CoachNote>,
+    #[serde(default)]
+    pub recording: Option<String>,   // WAV file of the call
+    #[serde(default)]
+    pub scratchpad: Vec<ScratchNote>,
+    #[serde(default)]
+    pub summary: Option<RollingSummary>,
+    #[serde(default)]
+    pub imported_from: Option<String>, // Transcript file of an imported session
+    #[serde(default)]
+    pub quiet_periods: Vec<QuietPeriod>,
+    #[serde(default)]
+    pub retranscribed_with: Option<String>, // Model whose re-transcription replaced the transcript
+    #[serde(default)]
+    pub muted_intervals: Vec<MutedInterval>,
+    #[serde(default)]
+    pub snippets: Vec<AudioSnippet>, // Audio of low-confidence lines awaiting correction
+    #[serde(default)]
+    pub clock: Option<ClockAnchor>,  // Start on the capture and wall clocks (recorded sessions)
+    #[serde(default)]
+    pub experiment: Option<ExperimentAssignment>, // Coaching variant of a running experiment
+    #[serde(default)]
+    pub recording_storage: Option<RecordingStorage>, // How the app's own recording was stored
+}
+
+impl Session {
+    fn new(started_at: u64) -> Self {
+        let id = chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string();
+        Self {
+            id,
+            started_at,
+            prompts: Vec::new(),
+            company: None,
+            brief: None,
+            chapters: Vec::new(),
+            metrics: None,
+            outcome: None,
+            template: None,
+            rubric: Vec::new(),
+            transcript: Vec::new(),
+            caller: None,
+            bookmarks: Vec::new(),
+            notes: Vec::new(),
+            recording: None,
+            scratchpad: Vec::new(),
+            summary: None,
+            imported_from: None,
+            quiet_periods: Vec::new(),
+            retranscribed_with: None,
+            muted_intervals: Vec::new(),
+            snippets: Vec::new(),
+            clock: None,
+            experiment: None,
+            recording_storage: None,
+        }
+    }
+
+    fn add_prompt(&mut self, offset_ms: u64, trigger: &str, context: Option<String>, rule: &str, suggestion: &CoachingSuggestion) -> &SessionPrompt {
+        let id = self.prompts.last().map_or(0, |p| p.id + 1);
+        self.prompts.push(SessionPrompt {
+            id,
+            timestamp: self.started_at + offset_ms,
+            offset_ms,
+            trigger: trigger.to_string(),
+            context,
+            rule: rule.to_string(),
+            suggestion: suggestion.clone(),
+            rating: None,
+        });
+        &self.prompts[self.prompts.len() - 1]
+    }
+
+    /// Start of the last line if it began within BOOKMARK_LOOKBACK_MS of `now_offset`
+    fn recent_line_start(&self, now_offset: u64) -> Option<u64> {
+        self.transcript.last()
+            .map(|line| line.offset_ms)
+            .filter(|&offset| now_offset.saturating_sub(offset) <= BOOKMARK_LOOKBACK_MS)
+    }
+
+    fn push_bookmark(&mut self, offset_ms: u64, label: Option<String>, source: &str, prompt_id: Option<u32>) -> Bookmark {
+        let bookmark = Bookmark {
+            id: self.bookmarks.last().map_or(0, |b| b.id + 1),
+            offset_ms,
+            label: label.filter(|l| !l.trim().is_empty()),
+            created_at: now_ms(),
+            source: source.to_string(),
+            prompt_id,
+        };
+        self.bookmarks.push(bookmark.clone());
+        bookmark
+    }
+}
+
+/// Label of an automatic bookmark: the start of the text it marks
+fn bookmark_label(prefix: &str, text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(AUTO_BOOKMARK_LABEL_CHARS) {
+        Some((end, _)) => format!("{}: {}…", prefix, &text[..end]),
+        None => format!("{}: {}", prefix, text),
+    }
+}
+
+// The call in progress (None until the first call of this run starts)
+static CURRENT: Lazy<Mutex<Option<Session>>> = Lazy::new(|| Mutex::new(None));
+// Clock anchor of the current session (audio_clock): capture clock and wall clock
+static CAPTURE_STARTED_MS: AtomicU64 = AtomicU64::new(0);
+static WALL_STARTED_MS: AtomicU64 = AtomicU64::new(0);
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+fn sessions_dir() -> PathBuf {
+    crate::app_paths::data_dir().join(SESSIONS_DIR)
+}
+
+fn session_path(id: &str) -> Result<PathBuf> {
+    // Ids come from the frontend; keep them to the generated format
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        anyhow::bail!("Invalid session id: {}", id);
+    }
+    Ok(sessions_dir().join(format!("{}.json", id)))
+}
+
+fn write_session(session: &Session) -> Result<()> {
+    let path = session_path(&session.id)?;
+    fs::create_dir_all(sessions_dir())?;
+    let json = serde_json::to_string_pretty(session)?;
+    // Replace the file in one step: the journal is emptied next, so a write torn by a
+    // crash would leave no intact copy of the earlier lines
+    let temp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&temp).context(format!("Failed to write session: {:?}", temp))?;
+    file.write_all(json.as_bytes())
+        .and_then(|_| file.sync_all())
+        .context(format!("Failed to write session: {:?}", temp))?;
+    fs::rename(&temp, &path).context(format!("Failed to replace session: {:?}", path))?;
+    // The file now holds every journaled line
+    crate::transcript_journal::compacted(&session.id);
+    Ok(())
+}
+
+fn read_session(id: &str) -> Result<Session> {
+    let path = session_path(id)?;
+    let contents = fs::read_to_string(&path).context(format!("No session {}", id))?;
+    serde_json::from_str(&contents).context(format!("Session file {:?} is invalid", path))
+}
+
+// Anchor the session that is starting on the capture clock and the wall clock
+fn anchored_session() -> Session {
+    let clock = ClockAnchor { capture_ms: crate::transcript_sequencer::capture_ms(), wall_ms: now_ms() };
+    CAPTURE_STARTED_MS.store(clock.capture_ms, Ordering::Relaxed);
+    WALL_STARTED_MS.store(clock.wall_ms, Ordering::Relaxed);
+    Session { clock: Some(clock), ..Session::new(clock.wall_ms) }
+}
+
+/// Start a new session for the call that is starting
+pub fn begin_session() {
+    let mut session = anchored_session();
+    if let Some(template) = crate::session_templates::active_template() {
+        session.template = Some(template.id);
+        session.rubric = template.rubric;
+    }
+    session.experiment = crate::experiments::assign();
+    let id = session.id.clone();
+    info!("🗂️ Session {} started{}", id, session.template.as_ref().map_or(String::new(), |t| format!(" from template {}", t)));
+    let finished = CURRENT.lock().unwrap().replace(session).map(|s| s.id);
+    crate::prospect_brief::session_started(&id);
+    if let Some(finished) = finished {
+        crate::transcript_stream::session_ended(&finished);
+        std::thread::spawn(move || crate::transcript_quality::call_finished(&finished));
+        crate::peer_benchmark::call_finished();
+    }
+}
+
+/// Id of the session in progress
+pub fn current_session_id() -> Option<String> {
+    CURRENT.lock().unwrap().as_ref().map(|s| s.id.clone())
+}
+
+/// Read the session in progress without copying it
+pub fn with_current<T>(read: impl FnOnce(&Session) -> T) -> Option<T> {
+    CURRENT.lock().unwrap().as_ref().map(read)
+}
+
+// Apply a change to a session (in memory if it is the current one) and persist it
+fn modify_session<T>(session_id: &str, change: impl FnOnce(&mut Session) -> T) -> Result<T> {
+    let mut current = CURRENT.lock().unwrap();
+    match current.as_mut().filter(|s| s.id == session_id) {
+        Some(session) => {
+            let result = change(session);
+            write_session(session)?;
+            Ok(result)
+        }
+        None => {
+            let mut session = read_session(session_id)?;
+            let result = change(&mut session);
+            write_session(&session)?;
+            Ok(result)
+        }
+    }
+}
+
+/// Record the prospect's company for a session
+pub fn set_company(session_id: &str, company: &str) {
+    if let Err(e) = modify_session(session_id, |s| s.company = Some(company.to_string())) {
+        warn!("⚠️ Failed to record company for session {}: {}", session_id, e);
+    }
+}
+
+/// Record the caller a dialer started a session for
+pub fn set_caller(session_id: &str, caller: &CallerInfo) {
+    if let Err(e) = modify_session(session_id, |s| s.caller = Some(caller.clone())) {
+        warn!("⚠️ Failed to record caller for session {}: {}", session_id, e);
+    }
+}
+
+/// Store the prospect brief prepared for a session
+pub fn attach_brief(session_id: &str, brief: ProspectBrief) -> Result<()> {
+    modify_session(session_id, |s| s.brief = Some(brief))
+}
+
+/// Brief of a session (the current one when no id is given)
+pub fn session_brief(session_id: Option<String>) -> Result<Option<ProspectBrief>> {
+    Ok(load_session(session_id)?.brief)
+}
+
+/// Replace the topic chapters of a session
+pub fn attach_chapters(session_id: &str, chapters: Vec<TopicChapter>) -> Result<()> {
+    modify_session(session_id, |s| s.chapters = chapters)
+}
+
+/// Topic chapters of a session (the current one when no id is given)
+pub fn session_chapters(session_id: Option<String>) -> Result<Vec<TopicChapter>> {
+    Ok(load_session(session_id)?.chapters)
+}
+
+/// Store the final summary of a session
+pub fn attach_summary(session_id: &str, summary: RollingSummary) -> Result<()> {
+    modify_session(session_id, |s| s.summary = Some(summary))
+}
+
+/// Store the metrics of the call in progress with its session
+pub fn attach_metrics(metrics: CallMetrics) -> Result<()> {
+    match current_session_id() {
+        Some(id) => modify_session(&id, |s| s.metrics = Some(metrics)),
+        None => Ok(()),  // Analytics of a call that never started a session
+    }
+}
+
+/// Add a final transcript line to the session in progress; `capture_ms` and the word
+/// times are on the capture clock. Returns the line's index in the transcript.
+pub fn record_line(capture_ms: u64, is_user: bool, text: &str, words: Vec<TranscriptWord>) -> Option<usize> {
+    let started = CAPTURE_STARTED_MS.load(Ordering::Relaxed);
+    let mut current = CURRENT.lock().unwrap();
+    let session = current.as_mut()?;
+    let line = TranscriptLine {
+        offset_ms: capture_ms.saturating_sub(started),
+        is_user,
+        text: text.to_string(),
+        words: words.into_iter()
+            .map(|w| TranscriptWord { start_ms: w.start_ms.saturating_sub(started), end_ms: w.end_ms.saturating_sub(started), word: w.word, confidence: w.confidence })
+            .collect(),
+    };
+    let entry = JournalEntry { session_id: session.id.clone(), started_at: session.started_at, index: session.transcript.len(), line: line.clone() };
+    if let Err(e) = crate::transcript_journal::append(&entry) {
+        warn!("⚠️ Failed to journal transcript line: {}", e);
+    }
+    crate::transcript_stream::append(&session.id, session.transcript.len(), &line);
+    session.transcript.push(line);
+    Some(session.transcript.len() - 1)
+}
+
+/// Bookmark the current moment of the session in progress - the start of the last
+/// line if it began within BOOKMARK_LOOKBACK_MS ("bookmark that")
+pub fn add_bookmark(label: Option<String>, source: &str) -> Result<Bookmark> {
+    let now_offset = current_offset_ms();
+    let mut current = CURRENT.lock().unwrap();
+    let session = current.as_mut().context("No session in progress")?;
+    let offset_ms = session.recent_line_start(now_offset).unwrap_or(now_offset);
+    let bookmark = session.push_bookmark(offset_ms, label, source, None);
+    write_session(session)?;
+    info!("🔖 Bookmark {} at {}ms ({})", bookmark.id, offset_ms, source);
+    Ok(bookmark)
+}
+
+/// Bookmark the start of a line of the session in progress where the prospect objected
+pub fn bookmark_objection(line: usize) {
+    let mut current = CURRENT.lock().unwrap();
+    let session = match current.as_mut() {
+        Some(session) => session,
+        None => return,
+    };
+    let (offset_ms, label) = match session.transcript.get(line) {
+        Some(line) => (line.offset_ms, bookmark_label("Objection", &line.text)),
+        None => return,
+    };
+    session.push_bookmark(offset_ms, Some(label), "objection", None);
+    if let Err(e) = write_session(session) {
+        warn!("⚠️ Failed to persist objection bookmark: {}", e);
+    }
+}
+
+/// Position of the call in progress on its transcript clock
+fn current_offset_ms() -> u64 {
+    offset_of(crate::transcript_sequencer::capture_ms())
+}
+
+/// Position on the transcript clock of the call in progress of a capture time
+pub fn offset_of(capture_ms: u64) -> u64 {
+    capture_ms.saturating_sub(CAPTURE_STARTED_MS.load(Ordering::Relaxed))
+}
+
+/// Wall-clock time of a capture time, from the session's anchor (from the current
+/// time before the first session)
+pub fn wall_ms(capture_ms: u64) -> u64 {
+    match WALL_STARTED_MS.load(Ordering::Relaxed) {
+        0 => now_ms().saturating_sub(crate::transcript_sequencer::capture_ms().saturating_sub(capture_ms)),
+        wall_started => wall_started + offset_of(capture_ms),
+    }
+}
+
+/// Record a finished hold or dead-air period of the call in progress
+pub fn add_quiet_period(period: QuietPeriod) -> Result<()> {
+    let mut current = CURRENT.lock().unwrap();
+    let session = current.as_mut().context("No session in progress")?;
+    session.quiet_periods.push(period);
+    write_session(session)
+}
+
+/// Record a finished interval of the rep's microphone muted on the device
+pub fn add_muted_interval(interval: MutedInterval) -> Result<()> {
+    let mut current = CURRENT.lock().unwrap();
+    let session = current.as_mut().context("No session in progress")?;
+    session.muted_intervals.push(interval);
+    write_session(session)
+}
+
+/// Attach the retained audio of a line
+pub fn attach_snippet(session_id: &str, snippet: AudioSnippet) -> Result<()> {
+    modify_session(session_id, |s| {
+        s.snippets.retain(|kept| kept.line != snippet.line);
+        s.snippets.push(snippet);
+    })
+}
+
+/// Link the app's recording of a session (`path` None: nothing was recorded) with how it
+/// was stored
+pub fn attach_recording(session_id: &str, path: Option<String>, storage: RecordingStorage) -> Result<()> {
+    modify_session(session_id, |s| {
+        if path.is_some() {
+            s.recording = path;
+        }
+        s.recording_storage = Some(storage);
+    })
+}
+
+/// Remove the retained audio of a line and delete its file
+pub fn detach_snippet(session_id: &str, line: usize) -> Result<()> {
+    let detached: Vec<AudioSnippet> = modify_session(session_id, |s| {
+        let (detached, kept) = std::mem::take(&mut s.snippets).into_iter().partition(|snippet| snippet.line == line);
+        s.snippets = kept;
+        detached
+    })?;
+    crate::audio_snippets::discard(&detached);
+    Ok(())
+}
+
+/// Replace the text of a line after the rep listened to it; its word timings and
+/// retained audio go with the old text
+pub fn correct_line(session_id: &str, index: usize, text: &str) -> Result<TranscriptLine> {
+    let (line, detached) = modify_session(session_id, |s| {
+        let line = s.transcript.get_mut(index).context(format!("No line {} in session {}", index, s.id))?;
+        line.text = text.to_string();
+        line.words.clear();
+        let line = line.clone();
+        let (detached, kept) = std::mem::take(&mut s.snippets).into_iter().partition(|snippet: &AudioSnippet| snippet.line == index);
+        s.snippets = kept;
+        Ok::<_, anyhow::Error>((line, detached))
+    })??;
+    crate::audio_snippets::discard(&detached);
+    Ok(line)
+}
+
+/// Add a note typed during the call in progress at the current transcript position
+pub fn add_scratch_note(text: &str) -> Result<ScratchNote> {
+    let offset_ms = current_offset_ms();
+    let mut current = CURRENT.lock().unwrap();
+    let session = current.as_mut().context("No session in progress")?;
+    let note = ScratchNote {
+        id: session.scratchpad.last().map_or(0, |n| n.id + 1),
+        offset_ms,
+        text: text.to_string(),
+        created_at: now_ms(),
+        edited_at: None,
+    };
+    session.scratchpad.push(note.clone());
+    write_session(session)?;
+    Ok(note)
+}
+
+/// Change the text of a scratchpad note (it keeps its position), or remove it (None)
+pub fn edit_scratch_note(session_id: &str, note_id: u32, text: Option<&str>) -> Result<Option<ScratchNote>> {
+    modify_session(session_id, |s| {
+        let index = s.scratchpad.iter().position(|n| n.id == note_id)
+            .context(format!("No note {} in session {}", note_id, session_id))?;
+        match text {
+            Some(text) => {
+                let note = &mut s.scratchpad[index];
+                note.text = text.to_string();
+                note.edited_at = Some(now_ms());
+                Ok(Some(note.clone()))
+            }
+            None => {
+                s.scratchpad.remove(index);
+                Ok(None)
+            }
+        }
+    })?
+}
+
+/// Attach a transcribed voice note to a session
+pub fn add_note(session_id: &str, recorded_at: u64, duration_ms: u64, text: &str) -> Result<CoachNote> {
+    modify_session(session_id, |s| {
+        let note = CoachNote {
+            id: s.notes.last().map_or(0, |n| n.id + 1),
+            recorded_at,
+            duration_ms,
+            text: text.to_string(),
+        };
+        s.notes.push(note.clone());
+        note
+    })
+}
+
+/// Coach notes of a session (the current one when no id is given)
+pub fn session_notes(session_id: Option<String>) -> Result<Vec<CoachNote>> {
+    Ok(load_session(session_id)?.notes)
+}
+
+/// Whether a session exists (in progress or stored)
+pub fn session_exists(session_id: &str) -> bool {
+    current_session_id().as_deref() == Some(session_id) || session_path(session_id).map_or(false, |path| path.exists())
+}
+
+/// Write the session in progress (transcript journal compaction)
+pub fn write_current() -> Result<()> {
+    match CURRENT.lock().unwrap().as_ref() {
+        Some(session) => write_session(session),
+        None => Ok(()),
+    }
+}
+
+/// Replay transcript journals left by a crash or kill into their sessions; returns
+/// the ids of the sessions that got lines back
+pub fn recover_journals() -> Vec<String> {
+    let mut recovered = Vec::new();
+    for (id, entries) in crate::transcript_journal::left_behind() {
+        let mut session = match read_session(&id) {
+            Ok(session) => session,
+            // Crashed before the session was first written
+            Err(_) if !session_path(&id).map_or(false, |path| path.exists()) => {
+                let mut session = Session::new(entries[0].started_at);
+                session.id = id.clone();
+                session
+            }
+            Err(e) => {
+                warn!("⚠️ Keeping the transcript journal of session {}: {}", id, e);
+                continue;
+            }
+        };
+        let missing = crate::transcript_journal::unrecovered(session.transcript.len(), entries);
+        let count = missing.len();
+        session.transcript.extend(missing.into_iter().map(|e| e.line));
+        match write_session(&session) {
+            Ok(()) if count > 0 => {
+                info!("📓 Recovered {} transcript lines of session {} from its journal", count, id);
+                recovered.push(id);
+            }
+            Ok(()) => {}
+            Err(e) => warn!("⚠️ Failed to recover session {} from its journal: {}", id, e),
+        }
+    }
+    recovered
+}
+
+/// Store a session for a call recorded outside VoiceCoach (its id comes from
+/// `started_at`, moved a second on while taken)
+pub fn import_session(started_at: u64, transcript: Vec<TranscriptLine>, recording: Option<String>, imported_from: &str, metrics: CallMetrics) -> Result<Session> {
+    let mut start = chrono::DateTime::from_timestamp_millis(started_at as i64)
+        .context(format!("Invalid start time {}", started_at))?;
+    let mut id = start.format("%Y%m%d-%H%M%S").to_string();
+    while session_path(&id)?.exists() || current_session_id().as_deref() == Some(id.as_str()) {
+        start += chrono::Duration::seconds(1);
+        id = start.format("%Y%m%d-%H%M%S").to_string();
+    }
+    let mut session = Session::new(started_at);
+    session.id = id;
+    session.transcript = transcript;
+    session.recording = recording;
+    session.metrics = Some(metrics);
+    session.imported_from = Some(imported_from.to_string());
+    write_session(&session)?;
+    info!("📥 Session {} imported from {}", session.id, imported_from);
+    Ok(session)
+}
+
+/// Replace a session's transcript with a re-transcription by `model`
+pub fn replace_transcript(session_id: &str, transcript: Vec<TranscriptLine>, model: &str) -> Result<()> {
+    // Retained audio belongs to the old lines
+    let detached = modify_session(session_id, |s| {
+        s.transcript = transcript;
+        s.retranscribed_with = Some(model.to_string());
+        std::mem::take(&mut s.snippets)
+    })?;
+    crate::audio_snippets::discard(&detached);
+    Ok(())
+}
+
+/// Transcript of a session (the current one when no id is given)
+pub fn session_transcript(session_id: Option<String>) -> Result<Vec<TranscriptLine>> {
+    Ok(load_session(session_id)?.transcript)
+}
+
+/// Every stored session, oldest first (the current one as it is in memory)
+pub fn all_sessions() -> Vec<Session> {
+    let current = CURRENT.lock().unwrap().clone();
+    let current_id = current.as_ref().map(|c| c.id.clone());
+    let mut sessions: Vec<Session> = fs::read_dir(sessions_dir())
+        .map(|entries| entries.flatten().collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".json")?.to_string();
+            if current_id.as_deref() == Some(id.as_str()) {
+                return None;
+            }
+            read_session(&id)
+                .map_err(|e| warn!("⚠️ Skipping session {}: {}", id, e))
+                .ok()
+        })
+        .chain(current)
+        .collect();
+    sessions.sort_by_key(|s| s.started_at);
+    sessions
+}
+
+/// Sessions in another folder of session files (e.g. one a teammate shared), oldest first
+pub fn sessions_in(dir: &Path) -> Result<Vec<Session>> {
+    let entries = fs::read_dir(dir).context(format!("Failed to read sessions folder {:?}", dir))?;
+    let mut sessions: Vec<Session> = entries.flatten()
+        .filter(|entry| entry.path().extension().map_or(false, |e| e == "json"))
+        .filter_map(|entry| {
+            let contents = fs::read_to_string(entry.path()).ok()?;
+            serde_json::from_str(&contents)
+                .map_err(|e| warn!("⚠️ Skipping {:?}: {}", entry.path(), e))
+                .ok()
+        })
+        .collect();
+    sessions.sort_by_key(|s| s.started_at);
+    Ok(sessions)
+}
+
+/// Start time of the session in progress
+pub fn current_started_at() -> Option<u64> {
+    CURRENT.lock().unwrap().as_ref().map(|s| s.started_at)
+}
+
+/// Persist a coaching prompt that was shown to the rep, bookmarked at the line that
+/// triggered it
+pub fn record_prompt(trigger: &str, context: Option<String>, rule: &str, suggestion: &CoachingSuggestion) {
+    let mut current = CURRENT.lock().unwrap();
+    // Prompts generated outside a call (e.g. from the practice screen) still get a session
+    let session = current.get_or_insert_with(anchored_session);
+    let now_offset = current_offset_ms();
+    let prompt_id = session.add_prompt(now_offset, trigger, context, rule, suggestion).id;
+    let offset_ms = session.recent_line_start(now_offset).unwrap_or(now_offset);
+    session.push_bookmark(offset_ms, Some(bookmark_label("Coaching", &suggestion.suggestion)), "prompt", Some(prompt_id));
+    if let Err(e) = write_session(session) {
+        warn!("⚠️ Failed to persist coaching prompt: {}", e);
+    }
+}
+
+/// A session (the current one when no id is given)
+pub fn load_session(session_id: Option<String>) -> Result<Session> {
+    let current = CURRENT.lock().unwrap();
+    match (session_id, current.as_ref()) {
+        (Some(id), Some(session)) if id == session.id => Ok(session.clone()),
+        (Some(id), _) => read_session(&id),
+        (None, Some(session)) => Ok(session.clone()),
+        (None, None) => anyhow::bail!("No session in progress"),
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Prompts shown during a session (the current one when no id is given)
+#[tauri::command]
+pub fn get_session_prompts(session_id: Option<String>) -> Result<Vec<SessionPrompt>, String> {
+    load_session(session_id).map(|s| s.prompts).map_err(|e| e.to_string())
+}
+
+// Rate a prompt (None clears the rating)
+#[tauri::command]
+pub fn rate_session_prompt(session_id: Option<String>, prompt_id: u32, rating: Option<PromptRating>) -> Result<SessionPrompt, String> {
+    let mut current = CURRENT.lock().unwrap();
+    let in_progress = current.as_mut().filter(|s| session_id.as_ref().map_or(true, |id| *id == s.id));
+    let mut stored;
+    let session = match in_progress {
+        Some(session) => session,
+        None => {
+            let id = session_id.ok_or("No session in progress")?;
+            stored = read_session(&id).map_err(|e| e.to_string())?;
+            &mut stored
+        }
+    };
+    let prompt = session.prompts.iter_mut()
+        .find(|p| p.id == prompt_id)
+        .ok_or_else(|| format!("No prompt {} in session {}", prompt_id, session.id))?;
+    prompt.rating = rating;
+    let prompt = prompt.clone();
+    write_session(session).map_err(|e| e.to_string())?;
+    Ok(prompt)
+}
+
+// Record how the call ended (None clears it)
+#[tauri::command]
+pub fn set_session_outcome(session_id: Option<String>, outcome: Option<CallOutcome>) -> Result<(), String> {
+    let id = session_id.or_else(current_session_id).ok_or("No session in progress")?;
+    modify_session(&id, |s| s.outcome = outcome).map_err(|e| e.to_string())
+}
+
+// Bookmark the current moment of the call in progress
+#[tauri::command]
+pub fn add_session_bookmark(label: Option<String>) -> Result<Bookmark, String> {
+    add_bookmark(label, "manual").map_err(|e| e.to_string())
+}
+
+// Bookmarks of a session (the current one when no id is given)
+#[tauri::command]
+pub fn get_session_bookmarks(session_id: Option<String>) -> Result<Vec<Bookmark>, String> {
+    load_session(session_id).map(|s| s.bookmarks).map_err(|e| e.to_string())
+}
+
+// Every stored session, newest first, with its transcript quality
+#[tauri::command]
+pub fn list_sessions() -> Result<Vec<SessionListing>, String> {
+    let poor_below = crate::preferences::load().transcript_quality.poor_below;
+    Ok(all_sessions().into_iter().rev().map(|s| {
+        let quality = crate::transcript_quality::assess(&s.transcript, poor_below);
+        SessionListing {
+            retranscribe_suggested: crate::transcript_quality::retranscribe_suggested(&s, quality.as_ref()),
+            retranscribe_queued: crate::transcript_quality::is_queued(&s.id),
+            duration_ms: s.transcript.last().map_or(0, |l| l.offset_ms),
+            lines: s.transcript.len(),
+            has_recording: s.recording.is_some(),
+            imported: s.imported_from.is_some(),
+            quality,
+            id: s.id,
+            started_at: s.started_at,
+            company: s.company,
+            outcome: s.outcome,
+            retranscribed_with: s.retranscribed_with,
+        }
+    }).collect())
+}
+
+// Link the call's WAV recording to a session (None unlinks it)
+#[tauri::command]
+pub fn set_session_recording(session_id: Option<String>, path: Option<String>) -> Result<(), String> {
+    let id = session_id.or_else(current_session_id).ok_or("No session in progress")?;
+    if let Some(path) = &path {
+        hound::WavReader::open(path).map_err(|e| format!("{} is not a readable WAV file: {}", path, e))?;
+    }
+    modify_session(&id, |s| s.recording = path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompts_get_sequential_ids_and_call_offsets() {
+        let suggestion = CoachingSuggestion {
+            suggestion: "Ask about budget".to_string(),
+            confidence: 0.5,
+            reasoning: None,
+            action_items: vec![],
+            citations: vec![],
+        };
+        let mut session = Session::new(1_000);
+        session.add_prompt(3_000, "what does it cost", None, "fallback:price", &suggestion);
+        let second = session.add_prompt(8_500, "sounds good", Some("discovery".to_string()), "ollama:coaching", &suggestion);
+        assert_eq!((second.id, second.offset_ms, second.timestamp), (1, 8_500, 9_500));
+        assert!(session_path("../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_automatic_bookmarks_mark_the_triggering_line() {
+        let mut session = Session::new(1_000);
+        session.transcript.push(TranscriptLine { offset_ms: 42_000, is_user: false, text: "That's way over our budget".to_string(), words: Vec::new() });
+        assert_eq!(session.recent_line_start(50_000), Some(42_000));
+        assert_eq!(session.recent_line_start(90_000), None);
+
+        let label = bookmark_label("Coaching", "Acknowledge the concern, then ask what budget they had in mind for this quarter");
+        assert!(label.starts_with("Coaching: Acknowledge") && label.ends_with('…'));
+        assert_eq!(label.chars().count(), "Coaching: ".len() + AUTO_BOOKMARK_LABEL_CHARS + 1);
+        assert_eq!(bookmark_label("Objection", " Too expensive "), "Objection: Too expensive");
+
+        session.push_bookmark(42_000, Some("Objection: That's way over our budget".to_string()), "objection", None);
+        let prompt = session.push_bookmark(42_000, Some(label), "prompt", Some(0));
+        assert_eq!((prompt.id, prompt.prompt_id), (1, Some(0)));
+    }
+}
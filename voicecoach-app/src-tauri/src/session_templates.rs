@@ -0,0 +1,288 @@
+// Session Templates - per call type setup (discovery call, renewal, demo ...)
+// A template bundles what differs between recurring call types: the checklist, a
+// coaching focus added to every coaching prompt, the knowledge documents coaching may
+// draw on (its workspace; empty = the whole knowledge base) and the rubric the call
+// is scored against. Starting a session from a template makes it the active one:
+// the checklist is reset to the template's, coaching picks up its focus and
+// workspace, and every session started while it is active (by either transcription
+// engine) records the template and its rubric. Templates live in preferences; the
+// built-in ones are used until the first template is saved.
+
+use serde::{Deserialize, Serialize};
+use log::info;
+
+use crate::call_analytics::{CallMetrics, ChecklistItemDef};
+use crate::preferences::Preferences;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RubricMetric {
+    TalkRatio,           // Rep share of the talk (0-1)
+    RepWpm,
+    ProspectWpm,
+    ChecklistProgress,   // Completed share of the checklist (0-1)
+    ProspectQuestions,
+    UnansweredQuestions,
+    Objections,
+}
+
+// A criterion is met when the metric lies within [min, max]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RubricCriterion {
+    pub label: String,
+    pub metric: RubricMetric,
+    #[serde(default)]
+    pub min: Option<f32>,
+    #[serde(default)]
+    pub max: Option<f32>,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+fn default_weight() -> f32 { 1.0 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SessionTemplate {
+    pub id: String,
+    pub name: String,
+    /// Checklist for the call; empty = the regular checklist
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItemDef>,
+    /// Guidance added to every coaching prompt
+    #[serde(default)]
+    pub coaching_focus: Option<String>,
+    /// Knowledge documents (filenames) coaching draws on; empty = all of them
+    #[serde(default)]
+    pub knowledge_documents: Vec<String>,
+    #[serde(default)]
+    pub rubric: Vec<RubricCriterion>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct SessionTemplateSettings {
+    /// Saved templates; empty = the built-in ones
+    #[serde(default)]
+    pub templates: Vec<SessionTemplate>,
+    #[serde(default)]
+    pub active: Option<String>,
+}
+
+impl RubricMetric {
+    fn value(self, metrics: &CallMetrics) -> Option<f32> {
+        match self {
+            RubricMetric::TalkRatio => Some(metrics.talk_ratio.rep_share),
+            RubricMetric::RepWpm => metrics.rep_wpm,
+            RubricMetric::ProspectWpm => metrics.prospect_wpm,
+            RubricMetric::ChecklistProgress => (metrics.checklist_total > 0)
+                .then(|| metrics.checklist_completed as f32 / metrics.checklist_total as f32),
+            RubricMetric::ProspectQuestions => Some(metrics.prospect_questions as f32),
+            RubricMetric::UnansweredQuestions => Some(metrics.unanswered_questions as f32),
+            RubricMetric::Objections => Some(metrics.objections as f32),
+        }
+    }
+}
+
+/// Weighted share of rubric criteria the call met (0-1); criteria whose metric the
+/// call doesn't have (e.g. no speaking rate) are left out. None when nothing applies.
+pub fn rubric_score(rubric: &[RubricCriterion], metrics: &CallMetrics) -> Option<f32> {
+    let (met, total) = rubric.iter()
+        .filter_map(|c| c.metric.value(metrics).map(|value| (c, value)))
+        .fold((0.0, 0.0), |(met, total), (c, value)| {
+            let within = c.min.map_or(true, |min| value >= min) && c.max.map_or(true, |max| value <= max);
+            (met + if within { c.weight } else { 0.0 }, total + c.weight)
+        });
+    (total > 0.0).then(|| met / total)
+}
+
+fn criterion(label: &str, metric: RubricMetric, min: Option<f32>, max: Option<f32>, weight: f32) -> RubricCriterion {
+    RubricCriterion { label: label.to_string(), metric, min, max, weight }
+}
+
+pub fn default_templates() -> Vec<SessionTemplate> {
+    let next_meeting = crate::call_analytics::default_checklist().into_iter()
+        .find(|i| i.id == "next_meeting_booked");
+    vec![
+        SessionTemplate {
+            id: "discovery".to_string(),
+            name: "Discovery call".to_string(),
+            checklist: crate::call_analytics::default_checklist(),
+            coaching_focus: Some("Discovery call: keep the prospect talking. Favor open questions about their current process, pain and its impact; hold back on presenting the product.".to_string()),
+            knowledge_documents: Vec::new(),
+            rubric: vec![
+                criterion("Prospect talks more", RubricMetric::TalkRatio, None, Some(0.45), 2.0),
+                criterion("Checklist mostly covered", RubricMetric::ChecklistProgress, Some(0.66), None, 2.0),
+                criterion("Every question answered", RubricMetric::UnansweredQuestions, None, Some(0.0), 1.0),
+                criterion("Unhurried pace", RubricMetric::RepWpm, Some(110.0), Some(170.0), 1.0),
+            ],
+        },
+        SessionTemplate {
+            id: "renewal".to_string(),
+            name: "Renewal".to_string(),
+            checklist: vec![
+                crate::call_analytics::item("usage_reviewed", "Usage reviewed", &[
+                    "usage", "adoption", "active users", "how many people use", "logins",
+                ]),
+                crate::call_analytics::item("renewal_terms", "Renewal terms discussed", &[
+                    "renewal", "renew", "contract term", "term length", "auto renew",
+                ]),
+                crate::call_analytics::item("expansion_explored", "Expansion explored", &[
+                    "additional seats", "more seats", "other teams", "expand", "upgrade",
+                ]),
+            ],
+            coaching_focus: Some("Renewal: confirm the value delivered so far and surface adoption problems early. Look for expansion; don't offer a discount before the customer's concerns are on the table.".to_string()),
+            knowledge_documents: Vec::new(),
+            rubric: vec![
+                criterion("Whole checklist covered", RubricMetric::ChecklistProgress, Some(1.0), None, 2.0),
+                criterion("Balanced conversation", RubricMetric::TalkRatio, None, Some(0.5), 1.0),
+                criterion("Every question answered", RubricMetric::UnansweredQuestions, None, Some(0.0), 1.0),
+            ],
+        },
+        SessionTemplate {
+            id: "demo".to_string(),
+            name: "Demo".to_string(),
+            checklist: vec![
+                crate::call_analytics::item("agenda_confirmed", "Agenda confirmed", &[
+                    "agenda", "what we'll cover", "plan for today", "does that work for you",
+                ]),
+                crate::call_analytics::item("use_case_confirmed", "Use case confirmed", &[
+                    "use case", "your workflow", "how you do this today", "for your team",
+                ]),
+            ].into_iter().chain(next_meeting).collect(),
+            coaching_focus: Some("Demo: tie every feature shown to a pain the prospect named, and pause for questions after each section.".to_string()),
+            knowledge_documents: Vec::new(),
+            rubric: vec![
+                criterion("Room for questions", RubricMetric::TalkRatio, None, Some(0.65), 1.0),
+                criterion("Every question answered", RubricMetric::UnansweredQuestions, None, Some(0.0), 2.0),
+                criterion("Checklist mostly covered", RubricMetric::ChecklistProgress, Some(0.66), None, 1.0),
+            ],
+        },
+    ]
+}
+
+fn templates_in(preferences: &Preferences) -> Vec<SessionTemplate> {
+    let saved = &preferences.session_templates.templates;
+    if saved.is_empty() { default_templates() } else { saved.clone() }
+}
+
+/// The active template, from already loaded preferences
+pub fn active_in(preferences: &Preferences) -> Option<SessionTemplate> {
+    let id = preferences.session_templates.active.as_ref()?;
+    templates_in(preferences).into_iter().find(|t| &t.id == id)
+}
+
+pub fn active_template() -> Option<SessionTemplate> {
+    active_in(&crate::preferences::load())
+}
+
+/// Coaching focus of the active template
+pub fn coaching_focus() -> Option<String> {
+    active_template().and_then(|t| t.coaching_focus)
+}
+
+/// Keep the knowledge documents in the active template's workspace
+pub fn filter_knowledge(documents: Vec<crate::ollama_integration::KnowledgeDocument>) -> Vec<crate::ollama_integration::KnowledgeDocument> {
+    match active_template().filter(|t| !t.knowledge_documents.is_empty()) {
+        Some(template) => documents.into_iter()
+            .filter(|d| template.knowledge_documents.contains(&d.filename))
+            .collect(),
+        None => documents,
+    }
+}
+
+fn validate(template: &SessionTemplate) -> Result<(), String> {
+    if template.id.is_empty() || !template.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("Template ids use letters, digits, '-' and '_'".to_string());
+    }
+    if template.name.trim().is_empty() {
+        return Err("Every template needs a name".to_string());
+    }
+    if template.checklist.iter().any(|i| i.id.trim().is_empty() || i.phrases.is_empty()) {
+        return Err("Every checklist item needs an id and at least one phrase".to_string());
+    }
+    if let Some(c) = template.rubric.iter().find(|c| c.weight <= 0.0 || c.min.zip(c.max).map_or(false, |(min, max)| min > max)) {
+        return Err(format!("Rubric criterion '{}' needs a positive weight and min <= max", c.label));
+    }
+    Ok(())
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn list_session_templates() -> Result<Vec<SessionTemplate>, String> {
+    Ok(templates_in(&crate::preferences::load()))
+}
+
+#[tauri::command]
+pub fn get_active_session_template() -> Result<Option<SessionTemplate>, String> {
+    Ok(active_template())
+}
+
+// Create a template, or replace the one with the same id
+#[tauri::command]
+pub fn save_session_template(template: SessionTemplate) -> Result<Vec<SessionTemplate>, String> {
+    validate(&template)?;
+    let preferences = crate::preferences::update(|p| {
+        let mut templates = templates_in(p);
+        match templates.iter_mut().find(|t| t.id == template.id) {
+            Some(existing) => *existing = template.clone(),
+            None => templates.push(template.clone()),
+        }
+        p.session_templates.templates = templates;
+    }).map_err(|e| e.to_string())?;
+    info!("📋 Session template '{}' saved", template.id);
+    Ok(templates_in(&preferences))
+}
+
+#[tauri::command]
+pub fn delete_session_template(id: String) -> Result<Vec<SessionTemplate>, String> {
+    let preferences = crate::preferences::load();
+    if !templates_in(&preferences).iter().any(|t| t.id == id) {
+        return Err(format!("Unknown session template: {}", id));
+    }
+    let preferences = crate::preferences::update(|p| {
+        let templates: Vec<SessionTemplate> = templates_in(p).into_iter().filter(|t| t.id != id).collect();
+        p.session_templates.templates = templates;
+        if p.session_templates.active.as_deref() == Some(id.as_str()) {
+            p.session_templates.active = None;
+        }
+    }).map_err(|e| e.to_string())?;
+    crate::transcript_plugins::reload();
+    info!("🗑️ Session template '{}' deleted", id);
+    Ok(templates_in(&preferences))
+}
+
+// Make a template active (None = no template) and start a session configured by it.
+// Sessions the transcription engines start keep using it until another is chosen.
+#[tauri::command]
+pub fn start_session_from_template(template_id: Option<String>) -> Result<Option<SessionTemplate>, String> {
+    if let Some(id) = &template_id {
+        if !templates_in(&crate::preferences::load()).iter().any(|t| &t.id == id) {
+            return Err(format!("Unknown session template: {}", id));
+        }
+    }
+    let preferences = crate::preferences::update(|p| p.session_templates.active = template_id.clone())
+        .map_err(|e| e.to_string())?;
+    crate::transcript_plugins::reload();
+    crate::call_analytics::begin_call();
+    crate::session_store::begin_session();
+    let template = active_in(&preferences);
+    info!("📋 Session started from template {}", template.as_ref().map_or("(none)", |t| t.name.as_str()));
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rubric_score_weights_met_criteria_and_skips_missing_metrics() {
+        let discovery = default_templates().into_iter().find(|t| t.id == "discovery").unwrap();
+        assert!(validate(&discovery).is_ok());
+
+        let mut metrics = CallMetrics { checklist_completed: 2, checklist_total: 3, unanswered_questions: 1, ..Default::default() };
+        metrics.talk_ratio.rep_share = 0.4;
+        // Talk ratio (2) and checklist (2) met, a question left unanswered (1), no speaking rate
+        assert_eq!(rubric_score(&discovery.rubric, &metrics), Some(0.8));
+        assert_eq!(rubric_score(&[], &metrics), None);
+    }
+}
@@ -0,0 +1,297 @@
+// Settings Profile - reproducible settings for fleet deployment
+// IT rolling VoiceCoach out to a sales team configures one install and exports its
+// settings as a profile: the preferences (coaching profile, competitor watchlist,
+// checklist, session templates and rubrics, stage vocabulary, privacy...) and the
+// engine configuration (vosk-config), signed with the device's export key (see
+// export_security). Credentials never leave the machine: the control interface token
+// and enrichment request headers are stripped, and per-machine state (mic calibration,
+// input levels, window layouts, the Whisper device, and the rep's own peer benchmark
+// opt-in and contributor id) is neither exported nor replaced on import. Importing
+// checks the signature and wants the signer in the trusted list (trust_export_signer)
+// unless allow_untrusted is set. On first run a profile placed
+// at the machine-wide path (admin-writable, so its location is its trust) is applied
+// before anything reads the preferences; VOICECOACH_DEFAULT_PROFILE overrides the path.
+
+use anyhow::{Result, Context, anyhow, bail};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Signer, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+
+use crate::preferences::Preferences;
+
+const PROFILE_FORMAT: &str = "voicecoach-settings-v1";
+const DEFAULT_PROFILE_ENV: &str = "VOICECOACH_DEFAULT_PROFILE";
+const DEFAULT_PROFILE_FILE: &str = "default-profile.json";
+// Per-machine preference sections a profile neither carries nor replaces
+const MACHINE_SECTIONS: [&str; 4] = ["calibration", "level_calibration", "window_layouts", "whisper_backend"];
+// Peer benchmark fields that are each rep's own: consent and contributor identity
+const MACHINE_BENCHMARK_FIELDS: [&str; 3] = ["benchmarks.opted_in", "benchmarks.contributor_id", "benchmarks.last_upload_at"];
+
+// What is signed: the profile exactly as it is stored in the bundle
+#[derive(Serialize, Deserialize)]
+struct SettingsProfile {
+    name: String,
+    created_at: u64,
+    app_version: String,
+    preferences: serde_json::Value,
+    #[serde(default)]
+    engine_config: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProfileBundle {
+    format: String,
+    profile: serde_json::Value,
+    signer_public_key: String,       // base64 ed25519
+    signature: String,               // base64, over the serialized profile
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SettingsProfileReport {
+    pub name: String,
+    pub path: String,
+    pub created_at: u64,
+    pub app_version: String,
+    pub sections: Vec<String>,       // Preference sections carried
+    pub engine_config: bool,
+    pub excluded: Vec<String>,       // Credentials and per-machine state left out or kept
+    pub signer_fingerprint: String,
+    pub trusted_signer: Option<String>,
+}
+
+/// Blank the credentials in preferences about to be exported; returns what was removed
+fn strip_credentials(preferences: &mut Preferences) -> Vec<String> {
+    let mut removed = Vec::new();
+    if preferences.control_interface.token.take().is_some() {
+        removed.push("control_interface.token".to_string());
+    }
+    for provider in preferences.enrichment.providers.iter_mut().filter(|p| !p.headers.is_empty()) {
+        provider.headers.clear();
+        removed.push(format!("enrichment.providers.{}.headers", provider.name));
+    }
+    removed
+}
+
+/// Keep this machine's own state and credentials in imported preferences
+fn keep_local(imported: &mut Preferences, local: &Preferences) {
+    imported.calibration = local.calibration.clone();
+    imported.level_calibration = local.level_calibration.clone();
+    imported.window_layouts = local.window_layouts.clone();
+    imported.whisper_backend = local.whisper_backend.clone();
+    imported.benchmarks.opted_in = local.benchmarks.opted_in;
+    imported.benchmarks.contributor_id = local.benchmarks.contributor_id.clone();
+    imported.benchmarks.last_upload_at = local.benchmarks.last_upload_at;
+    imported.control_interface.token = local.control_interface.token.clone();
+    let headers: HashMap<&str, &HashMap<String, String>> = local.enrichment.providers.iter()
+        .map(|p| (p.name.as_str(), &p.headers))
+        .collect();
+    for provider in imported.enrichment.providers.iter_mut().filter(|p| p.headers.is_empty()) {
+        if let Some(&local_headers) = headers.get(provider.name.as_str()) {
+            provider.headers = local_headers.clone();
+        }
+    }
+}
+
+/// Blank the credentials and per-machine fields of `preferences`; returns everything a
+/// profile leaves out (the MACHINE_SECTIONS are dropped when serializing), which is
+/// also what an import keeps
+fn strip_local(preferences: &mut Preferences) -> Vec<String> {
+    let mut excluded = strip_credentials(preferences);
+    preferences.benchmarks.opted_in = false;
+    preferences.benchmarks.contributor_id = None;
+    preferences.benchmarks.last_upload_at = None;
+    excluded.extend(MACHINE_BENCHMARK_FIELDS.iter().map(|s| s.to_string()));
+    excluded.extend(MACHINE_SECTIONS.iter().map(|s| s.to_string()));
+    excluded
+}
+
+fn shareable_preferences(preferences: &Preferences) -> Result<(serde_json::Value, Vec<String>)> {
+    let mut preferences = preferences.clone();
+    let excluded = strip_local(&mut preferences);
+    let mut value = serde_json::to_value(&preferences)?;
+    let sections = value.as_object_mut().context("Preferences are not an object")?;
+    for section in MACHINE_SECTIONS.iter() {
+        sections.remove(*section);
+    }
+    Ok((value, excluded))
+}
+
+fn sections(profile: &SettingsProfile) -> Vec<String> {
+    profile.preferences.as_object().map_or_else(Vec::new, |sections| sections.keys().cloned().collect())
+}
+
+fn report(profile: &SettingsProfile, path: &Path, excluded: Vec<String>, public_key: &VerifyingKey) -> SettingsProfileReport {
+    SettingsProfileReport {
+        name: profile.name.clone(),
+        path: path.display().to_string(),
+        created_at: profile.created_at,
+        app_version: profile.app_version.clone(),
+        sections: sections(profile),
+        engine_config: profile.engine_config.is_some(),
+        excluded,
+        signer_fingerprint: crate::credentials::fingerprint(public_key.as_bytes()),
+        trusted_signer: crate::credentials::trusted_signer_name(public_key),
+    }
+}
+
+fn export(path: &Path, name: String, include_engine_config: bool) -> Result<SettingsProfileReport> {
+    let (preferences, excluded) = shareable_preferences(&crate::preferences::load())?;
+    let engine_config = if include_engine_config { Some(crate::vosk_transcription::engine_config_json()?) } else { None };
+    let profile = SettingsProfile {
+        name,
+        created_at: chrono::Utc::now().timestamp_millis() as u64,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        preferences,
+        engine_config,
+    };
+    let signed = serde_json::to_value(&profile)?;
+    let key = crate::credentials::export_signing_key()?;
+    let bundle = ProfileBundle {
+        format: PROFILE_FORMAT.to_string(),
+        signature: BASE64.encode(key.sign(&serde_json::to_vec(&signed)?).to_bytes()),
+        signer_public_key: BASE64.encode(key.verifying_key().as_bytes()),
+        profile: signed,
+    };
+    fs::write(path, serde_json::to_string_pretty(&bundle)?).context(format!("Cannot write {}", path.display()))?;
+    Ok(report(&profile, path, excluded, &key.verifying_key()))
+}
+
+/// Read a profile and check its signature
+fn read(path: &Path) -> Result<(SettingsProfile, VerifyingKey)> {
+    let contents = fs::read_to_string(path).context(format!("Cannot read {}", path.display()))?;
+    let bundle: ProfileBundle = serde_json::from_str(&contents).context("Not a settings profile")?;
+    if bundle.format != PROFILE_FORMAT {
+        bail!("Unsupported settings profile format '{}'", bundle.format);
+    }
+    let public_key: [u8; 32] = BASE64.decode(&bundle.signer_public_key)?.try_into()
+        .map_err(|_| anyhow!("Signer key must be 32 bytes"))?;
+    let public_key = VerifyingKey::from_bytes(&public_key)?;
+    let signature = Signature::from_slice(&BASE64.decode(&bundle.signature)?)?;
+    public_key.verify(&serde_json::to_vec(&bundle.profile)?, &signature)
+        .map_err(|_| anyhow!("The settings profile signature is invalid (the file was changed after it was signed)"))?;
+    let profile = serde_json::from_value(bundle.profile).context("Settings profile is incomplete")?;
+    Ok((profile, public_key))
+}
+
+fn apply(path: &Path, require_trust: bool) -> Result<SettingsProfileReport> {
+    let (profile, public_key) = read(path)?;
+    let trusted = crate::credentials::trusted_signer_name(&public_key);
+    if require_trust && trusted.is_none() {
+        bail!("The profile is signed by {}, which is not a trusted signer", crate::credentials::fingerprint(public_key.as_bytes()));
+    }
+    let mut imported: Preferences = serde_json::from_value(profile.preferences.clone())
+        .context("The profile's preferences are invalid")?;
+    // Checked before anything is written
+    if let Some(engine_config) = &profile.engine_config {
+        crate::vosk_transcription::write_engine_config(engine_config)?;
+    }
+    let mut excluded = Vec::new();
+    crate::preferences::update(|preferences| {
+        excluded = strip_local(&mut preferences.clone());
+        keep_local(&mut imported, preferences);
+        *preferences = imported;
+    })?;
+    info!("📋 Settings profile '{}' applied from {} (signed by {})", profile.name, path.display(),
+        trusted.as_deref().unwrap_or("an untrusted key"));
+    Ok(report(&profile, path, excluded, &public_key))
+}
+
+/// Machine-wide location of the first-run profile
+fn machine_profile_path() -> PathBuf {
+    if let Ok(path) = std::env::var(DEFAULT_PROFILE_ENV) {
+        return PathBuf::from(path);
+    }
+    #[cfg(target_os = "windows")]
+    let dir = PathBuf::from(std::env::var("ProgramData").unwrap_or_else(|_| "C:\\ProgramData".to_string())).join("VoiceCoach");
+    #[cfg(target_os = "macos")]
+    let dir = PathBuf::from("/Library/Application Support/VoiceCoach");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let dir = PathBuf::from("/etc/voicecoach");
+    dir.join(DEFAULT_PROFILE_FILE)
+}
+
+/// First run: apply the machine-wide profile if IT placed one
+pub fn apply_machine_default() {
+    if crate::preferences::stored() {
+        return;
+    }
+    let path = machine_profile_path();
+    if !path.exists() {
+        return;
+    }
+    if let Err(e) = apply(&path, false) {
+        warn!("⚠️ Machine-wide settings profile {} not applied: {}", path.display(), e);
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Export the settings of this install as a signed profile (credentials and
+// per-machine state excluded); the engine configuration is included by default
+#[tauri::command]
+pub fn export_settings_profile(path: String, name: Option<String>, include_engine_config: Option<bool>) -> Result<SettingsProfileReport, String> {
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()).unwrap_or_else(|| "VoiceCoach settings".to_string());
+    let report = export(Path::new(&path), name, include_engine_config.unwrap_or(true)).map_err(|e| e.to_string())?;
+    info!("📋 Settings profile '{}' exported to {} ({} sections)", report.name, report.path, report.sections.len());
+    Ok(report)
+}
+
+// Apply a signed profile; the signer must be trusted unless allow_untrusted is set
+#[tauri::command]
+pub fn import_settings_profile(path: String, allow_untrusted: Option<bool>) -> Result<SettingsProfileReport, String> {
+    apply(Path::new(&path), !allow_untrusted.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+// Where a first-run profile is looked for on this machine
+#[tauri::command]
+pub fn get_default_profile_path() -> Result<String, String> {
+    Ok(machine_profile_path().display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_leave_credentials_and_machine_state_behind() {
+        let mut local = Preferences::default();
+        local.control_interface.token = Some("secret-token".to_string());
+        local.enrichment.providers[0].headers.insert("X-Api-Key".to_string(), "key".to_string());
+        local.playback_rate = Some(1.5);
+        local.benchmarks.opted_in = true;
+        local.benchmarks.contributor_id = Some("contributor-a".to_string());
+        local.benchmarks.last_upload_at = Some(1_700_000_000_000);
+        local.benchmarks.industry = Some("software".to_string());
+
+        let (shared, excluded) = shareable_preferences(&local).unwrap();
+        assert!(shared["control_interface"]["token"].is_null());
+        assert!(shared["enrichment"]["providers"][0]["headers"].as_object().unwrap().is_empty());
+        assert!(shared.get("calibration").is_none() && shared.get("whisper_backend").is_none());
+        assert!(excluded.contains(&"control_interface.token".to_string()));
+        assert_eq!(shared["benchmarks"]["opted_in"], false);
+        assert!(shared["benchmarks"]["contributor_id"].is_null() && shared["benchmarks"]["last_upload_at"].is_null());
+        assert_eq!(shared["benchmarks"]["industry"], "software");
+
+        // Importing on another machine keeps that machine's token and headers
+        let mut imported: Preferences = serde_json::from_value(shared).unwrap();
+        let mut other = Preferences::default();
+        other.control_interface.token = Some("other-token".to_string());
+        other.benchmarks.contributor_id = Some("contributor-b".to_string());
+        let kept = strip_local(&mut other.clone());
+        keep_local(&mut imported, &other);
+        // The import report lists everything kept, like the export lists what it left out
+        for field in ["control_interface.token", "benchmarks.contributor_id", "benchmarks.opted_in", "calibration"] {
+            assert!(kept.contains(&field.to_string()), "{} not reported", field);
+        }
+        assert_eq!(imported.control_interface.token.as_deref(), Some("other-token"));
+        assert!(!imported.benchmarks.opted_in);
+        assert_eq!(imported.benchmarks.contributor_id.as_deref(), Some("contributor-b"));
+        assert_eq!(imported.benchmarks.industry.as_deref(), Some("software"));
+        assert_eq!(imported.playback_rate, Some(1.5));
+        assert!(imported.enrichment.providers[0].headers.is_empty());
+    }
+}
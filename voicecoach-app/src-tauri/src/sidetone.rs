@@ -0,0 +1,279 @@
+// Sidetone - mic monitoring for headset users
+// Closed-back headsets make reps talk too loud because they can't hear themselves.
+// With sidetone on, the mic stream is opened together with an output stream on the
+// headset: the mic callback pushes its raw mono audio into a small ring buffer and
+// the output callback plays it back with its own gain (resampled to the output rate,
+// and trimmed whenever more than max_latency_ms is queued so the delay never grows).
+// Both streams live on the same keeper thread, so the monitor closes with the mic
+// (and with privacy mute). Gain and mute apply immediately; turning sidetone on or
+// switching the output device applies from the next capture start.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use cpal::traits::{DeviceTrait, HostTrait};
+use once_cell::sync::Lazy;
+use ringbuf::{HeapConsumer, HeapProducer, HeapRb};
+use log::{info, warn, error};
+
+use crate::privacy::CaptureStream;
+
+// Ring capacity (at the mic rate), well above any latency cap
+const RING_SECONDS: usize = 1;
+const MAX_GAIN: f32 = 2.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SidetoneSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Playback level of the monitored mic (1.0 = as captured)
+    #[serde(default = "default_gain")]
+    pub gain: f32,
+    #[serde(default)]
+    pub muted: bool,
+    /// Output device name; None = the default output device
+    #[serde(default)]
+    pub output_device: Option<String>,
+    /// Most mic audio queued for playback before the backlog is dropped
+    #[serde(default = "default_max_latency_ms")]
+    pub max_latency_ms: u32,
+}
+
+fn default_gain() -> f32 { 0.5 }
+fn default_max_latency_ms() -> u32 { 40 }
+
+impl Default for SidetoneSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gain: default_gain(),
+            muted: false,
+            output_device: None,
+            max_latency_ms: default_max_latency_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SidetoneStatus {
+    pub settings: SidetoneSettings,
+    pub active: bool,                   // A mic stream is being monitored
+    pub source: Option<String>,         // Owner of the monitored mic stream
+    pub output_device: Option<String>,
+}
+
+// Live controls, read by the output callback
+static GAIN: AtomicU32 = AtomicU32::new(0);
+static MUTED: AtomicBool = AtomicBool::new(false);
+
+struct Feed {
+    owner: &'static str,
+    producer: HeapProducer<f32>,
+    output_device: String,
+}
+
+// Mic side of the active monitor (one at a time: a second mic stream isn't monitored)
+static FEED: Lazy<Mutex<Option<Feed>>> = Lazy::new(|| Mutex::new(None));
+
+fn apply_controls(settings: &SidetoneSettings) {
+    GAIN.store(settings.gain.to_bits(), Ordering::Relaxed);
+    MUTED.store(settings.muted, Ordering::Relaxed);
+}
+
+/// Mic samples to output frames: linear resampling and a cap on queued audio
+struct Monitor {
+    step: f64,          // Mic samples per output frame
+    position: f64,      // Between `previous` and `next`
+    previous: f32,
+    next: f32,
+    max_queued: usize,  // Mic samples
+}
+
+impl Monitor {
+    fn new(input_rate: u32, output_rate: u32, max_latency_ms: u32) -> Self {
+        Self {
+            step: input_rate as f64 / output_rate as f64,
+            position: 0.0,
+            previous: 0.0,
+            next: 0.0,
+            max_queued: (input_rate as usize * max_latency_ms as usize / 1000).max(1),
+        }
+    }
+
+    /// Fill interleaved output frames (every channel gets the mono mic signal)
+    fn fill(&mut self, queue: &mut HeapConsumer<f32>, output: &mut [f32], channels: usize, gain: f32) {
+        // Drop the backlog down to half the cap (output started late or runs slow)
+        if queue.len() > self.max_queued {
+            queue.skip(queue.len() - self.max_queued / 2);
+        }
+        for frame in output.chunks_mut(channels.max(1)) {
+            while self.position >= 1.0 {
+                self.previous = self.next;
+                self.next = queue.pop().unwrap_or(0.0);  // Underrun plays silence
+                self.position -= 1.0;
+            }
+            let sample = self.previous + (self.next - self.previous) * self.position as f32;
+            frame.fill((sample * gain).clamp(-1.0, 1.0));
+            self.position += self.step;
+        }
+    }
+}
+
+/// A mic stream with its optional monitor output; starting and dropping it covers both
+pub struct Monitored {
+    input: cpal::Stream,
+    output: Option<cpal::Stream>,
+    owner: &'static str,
+}
+
+impl CaptureStream for Monitored {
+    fn start(&self) -> Result<(), String> {
+        self.input.start()?;
+        if let Some(output) = &self.output {
+            // The mic works without its monitor
+            if let Err(e) = output.start() {
+                warn!("⚠️ Sidetone output failed to start: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Monitored {
+    fn drop(&mut self) {
+        let mut feed = FEED.lock().unwrap();
+        if self.output.is_some() && feed.as_ref().map_or(false, |f| f.owner == self.owner) {
+            *feed = None;
+            info!("🎧 Sidetone stopped ({})", self.owner);
+        }
+    }
+}
+
+fn output_device(host: &cpal::Host, name: Option<&str>) -> Option<cpal::Device> {
+    match name {
+        Some(name) => host.output_devices().ok()?.find(|d| d.name().map_or(false, |n| n == name)),
+        None => host.default_output_device(),
+    }
+}
+
+fn build_output(device: &cpal::Device, config: &cpal::StreamConfig, format: cpal::SampleFormat, mut monitor: Monitor, mut queue: HeapConsumer<f32>) -> Result<cpal::Stream, String> {
+    let channels = config.channels as usize;
+    let sample_rate = config.sample_rate.0;
+    crate::sample_format::build_output_stream(
+        device,
+        config,
+        format,
+        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+            let _timing = crate::pipeline_stats::time_callback(crate::pipeline_stats::PipelineStream::SidetoneOutput, data.len() / channels.max(1), sample_rate);
+            let gain = if MUTED.load(Ordering::Relaxed) { 0.0 } else { f32::from_bits(GAIN.load(Ordering::Relaxed)) };
+            monitor.fill(&mut queue, data, channels, gain);
+        },
+        |err| error!("Sidetone output error: {:?}", err),
+    ).map_err(|e| format!("Failed to build sidetone output: {}", e))
+}
+
+fn open_monitor(owner: &'static str, input_rate: u32, settings: &SidetoneSettings) -> Result<cpal::Stream, String> {
+    let host = cpal::default_host();
+    let device = output_device(&host, settings.output_device.as_deref())
+        .ok_or_else(|| format!("Output device not found: {}", settings.output_device.as_deref().unwrap_or("default")))?;
+    let supported = device.default_output_config().map_err(|e| format!("Failed to get output config: {}", e))?;
+    let config: cpal::StreamConfig = supported.config();
+    let monitor = Monitor::new(input_rate, config.sample_rate.0, settings.max_latency_ms);
+    let (producer, consumer) = HeapRb::<f32>::new(input_rate as usize * RING_SECONDS).split();
+    let stream = build_output(&device, &config, supported.sample_format(), monitor, consumer)?;
+    let output_device = device.name().unwrap_or_default();
+    info!("🎧 Sidetone on {} ({} Hz mic -> {} Hz, gain {:.2})", output_device, input_rate, config.sample_rate.0, settings.gain);
+    *FEED.lock().unwrap() = Some(Feed { owner, producer, output_device });
+    Ok(stream)
+}
+
+/// Wrap a freshly built capture stream, adding the monitor output when it is the mic
+/// (`mic`) and sidetone is on. Runs on the capture keeper thread (from the
+/// privacy::open_stream build closure).
+pub fn monitored(owner: &'static str, input: cpal::Stream, input_rate: u32, mic: bool) -> Monitored {
+    let settings = crate::preferences::load().sidetone;
+    apply_controls(&settings);
+    let output = if mic && settings.enabled && FEED.lock().unwrap().is_none() {
+        open_monitor(owner, input_rate, &settings)
+            .map_err(|e| warn!("⚠️ Sidetone unavailable: {}", e))
+            .ok()
+    } else {
+        None
+    };
+    Monitored { input, output, owner }
+}
+
+/// Mic callback: queue interleaved input for the monitor (no-op unless `owner` is monitored)
+pub fn feed(owner: &'static str, data: &[f32], channels: usize) {
+    // Never block the audio thread on the lock
+    let mut guard = match FEED.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+    if let Some(feed) = guard.as_mut().filter(|f| f.owner == owner) {
+        if channels > 1 {
+            feed.producer.push_slice(&crate::device_conflict::downmix_to_mono(data, channels));
+        } else {
+            feed.producer.push_slice(data);
+        }
+    }
+}
+
+fn status() -> SidetoneStatus {
+    let feed = FEED.lock().unwrap();
+    SidetoneStatus {
+        settings: crate::preferences::load().sidetone,
+        active: feed.is_some(),
+        source: feed.as_ref().map(|f| f.owner.to_string()),
+        output_device: feed.as_ref().map(|f| f.output_device.clone()),
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_sidetone() -> Result<SidetoneStatus, String> {
+    Ok(status())
+}
+
+// Gain and mute apply at once; enabling and the output device from the next capture start
+#[tauri::command]
+pub fn set_sidetone(settings: SidetoneSettings) -> Result<SidetoneStatus, String> {
+    if !(0.0..=MAX_GAIN).contains(&settings.gain) {
+        return Err(format!("gain must be between 0 and {}", MAX_GAIN));
+    }
+    if settings.max_latency_ms == 0 {
+        return Err("max_latency_ms must be above 0".to_string());
+    }
+    crate::preferences::update(|p| p.sidetone = settings.clone())
+        .map_err(|e| e.to_string())?;
+    apply_controls(&settings);
+    info!("🎧 Sidetone {} (gain {:.2}{})", if settings.enabled { "enabled" } else { "disabled" },
+        settings.gain, if settings.muted { ", muted" } else { "" });
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_resamples_and_caps_the_backlog() {
+        let (mut producer, mut consumer) = HeapRb::<f32>::new(1_000).split();
+        // 16 kHz mic into a 32 kHz stereo output: two frames per mic sample, one sample behind
+        let mut monitor = Monitor::new(16_000, 32_000, 10);
+        producer.push_slice(&[0.3, 0.6]);
+        let mut output = vec![0.0f32; 12];
+        monitor.fill(&mut consumer, &mut output, 2, 1.0);
+        let left: Vec<f32> = output.iter().step_by(2).copied().collect();
+        let expected = [0.0, 0.0, 0.0, 0.15, 0.3, 0.45];
+        assert!(left.iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < 1e-6));
+        assert_eq!(output[8], output[9]);
+
+        // 10 ms cap at 16 kHz is 160 samples: a 500-sample backlog is cut to 80 (3 then played)
+        producer.push_slice(&[0.5; 500]);
+        monitor.fill(&mut consumer, &mut output, 2, 0.5);
+        assert_eq!(consumer.len(), 77);
+        assert!((output[11] - 0.25).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,299 @@
+// Similar Sessions - past calls found by what happened in them
+// Every session's final summary is embedded when summarize_call stores it and kept in
+// a small vector index (session_index.json, beside the sessions folder): the Ollama
+// embedding of the summary where Ollama is running, and always a hashed bag-of-words
+// vector so the index also works without it. find_similar_sessions takes a question
+// ("calls where the prospect had security objections") or a session to compare with
+// (the call in progress when neither is given) and ranks the indexed sessions by cosine
+// similarity - on the embeddings when the query and the session both have one, on the
+// lexical vectors otherwise. Each hit links to its session timeline: the session id
+// and the moment of the call whose window summary matches best. index_past_sessions
+// embeds sessions summarized before the index existed or while Ollama was down.
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+use crate::ollama_integration::OllamaCoachingService;
+use crate::rolling_summary::{RollingSummary, WindowSummary};
+use crate::session_store::Session;
+use crate::topic_segmentation::{cosine, lexical_vector, EMBEDDING_MODEL};
+
+const INDEX_FILE: &str = "session_index.json";
+const DEFAULT_RESULTS: usize = 5;
+const MAX_RESULTS: usize = 20;
+// Below this a session shares no more than a stray word with the query
+const MIN_SCORE: f32 = 0.05;
+// Query text taken from an unsummarized session (the end of its transcript)
+const MAX_QUERY_CHARS: usize = 2_000;
+const EXCERPT_CHARS: usize = 240;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    session_id: String,
+    started_at: u64,
+    summary_updated_at: u64,         // Summary this entry was made from
+    lexical: Vec<f32>,
+    embedding: Option<Vec<f32>>,
+    model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SimilarSession {
+    pub session_id: String,
+    pub started_at: u64,
+    pub company: Option<String>,
+    pub score: f32,
+    pub summary: String,             // Excerpt of the session summary
+    pub moment_ms: Option<u64>,      // Timeline offset of the best-matching window
+    pub matched_by: String,          // "embedding" or "keywords"
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SessionIndexStatus {
+    pub indexed: usize,
+    pub embedded: usize,             // Entries with an Ollama embedding
+    pub updated: usize,              // Entries written by this run
+    pub unsummarized: usize,         // Sessions with no summary to index yet
+}
+
+// Loaded from disk on first use
+static INDEX: Lazy<Mutex<Option<Vec<IndexEntry>>>> = Lazy::new(|| Mutex::new(None));
+
+fn index_path() -> PathBuf {
+    crate::app_paths::data_dir().join(INDEX_FILE)
+}
+
+fn load_index() -> Vec<IndexEntry> {
+    match std::fs::read_to_string(index_path()) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            warn!("⚠️ Session index unreadable, rebuilding: {}", e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn save_index(entries: &[IndexEntry]) -> Result<()> {
+    let path = index_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string(entries)?).context(format!("Cannot write {}", path.display()))
+}
+
+fn with_index<T>(f: impl FnOnce(&mut Vec<IndexEntry>) -> T) -> T {
+    let mut index = INDEX.lock().unwrap();
+    f(index.get_or_insert_with(load_index))
+}
+
+fn upsert(entry: IndexEntry) -> Result<()> {
+    with_index(|entries| {
+        entries.retain(|e| e.session_id != entry.session_id);
+        entries.push(entry);
+        save_index(entries)
+    })
+}
+
+async fn embed(text: &str) -> Option<Vec<f32>> {
+    let service = OllamaCoachingService::new();
+    if !service.check_availability().await.unwrap_or(false) {
+        return None;
+    }
+    service.embed(EMBEDDING_MODEL, text).await.ok()
+}
+
+async fn entry_for(session: &Session, summary: &RollingSummary) -> IndexEntry {
+    let embedding = embed(&summary.summary).await;
+    IndexEntry {
+        session_id: session.id.clone(),
+        started_at: session.started_at,
+        summary_updated_at: summary.updated_at,
+        lexical: lexical_vector(&summary.summary),
+        model: embedding.as_ref().map(|_| EMBEDDING_MODEL.to_string()),
+        embedding,
+    }
+}
+
+/// Add (or refresh) a session's summary in the index
+pub async fn index_session(session_id: &str) {
+    let session = match crate::session_store::load_session(Some(session_id.to_string())) {
+        Ok(session) => session,
+        Err(e) => return warn!("⚠️ Session {} not indexed: {}", session_id, e),
+    };
+    let summary = match &session.summary {
+        Some(summary) => summary,
+        None => return,
+    };
+    let entry = entry_for(&session, summary).await;
+    let embedded = entry.embedding.is_some();
+    match upsert(entry) {
+        Ok(()) => info!("🔎 Session {} indexed ({})", session.id, if embedded { "embedding" } else { "keywords only" }),
+        Err(e) => warn!("⚠️ Session {} not indexed: {}", session.id, e),
+    }
+}
+
+// What a session is compared by: its summary, or the end of its transcript
+fn session_text(session: &Session) -> String {
+    if let Some(summary) = &session.summary {
+        return summary.summary.clone();
+    }
+    let transcript = session.transcript.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join(" ");
+    let skip = transcript.chars().count().saturating_sub(MAX_QUERY_CHARS);
+    transcript.chars().skip(skip).collect()
+}
+
+fn score(entry: &IndexEntry, lexical: &[f32], embedding: Option<&[f32]>) -> (f32, bool) {
+    match (entry.embedding.as_deref(), embedding) {
+        (Some(a), Some(b)) if a.len() == b.len() => (cosine(a, b), true),
+        _ => (cosine(&entry.lexical, lexical), false),
+    }
+}
+
+/// Indexed sessions most similar to the query, best first: (entry index, score, by embedding)
+fn rank(entries: &[IndexEntry], lexical: &[f32], embedding: Option<&[f32]>, exclude: Option<&str>, limit: usize) -> Vec<(usize, f32, bool)> {
+    let mut ranked: Vec<(usize, f32, bool)> = entries.iter().enumerate()
+        .filter(|(_, e)| Some(e.session_id.as_str()) != exclude)
+        .map(|(i, e)| {
+            let (score, embedded) = score(e, lexical, embedding);
+            (i, score, embedded)
+        })
+        .filter(|&(_, score, _)| score >= MIN_SCORE)
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)
+        .then_with(|| entries[b.0].started_at.cmp(&entries[a.0].started_at)));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Start of the window summary closest to the query
+fn best_moment(windows: &[WindowSummary], lexical: &[f32]) -> Option<u64> {
+    windows.iter()
+        .map(|w| (w.start_ms, cosine(&lexical_vector(&w.summary), lexical)))
+        .filter(|&(_, score)| score > 0.0)
+        .fold(None, |best: Option<(u64, f32)>, (start_ms, score)| match best {
+            Some((_, top)) if top >= score => best,
+            _ => Some((start_ms, score)),
+        })
+        .map(|(start_ms, _)| start_ms)
+}
+
+fn excerpt(text: &str) -> String {
+    if text.chars().count() <= EXCERPT_CHARS {
+        return text.to_string();
+    }
+    format!("{}…", text.chars().take(EXCERPT_CHARS).collect::<String>().trim_end())
+}
+
+// ========== Tauri Commands ==========
+
+// Past sessions like a question ("calls where the prospect had security objections")
+// or like a session (the call in progress when neither is given), best first
+#[tauri::command]
+pub async fn find_similar_sessions(query: Option<String>, session_id: Option<String>, limit: Option<usize>) -> Result<Vec<SimilarSession>, String> {
+    let query = query.map(|q| q.trim().to_string()).filter(|q| !q.is_empty());
+    let (text, exclude) = match query {
+        Some(query) => (query, None),
+        None => {
+            let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+            (session_text(&session), Some(session.id))
+        }
+    };
+    if text.trim().is_empty() {
+        return Err("Nothing to compare with yet: the session has no transcript".to_string());
+    }
+
+    let lexical = lexical_vector(&text);
+    let embedding = embed(&text).await;
+    let limit = limit.unwrap_or(DEFAULT_RESULTS).clamp(1, MAX_RESULTS);
+    let hits: Vec<(IndexEntry, f32, bool)> = with_index(|entries| {
+        rank(entries, &lexical, embedding.as_deref(), exclude.as_deref(), limit).into_iter()
+            .map(|(i, score, embedded)| (entries[i].clone(), score, embedded))
+            .collect()
+    });
+
+    // Sessions deleted since they were indexed are skipped
+    let similar: Vec<SimilarSession> = hits.into_iter().filter_map(|(entry, score, embedded)| {
+        let session = crate::session_store::load_session(Some(entry.session_id.clone())).ok()?;
+        let summary = session.summary.as_ref();
+        Some(SimilarSession {
+            session_id: session.id.clone(),
+            started_at: session.started_at,
+            company: session.company.clone(),
+            score,
+            summary: summary.map(|s| excerpt(&s.summary)).unwrap_or_default(),
+            moment_ms: summary.and_then(|s| best_moment(&s.windows, &lexical)),
+            matched_by: if embedded { "embedding" } else { "keywords" }.to_string(),
+        })
+    }).collect();
+    info!("🔎 {} similar session(s) found{}", similar.len(), if embedding.is_some() { "" } else { " (keywords only, Ollama unavailable)" });
+    Ok(similar)
+}
+
+// Index summarized sessions that are missing, out of date or not yet embedded
+#[tauri::command]
+pub async fn index_past_sessions() -> Result<SessionIndexStatus, String> {
+    let sessions = tokio::task::spawn_blocking(crate::session_store::all_sessions).await.map_err(|e| e.to_string())?;
+    let ollama_available = OllamaCoachingService::new().check_availability().await.unwrap_or(false);
+    let mut updated = 0;
+    let mut unsummarized = 0;
+    for session in &sessions {
+        let summary = match &session.summary {
+            Some(summary) => summary,
+            None => {
+                unsummarized += 1;
+                continue;
+            }
+        };
+        let current = with_index(|entries| entries.iter().any(|e| e.session_id == session.id
+            && e.summary_updated_at == summary.updated_at
+            && (e.embedding.is_some() || !ollama_available)));
+        if !current {
+            upsert(entry_for(session, summary).await).map_err(|e| e.to_string())?;
+            updated += 1;
+        }
+    }
+    let status = with_index(|entries| SessionIndexStatus {
+        indexed: entries.len(),
+        embedded: entries.iter().filter(|e| e.embedding.is_some()).count(),
+        updated,
+        unsummarized,
+    });
+    info!("🔎 Session index: {} indexed ({} embedded), {} updated", status.indexed, status.embedded, status.updated);
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(session_id: &str, started_at: u64, summary: &str) -> IndexEntry {
+        IndexEntry { session_id: session_id.to_string(), started_at, summary_updated_at: 0, lexical: lexical_vector(summary), embedding: None, model: None }
+    }
+
+    #[test]
+    fn test_sessions_rank_by_summary_similarity() {
+        let entries = vec![
+            entry("a", 1, "Prospect raised security concerns about data encryption and compliance audits"),
+            entry("b", 2, "Discussed pricing tiers and annual discount for the team plan"),
+            entry("c", 3, "Security review required; prospect asked about encryption at rest"),
+        ];
+        let query = lexical_vector("calls where the prospect had security objections about encryption");
+        let ranked = rank(&entries, &query, None, None, 5);
+        let ids: Vec<&str> = ranked.iter().map(|&(i, _, _)| entries[i].session_id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"a") && ids.contains(&"c"));
+        assert!(ranked.iter().all(|&(_, _, embedded)| !embedded));
+        // The session compared with is never its own match
+        assert!(rank(&entries, &query, None, Some("a"), 5).iter().all(|&(i, _, _)| entries[i].session_id != "a"));
+
+        let windows = vec![
+            WindowSummary { start_ms: 0, end_ms: 300_000, summary: "Introductions and agenda".to_string() },
+            WindowSummary { start_ms: 300_000, end_ms: 600_000, summary: "Prospect worried about encryption and security".to_string() },
+        ];
+        assert_eq!(best_moment(&windows, &query), Some(300_000));
+    }
+}
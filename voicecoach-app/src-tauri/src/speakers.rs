@@ -0,0 +1,157 @@
+// Speakers - multi-participant labelling for the system-audio (loopback) side
+// With diarization enabled the engine clusters voices into numeric speaker ids.
+// This maps them to stable "Prospect A", "Prospect B", ... labels in order of
+// first appearance, tracks talk time, and estimates how many real participants
+// there are (clusters with only a second or two of speech are usually spurious
+// splits of another voice). Users can rename speakers for the current call.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+// Talk time a cluster needs before it counts as a participant
+const MIN_SPEAKER_SECONDS: f32 = 3.0;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SpeakerInfo {
+    pub speaker_id: u32,
+    pub label: String,          // "Prospect A"
+    pub name: Option<String>,   // Set via rename_speaker
+    pub display_name: String,
+    pub talk_seconds: f32,
+    pub segments: u32,
+    pub counted: bool,          // Included in the participant estimate
+}
+
+// Payload of "speaker_count_changed" and "speaker_renamed"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SpeakersUpdate {
+    pub estimated_count: usize,
+    pub speakers: Vec<SpeakerInfo>,
+}
+
+#[derive(Default)]
+struct SpeakerRegistry {
+    speakers: BTreeMap<u32, SpeakerInfo>,
+    estimated_count: usize,
+}
+
+static REGISTRY: Lazy<Mutex<SpeakerRegistry>> = Lazy::new(|| Mutex::new(SpeakerRegistry::default()));
+
+/// "Prospect A" .. "Prospect Z", then "Prospect 27", ...
+fn label_for_index(index: usize) -> String {
+    match (b'A'..=b'Z').nth(index) {
+        Some(letter) => format!("Prospect {}", letter as char),
+        None => format!("Prospect {}", index + 1),
+    }
+}
+
+impl SpeakerRegistry {
+    /// Record a diarized segment; returns (display name, participant count if it changed)
+    fn record(&mut self, speaker_id: u32, seconds: f32) -> (String, Option<usize>) {
+        let next_index = self.speakers.len();
+        let speaker = self.speakers.entry(speaker_id).or_insert_with(|| {
+            let label = label_for_index(next_index);
+            SpeakerInfo {
+                speaker_id,
+                display_name: label.clone(),
+                label,
+                name: None,
+                talk_seconds: 0.0,
+                segments: 0,
+                counted: false,
+            }
+        });
+        speaker.talk_seconds += seconds.max(0.0);
+        speaker.segments += 1;
+        speaker.counted = speaker.talk_seconds >= MIN_SPEAKER_SECONDS;
+        let display_name = speaker.display_name.clone();
+
+        let estimated = self.speakers.values().filter(|s| s.counted).count();
+        let changed = (estimated != self.estimated_count).then(|| {
+            self.estimated_count = estimated;
+            estimated
+        });
+        (display_name, changed)
+    }
+
+    fn update(&self) -> SpeakersUpdate {
+        SpeakersUpdate {
+            estimated_count: self.estimated_count,
+            speakers: self.speakers.values().cloned().collect(),
+        }
+    }
+}
+
+/// Forget all speakers (start of a call)
+pub fn begin_call() {
+    *REGISTRY.lock().unwrap() = SpeakerRegistry::default();
+}
+
+/// Label for a diarized segment of `seconds` length; emits "speaker_count_changed"
+/// when the participant estimate moves
+pub fn label_segment(app: &AppHandle, speaker_id: u32, seconds: f32) -> String {
+    let (name, changed, update) = {
+        let mut registry = REGISTRY.lock().unwrap();
+        let (name, changed) = registry.record(speaker_id, seconds);
+        (name, changed, registry.update())
+    };
+    if let Some(count) = changed {
+        info!("👥 LED 8900: Estimated {} participant(s) on system audio", count);
+        if let Err(e) = app.emit_all("speaker_count_changed", update) {
+            error!("❌ LED 8901: Failed to emit speaker_count_changed: {:?}", e);
+        }
+    }
+    name
+}
+
+/// Current display name for a speaker id without recording talk time (interim results)
+pub fn display_name(speaker_id: u32) -> Option<String> {
+    REGISTRY.lock().unwrap().speakers.get(&speaker_id).map(|s| s.display_name.clone())
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_speakers() -> Result<SpeakersUpdate, String> {
+    Ok(REGISTRY.lock().unwrap().update())
+}
+
+// Give a diarized speaker a real name for the rest of the call (empty name reverts to the label)
+#[tauri::command]
+pub fn rename_speaker(app: AppHandle, speaker_id: u32, name: String) -> Result<SpeakersUpdate, String> {
+    let update = {
+        let mut registry = REGISTRY.lock().unwrap();
+        let speaker = registry.speakers.get_mut(&speaker_id)
+            .ok_or_else(|| format!("Unknown speaker {}", speaker_id))?;
+        let name = name.trim();
+        speaker.name = (!name.is_empty()).then(|| name.to_string());
+        speaker.display_name = speaker.name.clone().unwrap_or_else(|| speaker.label.clone());
+        info!("✏️ Speaker {} ({}) is now '{}'", speaker_id, speaker.label, speaker.display_name);
+        registry.update()
+    };
+    if let Err(e) = app.emit_all("speaker_renamed", update.clone()) {
+        error!("❌ LED 8901: Failed to emit speaker_renamed: {:?}", e);
+    }
+    Ok(update)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_follow_first_appearance_and_short_clusters_are_not_counted() {
+        let mut registry = SpeakerRegistry::default();
+
+        assert_eq!(registry.record(3, 2.0), ("Prospect A".to_string(), None));
+        assert_eq!(registry.record(0, 4.0), ("Prospect B".to_string(), Some(1)));
+        assert_eq!(registry.record(3, 1.5), ("Prospect A".to_string(), Some(2)));
+        // A brief blip from a third cluster doesn't change the estimate
+        assert_eq!(registry.record(7, 0.8), ("Prospect C".to_string(), None));
+        assert_eq!(registry.update().estimated_count, 2);
+    }
+}
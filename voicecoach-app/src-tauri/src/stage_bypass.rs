@@ -0,0 +1,36 @@
+// Stage Bypass Commands - support's switches for the audio pipeline stages
+// The bitmask the audio callbacks read is in voicecoach-core (re-exported here under the
+// old path). Every change is announced as "stage_bypass_changed" so the UI can warn
+// while any stage is off.
+
+pub use voicecoach_core::stage_bypass::*;
+
+use tauri::{AppHandle, Manager};
+use log::error;
+
+fn announce(app: &AppHandle, bypassed: Vec<PipelineStage>) -> Vec<PipelineStage> {
+    if let Err(e) = app.emit_all("stage_bypass_changed", &bypassed) {
+        error!("Failed to emit stage_bypass_changed: {:?}", e);
+    }
+    bypassed
+}
+
+// ========== Tauri Commands ==========
+
+// Switch a pipeline stage off (or back on); returns every stage now bypassed
+#[tauri::command]
+pub fn set_stage_bypass(app: AppHandle, stage: PipelineStage, bypass: bool) -> Result<Vec<PipelineStage>, String> {
+    Ok(announce(&app, set(stage, bypass)))
+}
+
+#[tauri::command]
+pub fn get_stage_bypasses() -> Result<Vec<PipelineStage>, String> {
+    Ok(current())
+}
+
+// Put every stage back
+#[tauri::command]
+pub fn clear_stage_bypasses(app: AppHandle) -> Result<Vec<PipelineStage>, String> {
+    clear();
+    Ok(announce(&app, Vec::new()))
+}
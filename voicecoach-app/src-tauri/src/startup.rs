@@ -0,0 +1,239 @@
+// Startup - what VoiceCoach does when it launches
+// Preferences-backed options applied by the setup hook: start hidden in the tray
+// instead of opening the window, enter warm transcription standby so the first call
+// starts instantly, and check the audio devices (microphone, system audio capture,
+// output) so a missing device is reported before the call rather than during it
+// ("startup_device_check"). Launching at login registers VoiceCoach with the OS: the
+// per-user Run key on Windows, an autostart entry on Linux desktops.
+
+use serde::{Deserialize, Serialize};
+use cpal::traits::{DeviceTrait, HostTrait};
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+const APP_NAME: &str = "VoiceCoach";
+#[cfg(windows)]
+const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct StartupOptions {
+    /// Start hidden in the tray (the window opens from the tray menu)
+    #[serde(default)]
+    pub start_minimized: bool,
+    /// Enter warm transcription standby with the preloaded model
+    #[serde(default)]
+    pub standby_capture: bool,
+    #[serde(default = "default_true")]
+    pub auto_check_devices: bool,
+    /// Registered with the OS to start at login
+    #[serde(default)]
+    pub launch_at_login: bool,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for StartupOptions {
+    fn default() -> Self {
+        Self { start_minimized: false, standby_capture: false, auto_check_devices: true, launch_at_login: false }
+    }
+}
+
+// Payload of "startup_device_check"
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct StartupDeviceCheck {
+    pub input_device: Option<String>,
+    pub system_audio_device: Option<String>,
+    pub output_device: Option<String>,
+    pub problems: Vec<String>,
+}
+
+fn check_devices() -> StartupDeviceCheck {
+    let host = cpal::default_host();
+    let input_device = crate::device_selection::select_input_device(&host).and_then(|d| d.name().ok());
+    let system_audio_device = crate::device_selection::system_audio_device(&host).and_then(|d| d.name().ok());
+    let output_device = host.default_output_device().and_then(|d| d.name().ok());
+    let mut problems = Vec::new();
+    if input_device.is_none() {
+        problems.push("No microphone found: the rep's side can't be transcribed".to_string());
+    }
+    if system_audio_device.is_none() {
+        problems.push("No system audio capture device: the prospect's side can't be transcribed".to_string());
+    }
+    if output_device.is_none() {
+        problems.push("No output device found".to_string());
+    }
+    StartupDeviceCheck { input_device, system_audio_device, output_device, problems }
+}
+
+#[cfg(windows)]
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Command line the OS runs at login
+fn launch_command() -> Result<String, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the executable: {}", e))?;
+    Ok(format!("\"{}\"", exe.display()))
+}
+
+#[cfg(windows)]
+fn set_login_item(enabled: bool) -> Result<(), String> {
+    use windows_sys::Win32::System::Registry::{RegDeleteKeyValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ};
+    const ERROR_FILE_NOT_FOUND: u32 = 2;
+    let (key, name) = (wide(RUN_KEY), wide(APP_NAME));
+    let status = if enabled {
+        let command = wide(&launch_command()?);
+        unsafe {
+            RegSetKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr(), REG_SZ,
+                command.as_ptr() as *const core::ffi::c_void, (command.len() * 2) as u32)
+        }
+    } else {
+        match unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr()) } {
+            ERROR_FILE_NOT_FOUND => 0,
+            status => status,
+        }
+    };
+    if status != 0 {
+        return Err(format!("Failed to update the Run key (error {})", status));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn login_item_registered() -> bool {
+    use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+    let (key, name) = (wide(RUN_KEY), wide(APP_NAME));
+    let mut size = 0u32;
+    let status = unsafe {
+        RegGetValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr(), RRF_RT_REG_SZ,
+            std::ptr::null_mut(), std::ptr::null_mut(), &mut size)
+    };
+    status == 0
+}
+
+#[cfg(target_os = "linux")]
+fn autostart_entry() -> Result<std::path::PathBuf, String> {
+    let config = std::env::var_os("XDG_CONFIG_HOME").map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".config")))
+        .ok_or("No home directory to register the autostart entry in")?;
+    Ok(config.join("autostart").join("voicecoach.desktop"))
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_entry(command: &str) -> String {
+    format!("[Desktop Entry]\nType=Application\nName={}\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n", APP_NAME, command)
+}
+
+#[cfg(target_os = "linux")]
+fn set_login_item(enabled: bool) -> Result<(), String> {
+    let entry = autostart_entry()?;
+    if !enabled {
+        return match std::fs::remove_file(&entry) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {:?}: {}", entry, e)),
+            _ => Ok(()),
+        };
+    }
+    let desktop = desktop_entry(&launch_command()?);
+    if let Some(dir) = entry.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    }
+    std::fs::write(&entry, desktop).map_err(|e| format!("Failed to write {:?}: {}", entry, e))
+}
+
+#[cfg(target_os = "linux")]
+fn login_item_registered() -> bool {
+    autostart_entry().map_or(false, |entry| entry.exists())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn set_login_item(_enabled: bool) -> Result<(), String> {
+    Err("Launching at login isn't supported on this platform yet".to_string())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn login_item_registered() -> bool {
+    false
+}
+
+/// The saved options, with launch_at_login as the OS has it (it can be removed outside the app)
+fn current() -> StartupOptions {
+    let mut options = crate::preferences::load().startup;
+    options.launch_at_login = login_item_registered();
+    options
+}
+
+/// Apply the options at launch (setup hook)
+pub fn apply(app: &AppHandle, model_path: String) {
+    let options = crate::preferences::load().startup;
+    if let Some(window) = app.get_window("main") {
+        if options.start_minimized {
+            info!("🗕 Starting minimized to the tray");
+            let _ = window.hide();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+    if options.auto_check_devices {
+        let handle = app.clone();
+        std::thread::spawn(move || {
+            let check = check_devices();
+            for problem in &check.problems {
+                warn!("⚠️ Startup device check: {}", problem);
+            }
+            if let Err(e) = handle.emit_all("startup_device_check", check) {
+                error!("Failed to emit startup_device_check: {:?}", e);
+            }
+        });
+    }
+    if options.standby_capture {
+        let handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match crate::vosk_transcription::enter_transcription_standby(handle, model_path, None).await {
+                Ok(_) => info!("💤 Warm standby entered at startup"),
+                Err(e) => warn!("⚠️ Startup standby failed: {}", e),
+            }
+        });
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_startup_options() -> Result<StartupOptions, String> {
+    Ok(current())
+}
+
+// Window and standby options apply from the next launch; launch_at_login at once
+#[tauri::command]
+pub fn set_startup_options(options: StartupOptions) -> Result<StartupOptions, String> {
+    if options.launch_at_login != login_item_registered() {
+        set_login_item(options.launch_at_login)?;
+        info!("🚀 Launch at login {}", if options.launch_at_login { "registered" } else { "removed" });
+    }
+    crate::preferences::update(|p| p.startup = options.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(current())
+}
+
+// Run the device check now (as at startup)
+#[tauri::command]
+pub fn check_startup_devices() -> Result<StartupDeviceCheck, String> {
+    Ok(check_devices())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_default_to_a_visible_start_with_a_device_check() {
+        // Preference files from before the startup options
+        let options: StartupOptions = serde_json::from_str("{}").unwrap();
+        assert!(!options.start_minimized && !options.standby_capture && !options.launch_at_login);
+        assert!(options.auto_check_devices);
+
+        #[cfg(target_os = "linux")]
+        assert!(desktop_entry("\"/opt/VoiceCoach/voicecoach\"").contains("\nExec=\"/opt/VoiceCoach/voicecoach\"\n"));
+    }
+}
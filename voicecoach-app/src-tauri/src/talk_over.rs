@@ -0,0 +1,44 @@
+// Talk Over Commands - interruption tracking wired to the app's settings and windows
+// Framing and detection are in voicecoach-core (re-exported here under the old path);
+// `tracker` builds a capture stream's tracker from preferences (settings and the
+// source's level calibration), with nudges going to the windows and timed on the
+// session's transcript clock.
+
+pub use voicecoach_core::talk_over::*;
+
+use std::sync::Arc;
+use tauri::AppHandle;
+
+use crate::event_sink::AppEvents;
+
+/// A tracker for the rep's (`is_user`) or the prospect's stream (None when detection is off)
+pub fn tracker(app: &AppHandle, is_user: bool, sample_rate: u32) -> Option<TalkOverTracker> {
+    let preferences = crate::preferences::load();
+    let settings = preferences.talk_over;
+    if !settings.enabled {
+        return None;
+    }
+    let calibration = if is_user { preferences.level_calibration.microphone } else { preferences.level_calibration.system_audio };
+    Some(TalkOverTracker::new(Arc::new(AppEvents(app.clone())), crate::session_store::offset_of, is_user, sample_rate,
+        settings, calibration.map(|c| c.vad_threshold)))
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_talk_over_settings() -> Result<TalkOverSettings, String> {
+    Ok(crate::preferences::load().talk_over)
+}
+
+// Applies to capture streams started from now on
+#[tauri::command]
+pub fn set_talk_over_settings(settings: TalkOverSettings) -> Result<TalkOverSettings, String> {
+    crate::preferences::update(|p| p.talk_over = settings.clone()).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+// Interruptions in the call so far
+#[tauri::command]
+pub fn get_talk_over_count() -> Result<usize, String> {
+    Ok(interruptions())
+}
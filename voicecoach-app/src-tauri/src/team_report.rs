@@ -0,0 +1,248 @@
+// Team Report - coaching program report across reps and sessions
+// Sales managers running a coaching program collect each rep's session files (the
+// rep's sessions folder, shared or copied) and generate_team_report aggregates the
+// sessions that started in a date range, per rep and for the team: scorecards (the
+// session template rubric score), checklist completion, objection handling (objections
+// per call, how calls with objections ended, prospect questions left unanswered),
+// talk ratio and how reps rated their coaching prompts. The report is written twice:
+// as JSON for other tooling and rendered as Markdown for reading and sharing. A member
+// without a folder is this machine's own sessions.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use log::info;
+
+use crate::session_store::{CallOutcome, PromptRating, Session};
+
+const OUTCOMES: [CallOutcome; 4] = [CallOutcome::Won, CallOutcome::Lost, CallOutcome::FollowUp, CallOutcome::NoDecision];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TeamMember {
+    pub name: String,
+    /// Folder of the rep's session files; None = this machine's sessions
+    #[serde(default)]
+    pub sessions_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct OutcomeCount {
+    pub outcome: CallOutcome,
+    pub count: usize,
+}
+
+// Aggregates of one rep (or the whole team); averages are None without data
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RepScorecard {
+    pub rep: String,
+    pub sessions: usize,
+    pub calls: usize,                          // Sessions with call metrics
+    pub talk_minutes: f32,
+    pub avg_rubric_score: Option<f32>,         // 0-1, sessions with a rubric
+    pub checklist_completion: Option<f32>,     // Items completed / items, all calls
+    pub avg_talk_ratio: Option<f32>,           // Rep share of words
+    pub objections: usize,
+    pub objections_per_call: Option<f32>,
+    pub calls_with_objections: usize,
+    pub won_with_objections: usize,            // Of calls_with_objections
+    pub question_answer_rate: Option<f32>,     // Prospect questions answered
+    pub helpful_prompt_share: Option<f32>,     // Of rated prompts
+    pub outcomes: Vec<OutcomeCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TeamReport {
+    pub generated_at: u64,
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub team: RepScorecard,
+    pub reps: Vec<RepScorecard>,
+    pub json_path: String,
+    pub markdown_path: String,
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f32)
+}
+
+fn ratio(part: usize, whole: usize) -> Option<f32> {
+    (whole > 0).then(|| part as f32 / whole as f32)
+}
+
+fn scorecard(rep: &str, sessions: &[&Session]) -> RepScorecard {
+    let calls: Vec<(&Session, &crate::call_analytics::CallMetrics)> = sessions.iter()
+        .filter_map(|s| s.metrics.as_ref().map(|m| (*s, m)))
+        .collect();
+    let objection_calls: Vec<&Session> = calls.iter().filter(|(_, m)| m.objections > 0).map(|(s, _)| *s).collect();
+    let objections: usize = calls.iter().map(|(_, m)| m.objections).sum();
+    let questions: usize = calls.iter().map(|(_, m)| m.prospect_questions).sum();
+    let unanswered: usize = calls.iter().map(|(_, m)| m.unanswered_questions).sum();
+    let (completed, total) = calls.iter().fold((0, 0), |(c, t), (_, m)| (c + m.checklist_completed, t + m.checklist_total));
+    let rated = |rating: PromptRating| sessions.iter().flat_map(|s| &s.prompts).filter(|p| p.rating == Some(rating)).count();
+    let (helpful, unhelpful) = (rated(PromptRating::Helpful), rated(PromptRating::Unhelpful));
+    RepScorecard {
+        rep: rep.to_string(),
+        sessions: sessions.len(),
+        calls: calls.len(),
+        talk_minutes: calls.iter().map(|(s, m)| m.updated_at.saturating_sub(s.started_at) as f32 / 60_000.0).sum(),
+        avg_rubric_score: mean(calls.iter().filter_map(|(s, m)| crate::session_templates::rubric_score(&s.rubric, m))),
+        checklist_completion: ratio(completed, total),
+        avg_talk_ratio: mean(calls.iter()
+            .filter(|(_, m)| m.talk_ratio.rep_words + m.talk_ratio.prospect_words > 0)
+            .map(|(_, m)| m.talk_ratio.rep_share)),
+        objections,
+        objections_per_call: ratio(objections, calls.len()),
+        calls_with_objections: objection_calls.len(),
+        won_with_objections: objection_calls.iter().filter(|s| s.outcome == Some(CallOutcome::Won)).count(),
+        question_answer_rate: ratio(questions.saturating_sub(unanswered), questions),
+        helpful_prompt_share: ratio(helpful, helpful + unhelpful),
+        outcomes: OUTCOMES.iter()
+            .map(|&outcome| OutcomeCount { outcome, count: sessions.iter().filter(|s| s.outcome == Some(outcome)).count() })
+            .filter(|o| o.count > 0)
+            .collect(),
+    }
+}
+
+fn percent(value: Option<f32>) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.0}%", v * 100.0))
+}
+
+fn date(ms: Option<u64>) -> String {
+    ms.and_then(|ms| chrono::DateTime::from_timestamp_millis(ms as i64))
+        .map_or("…".to_string(), |t| t.format("%Y-%m-%d").to_string())
+}
+
+fn render_markdown(report: &TeamReport) -> String {
+    let mut md = format!("# Coaching program report\n\n{} to {} · {} reps · {} sessions\n\n",
+        date(report.from), date(report.to), report.reps.len(), report.team.sessions);
+
+    md.push_str("## Scorecards\n\n");
+    md.push_str("| Rep | Sessions | Calls | Minutes | Rubric score | Checklist | Talk ratio | Helpful prompts |\n");
+    md.push_str("|---|---:|---:|---:|---:|---:|---:|---:|\n");
+    for card in report.reps.iter().chain(std::iter::once(&report.team)) {
+        md.push_str(&format!("| {} | {} | {} | {:.0} | {} | {} | {} | {} |\n",
+            card.rep.replace('|', "\\|"), card.sessions, card.calls, card.talk_minutes, percent(card.avg_rubric_score),
+            percent(card.checklist_completion), percent(card.avg_talk_ratio), percent(card.helpful_prompt_share)));
+    }
+
+    md.push_str("\n## Objection handling\n\n");
+    md.push_str("| Rep | Objections | Per call | Calls with objections | Won | Questions answered |\n");
+    md.push_str("|---|---:|---:|---:|---:|---:|\n");
+    for card in report.reps.iter().chain(std::iter::once(&report.team)) {
+        md.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+            card.rep.replace('|', "\\|"), card.objections,
+            card.objections_per_call.map_or("-".to_string(), |v| format!("{:.1}", v)),
+            card.calls_with_objections, card.won_with_objections, percent(card.question_answer_rate)));
+    }
+
+    md.push_str("\n## Outcomes\n\n");
+    for card in report.reps.iter().chain(std::iter::once(&report.team)) {
+        let outcomes: Vec<String> = card.outcomes.iter()
+            .map(|o| format!("{} {}", serde_json::to_value(o.outcome).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default(), o.count))
+            .collect();
+        md.push_str(&format!("- **{}**: {}\n", card.rep, if outcomes.is_empty() { "none recorded".to_string() } else { outcomes.join(", ") }));
+    }
+    md
+}
+
+fn member_sessions(member: &TeamMember) -> Result<Vec<Session>> {
+    match &member.sessions_dir {
+        Some(dir) => crate::session_store::sessions_in(Path::new(dir)),
+        None => Ok(crate::session_store::all_sessions()),
+    }
+}
+
+fn write(report: &TeamReport) -> Result<()> {
+    fs::write(&report.json_path, serde_json::to_string_pretty(report)?)
+        .context(format!("Failed to write {}", report.json_path))?;
+    fs::write(&report.markdown_path, render_markdown(report))
+        .context(format!("Failed to write {}", report.markdown_path))?;
+    Ok(())
+}
+
+// ========== Tauri Commands ==========
+
+// Report over the members' sessions started in [from, to), written to `path` (.json)
+// and beside it as .md
+#[tauri::command]
+pub fn generate_team_report(members: Vec<TeamMember>, from: Option<u64>, to: Option<u64>, path: String) -> Result<TeamReport, String> {
+    if members.is_empty() {
+        return Err("Add at least one rep to the report".to_string());
+    }
+    if let (Some(from), Some(to)) = (from, to) {
+        if from >= to {
+            return Err("The start of the range must be before its end".to_string());
+        }
+    }
+    let mut per_rep: Vec<(String, Vec<Session>)> = Vec::new();
+    for member in &members {
+        let sessions = member_sessions(member).map_err(|e| format!("{}: {}", member.name, e))?;
+        per_rep.push((member.name.clone(), sessions.into_iter()
+            .filter(|s| from.map_or(true, |f| s.started_at >= f) && to.map_or(true, |t| s.started_at < t))
+            .collect()));
+    }
+
+    let all: Vec<&Session> = per_rep.iter().flat_map(|(_, sessions)| sessions).collect();
+    let base = Path::new(&path);
+    let report = TeamReport {
+        generated_at: chrono::Utc::now().timestamp_millis() as u64,
+        from,
+        to,
+        team: scorecard("Team", &all),
+        reps: per_rep.iter().map(|(rep, sessions)| scorecard(rep, &sessions.iter().collect::<Vec<_>>())).collect(),
+        json_path: base.with_extension("json").to_string_lossy().to_string(),
+        markdown_path: base.with_extension("md").to_string_lossy().to_string(),
+    };
+    write(&report).map_err(|e| e.to_string())?;
+    info!("📋 Team report over {} sessions of {} reps written to {}", report.team.sessions, report.reps.len(), report.json_path);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::call_analytics::CallMetrics;
+
+    fn session(objections: usize, outcome: Option<CallOutcome>) -> Session {
+        let metrics = CallMetrics {
+            objections,
+            prospect_questions: 4,
+            unanswered_questions: 1,
+            checklist_completed: 1,
+            checklist_total: 2,
+            updated_at: 1_700_000_600_000,
+            ..Default::default()
+        };
+        let mut session: Session = serde_json::from_value(serde_json::json!({
+            "id": "20231114-221320",
+            "started_at": 1_700_000_000_000u64,
+        })).unwrap();
+        session.metrics = Some(metrics);
+        session.outcome = outcome;
+        session
+    }
+
+    #[test]
+    fn test_scorecard_aggregates_objection_handling_and_renders() {
+        let sessions = [session(2, Some(CallOutcome::Won)), session(0, Some(CallOutcome::Lost)), session(1, None)];
+        let card = scorecard("Dana", &sessions.iter().collect::<Vec<_>>());
+        assert_eq!((card.calls, card.objections, card.calls_with_objections, card.won_with_objections), (3, 3, 2, 1));
+        assert_eq!(card.objections_per_call, Some(1.0));
+        assert_eq!(card.checklist_completion, Some(0.5));
+        assert_eq!(card.question_answer_rate, Some(0.75));
+        assert_eq!(card.helpful_prompt_share, None);
+        assert!((card.talk_minutes - 30.0).abs() < 0.01);
+
+        let report = TeamReport {
+            generated_at: 0, from: None, to: None, team: scorecard("Team", &[]), reps: vec![card],
+            json_path: String::new(), markdown_path: String::new(),
+        };
+        let md = render_markdown(&report);
+        assert!(md.contains("| Dana | 3 | 3 | 30 | - | 50% | - | - |"));
+        assert!(md.contains("| Dana | 3 | 1.0 | 2 | 1 | 75% |"));
+        assert!(md.contains("- **Dana**: won 1, lost 1"));
+        assert!(md.contains("- **Team**: none recorded"));
+    }
+}
@@ -0,0 +1,350 @@
+// Telemetry - optional OpenTelemetry export of pipeline metrics (OTLP/HTTP, JSON)
+// For fleet deployments: per-stage latency histograms (capture -> transcribe, and
+// statement -> coaching prompt), error counts per component and engine usage, plus
+// one trace span per coaching generation. Off unless the vosk-config "telemetry"
+// section enables it; nothing is recorded or sent otherwise. Metrics are cumulative
+// since launch, so a missed export loses no data.
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+// Latency bucket bounds (ms)
+const LATENCY_BOUNDS_MS: &[f64] = &[5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0];
+// Finished spans kept between exports
+const MAX_PENDING_SPANS: usize = 500;
+
+// vosk-config "telemetry" section
+#[derive(Serialize, Deserialize, Clone, Debug, specta::Type)]
+pub struct TelemetrySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// OTLP/HTTP base URL; /v1/metrics and /v1/traces are appended
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    #[serde(default = "default_export_interval_secs")]
+    pub export_interval_secs: u64,
+    #[serde(default = "default_true")]
+    pub traces: bool,
+    /// Extra request headers (e.g. collector auth)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Reported as service.instance.id (defaults to the host name)
+    #[serde(default)]
+    pub instance_id: Option<String>,
+}
+
+fn default_endpoint() -> String { "http://localhost:4318".to_string() }
+fn default_export_interval_secs() -> u64 { 30 }
+fn default_true() -> bool { true }
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_endpoint(),
+            export_interval_secs: default_export_interval_secs(),
+            traces: true,
+            headers: HashMap::new(),
+            instance_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Transcribe,  // Audio captured -> final segment available
+    Coach,       // Statement submitted -> coaching prompt ready
+}
+
+impl Stage {
+    fn name(self) -> &'static str {
+        match self {
+            Stage::Transcribe => "transcribe",
+            Stage::Coach => "coach",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    bucket_counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn record(&mut self, value: f64) {
+        if self.bucket_counts.is_empty() {
+            self.bucket_counts = vec![0; LATENCY_BOUNDS_MS.len() + 1];
+        }
+        let bucket = LATENCY_BOUNDS_MS.iter().position(|&bound| value <= bound).unwrap_or(LATENCY_BOUNDS_MS.len());
+        self.bucket_counts[bucket] += 1;
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Span {
+    trace_id: String,
+    span_id: String,
+    name: String,
+    start_ns: u64,
+    end_ns: u64,
+    attributes: Vec<(String, String)>,
+    ok: bool,
+}
+
+#[derive(Default)]
+struct Metrics {
+    latencies: BTreeMap<(Stage, String), Histogram>,  // (stage, engine)
+    errors: BTreeMap<String, u64>,                    // component
+    usage: BTreeMap<String, u64>,                     // engine or coaching source
+    spans: Vec<Span>,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TRACES: AtomicBool = AtomicBool::new(false);
+static START_NS: Lazy<u64> = Lazy::new(now_ns);
+static EXPORTS: AtomicU64 = AtomicU64::new(0);
+static METRICS: Lazy<Mutex<Metrics>> = Lazy::new(|| Mutex::new(Metrics::default()));
+static LAST_ERROR: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+fn now_ns() -> u64 {
+    chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0) as u64
+}
+
+fn random_hex(bytes: usize) -> String {
+    (0..bytes).map(|_| format!("{:02x}", rand::random::<u8>())).collect()
+}
+
+/// Record a stage latency for an engine ("vosk", "deepgram", "ollama", ...)
+pub fn record_latency(stage: Stage, engine: &str, ms: f64) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    METRICS.lock().unwrap().latencies.entry((stage, engine.to_string())).or_default().record(ms);
+}
+
+/// Count an error in a pipeline component
+pub fn record_error(component: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    *METRICS.lock().unwrap().errors.entry(component.to_string()).or_insert(0) += 1;
+}
+
+/// Count one unit of work handled by an engine (final segment, coaching prompt)
+pub fn record_usage(engine: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    *METRICS.lock().unwrap().usage.entry(engine.to_string()).or_insert(0) += 1;
+}
+
+/// Record a finished span that started `duration` ago
+pub fn record_span(name: &str, duration: Duration, attributes: &[(&str, &str)], ok: bool) {
+    if !ENABLED.load(Ordering::Relaxed) || !TRACES.load(Ordering::Relaxed) {
+        return;
+    }
+    let end_ns = now_ns();
+    let mut metrics = METRICS.lock().unwrap();
+    if metrics.spans.len() >= MAX_PENDING_SPANS {
+        metrics.spans.remove(0);
+    }
+    metrics.spans.push(Span {
+        trace_id: random_hex(16),
+        span_id: random_hex(8),
+        name: name.to_string(),
+        start_ns: end_ns.saturating_sub(duration.as_nanos() as u64),
+        end_ns,
+        attributes: attributes.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        ok,
+    });
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({"key": key, "value": {"stringValue": value}})
+}
+
+fn resource(settings: &TelemetrySettings) -> Value {
+    let instance = settings.instance_id.clone()
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| "unknown".to_string());
+    json!({"attributes": [
+        attribute("service.name", "voicecoach"),
+        attribute("service.version", env!("CARGO_PKG_VERSION")),
+        attribute("service.instance.id", &instance),
+    ]})
+}
+
+fn scope() -> Value {
+    json!({"name": "voicecoach", "version": env!("CARGO_PKG_VERSION")})
+}
+
+fn counter(name: &str, key: &str, values: &BTreeMap<String, u64>, start: &str, now: &str) -> Value {
+    let points: Vec<Value> = values.iter().map(|(label, count)| json!({
+        "attributes": [attribute(key, label)],
+        "startTimeUnixNano": start,
+        "timeUnixNano": now,
+        "asInt": count.to_string(),
+    })).collect();
+    // aggregationTemporality 2 = cumulative
+    json!({"name": name, "unit": "1", "sum": {"aggregationTemporality": 2, "isMonotonic": true, "dataPoints": points}})
+}
+
+// OTLP JSON body for /v1/metrics
+fn metrics_body(metrics: &Metrics, settings: &TelemetrySettings, now_ns: u64) -> Value {
+    let (start, now) = (START_NS.to_string(), now_ns.to_string());
+    let latency_points: Vec<Value> = metrics.latencies.iter().map(|((stage, engine), histogram)| json!({
+        "attributes": [attribute("stage", stage.name()), attribute("engine", engine)],
+        "startTimeUnixNano": start,
+        "timeUnixNano": now,
+        "count": histogram.count.to_string(),
+        "sum": histogram.sum,
+        "bucketCounts": histogram.bucket_counts.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+        "explicitBounds": LATENCY_BOUNDS_MS,
+    })).collect();
+    json!({"resourceMetrics": [{
+        "resource": resource(settings),
+        "scopeMetrics": [{
+            "scope": scope(),
+            "metrics": [
+                {"name": "voicecoach.pipeline.latency", "unit": "ms",
+                 "histogram": {"aggregationTemporality": 2, "dataPoints": latency_points}},
+                counter("voicecoach.errors", "component", &metrics.errors, &start, &now),
+                counter("voicecoach.engine.usage", "engine", &metrics.usage, &start, &now),
+            ],
+        }],
+    }]})
+}
+
+// OTLP JSON body for /v1/traces
+fn traces_body(spans: &[Span], settings: &TelemetrySettings) -> Value {
+    let spans: Vec<Value> = spans.iter().map(|span| json!({
+        "traceId": span.trace_id,
+        "spanId": span.span_id,
+        "name": span.name,
+        "kind": 1,
+        "startTimeUnixNano": span.start_ns.to_string(),
+        "endTimeUnixNano": span.end_ns.to_string(),
+        "attributes": span.attributes.iter().map(|(k, v)| attribute(k, v)).collect::<Vec<_>>(),
+        "status": {"code": if span.ok { 1 } else { 2 }},
+    })).collect();
+    json!({"resourceSpans": [{
+        "resource": resource(settings),
+        "scopeSpans": [{"scope": scope(), "spans": spans}],
+    }]})
+}
+
+fn post(client: &reqwest::blocking::Client, settings: &TelemetrySettings, path: &str, body: &Value) -> Result<()> {
+    let url = format!("{}{}", settings.endpoint.trim_end_matches('/'), path);
+    let mut request = client.post(&url).json(body);
+    for (name, value) in &settings.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = request.send().context(format!("OTLP export to {} failed", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("OTLP export to {} returned {}", url, response.status());
+    }
+    Ok(())
+}
+
+fn export(client: &reqwest::blocking::Client, settings: &TelemetrySettings) -> Result<()> {
+    let (metrics, spans) = {
+        let mut metrics = METRICS.lock().unwrap();
+        let spans = std::mem::take(&mut metrics.spans);
+        (metrics_body(&metrics, settings, now_ns()), spans)
+    };
+    post(client, settings, "/v1/metrics", &metrics)?;
+    if !spans.is_empty() {
+        post(client, settings, "/v1/traces", &traces_body(&spans, settings))?;
+    }
+    EXPORTS.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Start recording and the export loop if the config enables telemetry
+pub fn start_exporter() {
+    let settings = crate::vosk_transcription::configured_telemetry();
+    if !settings.enabled {
+        return;
+    }
+    Lazy::force(&START_NS);
+    TRACES.store(settings.traces, Ordering::Relaxed);
+    ENABLED.store(true, Ordering::Relaxed);
+    info!("📡 OpenTelemetry export to {} every {}s", settings.endpoint, settings.export_interval_secs);
+
+    std::thread::spawn(move || {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        loop {
+            std::thread::sleep(Duration::from_secs(settings.export_interval_secs.max(5)));
+            let result = export(&client, &settings);
+            let mut last_error = LAST_ERROR.lock().unwrap();
+            match result {
+                Ok(()) => *last_error = None,
+                Err(e) => {
+                    // Log once per outage, not every interval
+                    if last_error.is_none() {
+                        warn!("⚠️ {}", e);
+                    }
+                    *last_error = Some(e.to_string());
+                }
+            }
+        }
+    });
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub exports: u64,
+    pub last_error: Option<String>,
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_telemetry_status() -> Result<TelemetryStatus, String> {
+    let settings = crate::vosk_transcription::configured_telemetry();
+    Ok(TelemetryStatus {
+        enabled: ENABLED.load(Ordering::Relaxed),
+        endpoint: settings.endpoint,
+        exports: EXPORTS.load(Ordering::Relaxed),
+        last_error: LAST_ERROR.lock().unwrap().clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram_is_exported_as_otlp_json() {
+        let mut metrics = Metrics::default();
+        let histogram = metrics.latencies.entry((Stage::Transcribe, "vosk".to_string())).or_default();
+        histogram.record(40.0);
+        histogram.record(20000.0);
+        metrics.errors.insert("deepgram".to_string(), 2);
+
+        let body = metrics_body(&metrics, &TelemetrySettings::default(), 1);
+        let exported = &body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+        let point = &exported[0]["histogram"]["dataPoints"][0];
+        assert_eq!(point["count"], "2");
+        assert_eq!(point["bucketCounts"][3], "1");   // <= 50 ms
+        assert_eq!(point["bucketCounts"][11], "1");  // Overflow bucket
+        assert_eq!(exported[1]["sum"]["dataPoints"][0]["asInt"], "2");
+    }
+}
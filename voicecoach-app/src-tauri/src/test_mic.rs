@@ -43,8 +43,11 @@ pub fn test_microphone_access() -> Result<String, String> {
     let max_amplitude = Arc::new(AtomicU32::new(0));
     let max_amplitude_clone = max_amplitude.clone();
     
-    let stream = device.build_input_stream(
+    let sample_format = config.sample_format();
+    let stream = crate::sample_format::build_input_stream(
+        &device,
         &config.into(),
+        sample_format,
         move |data: &[f32], _: &cpal::InputCallbackInfo| {
             sample_count_clone.fetch_add(data.len() as u32, Ordering::Relaxed);
             
@@ -60,7 +63,6 @@ pub fn test_microphone_access() -> Result<String, String> {
             }
         },
         |err| eprintln!("Stream error: {}", err),
-        None
     ).map_err(|e| format!("Failed to build stream: {}", e))?;
     
     // Start the stream
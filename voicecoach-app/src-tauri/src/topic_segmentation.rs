@@ -0,0 +1,342 @@
+// Topic Segmentation - splits the call transcript into labeled chapters
+// Final transcript lines are kept for the call in progress. Every few lines the
+// transcript is re-segmented in the background: each line is embedded (Ollama
+// embedding model, or hashed bag-of-words vectors when Ollama is unavailable), the
+// similarity between the windows before and after every line gap is computed, and
+// gaps where similarity dips deepest become chapter boundaries (TextTiling-style
+// change-point detection). Chapters are labeled ("pricing discussion") by the local
+// LLM or from their most frequent keywords, stored with the session, emitted as
+// "topic_chapters" and included in the call summary. Chapter offsets are relative
+// to the session start, so playback can seek to them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+use crate::ollama_integration::OllamaCoachingService;
+
+pub(crate) const EMBEDDING_MODEL: &str = "nomic-embed-text";
+// Lines averaged on each side of a gap
+const WINDOW_LINES: usize = 4;
+// Shortest chapter, in transcript lines
+const MIN_CHAPTER_LINES: usize = 6;
+// Re-segment after this many new lines
+const RESEGMENT_EVERY_LINES: usize = 10;
+// Similarity dips shallower than this are never boundaries
+const MIN_DEPTH: f32 = 0.1;
+// Dimensions of the bag-of-words fallback vectors
+const LEXICAL_DIMS: usize = 512;
+const MAX_LABEL_CHARS: usize = 40;
+// Chapter text passed to the labeler
+const MAX_LABEL_PROMPT_CHARS: usize = 1500;
+
+const STOPWORDS: &[&str] = &[
+    "that", "this", "with", "have", "from", "they", "them", "there", "their", "what",
+    "when", "where", "which", "would", "could", "should", "about", "just", "like", "yeah",
+    "know", "think", "really", "right", "okay", "going", "want", "well", "will", "your",
+    "been", "were", "then", "than", "some", "also", "because", "actually", "mean",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TopicChapter {
+    pub index: usize,
+    pub label: String,
+    pub start_ms: u64,     // Offset from the session start
+    pub end_ms: u64,
+    pub first_line: usize, // Transcript lines covered (inclusive)
+    pub last_line: usize,
+}
+
+struct Line {
+    offset_ms: u64,
+    text: String,
+    embedding: Option<Vec<f32>>,  // From the Ollama embedding model
+}
+
+#[derive(Default)]
+struct Transcript {
+    lines: Vec<Line>,
+    segmented_at: usize,  // Line count at the last segmentation
+    chapters: Vec<TopicChapter>,
+    labels: HashMap<(usize, usize), String>,  // Cached by line range
+}
+
+static TRANSCRIPT: Lazy<Mutex<Transcript>> = Lazy::new(|| Mutex::new(Transcript::default()));
+// Bumped on every call start so stale background runs are discarded
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static SEGMENTING: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn keywords(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| w.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase())
+        .filter(|w| w.len() > 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// FNV-1a, stable across runs (unlike the std hasher)
+fn fnv1a(word: &str) -> u64 {
+    word.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Hashed bag-of-words vector (used when no embedding model is available)
+pub(crate) fn lexical_vector(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; LEXICAL_DIMS];
+    for word in keywords(text) {
+        vector[(fnv1a(&word) % LEXICAL_DIMS as u64) as usize] += 1.0;
+    }
+    vector
+}
+
+pub(crate) fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 { 0.0 } else { dot / (norm_a * norm_b) }
+}
+
+fn mean(vectors: &[Vec<f32>]) -> Vec<f32> {
+    let mut sum = vec![0.0; vectors.first().map_or(0, |v| v.len())];
+    for vector in vectors {
+        for (s, x) in sum.iter_mut().zip(vector) {
+            *s += x;
+        }
+    }
+    sum
+}
+
+/// Chapter boundaries (index of the first line of each chapter after the first)
+fn boundaries(vectors: &[Vec<f32>], window: usize, min_len: usize) -> Vec<usize> {
+    let n = vectors.len();
+    if n < min_len * 2 {
+        return Vec::new();
+    }
+    // Similarity across the gap before line g, for g in 1..n
+    let scores: Vec<f32> = (1..n)
+        .map(|g| cosine(&mean(&vectors[g.saturating_sub(window)..g]), &mean(&vectors[g..(g + window).min(n)])))
+        .collect();
+    // Depth of each dip: how far similarity climbs back on both sides
+    let depths: Vec<f32> = (0..scores.len())
+        .map(|i| {
+            let mut left = scores[i];
+            for &s in scores[..i].iter().rev() {
+                if s < left { break; }
+                left = s;
+            }
+            let mut right = scores[i];
+            for &s in &scores[i + 1..] {
+                if s < right { break; }
+                right = s;
+            }
+            (left - scores[i]) + (right - scores[i])
+        })
+        .collect();
+    let average = depths.iter().sum::<f32>() / depths.len() as f32;
+    let spread = (depths.iter().map(|d| (d - average).powi(2)).sum::<f32>() / depths.len() as f32).sqrt();
+    let cutoff = (average + spread / 2.0).max(MIN_DEPTH);
+
+    let mut candidates: Vec<(usize, f32)> = depths.iter().enumerate()
+        .filter(|(_, &d)| d >= cutoff)
+        .map(|(i, &d)| (i + 1, d))
+        .collect();
+    candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let mut chosen: Vec<usize> = Vec::new();
+    for (gap, _) in candidates {
+        let fits = gap >= min_len && n - gap >= min_len && chosen.iter().all(|&c| c.max(gap) - c.min(gap) >= min_len);
+        if fits {
+            chosen.push(gap);
+        }
+    }
+    chosen.sort_unstable();
+    chosen
+}
+
+/// Label from the chapter's most frequent keywords
+fn keyword_label(texts: &[String]) -> String {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in texts.iter().flat_map(|t| keywords(t)) {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let top: Vec<String> = ranked.into_iter().take(2).map(|(w, _)| w).collect();
+    if top.is_empty() { "small talk".to_string() } else { top.join(", ") }
+}
+
+async fn llm_label(service: &OllamaCoachingService, texts: &[String]) -> Option<String> {
+    let mut excerpt = texts.join("\n");
+    if let Some((end, _)) = excerpt.char_indices().nth(MAX_LABEL_PROMPT_CHARS) {
+        excerpt.truncate(end);
+    }
+    let prompt = format!(
+        "Give a short title (2-4 words, lowercase) for this part of a sales call, like \
+         \"pricing discussion\" or \"technical questions\". Reply with the title only.\n\n{}\n",
+        excerpt
+    );
+    let reply = service.complete(prompt, 0.2, 16, 30).await.ok()?;
+    let label = reply.lines().next().unwrap_or("").trim().trim_matches(|c| c == '"' || c == '.').to_lowercase();
+    (!label.is_empty()).then(|| label.chars().take(MAX_LABEL_CHARS).collect())
+}
+
+async fn segment(app: AppHandle) {
+    let generation = GENERATION.load(Ordering::SeqCst);
+    let (texts, offsets, mut embeddings, mut labels) = {
+        let transcript = TRANSCRIPT.lock().unwrap();
+        (
+            transcript.lines.iter().map(|l| l.text.clone()).collect::<Vec<_>>(),
+            transcript.lines.iter().map(|l| l.offset_ms).collect::<Vec<_>>(),
+            transcript.lines.iter().map(|l| l.embedding.clone()).collect::<Vec<_>>(),
+            transcript.labels.clone(),
+        )
+    };
+
+    let service = OllamaCoachingService::new();
+    let ollama = service.check_availability().await.unwrap_or(false);
+    if ollama {
+        for (text, embedding) in texts.iter().zip(embeddings.iter_mut()).filter(|(_, e)| e.is_none()) {
+            match service.embed(EMBEDDING_MODEL, text).await {
+                Ok(vector) => *embedding = Some(vector),
+                Err(e) => {
+                    warn!("⚠️ Embedding failed, segmenting by keywords: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+    let vectors: Vec<Vec<f32>> = if embeddings.iter().all(|e| e.is_some()) {
+        embeddings.iter().flatten().cloned().collect()
+    } else {
+        texts.iter().map(|t| lexical_vector(t)).collect()
+    };
+
+    let mut starts = vec![0];
+    starts.extend(boundaries(&vectors, WINDOW_LINES, MIN_CHAPTER_LINES));
+    let mut chapters = Vec::new();
+    for (index, &first) in starts.iter().enumerate() {
+        let last = starts.get(index + 1).map_or(texts.len(), |&next| next) - 1;
+        let label = match labels.get(&(first, last)) {
+            Some(label) => label.clone(),
+            None => {
+                let chapter_texts = &texts[first..=last];
+                let label = if ollama { llm_label(&service, chapter_texts).await } else { None };
+                let label = label.unwrap_or_else(|| keyword_label(chapter_texts));
+                labels.insert((first, last), label.clone());
+                label
+            }
+        };
+        chapters.push(TopicChapter {
+            index,
+            label,
+            start_ms: offsets[first],
+            end_ms: offsets[last],
+            first_line: first,
+            last_line: last,
+        });
+    }
+
+    {
+        let mut transcript = TRANSCRIPT.lock().unwrap();
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            return;  // A new call started meanwhile
+        }
+        for (line, embedding) in transcript.lines.iter_mut().zip(embeddings) {
+            if line.embedding.is_none() {
+                line.embedding = embedding;
+            }
+        }
+        transcript.labels = labels;
+        transcript.chapters = chapters.clone();
+    }
+
+    info!("📑 Transcript segmented into {} chapter(s)", chapters.len());
+    if let Some(session_id) = crate::session_store::current_session_id() {
+        if let Err(e) = crate::session_store::attach_chapters(&session_id, chapters.clone()) {
+            warn!("⚠️ Failed to store topic chapters: {}", e);
+        }
+    }
+    if let Err(e) = app.emit_all("topic_chapters", chapters) {
+        error!("Failed to emit topic_chapters: {:?}", e);
+    }
+}
+
+fn spawn_segmentation(app: AppHandle) -> bool {
+    if SEGMENTING.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    {
+        let mut transcript = TRANSCRIPT.lock().unwrap();
+        transcript.segmented_at = transcript.lines.len();
+    }
+    tauri::async_runtime::spawn(async move {
+        segment(app).await;
+        SEGMENTING.store(false, Ordering::SeqCst);
+    });
+    true
+}
+
+/// Start of a new call
+pub fn begin_call() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    *TRANSCRIPT.lock().unwrap() = Transcript::default();
+}
+
+/// Feed a final transcript line; re-segments in the background every few lines
+pub fn observe(app: &AppHandle, text: &str) {
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let offset_ms = now.saturating_sub(crate::session_store::current_started_at().unwrap_or(now));
+    let due = {
+        let mut transcript = TRANSCRIPT.lock().unwrap();
+        transcript.lines.push(Line { offset_ms, text: text.to_string(), embedding: None });
+        transcript.lines.len() >= MIN_CHAPTER_LINES * 2
+            && transcript.lines.len() - transcript.segmented_at >= RESEGMENT_EVERY_LINES
+    };
+    if due {
+        spawn_segmentation(app.clone());
+    }
+}
+
+/// Chapters of the call in progress (as of the last segmentation)
+pub fn chapters() -> Vec<TopicChapter> {
+    TRANSCRIPT.lock().unwrap().chapters.clone()
+}
+
+// ========== Tauri Commands ==========
+
+// Re-segment the current call now (results arrive as "topic_chapters")
+#[tauri::command]
+pub fn segment_topics(app: AppHandle) -> Result<bool, String> {
+    if TRANSCRIPT.lock().unwrap().lines.is_empty() {
+        return Err("No transcript to segment".to_string());
+    }
+    Ok(spawn_segmentation(app))
+}
+
+// Chapters of a session (the current one when no id is given)
+#[tauri::command]
+pub fn get_topic_chapters(session_id: Option<String>) -> Result<Vec<TopicChapter>, String> {
+    crate::session_store::session_chapters(session_id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boundaries_at_topic_changes() {
+        let pricing = "pricing per seat discount annual contract budget";
+        let tech = "integration salesforce webhooks security single sign";
+        let texts: Vec<&str> = std::iter::repeat(pricing).take(10).chain(std::iter::repeat(tech).take(9)).collect();
+        let vectors: Vec<Vec<f32>> = texts.iter().map(|t| lexical_vector(t)).collect();
+        assert_eq!(boundaries(&vectors, WINDOW_LINES, MIN_CHAPTER_LINES), vec![10]);
+
+        // One topic throughout, or too short to split
+        assert!(boundaries(&vectors[..10], WINDOW_LINES, MIN_CHAPTER_LINES).is_empty());
+        assert!(boundaries(&vectors[5..15], WINDOW_LINES, MIN_CHAPTER_LINES).is_empty());
+
+        let texts = vec!["The pricing works per seat".to_string(), "Pricing for annual contracts".to_string()];
+        assert_eq!(keyword_label(&texts), "pricing, annual");
+    }
+}
@@ -0,0 +1,14 @@
+// Transcript Confidence Commands - a session's confidence heatmap for the UI
+// Grading is in voicecoach-core (re-exported here under the old path).
+
+pub use voicecoach_core::transcript_confidence::*;
+
+// ========== Tauri Commands ==========
+
+// Per-line and per-word confidence of a session's transcript (the current one when no
+// id is given)
+#[tauri::command]
+pub fn get_transcript_confidence(session_id: Option<String>) -> Result<TranscriptConfidence, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    Ok(transcript_confidence(session.id, &session.transcript))
+}
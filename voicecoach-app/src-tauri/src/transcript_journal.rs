@@ -0,0 +1,170 @@
+// Transcript Journal - crash-safe record of final transcript lines
+// The session store keeps a call's transcript in memory and writes the session file
+// only with metrics snapshots. So that a crash never loses more than the line being
+// recognized, every final line is also appended to journal/<session id>.jsonl and
+// flushed to disk before the next one. Once the session file holds the lines (any
+// write of the session, which replaces the file whole via a synced temporary file)
+// the journal is compacted - emptied, or removed for a session
+// no longer in progress; a background pass writes the session in progress every
+// COMPACT_SECS when lines are waiting. On startup journals left behind are replayed
+// into their sessions (lines the session file lacks are appended), so a crashed or
+// killed call keeps its transcript. A line torn by the crash is skipped.
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+use crate::session_store::TranscriptLine;
+
+const JOURNAL_DIR: &str = "journal";
+const COMPACT_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub session_id: String,
+    pub started_at: u64,
+    pub index: usize,  // Position in the session transcript
+    pub line: TranscriptLine,
+}
+
+// Journal of the session in progress, kept open for appends
+static OPEN: Lazy<Mutex<Option<(String, File)>>> = Lazy::new(|| Mutex::new(None));
+// Lines appended since the journal was last compacted
+static PENDING: AtomicUsize = AtomicUsize::new(0);
+
+fn journal_dir() -> PathBuf {
+    crate::app_paths::data_dir().join(JOURNAL_DIR)
+}
+
+fn journal_path(session_id: &str) -> PathBuf {
+    journal_dir().join(format!("{}.jsonl", session_id))
+}
+
+/// Append a line and flush it to disk
+pub fn append(entry: &JournalEntry) -> Result<()> {
+    let mut open = OPEN.lock().unwrap();
+    if open.as_ref().map_or(true, |(id, _)| id != &entry.session_id) {
+        fs::create_dir_all(journal_dir())?;
+        let path = journal_path(&entry.session_id);
+        let file = OpenOptions::new().create(true).append(true).open(&path)
+            .context(format!("Failed to open transcript journal {:?}", path))?;
+        *open = Some((entry.session_id.clone(), file));
+        PENDING.store(0, Ordering::Relaxed);
+    }
+    if let Some((_, file)) = open.as_mut() {
+        let mut record = serde_json::to_string(entry)?;
+        record.push('\n');
+        file.write_all(record.as_bytes())?;
+        file.sync_data()?;
+        PENDING.fetch_add(1, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// The session file now holds every journaled line of `session_id`
+pub fn compacted(session_id: &str) {
+    let mut open = OPEN.lock().unwrap();
+    let result = match open.as_mut() {
+        Some((id, file)) if id == session_id => {
+            PENDING.store(0, Ordering::Relaxed);
+            file.set_len(0)
+        }
+        _ => match fs::remove_file(journal_path(session_id)) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        },
+    };
+    if let Err(e) = result {
+        warn!("⚠️ Failed to compact transcript journal of {}: {}", session_id, e);
+    }
+}
+
+/// Lines journaled since the last compaction
+pub fn pending_lines() -> usize {
+    PENDING.load(Ordering::Relaxed)
+}
+
+/// Entries of a journal file; a torn last record (crash mid-write) ends the replay
+fn parse(contents: &str) -> Vec<JournalEntry> {
+    contents.lines()
+        .map_while(|line| serde_json::from_str::<JournalEntry>(line).ok())
+        .collect()
+}
+
+/// Entries not yet in a transcript of `stored` lines, in transcript order
+pub fn unrecovered(stored: usize, mut entries: Vec<JournalEntry>) -> Vec<JournalEntry> {
+    entries.sort_by_key(|e| e.index);
+    entries.dedup_by_key(|e| e.index);
+    entries.into_iter().filter(|e| e.index >= stored).collect()
+}
+
+/// Journals left by earlier runs: (session id, entries), removing empty ones
+pub fn left_behind() -> Vec<(String, Vec<JournalEntry>)> {
+    let entries = match fs::read_dir(journal_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut journals = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let session_id = match name.strip_suffix(".jsonl") {
+            Some(id) => id.to_string(),
+            None => continue,
+        };
+        let lines = parse(&fs::read_to_string(entry.path()).unwrap_or_default());
+        if lines.is_empty() {
+            let _ = fs::remove_file(entry.path());
+        } else {
+            journals.push((session_id, lines));
+        }
+    }
+    journals
+}
+
+/// Write the session in progress whenever journaled lines are waiting
+pub fn start_compactor() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(Duration::from_secs(COMPACT_SECS));
+        if pending_lines() > 0 {
+            if let Err(e) = crate::session_store::write_current() {
+                warn!("⚠️ Transcript journal compaction failed: {}", e);
+            }
+        }
+    });
+    info!("📓 Transcript journal compaction every {}s", COMPACT_SECS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(index: usize, text: &str) -> JournalEntry {
+        JournalEntry {
+            session_id: "20260101-120000".to_string(),
+            started_at: 0,
+            index,
+            line: TranscriptLine { offset_ms: index as u64 * 1000, is_user: true, text: text.to_string(), words: vec![] },
+        }
+    }
+
+    #[test]
+    fn test_replay_skips_torn_records_and_stored_lines() {
+        let mut journal: String = [entry(0, "hello"), entry(1, "how are you"), entry(2, "fine")].iter()
+            .map(|e| serde_json::to_string(e).unwrap() + "\n")
+            .collect();
+        journal.push_str(r#"{"session_id":"20260101-120000","started_at":0,"ind"#);
+        let entries = parse(&journal);
+        assert_eq!(entries.len(), 3);
+
+        // The session file already had the first line (crash between write and compaction)
+        let replayed: Vec<String> = unrecovered(1, entries).into_iter().map(|e| e.line.text).collect();
+        assert_eq!(replayed, vec!["how are you", "fine"]);
+    }
+}
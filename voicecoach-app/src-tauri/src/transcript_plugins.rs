@@ -0,0 +1,341 @@
+// Transcript Plugins - user WASM modules that rewrite final transcript segments
+// Teams have their own rewrites: expanding in-house abbreviations, masking internal
+// project codenames before a transcript reaches a CRM. A plugin is a WebAssembly module
+// dropped into <app data>/plugins (install_transcript_plugin copies one there). Each
+// workspace (the active session template) can enable its own plugins in its own order,
+// and the install-wide list is used for workspaces without one; the enabled plugins run
+// in order on every final segment, after punctuation and the profanity filter, before
+// it is stored or emitted. Word timings
+// keep the recognized words. The interface is plain memory: the module exports `memory`,
+// `alloc(len) -> ptr` and `transform(ptr, len) -> i64`; the segment is written as UTF-8
+// where alloc points, and the result is read back from (ptr << 32 | len).
+// Plugins are sandboxed: a module that imports anything (host functions, so files,
+// network, clock) is rejected, memory is capped, every segment gets a fresh instance,
+// and execution is metered in fuel sized from the time budget (budget_ms), with the
+// wall time checked as well. A plugin that fails or runs over leaves the segment as it
+// was; after MAX_FAILURES in a row it is switched off until its settings are saved again.
+
+use anyhow::{Result, Context, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use once_cell::sync::Lazy;
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc};
+use log::{info, warn};
+
+const PLUGINS_DIR: &str = "plugins";
+const MAX_MEMORY_BYTES: usize = 16 * 1024 * 1024;
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+// Roughly what the interpreter executes in a millisecond
+const FUEL_PER_MS: u64 = 200_000;
+// A plugin slower than this would hold every final segment back noticeably
+const MAX_BUDGET_MS: u64 = 1_000;
+const MAX_FAILURES: u32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TranscriptPluginSettings {
+    /// Plugin files run on final segments, in this order
+    #[serde(default)]
+    pub enabled: Vec<String>,
+    /// Plugins (in order) per workspace, by session template id; others use `enabled`
+    #[serde(default)]
+    pub workspaces: HashMap<String, Vec<String>>,
+    /// Time budget of each plugin per segment
+    #[serde(default = "default_budget_ms")]
+    pub budget_ms: u64,
+}
+
+fn default_budget_ms() -> u64 { 20 }
+
+impl Default for TranscriptPluginSettings {
+    fn default() -> Self {
+        Self { enabled: Vec::new(), workspaces: HashMap::new(), budget_ms: default_budget_ms() }
+    }
+}
+
+impl TranscriptPluginSettings {
+    /// Plugins enabled in a workspace (None = no active template), in order
+    pub fn enabled_in(&self, workspace: Option<&str>) -> &[String] {
+        workspace.and_then(|id| self.workspaces.get(id)).unwrap_or(&self.enabled)
+    }
+}
+
+/// Plugins enabled in the active template's workspace
+fn active_plugins(preferences: &crate::preferences::Preferences) -> Vec<String> {
+    let workspace = preferences.session_templates.active.as_deref();
+    preferences.transcript_plugins.enabled_in(workspace).to_vec()
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct TranscriptPlugin {
+    pub name: String,
+    pub size_bytes: u64,
+    pub enabled: bool,
+    pub error: Option<String>,       // Not a valid plugin, or switched off after failing
+}
+
+struct LoadedPlugin {
+    name: String,
+    module: Module,
+    failures: u32,
+    last_error: Option<String>,
+}
+
+struct Runtime {
+    engine: Engine,
+    plugins: Vec<LoadedPlugin>,
+    budget_ms: u64,
+}
+
+// Compiled enabled plugins (None until the first segment, or after a settings change)
+static RUNTIME: Lazy<Mutex<Option<Runtime>>> = Lazy::new(|| Mutex::new(None));
+
+fn plugins_dir() -> PathBuf {
+    crate::app_paths::data_dir().join(PLUGINS_DIR)
+}
+
+fn plugin_path(name: &str) -> Result<PathBuf> {
+    if Path::new(name).file_name().and_then(|n| n.to_str()) != Some(name) {
+        bail!("'{}' is not a plugin file name", name);
+    }
+    Ok(plugins_dir().join(name))
+}
+
+fn engine() -> Engine {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+/// Compile a plugin, refusing modules that want anything from the host
+fn compile(engine: &Engine, wasm: &[u8]) -> Result<Module> {
+    let module = Module::new(engine, wasm).context("Not a WebAssembly module")?;
+    if let Some(import) = module.imports().next() {
+        bail!("Plugins may not import anything (imports {}::{})", import.module(), import.name());
+    }
+    Ok(module)
+}
+
+/// One segment through one plugin, in a fresh instance
+fn run(engine: &Engine, module: &Module, text: &str, budget_ms: u64) -> Result<String> {
+    let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).instances(1).build();
+    let mut store = Store::new(engine, limits);
+    store.limiter(|limits| limits);
+    store.add_fuel(budget_ms.max(1).saturating_mul(FUEL_PER_MS)).map_err(|e| anyhow!("{}", e))?;
+
+    let instance = Linker::<StoreLimits>::new(engine).instantiate(&mut store, module)?.start(&mut store)?;
+    let memory = instance.get_memory(&store, "memory").context("The plugin exports no memory")?;
+    let alloc: TypedFunc<i32, i32> = instance.get_typed_func(&store, "alloc")?;
+    let transform: TypedFunc<(i32, i32), i64> = instance.get_typed_func(&store, "transform")?;
+
+    let input = text.as_bytes();
+    let ptr = alloc.call(&mut store, input.len() as i32)?;
+    memory.write(&mut store, ptr as u32 as usize, input).map_err(|e| anyhow!("Input out of plugin memory: {}", e))?;
+    let packed = transform.call(&mut store, (ptr, input.len() as i32))? as u64;
+    let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    if out_len > MAX_OUTPUT_BYTES {
+        bail!("Output of {} bytes is over the {} byte limit", out_len, MAX_OUTPUT_BYTES);
+    }
+    let mut output = vec![0; out_len];
+    memory.read(&store, out_ptr, &mut output).map_err(|e| anyhow!("Output out of plugin memory: {}", e))?;
+    String::from_utf8(output).context("The plugin output is not UTF-8")
+}
+
+/// Run with the wall-clock budget checked too
+fn run_timed(engine: &Engine, module: &Module, text: &str, budget_ms: u64) -> Result<String> {
+    let started = Instant::now();
+    let output = run(engine, module, text, budget_ms)?;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    if elapsed_ms > budget_ms {
+        bail!("Took {}ms, over the {}ms budget", elapsed_ms, budget_ms);
+    }
+    Ok(output)
+}
+
+fn load() -> Runtime {
+    let preferences = crate::preferences::load();
+    let settings = &preferences.transcript_plugins;
+    let engine = engine();
+    let mut plugins = Vec::new();
+    for name in &active_plugins(&preferences) {
+        let compiled = plugin_path(name)
+            .and_then(|path| fs::read(&path).context(format!("Cannot read {}", path.display())))
+            .and_then(|wasm| compile(&engine, &wasm));
+        match compiled {
+            Ok(module) => plugins.push(LoadedPlugin { name: name.clone(), module, failures: 0, last_error: None }),
+            Err(e) => warn!("⚠️ Transcript plugin {} not loaded: {}", name, e),
+        }
+    }
+    if !plugins.is_empty() {
+        info!("🧩 {} transcript plugin(s) loaded, {}ms budget each", plugins.len(), settings.budget_ms);
+    }
+    Runtime { engine, plugins, budget_ms: settings.budget_ms }
+}
+
+/// Recompile for the next segment, e.g. when another workspace became active
+pub fn reload() {
+    *RUNTIME.lock().unwrap() = None;
+}
+
+/// A final segment through the enabled plugins (unchanged when none are)
+pub fn apply(text: &str) -> String {
+    let mut runtime = RUNTIME.lock().unwrap();
+    let Runtime { engine, plugins, budget_ms } = runtime.get_or_insert_with(load);
+    let mut text = text.to_string();
+    for plugin in plugins.iter_mut().filter(|p| p.failures < MAX_FAILURES) {
+        match run_timed(engine, &plugin.module, &text, *budget_ms) {
+            Ok(output) => {
+                plugin.failures = 0;
+                text = output;
+            }
+            Err(e) => {
+                plugin.failures += 1;
+                warn!("⚠️ Transcript plugin {} failed ({} in a row): {}", plugin.name, plugin.failures, e);
+                plugin.last_error = Some(e.to_string());
+            }
+        }
+    }
+    text
+}
+
+fn describe(path: &Path, enabled: &[String]) -> TranscriptPlugin {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut error = fs::read(path).map_err(anyhow::Error::from)
+        .and_then(|wasm| compile(&engine(), &wasm))
+        .err()
+        .map(|e| e.to_string());
+    if let Some(runtime) = RUNTIME.lock().unwrap().as_ref() {
+        let switched_off = runtime.plugins.iter()
+            .find(|p| p.name == name && p.failures >= MAX_FAILURES)
+            .and_then(|p| p.last_error.clone());
+        if let Some(last_error) = switched_off {
+            error = Some(format!("Switched off after {} failures: {}", MAX_FAILURES, last_error));
+        }
+    }
+    TranscriptPlugin {
+        enabled: enabled.contains(&name),
+        size_bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        name,
+        error,
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Plugin files in the plugins folder, with whether the active workspace runs them
+#[tauri::command]
+pub fn list_transcript_plugins() -> Result<Vec<TranscriptPlugin>, String> {
+    let enabled = active_plugins(&crate::preferences::load());
+    let mut plugins: Vec<TranscriptPlugin> = match fs::read_dir(plugins_dir()) {
+        Ok(entries) => entries.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map_or(false, |e| e == "wasm"))
+            .map(|path| describe(&path, &enabled))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(plugins)
+}
+
+// Copy a WASM module into the plugins folder (it is checked, not enabled)
+#[tauri::command]
+pub fn install_transcript_plugin(path: String) -> Result<TranscriptPlugin, String> {
+    let source = Path::new(&path);
+    let wasm = fs::read(source).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    compile(&engine(), &wasm).map_err(|e| e.to_string())?;
+    let name = source.file_name().map(|n| n.to_string_lossy().to_string()).ok_or("No file name")?;
+    let target = plugin_path(&name).map_err(|e| e.to_string())?;
+    fs::create_dir_all(plugins_dir()).map_err(|e| e.to_string())?;
+    fs::write(&target, wasm).map_err(|e| e.to_string())?;
+    // A replaced module is recompiled at the next segment
+    reload();
+    info!("🧩 Transcript plugin {} installed", name);
+    Ok(describe(&target, &active_plugins(&crate::preferences::load())))
+}
+
+// Which plugins run (install-wide and per workspace), in order, and their time budget
+#[tauri::command]
+pub fn set_transcript_plugins(settings: TranscriptPluginSettings) -> Result<Vec<TranscriptPlugin>, String> {
+    for name in settings.enabled.iter().chain(settings.workspaces.values().flatten()) {
+        let path = plugin_path(name).map_err(|e| e.to_string())?;
+        if !path.exists() {
+            return Err(format!("No plugin named {}", name));
+        }
+    }
+    if settings.budget_ms == 0 || settings.budget_ms > MAX_BUDGET_MS {
+        return Err(format!("The time budget must be between 1 and {}ms", MAX_BUDGET_MS));
+    }
+    crate::preferences::update(|p| p.transcript_plugins = settings.clone())
+        .map_err(|e| e.to_string())?;
+    reload();
+    list_transcript_plugins()
+}
+
+// Run one plugin on sample text (whether or not it is enabled)
+#[tauri::command]
+pub fn test_transcript_plugin(name: String, text: String) -> Result<String, String> {
+    let path = plugin_path(&name).map_err(|e| e.to_string())?;
+    let wasm = fs::read(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let engine = engine();
+    let module = compile(&engine, &wasm).map_err(|e| e.to_string())?;
+    let budget_ms = crate::preferences::load().transcript_plugins.budget_ms;
+    run_timed(&engine, &module, &text, budget_ms).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Upper-cases ASCII letters in place
+    const UPPERCASE: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "alloc") (param i32) (result i32) i32.const 1024)
+        (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+            (local $i i32) (local $c i32)
+            (block $done
+                (loop $next
+                    (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                    (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+                    (if (i32.and (i32.ge_u (local.get $c) (i32.const 97)) (i32.le_u (local.get $c) (i32.const 122)))
+                        (then (i32.store8 (i32.add (local.get $ptr) (local.get $i)) (i32.sub (local.get $c) (i32.const 32)))))
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br $next)))
+            (i64.or (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32)) (i64.extend_i32_u (local.get $len)))))"#;
+
+    const RUNAWAY: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "alloc") (param i32) (result i32) i32.const 0)
+        (func (export "transform") (param i32 i32) (result i64) (loop $forever (br $forever)) i64.const 0))"#;
+
+    const IMPORTS: &str = r#"(module (import "env" "read_file" (func)) (memory (export "memory") 1))"#;
+
+    #[test]
+    fn test_plugins_transform_within_the_sandbox() {
+        let engine = engine();
+        let uppercase = compile(&engine, &wat::parse_str(UPPERCASE).unwrap()).unwrap();
+        assert_eq!(run(&engine, &uppercase, "project falcon ships q3", 20).unwrap(), "PROJECT FALCON SHIPS Q3");
+
+        // Out of fuel instead of hanging the transcript
+        let runaway = compile(&engine, &wat::parse_str(RUNAWAY).unwrap()).unwrap();
+        assert!(run(&engine, &runaway, "hello", 1).is_err());
+
+        assert!(compile(&engine, &wat::parse_str(IMPORTS).unwrap()).unwrap_err().to_string().contains("may not import"));
+        assert!(plugin_path("../escape.wasm").is_err());
+    }
+
+    #[test]
+    fn test_workspaces_fall_back_to_the_install_wide_list() {
+        let settings = TranscriptPluginSettings {
+            enabled: vec!["mask.wasm".to_string()],
+            workspaces: HashMap::from([("renewal".to_string(), vec!["expand.wasm".to_string(), "mask.wasm".to_string()])]),
+            ..Default::default()
+        };
+        assert_eq!(settings.enabled_in(Some("renewal")), ["expand.wasm", "mask.wasm"]);
+        assert_eq!(settings.enabled_in(Some("discovery")), ["mask.wasm"]);
+        assert_eq!(settings.enabled_in(None), ["mask.wasm"]);
+    }
+}
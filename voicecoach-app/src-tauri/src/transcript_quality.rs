@@ -0,0 +1,321 @@
+// Transcript Quality - how trustworthy a session's transcript is, and a second pass
+// A transcript made over a bad line or with the small model can be too garbled for
+// search, summaries and analytics to mean much. Each session gets a heuristic score
+// from three signals:
+// - the share of its words found in a dictionary of common English (plus numbers,
+//   contractions and simple inflections) - garbled recognition produces rare words;
+// - the engines' mean word confidence, where they gave word confidences;
+// - fragmentation: the share of lines under three words - broken-up recognition
+//   splits sentences into scraps.
+// list_sessions shows the score, and a poor session that has a recording is suggested
+// for re-transcription with the large Vosk model. With auto_retranscribe on, finished
+// calls and imported sessions that score poorly are queued right away. Re-transcribed
+// lines keep the speaker of the original line at their start, and replace the
+// transcript only when they score better.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{mpsc, Arc, Mutex};
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+use crate::session_store::{Session, TranscriptLine};
+
+// Lines shorter than this count as fragments
+const MIN_LINE_WORDS: usize = 3;
+// What clean conversational transcripts reach (names and jargon are never all known)
+const EXPECTED_DICTIONARY_RATIO: f32 = 0.85;
+const EXPECTED_WHOLE_LINES: f32 = 0.7;
+
+const COMMON_WORDS: &str = "
+a about above across act action actually add after afternoon again against ago agree ahead all allow
+almost alone along already alright also although always am amazing among amount an and annual another
+answer any anyone anything anyway apart app appreciate approach april are area aren around as ask
+asked at august available average aware away awesome back bad based basically be because become been
+before begin behind being believe benefit best better between big bit board both bottom box break bring
+budget build building business busy but buy by call called came can cannot cant care case certain
+certainly chance change check choose clear clearly client close code cold come comes coming company
+compare complete concern confirm consider contact contract control cool correct cost could couldn
+couple course cover create current customer customers cut daily data date day days deal decide decision
+definitely deliver demo department depend detail details did didn difference different difficult do
+does doesn doing done don door down during each early easy either else email end enough entire even
+evening ever every everyone everything exactly example excellent expect expensive experience explain
+fact fair far fast feature features february feel few figure fill final finally find fine first fit
+five focus follow for forward four free friday from front full fun further future get gets getting give
+given glad go goal goes going gone good got great group grow growth guess guy guys had half hand happen
+happy hard has hasn have haven having he hear heard hello help her here hey hi high him his hold home
+hope hour hours how however huge i idea if important in include including increase indeed information
+instead interest interested into is isn issue issues it its itself january job join july june just keep
+kind know knows large last late later learn least leave left less let level like likely line list
+listen little live long look looking looks lose lot lots love low made main make makes making manage
+manager many march market may maybe me mean means meet meeting mention might mind minute minutes miss
+moment monday money month monthly months more morning most move much must my myself name need needs
+never new next nice night no none not note nothing now number of off offer office often oh ok okay old
+on once one ones only open option options or order other others our out over own page part particular
+partner pay people per percent perfect perhaps person phone pick place plan platform play please point
+possible power pretty price pricing probably problem problems process product products project provide
+pull purchase put quarter question questions quick quickly quite rather reach read ready real really
+reason recall record reduce remember renewal report request require right risk role run said sale
+sales same saturday save saw say saying says schedule second see seem seems seen sell send sense
+september service services set setup seven share she short should show side sign signed simple since
+single six size so software solution solutions some someone something sometimes soon sorry sort sound
+sounds speak specific spend start started state still stop story stuff such suggest sunday support
+supposed sure system take taking talk talked talking team teams tell ten term terms test than thank
+thanks that the their them then there these they thing things think thinking third this those though
+thought three through thursday time times to today together told tomorrow too took tool tools top total
+track trial tried true try trying tuesday turn two type under understand unfortunately unless until up
+update us use used user users using usually value very via video view wait want wanted wants was wasn
+watch way we website wednesday week weekly weeks welcome well went were what whatever when where
+whether which while who whole why will win with within without won wonder word words work worked
+working works world worth would wouldn wow write wrong yeah year years yes yesterday yet you your
+yourself zero ah hmm huh mm mhm uh um
+";
+
+static DICTIONARY: Lazy<HashSet<&'static str>> = Lazy::new(|| COMMON_WORDS.split_whitespace().collect());
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TranscriptQualitySettings {
+    /// Score below which a transcript is poor (0-1)
+    #[serde(default = "default_poor_below")]
+    pub poor_below: f32,
+    /// Queue poor finished calls that have a recording for re-transcription
+    #[serde(default)]
+    pub auto_retranscribe: bool,
+}
+
+fn default_poor_below() -> f32 { 0.6 }
+
+impl Default for TranscriptQualitySettings {
+    fn default() -> Self {
+        Self { poor_below: default_poor_below(), auto_retranscribe: false }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TranscriptQuality {
+    pub score: f32,                  // 0-1
+    pub dictionary_ratio: f32,       // Words found in the dictionary
+    pub mean_confidence: Option<f32>, // None when no word carried a confidence
+    pub fragmentation: f32,          // Share of lines under three words
+    pub poor: bool,
+}
+
+// Sessions waiting for (or in) re-transcription
+static QUEUED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+// One session at a time: the large model is heavy
+static QUEUE: Lazy<Mutex<mpsc::Sender<String>>> = Lazy::new(|| {
+    let (sender, receiver) = mpsc::channel::<String>();
+    std::thread::spawn(move || {
+        for session_id in receiver {
+            match retranscribe(&session_id) {
+                Ok(true) => info!("🔁 Session {} re-transcribed", session_id),
+                Ok(false) => info!("🔁 Re-transcription of session {} scored no better, kept the original", session_id),
+                Err(e) => warn!("⚠️ Re-transcription of session {} failed: {}", session_id, e),
+            }
+            QUEUED.lock().unwrap().remove(&session_id);
+        }
+    });
+    Mutex::new(sender)
+});
+
+/// Whether a token is a known word: in the dictionary, a number, or a contraction or
+/// simple inflection of a dictionary word
+fn known(token: &str) -> bool {
+    let word = token.to_lowercase();
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'');
+    if word.is_empty() || word.chars().all(|c| c.is_ascii_digit() || c == '\'') {
+        return true;
+    }
+    let stem = ["'s", "'re", "'ll", "'ve", "'d", "'m", "n't"].iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(word);
+    DICTIONARY.contains(stem) || ["s", "es", "ed", "d", "ing", "ly", "er"].iter()
+        .filter_map(|suffix| stem.strip_suffix(suffix))
+        .any(|base| DICTIONARY.contains(base))
+}
+
+/// Quality of a transcript (None when it has no words)
+pub fn assess(transcript: &[TranscriptLine], poor_below: f32) -> Option<TranscriptQuality> {
+    let tokens: Vec<&str> = transcript.iter().flat_map(|l| l.text.split_whitespace()).collect();
+    if tokens.is_empty() {
+        return None;
+    }
+    let dictionary_ratio = tokens.iter().filter(|t| known(t)).count() as f32 / tokens.len() as f32;
+    let confidences: Vec<f32> = transcript.iter().flat_map(|l| &l.words).filter_map(|w| w.confidence).collect();
+    let mean_confidence = (!confidences.is_empty()).then(|| confidences.iter().sum::<f32>() / confidences.len() as f32);
+    let fragments = transcript.iter().filter(|l| l.text.split_whitespace().count() < MIN_LINE_WORDS).count();
+    let fragmentation = fragments as f32 / transcript.len() as f32;
+
+    let dictionary = (dictionary_ratio / EXPECTED_DICTIONARY_RATIO).min(1.0);
+    let cohesion = ((1.0 - fragmentation) / EXPECTED_WHOLE_LINES).min(1.0);
+    let score = match mean_confidence {
+        Some(confidence) => 0.5 * dictionary + 0.3 * confidence.clamp(0.0, 1.0) + 0.2 * cohesion,
+        None => 0.7 * dictionary + 0.3 * cohesion,
+    };
+    Some(TranscriptQuality { score, dictionary_ratio, mean_confidence, fragmentation, poor: score < poor_below })
+}
+
+/// Whether a session should be re-transcribed: poor, with a recording, not done yet
+pub fn retranscribe_suggested(session: &Session, quality: Option<&TranscriptQuality>) -> bool {
+    quality.map_or(false, |q| q.poor) && session.recording.is_some() && session.retranscribed_with.is_none()
+}
+
+/// Whether a session is waiting for (or in) re-transcription
+pub fn is_queued(session_id: &str) -> bool {
+    QUEUED.lock().unwrap().contains(session_id)
+}
+
+fn enqueue(session_id: &str) -> Result<(), String> {
+    if !QUEUED.lock().unwrap().insert(session_id.to_string()) {
+        return Ok(());  // Already queued
+    }
+    QUEUE.lock().unwrap().send(session_id.to_string()).map_err(|e| e.to_string())
+}
+
+/// A session was finished (its call ended, or it was imported): queue it for
+/// re-transcription when it scores poorly and auto_retranscribe is on
+pub fn call_finished(session_id: &str) {
+    let settings = crate::preferences::load().transcript_quality;
+    if !settings.auto_retranscribe || crate::file_transcription::large_model_path().is_none() {
+        return;
+    }
+    let session = match crate::session_store::load_session(Some(session_id.to_string())) {
+        Ok(session) => session,
+        Err(e) => {
+            warn!("⚠️ Cannot assess session {}: {}", session_id, e);
+            return;
+        }
+    };
+    let quality = assess(&session.transcript, settings.poor_below);
+    if retranscribe_suggested(&session, quality.as_ref()) {
+        info!("🔁 Session {} scored {:.2}, queued for re-transcription", session_id, quality.map_or(0.0, |q| q.score));
+        if let Err(e) = enqueue(session_id) {
+            warn!("⚠️ Failed to queue session {}: {}", session_id, e);
+        }
+    }
+}
+
+/// Side of the original line being spoken at `offset_ms` (the rep's before any line)
+fn speaker_at(original: &[TranscriptLine], offset_ms: u64) -> bool {
+    original.iter().rev().find(|l| l.offset_ms <= offset_ms)
+        .or_else(|| original.first())
+        .map_or(true, |l| l.is_user)
+}
+
+/// Re-transcribe a session's recording with the large model; Ok(false) when the new
+/// transcript scores no better than the old one
+fn retranscribe(session_id: &str) -> Result<bool, String> {
+    let session = crate::session_store::load_session(Some(session_id.to_string())).map_err(|e| e.to_string())?;
+    let recording = session.recording.clone().ok_or("The session has no recording")?;
+    let model_path = crate::file_transcription::large_model_path().ok_or("The large model is not installed")?;
+    let model = vosk::Model::new(model_path.as_str()).ok_or_else(|| format!("Failed to load model at: {}", model_path))?;
+    let pool = crate::recognizer_pool::RecognizerPool::new(
+        Arc::new(model), crate::file_transcription::resolve_pool_size(), crate::file_transcription::VOSK_SAMPLE_RATE as f32);
+    let file = crate::file_transcription::transcribe_wav_file(&pool, &recording).map_err(|e| e.to_string())?;
+
+    let lines: Vec<TranscriptLine> = file.segments.into_iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .map(|s| TranscriptLine {
+            offset_ms: s.start_ms,
+            is_user: speaker_at(&session.transcript, (s.start_ms + s.end_ms) / 2),
+            text: s.text,
+            words: Vec::new(),
+        })
+        .collect();
+    let score = |transcript: &[TranscriptLine]| assess(transcript, 0.0).map_or(0.0, |q| q.score);
+    if score(&lines) <= score(&session.transcript) {
+        return Ok(false);
+    }
+    crate::session_store::replace_transcript(session_id, lines, &model_path).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_transcript_quality_settings() -> Result<TranscriptQualitySettings, String> {
+    Ok(crate::preferences::load().transcript_quality)
+}
+
+#[tauri::command]
+pub fn set_transcript_quality_settings(settings: TranscriptQualitySettings) -> Result<TranscriptQualitySettings, String> {
+    if !(0.0..=1.0).contains(&settings.poor_below) {
+        return Err("poor_below must be between 0 and 1".to_string());
+    }
+    crate::preferences::update(|p| p.transcript_quality = settings.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+// Quality of a session's transcript (the current one when no id is given)
+#[tauri::command]
+pub fn get_transcript_quality(session_id: Option<String>) -> Result<Option<TranscriptQuality>, String> {
+    let session = crate::session_store::load_session(session_id).map_err(|e| e.to_string())?;
+    Ok(assess(&session.transcript, crate::preferences::load().transcript_quality.poor_below))
+}
+
+// Queue a session's recording for re-transcription with the large model
+#[tauri::command]
+pub fn retranscribe_session(session_id: String) -> Result<(), String> {
+    let session = crate::session_store::load_session(Some(session_id.clone())).map_err(|e| e.to_string())?;
+    if session.recording.is_none() {
+        return Err("The session has no recording to re-transcribe".to_string());
+    }
+    if crate::file_transcription::large_model_path().is_none() {
+        return Err("Re-transcription needs the large model: configure model_paths.large_model and download it".to_string());
+    }
+    enqueue(&session_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_store::TranscriptWord;
+
+    fn line(offset_ms: u64, is_user: bool, text: &str) -> TranscriptLine {
+        TranscriptLine { offset_ms, is_user, text: text.to_string(), words: Vec::new() }
+    }
+
+    #[test]
+    fn test_garbled_and_fragmented_transcripts_score_poorly() {
+        let clean = vec![
+            line(0, true, "Thanks for making the time today, how are things going?"),
+            line(4_000, false, "Pretty good. We're looking at a new tool for the sales team."),
+            line(9_000, true, "Great, what's the main problem you'd like it to solve?"),
+            line(14_000, false, "Mostly the time our managers spend on weekly reports."),
+        ];
+        let good = assess(&clean, 0.6).unwrap();
+        assert!(good.dictionary_ratio > 0.9 && good.fragmentation == 0.0 && !good.poor, "{:?}", good);
+        assert!(good.mean_confidence.is_none());
+
+        let garbled = vec![
+            line(0, true, "thanks form acing the thyme"),
+            line(2_000, false, "prettier"),
+            line(3_000, false, "cool gulf wee"),
+            line(4_000, true, "grate"),
+            line(5_000, false, "mosque lee the thyme hour manatees"),
+        ];
+        let poor = assess(&garbled, 0.6).unwrap();
+        assert!(poor.poor && poor.fragmentation > 0.3, "{:?}", poor);
+
+        // Low word confidence drags an otherwise clean transcript down
+        let mut unsure = clean.clone();
+        for l in &mut unsure {
+            l.words = l.text.split_whitespace().map(|w| TranscriptWord { word: w.to_string(), start_ms: 0, end_ms: 0, confidence: Some(0.2) }).collect();
+        }
+        assert!(assess(&unsure, 0.6).unwrap().score < good.score);
+        assert!(assess(&[], 0.6).is_none());
+    }
+
+    #[test]
+    fn test_known_words_and_speakers() {
+        assert!(known("Reports.") && known("we're") && known("2024") && known("talked") && known("didn't"));
+        assert!(!known("manatees") && !known("xqzt"));
+
+        let original = vec![line(1_000, true, "Hi"), line(5_000, false, "Hello"), line(9_000, true, "So")];
+        assert!(speaker_at(&original, 0));
+        assert!(!speaker_at(&original, 6_000));
+        assert!(speaker_at(&original, 12_000));
+        assert!(speaker_at(&[], 0));
+    }
+}
@@ -0,0 +1,152 @@
+// Transcript Search - every mention of a word or phrase in a session's transcript
+// search_in_session matches the query word by word against the transcript stored with
+// the session (session_store), ignoring case and punctuation; each query word also
+// matches longer words it starts ("discount" finds "discounts" and "discounted").
+// A match carries when the mention starts and ends - from the engine's word timings
+// when the line has them (word_level), otherwise the span of the whole line - so the
+// review screen can seek playback straight to it. The session's coach notes (post-call
+// voice notes) are searched too, after the transcript.
+
+use serde::{Deserialize, Serialize};
+use log::info;
+
+use crate::session_store::{CoachNote, TranscriptLine, TranscriptWord};
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TranscriptMatch {
+    pub line: usize,        // Index in the session transcript (in its notes for a note)
+    pub is_user: bool,
+    pub text: String,       // The whole line
+    pub matched: String,    // Words of the line that matched
+    pub start_ms: u64,      // Time since the call started
+    pub end_ms: u64,
+    pub word_level: bool,   // Times are the words' own rather than the line's
+    #[serde(default)]
+    pub note: Option<u32>,  // Coach note matched; times are then within the note
+}
+
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '\'')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn query_terms(query: &str) -> Vec<String> {
+    query.split_whitespace().map(normalize).filter(|t| !t.is_empty()).collect()
+}
+
+/// Matches of `terms` in one line
+fn search_line(index: usize, line: &TranscriptLine, line_end_ms: u64, terms: &[String]) -> Vec<TranscriptMatch> {
+    // Timed words when the engine gave them, the line's text otherwise
+    let words: Vec<(&str, Option<&TranscriptWord>)> = if line.words.is_empty() {
+        line.text.split_whitespace().map(|w| (w, None)).collect()
+    } else {
+        line.words.iter().map(|w| (w.word.as_str(), Some(w))).collect()
+    };
+    let normalized: Vec<String> = words.iter().map(|(w, _)| normalize(w)).collect();
+
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i + terms.len() <= normalized.len() {
+        if !terms.iter().zip(&normalized[i..]).all(|(term, word)| word.starts_with(term.as_str())) {
+            i += 1;
+            continue;
+        }
+        let span = &words[i..i + terms.len()];
+        let (start_ms, end_ms, word_level) = match (span[0].1, span[span.len() - 1].1) {
+            (Some(first), Some(last)) => (first.start_ms, last.end_ms, true),
+            _ => (line.offset_ms, line_end_ms, false),
+        };
+        matches.push(TranscriptMatch {
+            line: index,
+            is_user: line.is_user,
+            text: line.text.clone(),
+            matched: span.iter().map(|(w, _)| *w).collect::<Vec<_>>().join(" "),
+            start_ms,
+            end_ms,
+            word_level,
+            note: None,
+        });
+        i += terms.len();
+    }
+    matches
+}
+
+fn search(transcript: &[TranscriptLine], terms: &[String]) -> Vec<TranscriptMatch> {
+    transcript.iter().enumerate()
+        .flat_map(|(index, line)| {
+            // A line without word timings runs until the next one starts
+            let line_end_ms = transcript.get(index + 1).map_or(line.offset_ms, |next| next.offset_ms);
+            search_line(index, line, line_end_ms, terms)
+        })
+        .collect()
+}
+
+/// Matches in coach notes (no word timings: the span of the whole note)
+fn search_notes(notes: &[CoachNote], terms: &[String]) -> Vec<TranscriptMatch> {
+    notes.iter().enumerate()
+        .flat_map(|(index, note)| {
+            let line = TranscriptLine { offset_ms: 0, is_user: true, text: note.text.clone(), words: Vec::new() };
+            search_line(index, &line, note.duration_ms, terms).into_iter()
+                .map(move |m| TranscriptMatch { note: Some(note.id), ..m })
+        })
+        .collect()
+}
+
+// ========== Tauri Commands ==========
+
+// Mentions of `query` in a session (the current one when no id is given), in call order,
+// then in its coach notes
+#[tauri::command]
+pub fn search_in_session(session_id: Option<String>, query: String) -> Result<Vec<TranscriptMatch>, String> {
+    let terms = query_terms(&query);
+    if terms.is_empty() {
+        return Err("Search for at least one word".to_string());
+    }
+    let transcript = crate::session_store::session_transcript(session_id.clone()).map_err(|e| e.to_string())?;
+    let notes = crate::session_store::session_notes(session_id).map_err(|e| e.to_string())?;
+    let mut matches = search(&transcript, &terms);
+    matches.extend(search_notes(&notes, &terms));
+    info!("🔎 '{}' found {} times in {} transcript lines and {} notes", query, matches.len(), transcript.len(), notes.len());
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, start_ms: u64, end_ms: u64) -> TranscriptWord {
+        TranscriptWord { word: word.to_string(), start_ms, end_ms, confidence: None }
+    }
+
+    #[test]
+    fn test_search_uses_word_times_when_available() {
+        let transcript = vec![
+            TranscriptLine {
+                offset_ms: 1_000,
+                is_user: false,
+                text: "Is there a discount? Volume discounts matter.".to_string(),
+                words: vec![
+                    word("Is", 1_000, 1_100), word("there", 1_100, 1_300), word("a", 1_300, 1_350),
+                    word("discount?", 1_350, 1_900), word("Volume", 2_100, 2_500), word("discounts", 2_500, 3_000),
+                    word("matter.", 3_000, 3_400),
+                ],
+            },
+            TranscriptLine { offset_ms: 5_000, is_user: true, text: "We can talk about a Discount later".to_string(), words: vec![] },
+            TranscriptLine { offset_ms: 9_000, is_user: false, text: "Okay.".to_string(), words: vec![] },
+        ];
+
+        let found = search(&transcript, &query_terms("DISCOUNT"));
+        let spans: Vec<(usize, u64, u64, bool)> = found.iter().map(|m| (m.line, m.start_ms, m.end_ms, m.word_level)).collect();
+        assert_eq!(spans, vec![(0, 1_350, 1_900, true), (0, 2_500, 3_000, true), (1, 5_000, 9_000, false)]);
+
+        let phrase = search(&transcript, &query_terms("volume discount"));
+        assert_eq!((phrase.len(), phrase[0].matched.as_str(), phrase[0].start_ms), (1, "Volume discounts", 2_100));
+        assert!(search(&transcript, &query_terms("count")).is_empty());
+
+        let notes = vec![CoachNote { id: 3, recorded_at: 0, duration_ms: 12_000, text: "follow up on the volume discount".to_string() }];
+        let in_notes = search_notes(&notes, &query_terms("discount"));
+        assert_eq!((in_notes.len(), in_notes[0].note, in_notes[0].end_ms), (1, Some(3), 12_000));
+    }
+}
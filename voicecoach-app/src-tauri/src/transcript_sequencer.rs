@@ -0,0 +1,10 @@
+// Transcript Sequencer - capture-order release of finals on the session's wall clock
+// The sequencer and capture clock are in voicecoach-core (re-exported here under the
+// old path); `submit` times released segments with the session store's clock anchor.
+
+pub use voicecoach_core::transcript_sequencer::*;
+
+/// Queue a final segment captured at `capture_ms` (see voicecoach_core::transcript_sequencer)
+pub fn submit(capture_ms: u64, release: Release) {
+    voicecoach_core::transcript_sequencer::submit(capture_ms, crate::session_store::wall_ms, release);
+}
@@ -0,0 +1,184 @@
+// Transcript Stream - final lines appended to a file on disk as the call goes
+// Note-taking tools (Obsidian, a tail -f, a script watching a folder) can follow a call
+// from a plain file without speaking to the WebSocket server. stream_transcript_to_file
+// starts the file over with the lines the session already has, then every final line
+// is appended and flushed as the session records it - as text ("[01:23] Rep: ...") or
+// as JSON Lines (one object per line with its index, offset and speaker). A stream
+// ends with its session (when the next call starts) or with stop_transcript_stream.
+// A session that is no longer in progress is written out whole and not kept open.
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+use crate::session_store::TranscriptLine;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptStreamFormat {
+    Txt,
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct TranscriptStreamStatus {
+    pub session_id: String,
+    pub path: String,
+    pub format: TranscriptStreamFormat,
+    pub lines_written: usize,
+    pub live: bool,                  // Still appending (the session is in progress)
+}
+
+// One JSON Lines record
+#[derive(Serialize)]
+struct StreamedLine<'a> {
+    session_id: &'a str,
+    index: usize,
+    offset_ms: u64,
+    speaker: &'a str,
+    is_user: bool,
+    text: &'a str,
+}
+
+struct FileStream {
+    status: TranscriptStreamStatus,
+    file: File,
+}
+
+static STREAMS: Lazy<Mutex<Vec<FileStream>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn clock(offset_ms: u64) -> String {
+    let seconds = offset_ms / 1000;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn render(format: TranscriptStreamFormat, session_id: &str, index: usize, line: &TranscriptLine) -> String {
+    let speaker = if line.is_user { "Rep" } else { "Prospect" };
+    match format {
+        TranscriptStreamFormat::Txt => format!("[{}] {}: {}\n", clock(line.offset_ms), speaker, line.text),
+        TranscriptStreamFormat::Jsonl => {
+            let record = StreamedLine { session_id, index, offset_ms: line.offset_ms, speaker, is_user: line.is_user, text: &line.text };
+            format!("{}\n", serde_json::to_string(&record).unwrap_or_default())
+        }
+    }
+}
+
+impl FileStream {
+    fn write(&mut self, index: usize, line: &TranscriptLine) -> Result<()> {
+        let text = render(self.status.format, &self.status.session_id, index, line);
+        self.file.write_all(text.as_bytes())?;
+        self.file.flush()?;
+        self.status.lines_written += 1;
+        Ok(())
+    }
+}
+
+/// A final line was recorded at `index` of the session in progress
+pub fn append(session_id: &str, index: usize, line: &TranscriptLine) {
+    let mut streams = STREAMS.lock().unwrap();
+    let mut failed = Vec::new();
+    for stream in streams.iter_mut().filter(|s| s.status.session_id == session_id) {
+        if let Err(e) = stream.write(index, line) {
+            warn!("⚠️ Stopped streaming the transcript to {}: {}", stream.status.path, e);
+            failed.push(stream.status.path.clone());
+        }
+    }
+    streams.retain(|s| !failed.contains(&s.status.path));
+}
+
+/// The session ended: its streams are complete
+pub fn session_ended(session_id: &str) {
+    STREAMS.lock().unwrap().retain(|stream| {
+        let ended = stream.status.session_id == session_id;
+        if ended {
+            info!("📄 Transcript stream {} complete ({} lines)", stream.status.path, stream.status.lines_written);
+        }
+        !ended
+    });
+}
+
+/// Start `path` over with the lines so far
+fn open(session_id: &str, path: &str, format: TranscriptStreamFormat, transcript: &[TranscriptLine], live: bool) -> Result<FileStream> {
+    let file = File::create(path).context(format!("Cannot write {}", path))?;
+    let mut stream = FileStream {
+        status: TranscriptStreamStatus { session_id: session_id.to_string(), path: path.to_string(), format, lines_written: 0, live },
+        file,
+    };
+    for (index, line) in transcript.iter().enumerate() {
+        stream.write(index, line)?;
+    }
+    Ok(stream)
+}
+
+// ========== Tauri Commands ==========
+
+// Stream a session's transcript (the current one when no id is given) to `path`
+// ("txt" or "jsonl"); the file is started over
+#[tauri::command]
+pub fn stream_transcript_to_file(session_id: Option<String>, path: String, format: TranscriptStreamFormat) -> Result<TranscriptStreamStatus, String> {
+    if !Path::new(&path).is_absolute() {
+        return Err("The transcript file needs an absolute path".to_string());
+    }
+    let session_id = session_id.or_else(crate::session_store::current_session_id)
+        .ok_or("No session in progress")?;
+    // Registered under the session lock, so no line lands between the catch-up and the stream
+    let live = crate::session_store::with_current(|session| {
+        if session.id != session_id {
+            return None;
+        }
+        let mut streams = STREAMS.lock().unwrap();
+        streams.retain(|s| s.status.path != path);
+        Some(open(&session_id, &path, format, &session.transcript, true).map(|stream| {
+            let status = stream.status.clone();
+            streams.push(stream);
+            status
+        }))
+    }).flatten();
+    let status = match live {
+        Some(status) => status,
+        None => {
+            let session = crate::session_store::load_session(Some(session_id.clone())).map_err(|e| e.to_string())?;
+            open(&session_id, &path, format, &session.transcript, false).map(|stream| stream.status)
+        }
+    }.map_err(|e| e.to_string())?;
+    info!("📄 Streaming the transcript of {} to {} ({} lines so far{})", status.session_id, status.path, status.lines_written,
+        if status.live { "" } else { ", session finished" });
+    Ok(status)
+}
+
+// Stop the stream to `path` (every stream when no path is given)
+#[tauri::command]
+pub fn stop_transcript_stream(path: Option<String>) -> Result<Vec<TranscriptStreamStatus>, String> {
+    let mut streams = STREAMS.lock().unwrap();
+    let (stopped, kept): (Vec<FileStream>, Vec<FileStream>) = streams.drain(..)
+        .partition(|s| path.as_ref().map_or(true, |p| &s.status.path == p));
+    *streams = kept;
+    Ok(stopped.into_iter().map(|s| TranscriptStreamStatus { live: false, ..s.status }).collect())
+}
+
+#[tauri::command]
+pub fn get_transcript_streams() -> Result<Vec<TranscriptStreamStatus>, String> {
+    Ok(STREAMS.lock().unwrap().iter().map(|s| s.status.clone()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_render_as_text_and_json_lines() {
+        let line = TranscriptLine { offset_ms: 83_400, is_user: false, text: "What does \"annual\" mean here?".to_string(), words: Vec::new() };
+        assert_eq!(render(TranscriptStreamFormat::Txt, "s1", 4, &line), "[01:23] Prospect: What does \"annual\" mean here?\n");
+        let json = render(TranscriptStreamFormat::Jsonl, "s1", 4, &line);
+        assert!(json.ends_with('\n') && json.matches('\n').count() == 1);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["index"], 4);
+        assert_eq!(value["speaker"], "Prospect");
+        assert_eq!(value["text"], "What does \"annual\" mean here?");
+    }
+}
@@ -0,0 +1,297 @@
+// Two-Pass Transcription - local engine for instant text, cloud engine for accuracy
+// With two-pass on, both engines transcribe the microphone. The partials engine
+// (Vosk by default) shows its partials immediately and its finals as provisional
+// segments ("transcript_provisional"). The finals engine (Deepgram by default) has
+// its interim results suppressed; each of its finals replaces the provisional
+// segments whose capture-time spans overlap it ("transcript_reconciled"). Only the
+// authoritative text goes through the sequencer and the analytics/coaching chain.
+// A provisional segment the finals engine never covers is confirmed after a wait
+// (e.g. the cloud connection dropped) or dropped once cloud finals have moved past
+// it without overlapping it (the cloud engine heard no speech there).
+// The legacy TranscriptionConfig (transcription_service.rs) is not used by the live
+// engines, so the roles are stored in preferences.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+use crate::transcript_sequencer::Release;
+// The engines are named in voicecoach-core (capture_host)
+pub use voicecoach_core::capture_host::Engine;
+
+// Spans closer than this still count as overlapping (engines segment differently)
+const OVERLAP_TOLERANCE_MS: u64 = 250;
+const TICK_MS: u64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TwoPassMode {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Engine whose partials (and provisional finals) are shown immediately
+    #[serde(default = "default_partials")]
+    pub partials: Engine,
+    /// Engine whose finals replace the provisional segments
+    #[serde(default = "default_finals")]
+    pub finals: Engine,
+    /// How long a provisional segment waits for the finals engine before it is kept
+    #[serde(default = "default_final_wait_ms")]
+    pub final_wait_ms: u64,
+}
+
+fn default_partials() -> Engine { Engine::Vosk }
+fn default_finals() -> Engine { Engine::Deepgram }
+fn default_final_wait_ms() -> u64 { 3000 }
+
+impl Default for TwoPassMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            partials: default_partials(),
+            finals: default_finals(),
+            final_wait_ms: default_final_wait_ms(),
+        }
+    }
+}
+
+// Payload of "transcript_provisional"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProvisionalSegment {
+    pub segment_id: u64,
+    pub text: String,
+    pub start_ms: u64,  // Session audio clock (see audio_clock)
+    pub end_ms: u64,
+    pub engine: Engine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Replaced,   // Superseded by a final of the finals engine
+    Confirmed,  // Kept: the finals engine didn't answer in time
+    Dropped,    // The finals engine heard no speech there
+}
+
+// Payload of "transcript_reconciled"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct Reconciled {
+    pub segment_ids: Vec<u64>,
+    pub outcome: Outcome,
+    pub text: Option<String>,  // Replacing text (Replaced only)
+    pub engine: Engine,
+}
+
+struct Provisional<T> {
+    id: u64,
+    start_ms: u64,
+    end_ms: u64,
+    arrived: Instant,
+    item: T,
+}
+
+struct Reconciler<T> {
+    pending: Vec<Provisional<T>>,
+    next_id: u64,
+    finals_through_ms: u64,  // End of the latest final from the finals engine
+}
+
+impl<T> Reconciler<T> {
+    fn new() -> Self {
+        Self { pending: Vec::new(), next_id: 0, finals_through_ms: 0 }
+    }
+
+    /// Hold a provisional segment; None if the finals engine already covered its span
+    fn provisional(&mut self, start_ms: u64, end_ms: u64, arrived: Instant, item: T) -> Option<u64> {
+        if end_ms <= self.finals_through_ms + OVERLAP_TOLERANCE_MS {
+            return None;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.push(Provisional { id, start_ms, end_ms, arrived, item });
+        Some(id)
+    }
+
+    /// Provisional segments replaced by a final spanning start_ms..end_ms
+    fn replace(&mut self, start_ms: u64, end_ms: u64) -> Vec<u64> {
+        self.finals_through_ms = self.finals_through_ms.max(end_ms);
+        let (replaced, kept) = self.pending.drain(..).partition::<Vec<_>, _>(|p| {
+            p.start_ms < end_ms + OVERLAP_TOLERANCE_MS && start_ms < p.end_ms + OVERLAP_TOLERANCE_MS
+        });
+        self.pending = kept;
+        replaced.into_iter().map(|p| p.id).collect()
+    }
+
+    /// (confirmed, dropped) provisional segments at `now`
+    fn settle(&mut self, now: Instant, wait: Duration) -> (Vec<(u64, T)>, Vec<u64>) {
+        let finals_through_ms = self.finals_through_ms;
+        let (settled, kept) = self.pending.drain(..).partition::<Vec<_>, _>(|p| {
+            p.end_ms + OVERLAP_TOLERANCE_MS < finals_through_ms || now.duration_since(p.arrived) >= wait
+        });
+        self.pending = kept;
+        let (dropped, confirmed) = settled.into_iter()
+            .partition::<Vec<_>, _>(|p| p.end_ms + OVERLAP_TOLERANCE_MS < finals_through_ms);
+        (
+            confirmed.into_iter().map(|p| (p.id, p.item)).collect(),
+            dropped.into_iter().map(|p| p.id).collect(),
+        )
+    }
+}
+
+struct Provisionals {
+    reconciler: Reconciler<(u64, Release)>,  // (capture_ms, emission)
+    app: Option<AppHandle>,
+}
+
+static PROVISIONALS: Lazy<Mutex<Provisionals>> = Lazy::new(|| Mutex::new(Provisionals { reconciler: Reconciler::new(), app: None }));
+static SETTLE_THREAD: Once = Once::new();
+// Checked for every partial, so kept in memory rather than re-read from preferences
+static MODE: Lazy<Mutex<TwoPassMode>> = Lazy::new(|| Mutex::new(crate::preferences::load().two_pass));
+
+fn mode() -> TwoPassMode {
+    MODE.lock().unwrap().clone()
+}
+
+fn emit_reconciled(app: &AppHandle, reconciled: Reconciled) {
+    if let Err(e) = app.emit_all("transcript_reconciled", reconciled) {
+        error!("Failed to emit transcript_reconciled: {:?}", e);
+    }
+}
+
+fn start_settle_thread() {
+    SETTLE_THREAD.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(Duration::from_millis(TICK_MS));
+            let mode = mode();
+            let (confirmed, dropped, app) = {
+                let mut provisionals = PROVISIONALS.lock().unwrap();
+                let (confirmed, dropped) = provisionals.reconciler.settle(Instant::now(), Duration::from_millis(mode.final_wait_ms));
+                (confirmed, dropped, provisionals.app.clone())
+            };
+            let app = match app {
+                Some(app) => app,
+                None => continue,
+            };
+            if !dropped.is_empty() {
+                emit_reconciled(&app, Reconciled { segment_ids: dropped, outcome: Outcome::Dropped, text: None, engine: mode.finals });
+            }
+            for (id, (capture_ms, release)) in confirmed {
+                info!("⏳ No {:?} final for provisional segment {}, keeping it", mode.finals, id);
+                crate::transcript_sequencer::submit(capture_ms, release);
+                emit_reconciled(&app, Reconciled { segment_ids: vec![id], outcome: Outcome::Confirmed, text: None, engine: mode.partials });
+            }
+        });
+    });
+}
+
+/// Whether an engine's partial/interim results should be shown
+pub fn emits_partials(engine: Engine, is_user: bool) -> bool {
+    let mode = mode();
+    !(mode.enabled && is_user) || mode.partials == engine
+}
+
+/// Route a final segment (capture span start_ms..end_ms): straight to the sequencer,
+/// or through reconciliation when two-pass is on for the microphone
+pub fn submit_final(app: &AppHandle, engine: Engine, is_user: bool, start_ms: u64, end_ms: u64, text: &str, release: Release) {
+    // One-party consent: prospect text never enters the pipeline
+    if !crate::one_party::allows(is_user) {
+        return;
+    }
+    let mode = mode();
+    if !mode.enabled || !is_user || mode.partials == mode.finals {
+        crate::transcript_sequencer::submit(start_ms, release);
+        return;
+    }
+
+    if engine == mode.partials {
+        start_settle_thread();
+        let segment_id = {
+            let mut provisionals = PROVISIONALS.lock().unwrap();
+            provisionals.app = Some(app.clone());
+            provisionals.reconciler.provisional(start_ms, end_ms, Instant::now(), (start_ms, release))
+        };
+        // Arrived after the authoritative final for the same audio
+        let segment_id = match segment_id {
+            Some(id) => id,
+            None => return,
+        };
+        let (start_ms, end_ms) = (crate::session_store::offset_of(start_ms), crate::session_store::offset_of(end_ms));
+        let segment = ProvisionalSegment { segment_id, text: text.to_string(), start_ms, end_ms, engine };
+        if let Err(e) = app.emit_all("transcript_provisional", segment) {
+            error!("Failed to emit transcript_provisional: {:?}", e);
+        }
+    } else {
+        let replaced = PROVISIONALS.lock().unwrap().reconciler.replace(start_ms, end_ms);
+        if !replaced.is_empty() {
+            emit_reconciled(app, Reconciled { segment_ids: replaced, outcome: Outcome::Replaced, text: Some(text.to_string()), engine });
+        }
+        crate::transcript_sequencer::submit(start_ms, release);
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_two_pass_mode() -> Result<TwoPassMode, String> {
+    Ok(mode())
+}
+
+#[tauri::command]
+pub fn set_two_pass_mode(mode: TwoPassMode) -> Result<TwoPassMode, String> {
+    if mode.enabled && mode.partials == mode.finals {
+        return Err("Two-pass needs different engines for partials and finals".to_string());
+    }
+    crate::preferences::update(|p| p.two_pass = mode.clone())
+        .map_err(|e| e.to_string())?;
+    *MODE.lock().unwrap() = mode.clone();
+    info!("🔀 Two-pass transcription {}", if mode.enabled { "enabled" } else { "disabled" });
+    Ok(mode)
+}
+
+// Start both engines on the microphone in their two-pass roles
+#[tauri::command]
+pub async fn start_two_pass_transcription(app: AppHandle, api_key: String, model_path: String) -> Result<TwoPassMode, String> {
+    let mode = mode();
+    if !mode.enabled {
+        return Err("Two-pass mode is not enabled".to_string());
+    }
+    crate::vosk_transcription::start_vosk_transcription(app.clone(), model_path).await?;
+    if let Err(e) = crate::deepgram_transcription::start_deepgram_transcription(app, api_key, None, None).await {
+        // Vosk alone still works: its provisional segments are confirmed after the wait
+        error!("❌ Two-pass finals engine failed to start: {}", e);
+        return Err(e);
+    }
+    Ok(mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finals_replace_overlapping_provisionals_by_capture_time() {
+        let start = Instant::now();
+        let wait = Duration::from_millis(3000);
+        let mut reconciler = Reconciler::new();
+        let first = reconciler.provisional(1_000, 2_400, start, "hello there").unwrap();
+        let second = reconciler.provisional(2_600, 4_000, start, "how are you").unwrap();
+        let noise = reconciler.provisional(5_000, 5_300, start, "uh").unwrap();
+        let late = reconciler.provisional(9_000, 9_800, start, "thanks").unwrap();
+
+        // One cloud final covering both spoken segments
+        assert_eq!(reconciler.replace(950, 4_100), vec![first, second]);
+        // Cloud finals moved past the noise without covering it; the last one is still open
+        assert!(reconciler.replace(6_000, 7_000).is_empty());
+        let (confirmed, dropped) = reconciler.settle(start, wait);
+        assert!(confirmed.is_empty());
+        assert_eq!(dropped, vec![noise]);
+
+        // Nothing covers the last segment before the wait runs out: it is kept
+        let (confirmed, dropped) = reconciler.settle(start + wait, wait);
+        assert_eq!(confirmed, vec![(late, "thanks")]);
+        assert!(dropped.is_empty());
+        // Local result for audio the cloud already finalized
+        assert_eq!(reconciler.provisional(6_200, 6_900, start, "late local"), None);
+    }
+}
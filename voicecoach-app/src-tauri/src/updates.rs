@@ -0,0 +1,307 @@
+// Updates - release manifest checks with signature verification and staged rollout
+// The release pipeline publishes a manifest (all recent releases, newest first)
+// signed with the release ed25519 key; the public half is baked into the build via
+// VOICECOACH_UPDATE_PUBKEY. A release can be rolled out to a percentage of
+// installs: each machine gets a stable bucket per version, so raising the
+// percentage only ever adds machines. Users can defer a version for some days.
+// Results go to the UI as "update_status" events; nothing is downloaded here.
+
+use anyhow::{Result, anyhow, bail};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use log::{info, warn, error};
+
+const DEFAULT_MANIFEST_URL: &str = "https://releases.voicecoach.app/manifest.json";
+// Set by release builds; without it update checks report an error instead of trusting anything
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("VOICECOACH_UPDATE_PUBKEY");
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const CHECK_INTERVAL_SECS: u64 = 6 * 60 * 60;
+const STARTUP_DELAY_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct UpdateSettings {
+    #[serde(default = "default_true")]
+    pub auto_check: bool,
+    #[serde(default)]
+    pub deferred_version: Option<String>,
+    #[serde(default)]
+    pub deferred_until: Option<i64>,  // ms
+}
+
+fn default_true() -> bool { true }
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self { auto_check: true, deferred_version: None, deferred_until: None }
+    }
+}
+
+// Outer manifest document: base64 payload + detached signature over the payload bytes
+#[derive(Debug, Deserialize)]
+struct SignedManifest {
+    payload: String,
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestPayload {
+    releases: Vec<Release>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Release {
+    pub version: String,
+    #[serde(default)]
+    pub released_at: Option<String>,
+    #[serde(default)]
+    pub notes: Vec<String>,
+    #[serde(default = "default_rollout")]
+    pub rollout_percentage: u8,
+    #[serde(default)]
+    pub download_url: Option<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+fn default_rollout() -> u8 { 100 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateState {
+    UpToDate,
+    Available,
+    Deferred,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub released_at: Option<String>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UpdateStatus {
+    pub state: UpdateState,
+    pub current_version: String,
+    pub latest: Option<Release>,
+    pub changelog: Vec<ChangelogEntry>,  // Every release between current and latest
+    pub held_back: Option<String>,       // Newer version not yet rolled out to this machine
+    pub deferred_until: Option<i64>,
+    pub checked_at: i64,
+    pub error: Option<String>,
+}
+
+static LAST_STATUS: Lazy<Mutex<Option<UpdateStatus>>> = Lazy::new(|| Mutex::new(None));
+
+fn manifest_url() -> String {
+    std::env::var("VOICECOACH_UPDATE_MANIFEST").unwrap_or_else(|_| DEFAULT_MANIFEST_URL.to_string())
+}
+
+/// "v1.4.2-beta.1" -> [1, 4, 2]
+fn parse_version(version: &str) -> Vec<u64> {
+    version.trim().trim_start_matches('v')
+        .split(['-', '+']).next().unwrap_or("")
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+fn is_newer(candidate: &str, current: &str) -> bool {
+    let (mut a, mut b) = (parse_version(candidate), parse_version(current));
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    a > b
+}
+
+/// Stable 0..99 bucket for this machine and version
+fn rollout_bucket(machine_id: &str, version: &str) -> u8 {
+    let digest = Sha256::digest(format!("{}:{}", machine_id, version).as_bytes());
+    (u16::from_be_bytes([digest[0], digest[1]]) % 100) as u8
+}
+
+fn verify_manifest(document: &str, public_key: &str) -> Result<ManifestPayload> {
+    let signed: SignedManifest = serde_json::from_str(document)?;
+    let key_bytes: [u8; 32] = BASE64.decode(public_key.trim())?
+        .try_into()
+        .map_err(|_| anyhow!("Release public key must be 32 bytes"))?;
+    let key = VerifyingKey::from_bytes(&key_bytes)?;
+    let signature = Signature::from_slice(&BASE64.decode(signed.signature.trim())?)?;
+    let payload = BASE64.decode(signed.payload.trim())?;
+    key.verify(&payload, &signature)
+        .map_err(|_| anyhow!("Release manifest signature is invalid"))?;
+    Ok(serde_json::from_slice(&payload)?)
+}
+
+/// Newest release this machine may install, plus the changelog up to it and the
+/// newest version still held back by its rollout percentage
+fn select_release(releases: &[Release], current: &str, bucket_for: impl Fn(&str) -> u8)
+    -> (Option<Release>, Vec<ChangelogEntry>, Option<String>)
+{
+    let mut newer: Vec<&Release> = releases.iter().filter(|r| is_newer(&r.version, current)).collect();
+    newer.sort_by_key(|r| std::cmp::Reverse(parse_version(&r.version)));
+
+    let target = newer.iter().position(|r| bucket_for(&r.version) < r.rollout_percentage.min(100));
+    let held_back = match target {
+        Some(0) => None,
+        _ => newer.first().map(|r| r.version.clone()),
+    };
+    let changelog = target.map(|index| newer[index..].iter()
+        .map(|r| ChangelogEntry { version: r.version.clone(), released_at: r.released_at.clone(), notes: r.notes.clone() })
+        .collect())
+        .unwrap_or_default();
+    (target.map(|index| newer[index].clone()), changelog, held_back)
+}
+
+fn fetch_status() -> Result<UpdateStatus> {
+    let public_key = RELEASE_PUBLIC_KEY
+        .ok_or_else(|| anyhow!("This build has no release signing key; update checks are disabled"))?;
+    let response = reqwest::blocking::Client::new()
+        .get(manifest_url())
+        .timeout(Duration::from_secs(20))
+        .send()?;
+    if !response.status().is_success() {
+        bail!("Release manifest returned {}", response.status());
+    }
+    let manifest = verify_manifest(&response.text()?, public_key)?;
+
+    let machine_id = crate::license::machine_fingerprint();
+    let (latest, changelog, held_back) =
+        select_release(&manifest.releases, CURRENT_VERSION, |version| rollout_bucket(&machine_id, version));
+
+    let settings = crate::preferences::load().updates;
+    let now = chrono::Utc::now().timestamp_millis();
+    let deferred_until = match (&latest, &settings.deferred_version, settings.deferred_until) {
+        (Some(latest), Some(version), Some(until)) if &latest.version == version && until > now => Some(until),
+        _ => None,
+    };
+    let state = match (&latest, deferred_until) {
+        (None, _) => UpdateState::UpToDate,
+        (Some(_), Some(_)) => UpdateState::Deferred,
+        (Some(_), None) => UpdateState::Available,
+    };
+    Ok(UpdateStatus {
+        state,
+        current_version: CURRENT_VERSION.to_string(),
+        latest,
+        changelog,
+        held_back,
+        deferred_until,
+        checked_at: now,
+        error: None,
+    })
+}
+
+/// Run a check and emit "update_status" (blocking: network I/O)
+fn check_and_emit(app: &AppHandle) -> UpdateStatus {
+    let status = fetch_status().unwrap_or_else(|e| {
+        warn!("⚠️ LED 9101: Update check failed: {}", e);
+        UpdateStatus {
+            state: UpdateState::Error,
+            current_version: CURRENT_VERSION.to_string(),
+            latest: None,
+            changelog: Vec::new(),
+            held_back: None,
+            deferred_until: None,
+            checked_at: chrono::Utc::now().timestamp_millis(),
+            error: Some(e.to_string()),
+        }
+    });
+    if status.state == UpdateState::Available {
+        info!("⬆️ LED 9100: Update available: {} -> {}", CURRENT_VERSION,
+              status.latest.as_ref().map(|r| r.version.as_str()).unwrap_or(""));
+    }
+    *LAST_STATUS.lock().unwrap() = Some(status.clone());
+    if let Err(e) = app.emit_all("update_status", status.clone()) {
+        error!("❌ LED 9102: Failed to emit update_status: {:?}", e);
+    }
+    status
+}
+
+/// Background check shortly after startup and then periodically (if auto_check is on)
+pub fn start_update_checker(app: AppHandle) {
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(STARTUP_DELAY_SECS));
+        loop {
+            if crate::preferences::load().updates.auto_check {
+                check_and_emit(&app);
+            }
+            std::thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECS));
+        }
+    });
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<UpdateStatus, String> {
+    tokio::task::spawn_blocking(move || check_and_emit(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Hide `version` until `days` from now (a newer release is still offered)
+#[tauri::command]
+pub fn defer_update(app: AppHandle, version: String, days: u32) -> Result<UpdateStatus, String> {
+    let until = chrono::Utc::now().timestamp_millis() + days.max(1) as i64 * 24 * 60 * 60 * 1000;
+    crate::preferences::update(|p| {
+        p.updates.deferred_version = Some(version.clone());
+        p.updates.deferred_until = Some(until);
+    }).map_err(|e| e.to_string())?;
+    info!("⏸️ Update {} deferred for {} day(s)", version, days.max(1));
+
+    let mut status = LAST_STATUS.lock().unwrap().clone()
+        .ok_or("No update check has run yet")?;
+    if status.latest.as_ref().map(|r| &r.version) == Some(&version) {
+        status.state = UpdateState::Deferred;
+        status.deferred_until = Some(until);
+        *LAST_STATUS.lock().unwrap() = Some(status.clone());
+        let _ = app.emit_all("update_status", status.clone());
+    }
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn set_update_auto_check(enabled: bool) -> Result<UpdateSettings, String> {
+    crate::preferences::update(|p| p.updates.auto_check = enabled).map_err(|e| e.to_string())?;
+    Ok(crate::preferences::load().updates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str, rollout: u8) -> Release {
+        Release {
+            version: version.to_string(),
+            released_at: None,
+            notes: vec![format!("Changes in {}", version)],
+            rollout_percentage: rollout,
+            download_url: None,
+            sha256: None,
+        }
+    }
+
+    #[test]
+    fn test_staged_rollout_selects_newest_eligible_release() {
+        let releases = vec![release("0.1.0", 100), release("0.2.0", 100), release("v0.3.0", 100), release("0.4.0", 10)];
+        // Bucket 50: 0.4.0 (10%) is held back, 0.3.0 is offered with 0.2.0's notes too
+        let (latest, changelog, held_back) = select_release(&releases, "0.1.0", |_| 50);
+        assert_eq!(latest.unwrap().version, "v0.3.0");
+        assert_eq!(changelog.iter().map(|c| c.version.as_str()).collect::<Vec<_>>(), vec!["v0.3.0", "0.2.0"]);
+        assert_eq!(held_back.as_deref(), Some("0.4.0"));
+
+        let (latest, _, held_back) = select_release(&releases, "0.1.0", |_| 5);
+        assert_eq!(latest.unwrap().version, "0.4.0");
+        assert_eq!(held_back, None);
+        assert!(select_release(&releases, "0.4.0", |_| 0).0.is_none());
+    }
+}
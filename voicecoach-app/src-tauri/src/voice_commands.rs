@@ -0,0 +1,330 @@
+// Voice Commands - hands-free quick actions spoken by the rep
+// A command is the wake word followed by a command phrase, on the rep's channel only:
+// "coach, bookmark that", "coach, show the Gong battlecard", "coach, pause" / "coach,
+// resume". With Vosk a second recognizer restricted to the command grammar (wake word,
+// phrases and watched competitor names, everything else [unk]) runs on the mic audio
+// alongside transcription; Deepgram has no local model, so the rep's final lines are
+// matched instead. The wake word on its own switches transcription to the command
+// phrases for the next utterance (recognition_grammar), so "coach ... bookmark that"
+// works with a pause. A recognized command runs its action - a session bookmark, the
+// battlecard of the named (or last mentioned) competitor as "battlecard_requested",
+// pausing or resuming coaching prompts - and is reported as "voice_command".
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager};
+use vosk::{CompleteResult, DecodingState, Model, Recognizer};
+use log::{info, warn, error};
+
+use crate::competitor_watch::Competitor;
+use crate::recognition_grammar::GrammarPurpose;
+
+// The same action is not repeated within this time (grammar and transcript paths)
+const REPEAT_MS: u64 = 3_000;
+// How long the command phrases are listened for after the wake word alone
+const WAKE_LISTEN_SECS: u64 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceAction {
+    Bookmark,
+    ShowBattlecard,
+    PauseCoaching,
+    ResumeCoaching,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct VoiceCommandSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_wake_word")]
+    pub wake_word: String,
+}
+
+fn default_true() -> bool { true }
+fn default_wake_word() -> String { "coach".to_string() }
+
+impl Default for VoiceCommandSettings {
+    fn default() -> Self {
+        Self { enabled: true, wake_word: default_wake_word() }
+    }
+}
+
+// Payload of "voice_command"
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct VoiceCommand {
+    pub action: VoiceAction,
+    pub phrase: String,              // What was recognized
+    pub competitor: Option<String>,  // ShowBattlecard: the competitor named
+    pub ok: bool,
+    pub detail: Option<String>,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct VoiceCommandInfo {
+    pub settings: VoiceCommandSettings,
+    pub phrases: Vec<String>,        // Everything the command grammar accepts
+}
+
+// Last action run and when (repeat suppression)
+static LAST: Lazy<Mutex<Option<(VoiceAction, u64)>>> = Lazy::new(|| Mutex::new(None));
+// Settings and watched competitors, cached for the call (reloaded at the next one)
+type CommandConfig = (VoiceCommandSettings, Vec<Competitor>);
+static CACHED: Lazy<Mutex<Option<CommandConfig>>> = Lazy::new(|| Mutex::new(None));
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Lowercase words without punctuation ("Gong.io" is "gong io"), [unk] dropped
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .replace("battlecard", "battle card")
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '\'' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .filter(|w| *w != "unk")
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Command phrases after the wake word ("{competitor}" stands for each name)
+const PHRASES: &[(VoiceAction, &str)] = &[
+    (VoiceAction::Bookmark, "bookmark that"),
+    (VoiceAction::Bookmark, "bookmark this"),
+    (VoiceAction::Bookmark, "bookmark"),
+    (VoiceAction::ShowBattlecard, "show battle card"),
+    (VoiceAction::ShowBattlecard, "show the battle card"),
+    (VoiceAction::ShowBattlecard, "show {competitor} battle card"),
+    (VoiceAction::ShowBattlecard, "show the {competitor} battle card"),
+    (VoiceAction::PauseCoaching, "pause coaching"),
+    (VoiceAction::PauseCoaching, "pause"),
+    (VoiceAction::ResumeCoaching, "resume coaching"),
+    (VoiceAction::ResumeCoaching, "resume"),
+];
+
+fn competitor_names(competitors: &[Competitor]) -> Vec<(String, String)> {
+    competitors.iter()
+        .flat_map(|c| std::iter::once(&c.name).chain(&c.aliases).map(move |n| (normalize(n), c.name.clone())))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+/// The command phrases, without the wake word
+fn command_phrases(competitors: &[Competitor]) -> Vec<String> {
+    let names = competitor_names(competitors);
+    let mut phrases = Vec::new();
+    for (_, phrase) in PHRASES {
+        if phrase.contains("{competitor}") {
+            phrases.extend(names.iter().map(|(name, _)| phrase.replace("{competitor}", name)));
+        } else {
+            phrases.push(phrase.to_string());
+        }
+    }
+    phrases
+}
+
+/// Every phrase the command grammar accepts
+fn grammar(settings: &VoiceCommandSettings, competitors: &[Competitor]) -> Vec<String> {
+    let wake = normalize(&settings.wake_word);
+    command_phrases(competitors).iter().map(|phrase| format!("{} {}", wake, phrase)).collect()
+}
+
+/// The command in a recognized text: wake word, then a phrase (nothing after it)
+fn parse(text: &str, wake_word: &str, competitors: &[Competitor]) -> Option<(VoiceAction, Option<String>)> {
+    let text = format!(" {}", normalize(text));
+    let wake = format!(" {} ", normalize(wake_word));
+    let rest = &text[text.rfind(&wake)? + wake.len()..];
+    let names = competitor_names(competitors);
+    PHRASES.iter().find_map(|(action, phrase)| match phrase.split_once("{competitor}") {
+        None => (rest == *phrase).then(|| (*action, None)),
+        Some((before, after)) => {
+            let name = rest.strip_prefix(before)?.strip_suffix(after)?;
+            names.iter().find(|(n, _)| n == name).map(|(_, competitor)| (*action, Some(competitor.clone())))
+        }
+    })
+}
+
+fn run(app: &AppHandle, action: VoiceAction, competitor: Option<String>) -> Result<Option<String>, String> {
+    match action {
+        VoiceAction::Bookmark => {
+            let bookmark = crate::session_store::add_bookmark(Some("Voice bookmark".to_string()), "voice")
+                .map_err(|e| e.to_string())?;
+            Ok(Some(format!("Bookmarked at {}s", bookmark.offset_ms / 1000)))
+        }
+        VoiceAction::ShowBattlecard => {
+            let mention = crate::competitor_watch::battlecard_for(competitor.as_deref())
+                .ok_or("No competitor named or mentioned yet")?;
+            let detail = format!("{} ({} points)", mention.competitor, mention.points.len());
+            if let Err(e) = app.emit_all("battlecard_requested", mention) {
+                error!("Failed to emit battlecard_requested: {:?}", e);
+            }
+            Ok(Some(detail))
+        }
+        VoiceAction::PauseCoaching => {
+            crate::coaching_cooldown::set_paused(true);
+            Ok(None)
+        }
+        VoiceAction::ResumeCoaching => {
+            crate::coaching_cooldown::set_paused(false);
+            Ok(None)
+        }
+    }
+}
+
+/// Start of a new call: pick up settings and watchlist edits
+pub fn begin_call() {
+    *CACHED.lock().unwrap() = None;
+}
+
+// Settings and watchlist for the call, None when voice commands are off
+fn config() -> Option<CommandConfig> {
+    let mut cached = CACHED.lock().unwrap();
+    let config = cached.get_or_insert_with(|| {
+        let preferences = crate::preferences::load();
+        (preferences.voice_commands, preferences.competitors.competitors)
+    });
+    config.0.enabled.then(|| config.clone())
+}
+
+/// Run the command in a recognized text, if any
+fn handle(app: &AppHandle, text: &str) {
+    let command = match config() {
+        Some((settings, competitors)) => parse(text, &settings.wake_word, &competitors),
+        None => return,
+    };
+    let (action, competitor) = match command {
+        Some(command) => command,
+        None => return,
+    };
+    let now = now_ms();
+    {
+        let mut last = LAST.lock().unwrap();
+        if matches!(*last, Some((previous, at)) if previous == action && now.saturating_sub(at) < REPEAT_MS) {
+            return;
+        }
+        *last = Some((action, now));
+    }
+
+    let result = run(app, action, competitor.clone());
+    info!("🗣️ Voice command {:?}: {:?}", action, result);
+    let command = VoiceCommand {
+        action,
+        phrase: normalize(text),
+        competitor,
+        ok: result.is_ok(),
+        detail: result.unwrap_or_else(Some),
+        timestamp: now,
+    };
+    if let Err(e) = app.emit_all("voice_command", command) {
+        error!("Failed to emit voice_command: {:?}", e);
+    }
+}
+
+/// Recognizer limited to the command grammar (Vosk streams run it beside transcription)
+pub fn build_recognizer(model: &Model, sample_rate: f32) -> Option<Recognizer> {
+    let preferences = crate::preferences::load();
+    if !preferences.voice_commands.enabled {
+        return None;
+    }
+    let mut phrases = grammar(&preferences.voice_commands, &preferences.competitors.competitors);
+    phrases.push(normalize(&preferences.voice_commands.wake_word));
+    phrases.push("[unk]".to_string());
+    let recognizer = Recognizer::new_with_grammar(model, sample_rate, &phrases);
+    if recognizer.is_none() {
+        warn!("⚠️ Voice command recognizer could not be created");
+    }
+    recognizer
+}
+
+/// Feed mic audio to the command recognizer; a finished utterance is checked for a command
+pub fn accept(app: &AppHandle, recognizer: &mut Recognizer, samples: &[i16]) {
+    if let Ok(DecodingState::Finalized) = recognizer.accept_waveform(samples) {
+        if let CompleteResult::Single(result) = recognizer.final_result() {
+            if !result.text.is_empty() && result.text != "[unk]" && !listen_after_wake(app, result.text) {
+                handle(app, result.text);
+            }
+        }
+    }
+}
+
+/// The wake word on its own: recognize the next utterance against the command phrases
+fn listen_after_wake(app: &AppHandle, text: &str) -> bool {
+    let (settings, competitors) = match config() {
+        Some(config) => config,
+        None => return false,
+    };
+    if normalize(text) != normalize(&settings.wake_word) {
+        return false;
+    }
+    let phrases = command_phrases(&competitors);
+    if let Err(e) = crate::recognition_grammar::activate(app, &phrases, GrammarPurpose::VoiceCommand, 1, WAKE_LISTEN_SECS) {
+        warn!("⚠️ Command grammar not applied after the wake word: {}", e);
+    }
+    true
+}
+
+/// A command phrase recognized under the command grammar (the wake word came before)
+pub fn handle_after_wake(app: &AppHandle, phrase: &str) {
+    if let Some((settings, _)) = config() {
+        handle(app, &format!("{} {}", settings.wake_word, phrase));
+    }
+}
+
+/// Final transcript line (engines without a command recognizer); only the rep's count
+pub fn observe_transcript(app: &AppHandle, text: &str, is_user: bool) {
+    if is_user {
+        handle(app, text);
+    }
+}
+
+// ========== Tauri Commands ==========
+
+#[tauri::command]
+pub fn get_voice_commands() -> Result<VoiceCommandInfo, String> {
+    let preferences = crate::preferences::load();
+    Ok(VoiceCommandInfo {
+        phrases: grammar(&preferences.voice_commands, &preferences.competitors.competitors),
+        settings: preferences.voice_commands,
+    })
+}
+
+// Applies to transcription streams started afterwards
+#[tauri::command]
+pub fn set_voice_command_settings(settings: VoiceCommandSettings) -> Result<VoiceCommandInfo, String> {
+    if normalize(&settings.wake_word).is_empty() {
+        return Err("The wake word can't be empty".to_string());
+    }
+    crate::preferences::update(|p| p.voice_commands = settings.clone())
+        .map_err(|e| e.to_string())?;
+    begin_call();
+    get_voice_commands()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_need_the_wake_word_and_a_whole_phrase() {
+        let competitors = vec![Competitor { name: "Gong".to_string(), aliases: vec!["Gong.io".to_string()], battlecard: None }];
+        assert_eq!(parse("Coach, bookmark that.", "coach", &competitors), Some((VoiceAction::Bookmark, None)));
+        assert_eq!(parse("[unk] coach show the gong battlecard", "coach", &competitors),
+            Some((VoiceAction::ShowBattlecard, Some("Gong".to_string()))));
+        assert_eq!(parse("coach show gong io battle card", "coach", &competitors),
+            Some((VoiceAction::ShowBattlecard, Some("Gong".to_string()))));
+        assert_eq!(parse("Coach, pause.", "coach", &competitors), Some((VoiceAction::PauseCoaching, None)));
+
+        // Ordinary speech that happens to contain the words
+        assert_eq!(parse("I'd like to bookmark that page", "coach", &competitors), None);
+        assert_eq!(parse("our coach said pause before the price", "coach", &competitors), None);
+        assert_eq!(parse("coach show chorus battle card", "coach", &competitors), None);
+
+        let phrases = grammar(&VoiceCommandSettings::default(), &competitors);
+        assert!(phrases.contains(&"coach show the gong io battle card".to_string()));
+        assert!(phrases.iter().all(|p| p.starts_with("coach ")));
+    }
+}
@@ -0,0 +1,166 @@
+// Voice Notes - the coach's spoken notes after a call, transcribed into the session
+// record_voice_note captures the microphone only (the selected input device, nothing
+// from system audio) until stop_voice_note is called or max_seconds pass, then
+// transcribes the recording locally with the Vosk model (the preloaded one when
+// available) and attaches the text to the session as a coach note, where
+// search_in_session finds it alongside the transcript. Progress is reported as
+// "voice_note_progress" while recording. The audio itself is not kept.
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+use crate::file_transcription::VOSK_SAMPLE_RATE;
+use crate::recognizer_pool::{self, RecognizerPool};
+use crate::session_store::CoachNote;
+
+const DEFAULT_SECONDS: u32 = 60;
+const MAX_SECONDS: u32 = 300;
+const PROGRESS_INTERVAL_MS: u64 = 250;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct VoiceNoteProgress {
+    pub session_id: String,
+    pub elapsed_ms: u64,
+    pub max_ms: u64,
+    pub rms: f32,
+}
+
+// A recording is in progress / has been asked to stop
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static STOP: AtomicBool = AtomicBool::new(false);
+
+/// Record mono mic audio until stopped or `max_ms`; (samples, sample rate)
+fn record(app: &AppHandle, session_id: &str, max_ms: u64) -> Result<(Vec<f32>, u32), String> {
+    let host = cpal::default_host();
+    let device = crate::device_selection::select_input_device(&host)
+        .ok_or("No input device available")?;
+    let device_name = device.name().unwrap_or_default();
+    let supported = device.default_input_config()
+        .map_err(|e| format!("Failed to get device config: {}", e))?;
+    let channels = supported.channels() as usize;
+    let sample_rate = supported.sample_rate().0;
+    let sample_format = supported.sample_format();
+    let config: cpal::StreamConfig = supported.into();
+    info!("📝 Recording voice note for session {} on '{}'", session_id, device_name);
+
+    let audio = Arc::new(Mutex::new(Vec::<f32>::new()));
+    let audio_clone = audio.clone();
+    let stream = crate::sample_format::build_input_stream(
+        &device,
+        &config,
+        sample_format,
+        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+            let mono = if channels > 1 { crate::device_conflict::downmix_to_mono(data, channels) } else { data.to_vec() };
+            if let Ok(mut audio) = audio_clone.try_lock() {
+                audio.extend_from_slice(&mono);
+            }
+        },
+        |err| error!("❌ Voice note stream error: {:?}", err),
+    ).map_err(|e| format!("Failed to open '{}': {}", device_name, e))?;
+    stream.play().map_err(|e| format!("Failed to start stream: {}", e))?;
+
+    let started = Instant::now();
+    while !STOP.load(Ordering::Relaxed) && (started.elapsed().as_millis() as u64) < max_ms {
+        std::thread::sleep(Duration::from_millis(PROGRESS_INTERVAL_MS));
+        let rms = {
+            let audio = audio.lock().unwrap();
+            let recent = &audio[audio.len().saturating_sub(sample_rate as usize / 4)..];
+            (recent.iter().map(|s| s * s).sum::<f32>() / recent.len().max(1) as f32).sqrt()
+        };
+        let _ = app.emit_all("voice_note_progress", VoiceNoteProgress {
+            session_id: session_id.to_string(),
+            elapsed_ms: (started.elapsed().as_millis() as u64).min(max_ms),
+            max_ms,
+            rms,
+        });
+    }
+    drop(stream);
+
+    let samples = std::mem::take(&mut *audio.lock().unwrap());
+    if samples.is_empty() {
+        return Err(format!("No audio received from '{}'", device_name));
+    }
+    Ok((samples, sample_rate))
+}
+
+/// Transcribe 16kHz audio with a single recognizer
+fn transcribe(model: Arc<vosk::Model>, samples: &[f32]) -> Result<String, String> {
+    let pcm: Vec<i16> = samples.iter().map(|s| (s.clamp(-1.0, 1.0) * 32767.0) as i16).collect();
+    let pool = RecognizerPool::new(model, 1, VOSK_SAMPLE_RATE as f32);
+    let mut decoded = recognizer_pool::wait(pool.feed("voice_note", pcm)).map_err(|e| e.to_string())?;
+    decoded.extend(recognizer_pool::wait(pool.finish("voice_note")).map_err(|e| e.to_string())?);
+    Ok(decoded.into_iter().map(|d| d.text).collect::<Vec<_>>().join(" "))
+}
+
+// ========== Tauri Commands ==========
+
+// Record a voice note (until stop_voice_note or max_seconds, default 60) and attach its
+// transcript to the session
+#[tauri::command]
+pub async fn record_voice_note(
+    app: AppHandle,
+    state: tauri::State<'_, crate::VoskAppState>,
+    session_id: String,
+    max_seconds: Option<u32>,
+) -> Result<CoachNote, String> {
+    if crate::privacy::is_muted() {
+        return Err("Capture is muted (privacy mode)".to_string());
+    }
+    if !crate::session_store::session_exists(&session_id) {
+        return Err(format!("No session {}", session_id));
+    }
+    if RECORDING.swap(true, Ordering::SeqCst) {
+        return Err("A voice note is already being recorded".to_string());
+    }
+    STOP.store(false, Ordering::SeqCst);
+    let (model_path, preloaded) = state.preloaded();
+    let max_ms = max_seconds.unwrap_or(DEFAULT_SECONDS).clamp(3, MAX_SECONDS) as u64 * 1000;
+    let recorded_at = chrono::Utc::now().timestamp_millis() as u64;
+
+    let id = session_id.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let recorded = record(&app, &id, max_ms);
+        RECORDING.store(false, Ordering::SeqCst);
+        let (samples, sample_rate) = recorded?;
+        let samples = crate::file_transcription::resample_to_16k(&samples, sample_rate);
+        let model = match preloaded {
+            Some(model) => model,
+            None => Arc::new(vosk::Model::new(model_path.as_str())
+                .ok_or_else(|| format!("Failed to load model at: {}", model_path))?),
+        };
+        let duration_ms = samples.len() as u64 * 1000 / VOSK_SAMPLE_RATE as u64;
+        transcribe(model, &samples).map(|text| (text, duration_ms))
+    })
+        .await
+        .map_err(|e| format!("Voice note task failed: {}", e))?;
+    let (text, duration_ms) = result?;
+    if text.trim().is_empty() {
+        return Err("Nothing was recognized in the voice note".to_string());
+    }
+
+    let note = crate::session_store::add_note(&session_id, recorded_at, duration_ms, &text)
+        .map_err(|e| e.to_string())?;
+    info!("📝 Voice note {} ({}ms, {} words) attached to session {}", note.id, duration_ms, text.split_whitespace().count(), session_id);
+    Ok(note)
+}
+
+// Finish the voice note being recorded (it is then transcribed)
+#[tauri::command]
+pub fn stop_voice_note() -> Result<bool, String> {
+    let recording = RECORDING.load(Ordering::SeqCst);
+    if recording {
+        STOP.store(true, Ordering::SeqCst);
+    }
+    Ok(recording)
+}
+
+// Coach notes of a session (the current one when no id is given)
+#[tauri::command]
+pub fn get_session_notes(session_id: Option<String>) -> Result<Vec<CoachNote>, String> {
+    crate::session_store::session_notes(session_id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,212 @@
+// Waveform - min/max peaks of a session recording for the playback UI
+// The review screen draws a scrubbing waveform of the call recording linked to the
+// session, zoomed anywhere from the whole call down to a few seconds. Shipping the
+// audio over IPC for that would be megabytes per redraw, so peaks are computed here:
+// the recording is reduced once to the min/max of every 10 ms block, kept on disk
+// beside the sessions (waveforms/<id>.peaks, rebuilt when the recording changes) and
+// the last one in memory while the rep zooms; get_waveform merges the blocks of the
+// requested range into at most `resolution` points.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use log::{info, warn};
+
+const WAVEFORMS_DIR: &str = "waveforms";
+const MAGIC: &[u8; 8] = b"VCPEAKS1";
+const HEADER_BYTES: usize = 8 + 8 + 8 + 4 + 4 + 8;
+const BLOCK_MS: u32 = 10;
+const DEFAULT_RESOLUTION: u32 = 1000;
+const MAX_RESOLUTION: u32 = 8000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Waveform {
+    pub session_id: String,
+    pub start_ms: u64,
+    pub end_ms: u64,             // Clamped to the recording
+    pub duration_ms: u64,        // Of the whole recording
+    pub bucket_ms: f32,          // Time each point covers
+    pub min: Vec<f32>,           // Per point, -1.0..=1.0
+    pub max: Vec<f32>,
+}
+
+/// Block peaks of a recording, with what they were computed from
+#[derive(Debug, Clone, PartialEq)]
+struct Peaks {
+    source_len: u64,
+    source_modified: u64,
+    sample_rate: u32,
+    block_samples: u32,
+    total_samples: u64,
+    blocks: Vec<(i16, i16)>,
+}
+
+impl Peaks {
+    fn compute(samples: &[f32], sample_rate: u32, source_len: u64, source_modified: u64) -> Self {
+        let block_samples = (sample_rate * BLOCK_MS / 1000).max(1);
+        let quantize = |s: f32| (s.clamp(-1.0, 1.0) * 32767.0) as i16;
+        let blocks = samples.chunks(block_samples as usize)
+            .map(|block| block.iter().fold((i16::MAX, i16::MIN), |(lo, hi), &s| (lo.min(quantize(s)), hi.max(quantize(s)))))
+            .collect();
+        Self { source_len, source_modified, sample_rate, block_samples, total_samples: samples.len() as u64, blocks }
+    }
+
+    fn block_ms(&self) -> f64 {
+        self.block_samples as f64 * 1000.0 / self.sample_rate as f64
+    }
+
+    fn duration_ms(&self) -> u64 {
+        self.total_samples * 1000 / self.sample_rate.max(1) as u64
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_BYTES + self.blocks.len() * 4);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.source_len.to_le_bytes());
+        bytes.extend_from_slice(&self.source_modified.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&self.block_samples.to_le_bytes());
+        bytes.extend_from_slice(&self.total_samples.to_le_bytes());
+        for (lo, hi) in &self.blocks {
+            bytes.extend_from_slice(&lo.to_le_bytes());
+            bytes.extend_from_slice(&hi.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_BYTES || &bytes[..8] != MAGIC || (bytes.len() - HEADER_BYTES) % 4 != 0 {
+            return None;
+        }
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let i16_at = |at: usize| i16::from_le_bytes(bytes[at..at + 2].try_into().unwrap());
+        Some(Self {
+            source_len: u64_at(8),
+            source_modified: u64_at(16),
+            sample_rate: u32_at(24).max(1),
+            block_samples: u32_at(28).max(1),
+            total_samples: u64_at(32),
+            blocks: (HEADER_BYTES..bytes.len()).step_by(4).map(|at| (i16_at(at), i16_at(at + 2))).collect(),
+        })
+    }
+
+    /// Merge the blocks of [start_ms, end_ms) into at most `resolution` points
+    fn range(&self, start_ms: u64, end_ms: u64, resolution: usize) -> (f32, Vec<f32>, Vec<f32>) {
+        let block_ms = self.block_ms();
+        let first = ((start_ms as f64 / block_ms) as usize).min(self.blocks.len());
+        let last = ((end_ms as f64 / block_ms).ceil() as usize).clamp(first, self.blocks.len());
+        let count = last - first;
+        let points = count.min(resolution.max(1));
+        let (mut min, mut max) = (Vec::with_capacity(points), Vec::with_capacity(points));
+        for i in 0..points {
+            let blocks = &self.blocks[first + i * count / points..first + (i + 1) * count / points];
+            let (lo, hi) = blocks.iter().fold((i16::MAX, i16::MIN), |(lo, hi), &(l, h)| (lo.min(l), hi.max(h)));
+            min.push(lo as f32 / 32767.0);
+            max.push(hi as f32 / 32767.0);
+        }
+        let bucket_ms = if points == 0 { 0.0 } else { (count as f64 * block_ms / points as f64) as f32 };
+        (bucket_ms, min, max)
+    }
+}
+
+// Peaks last served (session id and peaks), reused while the rep zooms and scrolls
+type LoadedPeaks = (String, Arc<Peaks>);
+static LOADED: Lazy<Mutex<Option<LoadedPeaks>>> = Lazy::new(|| Mutex::new(None));
+
+fn waveforms_dir() -> PathBuf {
+    crate::app_paths::data_dir().join(WAVEFORMS_DIR)
+}
+
+/// (size, modification time in seconds) of the recording, to tell when it changed
+fn source_stamp(path: &Path) -> Result<(u64, u64)> {
+    let metadata = fs::metadata(path).context(format!("Recording {:?} is missing", path))?;
+    let modified = metadata.modified().ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    Ok((metadata.len(), modified))
+}
+
+/// Peaks of a session recording: in memory, from the cache file, or computed and cached
+fn peaks_for(session_id: &str, recording: &str) -> Result<Arc<Peaks>> {
+    let (source_len, source_modified) = source_stamp(Path::new(recording))?;
+    let fresh = |p: &Peaks| p.source_len == source_len && p.source_modified == source_modified;
+    {
+        let loaded = LOADED.lock().unwrap();
+        if let Some((id, peaks)) = loaded.as_ref() {
+            if id == session_id && fresh(peaks) {
+                return Ok(peaks.clone());
+            }
+        }
+    }
+
+    let cache = waveforms_dir().join(format!("{}.peaks", session_id));
+    let cached = fs::read(&cache).ok().and_then(|bytes| Peaks::decode(&bytes)).filter(|p| fresh(p));
+    let peaks = match cached {
+        Some(peaks) => peaks,
+        None => {
+            let (samples, sample_rate) = crate::file_transcription::read_wav_mono(recording)?;
+            let peaks = Peaks::compute(&samples, sample_rate, source_len, source_modified);
+            info!("〰️ Waveform of session {}: {} blocks from {}", session_id, peaks.blocks.len(), recording);
+            if let Err(e) = fs::create_dir_all(waveforms_dir()).and_then(|_| fs::write(&cache, peaks.encode())) {
+                warn!("⚠️ Failed to cache waveform {:?}: {}", cache, e);
+            }
+            peaks
+        }
+    };
+    let peaks = Arc::new(peaks);
+    *LOADED.lock().unwrap() = Some((session_id.to_string(), peaks.clone()));
+    Ok(peaks)
+}
+
+// ========== Tauri Commands ==========
+
+// Min/max peaks of the session recording between start_ms and end_ms (the whole
+// recording by default) in at most `resolution` points (1000 by default)
+#[tauri::command]
+pub async fn get_waveform(session_id: String, start_ms: Option<u64>, end_ms: Option<u64>, resolution: Option<u32>) -> Result<Waveform, String> {
+    let session = crate::session_store::load_session(Some(session_id.clone())).map_err(|e| e.to_string())?;
+    let recording = session.recording.ok_or_else(|| format!("Session {} has no recording", session_id))?;
+    let id = session_id.clone();
+    let peaks = tokio::task::spawn_blocking(move || peaks_for(&id, &recording))
+        .await
+        .map_err(|e| format!("Waveform task failed: {}", e))?
+        .map_err(|e| e.to_string())?;
+
+    let duration_ms = peaks.duration_ms();
+    let start_ms = start_ms.unwrap_or(0).min(duration_ms);
+    let end_ms = end_ms.unwrap_or(duration_ms).min(duration_ms);
+    if end_ms <= start_ms {
+        return Err(format!("Empty range {}-{} ms (the recording is {} ms)", start_ms, end_ms, duration_ms));
+    }
+    let resolution = resolution.unwrap_or(DEFAULT_RESOLUTION).clamp(1, MAX_RESOLUTION) as usize;
+    let (bucket_ms, min, max) = peaks.range(start_ms, end_ms, resolution);
+    Ok(Waveform { session_id, start_ms, end_ms, duration_ms, bucket_ms, min, max })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peaks_round_trip_and_merge_into_buckets() {
+        // One second at 1 kHz: silence, then a 0.5 pulse in the second half
+        let samples: Vec<f32> = (0..1000).map(|i| if i >= 500 && i % 2 == 0 { 0.5 } else if i >= 500 { -0.25 } else { 0.0 }).collect();
+        let peaks = Peaks::compute(&samples, 1000, 2_044, 1_700_000_000);
+        assert_eq!((peaks.block_samples, peaks.blocks.len(), peaks.duration_ms()), (10, 100, 1000));
+        assert_eq!(Peaks::decode(&peaks.encode()), Some(peaks.clone()));
+        assert_eq!(Peaks::decode(b"not a peak file"), None);
+
+        let (bucket_ms, min, max) = peaks.range(0, 1000, 4);
+        assert_eq!((bucket_ms, min.len()), (250.0, 4));
+        assert_eq!(max[..2], [0.0, 0.0]);
+        assert!((max[3] - 0.5).abs() < 0.001 && (min[3] + 0.25).abs() < 0.001);
+
+        // Zoomed in further than the blocks: one point per block
+        let (bucket_ms, min, _) = peaks.range(500, 530, 100);
+        assert_eq!((bucket_ms, min.len()), (10.0, 3));
+    }
+}
@@ -0,0 +1,274 @@
+// Whisper Channel - coach audio on a second output device only the rep hears
+// Reps who wear the call in one ear can put a separate earbud on the whisper channel:
+// coach audio is played there and never on the device carrying the call, so the
+// prospect can't hear it through an open mic or a shared speaker. The device carrying
+// the call is the default output (what system-audio capture records) and the sidetone
+// headset when one is set; the whisper device must be a different one. Clips are
+// queued and played in order on a worker thread that opens the device per clip. For
+// now the channel carries short cues: a chime when a coaching prompt is on its way
+// (two for urgent ones) and when a manager sends a private suggestion.
+
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use once_cell::sync::Lazy;
+use log::{info, warn, error};
+
+use crate::ollama_integration::PromptUrgency;
+
+const MAX_VOLUME: f32 = 1.0;
+const CUE_RATE: u32 = 48_000;
+const CUE_HZ: f32 = 880.0;
+const CUE_MS: u32 = 90;
+const CUE_GAP_MS: u32 = 70;
+// The device buffer drains after the last sample before the stream is closed
+const DRAIN_MS: u64 = 150;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct WhisperChannelSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Output device name; must not be a device carrying the call
+    #[serde(default)]
+    pub output_device: Option<String>,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    /// Chime when a coaching prompt is about to appear
+    #[serde(default = "default_true")]
+    pub prompt_cues: bool,
+    /// Chime when a manager sends a private suggestion (live listen)
+    #[serde(default = "default_true")]
+    pub manager_cues: bool,
+}
+
+fn default_volume() -> f32 { 0.4 }
+fn default_true() -> bool { true }
+
+impl Default for WhisperChannelSettings {
+    fn default() -> Self {
+        Self { enabled: false, output_device: None, volume: default_volume(), prompt_cues: true, manager_cues: true }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct OutputDeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+    pub carries_call: bool,          // Can't be the whisper channel
+}
+
+/// Mono audio to play on the whisper channel
+struct Clip {
+    samples: Vec<f32>,
+    sample_rate: u32,
+}
+
+static QUEUE: Lazy<Mutex<Option<Sender<Clip>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Names of the output devices the call is heard on
+fn call_devices(host: &cpal::Host) -> Vec<String> {
+    let mut names: Vec<String> = host.default_output_device().and_then(|d| d.name().ok()).into_iter().collect();
+    if let Some(sidetone) = crate::preferences::load().sidetone.output_device {
+        names.push(sidetone);
+    }
+    names
+}
+
+/// Err unless `device` is an output device other than the ones carrying the call
+fn check_device(device: Option<&str>, outputs: &[String], call: &[String]) -> Result<(), String> {
+    let device = device.ok_or("Choose an output device for the whisper channel (the default output carries the call)")?;
+    if !outputs.iter().any(|o| o == device) {
+        return Err(format!("Output device not found: {}", device));
+    }
+    if call.iter().any(|c| c == device) {
+        return Err(format!("{} carries the call; choose a separate device (e.g. a second earbud)", device));
+    }
+    Ok(())
+}
+
+fn output_names(host: &cpal::Host) -> Vec<String> {
+    host.output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Short chimes: one, or two for urgent prompts
+fn cue_samples(sample_rate: u32, beeps: u32) -> Vec<f32> {
+    let beep = (sample_rate * CUE_MS / 1000) as usize;
+    let gap = (sample_rate * CUE_GAP_MS / 1000) as usize;
+    let mut samples = Vec::new();
+    for i in 0..beeps {
+        if i > 0 {
+            samples.resize(samples.len() + gap, 0.0);
+        }
+        samples.extend((0..beep).map(|n| {
+            let t = n as f32 / sample_rate as f32;
+            // Raised-cosine envelope so the beep doesn't click
+            let envelope = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / beep as f32).cos();
+            (2.0 * std::f32::consts::PI * CUE_HZ * t).sin() * envelope
+        }));
+    }
+    samples
+}
+
+fn build_output(device: &cpal::Device, config: &cpal::StreamConfig, format: cpal::SampleFormat, frames: Arc<Mutex<std::vec::IntoIter<f32>>>) -> Result<cpal::Stream, String> {
+    let channels = config.channels as usize;
+    crate::sample_format::build_output_stream(
+        device,
+        config,
+        format,
+        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+            let mut frames = match frames.try_lock() {
+                Ok(frames) => frames,
+                Err(_) => return,
+            };
+            for frame in data.chunks_mut(channels.max(1)) {
+                frame.fill(frames.next().unwrap_or(0.0));
+            }
+        },
+        |err| error!("Whisper channel output error: {:?}", err),
+    ).map_err(|e| format!("Failed to build whisper channel output: {}", e))
+}
+
+/// Play one clip on the configured device, resampled to its rate, and wait for it to end
+fn play_clip(clip: Clip) -> Result<(), String> {
+    let settings = crate::preferences::load().whisper_channel;
+    if !settings.enabled {
+        return Ok(());
+    }
+    let host = cpal::default_host();
+    check_device(settings.output_device.as_deref(), &output_names(&host), &call_devices(&host))?;
+    let name = settings.output_device.unwrap_or_default();
+    let device = host.output_devices().map_err(|e| e.to_string())?
+        .find(|d| d.name().map_or(false, |n| n == name))
+        .ok_or_else(|| format!("Output device not found: {}", name))?;
+    let supported = device.default_output_config().map_err(|e| format!("Failed to get output config: {}", e))?;
+    let config: cpal::StreamConfig = supported.config();
+
+    let rate = config.sample_rate.0;
+    let step = clip.sample_rate as f64 / rate as f64;
+    let length = (clip.samples.len() as f64 / step) as usize;
+    let samples: Vec<f32> = (0..length)
+        .map(|i| clip.samples.get((i as f64 * step) as usize).copied().unwrap_or(0.0) * settings.volume)
+        .collect();
+    let duration = Duration::from_millis(length as u64 * 1000 / rate.max(1) as u64 + DRAIN_MS);
+    let frames = Arc::new(Mutex::new(samples.into_iter()));
+    let stream = build_output(&device, &config, supported.sample_format(), frames)?;
+    stream.play().map_err(|e| format!("Failed to start whisper channel output: {}", e))?;
+    std::thread::sleep(duration);
+    Ok(())
+}
+
+fn worker(clips: Receiver<Clip>) {
+    for clip in clips {
+        if let Err(e) = play_clip(clip) {
+            warn!("⚠️ Whisper channel: {}", e);
+        }
+    }
+}
+
+/// Queue mono audio for the whisper channel (dropped when the channel is off)
+pub fn play(samples: Vec<f32>, sample_rate: u32) {
+    let mut queue = QUEUE.lock().unwrap();
+    if queue.is_none() {
+        let (sender, receiver) = mpsc::channel();
+        if let Err(e) = std::thread::Builder::new().name("whisper-channel".to_string()).spawn(move || worker(receiver)) {
+            error!("Failed to spawn whisper channel thread: {}", e);
+            return;
+        }
+        *queue = Some(sender);
+    }
+    if let Some(sender) = queue.as_ref() {
+        let _ = sender.send(Clip { samples, sample_rate });
+    }
+}
+
+/// A coaching prompt is on its way
+pub fn prompt_cue(urgency: PromptUrgency) {
+    let settings = crate::preferences::load().whisper_channel;
+    if settings.enabled && settings.prompt_cues {
+        play(cue_samples(CUE_RATE, if urgency == PromptUrgency::High { 2 } else { 1 }), CUE_RATE);
+    }
+}
+
+/// A manager sent a private suggestion
+pub fn manager_cue() {
+    let settings = crate::preferences::load().whisper_channel;
+    if settings.enabled && settings.manager_cues {
+        play(cue_samples(CUE_RATE, 1), CUE_RATE);
+    }
+}
+
+// ========== Tauri Commands ==========
+
+// Output devices, marking the ones that carry the call
+#[tauri::command]
+pub fn list_output_devices() -> Result<Vec<OutputDeviceInfo>, String> {
+    let host = cpal::default_host();
+    let default = host.default_output_device().and_then(|d| d.name().ok());
+    let call = call_devices(&host);
+    Ok(output_names(&host).into_iter()
+        .map(|name| OutputDeviceInfo {
+            is_default: default.as_deref() == Some(name.as_str()),
+            carries_call: call.contains(&name),
+            name,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_whisper_channel() -> Result<WhisperChannelSettings, String> {
+    Ok(crate::preferences::load().whisper_channel)
+}
+
+// Enabling needs an output device that doesn't carry the call
+#[tauri::command]
+pub fn set_whisper_channel(settings: WhisperChannelSettings) -> Result<WhisperChannelSettings, String> {
+    if !(0.0..=MAX_VOLUME).contains(&settings.volume) {
+        return Err(format!("volume must be between 0 and {}", MAX_VOLUME));
+    }
+    if settings.enabled {
+        let host = cpal::default_host();
+        check_device(settings.output_device.as_deref(), &output_names(&host), &call_devices(&host))?;
+    }
+    crate::preferences::update(|p| p.whisper_channel = settings.clone())
+        .map_err(|e| e.to_string())?;
+    match (settings.enabled, settings.output_device.as_deref()) {
+        (true, Some(device)) => info!("🎧 Whisper channel on {}", device),
+        _ => info!("🎧 Whisper channel off"),
+    }
+    Ok(settings)
+}
+
+// Play a cue on the whisper channel so the rep can check which ear it reaches
+#[tauri::command]
+pub fn test_whisper_channel() -> Result<(), String> {
+    let settings = crate::preferences::load().whisper_channel;
+    if !settings.enabled {
+        return Err("The whisper channel is off".to_string());
+    }
+    play(cue_samples(CUE_RATE, 2), CUE_RATE);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whisper_device_must_differ_from_the_call_device() {
+        let outputs = vec!["Speakers".to_string(), "Headset".to_string(), "Earbud".to_string()];
+        let call = vec!["Speakers".to_string(), "Headset".to_string()];
+        assert!(check_device(Some("Earbud"), &outputs, &call).is_ok());
+        assert!(check_device(Some("Headset"), &outputs, &call).unwrap_err().contains("carries the call"));
+        assert!(check_device(Some("Bluetooth"), &outputs, &call).unwrap_err().starts_with("Output device not found"));
+        assert!(check_device(None, &outputs, &call).is_err());
+
+        // Two 90 ms beeps with a 70 ms gap, silent at the edges
+        let cue = cue_samples(1_000, 2);
+        assert_eq!(cue.len(), 90 + 70 + 90);
+        assert!(cue[0].abs() < 1e-6 && cue[120].abs() < 1e-6);
+    }
+}
@@ -0,0 +1,333 @@
+// Window Layout - window positions remembered per monitor arrangement
+// A rep docks the laptop in the morning (two external screens) and undocks for a call
+// in the meeting room: the OS drops windows wherever it likes, and a HUD left at a
+// coordinate of the external screen can end up off-screen. Each window's position is
+// stored for the arrangement of monitors it was placed in (names, positions and sizes),
+// relative to the monitor it is on. When the arrangement changes the window goes back
+// where it was last time this arrangement was connected; on an arrangement it has never
+// been placed in, it goes to the same relative spot on the same monitor if that monitor
+// is still there, else to its default place (the overlay HUD in the top-right corner of
+// the primary monitor, other windows centred). Every window of the app is tracked,
+// including the "overlay" HUD once it is created (track). snap_window_to_corner moves
+// a window (the HUD when there is one) to a corner of its monitor; reset_overlay_layout
+// forgets every stored position and puts the windows back in their default places.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager, PhysicalPosition, Window, WindowEvent};
+use log::{info, warn};
+
+pub const OVERLAY_WINDOW: &str = "overlay";
+const MAIN_WINDOW: &str = "main";
+// Gap kept between a snapped window and the monitor edges (physical pixels)
+const SNAP_MARGIN: i32 = 24;
+// A drag ends this long after the last move; only then is the position stored
+const SETTLE_MS: u64 = 1_000;
+// How often the monitor arrangement is checked (undocking doesn't always move windows)
+const POLL_MS: u64 = 2_000;
+// Placements kept over all windows and arrangements; the oldest are dropped
+const MAX_PLACEMENTS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Where a window was in one monitor arrangement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct WindowPlacement {
+    pub window: String,
+    pub arrangement: String,         // Every connected monitor, see arrangement_key
+    pub monitor: String,             // The monitor the window was on
+    pub x: i32,                      // Offset from the monitor's top-left corner
+    pub y: i32,
+    pub rel_x: f32,                  // Share of the monitor's free width left of the window
+    pub rel_y: f32,
+    pub stored_at: u64,
+}
+
+/// A connected monitor (physical pixels)
+#[derive(Debug, Clone, PartialEq)]
+struct Screen {
+    name: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Screen {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width as i32 && y < self.y + self.height as i32
+    }
+
+    /// Top-left position keeping a window of this size on the screen
+    fn clamp(&self, x: i32, y: i32, width: u32, height: u32) -> (i32, i32) {
+        let max_x = self.x + (self.width as i32 - width as i32).max(0);
+        let max_y = self.y + (self.height as i32 - height as i32).max(0);
+        (x.clamp(self.x, max_x), y.clamp(self.y, max_y))
+    }
+
+    fn corner(&self, corner: ScreenCorner, width: u32, height: u32) -> (i32, i32) {
+        let left = self.x + SNAP_MARGIN;
+        let right = self.x + self.width as i32 - width as i32 - SNAP_MARGIN;
+        let top = self.y + SNAP_MARGIN;
+        let bottom = self.y + self.height as i32 - height as i32 - SNAP_MARGIN;
+        let (x, y) = match corner {
+            ScreenCorner::TopLeft => (left, top),
+            ScreenCorner::TopRight => (right, top),
+            ScreenCorner::BottomLeft => (left, bottom),
+            ScreenCorner::BottomRight => (right, bottom),
+        };
+        self.clamp(x, y, width, height)
+    }
+}
+
+/// Identifies a set of connected monitors, whatever order the OS lists them in
+fn arrangement_key(screens: &[Screen]) -> String {
+    let mut parts: Vec<String> = screens.iter()
+        .map(|s| format!("{}@{},{}:{}x{}", s.name, s.x, s.y, s.width, s.height))
+        .collect();
+    parts.sort();
+    parts.join(";")
+}
+
+/// The placement of a window at `(x, y)` in this arrangement
+fn placement(window: &str, screens: &[Screen], x: i32, y: i32, width: u32, height: u32, now: u64) -> Option<WindowPlacement> {
+    // The monitor holding the window's centre (or its top-left corner)
+    let (cx, cy) = (x + width as i32 / 2, y + height as i32 / 2);
+    let screen = screens.iter().find(|s| s.contains(cx, cy)).or_else(|| screens.iter().find(|s| s.contains(x, y)))?;
+    let free = |total: u32, size: u32| (total as i32 - size as i32).max(1) as f32;
+    Some(WindowPlacement {
+        window: window.to_string(),
+        arrangement: arrangement_key(screens),
+        monitor: screen.name.clone(),
+        x: x - screen.x,
+        y: y - screen.y,
+        rel_x: ((x - screen.x) as f32 / free(screen.width, width)).clamp(0.0, 1.0),
+        rel_y: ((y - screen.y) as f32 / free(screen.height, height)).clamp(0.0, 1.0),
+        stored_at: now,
+    })
+}
+
+/// Where to put a window in this arrangement from what was stored, if anywhere
+fn restore_position(stored: &[WindowPlacement], window: &str, screens: &[Screen], width: u32, height: u32) -> Option<(i32, i32)> {
+    let key = arrangement_key(screens);
+    let screen_named = |name: &str| screens.iter().find(|s| s.name == name);
+    let mut mine: Vec<&WindowPlacement> = stored.iter().filter(|p| p.window == window).collect();
+    mine.sort_by_key(|p| std::cmp::Reverse(p.stored_at));
+    // Placed in this very arrangement before
+    if let Some((p, screen)) = mine.iter().filter(|p| p.arrangement == key).find_map(|p| screen_named(&p.monitor).map(|s| (p, s))) {
+        return Some(screen.clamp(screen.x + p.x, screen.y + p.y, width, height));
+    }
+    // Its monitor is still there, in another arrangement: same relative spot
+    mine.iter().find_map(|p| screen_named(&p.monitor).map(|s| (p, s))).map(|(p, screen)| {
+        let x = screen.x + (p.rel_x * (screen.width as i32 - width as i32).max(0) as f32).round() as i32;
+        let y = screen.y + (p.rel_y * (screen.height as i32 - height as i32).max(0) as f32).round() as i32;
+        screen.clamp(x, y, width, height)
+    })
+}
+
+/// Store a placement, replacing the window's previous one for that arrangement
+fn remember(stored: &mut Vec<WindowPlacement>, placement: WindowPlacement) {
+    stored.retain(|p| !(p.window == placement.window && p.arrangement == placement.arrangement));
+    stored.push(placement);
+    if stored.len() > MAX_PLACEMENTS {
+        stored.sort_by_key(|p| p.stored_at);
+        let excess = stored.len() - MAX_PLACEMENTS;
+        stored.drain(..excess);
+    }
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+fn screens(window: &Window) -> Vec<Screen> {
+    window.available_monitors().unwrap_or_default().iter().enumerate().map(|(index, monitor)| Screen {
+        name: monitor.name().cloned().unwrap_or_else(|| format!("monitor-{}", index)),
+        x: monitor.position().x,
+        y: monitor.position().y,
+        width: monitor.size().width,
+        height: monitor.size().height,
+    }).collect()
+}
+
+// Arrangement each tracked window was last placed in, by label
+static ARRANGEMENTS: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// Moves not yet stored: label -> last move
+static MOVED: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Put a window where it belongs in the current arrangement
+fn place(window: &Window) {
+    let screens = screens(window);
+    if screens.is_empty() {
+        return;
+    }
+    let size = match window.outer_size() {
+        Ok(size) => size,
+        Err(_) => return,
+    };
+    let label = window.label().to_string();
+    let stored = crate::preferences::load().window_layouts;
+    let position = restore_position(&stored, &label, &screens, size.width, size.height);
+    let placed = match position {
+        Some((x, y)) => window.set_position(PhysicalPosition { x, y }),
+        None if label == OVERLAY_WINDOW => {
+            let primary = window.primary_monitor().ok().flatten()
+                .and_then(|m| screens.iter().find(|s| Some(&s.name) == m.name()).cloned())
+                .unwrap_or_else(|| screens[0].clone());
+            let (x, y) = primary.corner(ScreenCorner::TopRight, size.width, size.height);
+            window.set_position(PhysicalPosition { x, y })
+        }
+        None => window.center(),
+    };
+    if let Err(e) = placed {
+        warn!("⚠️ Failed to place the {} window: {}", label, e);
+    }
+    ARRANGEMENTS.lock().unwrap().insert(label, arrangement_key(&screens));
+}
+
+/// Store where a window is now
+fn store(window: &Window) {
+    let (position, size) = match (window.outer_position(), window.outer_size()) {
+        (Ok(position), Ok(size)) => (position, size),
+        _ => return,
+    };
+    let screens = screens(window);
+    if let Some(placement) = placement(window.label(), &screens, position.x, position.y, size.width, size.height, now_ms()) {
+        if let Err(e) = crate::preferences::update(|p| remember(&mut p.window_layouts, placement.clone())) {
+            warn!("⚠️ Failed to store the {} window position: {}", window.label(), e);
+        }
+    }
+}
+
+/// Follow a window: placed now, stored when the rep moves it, put back when monitors change
+pub fn track(window: Window) {
+    place(&window);
+    let label = window.label().to_string();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Moved(_) = event {
+            MOVED.lock().unwrap().insert(label.clone(), Instant::now());
+        }
+    });
+}
+
+/// Track every window and watch the monitor arrangement (setup hook)
+pub fn start(app: AppHandle) {
+    for window in app.windows().into_values() {
+        track(window);
+    }
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_millis(POLL_MS));
+        for (label, window) in app.windows() {
+            let key = arrangement_key(&screens(&window));
+            let changed = ARRANGEMENTS.lock().unwrap().get(&label).map_or(false, |last| *last != key);
+            if changed {
+                // Whatever the OS did with the window on the way is not the rep's choice
+                MOVED.lock().unwrap().remove(&label);
+                info!("🖥️ Monitors changed: placing the {} window for this arrangement", label);
+                place(&window);
+                continue;
+            }
+            let settled = MOVED.lock().unwrap().get(&label)
+                .map_or(false, |moved| moved.elapsed() >= Duration::from_millis(SETTLE_MS));
+            if settled {
+                MOVED.lock().unwrap().remove(&label);
+                store(&window);
+            }
+        }
+    });
+}
+
+// ========== Tauri Commands ==========
+
+// Move a window (the overlay HUD when it exists, else the main window) to a corner of
+// the monitor it is on; the position is kept for this arrangement
+#[tauri::command]
+pub fn snap_window_to_corner(app: AppHandle, corner: ScreenCorner, window: Option<String>) -> Result<WindowPlacement, String> {
+    let target = match window {
+        Some(label) => app.get_window(&label).ok_or_else(|| format!("No window {}", label))?,
+        None => app.get_window(OVERLAY_WINDOW).or_else(|| app.get_window(MAIN_WINDOW)).ok_or("No window to snap")?,
+    };
+    let screens = screens(&target);
+    let (position, size) = match (target.outer_position(), target.outer_size()) {
+        (Ok(position), Ok(size)) => (position, size),
+        _ => return Err("Cannot read the window position".to_string()),
+    };
+    let (cx, cy) = (position.x + size.width as i32 / 2, position.y + size.height as i32 / 2);
+    let screen = screens.iter().find(|s| s.contains(cx, cy)).or_else(|| screens.first())
+        .ok_or("No monitor found")?;
+    let (x, y) = screen.corner(corner, size.width, size.height);
+    target.set_position(PhysicalPosition { x, y }).map_err(|e| e.to_string())?;
+    let placement = placement(target.label(), &screens, x, y, size.width, size.height, now_ms())
+        .ok_or("No monitor found")?;
+    MOVED.lock().unwrap().remove(target.label());
+    crate::preferences::update(|p| remember(&mut p.window_layouts, placement.clone()))
+        .map_err(|e| e.to_string())?;
+    Ok(placement)
+}
+
+// Forget every stored position and put the windows back in their default places
+#[tauri::command]
+pub fn reset_overlay_layout(app: AppHandle) -> Result<(), String> {
+    crate::preferences::update(|p| p.window_layouts.clear())
+        .map_err(|e| e.to_string())?;
+    MOVED.lock().unwrap().clear();
+    for window in app.windows().into_values() {
+        place(&window);
+    }
+    info!("🖥️ Window layout reset");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(name: &str, x: i32, y: i32, width: u32, height: u32) -> Screen {
+        Screen { name: name.to_string(), x, y, width, height }
+    }
+
+    #[test]
+    fn test_positions_follow_the_monitor_arrangement() {
+        let laptop = screen("Built-in", 0, 0, 1920, 1080);
+        let docked = vec![laptop.clone(), screen("DELL U2720Q", 1920, 0, 3840, 2160)];
+        let undocked = vec![laptop.clone()];
+        assert_eq!(arrangement_key(&docked), arrangement_key(&[docked[1].clone(), docked[0].clone()]));
+
+        // The HUD at the top right of the external screen while docked, bottom left when undocked
+        let mut stored = Vec::new();
+        remember(&mut stored, placement("overlay", &docked, 5_300, 40, 360, 200, 1).unwrap());
+        assert_eq!(stored[0].monitor, "DELL U2720Q");
+        assert_eq!((stored[0].x, stored[0].y), (3_380, 40));
+
+        // The external screen moved to the left: same monitor, same relative spot
+        let moved = vec![laptop.clone(), screen("DELL U2720Q", -3840, 0, 3840, 2160)];
+        let (x, y) = restore_position(&stored, "overlay", &moved, 360, 200).unwrap();
+        assert!((-600..-300).contains(&x) && (0..60).contains(&y), "{} {}", x, y);
+
+        remember(&mut stored, placement("overlay", &undocked, 24, 856, 360, 200, 2).unwrap());
+        assert_eq!(restore_position(&stored, "overlay", &docked, 360, 200), Some((5_300, 40)));
+        assert_eq!(restore_position(&stored, "overlay", &undocked, 360, 200), Some((24, 856)));
+
+        // A projector never seen before and no known monitor: default place
+        assert_eq!(restore_position(&stored, "overlay", &[screen("Projector", 0, 0, 1280, 720)], 360, 200), None);
+        assert_eq!(restore_position(&stored, "main", &docked, 1400, 900), None);
+
+        // Storing again for an arrangement replaces the old position
+        remember(&mut stored, placement("overlay", &docked, 2_000, 1_900, 360, 200, 3).unwrap());
+        assert_eq!(stored.len(), 2);
+        assert_eq!(restore_position(&stored, "overlay", &docked, 360, 200), Some((2_000, 1_900)));
+
+        assert_eq!(laptop.corner(ScreenCorner::BottomRight, 360, 200), (1_536, 856));
+        assert_eq!(laptop.clamp(1_900, -50, 360, 200), (1_560, 0));
+    }
+}
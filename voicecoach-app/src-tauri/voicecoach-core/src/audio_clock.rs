@@ -0,0 +1,85 @@
+// Audio Clock - session time counted in captured samples
+// Capture callbacks used to stamp a buffer with the moment the callback ran, and
+// partials and coaching prompts took the wall clock instead, so a transcript time could
+// not be laid on the recording reliably. Each capture stream now keeps a SampleClock:
+// a buffer starts where the stream's previous buffers end - the stream's anchor on the
+// capture clock (transcript_sequencer) plus the samples it has delivered - so stamps
+// follow the audio rather than callback scheduling. The anchor only moves earlier when
+// a callback shows it was taken late, and is re-taken when the count and the capture
+// clock part by more than RESYNC_MS (a device stall lost audio, or the stream sat in
+// standby). Stored records and events carry offsets from the session's start on this
+// clock (session_store::offset_of); the wall clock is anchored once, when the session
+// starts (Session.clock), and wall times are derived from that anchor.
+
+use serde::{Deserialize, Serialize};
+
+// Drift between the sample count and the capture clock that re-anchors a stream
+const RESYNC_MS: u64 = 250;
+
+/// Where a session starts on the capture clock and on the wall clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct ClockAnchor {
+    pub capture_ms: u64,
+    pub wall_ms: u64,
+}
+
+/// Sample-counting clock of one capture stream (owned by its audio callback)
+pub struct SampleClock {
+    rate: u64,
+    anchor_ms: Option<u64>,
+    frames: u64,
+}
+
+impl SampleClock {
+    pub fn new(sample_rate: u32) -> Self {
+        Self { rate: sample_rate.max(1) as u64, anchor_ms: None, frames: 0 }
+    }
+
+    fn frames_ms(&self, frames: u64) -> u64 {
+        frames * 1000 / self.rate
+    }
+
+    /// Capture time of the first frame of a buffer of `frames` that was complete at
+    /// `now_ms` (capture clock); buffers dropped later in the pipeline still count
+    pub fn stamp(&mut self, now_ms: u64, frames: usize) -> u64 {
+        let by_callback = now_ms.saturating_sub(self.frames_ms(frames as u64));
+        let counted = self.anchor_ms.map(|anchor| anchor + self.frames_ms(self.frames));
+        match counted {
+            Some(counted) if counted > by_callback + RESYNC_MS || by_callback > counted + RESYNC_MS => {
+                self.anchor_ms = Some(by_callback);
+                self.frames = 0;
+            }
+            // The anchor came from a late callback: this buffer shows where the audio is
+            Some(counted) if by_callback < counted => {
+                self.anchor_ms = self.anchor_ms.map(|anchor| anchor.saturating_sub(counted - by_callback));
+            }
+            Some(_) => {}
+            None => self.anchor_ms = Some(by_callback),
+        }
+        let start = self.anchor_ms.unwrap_or(by_callback) + self.frames_ms(self.frames);
+        self.frames += frames as u64;
+        start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffers_are_stamped_by_sample_count() {
+        // 100 ms buffers at 16 kHz; the first callback ran 30 ms late
+        let mut clock = SampleClock::new(16_000);
+        assert_eq!(clock.stamp(1_130, 1_600), 1_030);
+        // Jittery callbacks: the stamps follow the samples, not the callbacks
+        assert_eq!(clock.stamp(1_245, 1_600), 1_130);
+        assert_eq!(clock.stamp(1_330, 1_600), 1_230);
+        // A punctual callback moves the late anchor back
+        assert_eq!(clock.stamp(1_400, 1_600), 1_300);
+        assert_eq!(clock.stamp(1_520, 1_600), 1_400);
+
+        // The device stalled for a second: the lost audio is not squeezed out of the timeline
+        assert_eq!(clock.stamp(2_600, 1_600), 2_500);
+        assert_eq!(clock.stamp(2_700, 1_600), 2_600);
+    }
+}
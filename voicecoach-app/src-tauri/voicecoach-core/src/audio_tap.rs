@@ -0,0 +1,196 @@
+// Audio Tap - debug tee of the exact audio fed to the transcription engine
+// Writes post-resample 16kHz mono i16 audio into a rotating set of WAV files
+// so "transcription hears nothing" reports can include what the engine received
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, warn, error};
+use anyhow::{Result, Context};
+
+const TAP_SAMPLE_RATE: u32 = 16000;
+const DEFAULT_FILE_SECONDS: u32 = 60;
+const DEFAULT_MAX_FILES: u32 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct AudioTapStatus {
+    pub enabled: bool,
+    pub directory: Option<String>,
+    pub current_file: Option<String>,
+    pub file_seconds: u32,
+    pub max_files: u32,
+    pub samples_written: u64,
+}
+
+struct AudioTap {
+    directory: PathBuf,
+    writer: Option<hound::WavWriter<BufWriter<fs::File>>>,
+    current_file: PathBuf,
+    file_index: u32,
+    samples_in_file: u64,
+    samples_written: u64,
+    samples_per_file: u64,
+    file_seconds: u32,
+    max_files: u32,
+}
+
+impl AudioTap {
+    fn new(directory: PathBuf, file_seconds: u32, max_files: u32) -> Result<Self> {
+        fs::create_dir_all(&directory)
+            .context(format!("Failed to create tap directory: {:?}", directory))?;
+
+        let mut tap = Self {
+            directory,
+            writer: None,
+            current_file: PathBuf::new(),
+            file_index: 0,
+            samples_in_file: 0,
+            samples_written: 0,
+            samples_per_file: file_seconds as u64 * TAP_SAMPLE_RATE as u64,
+            file_seconds,
+            max_files,
+        };
+        tap.open_next_file()?;
+        Ok(tap)
+    }
+
+    /// Finalize the current file and start the next one, overwriting the oldest slot
+    fn open_next_file(&mut self) -> Result<()> {
+        self.finalize_current();
+
+        let slot = self.file_index % self.max_files;
+        let path = self.directory.join(format!("voicecoach_tap_{:03}.wav", slot));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: TAP_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let writer = hound::WavWriter::create(&path, spec)
+            .context(format!("Failed to create tap file: {:?}", path))?;
+
+        info!("🎙️ LED 8202: Audio tap writing to {:?}", path);
+        self.writer = Some(writer);
+        self.current_file = path;
+        self.samples_in_file = 0;
+        self.file_index += 1;
+        Ok(())
+    }
+
+    fn write(&mut self, samples: &[i16]) -> Result<()> {
+        for &sample in samples {
+            if self.samples_in_file >= self.samples_per_file {
+                self.open_next_file()?;
+            }
+            if let Some(writer) = self.writer.as_mut() {
+                writer.write_sample(sample)?;
+            }
+            self.samples_in_file += 1;
+            self.samples_written += 1;
+        }
+        Ok(())
+    }
+
+    fn finalize_current(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            if let Err(e) = writer.finalize() {
+                warn!("⚠️ LED 8203: Failed to finalize tap file {:?}: {}", self.current_file, e);
+            }
+        }
+    }
+
+    fn status(&self) -> AudioTapStatus {
+        AudioTapStatus {
+            enabled: true,
+            directory: Some(self.directory.to_string_lossy().to_string()),
+            current_file: Some(self.current_file.to_string_lossy().to_string()),
+            file_seconds: self.file_seconds,
+            max_files: self.max_files,
+            samples_written: self.samples_written,
+        }
+    }
+}
+
+// Fast-path flag so the audio callback never touches the mutex when the tap is off
+static TAP_ENABLED: AtomicBool = AtomicBool::new(false);
+
+static AUDIO_TAP: Lazy<Mutex<Option<AudioTap>>> = Lazy::new(|| Mutex::new(None));
+
+/// Tee samples into the tap (no-op unless enabled). Called from audio callbacks.
+pub fn write_samples(samples: &[i16]) {
+    if !TAP_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let mut guard = match AUDIO_TAP.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => return, // Never block the audio thread
+    };
+
+    if let Some(tap) = guard.as_mut() {
+        if let Err(e) = tap.write(samples) {
+            error!("❌ LED 8204: Audio tap write failed, disabling tap: {}", e);
+            tap.finalize_current();
+            *guard = None;
+            TAP_ENABLED.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Start teeing into rotating WAV files under `path` (replacing a running tap)
+pub fn enable(path: String, file_seconds: Option<u32>, max_files: Option<u32>) -> Result<AudioTapStatus, String> {
+    info!("🔌 LED 8200: Enabling audio tap at {}", path);
+
+    let file_seconds = file_seconds.unwrap_or(DEFAULT_FILE_SECONDS).max(1);
+    let max_files = max_files.unwrap_or(DEFAULT_MAX_FILES).max(1);
+
+    let tap = AudioTap::new(PathBuf::from(&path), file_seconds, max_files)
+        .map_err(|e| e.to_string())?;
+    let status = tap.status();
+
+    let mut guard = AUDIO_TAP.lock().unwrap();
+    if let Some(old) = guard.as_mut() {
+        old.finalize_current();
+    }
+    *guard = Some(tap);
+    TAP_ENABLED.store(true, Ordering::Relaxed);
+
+    Ok(status)
+}
+
+pub fn disable() -> AudioTapStatus {
+    info!("🔌 LED 8201: Disabling audio tap");
+    TAP_ENABLED.store(false, Ordering::Relaxed);
+
+    let mut guard = AUDIO_TAP.lock().unwrap();
+    let status = match guard.as_mut() {
+        Some(tap) => {
+            tap.finalize_current();
+            AudioTapStatus { enabled: false, ..tap.status() }
+        }
+        None => get_disabled_status(),
+    };
+    *guard = None;
+    status
+}
+
+pub fn status() -> AudioTapStatus {
+    let guard = AUDIO_TAP.lock().unwrap();
+    guard.as_ref().map(|tap| tap.status()).unwrap_or_else(get_disabled_status)
+}
+
+fn get_disabled_status() -> AudioTapStatus {
+    AudioTapStatus {
+        enabled: false,
+        directory: None,
+        current_file: None,
+        file_seconds: DEFAULT_FILE_SECONDS,
+        max_files: DEFAULT_MAX_FILES,
+        samples_written: 0,
+    }
+}
@@ -0,0 +1,193 @@
+// VoiceCoach CLI - headless knowledge base ingestion, batch transcription and model benchmarks
+// Run with: cargo run -p voicecoach-core --bin voicecoach-cli -- <command> [args]
+//
+// Built on voicecoach-core, the same knowledge_base and file_transcription code the
+// app uses, so scripted setups and CI playbook updates produce exactly what the GUI
+// would - without building the desktop shell.
+
+use std::path::PathBuf;
+use voicecoach_core::{events, file_transcription, knowledge_base, model_benchmark, recognizer_pool, vosk_model};
+use knowledge_base::KnowledgeBaseManager;
+
+// The desktop app's bundle identifier; its data directory is named after it
+const APP_IDENTIFIER: &str = "com.voicecoach.app";
+
+const USAGE: &str = "\
+VoiceCoach CLI
+
+USAGE:
+    voicecoach-cli <COMMAND> [ARGS]
+
+COMMANDS:
+    ingest <path> [--recursive]          Add a file or directory of documents to the knowledge base
+    search <query> [--max <n>]           Search the knowledge base
+    stats                                Print knowledge base statistics
+    transcribe <file.wav>... [--model <path>] [--json]
+                                         Transcribe WAV files with the local Vosk model
+    benchmark <model-path> [--test-set <dir>] [--json]
+                                         Measure a model's real-time factor, memory and accuracy
+    help                                 Show this message
+";
+
+fn main() {
+    env_logger::init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("ingest") => run_ingest(&args[1..]),
+        Some("search") => run_search(&args[1..]),
+        Some("stats") => run_stats(),
+        Some("transcribe") => run_transcribe(&args[1..]),
+        Some("benchmark") => run_benchmark(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") | None => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(format!("Unknown command: {}\n\n{}", other, USAGE)),
+    };
+
+    if let Err(e) = result {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// The desktop app's data directory, so the CLI works on the same knowledge base
+fn app_data_dir() -> PathBuf {
+    dirs_next::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .unwrap_or_else(|| PathBuf::from("./"))
+}
+
+/// Split args into positional values and the value of an optional `--flag <value>`
+fn take_flag_value(args: &[String], flag: &str) -> (Vec<String>, Option<String>) {
+    let mut positional = Vec::new();
+    let mut value = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            value = iter.next().cloned();
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+
+    (positional, value)
+}
+
+fn run_ingest(args: &[String]) -> Result<(), String> {
+    let recursive = args.iter().any(|a| a == "--recursive" || a == "-r");
+    let path = args.iter()
+        .find(|a| !a.starts_with('-'))
+        .ok_or("ingest requires a file or directory path")?;
+
+    let mut manager = KnowledgeBaseManager::new(&app_data_dir()).map_err(|e| e.to_string())?;
+    let stats = manager.process_directory(path, recursive)
+        .map_err(|e| e.to_string())?;
+
+    println!("✅ Ingested {} documents ({} chunks) in {}ms",
+        stats.total_documents, stats.total_chunks, stats.processing_time_ms);
+    println!("   Success rate: {:.0}%", stats.success_rate * 100.0);
+    println!("   Knowledge base now holds {} documents", stats.knowledge_base_size);
+    Ok(())
+}
+
+fn run_search(args: &[String]) -> Result<(), String> {
+    let (positional, max) = take_flag_value(args, "--max");
+    let query = positional.join(" ");
+    if query.is_empty() {
+        return Err("search requires a query".into());
+    }
+    let max_results = match max {
+        Some(n) => n.parse::<usize>().map_err(|_| format!("Invalid --max value: {}", n))?,
+        None => 5,
+    };
+
+    let manager = KnowledgeBaseManager::new(&app_data_dir()).map_err(|e| e.to_string())?;
+    let results = manager.search(&query, max_results);
+
+    if results.is_empty() {
+        println!("No results for '{}'", query);
+    }
+    for (i, (chunk, score)) in results.iter().enumerate() {
+        let preview: String = chunk.chars().take(200).collect();
+        println!("[{}] score {:.2}\n    {}\n", i + 1, score, preview.replace('\n', " "));
+    }
+    Ok(())
+}
+
+fn run_stats() -> Result<(), String> {
+    let manager = KnowledgeBaseManager::new(&app_data_dir()).map_err(|e| e.to_string())?;
+    let stats = manager.get_stats();
+    println!("{}", serde_json::to_string_pretty(&stats).map_err(|e| e.to_string())?);
+    Ok(())
+}
+
+fn run_transcribe(args: &[String]) -> Result<(), String> {
+    let as_json = args.iter().any(|a| a == "--json");
+    let args: Vec<String> = args.iter().filter(|a| *a != "--json").cloned().collect();
+    let (files, model_path) = take_flag_value(&args, "--model");
+    if files.is_empty() {
+        return Err("transcribe requires at least one WAV file".into());
+    }
+
+    let model_path = model_path.unwrap_or_else(file_transcription::resolve_model_path);
+    eprintln!("⏳ Loading Vosk model from {}", model_path);
+    let (model, _) = vosk_model::load(&model_path).map_err(|e| e.to_string())?;
+    let pool = recognizer_pool::RecognizerPool::new(
+        std::sync::Arc::new(model), file_transcription::resolve_pool_size(), file_transcription::VOSK_SAMPLE_RATE as f32);
+
+    let mut failures = 0;
+    for file in &files {
+        match file_transcription::transcribe_wav_file(&pool, file) {
+            Ok(transcript) => {
+                if as_json {
+                    println!("{}", serde_json::to_string(&transcript).map_err(|e| e.to_string())?);
+                } else {
+                    println!("=== {} ({:.1}s audio, {}ms) ===",
+                        file, transcript.duration_ms as f32 / 1000.0, transcript.processing_time_ms);
+                    println!("{}\n", transcript.text());
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ {}: {}", file, e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{} of {} files failed to transcribe", failures, files.len()));
+    }
+    Ok(())
+}
+
+fn run_benchmark(args: &[String]) -> Result<(), String> {
+    let as_json = args.iter().any(|a| a == "--json");
+    let args: Vec<String> = args.iter().filter(|a| *a != "--json").cloned().collect();
+    let (positional, test_set) = take_flag_value(&args, "--test-set");
+    let model_path = positional.first().ok_or("benchmark requires a model path")?;
+
+    eprintln!("⏳ Benchmarking Vosk model at {}", model_path);
+    let result = model_benchmark::benchmark(&events::NullSink, model_path, test_set.as_deref())
+        .map_err(|e| e.to_string())?;
+    if as_json {
+        println!("{}", serde_json::to_string(&result).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+
+    let layout = &result.layout;
+    println!("=== {} ===", layout.path);
+    println!("   Layout: {:?} graph{}{}, {} MB on disk", layout.graph,
+        if layout.rescoring { ", rescoring" } else { "" }, if layout.quantized { ", quantized" } else { "" }, layout.size_mb);
+    println!("   Load time: {}ms, memory: {}", result.load_ms,
+        result.memory_mb.map_or_else(|| "unavailable".to_string(), |mb| format!("{} MB", mb)));
+    println!("   Real-time factor: {:.2} ({:.1}s audio in {:.1}s)", result.real_time_factor,
+        result.audio_ms as f32 / 1000.0, result.decode_ms as f32 / 1000.0);
+    match (result.accuracy, &result.test_set) {
+        (Some(accuracy), Some(test_set)) => println!("   Accuracy: {:.1}% on {} ({} clips)", accuracy * 100.0, test_set, result.clips.len()),
+        _ => println!("   Accuracy: n/a (synthetic speech, no test set)"),
+    }
+    Ok(())
+}
@@ -0,0 +1,395 @@
+// Call Analytics - transcript-driven tracking for the live call
+// Every final transcript from Vosk/Deepgram is fed through here. Drives the in-call
+// checklist (items are ticked off when one of their intent phrases shows up in the
+// transcript, and the UI is notified for live ticks), sales stage detection, the
+// rep/prospect talk ratio (scripted read-aloud sections and words transcribed during
+// hold music are left out of the ratio) and the prospect question log. The rest of
+// what happens to a final line - storing it in the session, the coaching modules that
+// follow the call, hold and script detection - is the app's, reached through
+// CallObservers; the checklist and stage settings come from it too.
+// A metrics snapshot (talk ratio, speaking rates, objections, checklist score) is
+// handed to the app periodically, to keep with the call's session for cross-session
+// export.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, error};
+
+use crate::events::EventSink;
+use crate::phrases::find_phrase;
+use crate::prospect_questions::ProspectQuestion;
+use crate::sales_stage::StageBiasSettings;
+use crate::sentences::Sentence;
+use crate::transcript::{TranscriptLine, TranscriptWord};
+
+// User-defined checklist item (persisted in preferences)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ChecklistItemDef {
+    pub id: String,
+    pub label: String,
+    /// Phrases that indicate the item was covered (case-insensitive, whole words)
+    pub phrases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ChecklistItemStatus {
+    pub id: String,
+    pub label: String,
+    pub completed: bool,
+    pub completed_at: Option<u64>,
+    pub matched_phrase: Option<String>,
+    pub evidence: Option<String>,  // Transcript line that completed the item
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ChecklistStatus {
+    pub items: Vec<ChecklistItemStatus>,
+    pub completed: usize,
+    pub total: usize,
+    pub progress: f32,
+}
+
+// Payload of the "checklist_item_completed" event
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ChecklistItemCompleted {
+    pub item: ChecklistItemStatus,
+    pub completed: usize,
+    pub total: usize,
+}
+
+// Word-count talk ratio for the current call
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct TalkRatio {
+    pub rep_words: usize,
+    pub prospect_words: usize,
+    pub scripted_words: usize,  // Rep words read from scripts (excluded from the ratio)
+    pub rep_share: f32,         // rep_words / (rep_words + prospect_words)
+    #[serde(default)]
+    pub rep_speech_ms: u64,     // Speaking time behind rep_words
+    #[serde(default)]
+    pub prospect_speech_ms: u64,
+    #[serde(default)]
+    pub held_words: usize,      // Prospect words during hold music (excluded from the ratio)
+}
+
+// Snapshot of a call's metrics, stored with its session
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct CallMetrics {
+    pub talk_ratio: TalkRatio,
+    pub rep_wpm: Option<f32>,       // None without measured speaking time
+    pub prospect_wpm: Option<f32>,
+    pub objections: usize,          // Prospect lines with objection cues
+    pub prospect_questions: usize,
+    pub unanswered_questions: usize,
+    pub checklist_completed: usize,
+    pub checklist_total: usize,
+    pub updated_at: u64,
+    #[serde(default)]
+    pub interruptions: usize,       // Times the rep talked over the prospect
+}
+
+/// The app side of a call's final lines
+pub trait CallObservers: Send + Sync {
+    /// Checklist of a call started without begin_call (the app's configured one)
+    fn checklist(&self) -> Vec<ChecklistItemDef>;
+    fn stage_bias(&self) -> StageBiasSettings;
+    /// Session offset of a capture clock time
+    fn offset_of(&self, capture_ms: u64) -> u64;
+    /// Keep a final line in the call's session; its index there
+    fn record_line(&self, text: &str, is_user: bool, speech_ms: u64, capture_ms: u64, words: Vec<TranscriptWord>) -> Option<usize>;
+    /// Hand a final line to the coaching modules following the call; whether the rep
+    /// was reading it from a script
+    fn follow_line(&self, text: &str, is_user: bool) -> bool;
+    /// Whether the prospect was on hold at `capture_ms`
+    fn on_hold_at(&self, capture_ms: u64) -> bool;
+    fn bookmark_objection(&self, line: usize);
+    /// Keep the call's metrics with its session
+    fn save_metrics(&self, metrics: CallMetrics);
+}
+
+pub fn item(id: &str, label: &str, phrases: &[&str]) -> ChecklistItemDef {
+    ChecklistItemDef {
+        id: id.to_string(),
+        label: label.to_string(),
+        phrases: phrases.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+pub fn default_checklist() -> Vec<ChecklistItemDef> {
+    vec![
+        item("budget_discussed", "Budget discussed", &[
+            "budget", "price range", "how much", "spend", "allocated", "cost", "pricing", "afford",
+        ]),
+        item("decision_maker_identified", "Decision-maker identified", &[
+            "decision maker", "sign off", "final say", "who else", "approve", "approval",
+            "my boss", "stakeholders", "procurement", "signs the contract",
+        ]),
+        item("next_meeting_booked", "Next meeting booked", &[
+            "next meeting", "follow up call", "follow-up call", "calendar invite", "send an invite",
+            "book a time", "schedule a call", "schedule a demo", "same time next week", "let's meet",
+        ]),
+    ]
+}
+
+// Final lines between metrics snapshots written to the session
+const METRICS_SNAPSHOT_LINES: usize = 10;
+
+struct CallState {
+    definitions: Vec<ChecklistItemDef>,
+    items: Vec<ChecklistItemStatus>,
+    talk: TalkRatio,
+    objections: usize,
+    lines: usize,
+    prospect_talk: Vec<(u64, usize, u64)>,  // (capture_ms, words, speech_ms) of counted prospect lines
+}
+
+impl CallState {
+    fn new(definitions: Vec<ChecklistItemDef>) -> Self {
+        let items = definitions.iter().map(|def| ChecklistItemStatus {
+            id: def.id.clone(),
+            label: def.label.clone(),
+            completed: false,
+            completed_at: None,
+            matched_phrase: None,
+            evidence: None,
+        }).collect();
+        Self { definitions, items, talk: TalkRatio::default(), objections: 0, lines: 0, prospect_talk: Vec::new() }
+    }
+
+    /// Count the words and speaking time of a line's sentences (pace comes from the
+    /// sentences' own spans, so pauses between sentences aren't counted as speech)
+    fn record_talk(&mut self, sentences: &[Sentence], is_user: bool, scripted: bool, capture_ms: u64) {
+        let words = sentences.iter().map(|s| s.words).sum();
+        let speech_ms = sentences.iter().map(Sentence::duration_ms).sum();
+        match (is_user, scripted) {
+            (true, true) => self.talk.scripted_words += words,
+            (true, false) => {
+                self.talk.rep_words += words;
+                self.talk.rep_speech_ms += speech_ms;
+            }
+            (false, _) => {
+                self.talk.prospect_words += words;
+                self.talk.prospect_speech_ms += speech_ms;
+                self.prospect_talk.push((capture_ms, words, speech_ms));
+            }
+        }
+        self.update_share();
+    }
+
+    /// Move prospect talk captured from `since_ms` on out of the ratio (hold music found)
+    fn exclude_prospect_since(&mut self, since_ms: u64) {
+        let talk = &mut self.talk;
+        self.prospect_talk.retain(|&(capture_ms, words, speech_ms)| {
+            if capture_ms < since_ms {
+                return true;
+            }
+            talk.prospect_words -= words;
+            talk.prospect_speech_ms -= speech_ms;
+            talk.held_words += words;
+            false
+        });
+        self.update_share();
+    }
+
+    fn update_share(&mut self) {
+        let counted = self.talk.rep_words + self.talk.prospect_words;
+        self.talk.rep_share = if counted > 0 { self.talk.rep_words as f32 / counted as f32 } else { 0.0 };
+    }
+
+    /// Tick off items whose phrases appear in `text`, returning the newly completed ones
+    fn apply_transcript(&mut self, text: &str, now: u64) -> Vec<ChecklistItemStatus> {
+        let mut completed = Vec::new();
+        for (def, status) in self.definitions.iter().zip(self.items.iter_mut()) {
+            if status.completed {
+                continue;
+            }
+            if let Some(phrase) = find_phrase(text, &def.phrases) {
+                status.completed = true;
+                status.completed_at = Some(now);
+                status.matched_phrase = Some(phrase.to_string());
+                status.evidence = Some(text.to_string());
+                completed.push(status.clone());
+            }
+        }
+        completed
+    }
+
+    fn status(&self) -> ChecklistStatus {
+        let completed = self.items.iter().filter(|i| i.completed).count();
+        let total = self.items.len();
+        ChecklistStatus {
+            items: self.items.clone(),
+            completed,
+            total,
+            progress: if total > 0 { completed as f32 / total as f32 } else { 0.0 },
+        }
+    }
+
+    fn metrics(&self, now: u64, questions: &[ProspectQuestion]) -> CallMetrics {
+        let wpm = |words: usize, ms: u64| (ms > 0).then(|| words as f32 * 60_000.0 / ms as f32);
+        let checklist = self.status();
+        CallMetrics {
+            talk_ratio: self.talk.clone(),
+            rep_wpm: wpm(self.talk.rep_words, self.talk.rep_speech_ms),
+            prospect_wpm: wpm(self.talk.prospect_words, self.talk.prospect_speech_ms),
+            objections: self.objections,
+            prospect_questions: questions.len(),
+            unanswered_questions: questions.iter().filter(|q| !q.answered).count(),
+            checklist_completed: checklist.completed,
+            checklist_total: checklist.total,
+            updated_at: now,
+            interruptions: crate::talk_over::interruptions(),
+        }
+    }
+}
+
+static CALL_STATE: Lazy<Mutex<Option<CallState>>> = Lazy::new(|| Mutex::new(None));
+
+/// Run `f` on the call's state, starting it with `checklist` if no call has begun
+fn with_state<T>(checklist: impl FnOnce() -> Vec<ChecklistItemDef>, f: impl FnOnce(&mut CallState) -> T) -> T {
+    let mut guard = CALL_STATE.lock().unwrap();
+    let state = guard.get_or_insert_with(|| CallState::new(checklist()));
+    f(state)
+}
+
+/// Start of a new call with `checklist`: clear per-call progress. Returns the finished
+/// call's final numbers, for its session (still the current one)
+pub fn begin_call(checklist: Vec<ChecklistItemDef>) -> Option<CallMetrics> {
+    let finished = CALL_STATE.lock().unwrap()
+        .replace(CallState::new(checklist))
+        .filter(|state| state.lines > 0)
+        .map(|state| state.metrics(chrono::Utc::now().timestamp_millis() as u64, &crate::prospect_questions::questions()));
+    crate::sales_stage::begin_call();
+    crate::prospect_questions::begin_call();
+    crate::sentences::begin_call();
+    crate::talk_over::begin_call();
+    finished
+}
+
+/// Feed a final transcript line (`speech_ms` of audio first captured at `capture_ms`,
+/// with the engine's word timings when it gave them) through the analytics engine
+pub fn process_final_transcript<O: CallObservers + ?Sized>(sink: &dyn EventSink, observers: &O, text: &str, is_user: bool,
+    speech_ms: u64, capture_ms: u64, words: Vec<TranscriptWord>)
+{
+    let sentences = crate::sentences::observe(text, is_user, speech_ms, capture_ms, &words, |ms| observers.offset_of(ms));
+    let line = observers.record_line(text, is_user, speech_ms, capture_ms, words);
+    let stage_bias = observers.stage_bias();
+    crate::sales_stage::process_final_transcript(sink, text, &stage_bias);
+    let scripted = observers.follow_line(text, is_user);
+    crate::prospect_questions::observe(&sentences);
+    let objection = !is_user && crate::sales_stage::is_objection(text, &stage_bias);
+    // Announcements and lyrics transcribed during hold music aren't the prospect talking
+    let held = !is_user && observers.on_hold_at(capture_ms);
+    if objection && !held {
+        if let Some(line) = line {
+            observers.bookmark_objection(line);
+        }
+    }
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let (newly_completed, snapshot) = with_state(|| observers.checklist(), |state| {
+        if held {
+            state.talk.held_words += text.split_whitespace().count();
+        } else {
+            state.record_talk(&sentences, is_user, scripted, capture_ms);
+        }
+        state.objections += objection as usize;
+        state.lines += 1;
+        let completed = state.apply_transcript(text, now);
+        let summary = state.status();
+        let completed = completed.into_iter()
+            .map(|item| ChecklistItemCompleted { item, completed: summary.completed, total: summary.total })
+            .collect::<Vec<_>>();
+        let snapshot = (state.lines % METRICS_SNAPSHOT_LINES == 0).then(|| state.metrics(now, &crate::prospect_questions::questions()));
+        (completed, snapshot)
+    });
+    // Periodic snapshots keep the session's metrics close if the app exits mid-call
+    if let Some(metrics) = snapshot {
+        observers.save_metrics(metrics);
+    }
+
+    for event in newly_completed {
+        info!("☑️ Checklist item completed: {} ('{}')", event.item.label, event.item.matched_phrase.as_deref().unwrap_or(""));
+        if let Err(e) = sink.emit("checklist_item_completed", event) {
+            error!("Failed to emit checklist_item_completed: {:?}", e);
+        }
+    }
+}
+
+/// Metrics of a finished transcript, as the live call would have produced them
+/// (`speech_ms` of each line where known; sessions imported from other tools)
+pub fn transcript_metrics(lines: &[(TranscriptLine, u64)], checklist: Vec<ChecklistItemDef>, stage_bias: &StageBiasSettings) -> CallMetrics {
+    let mut state = CallState::new(checklist);
+    for (index, (line, speech_ms)) in lines.iter().enumerate() {
+        // Imported transcripts carry no read-aloud detection
+        state.record_talk(&crate::sentences::of_line(index, line, *speech_ms), line.is_user, false, line.offset_ms);
+        state.objections += (!line.is_user && crate::sales_stage::is_objection(&line.text, stage_bias)) as usize;
+        state.lines += 1;
+        state.apply_transcript(&line.text, line.offset_ms);
+    }
+    let transcript: Vec<TranscriptLine> = lines.iter().map(|(line, _)| line.clone()).collect();
+    state.metrics(chrono::Utc::now().timestamp_millis() as u64, &crate::prospect_questions::questions_in(&transcript))
+}
+
+/// Take prospect talk captured since `since_capture_ms` out of the talk ratio (the
+/// start of hold music, confirmed only after it has played a while)
+pub fn exclude_prospect_talk(since_capture_ms: u64) {
+    if let Some(state) = CALL_STATE.lock().unwrap().as_mut() {
+        state.exclude_prospect_since(since_capture_ms);
+    }
+}
+
+/// Talk ratio of the call so far (`checklist` starts the call if none has begun)
+pub fn talk_ratio(checklist: impl FnOnce() -> Vec<ChecklistItemDef>) -> TalkRatio {
+    with_state(checklist, |state| state.talk.clone())
+}
+
+/// Checklist progress of the call so far (`checklist` starts the call if none has begun)
+pub fn checklist_status(checklist: impl FnOnce() -> Vec<ChecklistItemDef>) -> ChecklistStatus {
+    with_state(checklist, |state| state.status())
+}
+
+/// Manual tick/untick of item `id`
+pub fn mark_item(checklist: impl FnOnce() -> Vec<ChecklistItemDef>, id: &str, completed: bool) -> Result<ChecklistStatus, String> {
+    with_state(checklist, |state| {
+        let item = state.items.iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("Unknown checklist item: {}", id))?;
+        item.completed = completed;
+        item.completed_at = completed.then(|| chrono::Utc::now().timestamp_millis() as u64);
+        item.matched_phrase = None;
+        item.evidence = None;
+        Ok(state.status())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phrase_matching_is_whole_word_and_case_insensitive() {
+        let phrases = vec!["budget".to_string(), "next meeting".to_string()];
+
+        assert_eq!(find_phrase("What's your Budget for this?", &phrases), Some("budget"));
+        assert_eq!(find_phrase("Let's set up the next   meeting.", &phrases), Some("next meeting"));
+        assert_eq!(find_phrase("We budgeted nothing", &phrases), None);
+    }
+
+    #[test]
+    fn test_default_checklist_matches_sample_call() {
+        let mut state = CallState::new(default_checklist());
+
+        let first = state.apply_transcript("Who else needs to sign off on this?", 1);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].id, "decision_maker_identified");
+
+        state.apply_transcript("Can I send an invite for Tuesday?", 2);
+        // Already-completed items don't fire again
+        assert!(state.apply_transcript("and who else should approve it", 3).is_empty());
+
+        let status = state.status();
+        assert_eq!(status.completed, 2);
+        assert!(!status.items.iter().find(|i| i.id == "budget_discussed").unwrap().completed);
+    }
+}
@@ -0,0 +1,101 @@
+// Capture Host - what the live capture engines need from the app around them
+// The engines (vosk_transcription, deepgram_transcription) own the audio callbacks,
+// recognizers and connections; everything around a stream belongs to the app: which
+// device to open, the capture registry a privacy mute closes, the detectors and
+// recorders fed from the audio, transcript clean-up, two-pass routing and where a
+// final line is published. The desktop app implements CaptureHost for its event
+// adapter; per-stream processors come from `stream_audio`, built once per stream.
+
+use serde::{Deserialize, Serialize};
+
+use crate::call_analytics::CallObservers;
+use crate::transcript_sequencer::Release;
+
+/// The live transcription engines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Engine {
+    Vosk,
+    Deepgram,
+}
+
+/// Builds a capture stream on the registry's keeper thread
+pub type BuildStream = Box<dyn FnOnce() -> Result<cpal::Stream, String> + Send>;
+
+/// Per-application capture callback: mono i16 buffers; false stops the capture
+pub type AppSamples = Box<dyn FnMut(&[i16]) -> bool + Send>;
+
+pub trait CaptureHost: CallObservers {
+    /// The rep's microphone as the device rules rank it
+    fn input_device(&self) -> Option<cpal::Device>;
+    /// Loopback (monitor) device carrying the other side of the call
+    fn system_audio_device(&self) -> Option<cpal::Device>;
+    fn system_audio_config(&self, device: &cpal::Device) -> Result<cpal::SupportedStreamConfig, String>;
+    /// The requested config, or the device's shared-mode format when another app holds
+    /// it exclusively
+    fn negotiate_input_config(&self, owner: &'static str, device: &cpal::Device, config: cpal::StreamConfig) -> Result<cpal::StreamConfig, String>;
+
+    /// Open a stream in the capture registry (closed by a privacy mute); `mic` streams
+    /// can be monitored (sidetone). Returns the stream's registry id.
+    fn open_stream(&self, owner: &'static str, sample_rate: u32, mic: bool, build: BuildStream) -> Result<u64, String>;
+    fn close_streams(&self, owner: &'static str);
+    /// Whether the stream was still open
+    fn close_stream(&self, id: u64) -> bool;
+    /// Interleaved mic input for the sidetone monitor
+    fn sidetone(&self, owner: &'static str, data: &[f32], channels: usize);
+    /// Capture one application's audio (16 kHz mono)
+    fn start_app_capture(&self, pid: u32, on_samples: AppSamples) -> Result<(), String>;
+    /// Processors fed from one stream's audio; `commands` is the model for wake-word
+    /// commands (None when the engine has no local model)
+    fn stream_audio(&self, is_user: bool, sample_rate: u32, commands: Option<&vosk::Model>) -> Box<dyn StreamAudio>;
+
+    /// Session wall clock of a capture clock time
+    fn wall_ms(&self, capture_ms: u64) -> u64;
+    /// Profanity filter (partials and words as well as finals)
+    fn clean_text(&self, text: &str) -> String;
+    /// Restore punctuation in raw Vosk output
+    fn punctuate(&self, text: &str) -> String;
+    /// Run a final through the transcript plugins
+    fn apply_plugins(&self, text: &str) -> String;
+    /// A final from `engine`, `latency_ms` after its audio ended
+    fn transcribed(&self, engine: &str, latency_ms: u64);
+    fn engine_error(&self, engine: &str);
+
+    /// Active recognition grammar (Vosk phrases), if any
+    fn recognition_grammar(&self) -> Option<Vec<String>>;
+    /// Bumped when the recognition grammar is switched or expires
+    fn grammar_generation(&self) -> u64;
+    /// An utterance recognized against the grammar
+    fn grammar_finished(&self, text: &str, confidence: Option<f32>);
+
+    /// Whether an engine's partials are shown (two-pass)
+    fn emits_partials(&self, engine: Engine, is_user: bool) -> bool;
+    /// Route a final (capture span start_ms..end_ms) to the sequencer or two-pass
+    /// reconciliation; `release` emits it
+    fn submit_final(&self, engine: Engine, is_user: bool, start_ms: u64, end_ms: u64, text: &str, release: Release);
+    /// A released final, for captions, the live document and live listeners
+    fn publish_final(&self, text: &str, is_user: bool, speaker: Option<&str>);
+    /// A released final, for spoken commands
+    fn observe_commands(&self, text: &str, is_user: bool);
+    /// Label of a diarized speaker's turn of `seconds`
+    fn speaker_label(&self, speaker_id: u32, seconds: f32) -> String;
+    fn speaker_name(&self, speaker_id: u32) -> Option<String>;
+}
+
+/// The app's processors for one capture stream (mono f32 unless noted)
+pub trait StreamAudio: Send {
+    /// Live gain factor on top of the calibrated gain (mic quality auto-adjust)
+    fn gain(&self) -> f32;
+    /// Input quality watch (the rep's microphone only)
+    fn check_quality(&mut self, samples: &[f32]);
+    /// Whether to hold the audio back: on hold (prospect) or muted on the device (rep)
+    fn pausing(&mut self, samples: &[f32], capture_ms: u64) -> bool;
+    /// Noise-suppressed copy, when suppression is on for the stream
+    fn denoised(&mut self, samples: &[f32]) -> Option<Vec<f32>>;
+    /// Threshold learned from the noise floor, once there is one
+    fn learn_threshold(&mut self, samples: &[f32]) -> Option<f32>;
+    /// Speech for talk-over detection
+    fn track_talk(&mut self, samples: &[f32], capture_ms: u64, threshold: Option<f32>);
+    /// The audio as sent to the engine (i16): snippets, the call recording, commands
+    fn record(&mut self, pcm: &[i16], capture_ms: u64);
+}
@@ -0,0 +1,134 @@
+// Chaos - fault injection for exercising the error-recovery paths
+// Hooks in the audio, transcription and network code ask `inject(fault)` whether
+// to simulate a failure: the input device disappearing, a saturated audio channel
+// (buffers dropped), Vosk reporting DecodingState::Failed, or a network timeout.
+// Faults are armed with `arm` (set_fault_injection in the app). Only builds with the
+// `chaos` cargo feature can arm anything; in every other build `inject` is a constant false.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "chaos")]
+use std::collections::HashMap;
+#[cfg(feature = "chaos")]
+use std::sync::Mutex;
+#[cfg(feature = "chaos")]
+use once_cell::sync::Lazy;
+#[cfg(feature = "chaos")]
+use log::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Fault {
+    DeviceDisappearance,  // Device selection finds nothing (system audio falls back to mic-only)
+    ChannelSaturation,    // Audio buffers are dropped as if the consumer fell behind
+    VoskFailed,           // accept_waveform reports DecodingState::Failed
+    NetworkTimeout,       // Deepgram connect / Ollama requests time out (coaching falls back to rules)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct FaultInjection {
+    pub fault: Fault,
+    /// Chance that each check fails (0.0 - 1.0)
+    #[serde(default = "default_probability")]
+    pub probability: f32,
+    /// Failures left before the fault disarms itself; None = until cleared
+    #[serde(default)]
+    pub remaining: Option<u32>,
+}
+
+fn default_probability() -> f32 { 1.0 }
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct FaultStatus {
+    pub available: bool,                // Built with the `chaos` feature
+    pub armed: Vec<FaultInjection>,
+    pub triggered: Vec<(Fault, u32)>,   // Failures injected since the faults were armed
+}
+
+#[cfg(feature = "chaos")]
+#[derive(Default)]
+struct ChaosState {
+    armed: Vec<FaultInjection>,
+    triggered: HashMap<Fault, u32>,
+}
+
+#[cfg(feature = "chaos")]
+static STATE: Lazy<Mutex<ChaosState>> = Lazy::new(|| Mutex::new(ChaosState::default()));
+
+#[cfg(feature = "chaos")]
+impl ChaosState {
+    fn fire(&mut self, fault: Fault, roll: f32) -> bool {
+        let index = match self.armed.iter().position(|f| f.fault == fault) {
+            Some(index) => index,
+            None => return false,
+        };
+        if roll >= self.armed[index].probability {
+            return false;
+        }
+        if let Some(remaining) = self.armed[index].remaining.as_mut() {
+            *remaining = remaining.saturating_sub(1);
+            if *remaining == 0 {
+                self.armed.remove(index);
+            }
+        }
+        *self.triggered.entry(fault).or_insert(0) += 1;
+        true
+    }
+}
+
+/// True if an armed fault should fail this check
+#[cfg(feature = "chaos")]
+pub fn inject(fault: Fault) -> bool {
+    let fired = STATE.lock().unwrap().fire(fault, rand::random::<f32>());
+    if fired {
+        warn!("💥 Injected fault: {:?}", fault);
+    }
+    fired
+}
+
+#[cfg(not(feature = "chaos"))]
+#[inline(always)]
+pub fn inject(_fault: Fault) -> bool {
+    false
+}
+
+/// Armed faults and what they have injected so far
+#[cfg(feature = "chaos")]
+pub fn status() -> FaultStatus {
+    let state = STATE.lock().unwrap();
+    let mut triggered: Vec<(Fault, u32)> = state.triggered.iter().map(|(f, n)| (*f, *n)).collect();
+    triggered.sort_by_key(|(f, _)| *f as u8);
+    FaultStatus { available: true, armed: state.armed.clone(), triggered }
+}
+
+/// Replace the armed faults (an empty list clears them) and reset the counters
+#[cfg(feature = "chaos")]
+pub fn arm(faults: Vec<FaultInjection>) -> Result<FaultStatus, String> {
+    if faults.iter().any(|f| !(0.0..=1.0).contains(&f.probability)) {
+        return Err("probability must be between 0 and 1".to_string());
+    }
+    let mut state = STATE.lock().unwrap();
+    state.armed = faults.into_iter().filter(|f| f.remaining != Some(0)).collect();
+    state.triggered.clear();
+    warn!("💥 Fault injection armed: {:?}", state.armed.iter().map(|f| f.fault).collect::<Vec<_>>());
+    drop(state);
+    Ok(status())
+}
+
+#[cfg(all(test, feature = "chaos"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counted_faults_disarm_after_firing() {
+        let mut state = ChaosState::default();
+        state.armed.push(FaultInjection { fault: Fault::VoskFailed, probability: 0.5, remaining: Some(2) });
+
+        assert!(!state.fire(Fault::NetworkTimeout, 0.0));
+        assert!(!state.fire(Fault::VoskFailed, 0.7));  // Roll above probability
+        assert!(state.fire(Fault::VoskFailed, 0.1));
+        assert!(state.fire(Fault::VoskFailed, 0.1));
+        assert!(!state.fire(Fault::VoskFailed, 0.1));  // Exhausted
+        assert!(state.armed.is_empty());
+        assert_eq!(state.triggered.get(&Fault::VoskFailed), Some(&2));
+    }
+}
@@ -0,0 +1,308 @@
+// Cloud Usage - silence skipping for billed streaming engines, and the usage report
+// Cloud engines bill for every second of audio streamed. With silence skipping on,
+// audio below the VAD threshold is not sent: a gate opens on voiced audio (with a
+// short pad of the preceding silence so word onsets survive) and closes after a
+// hangover of silence. The engine only sees the voiced segments back to back, so a
+// timeline of stitch points maps its timestamps back to capture time. Hold music and
+// dead air on the prospect channel (hold_detection) are held back the same way, with
+// or without silence skipping. Streamed and skipped audio are counted per session for
+// the usage report.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::info;
+
+// Deepgram list price per streamed minute (estimate shown in the usage report)
+const DEEPGRAM_USD_PER_MINUTE: f64 = 0.0043;
+// Threshold when the source has no level calibration (RMS, full scale = 1.0)
+const DEFAULT_THRESHOLD: f32 = 0.005;
+// Audio kept while suppressed, sent ahead of whatever ends the suppression (hold is
+// only found to be over a block after the prospect starts talking)
+const RESUME_PAD_MS: usize = 1_500;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SilenceSkipSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// RMS threshold override; None = the source's level calibration
+    #[serde(default)]
+    pub threshold: Option<f32>,
+    /// Silence streamed after speech before the gate closes
+    #[serde(default = "default_hangover_ms")]
+    pub hangover_ms: u32,
+    /// Silence sent ahead of speech when the gate opens
+    #[serde(default = "default_pad_ms")]
+    pub pad_ms: u32,
+}
+
+fn default_true() -> bool { true }
+fn default_hangover_ms() -> u32 { 800 }
+fn default_pad_ms() -> u32 { 300 }
+
+impl Default for SilenceSkipSettings {
+    fn default() -> Self {
+        Self { enabled: true, threshold: None, hangover_ms: default_hangover_ms(), pad_ms: default_pad_ms() }
+    }
+}
+
+/// What the gate wants sent for one captured buffer
+pub enum GateOutput {
+    Send { capture_ms: u64, samples: Vec<i16> },
+    Pause,  // Gate just closed: the engine can finalize what it has
+    Skip,
+}
+
+pub struct SilenceGate {
+    enabled: bool,
+    threshold: f32,
+    fixed: bool,  // Threshold set explicitly in the settings (never adapted)
+    hangover_ms: u64,
+    pad_samples: usize,
+    resume_samples: usize,
+    sample_rate: u64,
+    open: bool,
+    silent_ms: u64,
+    padding: VecDeque<i16>,
+}
+
+impl SilenceGate {
+    pub fn new(settings: &SilenceSkipSettings, threshold: f32, sample_rate: u32) -> Self {
+        Self {
+            enabled: settings.enabled,
+            threshold: settings.threshold.unwrap_or(threshold),
+            fixed: settings.threshold.is_some(),
+            hangover_ms: settings.hangover_ms as u64,
+            pad_samples: settings.pad_ms as usize * sample_rate as usize / 1000,
+            resume_samples: RESUME_PAD_MS * sample_rate as usize / 1000,
+            sample_rate: sample_rate as u64,
+            open: false,
+            silent_ms: 0,
+            padding: VecDeque::new(),
+        }
+    }
+
+    /// Gate with the given settings and the source's calibrated VAD threshold (if any)
+    pub fn for_source(settings: &SilenceSkipSettings, calibrated_threshold: Option<f32>, sample_rate: u32) -> Self {
+        Self::new(settings, calibrated_threshold.unwrap_or(DEFAULT_THRESHOLD), sample_rate)
+    }
+
+    /// Use the threshold adaptive_vad learned for this call (unless one is set explicitly)
+    pub fn adapt_threshold(&mut self, threshold: f32) {
+        if !self.fixed {
+            self.threshold = threshold;
+        }
+    }
+
+    /// Decide what to send for a mono buffer captured at `capture_ms`
+    pub fn process(&mut self, samples: &[i16], capture_ms: u64) -> GateOutput {
+        let duration_ms = samples.len() as u64 * 1000 / self.sample_rate;
+        // VAD bypassed (stage_bypass): stream everything, as with skipping turned off
+        if !self.enabled || crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::Vad) {
+            if !self.padding.is_empty() {
+                // Suppression just ended
+                return self.send_padded(samples, capture_ms, duration_ms);
+            }
+            USAGE.streamed_ms.fetch_add(duration_ms, Ordering::Relaxed);
+            return GateOutput::Send { capture_ms, samples: samples.to_vec() };
+        }
+        let rms = if samples.is_empty() {
+            0.0
+        } else {
+            (samples.iter().map(|&s| (s as f32 / 32768.0).powi(2)).sum::<f32>() / samples.len() as f32).sqrt()
+        };
+
+        if rms >= self.threshold {
+            self.silent_ms = 0;
+            if !self.open {
+                self.open = true;
+                return self.send_padded(samples, capture_ms, duration_ms);
+            }
+        } else if self.open {
+            self.silent_ms += duration_ms;
+            if self.silent_ms > self.hangover_ms {
+                self.open = false;
+                self.padding.clear();
+                USAGE.skipped_ms.fetch_add(duration_ms, Ordering::Relaxed);
+                return GateOutput::Pause;
+            }
+        }
+
+        if self.open {
+            USAGE.streamed_ms.fetch_add(duration_ms, Ordering::Relaxed);
+            GateOutput::Send { capture_ms, samples: samples.to_vec() }
+        } else {
+            self.padding.extend(samples.iter().copied());
+            let excess = self.padding.len().saturating_sub(self.pad_samples);
+            self.padding.drain(..excess);
+            USAGE.skipped_ms.fetch_add(duration_ms, Ordering::Relaxed);
+            GateOutput::Skip
+        }
+    }
+
+    /// Hold back a buffer whatever its level (hold music, dead air)
+    pub fn suppress(&mut self, samples: &[i16]) -> GateOutput {
+        // Streaming until now: let the engine finalize what it has
+        let streaming = self.open || (!self.enabled && self.padding.is_empty());
+        USAGE.skipped_ms.fetch_add(samples.len() as u64 * 1000 / self.sample_rate, Ordering::Relaxed);
+        self.padding.extend(samples.iter().copied());
+        let excess = self.padding.len().saturating_sub(self.resume_samples);
+        self.padding.drain(..excess);
+        self.open = false;
+        self.silent_ms = 0;
+        if streaming { GateOutput::Pause } else { GateOutput::Skip }
+    }
+
+    /// Send a buffer with the held-back audio in front (it was counted as skipped)
+    fn send_padded(&mut self, samples: &[i16], capture_ms: u64, duration_ms: u64) -> GateOutput {
+        let pad_ms = self.padding.len() as u64 * 1000 / self.sample_rate;
+        USAGE.skipped_ms.fetch_sub(pad_ms.min(USAGE.skipped_ms.load(Ordering::Relaxed)), Ordering::Relaxed);
+        USAGE.streamed_ms.fetch_add(pad_ms + duration_ms, Ordering::Relaxed);
+        let mut padded: Vec<i16> = self.padding.drain(..).collect();
+        padded.extend_from_slice(samples);
+        GateOutput::Send { capture_ms: capture_ms.saturating_sub(pad_ms), samples: padded }
+    }
+}
+
+/// Maps offsets in the audio an engine received back to capture time
+#[derive(Debug, Default)]
+pub struct Timeline {
+    points: Vec<(u64, u64)>,  // (sent_ms, capture_ms) where a voiced segment starts
+    sent_ms: u64,
+    next_capture_ms: Option<u64>,
+}
+
+impl Timeline {
+    /// Record a chunk as sent; a capture gap starts a new stitched segment
+    pub fn record(&mut self, capture_ms: u64, duration_ms: u64) {
+        // Callback jitter below this is not a gap
+        const GAP_TOLERANCE_MS: u64 = 100;
+        // (a jump back in capture time - audio replayed after a reconnect - starts one too)
+        let contiguous = self.next_capture_ms
+            .map_or(false, |next| capture_ms <= next + GAP_TOLERANCE_MS && capture_ms + GAP_TOLERANCE_MS >= next);
+        if !contiguous {
+            self.points.push((self.sent_ms, capture_ms));
+        }
+        self.sent_ms += duration_ms;
+        self.next_capture_ms = Some(capture_ms + duration_ms);
+    }
+
+    /// Total audio sent so far (the offset a new connection starts at)
+    pub fn sent_ms(&self) -> u64 {
+        self.sent_ms
+    }
+
+    /// Capture time of an offset into the sent audio
+    pub fn capture_ms(&self, sent_ms: u64) -> u64 {
+        match self.points.iter().rev().find(|(sent, _)| *sent <= sent_ms) {
+            Some((sent, capture)) => capture + (sent_ms - sent),
+            None => self.points.first().map_or(0, |(_, capture)| *capture),
+        }
+    }
+}
+
+struct Usage {
+    streamed_ms: AtomicU64,
+    skipped_ms: AtomicU64,
+    session_started: AtomicU64,
+}
+
+static USAGE: Usage = Usage {
+    streamed_ms: AtomicU64::new(0),
+    skipped_ms: AtomicU64::new(0),
+    session_started: AtomicU64::new(0),
+};
+
+// Totals of earlier sessions this run (the report covers both)
+static TOTALS: Lazy<Mutex<(u64, u64)>> = Lazy::new(|| Mutex::new((0, 0)));
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UsagePeriod {
+    pub streamed_seconds: f64,
+    pub skipped_seconds: f64,
+    pub estimated_cost_usd: f64,
+    pub estimated_savings_usd: f64,
+    pub savings_percent: f32,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UsageReport {
+    pub silence_skipping: bool,
+    pub session_started_at: Option<u64>,
+    pub session: UsagePeriod,
+    pub since_launch: UsagePeriod,
+}
+
+fn period(streamed_ms: u64, skipped_ms: u64) -> UsagePeriod {
+    let minutes = |ms: u64| ms as f64 / 60_000.0;
+    let total = streamed_ms + skipped_ms;
+    UsagePeriod {
+        streamed_seconds: streamed_ms as f64 / 1000.0,
+        skipped_seconds: skipped_ms as f64 / 1000.0,
+        estimated_cost_usd: minutes(streamed_ms) * DEEPGRAM_USD_PER_MINUTE,
+        estimated_savings_usd: minutes(skipped_ms) * DEEPGRAM_USD_PER_MINUTE,
+        savings_percent: if total > 0 { skipped_ms as f32 * 100.0 / total as f32 } else { 0.0 },
+    }
+}
+
+/// Start counting a new cloud streaming session
+pub fn begin_session() {
+    let streamed = USAGE.streamed_ms.swap(0, Ordering::Relaxed);
+    let skipped = USAGE.skipped_ms.swap(0, Ordering::Relaxed);
+    if streamed + skipped > 0 {
+        let mut totals = TOTALS.lock().unwrap();
+        totals.0 += streamed;
+        totals.1 += skipped;
+        info!("💰 Last session streamed {:.0}s, skipped {:.0}s of silence", streamed as f64 / 1000.0, skipped as f64 / 1000.0);
+    }
+    USAGE.session_started.store(chrono::Utc::now().timestamp_millis() as u64, Ordering::Relaxed);
+}
+
+/// Usage of the current session and since launch
+pub fn report(silence_skipping: bool) -> UsageReport {
+    let streamed = USAGE.streamed_ms.load(Ordering::Relaxed);
+    let skipped = USAGE.skipped_ms.load(Ordering::Relaxed);
+    let totals = *TOTALS.lock().unwrap();
+    let started = USAGE.session_started.load(Ordering::Relaxed);
+    UsageReport {
+        silence_skipping,
+        session_started_at: Some(started).filter(|&s| s > 0),
+        session: period(streamed, skipped),
+        since_launch: period(totals.0 + streamed, totals.1 + skipped),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skipped_silence_is_stitched_back_to_capture_time() {
+        let settings = SilenceSkipSettings { enabled: true, threshold: Some(0.1), hangover_ms: 200, pad_ms: 100 };
+        let mut gate = SilenceGate::new(&settings, DEFAULT_THRESHOLD, 1000);
+        let (silence, speech) = (vec![0i16; 100], vec![8000i16; 100]);  // 100 ms buffers
+        let mut timeline = Timeline::default();
+        let mut pauses = 0;
+
+        // Speech, 1 s of silence, speech again
+        let buffers = std::iter::repeat(&speech).take(3)
+            .chain(std::iter::repeat(&silence).take(10))
+            .chain(std::iter::repeat(&speech).take(2));
+        for (i, buffer) in buffers.enumerate() {
+            match gate.process(buffer, 10_000 + i as u64 * 100) {
+                GateOutput::Send { capture_ms, samples } => timeline.record(capture_ms, samples.len() as u64),
+                GateOutput::Pause => pauses += 1,
+                GateOutput::Skip => {}
+            }
+        }
+
+        assert_eq!(pauses, 1);
+        // 3 speech + 2 hangover, then 100 ms pad + 2 speech = 800 ms sent instead of 1500
+        assert_eq!(timeline.sent_ms(), 800);
+        assert_eq!(timeline.capture_ms(0), 10_000);
+        // Offset 500 is the pad in front of the second segment (captured at 11.2 s)
+        assert_eq!(timeline.capture_ms(550), 11_250);
+    }
+}
@@ -0,0 +1,737 @@
+// Deepgram Real-time Transcription for VoiceCoach
+// WebKit-quality cloud transcription with ultra-low latency
+// A results watchdog (ws_watchdog) reconnects a connection that stops answering
+// and replays the audio it swallowed. The app decides whether a source may be
+// transcribed at all (licensing, consent) and passes in its settings; the capture
+// stream and everything around it come through CaptureHost.
+
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use futures_util::{StreamExt, SinkExt};
+use futures_util::stream::{SplitSink, SplitStream};
+use serde::{Deserialize, Serialize};
+use cpal::traits::DeviceTrait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use log::{info, error, warn};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use crate::capture_host::{CaptureHost, Engine};
+use crate::cloud_usage::SilenceSkipSettings;
+use crate::events::EventSink;
+use crate::ws_watchdog::{CloudReconnect, ResultsWatchdog};
+
+#[derive(Clone, Serialize, Deserialize, specta::Type)]
+#[specta(rename = "DeepgramTranscriptionPayload")]
+pub struct TranscriptionPayload {
+    pub text: String,
+    pub is_final: bool,
+    pub timestamp: u64,
+    pub is_user: bool,
+    // Start of the speech on the session's audio clock (see audio_clock)
+    #[serde(default)]
+    pub audio_ms: u64,
+    // Diarized system-audio segments: "Prospect A" (or the renamed speaker) + engine speaker id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker_id: Option<u32>,
+    // Finals only: position in the chronological transcript (see transcript_sequencer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_index: Option<u64>,
+    // Mean word confidence and each word's, for the confidence heatmap (see transcript_confidence)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<crate::transcript_confidence::WordConfidence>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeepgramResponse {
+    channel: Option<Channel>,
+    is_final: Option<bool>,
+    speech_final: Option<bool>,
+    #[serde(default)]
+    start: Option<f64>,  // Seconds into the audio this connection received
+    #[serde(default)]
+    duration: Option<f64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Channel {
+    alternatives: Vec<Alternative>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Alternative {
+    transcript: String,
+    confidence: f32,
+    #[serde(default)]
+    words: Vec<Word>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Word {
+    word: String,
+    #[serde(default)]
+    punctuated_word: Option<String>,
+    start: f32,
+    end: f32,
+    #[serde(default)]
+    confidence: Option<f32>,
+    #[serde(default)]
+    speaker: Option<u32>,  // Only present with diarize=true
+}
+
+/// What is transcribed
+pub enum Source {
+    Microphone,
+    /// Loopback (monitor) device: the other side of the call
+    SystemAudio,
+    /// One application's audio, mono i16 at `sample_rate`
+    App { pid: u32, name: String, sample_rate: u32 },
+}
+
+/// What a session is started with
+pub struct StreamSettings {
+    pub api_key: String,
+    pub source: Source,
+    /// Separate "Prospect A" / "Prospect B" on multi-party calls
+    pub diarize: bool,
+    pub silence_skipping: SilenceSkipSettings,
+    /// Calibrated VAD threshold of the source, if the level wizard has been run
+    pub vad_threshold: Option<f32>,
+    /// Calibrated input gain
+    pub mic_gain: f32,
+}
+
+/// Split diarized words into consecutive same-speaker runs: (speaker, text, seconds, words)
+fn speaker_runs(words: &[Word]) -> Vec<(u32, String, f32, &[Word])> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for end in 1..=words.len() {
+        if end < words.len() && words[end].speaker.unwrap_or(0) == words[start].speaker.unwrap_or(0) {
+            continue;
+        }
+        let run = &words[start..end];
+        let text = run.iter()
+            .map(|w| w.punctuated_word.as_deref().unwrap_or(&w.word))
+            .collect::<Vec<_>>()
+            .join(" ");
+        runs.push((run[0].speaker.unwrap_or(0), text, run[run.len() - 1].end - run[0].start, run));
+        start = end;
+    }
+    runs
+}
+
+/// Word timings on the capture clock (word times count from this connection's start)
+fn timed_words(host: &dyn CaptureHost, words: &[Word], sent_base_ms: u64, timeline: &crate::cloud_usage::Timeline) -> Vec<crate::transcript::TranscriptWord> {
+    let capture_ms = |seconds: f32| timeline.capture_ms(sent_base_ms + (seconds * 1000.0) as u64);
+    words.iter()
+        .map(|w| crate::transcript::TranscriptWord {
+            word: host.clean_text(w.punctuated_word.as_deref().unwrap_or(&w.word)),
+            start_ms: capture_ms(w.start),
+            end_ms: capture_ms(w.end),
+            confidence: w.confidence,
+        })
+        .collect()
+}
+
+// Global connection state
+static IS_RUNNING: AtomicBool = AtomicBool::new(false);
+// The running transcription is of the prospect channel
+static PROSPECT: AtomicBool = AtomicBool::new(false);
+
+type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+type WsSink = SplitSink<WsStream, Message>;
+type WsSource = SplitStream<WsStream>;
+type AudioSender = tokio::sync::mpsc::UnboundedSender<Outgoing>;
+type SharedTimeline = Arc<std::sync::Mutex<crate::cloud_usage::Timeline>>;
+type SharedWatchdog = Arc<std::sync::Mutex<ResultsWatchdog>>;
+
+// Owner name of the capture stream in the capture registry (privacy mute)
+pub const STREAM_OWNER: &str = "deepgram";
+
+// Idle time (silence skipped) after which the connection is kept open explicitly
+const KEEPALIVE_SECS: u64 = 5;
+
+// Queued for the forwarder task
+enum Outgoing {
+    Audio { capture_ms: u64, samples: Vec<i16> },
+    Pause,  // Silence gate closed: ask Deepgram to finalize the pending utterance
+}
+
+// Queue what the silence gate lets through
+fn forward(audio_tx: &AudioSender, output: crate::cloud_usage::GateOutput) -> bool {
+    match output {
+        crate::cloud_usage::GateOutput::Send { capture_ms, samples } => audio_tx.send(Outgoing::Audio { capture_ms, samples }).is_ok(),
+        crate::cloud_usage::GateOutput::Pause => audio_tx.send(Outgoing::Pause).is_ok(),
+        crate::cloud_usage::GateOutput::Skip => true,
+    }
+}
+
+// Bumped per WebSocket connection; stage re-biasing replaces the live connection
+static CONNECTION: AtomicU32 = AtomicU32::new(0);
+const STAGE_CHECK_SECS: u64 = 1;
+const WATCHDOG_CHECK_SECS: u64 = 1;
+// A replaced connection with no traffic for this long is abandoned (half-open sockets never close)
+const SUPERSEDED_IDLE_SECS: u64 = 5;
+
+fn listen_url(host: &dyn CaptureHost, sample_rate: u32, diarize: bool) -> String {
+    format!(
+        "wss://api.deepgram.com/v1/listen?\
+        encoding=linear16&\
+        sample_rate={}&\
+        channels=1&\
+        punctuate=true&\
+        interim_results=true&\
+        endpointing=300&\
+        vad_turnoff=500{}{}",
+        sample_rate,
+        if diarize { "&diarize=true" } else { "" },
+        crate::sales_stage::deepgram_keywords_query(&host.stage_bias())
+    )
+}
+
+async fn connect(ws_url: &str, api_key: &str) -> Result<WsStream, String> {
+    if crate::chaos::inject(crate::chaos::Fault::NetworkTimeout) {
+        return Err("Failed to connect to Deepgram: connection timed out".to_string());
+    }
+    // Create connection with auth
+    let request = http::Request::builder()
+        .uri(ws_url)
+        .header("Authorization", format!("Token {}", api_key))
+        .header("Sec-WebSocket-Protocol", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .body(())
+        .map_err(|e| format!("Failed to build request: {}", e))?;
+
+    // Connect to WebSocket
+    let (ws_stream, _) = connect_async(request)
+        .await
+        .map_err(|e| format!("Failed to connect to Deepgram: {}. Check your API key.", e))?;
+    Ok(ws_stream)
+}
+
+/// Start Deepgram real-time transcription of `settings.source`
+pub async fn start(events: Arc<dyn EventSink>, host: Arc<dyn CaptureHost>, settings: StreamSettings) -> Result<(), String> {
+    let StreamSettings { api_key, source, diarize, silence_skipping, vad_threshold, mic_gain } = settings;
+    let system_audio = matches!(source, Source::SystemAudio);
+
+    // 16kHz mono for Deepgram
+    let requested = cpal::StreamConfig {
+        channels: 1,
+        sample_rate: cpal::SampleRate(16000),
+        buffer_size: cpal::BufferSize::Fixed(1600), // 100ms chunks for low latency
+    };
+
+    // Exclusive-mode conflicts: fall back to the shared format and tell Deepgram its rate
+    // (scoped so the non-Send cpal device isn't held across the connect await)
+    let config = match &source {
+        Source::App { sample_rate, .. } => cpal::StreamConfig {
+            channels: 1,
+            sample_rate: cpal::SampleRate(*sample_rate),
+            buffer_size: cpal::BufferSize::Default,
+        },
+        Source::SystemAudio => {
+            let device = host.system_audio_device()
+                .ok_or("No system audio (loopback/monitor) device available")?;
+            let mut config: cpal::StreamConfig = host.system_audio_config(&device)?.into();
+            config.buffer_size = cpal::BufferSize::Default;
+            config
+        }
+        Source::Microphone => {
+            let device = host.input_device()
+                .ok_or("No input device available")?;
+            host.negotiate_input_config(STREAM_OWNER, &device, requested)?
+        }
+    };
+    let input_channels = config.channels as usize;
+    let tap_compatible = config.sample_rate.0 == 16000;
+
+    // Deepgram WebSocket URL with parameters for best quality (+ sales stage keyword boosting)
+    let sample_rate = config.sample_rate.0;
+    let ws_url = listen_url(&*host, sample_rate, diarize);
+    let ws_stream = connect(&ws_url, &api_key).await?;
+
+    info!("✅ Connected to Deepgram WebSocket");
+    IS_RUNNING.store(true, Ordering::Relaxed);
+    let connection = CONNECTION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let (ws_sender, ws_receiver) = ws_stream.split();
+    let ws_sender = Arc::new(Mutex::new(ws_sender));
+
+    // Handle incoming transcriptions (loopback audio is the other side of the call)
+    let is_user = matches!(source, Source::Microphone);
+    PROSPECT.store(!is_user, Ordering::Relaxed);
+    let timeline: SharedTimeline = Arc::new(std::sync::Mutex::new(crate::cloud_usage::Timeline::default()));
+    let watchdog: SharedWatchdog = Arc::new(std::sync::Mutex::new(ResultsWatchdog::default()));
+    let stream = StreamContext { events, host: host.clone(), api_key, sample_rate, diarize, is_user, ws_sender: ws_sender.clone(), timeline: timeline.clone(), watchdog: watchdog.clone() };
+    spawn_receiver(&stream, ws_receiver, connection, 0);
+
+    // Reconnect with new keywords when the detected sales stage changes
+    spawn_stage_rebias(stream.clone(), ws_url);
+    // Reconnect (and replay) when the connection stops returning results
+    spawn_results_watchdog(stream);
+
+    // Capture callbacks queue chunks; one task sends them so they reach Deepgram in order
+    let audio_tx = spawn_audio_forwarder(ws_sender.clone(), timeline, watchdog, sample_rate, host.clone());
+    // Silence is not streamed (billed per second); the timeline maps results back
+    crate::cloud_usage::begin_session();
+    let mut gate = crate::cloud_usage::SilenceGate::for_source(&silence_skipping, vad_threshold, sample_rate);
+    // The app's processors on the source: the learned VAD threshold, hold detection
+    // (prospect) or the headset mute (rep) - neither hold music nor a muted mic is
+    // streamed - talk-over, noise removal on the rep's microphone (the detectors keep
+    // the raw signal), snippets and the call recording
+    let mut audio = host.stream_audio(is_user, sample_rate, None);
+    // Buffers are stamped by the samples the device delivered
+    let mut sample_clock = crate::audio_clock::SampleClock::new(sample_rate);
+
+    // Per-application loopback delivers mono i16 from its own capture thread
+    if let Source::App { pid, name, .. } = source {
+        let audio_tx = audio_tx.clone();
+        let started = host.start_app_capture(pid, Box::new(move |samples: &[i16]| {
+            if !IS_RUNNING.load(Ordering::Relaxed) {
+                return false;
+            }
+            let capture_ms = sample_clock.stamp(crate::transcript_sequencer::capture_ms(), samples.len());
+            if crate::chaos::inject(crate::chaos::Fault::ChannelSaturation) {
+                return true;
+            }
+            // Loopback silenced (stage_bypass): is the prospect side what stops transcription?
+            let silenced;
+            let samples = if crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::LoopbackSilence) {
+                silenced = vec![0i16; samples.len()];
+                &silenced[..]
+            } else {
+                samples
+            };
+            crate::audio_tap::write_samples(samples);
+            audio.record(samples, capture_ms);
+            let levels: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+            audio.track_talk(&levels, capture_ms, None);
+            let paused = audio.pausing(&levels, capture_ms) && !crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::Vad);
+            let output = if paused { gate.suppress(samples) } else { gate.process(samples, capture_ms) };
+            forward(&audio_tx, output)
+        }));
+        if let Err(e) = started {
+            IS_RUNNING.store(false, Ordering::Relaxed);
+            return Err(e);
+        }
+        info!("Using audio from {} (pid {})", name, pid);
+        return Ok(());
+    }
+
+    // Setup audio capture
+    let device = if system_audio {
+        host.system_audio_device()
+    } else {
+        host.input_device()
+    }.ok_or("No input device available")?;
+
+    info!("Using audio device: {}", device.name().unwrap_or_default());
+    // The device's own sample format (converted to f32 in the stream)
+    let sample_format = if system_audio {
+        host.system_audio_config(&device).map_or(cpal::SampleFormat::F32, |c| c.sample_format())
+    } else {
+        crate::sample_format::input_format(&device)
+    };
+
+    // Build audio stream
+    let callback_host = host.clone();
+    host.open_stream(STREAM_OWNER, sample_rate, is_user, Box::new(move || crate::sample_format::build_input_stream(
+        &device,
+        &config,
+        sample_format,
+        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+            if !IS_RUNNING.load(Ordering::Relaxed) {
+                return;
+            }
+            let _timing = crate::pipeline_stats::time_callback(crate::pipeline_stats::PipelineStream::DeepgramCapture, data.len() / input_channels.max(1), sample_rate);
+            let capture_ms = sample_clock.stamp(crate::transcript_sequencer::capture_ms(), data.len() / input_channels.max(1));
+
+            // Sidetone (mic only; a no-op for loopback, which has no monitor)
+            callback_host.sidetone(STREAM_OWNER, data, input_channels);
+
+            // Saturated pipeline: drop the chunk rather than queue unbounded sends
+            if crate::chaos::inject(crate::chaos::Fault::ChannelSaturation) {
+                return;
+            }
+
+            let downmixed;
+            let data = if input_channels > 1 {
+                downmixed = crate::sample_format::downmix_to_mono(data, input_channels);
+                &downmixed[..]
+            } else {
+                data
+            };
+            // Loopback silenced (stage_bypass): is the prospect side what stops transcription?
+            let silenced;
+            let data = if !is_user && crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::LoopbackSilence) {
+                silenced = vec![0.0f32; data.len()];
+                &silenced[..]
+            } else {
+                data
+            };
+
+            // Calibrated gain, stepped live by the mic quality watch when auto-adjust is on
+            let preprocess = !crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::Preprocessing);
+            let gain = if preprocess { mic_gain * audio.gain() } else { 1.0 };
+            let gained: Vec<f32> = data.iter().map(|&sample| (sample * gain).clamp(-1.0, 1.0)).collect();
+            audio.check_quality(&gained);
+            let learned = audio.learn_threshold(&gained);
+            if let Some(threshold) = learned {
+                gate.adapt_threshold(threshold);
+            }
+            audio.track_talk(&gained, capture_ms, learned);
+
+            let cleaned = if preprocess { audio.denoised(&gained) } else { None };
+
+            // Convert f32 to i16 (LINEAR16 format)
+            let i16_data: Vec<i16> = cleaned.as_deref().unwrap_or(&gained).iter()
+                .map(|&sample| (sample * 32767.0) as i16)
+                .collect();
+
+            // Debug tap: tee exactly what Deepgram receives (no-op unless enabled; tap files are 16kHz)
+            if tap_compatible {
+                crate::audio_tap::write_samples(&i16_data);
+            }
+
+            // Send to Deepgram (unless the silence gate, hold detection or a mute holds it back)
+            audio.record(&i16_data, capture_ms);
+            let paused = audio.pausing(&gained, capture_ms)
+                && !crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::Vad);
+            let output = if paused { gate.suppress(&i16_data) } else { gate.process(&i16_data, capture_ms) };
+            forward(&audio_tx, output);
+        },
+        |err| {
+            error!("Audio stream error: {:?}", err);
+        },
+    ).map_err(|e| format!("Failed to build audio stream: {}", e))))?;
+
+    Ok(())
+}
+
+// What a connection of the session needs; shared by the tasks that replace connections
+#[derive(Clone)]
+struct StreamContext {
+    events: Arc<dyn EventSink>,
+    host: Arc<dyn CaptureHost>,
+    api_key: String,
+    sample_rate: u32,
+    diarize: bool,
+    is_user: bool,
+    ws_sender: Arc<Mutex<WsSink>>,
+    timeline: SharedTimeline,
+    watchdog: SharedWatchdog,
+}
+
+fn audio_message(samples: &[i16]) -> Message {
+    Message::Binary(samples.iter().flat_map(|&sample| sample.to_le_bytes()).collect())
+}
+
+// Send captured chunks in capture order (one task; the sink is swapped on re-bias).
+// Sent audio is recorded on the timeline so skipped silence can be stitched out, and
+// with the watchdog so it can be replayed if the connection turns out to be dead.
+fn spawn_audio_forwarder(ws_sender: Arc<Mutex<WsSink>>, timeline: SharedTimeline, watchdog: SharedWatchdog, sample_rate: u32, host: Arc<dyn CaptureHost>) -> AudioSender {
+    let (audio_tx, mut audio_rx) = tokio::sync::mpsc::unbounded_channel::<Outgoing>();
+    tokio::spawn(async move {
+        loop {
+            let message = match tokio::time::timeout(std::time::Duration::from_secs(KEEPALIVE_SECS), audio_rx.recv()).await {
+                Ok(Some(Outgoing::Audio { capture_ms, samples })) => {
+                    let duration_ms = samples.len() as u64 * 1000 / sample_rate as u64;
+                    (audio_message(&samples), Some((capture_ms, duration_ms, samples)))
+                }
+                Ok(Some(Outgoing::Pause)) => (Message::Text(r#"{"type":"Finalize"}"#.to_string()), None),
+                Ok(None) => break,
+                // Nothing sent for a while (silence skipped): Deepgram closes idle streams
+                Err(_) if IS_RUNNING.load(Ordering::Relaxed) => (Message::Text(r#"{"type":"KeepAlive"}"#.to_string()), None),
+                Err(_) => break,
+            };
+            let mut sender = ws_sender.lock().await;
+            if let Some((capture_ms, duration_ms, samples)) = message.1 {
+                let sent_ms = {
+                    let mut timeline = timeline.lock().unwrap();
+                    timeline.record(capture_ms, duration_ms);
+                    timeline.sent_ms()
+                };
+                watchdog.lock().unwrap().sent(sent_ms, capture_ms, samples);
+            }
+            if let Err(e) = sender.send(message.0).await {
+                error!("Failed to send audio to Deepgram: {}", e);
+                host.engine_error("deepgram");
+            }
+        }
+    });
+    audio_tx
+}
+
+// Forward transcripts from one Deepgram connection to the frontend. Only the newest
+// connection ends the session when it closes (stage re-biasing and the watchdog
+// replace connections). `sent_base_ms` is where this connection's audio starts on the
+// session timeline.
+fn spawn_receiver(stream: &StreamContext, mut ws_receiver: WsSource, connection: u32, sent_base_ms: u64) {
+    let (events, host) = (stream.events.clone(), stream.host.clone());
+    let (diarize, is_user) = (stream.diarize, stream.is_user);
+    let timeline = stream.timeline.clone();
+    let watchdog = stream.watchdog.clone();
+    tokio::spawn(async move {
+        let mut last_transcript = String::new();
+
+        loop {
+            let msg = match tokio::time::timeout(std::time::Duration::from_secs(SUPERSEDED_IDLE_SECS), ws_receiver.next()).await {
+                Ok(Some(msg)) => msg,
+                Ok(None) => break,
+                // Replaced or stopped and gone quiet: don't wait on a dead socket forever
+                Err(_) if CONNECTION.load(Ordering::SeqCst) != connection || !IS_RUNNING.load(Ordering::Relaxed) => break,
+                Err(_) => continue,
+            };
+            match msg {
+                Ok(Message::Text(text)) => {
+                    if let Ok(response) = serde_json::from_str::<DeepgramResponse>(&text) {
+                        if response.channel.is_some() {
+                            // Any result (even an empty one) shows the connection is alive
+                            let end_s = response.start.unwrap_or(0.0) + response.duration.unwrap_or(0.0);
+                            watchdog.lock().unwrap().result(sent_base_ms + (end_s * 1000.0) as u64, response.is_final.unwrap_or(false));
+                        }
+                        if let Some(channel) = response.channel {
+                            if let Some(alt) = channel.alternatives.first() {
+                                let transcript = &alt.transcript;
+
+                                // Skip empty transcripts
+                                if transcript.is_empty() {
+                                    continue;
+                                }
+
+                                // Determine if this is final or interim
+                                let is_final = response.is_final.unwrap_or(false) ||
+                                              response.speech_final.unwrap_or(false);
+
+                                // Only emit if text changed (avoid duplicates)
+                                if transcript != &last_transcript {
+                                    info!("{} transcript: {} (confidence: {:.2})",
+                                        if is_final { "Final" } else { "Interim" },
+                                        transcript, alt.confidence);
+
+                                    let diarized = diarize && alt.words.iter().any(|w| w.speaker.is_some());
+                                    // Finals are released in capture order across engines and connections
+                                    let start_ms = sent_base_ms + (response.start.unwrap_or(0.0) * 1000.0) as u64;
+                                    let capture_ms = timeline.lock().unwrap().capture_ms(start_ms);
+                                    let end_ms = start_ms + (response.duration.unwrap_or(0.0) * 1000.0) as u64;
+                                    let audio_end_ms = timeline.lock().unwrap().capture_ms(end_ms);
+                                    let audio_ms = host.offset_of(capture_ms);
+                                    if is_final {
+                                        // Time from the end of the segment's audio to its result arriving
+                                        let latency_ms = crate::transcript_sequencer::capture_ms().saturating_sub(audio_end_ms);
+                                        host.transcribed("deepgram", latency_ms);
+                                    }
+                                    if diarized && is_final {
+                                        // One event per speaker turn inside the final result
+                                        for (speaker_id, run_text, seconds, run_words) in speaker_runs(&alt.words) {
+                                            let label = host.speaker_label(speaker_id, seconds);
+                                            let text = host.apply_plugins(&host.clean_text(&run_text));
+                                            let (release_events, release_host) = (events.clone(), host.clone());
+                                            let submitted = text.clone();
+                                            let speech_ms = (seconds * 1000.0) as u64;
+                                            let words = timed_words(&*host, run_words, sent_base_ms, &timeline.lock().unwrap());
+                                            let run_start_ms = words.first().map_or(capture_ms, |w| w.start_ms);
+                                            let audio_ms = host.offset_of(run_start_ms);
+                                            let confidence = crate::transcript_confidence::segment_confidence(&words);
+                                            let word_confidences = crate::transcript_confidence::live_words(&words, |ms| host.offset_of(ms));
+                                            host.submit_final(Engine::Deepgram, is_user, capture_ms, audio_end_ms, &submitted, Box::new(move |segment_index, timestamp| {
+                                                let (events, host) = (&*release_events, &*release_host);
+                                                let payload = TranscriptionPayload {
+                                                    text: text.clone(),
+                                                    is_final,
+                                                    timestamp,
+                                                    is_user,
+                                                    audio_ms,
+                                                    speaker: Some(label.clone()),
+                                                    speaker_id: Some(speaker_id),
+                                                    segment_index: Some(segment_index),
+                                                    confidence,
+                                                    words: word_confidences,
+                                                };
+                                                let _ = events.emit("voice_transcription", payload);
+                                                host.publish_final(&text, is_user, Some(&label));
+                                                crate::call_analytics::process_final_transcript(events, host, &text, is_user, speech_ms, run_start_ms, words);
+                                                host.observe_commands(&text, is_user);
+                                            }));
+                                        }
+                                    } else {
+                                        let speaker_id = alt.words.first().and_then(|w| w.speaker).filter(|_| diarized);
+                                        let text = host.clean_text(transcript);
+                                        let text = if is_final { host.apply_plugins(&text) } else { text };
+                                        let words = timed_words(&*host, &alt.words, sent_base_ms, &timeline.lock().unwrap());
+                                        let mut payload = TranscriptionPayload {
+                                            text: text.clone(),
+                                            is_final,
+                                            timestamp: host.wall_ms(capture_ms),
+                                            is_user,
+                                            audio_ms,
+                                            speaker: speaker_id.and_then(|id| host.speaker_name(id)),
+                                            speaker_id,
+                                            segment_index: None,
+                                            confidence: crate::transcript_confidence::segment_confidence(&words),
+                                            words: crate::transcript_confidence::live_words(&words, |ms| host.offset_of(ms)),
+                                        };
+
+                                        if is_final {
+                                            let (release_events, release_host) = (events.clone(), host.clone());
+                                            let submitted = text.clone();
+                                            let speech_ms = audio_end_ms.saturating_sub(capture_ms);
+                                            host.submit_final(Engine::Deepgram, is_user, capture_ms, audio_end_ms, &submitted, Box::new(move |segment_index, timestamp| {
+                                                let (events, host) = (&*release_events, &*release_host);
+                                                payload.segment_index = Some(segment_index);
+                                                payload.timestamp = timestamp;
+                                                let _ = events.emit("voice_transcription", payload);
+                                                host.publish_final(&text, is_user, None);
+                                                crate::call_analytics::process_final_transcript(events, host, &text, is_user, speech_ms, capture_ms, words);
+                                                host.observe_commands(&text, is_user);
+                                            }));
+                                        } else if host.emits_partials(Engine::Deepgram, is_user) {
+                                            let _ = events.emit("voice_transcription", payload);
+                                        }
+                                    }
+                                    last_transcript = transcript.clone();
+                                }
+
+                                // Clear last transcript on final to prepare for next utterance
+                                if is_final {
+                                    last_transcript.clear();
+                                }
+                            }
+                        }
+                    }
+                },
+                Ok(Message::Close(_)) => {
+                    info!("WebSocket connection closed");
+                    break;
+                },
+                Err(e) => {
+                    error!("WebSocket error: {}", e);
+                    break;
+                },
+                _ => {}
+            }
+        }
+
+        if CONNECTION.load(Ordering::SeqCst) == connection {
+            IS_RUNNING.store(false, Ordering::Relaxed);
+        }
+    });
+}
+
+fn spawn_stage_rebias(stream: StreamContext, mut applied_url: String) {
+    let StreamContext { host, api_key, sample_rate, diarize, ws_sender, timeline, .. } = stream.clone();
+    tokio::spawn(async move {
+        let mut applied_generation = crate::sales_stage::generation();
+        while IS_RUNNING.load(Ordering::Relaxed) {
+            tokio::time::sleep(std::time::Duration::from_secs(STAGE_CHECK_SECS)).await;
+            let generation = crate::sales_stage::generation();
+            if generation == applied_generation {
+                continue;
+            }
+            applied_generation = generation;
+            let url = listen_url(&*host, sample_rate, diarize);
+            if url == applied_url || !IS_RUNNING.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            match connect(&url, &api_key).await {
+                Ok(ws_stream) => {
+                    let connection = CONNECTION.fetch_add(1, Ordering::SeqCst) + 1;
+                    let (new_sender, new_receiver) = ws_stream.split();
+                    let mut sink = ws_sender.lock().await;
+                    // The new connection's audio starts with the next chunk forwarded after the swap
+                    let sent_base_ms = timeline.lock().unwrap().sent_ms();
+                    spawn_receiver(&stream, new_receiver, connection, sent_base_ms);
+                    let mut old_sender = std::mem::replace(&mut *sink, new_sender);
+                    drop(sink);
+                    // The old connection flushes its pending results, then closes
+                    let _ = old_sender.send(Message::Text(r#"{"type":"CloseStream"}"#.to_string())).await;
+                    applied_url = url;
+                    info!("🧭 Deepgram reconnected with sales stage keywords");
+                }
+                Err(e) => warn!("⚠️ Stage keyword reconnect failed, keeping current connection: {}", e),
+            }
+        }
+    });
+}
+
+// Replace a connection that stopped returning results and replay what it swallowed
+fn spawn_results_watchdog(stream: StreamContext) {
+    tokio::spawn(async move {
+        while IS_RUNNING.load(Ordering::Relaxed) {
+            tokio::time::sleep(std::time::Duration::from_secs(WATCHDOG_CHECK_SECS)).await;
+            let (stalled, unanswered_ms) = {
+                let watchdog = stream.watchdog.lock().unwrap();
+                (watchdog.stalled(), watchdog.unanswered_ms())
+            };
+            if !stalled || !IS_RUNNING.load(Ordering::Relaxed) {
+                continue;
+            }
+            warn!("⚠️ No Deepgram results for {}ms of sent audio, reconnecting", unanswered_ms);
+            stream.host.engine_error("deepgram");
+
+            let ws_stream = match connect(&listen_url(&*stream.host, stream.sample_rate, stream.diarize), &stream.api_key).await {
+                Ok(ws_stream) => ws_stream,
+                Err(e) => {
+                    // Still stalled: retried on the next check
+                    warn!("⚠️ Deepgram watchdog reconnect failed: {}", e);
+                    continue;
+                }
+            };
+            let connection = CONNECTION.fetch_add(1, Ordering::SeqCst) + 1;
+            let (new_sender, new_receiver) = ws_stream.split();
+            let mut sink = stream.ws_sender.lock().await;
+            let sent_base_ms = stream.timeline.lock().unwrap().sent_ms();
+            spawn_receiver(&stream, new_receiver, connection, sent_base_ms);
+            let mut old_sender = std::mem::replace(&mut *sink, new_sender);
+
+            // Replay before the forwarder can send new audio (it waits on the sink)
+            let replay = stream.watchdog.lock().unwrap().reconnected(sent_base_ms);
+            let mut replayed_ms = 0;
+            for (capture_ms, samples) in replay {
+                let duration_ms = samples.len() as u64 * 1000 / stream.sample_rate as u64;
+                if let Err(e) = sink.send(audio_message(&samples)).await {
+                    error!("Failed to replay audio to Deepgram: {}", e);
+                    break;
+                }
+                let sent_ms = {
+                    let mut timeline = stream.timeline.lock().unwrap();
+                    timeline.record(capture_ms, duration_ms);
+                    timeline.sent_ms()
+                };
+                stream.watchdog.lock().unwrap().sent(sent_ms, capture_ms, samples);
+                replayed_ms += duration_ms;
+            }
+            drop(sink);
+            // The old socket is half-open; closing may never complete
+            tokio::spawn(async move {
+                let _ = tokio::time::timeout(std::time::Duration::from_secs(SUPERSEDED_IDLE_SECS), old_sender.close()).await;
+            });
+
+            info!("🔁 Deepgram reconnected by watchdog, replayed {}ms of audio", replayed_ms);
+            let event = CloudReconnect { engine: "deepgram".to_string(), unanswered_ms, replayed_ms };
+            if let Err(e) = stream.events.emit("cloud_reconnected", event) {
+                error!("Failed to emit cloud_reconnected: {:?}", e);
+            }
+        }
+    });
+}
+
+/// Whether a transcription session is streaming
+pub fn is_running() -> bool {
+    IS_RUNNING.load(Ordering::Relaxed)
+}
+
+/// Whether the prospect channel is being transcribed
+pub fn transcribing_prospect() -> bool {
+    IS_RUNNING.load(Ordering::Relaxed) && PROSPECT.load(Ordering::Relaxed)
+}
+
+/// Stop streaming (the capture stream is closed by its owner; app audio capture ends
+/// on its next buffer)
+pub fn stop() {
+    IS_RUNNING.store(false, Ordering::Relaxed);
+}
@@ -0,0 +1,74 @@
+// Events - where core logic sends what the app shows as window events
+// Long-running work (conversions, ingestion, transcription) reports progress as named
+// events with a JSON payload. Core code emits them to an EventSink rather than a Tauri
+// AppHandle: the desktop app's sink forwards them to every window (emit_all), the CLI
+// and tests use NullSink or RecordingSink. Emitting is best effort - a failed emit is
+// returned for the caller to log, never fatal to the work.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Mutex;
+
+/// Receiver of named events with JSON payloads
+pub trait EventSink: Send + Sync {
+    fn emit_json(&self, event: &str, payload: Value) -> Result<(), String>;
+}
+
+impl dyn EventSink + '_ {
+    /// Serialize `payload` and emit it as `event`
+    pub fn emit<P: Serialize>(&self, event: &str, payload: P) -> Result<(), String> {
+        let payload = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+        self.emit_json(event, payload)
+    }
+}
+
+/// Drops every event (the CLI, callers with nobody to tell)
+pub struct NullSink;
+
+impl EventSink for NullSink {
+    fn emit_json(&self, _event: &str, _payload: Value) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Keeps every event in order, for tests
+#[derive(Default)]
+pub struct RecordingSink {
+    events: Mutex<Vec<(String, Value)>>,
+}
+
+impl RecordingSink {
+    pub fn events(&self) -> Vec<(String, Value)> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl EventSink for RecordingSink {
+    fn emit_json(&self, event: &str, payload: Value) -> Result<(), String> {
+        self.events.lock().unwrap().push((event.to_string(), payload));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Progress {
+        done_ms: u64,
+    }
+
+    #[test]
+    fn test_events_are_serialized_to_the_sink() {
+        let recording = RecordingSink::default();
+        let sink: &dyn EventSink = &recording;
+        sink.emit("progress", Progress { done_ms: 1_500 }).unwrap();
+        sink.emit("finished", true).unwrap();
+        assert_eq!(recording.events(), vec![
+            ("progress".to_string(), serde_json::json!({ "done_ms": 1_500 })),
+            ("finished".to_string(), Value::Bool(true)),
+        ]);
+        assert!((&NullSink as &dyn EventSink).emit("ignored", 1).is_ok());
+    }
+}
@@ -0,0 +1,240 @@
+// File-based Vosk transcription for VoiceCoach
+// Shared by the GUI (transcribe_audio_file command) and the headless CLI
+
+use serde::{Serialize, Deserialize};
+use log::info;
+use anyhow::{Result, Context};
+use std::time::Instant;
+
+use crate::recognizer_pool::{self, RecognizerPool};
+
+pub const VOSK_SAMPLE_RATE: u32 = 16000;
+const DEFAULT_MODEL_PATH: &str = "../models/vosk-model-small-en-us-0.15";
+// Parallel decoding splits files into windows of about this length
+const WINDOW_MS: u64 = 30_000;
+// ... cut at the quietest point in this final stretch of each window
+const CUT_SEARCH_MS: u64 = 5_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct FileTranscriptSegment {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct FileTranscript {
+    pub file_path: String,
+    pub duration_ms: u64,
+    pub processing_time_ms: u64,
+    pub segments: Vec<FileTranscriptSegment>,
+}
+
+impl FileTranscript {
+    /// Plain text transcript, one segment per line
+    pub fn text(&self) -> String {
+        self.segments.iter()
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// vosk-config.jsonc/.json with comment lines stripped
+fn read_config() -> Option<serde_json::Value> {
+    let config_str = std::fs::read_to_string("vosk-config.jsonc")
+        .or_else(|_| std::fs::read_to_string("vosk-config.json"))
+        .ok()?;
+    let clean_json = config_str
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            !trimmed.starts_with("//") && !trimmed.starts_with("/*") && !trimmed.starts_with("*")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    serde_json::from_str(&clean_json).ok()
+}
+
+/// Resolve the Vosk model path from vosk-config.jsonc/.json (the large model where the
+/// hardware suits it, then small)
+pub fn resolve_model_path() -> String {
+    if let Some(config) = read_config() {
+        let large = config["model_paths"]["large_model"].as_str().unwrap_or("");
+        let small = config["model_paths"]["small_model"].as_str().unwrap_or("");
+        if let Some(path) = crate::hardware_profile::default_model_path(large, small) {
+            return path;
+        }
+    }
+
+    DEFAULT_MODEL_PATH.to_string()
+}
+
+/// The configured large model, when it is installed (whatever the hardware)
+pub fn large_model_path() -> Option<String> {
+    let config = read_config()?;
+    let large = config["model_paths"]["large_model"].as_str()?;
+    (!large.is_empty() && std::path::Path::new(large).exists()).then(|| large.to_string())
+}
+
+/// Recognizer pool size: recognizer_settings.pool_size, or the hardware profile's thread
+/// count when 0/unset
+pub fn resolve_pool_size() -> usize {
+    let configured = read_config()
+        .and_then(|config| config["recognizer_settings"]["pool_size"].as_u64())
+        .unwrap_or(0) as usize;
+    if configured > 0 {
+        return configured;
+    }
+    crate::hardware_profile::decision().recognizer_threads
+}
+
+/// Read a WAV file and return 16kHz mono i16 samples ready for Vosk
+pub fn load_wav_for_vosk(path: &str) -> Result<Vec<i16>> {
+    let (mono, sample_rate) = read_wav_mono(path)?;
+    let resampled = resample_to_16k(&mono, sample_rate);
+
+    Ok(resampled.iter()
+        .map(|&sample| (sample.clamp(-1.0, 1.0) * 32767.0) as i16)
+        .collect())
+}
+
+/// Read a WAV file as mono f32 samples in [-1.0, 1.0] at its own sample rate
+pub fn read_wav_mono(path: &str) -> Result<(Vec<f32>, u32)> {
+    let mut reader = hound::WavReader::open(path)
+        .context(format!("Failed to open WAV file: {}", path))?;
+    let spec = reader.spec();
+
+    info!("📂 LED 8101: Reading {} ({} Hz, {} channels, {} bit {:?})",
+        path, spec.sample_rate, spec.channels, spec.bits_per_sample, spec.sample_format);
+
+    // Normalize everything to f32 in [-1.0, 1.0]
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>()
+            .collect::<std::result::Result<Vec<_>, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        }
+    };
+
+    // Downmix to mono - Vosk ONLY works with mono audio
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok((mono, spec.sample_rate))
+}
+
+/// Linear interpolation resampler (same approach as the live Vosk stream)
+pub fn resample_to_16k(data: &[f32], source_rate: u32) -> Vec<f32> {
+    if source_rate == VOSK_SAMPLE_RATE || data.is_empty() {
+        return data.to_vec();
+    }
+
+    let ratio = source_rate as f32 / VOSK_SAMPLE_RATE as f32;
+    let output_len = (data.len() as f32 / ratio) as usize;
+    let mut resampled = Vec::with_capacity(output_len);
+
+    for i in 0..output_len {
+        let src_idx = i as f32 * ratio;
+        let idx_floor = src_idx.floor() as usize;
+        let idx_ceil = (idx_floor + 1).min(data.len() - 1);
+        let frac = src_idx - idx_floor as f32;
+
+        let sample = if idx_floor < data.len() {
+            data[idx_floor] * (1.0 - frac) + data[idx_ceil] * frac
+        } else {
+            0.0
+        };
+        resampled.push(sample);
+    }
+
+    resampled
+}
+
+/// Split points for parallel decoding: windows of about WINDOW_MS, each cut at the
+/// quietest 100ms frame in its last few seconds so words aren't split across windows
+fn window_bounds(samples: &[i16], workers: usize) -> Vec<(usize, usize)> {
+    let window = (WINDOW_MS * VOSK_SAMPLE_RATE as u64 / 1000) as usize;
+    if workers <= 1 || samples.len() <= window {
+        return vec![(0, samples.len())];
+    }
+    let frame = VOSK_SAMPLE_RATE as usize / 10;
+    let search = (CUT_SEARCH_MS * VOSK_SAMPLE_RATE as u64 / 1000) as usize;
+    let mut bounds = Vec::new();
+    let mut start = 0;
+    while samples.len() - start > window {
+        let target = start + window;
+        let cut = (target - search..target)
+            .step_by(frame)
+            .min_by_key(|&at| samples[at..at + frame].iter().map(|&s| (s as i64).abs()).sum::<i64>())
+            .unwrap_or(target);
+        bounds.push((start, cut));
+        start = cut;
+    }
+    bounds.push((start, samples.len()));
+    bounds
+}
+
+/// Transcribe a WAV file, decoding its windows in parallel over the recognizer pool
+pub fn transcribe_wav_file(pool: &RecognizerPool, path: &str) -> Result<FileTranscript> {
+    let start = Instant::now();
+    let samples = load_wav_for_vosk(path)?;
+    let duration_ms = samples.len() as u64 * 1000 / VOSK_SAMPLE_RATE as u64;
+
+    // Every window is its own source; all are queued before any reply is awaited
+    let windows = window_bounds(&samples, pool.size());
+    let replies: Vec<_> = windows.iter().enumerate().map(|(index, &(from, to))| {
+        let source = format!("{}#{}", path, index);
+        let fed = pool.feed(&source, samples[from..to].to_vec());
+        (from, fed, pool.finish(&source))
+    }).collect();
+
+    let mut segments = Vec::new();
+    for (from, fed, finished) in replies {
+        let offset_ms = from as u64 * 1000 / VOSK_SAMPLE_RATE as u64;
+        let mut decoded = recognizer_pool::wait(fed)?;
+        decoded.extend(recognizer_pool::wait(finished)?);
+        segments.extend(decoded.into_iter().map(|d| FileTranscriptSegment {
+            text: d.text,
+            start_ms: d.start_ms + offset_ms,
+            end_ms: d.end_ms + offset_ms,
+        }));
+    }
+
+    let processing_time_ms = start.elapsed().as_millis() as u64;
+    info!("✅ LED 8102: Transcribed {} ({}ms audio, {} windows on {} workers) into {} segments in {}ms",
+        path, duration_ms, windows.len(), pool.size(), segments.len(), processing_time_ms);
+
+    Ok(FileTranscript {
+        file_path: path.to_string(),
+        duration_ms,
+        processing_time_ms,
+        segments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_cover_the_file_and_cut_in_silence() {
+        let rate = VOSK_SAMPLE_RATE as usize;
+        // 70 s of "speech" with a 200 ms gap at 28 s
+        let mut samples = vec![1000i16; 70 * rate];
+        samples[28 * rate..28 * rate + rate / 5].iter_mut().for_each(|s| *s = 0);
+
+        let bounds = window_bounds(&samples, 4);
+        assert_eq!(bounds.first().unwrap().0, 0);
+        assert_eq!(bounds.last().unwrap().1, samples.len());
+        assert!(bounds.windows(2).all(|w| w[0].1 == w[1].0));
+        assert_eq!(bounds[0].1, 28 * rate);
+        assert_eq!(window_bounds(&samples, 1), vec![(0, samples.len())]);
+    }
+}
@@ -0,0 +1,231 @@
+// Hardware Profile - default Vosk model and decoder threads from the machine
+// Detected once per run: SIMD support (AVX2/AVX-512 on x86, NEON on ARM), logical
+// cores and RAM. The large model is the default only where it runs comfortably -
+// vector instructions, enough cores to decode in real time and enough memory to hold
+// it - and the small model otherwise, with the reasons recorded so get_engine_health
+// can explain the choice. The recognizer pool gets one thread per core (one kept for
+// audio capture), capped by what the CPU's vector width makes worthwhile. A calibrated
+// model or a configured pool size still take precedence.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use once_cell::sync::Lazy;
+use log::info;
+
+// The large English model needs about this much memory, plus headroom for the app
+const LARGE_MODEL_TOTAL_MB: u64 = 8 * 1024;
+const LARGE_MODEL_AVAILABLE_MB: u64 = 4 * 1024;
+const LARGE_MODEL_MIN_CORES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelSize {
+    Small,
+    Large,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct HardwareProfile {
+    pub arch: String,
+    pub cpu_features: Vec<String>,          // Of interest to the decoder: avx2, avx512f, neon
+    pub logical_cores: usize,
+    pub total_memory_mb: Option<u64>,       // None where it can't be read
+    pub available_memory_mb: Option<u64>,
+}
+
+impl HardwareProfile {
+    fn has(&self, feature: &str) -> bool {
+        self.cpu_features.iter().any(|f| f == feature)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ModelDecision {
+    pub model_size: ModelSize,
+    pub recognizer_threads: usize,
+    pub rationale: Vec<String>,
+}
+
+// Reported by get_engine_health
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct EngineHealth {
+    pub hardware: HardwareProfile,
+    pub decision: ModelDecision,
+    pub model_path: String,          // Model of the next stream
+    pub model_loaded: bool,          // Preloaded at startup
+    pub memory_fallback: bool,       // Switched to the small model under memory pressure
+}
+
+static DETECTED: Lazy<(HardwareProfile, ModelDecision)> = Lazy::new(|| {
+    let profile = detect();
+    let decision = decide(&profile);
+    info!("🖥️ {} cores, features {:?}, {:?} MB RAM: {:?} model, {} recognizer threads",
+        profile.logical_cores, profile.cpu_features, profile.total_memory_mb, decision.model_size, decision.recognizer_threads);
+    (profile, decision)
+});
+
+#[allow(unused_mut)]
+fn cpu_features() -> Vec<String> {
+    let mut features: Vec<&str> = Vec::new();
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            features.push("avx2");
+        }
+        if is_x86_feature_detected!("avx512f") {
+            features.push("avx512f");
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            features.push("neon");
+        }
+    }
+    features.into_iter().map(str::to_string).collect()
+}
+
+/// (total, available) physical memory in MB
+#[cfg(target_os = "linux")]
+fn memory_mb() -> (Option<u64>, Option<u64>) {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+    let field = |name: &str| meminfo.lines()
+        .find(|l| l.starts_with(name))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb / 1024);
+    (field("MemTotal:"), field("MemAvailable:"))
+}
+
+#[cfg(windows)]
+fn memory_mb() -> (Option<u64>, Option<u64>) {
+    use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+    let mut status: MEMORYSTATUSEX = unsafe { std::mem::zeroed() };
+    status.dwLength = std::mem::size_of::<MEMORYSTATUSEX>() as u32;
+    if unsafe { GlobalMemoryStatusEx(&mut status) } == 0 {
+        return (None, None);
+    }
+    (Some(status.ullTotalPhys / (1024 * 1024)), Some(status.ullAvailPhys / (1024 * 1024)))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn memory_mb() -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+/// Resident set size of this process (Linux: /proc; elsewhere unavailable)
+pub fn process_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+fn detect() -> HardwareProfile {
+    let (total_memory_mb, available_memory_mb) = memory_mb();
+    HardwareProfile {
+        arch: std::env::consts::ARCH.to_string(),
+        cpu_features: cpu_features(),
+        logical_cores: std::thread::available_parallelism().map_or(1, |cores| cores.get()),
+        total_memory_mb,
+        available_memory_mb,
+    }
+}
+
+fn decide(profile: &HardwareProfile) -> ModelDecision {
+    let mut rationale = Vec::new();
+    let simd = profile.has("avx2") || profile.has("avx512f") || profile.has("neon");
+    let mut large = true;
+
+    if simd {
+        rationale.push(format!("Vector instructions available ({})", profile.cpu_features.join(", ")));
+    } else {
+        rationale.push("No AVX2/AVX-512/NEON: the large model would decode too slowly".to_string());
+        large = false;
+    }
+    if profile.logical_cores < LARGE_MODEL_MIN_CORES {
+        rationale.push(format!("{} cores (the large model needs {}+ to keep up)", profile.logical_cores, LARGE_MODEL_MIN_CORES));
+        large = false;
+    }
+    match (profile.total_memory_mb, profile.available_memory_mb) {
+        (None, _) => {
+            rationale.push("Memory size unknown: staying with the small model".to_string());
+            large = false;
+        }
+        (Some(total), _) if total < LARGE_MODEL_TOTAL_MB => {
+            rationale.push(format!("{} MB RAM (the large model needs {} MB)", total, LARGE_MODEL_TOTAL_MB));
+            large = false;
+        }
+        (Some(_), Some(available)) if available < LARGE_MODEL_AVAILABLE_MB => {
+            rationale.push(format!("Only {} MB RAM free (the large model needs {} MB)", available, LARGE_MODEL_AVAILABLE_MB));
+            large = false;
+        }
+        (Some(total), _) => rationale.push(format!("{} MB RAM", total)),
+    }
+
+    let cap = if profile.has("avx512f") { 8 } else if simd { 4 } else { 2 };
+    let recognizer_threads = profile.logical_cores.saturating_sub(1).clamp(1, cap);
+    rationale.push(format!("{} recognizer threads ({} cores, one left for audio, at most {} for this CPU)",
+        recognizer_threads, profile.logical_cores, cap));
+
+    ModelDecision { model_size: if large { ModelSize::Large } else { ModelSize::Small }, recognizer_threads, rationale }
+}
+
+/// This machine's profile
+pub fn profile() -> HardwareProfile {
+    DETECTED.0.clone()
+}
+
+/// Default model and threads for this machine
+pub fn decision() -> ModelDecision {
+    DETECTED.1.clone()
+}
+
+/// Whether the large model is the default here
+pub fn large_model_suitable() -> bool {
+    DETECTED.1.model_size == ModelSize::Large
+}
+
+/// Default model of the configured ones: the large model where suitable, else the small one
+pub fn default_model_path(large: &str, small: &str) -> Option<String> {
+    if large_model_suitable() && !large.is_empty() && Path::new(large).exists() {
+        Some(large.to_string())
+    } else if !small.is_empty() && Path::new(small).exists() {
+        Some(small.to_string())
+    } else if !large.is_empty() && Path::new(large).exists() {
+        Some(large.to_string())  // The only model installed
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(features: &[&str], cores: usize, total: Option<u64>, available: Option<u64>) -> HardwareProfile {
+        HardwareProfile {
+            arch: "x86_64".to_string(),
+            cpu_features: features.iter().map(|f| f.to_string()).collect(),
+            logical_cores: cores,
+            total_memory_mb: total,
+            available_memory_mb: available,
+        }
+    }
+
+    #[test]
+    fn test_large_model_needs_simd_cores_and_memory() {
+        let workstation = decide(&profile(&["avx2", "avx512f"], 16, Some(32_768), Some(20_000)));
+        assert_eq!((workstation.model_size, workstation.recognizer_threads), (ModelSize::Large, 8));
+
+        let laptop = decide(&profile(&["avx2"], 8, Some(8_192), Some(2_048)));
+        assert_eq!((laptop.model_size, laptop.recognizer_threads), (ModelSize::Small, 4));
+        assert!(laptop.rationale.iter().any(|r| r.contains("2048 MB RAM free")));
+
+        let old = decide(&profile(&[], 2, Some(4_096), None));
+        assert_eq!((old.model_size, old.recognizer_threads), (ModelSize::Small, 1));
+        assert!(old.rationale[0].starts_with("No AVX2"));
+
+        assert_eq!(decide(&profile(&["neon"], 8, None, None)).model_size, ModelSize::Small);
+    }
+}
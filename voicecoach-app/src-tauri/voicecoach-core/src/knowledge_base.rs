@@ -0,0 +1,809 @@
+// Knowledge Base Management Module
+// Handles document upload, processing, chunking, and storage for RAG system
+//
+// Documents can be added mid-call without stalling coaching retrieval. Queries run
+// against a snapshot of the index (a shared, immutable document list) and hold no lock
+// while they search. Writes are parsed and chunked up front, queued, and applied in a
+// batch: the writer that flushes the queue applies every queued write to a copy of the
+// index, publishes it with a pointer swap and saves it once. A query therefore sees a
+// batch entirely or not at all, and a batch lands between queries rather than under one.
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use log::{info, warn, error};
+use chrono::Utc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct KnowledgeDocument {
+    pub filename: String,
+    pub content: String,
+    pub chunks: Vec<String>,
+    pub timestamp: i64,
+    #[serde(rename = "type")]
+    pub doc_type: Option<String>,
+    #[serde(rename = "isAIGenerated")]
+    pub is_ai_generated: bool,
+    #[serde(rename = "sourceUrl", default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+}
+
+// Document type of call scripts / legal disclosures the rep reads verbatim. They are
+// only used to detect read-aloud sections and are never used to ground coaching.
+pub const SCRIPT_DOC_TYPE: &str = "script";
+
+// Document type of competitor battlecards (competitor_watch looks them up by name)
+pub const BATTLECARD_DOC_TYPE: &str = "battlecard";
+
+impl KnowledgeDocument {
+    pub fn is_script(&self) -> bool {
+        self.doc_type.as_deref() == Some(SCRIPT_DOC_TYPE)
+    }
+}
+
+// Where a RAG passage came from - attached to coaching suggestions so the UI
+// can link back to the source passage in the playbook
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct KnowledgeCitation {
+    pub document: String,
+    pub section: Option<String>,
+    pub chunk_index: usize,
+    pub similarity: f32,
+    pub excerpt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+}
+
+// Full passage returned for citation click-through
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct KnowledgePassage {
+    pub document: String,
+    pub section: Option<String>,
+    pub chunk_index: usize,
+    pub text: String,
+    pub source_url: Option<String>,
+}
+
+const CITATION_EXCERPT_CHARS: usize = 160;
+
+#[derive(Debug, Serialize, Deserialize, specta::Type)]
+pub struct ProcessingStats {
+    pub total_documents: usize,
+    pub total_chunks: usize,
+    pub processing_time_ms: u64,
+    pub success_rate: f32,
+    pub knowledge_base_size: usize,
+}
+
+// Also what the integration script's "stats" command prints; it leaves out what it can't tell
+#[derive(Debug, Default, Serialize, Deserialize, specta::Type)]
+#[serde(default)]
+pub struct KnowledgeBaseStats {
+    pub total_documents: usize,
+    pub total_chunks: usize,
+    pub collection_size: usize,
+    pub last_updated: String,
+    pub health_status: String,
+}
+
+// Cheap to clone: documents are shared until the clone is modified
+#[derive(Clone)]
+pub struct KnowledgeBaseManager {
+    storage_path: PathBuf,
+    knowledge_base: Arc<Vec<KnowledgeDocument>>,
+    max_chunk_size: usize,
+}
+
+impl KnowledgeBaseManager {
+    /// Open the knowledge base kept in `data_dir` (the app's data directory)
+    pub fn new(data_dir: &Path) -> Result<Self> {
+        // Create storage directory in app data
+        let storage_path = data_dir.join("voicecoach_knowledge");
+        
+        // Ensure directory exists
+        fs::create_dir_all(&storage_path)?;
+        
+        info!("📁 LED 7001: Knowledge base storage initialized at {:?}", storage_path);
+        
+        let mut manager = Self {
+            storage_path: storage_path.clone(),
+            knowledge_base: Arc::default(),
+            max_chunk_size: 8000, // Conservative chunk size for Ollama
+        };
+        
+        // Load existing knowledge base
+        manager.load_from_disk()?;
+        
+        Ok(manager)
+    }
+    
+    /// Load knowledge base from disk
+    fn load_from_disk(&mut self) -> Result<()> {
+        let kb_file = self.storage_path.join("knowledge_base.json");
+        
+        if kb_file.exists() {
+            info!("📖 LED 7002: Loading existing knowledge base from disk");
+            let contents = fs::read_to_string(&kb_file)?;
+            self.knowledge_base = Arc::new(serde_json::from_str(&contents)?);
+            info!("✅ LED 7003: Loaded {} documents from disk", self.knowledge_base.len());
+        } else {
+            info!("📝 LED 7004: No existing knowledge base found, starting fresh");
+        }
+        
+        Ok(())
+    }
+    
+    /// Save knowledge base to disk
+    pub fn save_to_disk(&self) -> Result<()> {
+        let kb_file = self.storage_path.join("knowledge_base.json");
+        
+        info!("💾 LED 7010: Saving knowledge base to disk");
+        let json = serde_json::to_string_pretty(&*self.knowledge_base)?;
+        fs::write(&kb_file, json)?;
+        info!("✅ LED 7011: Saved {} documents to disk", self.knowledge_base.len());
+        
+        Ok(())
+    }
+    
+    /// Process a single document file
+    pub fn process_document_file(&self, file_path: &str) -> Result<KnowledgeDocument> {
+        info!("📄 LED 7020: Processing document: {}", file_path);
+        
+        let path = Path::new(file_path);
+        let filename = path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown.txt")
+            .to_string();
+        
+        // Read file content
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read file: {}", file_path))?;
+        
+        info!("📊 LED 7021: Document size: {} chars", content.len());
+        
+        // Create chunks
+        let chunks = self.create_intelligent_chunks(&content);
+        info!("✂️ LED 7022: Created {} chunks", chunks.len());
+        
+        let document = KnowledgeDocument {
+            filename,
+            content,
+            chunks,
+            timestamp: Utc::now().timestamp(),
+            doc_type: Some("user_upload".to_string()),
+            is_ai_generated: false,
+            source_url: None,
+        };
+        
+        Ok(document)
+    }
+    
+    /// Process multiple files from a directory and save (the CLI; the app batches
+    /// read_directory's documents instead)
+    pub fn process_directory(&mut self, dir_path: &str, recursive: bool) -> Result<ProcessingStats> {
+        let start_time = std::time::Instant::now();
+        let (documents, total_files) = self.read_directory(dir_path, recursive)?;
+        let total_chunks = documents.iter().map(|d| d.chunks.len()).sum();
+        let total_documents = documents.len();
+        
+        for doc in documents {
+            self.add_document(doc)?;
+        }
+        
+        // Save to disk after processing
+        self.save_to_disk()?;
+        
+        Ok(processing_stats(total_documents, total_chunks, total_files, start_time, self.knowledge_base.len()))
+    }
+    
+    /// Read and chunk every document in a directory without adding them;
+    /// returns the documents and the number of files found
+    pub fn read_directory(&self, dir_path: &str, recursive: bool) -> Result<(Vec<KnowledgeDocument>, usize)> {
+        info!("📁 LED 7030: Processing directory: {} (recursive: {})", dir_path, recursive);
+        
+        // Collect all files to process
+        let files = self.collect_files(dir_path, recursive)?;
+        let total_files = files.len();
+        
+        info!("📋 LED 7031: Found {} files to process", total_files);
+        
+        let mut documents = Vec::new();
+        for file_path in files {
+            match self.process_document_file(&file_path) {
+                Ok(doc) => documents.push(doc),
+                Err(e) => {
+                    error!("❌ LED 7032: Failed to process {}: {}", file_path, e);
+                }
+            }
+        }
+        
+        Ok((documents, total_files))
+    }
+    
+    /// Collect files from directory
+    fn collect_files(&self, dir_path: &str, recursive: bool) -> Result<Vec<String>> {
+        let mut files = Vec::new();
+        let path = Path::new(dir_path);
+        
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Directory does not exist: {}", dir_path));
+        }
+        
+        if path.is_file() {
+            files.push(dir_path.to_string());
+            return Ok(files);
+        }
+        
+        // Supported file extensions
+        let extensions = vec!["txt", "md", "pdf", "docx", "json"];
+        
+        if recursive {
+            self.collect_files_recursive(path, &extensions, &mut files)?;
+        } else {
+            for entry in fs::read_dir(path)? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.is_file() {
+                    if let Some(ext) = path.extension() {
+                        if extensions.contains(&ext.to_str().unwrap_or("")) {
+                            files.push(path.to_str().unwrap_or("").to_string());
+                        }
+                    }
+                }
+            }
+        }
+        
+        Ok(files)
+    }
+    
+    /// Recursively collect files
+    fn collect_files_recursive(&self, dir: &Path, extensions: &[&str], files: &mut Vec<String>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            
+            if path.is_dir() {
+                self.collect_files_recursive(&path, extensions, files)?;
+            } else if path.is_file() {
+                if let Some(ext) = path.extension() {
+                    if extensions.contains(&ext.to_str().unwrap_or("")) {
+                        files.push(path.to_str().unwrap_or("").to_string());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Create intelligent chunks from document content
+    pub fn create_intelligent_chunks(&self, content: &str) -> Vec<String> {
+        let mut chunks = Vec::new();
+        
+        if content.len() <= self.max_chunk_size {
+            // Document fits in single chunk
+            chunks.push(content.to_string());
+            return chunks;
+        }
+        
+        // Split into chunks at natural boundaries
+        let mut start_index = 0;
+        
+        while start_index < content.len() {
+            let mut end_index = std::cmp::min(start_index + self.max_chunk_size, content.len());
+            
+            // If not at end, find good break point
+            if end_index < content.len() {
+                // Try to break at sentence
+                if let Some(pos) = content[start_index..end_index].rfind(". ") {
+                    end_index = start_index + pos + 1;
+                }
+                // Otherwise try paragraph
+                else if let Some(pos) = content[start_index..end_index].rfind("\n\n") {
+                    end_index = start_index + pos + 2;
+                }
+                // Otherwise break at any whitespace
+                else if let Some(pos) = content[start_index..end_index].rfind(' ') {
+                    end_index = start_index + pos;
+                }
+            }
+            
+            let chunk = content[start_index..end_index].trim().to_string();
+            if chunk.len() > 100 {  // Only add substantial chunks
+                chunks.push(chunk);
+            }
+            
+            start_index = end_index;
+        }
+        
+        chunks
+    }
+    
+    /// Add document to knowledge base
+    pub fn add_document(&mut self, document: KnowledgeDocument) -> Result<()> {
+        info!("➕ LED 7040: Adding document {} to knowledge base", document.filename);
+        
+        // Hard cap on the in-memory index (a replaced document frees its own size)
+        let replaced: usize = self.knowledge_base.iter()
+            .filter(|d| d.filename == document.filename)
+            .map(document_bytes)
+            .sum();
+        let limit = INDEX_BYTE_LIMIT.load(Ordering::Relaxed);
+        let required = self.memory_bytes() - replaced + document_bytes(&document);
+        if limit > 0 && required > limit {
+            return Err(anyhow::anyhow!(
+                "Knowledge base memory budget exceeded ({} MB needed, {} MB allowed); remove documents or raise the budget",
+                required / (1024 * 1024), limit / (1024 * 1024)));
+        }
+        
+        // Remove existing document with same filename if it exists
+        let documents = Arc::make_mut(&mut self.knowledge_base);
+        documents.retain(|d| d.filename != document.filename);
+        
+        // Add new document
+        documents.push(document);
+        
+        Ok(())
+    }
+    
+    /// Search knowledge base for relevant content
+    pub fn search(&self, query: &str, max_results: usize) -> Vec<(String, f32)> {
+        info!("🔍 LED 7050: Searching knowledge base for: {}", query);
+        
+        let query_lower = query.to_lowercase();
+        let mut results = Vec::new();
+        
+        for doc in self.knowledge_base.iter().filter(|d| !d.is_script()) {
+            for chunk in &doc.chunks {
+                let chunk_lower = chunk.to_lowercase();
+                
+                // Simple keyword matching (can be enhanced with embeddings)
+                let score = self.calculate_relevance_score(&query_lower, &chunk_lower);
+                
+                if score > 0.1 {
+                    results.push((chunk.clone(), score));
+                }
+            }
+        }
+        
+        // Sort by score descending
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        
+        // Return top N results
+        results.truncate(max_results);
+        
+        info!("✅ LED 7051: Found {} relevant results", results.len());
+        results
+    }
+    
+    /// Search returning provenance (document, section, similarity) for each hit
+    pub fn search_with_citations(&self, query: &str, max_results: usize) -> Vec<(String, KnowledgeCitation)> {
+        let query_lower = query.to_lowercase();
+        let mut results = Vec::new();
+        
+        for doc in self.knowledge_base.iter().filter(|d| !d.is_script()) {
+            for (index, chunk) in doc.chunks.iter().enumerate() {
+                let score = self.calculate_relevance_score(&query_lower, &chunk.to_lowercase());
+                if score > 0.1 {
+                    results.push((chunk.clone(), citation_for(&doc.filename, &doc.content, doc.source_url.clone(), index, chunk, score)));
+                }
+            }
+        }
+        
+        results.sort_by(|a, b| b.1.similarity.partial_cmp(&a.1.similarity).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(max_results);
+        results
+    }
+    
+    /// Look up one chunk of a document (citation click-through)
+    pub fn get_passage(&self, document: &str, chunk_index: usize) -> Option<KnowledgePassage> {
+        let doc = self.knowledge_base.iter().find(|d| d.filename == document)?;
+        let chunk = doc.chunks.get(chunk_index)?;
+        Some(KnowledgePassage {
+            document: doc.filename.clone(),
+            section: section_heading(&doc.content, chunk),
+            chunk_index,
+            text: chunk.clone(),
+            source_url: doc.source_url.clone(),
+        })
+    }
+    
+    /// Calculate simple relevance score
+    fn calculate_relevance_score(&self, query: &str, text: &str) -> f32 {
+        let query_words: Vec<&str> = query.split_whitespace().collect();
+        let mut matches = 0;
+        
+        for word in &query_words {
+            if text.contains(word) {
+                matches += 1;
+            }
+        }
+        
+        if query_words.is_empty() {
+            return 0.0;
+        }
+        
+        matches as f32 / query_words.len() as f32
+    }
+    
+    /// Get knowledge base statistics
+    pub fn get_stats(&self) -> KnowledgeBaseStats {
+        let total_chunks: usize = self.knowledge_base.iter()
+            .map(|d| d.chunks.len())
+            .sum();
+        
+        let collection_size: usize = self.knowledge_base.iter()
+            .map(|d| d.content.len())
+            .sum();
+        
+        let last_updated = if let Some(latest) = self.knowledge_base.iter()
+            .max_by_key(|d| d.timestamp) {
+            chrono::DateTime::from_timestamp(latest.timestamp, 0)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_else(|| "Unknown".to_string())
+        } else {
+            "Never".to_string()
+        };
+        
+        KnowledgeBaseStats {
+            total_documents: self.knowledge_base.len(),
+            total_chunks,
+            collection_size,
+            last_updated,
+            health_status: "healthy".to_string(),
+        }
+    }
+    
+    /// Get all documents
+    pub fn get_documents(&self) -> &Vec<KnowledgeDocument> {
+        &self.knowledge_base
+    }
+    
+    /// Approximate heap size of the loaded index
+    pub fn memory_bytes(&self) -> usize {
+        self.knowledge_base.iter().map(document_bytes).sum()
+    }
+    
+    /// Clear knowledge base (not saved; callers persist like after add_document)
+    pub fn clear(&mut self) {
+        info!("🗑️ LED 7060: Clearing knowledge base");
+        self.knowledge_base = Arc::default();
+    }
+    
+    /// Remove document by filename (not saved; callers persist like after add_document)
+    pub fn remove_document(&mut self, filename: &str) -> bool {
+        info!("🗑️ LED 7061: Removing document: {}", filename);
+        
+        let removed = self.knowledge_base.iter().any(|d| d.filename == filename);
+        if removed {
+            Arc::make_mut(&mut self.knowledge_base).retain(|d| d.filename != filename);
+            info!("✅ LED 7062: Document removed successfully");
+        } else {
+            warn!("⚠️ LED 7063: Document not found: {}", filename);
+        }
+        
+        removed
+    }
+}
+
+pub fn processing_stats(total_documents: usize, total_chunks: usize, total_files: usize, start_time: std::time::Instant, knowledge_base_size: usize) -> ProcessingStats {
+    let processing_time = start_time.elapsed().as_millis() as u64;
+    let success_rate = if total_files > 0 {
+        total_documents as f32 / total_files as f32
+    } else {
+        1.0
+    };
+    
+    info!("✅ LED 7033: Processing complete. {} documents, {} chunks in {}ms", 
+          total_documents, total_chunks, processing_time);
+    
+    ProcessingStats {
+        total_documents,
+        total_chunks,
+        processing_time_ms: processing_time,
+        success_rate,
+        knowledge_base_size,
+    }
+}
+
+/// Nearest heading above `chunk` in the document: markdown "#" lines, or short
+/// title-like lines (no trailing punctuation) as produced by PDF/DOCX extraction
+pub fn section_heading(content: &str, chunk: &str) -> Option<String> {
+    let probe: String = chunk.chars().take(60).collect();
+    let position = content.find(probe.trim())?;
+    
+    content[..position].lines().rev()
+        .map(str::trim)
+        .find(|line| {
+            if line.starts_with('#') {
+                return true;
+            }
+            let words = line.split_whitespace().count();
+            (1..=8).contains(&words)
+                && line.len() <= 80
+                && !line.ends_with(['.', ',', ';', ':', '?', '!'])
+                && line.chars().next().map_or(false, |c| c.is_uppercase() || c.is_numeric())
+        })
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+}
+
+/// Build the citation for chunk `index` of a document
+pub fn citation_for(document: &str, content: &str, source_url: Option<String>, index: usize, chunk: &str, similarity: f32) -> KnowledgeCitation {
+    let mut excerpt: String = chunk.chars().take(CITATION_EXCERPT_CHARS).collect();
+    if chunk.chars().count() > CITATION_EXCERPT_CHARS {
+        excerpt.push('…');
+    }
+    KnowledgeCitation {
+        document: document.to_string(),
+        section: section_heading(content, chunk),
+        chunk_index: index,
+        similarity,
+        excerpt,
+        source_url,
+    }
+}
+
+fn document_bytes(document: &KnowledgeDocument) -> usize {
+    document.filename.len() + document.content.len() + document.chunks.iter().map(|c| c.len()).sum::<usize>()
+}
+
+// Global knowledge base instance
+use std::sync::{mpsc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use once_cell::sync::Lazy;
+
+// The published index; held only long enough to clone or swap it
+static KNOWLEDGE_BASE: Lazy<Mutex<Option<KnowledgeBaseManager>>> = Lazy::new(|| {
+    Mutex::new(None)
+});
+// Writes waiting for the next batch
+static PENDING_WRITES: Lazy<Mutex<Vec<QueuedWrite>>> = Lazy::new(|| Mutex::new(Vec::new()));
+// Held while a batch is applied and saved, so batches build on each other
+static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+// Set when memory pressure unloaded the index; the next access reloads it from disk
+static INDEX_UNLOADED: AtomicBool = AtomicBool::new(false);
+// Hard cap on the in-memory index in bytes (0 = unlimited, e.g. the CLI)
+static INDEX_BYTE_LIMIT: AtomicUsize = AtomicUsize::new(0);
+// Data directory the index was opened from, to reload it after an unload
+static DATA_DIR: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
+
+/// A change to the index, applied in the next batch
+pub enum KnowledgeWrite {
+    Add(KnowledgeDocument),
+    Remove(String),
+    Clear,
+}
+
+struct QueuedWrite {
+    write: KnowledgeWrite,
+    // Whether the write changed anything, or why it was refused
+    done: mpsc::Sender<Result<bool, String>>,
+}
+
+/// Set the index size cap enforced when documents are added
+pub fn set_index_limit(bytes: usize) {
+    INDEX_BYTE_LIMIT.store(bytes, Ordering::Relaxed);
+}
+
+/// Initialize knowledge base manager from the app's data directory
+pub fn initialize_knowledge_base(data_dir: &Path) -> Result<()> {
+    let manager = KnowledgeBaseManager::new(data_dir)?;
+    *DATA_DIR.lock().unwrap() = Some(data_dir.to_path_buf());
+    let mut kb = KNOWLEDGE_BASE.lock().unwrap();
+    *kb = Some(manager);
+    Ok(())
+}
+
+/// Get knowledge base manager instance
+fn get_knowledge_base() -> Result<std::sync::MutexGuard<'static, Option<KnowledgeBaseManager>>> {
+    let mut kb = KNOWLEDGE_BASE.lock().unwrap();
+    if kb.is_none() && INDEX_UNLOADED.swap(false, Ordering::SeqCst) {
+        info!("📖 LED 7120: Reloading knowledge index unloaded under memory pressure");
+        let data_dir = DATA_DIR.lock().unwrap().clone()
+            .ok_or_else(|| anyhow::anyhow!("Knowledge base not initialized"))?;
+        *kb = Some(KnowledgeBaseManager::new(&data_dir)?);
+    }
+    Ok(kb)
+}
+
+/// The index as currently published; queries run on it without holding any lock
+pub fn snapshot() -> Result<KnowledgeBaseManager> {
+    get_knowledge_base()?.clone().ok_or_else(|| anyhow::anyhow!("Knowledge base not initialized"))
+}
+
+/// Apply writes to the index in order, returning each one's result
+fn apply_writes(manager: &mut KnowledgeBaseManager, writes: Vec<KnowledgeWrite>) -> Vec<Result<bool, String>> {
+    writes.into_iter().map(|write| match write {
+        KnowledgeWrite::Add(document) => manager.add_document(document).map(|_| true).map_err(|e| e.to_string()),
+        KnowledgeWrite::Remove(filename) => Ok(manager.remove_document(&filename)),
+        KnowledgeWrite::Clear => {
+            manager.clear();
+            Ok(true)
+        }
+    }).collect()
+}
+
+/// Apply every queued write as one batch: a copy of the index is changed, published
+/// with a swap and saved once. Queries keep the snapshot they started with.
+fn flush_writes() -> Result<()> {
+    let _writer = WRITE_LOCK.lock().unwrap();
+    let queued: Vec<QueuedWrite> = std::mem::take(&mut *PENDING_WRITES.lock().unwrap());
+    if queued.is_empty() {
+        // An earlier flush already applied them
+        return Ok(());
+    }
+    
+    let mut manager = snapshot()?;
+    let (writes, senders): (Vec<_>, Vec<_>) = queued.into_iter().map(|q| (q.write, q.done)).unzip();
+    info!("📦 LED 7130: Applying batch of {} knowledge base writes", writes.len());
+    let mut results = apply_writes(&mut manager, writes);
+    
+    *KNOWLEDGE_BASE.lock().unwrap() = Some(manager.clone());
+    if let Err(e) = manager.save_to_disk() {
+        error!("❌ LED 7131: Knowledge base batch applied but not saved: {}", e);
+        for result in results.iter_mut().filter(|r| r.is_ok()) {
+            *result = Err(format!("Failed to save knowledge base: {}", e));
+        }
+    }
+    for (done, result) in senders.into_iter().zip(results) {
+        let _ = done.send(result);
+    }
+    Ok(())
+}
+
+/// Queue writes, flush them (with whatever else is queued) and wait for their results
+pub fn submit(writes: Vec<KnowledgeWrite>) -> Result<Vec<Result<bool, String>>> {
+    let receivers: Vec<mpsc::Receiver<Result<bool, String>>> = {
+        let mut pending = PENDING_WRITES.lock().unwrap();
+        writes.into_iter().map(|write| {
+            let (done, receiver) = mpsc::channel();
+            pending.push(QueuedWrite { write, done });
+            receiver
+        }).collect()
+    };
+    flush_writes()?;
+    Ok(receivers.into_iter()
+        .map(|r| r.recv().unwrap_or_else(|_| Err("Knowledge base write was dropped".to_string())))
+        .collect())
+}
+
+/// Submit a single write
+pub fn submit_one(write: KnowledgeWrite) -> Result<bool> {
+    submit(vec![write])?.pop()
+        .unwrap_or_else(|| Err("Knowledge base write was dropped".to_string()))
+        .map_err(|e| anyhow::anyhow!(e))
+}
+
+/// In-memory size of the knowledge index (0 while unloaded)
+pub fn index_bytes() -> usize {
+    KNOWLEDGE_BASE.lock().unwrap().as_ref().map_or(0, |m| m.memory_bytes())
+}
+
+/// Persist and drop the in-memory index (memory pressure); false if nothing was unloaded
+pub fn unload_index() -> bool {
+    let _writer = WRITE_LOCK.lock().unwrap();
+    let mut kb = KNOWLEDGE_BASE.lock().unwrap();
+    match kb.as_ref() {
+        Some(manager) if manager.memory_bytes() > 0 => {
+            if let Err(e) = manager.save_to_disk() {
+                error!("❌ LED 7121: Keeping knowledge index loaded, save failed: {}", e);
+                return false;
+            }
+            *kb = None;
+            INDEX_UNLOADED.store(true, Ordering::SeqCst);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Chunk and store a fetched web page, replacing any earlier copy of the same URL
+pub fn add_web_document(url: &str, title: &str, content: String) -> Result<KnowledgeDocument> {
+    let chunks = snapshot()?.create_intelligent_chunks(&content);
+    info!("🌐 LED 7110: Indexed {} ({} chunks) from {}", title, chunks.len(), url);
+    
+    let document = KnowledgeDocument {
+        filename: url.to_string(),
+        content,
+        chunks,
+        timestamp: Utc::now().timestamp(),
+        doc_type: Some("web_page".to_string()),
+        is_ai_generated: false,
+        source_url: Some(url.to_string()),
+    };
+    
+    submit_one(KnowledgeWrite::Add(document.clone()))?;
+    Ok(document)
+}
+
+/// (filename, content) of every script document, for read-aloud detection
+pub fn script_documents() -> Vec<(String, String)> {
+    match snapshot() {
+        Ok(manager) => manager.get_documents().iter()
+            .filter(|d| d.is_script())
+            .map(|d| (d.filename.clone(), d.content.clone()))
+            .collect(),
+        Err(e) => {
+            warn!("⚠️ Knowledge base unavailable for script matching: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// (filename, content) of the battlecard for a competitor: the named document when
+/// given, otherwise a battlecard document whose filename mentions the competitor
+pub fn battlecard(competitor: &str, filename: Option<&str>) -> Option<(String, String)> {
+    let manager = snapshot().map_err(|e| warn!("⚠️ Knowledge base unavailable for battlecards: {}", e)).ok()?;
+    let documents = manager.get_documents();
+    let name = competitor.to_lowercase();
+    let found = match filename {
+        Some(filename) => documents.iter().find(|d| d.filename == filename),
+        None => documents.iter()
+            .filter(|d| d.filename.to_lowercase().contains(&name))
+            .find(|d| d.doc_type.as_deref() == Some(BATTLECARD_DOC_TYPE) || d.filename.to_lowercase().contains("battlecard")),
+    };
+    found.map(|d| (d.filename.clone(), d.content.clone()))
+}
+
+/// Best-matching passages for a query with their citations (answer synthesis)
+pub fn search_passages(query: &str, max_results: usize) -> Result<Vec<(String, KnowledgeCitation)>> {
+    Ok(snapshot()?.search_with_citations(query, max_results))
+}
+
+/// Remove a previously ingested web page
+pub fn remove_web_document(url: &str) -> Result<bool> {
+    if get_knowledge_base()?.is_none() {
+        return Ok(false);
+    }
+    submit_one(KnowledgeWrite::Remove(url.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(filename: &str, content: &str) -> KnowledgeDocument {
+        KnowledgeDocument {
+            filename: filename.to_string(),
+            content: content.to_string(),
+            chunks: vec![content.to_string()],
+            timestamp: 0,
+            doc_type: None,
+            is_ai_generated: false,
+            source_url: None,
+        }
+    }
+
+    #[test]
+    fn test_batch_is_applied_to_a_copy_while_snapshots_keep_their_documents() {
+        let mut manager = KnowledgeBaseManager {
+            storage_path: std::env::temp_dir(),
+            knowledge_base: Arc::new(vec![document("pricing.md", "annual pricing discount")]),
+            max_chunk_size: 8000,
+        };
+        let query_snapshot = manager.clone();
+
+        let results = apply_writes(&mut manager, vec![
+            KnowledgeWrite::Add(document("acme-battlecard.md", "acme pricing is per seat")),
+            KnowledgeWrite::Remove("pricing.md".to_string()),
+            KnowledgeWrite::Remove("missing.md".to_string()),
+        ]);
+        assert_eq!(results, vec![Ok(true), Ok(true), Ok(false)]);
+
+        // The query that started before the batch still searches the old index
+        assert_eq!(query_snapshot.search("pricing", 5), vec![("annual pricing discount".to_string(), 1.0)]);
+        assert_eq!(manager.search("pricing", 5), vec![("acme pricing is per seat".to_string(), 1.0)]);
+
+        apply_writes(&mut manager, vec![KnowledgeWrite::Clear]);
+        assert!(manager.get_documents().is_empty());
+        assert_eq!(query_snapshot.get_documents().len(), 1);
+    }
+
+    #[test]
+    fn test_stats_script_output_may_leave_fields_out() {
+        let stats: KnowledgeBaseStats = serde_json::from_str(r#"{"total_documents": 3, "total_chunks": 41}"#).unwrap();
+        assert_eq!((stats.total_documents, stats.total_chunks, stats.collection_size), (3, 41, 0));
+        assert!(stats.last_updated.is_empty() && stats.health_status.is_empty());
+    }
+}
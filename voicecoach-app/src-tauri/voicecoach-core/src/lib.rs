@@ -0,0 +1,75 @@
+// VoiceCoach Core - the app's logic without the desktop shell
+// Audio streams and clocks, file transcription, the knowledge base (RAG), sales stage
+// and talk-over detection and the other pieces of analytics that don't need a window
+// live here, so they can be unit tested with plain `cargo test -p voicecoach-core`, and
+// shared by the Tauri app, the CLI and a headless server. Nothing in this crate depends
+// on Tauri: progress and results that the app shows as window events go to an
+// EventSink (events.rs), which the Tauri crate implements for its AppHandle, and
+// settings are passed in rather than read from the app's preferences. The Tauri crate
+// keeps the #[tauri::command] wrappers, window/dialog code and the modules that still
+// need an AppHandle; it re-exports these modules under their old paths.
+// The live capture engines run the same way: what they need from the app (devices,
+// the session store, coaching modules following the call) comes through CaptureHost.
+
+pub mod events;
+
+// Sample-counting capture clock that session times and event timestamps are kept on
+pub mod audio_clock;
+// Device sample formats converted to and from f32
+pub mod sample_format;
+// CPU/RAM-based default model and decoder threads
+pub mod hardware_profile;
+// Vosk recognizer pool (parallel decoding of file chunks)
+pub mod recognizer_pool;
+// File-based Vosk transcription
+pub mod file_transcription;
+// Document storage, chunking and search for RAG
+pub mod knowledge_base;
+// Name pseudonymization and contact redaction for shared transcripts
+pub mod pii_redaction;
+// Half-open detection for the cloud streaming connection
+pub mod ws_watchdog;
+// MP3/Opus sharing copies of call recordings
+pub mod recording_conversion;
+// Installed Vosk model layouts (static, pruned, quantized) and checked loading
+pub mod vosk_model;
+// RTF, memory and accuracy benchmark of an installed model
+pub mod model_benchmark;
+// Whole-word phrase matching for checklist, stage and objection cues
+pub mod phrases;
+// Conversation stage detection and stage vocabulary biasing
+pub mod sales_stage;
+// Rep-talks-over-prospect detection on the capture clock
+pub mod talk_over;
+// Timed transcript lines and words
+pub mod transcript;
+// The punctuated transcript as timed sentences
+pub mod sentences;
+// Log of the prospect's questions
+pub mod prospect_questions;
+// Per-word and per-line transcript confidence
+pub mod transcript_confidence;
+// Checklist, talk ratio and call metrics for the live call
+pub mod call_analytics;
+// Breadcrumb tracing of operations, for debugging
+pub mod breadcrumb_system;
+// Fault injection hooks (chaos feature)
+pub mod chaos;
+// Runtime bypass of single audio pipeline stages
+pub mod stage_bypass;
+// Audio callback timing stats
+pub mod pipeline_stats;
+// Debug tee of the audio fed to the engine
+pub mod audio_tap;
+// Capture-clock ordering of finals across engines
+pub mod transcript_sequencer;
+// Cloud silence skipping and usage accounting
+pub mod cloud_usage;
+// What the live capture engines need from the app
+pub mod capture_host;
+// Live Vosk transcription of the microphone, with warm standby
+pub mod vosk_transcription;
+// Live Deepgram transcription with keyword biasing and a results watchdog
+pub mod deepgram_transcription;
+// Legacy chunked transcription manager
+pub mod transcription_service;
@@ -0,0 +1,251 @@
+// Model Benchmark - speed, memory and accuracy of an installed Vosk model
+// Whether the large model is worth it depends on the machine, so any installed model
+// (small, large, pruned or quantized - see vosk_model.rs) can be measured here. The
+// model is loaded fresh and its memory footprint read as the growth of the process's
+// resident memory (where the platform reports it). Then a test set is decoded by one
+// recognizer fed 250 ms at a time, as the live stream feeds it: the real-time factor
+// is decode time over audio time (below 1 keeps up with a call), and accuracy is one
+// minus the word error rate against each clip's reference, case and punctuation
+// ignored. A test set is a folder of <name>.wav clips with <name>.txt references; the
+// app ships a small one as the benchmark resource and passes it in. Without a test
+// set, synthetic speech still measures speed and memory, and accuracy is left out.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use log::{info, warn};
+use vosk::{CompleteResult, DecodingState, Recognizer};
+
+use crate::events::EventSink;
+use crate::file_transcription::{self, VOSK_SAMPLE_RATE};
+use crate::vosk_model::{self, ModelLayout};
+
+const FEED_CHUNK_MS: u32 = 250;
+// Length of the synthetic clip used without a test set
+const SYNTHETIC_SECONDS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ClipBenchmark {
+    pub name: String,
+    pub audio_ms: u64,
+    pub decode_ms: u64,
+    pub real_time_factor: f32,
+    pub reference_words: Option<u32>,  // None for synthetic speech
+    pub word_errors: Option<u32>,
+    pub transcript: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ModelBenchmark {
+    pub layout: ModelLayout,
+    pub load_ms: u64,
+    pub memory_mb: Option<u64>,        // Resident memory the loaded model added
+    pub test_set: Option<String>,      // None: synthetic speech, no accuracy
+    pub audio_ms: u64,
+    pub decode_ms: u64,
+    pub real_time_factor: f32,
+    pub word_error_rate: Option<f32>,
+    pub accuracy: Option<f32>,
+    pub clips: Vec<ClipBenchmark>,
+    pub benchmarked_at: String,
+}
+
+// "model_benchmark_progress" payload
+#[derive(Serialize)]
+struct Progress<'a> {
+    model_path: &'a str,
+    clip: &'a str,
+    done: usize,
+    total: usize,
+}
+
+struct Clip {
+    name: String,
+    samples: Vec<i16>,
+    reference: Option<String>,
+}
+
+/// Speech-like test signal: voiced harmonics with syllable-rate envelope, short pauses and noise
+pub fn synthetic_speech(seconds: usize) -> Vec<i16> {
+    let total = seconds * VOSK_SAMPLE_RATE as usize;
+    let mut noise_state: u32 = 0x1234_5678;
+
+    (0..total)
+        .map(|i| {
+            let t = i as f32 / VOSK_SAMPLE_RATE as f32;
+            let pitch = 120.0 + 20.0 * (t * 1.3 * std::f32::consts::TAU).sin();
+            let voiced: f32 = (1..=8)
+                .map(|h| (t * pitch * h as f32 * std::f32::consts::TAU).sin() / h as f32)
+                .sum();
+            // ~4 syllables per second, with a pause every 1.5s
+            let syllable = (t * 4.0 * std::f32::consts::PI).sin().abs();
+            let in_pause = (t % 1.5) > 1.2;
+            let envelope = if in_pause { 0.0 } else { syllable };
+
+            noise_state = noise_state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let noise = (noise_state >> 16) as f32 / 65536.0 - 0.5;
+
+            let sample = 0.25 * envelope * voiced + 0.01 * noise;
+            (sample.clamp(-1.0, 1.0) * 32767.0) as i16
+        })
+        .collect()
+}
+
+/// Words compared for accuracy: lowercase, without punctuation
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.chars().filter(|c| c.is_alphanumeric() || *c == '\'').collect::<String>().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// (word errors, reference words): substitutions, insertions and deletions that turn
+/// the reference into the hypothesis
+pub fn word_errors(reference: &str, hypothesis: &str) -> (usize, usize) {
+    let reference = normalized_words(reference);
+    let hypothesis = normalized_words(hypothesis);
+    // Edit distance, one row at a time
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, expected) in reference.iter().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, heard) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(expected != heard);
+            row.push(substitution.min(previous[j + 1] + 1).min(row[j] + 1));
+        }
+        previous = row;
+    }
+    (previous[hypothesis.len()], reference.len())
+}
+
+/// <name>.wav clips of a test set with their <name>.txt references
+fn load_test_set(dir: &Path) -> Result<Vec<Clip>> {
+    let mut wavs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .context(format!("Failed to read test set: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("wav")))
+        .collect();
+    wavs.sort();
+
+    let mut clips = Vec::new();
+    for wav in wavs {
+        let reference = match std::fs::read_to_string(wav.with_extension("txt")) {
+            Ok(reference) => reference,
+            Err(_) => {
+                warn!("⚠️ Skipping benchmark clip without a reference transcript: {}", wav.display());
+                continue;
+            }
+        };
+        let name = wav.file_stem().map_or_else(String::new, |s| s.to_string_lossy().to_string());
+        let samples = file_transcription::load_wav_for_vosk(&wav.to_string_lossy())?;
+        clips.push(Clip { name, samples, reference: Some(reference) });
+    }
+    if clips.is_empty() {
+        return Err(anyhow!("No <name>.wav clips with <name>.txt references in {}", dir.display()));
+    }
+    Ok(clips)
+}
+
+/// Decode a clip the way the live stream does; returns the transcript and decode time
+fn decode(model: &vosk::Model, samples: &[i16]) -> Result<(String, f64)> {
+    let mut recognizer = Recognizer::new(model, VOSK_SAMPLE_RATE as f32)
+        .ok_or_else(|| anyhow!("Failed to create recognizer"))?;
+    let chunk_samples = (VOSK_SAMPLE_RATE * FEED_CHUNK_MS / 1000) as usize;
+    let mut texts = Vec::new();
+    let mut text_of = |result: CompleteResult| {
+        if let CompleteResult::Single(result) = result {
+            if !result.text.is_empty() {
+                texts.push(result.text.to_string());
+            }
+        }
+    };
+
+    let start = Instant::now();
+    for chunk in samples.chunks(chunk_samples) {
+        let decoding = recognizer.accept_waveform(chunk)
+            .map_err(|e| anyhow!("Decode failed: {:?}", e))?;
+        if decoding == DecodingState::Finalized {
+            text_of(recognizer.result());
+        }
+    }
+    text_of(recognizer.final_result());
+    let decode_secs = start.elapsed().as_secs_f64();
+    Ok((texts.join(" "), decode_secs))
+}
+
+/// Benchmark the model at `model_path` on `test_set` (synthetic speech without one),
+/// reporting each clip as "model_benchmark_progress"
+pub fn benchmark(sink: &dyn EventSink, model_path: &str, test_set: Option<&str>) -> Result<ModelBenchmark> {
+    let test_set = test_set.map(str::to_string);
+    let clips = match &test_set {
+        Some(dir) => load_test_set(Path::new(dir))?,
+        None => vec![Clip { name: "synthetic".to_string(), samples: synthetic_speech(SYNTHETIC_SECONDS), reference: None }],
+    };
+
+    let rss_before = crate::hardware_profile::process_rss_bytes();
+    let load_start = Instant::now();
+    let (model, layout) = vosk_model::load(model_path)?;
+    let load_ms = load_start.elapsed().as_millis() as u64;
+    let memory_mb = rss_before.zip(crate::hardware_profile::process_rss_bytes())
+        .map(|(before, after)| after.saturating_sub(before) / (1024 * 1024));
+
+    let mut results = Vec::new();
+    for (done, clip) in clips.iter().enumerate() {
+        let (transcript, decode_secs) = decode(&model, &clip.samples)?;
+        let audio_ms = clip.samples.len() as u64 * 1000 / VOSK_SAMPLE_RATE as u64;
+        let errors = clip.reference.as_deref().map(|reference| word_errors(reference, &transcript));
+        results.push(ClipBenchmark {
+            name: clip.name.clone(),
+            audio_ms,
+            decode_ms: (decode_secs * 1000.0) as u64,
+            real_time_factor: (decode_secs * 1000.0 / audio_ms.max(1) as f64) as f32,
+            reference_words: errors.map(|(_, words)| words as u32),
+            word_errors: errors.map(|(errors, _)| errors as u32),
+            transcript,
+        });
+        if let Err(e) = sink.emit("model_benchmark_progress",
+            Progress { model_path, clip: &clip.name, done: done + 1, total: clips.len() }) {
+            warn!("Failed to emit benchmark progress: {}", e);
+        }
+    }
+
+    let audio_ms: u64 = results.iter().map(|c| c.audio_ms).sum();
+    let decode_ms: u64 = results.iter().map(|c| c.decode_ms).sum();
+    let reference_words: u32 = results.iter().filter_map(|c| c.reference_words).sum();
+    let word_error_rate = (reference_words > 0).then(|| {
+        results.iter().filter_map(|c| c.word_errors).sum::<u32>() as f32 / reference_words as f32
+    });
+    let benchmark = ModelBenchmark {
+        layout,
+        load_ms,
+        memory_mb,
+        test_set,
+        audio_ms,
+        decode_ms,
+        real_time_factor: decode_ms as f32 / audio_ms.max(1) as f32,
+        word_error_rate,
+        accuracy: word_error_rate.map(|wer| (1.0 - wer).max(0.0)),
+        clips: results,
+        benchmarked_at: chrono::Utc::now().to_rfc3339(),
+    };
+    info!("🏁 Benchmarked {}: RTF {:.2}, {} MB, accuracy {}", benchmark.layout.path, benchmark.real_time_factor,
+        benchmark.memory_mb.map_or_else(|| "?".to_string(), |mb| mb.to_string()),
+        benchmark.accuracy.map_or_else(|| "n/a".to_string(), |a| format!("{:.1}%", a * 100.0)));
+    Ok(benchmark)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_errors_ignore_case_and_punctuation() {
+        assert_eq!(word_errors("Hello, how are you?", "hello how are you"), (0, 4));
+        // One substitution, one deletion
+        assert_eq!(word_errors("We can't ship it Friday.", "we can ship Friday"), (2, 5));
+        // Insertions count too
+        assert_eq!(word_errors("Yes", "yes yes okay"), (2, 1));
+        assert_eq!(word_errors("", "anything"), (1, 0));
+        assert_eq!(synthetic_speech(1).len(), VOSK_SAMPLE_RATE as usize);
+    }
+}
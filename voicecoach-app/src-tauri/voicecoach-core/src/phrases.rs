@@ -0,0 +1,37 @@
+// Phrases - whole-word phrase matching on transcript lines
+// Checklist intents, stage cues, objection and commitment phrases are all matched the
+// same way: case and punctuation are ignored and a phrase only counts as whole words
+// ("budget" is not found in "we budgeted").
+
+/// Normalize to lowercase words separated by single spaces (punctuation dropped)
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '\'' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// First phrase of `phrases` appearing in `text` as whole words
+pub fn find_phrase<'a>(text: &str, phrases: &'a [String]) -> Option<&'a str> {
+    let haystack = format!(" {} ", normalize(text));
+    phrases.iter()
+        .find(|phrase| {
+            let needle = normalize(phrase);
+            !needle.is_empty() && haystack.contains(&format!(" {} ", needle))
+        })
+        .map(|p| p.as_str())
+}
+
+/// Number of `phrases` appearing in `text` as whole words
+pub fn count_phrases(text: &str, phrases: &[String]) -> usize {
+    let haystack = format!(" {} ", normalize(text));
+    phrases.iter()
+        .filter(|phrase| {
+            let needle = normalize(phrase);
+            !needle.is_empty() && haystack.contains(&format!(" {} ", needle))
+        })
+        .count()
+}
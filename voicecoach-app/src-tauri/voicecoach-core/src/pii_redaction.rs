@@ -0,0 +1,188 @@
+// PII Redaction - consistent pseudonyms for the people and companies in a transcript
+// Shared sessions (session_share) must not name the prospect. The names a session
+// knows - the caller and company from the dialer, the company the brief was prepared
+// for, plus any the rep adds - are replaced everywhere they appear, whole words and
+// regardless of case, by a stable alias ("Person 1", "Company 1"): a full name and each
+// part of it map to the same alias, so the conversation still reads naturally. Email
+// addresses and phone numbers are replaced whatever they belong to.
+
+use serde_json::Value;
+
+// Digits that make a run of digits and separators a phone number
+const MIN_PHONE_DIGITS: usize = 7;
+// Shorter name parts ("Al", "Li") are too likely to be ordinary words
+const MIN_NAME_PART: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    Person,
+    Company,
+}
+
+#[derive(Default)]
+pub struct Pseudonymizer {
+    // (lowercase name, alias), longest first
+    names: Vec<(String, String)>,
+    people: usize,
+    companies: usize,
+}
+
+impl Pseudonymizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Alias a name (and each part of a multi-word name); a name seen before keeps its alias
+    pub fn add(&mut self, name: &str, kind: EntityKind) {
+        let name = name.trim();
+        if name.chars().count() < MIN_NAME_PART || self.alias_of(name).is_some() {
+            return;
+        }
+        let alias = match kind {
+            EntityKind::Person => {
+                self.people += 1;
+                format!("Person {}", self.people)
+            }
+            EntityKind::Company => {
+                self.companies += 1;
+                format!("Company {}", self.companies)
+            }
+        };
+        let mut forms = vec![name.to_ascii_lowercase()];
+        forms.extend(name.split_whitespace()
+            .filter(|part| part.chars().count() >= MIN_NAME_PART)
+            .map(|part| part.to_ascii_lowercase()));
+        for form in forms {
+            if !self.names.iter().any(|(known, _)| *known == form) {
+                self.names.push((form, alias.clone()));
+            }
+        }
+        self.names.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+    }
+
+    fn alias_of(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.names.iter().find(|(known, _)| *known == name).map(|(_, alias)| alias.as_str())
+    }
+
+    /// Distinct entities aliased
+    pub fn entities(&self) -> usize {
+        self.people + self.companies
+    }
+
+    /// The text with names, email addresses and phone numbers replaced
+    pub fn apply(&self, text: &str) -> String {
+        // Contacts first: a name inside an email address must not break it up
+        let mut text = redact_contacts(text);
+        for (name, alias) in &self.names {
+            text = replace_word(&text, name, alias);
+        }
+        text
+    }
+
+    /// Every string inside a JSON value, in place
+    pub fn apply_to_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.apply(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.apply_to_value(item)),
+            Value::Object(fields) => fields.values_mut().for_each(|field| self.apply_to_value(field)),
+            _ => {}
+        }
+    }
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte >= 0x80
+}
+
+// Whole-word, ASCII case-insensitive replacement (byte offsets survive lowercasing)
+fn replace_word(text: &str, needle: &str, replacement: &str) -> String {
+    let haystack = text.to_ascii_lowercase();
+    let bytes = text.as_bytes();
+    let mut result = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut from = 0;
+    while let Some(found) = haystack[from..].find(needle) {
+        let start = from + found;
+        let end = start + needle.len();
+        let bounded = (start == 0 || !is_word_byte(bytes[start - 1])) && (end == bytes.len() || !is_word_byte(bytes[end]));
+        if bounded {
+            result.push_str(&text[copied..start]);
+            result.push_str(replacement);
+            copied = end;
+        }
+        from = end;
+    }
+    result.push_str(&text[copied..]);
+    result
+}
+
+fn is_email(token: &str) -> bool {
+    let token = token.trim_matches(|c: char| !c.is_alphanumeric());
+    match token.split_once('@') {
+        Some((user, domain)) => !user.is_empty() && domain.contains('.') && !domain.ends_with('.'),
+        None => false,
+    }
+}
+
+// Email addresses become "[email]", runs of 7+ digits (with separators) "[phone]"
+fn redact_contacts(text: &str) -> String {
+    let words: Vec<String> = text.split(' ')
+        .map(|word| if is_email(word) { "[email]".to_string() } else { word.to_string() })
+        .collect();
+    let text = words.join(" ");
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_ascii_digit() || (chars[i] == '+' && chars.get(i + 1).map_or(false, |c| c.is_ascii_digit())) {
+            let mut end = i;
+            let mut digits = 0;
+            while end < chars.len() && (chars[end].is_ascii_digit() || "+-(). ".contains(chars[end])) {
+                digits += chars[end].is_ascii_digit() as usize;
+                end += 1;
+            }
+            // Separators trailing the number stay in the text
+            while end > i && !chars[end - 1].is_ascii_digit() {
+                end -= 1;
+            }
+            if digits >= MIN_PHONE_DIGITS {
+                result.push_str("[phone]");
+            } else {
+                result.extend(&chars[i..end]);
+            }
+            i = end.max(i + 1);
+        } else {
+            result.push(chars[i]);
+            i += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_and_contacts_are_replaced_consistently() {
+        let mut pseudonyms = Pseudonymizer::new();
+        pseudonyms.add("Dana Whitfield", EntityKind::Person);
+        pseudonyms.add("Acme Corp", EntityKind::Company);
+        pseudonyms.add("dana whitfield", EntityKind::Person);
+        assert_eq!(pseudonyms.entities(), 2);
+
+        assert_eq!(pseudonyms.apply("Hi Dana, this is about ACME Corp's renewal. Whitfield agreed."),
+            "Hi Person 1, this is about Company 1's renewal. Person 1 agreed.");
+        // Whole words only
+        assert_eq!(pseudonyms.apply("Danalyn from Acmeware"), "Danalyn from Acmeware");
+        assert_eq!(pseudonyms.apply("Mail dana@acme.com or call +1 (555) 010-4477 by 5 pm."),
+            "Mail [email] or call [phone] by 5 pm.");
+
+        let mut value = serde_json::json!({ "company": "Acme Corp", "transcript": [{ "text": "Dana Whitfield here" }] });
+        pseudonyms.apply_to_value(&mut value);
+        assert_eq!(value["company"], "Company 1");
+        assert_eq!(value["transcript"][0]["text"], "Person 1 here");
+    }
+}
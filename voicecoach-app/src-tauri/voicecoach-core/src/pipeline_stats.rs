@@ -0,0 +1,243 @@
+// Pipeline Stats - how long the audio callbacks take and how regularly they arrive
+// A glitch report ("the transcript skipped a few words at 14:02") is only actionable
+// when it can be matched with what the audio thread was doing. Each instrumented
+// CPAL callback is timed with a guard taken at its top: on drop it records the
+// callback's duration, and at the start the time since the previous callback is
+// compared with the buffer length to get the jitter. Both go into histograms of
+// power-of-two microsecond buckets made of atomics - no locks and no allocation on
+// the audio thread. A callback that runs longer than the audio it carries is an
+// overrun; the wall time of the last one is kept for correlation.
+// `snapshot` returns the histograms of every stream.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use once_cell::sync::Lazy;
+
+// Bucket i counts values below 2^(i+1) µs (the last one everything above ~4 s)
+const BUCKETS: usize = 23;
+// A gap this long between callbacks is a stream (re)start, not jitter
+const RESTART_GAP_US: u64 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStream {
+    VoskCapture,
+    DeepgramCapture,
+    ProspectMeter,
+    SidetoneOutput,
+}
+
+const STREAMS: [PipelineStream; 4] = [
+    PipelineStream::VoskCapture,
+    PipelineStream::DeepgramCapture,
+    PipelineStream::ProspectMeter,
+    PipelineStream::SidetoneOutput,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct HistogramBucket {
+    pub upper_us: u64,               // Values below this (and above the previous bucket)
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub mean_us: u64,
+    pub max_us: u64,
+    pub p50_us: u64,                 // Bucket bounds, so within a factor of two
+    pub p99_us: u64,
+    pub buckets: Vec<HistogramBucket>, // Non-empty buckets only
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CallbackStats {
+    pub stream: PipelineStream,
+    pub callbacks: u64,
+    pub overruns: u64,               // Callbacks longer than the audio they carried
+    pub last_overrun_at: Option<u64>,
+    pub buffer_us: u64,              // Audio per callback, as of the last one
+    pub duration: HistogramSnapshot,
+    pub jitter: HistogramSnapshot,   // |time since the previous callback - buffer length|
+}
+
+struct Histogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum: AtomicU64,
+    max: AtomicU64,
+}
+
+fn bucket(us: u64) -> usize {
+    ((64 - us.leading_zeros()) as usize).saturating_sub(1).min(BUCKETS - 1)
+}
+
+fn upper_us(bucket: usize) -> u64 {
+    1 << (bucket + 1)
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            buckets: (0..BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0),
+            max: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, us: u64) {
+        self.buckets[bucket(us)].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(us, Ordering::Relaxed);
+        self.max.fetch_max(us, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        for b in self.buckets.iter().chain([&self.count, &self.sum, &self.max]) {
+            b.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn snapshot(&self) -> HistogramSnapshot {
+        let counts: Vec<u64> = self.buckets.iter().map(|b| b.load(Ordering::Relaxed)).collect();
+        let count: u64 = counts.iter().sum();
+        let percentile = |p: f64| {
+            let rank = (count as f64 * p).ceil().max(1.0) as u64;
+            let mut seen = 0;
+            for (i, &c) in counts.iter().enumerate() {
+                seen += c;
+                if seen >= rank {
+                    return upper_us(i);
+                }
+            }
+            0
+        };
+        HistogramSnapshot {
+            count,
+            mean_us: self.sum.load(Ordering::Relaxed).checked_div(count).unwrap_or(0),
+            max_us: self.max.load(Ordering::Relaxed),
+            p50_us: if count == 0 { 0 } else { percentile(0.5) },
+            p99_us: if count == 0 { 0 } else { percentile(0.99) },
+            buckets: counts.iter().enumerate()
+                .filter(|(_, &c)| c > 0)
+                .map(|(i, &c)| HistogramBucket { upper_us: upper_us(i), count: c })
+                .collect(),
+        }
+    }
+}
+
+struct StreamStats {
+    duration: Histogram,
+    jitter: Histogram,
+    overruns: AtomicU64,
+    last_overrun_at: AtomicU64,      // Wall ms, 0 = none
+    last_start_us: AtomicU64,        // Since EPOCH, 0 = no callback yet
+    buffer_us: AtomicU64,
+}
+
+impl StreamStats {
+    fn new() -> Self {
+        Self {
+            duration: Histogram::new(),
+            jitter: Histogram::new(),
+            overruns: AtomicU64::new(0),
+            last_overrun_at: AtomicU64::new(0),
+            last_start_us: AtomicU64::new(0),
+            buffer_us: AtomicU64::new(0),
+        }
+    }
+}
+
+static EPOCH: Lazy<Instant> = Lazy::new(Instant::now);
+static STATS: Lazy<Vec<StreamStats>> = Lazy::new(|| STREAMS.iter().map(|_| StreamStats::new()).collect());
+
+/// Times one callback from its creation until it is dropped
+pub struct CallbackTimer {
+    stats: &'static StreamStats,
+    started: Instant,
+    buffer_us: u64,
+}
+
+/// Start timing a callback of `stream` carrying `frames` frames at `sample_rate`
+pub fn time_callback(stream: PipelineStream, frames: usize, sample_rate: u32) -> CallbackTimer {
+    let stats = &STATS[stream as usize];
+    let started = Instant::now();
+    let buffer_us = frames as u64 * 1_000_000 / sample_rate.max(1) as u64;
+    // +1 keeps 0 free for "no callback yet"
+    let start_us = started.duration_since(*EPOCH).as_micros() as u64 + 1;
+    let previous = stats.last_start_us.swap(start_us, Ordering::Relaxed);
+    if previous != 0 {
+        let interval = start_us.saturating_sub(previous);
+        if interval < RESTART_GAP_US {
+            stats.jitter.record(interval.abs_diff(buffer_us));
+        }
+    }
+    stats.buffer_us.store(buffer_us, Ordering::Relaxed);
+    CallbackTimer { stats, started, buffer_us }
+}
+
+impl Drop for CallbackTimer {
+    fn drop(&mut self) {
+        let us = self.started.elapsed().as_micros() as u64;
+        self.stats.duration.record(us);
+        if self.buffer_us > 0 && us > self.buffer_us {
+            self.stats.overruns.fetch_add(1, Ordering::Relaxed);
+            self.stats.last_overrun_at.store(chrono::Utc::now().timestamp_millis() as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Callback timing of every audio stream since launch (or the last reset); streams
+/// that never ran have zero callbacks
+pub fn snapshot() -> Vec<CallbackStats> {
+    STREAMS.iter().map(|&stream| {
+        let stats = &STATS[stream as usize];
+        let last_overrun_at = stats.last_overrun_at.load(Ordering::Relaxed);
+        let duration = stats.duration.snapshot();
+        CallbackStats {
+            stream,
+            callbacks: duration.count,
+            overruns: stats.overruns.load(Ordering::Relaxed),
+            last_overrun_at: (last_overrun_at > 0).then(|| last_overrun_at),
+            buffer_us: stats.buffer_us.load(Ordering::Relaxed),
+            duration,
+            jitter: stats.jitter.snapshot(),
+        }
+    }).collect()
+}
+
+pub fn reset() {
+    for stats in STATS.iter() {
+        stats.duration.reset();
+        stats.jitter.reset();
+        stats.overruns.store(0, Ordering::Relaxed);
+        stats.last_overrun_at.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_and_percentiles() {
+        assert_eq!((bucket(0), bucket(1), bucket(2), bucket(3), bucket(1_000)), (0, 0, 1, 1, 9));
+        assert_eq!(bucket(u64::MAX), BUCKETS - 1);
+
+        let histogram = Histogram::new();
+        for _ in 0..98 {
+            histogram.record(100);
+        }
+        histogram.record(5_000);
+        histogram.record(20_000);
+        let snapshot = histogram.snapshot();
+        assert_eq!((snapshot.count, snapshot.max_us, snapshot.mean_us), (100, 20_000, 348));
+        assert_eq!((snapshot.p50_us, snapshot.p99_us), (128, 8_192));
+        assert_eq!(snapshot.buckets.len(), 3);
+
+        histogram.reset();
+        assert_eq!(histogram.snapshot().p99_us, 0);
+    }
+}
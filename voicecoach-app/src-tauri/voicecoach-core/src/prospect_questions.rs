@@ -0,0 +1,160 @@
+// Prospect Questions - log of the questions the prospect asked during the call
+// Works on the transcript's sentences (sentences.rs): a prospect sentence is a question
+// when it ends with '?' or opens with an interrogative word (Vosk output may be
+// unpunctuated), and is logged at the time the sentence started. A question counts as
+// answered when a rep sentence within ANSWER_WINDOW_MS of it reuses one of its keywords.
+// The log feeds get_prospect_questions and the call summary.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::info;
+
+use crate::sentences::Sentence;
+use crate::transcript::TranscriptLine;
+
+// Rep speech later than this after a question doesn't count as its answer
+const ANSWER_WINDOW_MS: u64 = 30_000;
+// Sentences shorter than this are not logged ("what?", "really?")
+const MIN_QUESTION_WORDS: usize = 3;
+
+const INTERROGATIVES: &[&str] = &[
+    "what", "what's", "how", "how's", "why", "when", "where", "who", "who's", "which",
+    "can", "could", "do", "does", "did", "is", "are", "will", "would", "should", "have", "has",
+];
+
+// Not useful as evidence that the rep addressed the question
+const STOPWORDS: &[&str] = &[
+    "the", "and", "that", "this", "with", "for", "you", "your", "our", "are", "was", "were",
+    "what", "how", "why", "when", "where", "who", "which", "can", "could", "does", "did",
+    "will", "would", "should", "have", "has", "there", "they", "them", "about", "it's",
+    "from", "into", "any", "all", "get", "just", "like", "know", "think", "want",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProspectQuestion {
+    pub id: usize,
+    pub text: String,
+    pub asked_at: u64,           // Time since the call started
+    pub answered: bool,
+    pub answered_at: Option<u64>,
+    pub answer: Option<String>,  // Rep sentence taken as the answer
+}
+
+#[derive(Default)]
+struct QuestionLog {
+    questions: Vec<ProspectQuestion>,
+}
+
+fn keywords(text: &str) -> HashSet<String> {
+    text.split_whitespace()
+        .map(|w| w.chars().filter(|c| c.is_alphanumeric() || *c == '\'').collect::<String>().to_lowercase())
+        .filter(|w| w.len() > 2 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+fn as_question(sentence: &str) -> Option<String> {
+    let sentence = sentence.trim();
+    let words: Vec<&str> = sentence.split_whitespace().collect();
+    if words.len() < MIN_QUESTION_WORDS {
+        return None;
+    }
+    let first = words[0].trim_matches(|c: char| !c.is_alphanumeric() && c != '\'').to_lowercase();
+    let question = sentence.ends_with('?')
+        || (!sentence.ends_with('.') && !sentence.ends_with('!') && INTERROGATIVES.contains(&first.as_str()));
+    question.then(|| sentence.to_string())
+}
+
+impl QuestionLog {
+    fn observe(&mut self, sentence: &Sentence) {
+        let now = sentence.start_ms;
+        if !sentence.is_user {
+            if let Some(question) = as_question(&sentence.text) {
+                info!("❓ Prospect asked: {}", question);
+                let id = self.questions.len();
+                self.questions.push(ProspectQuestion {
+                    id,
+                    text: question,
+                    asked_at: now,
+                    answered: false,
+                    answered_at: None,
+                    answer: None,
+                });
+            }
+            return;
+        }
+
+        let said = keywords(&sentence.text);
+        for question in self.questions.iter_mut()
+            .filter(|q| !q.answered && now.saturating_sub(q.asked_at) <= ANSWER_WINDOW_MS)
+        {
+            if keywords(&question.text).iter().any(|k| said.contains(k)) {
+                question.answered = true;
+                question.answered_at = Some(now);
+                question.answer = Some(sentence.text.clone());
+            }
+        }
+    }
+}
+
+static LOG: Lazy<Mutex<QuestionLog>> = Lazy::new(|| Mutex::new(QuestionLog::default()));
+
+/// Start of a new call
+pub fn begin_call() {
+    *LOG.lock().unwrap() = QuestionLog::default();
+}
+
+/// Feed the sentences of a final line (prospect sentences are scanned for questions, rep
+/// sentences for answers)
+pub fn observe(sentences: &[Sentence]) {
+    let mut log = LOG.lock().unwrap();
+    for sentence in sentences {
+        log.observe(sentence);
+    }
+}
+
+pub fn questions() -> Vec<ProspectQuestion> {
+    LOG.lock().unwrap().questions.clone()
+}
+
+/// Questions of a finished transcript
+pub fn questions_in(transcript: &[TranscriptLine]) -> Vec<ProspectQuestion> {
+    let mut log = QuestionLog::default();
+    for sentence in crate::sentences::segment(transcript) {
+        log.observe(&sentence);
+    }
+    log.questions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn say(log: &mut QuestionLog, text: &str, is_user: bool, offset_ms: u64) {
+        let line = TranscriptLine { offset_ms, is_user, text: text.to_string(), words: Vec::new() };
+        for sentence in crate::sentences::of_line(0, &line, 0) {
+            log.observe(&sentence);
+        }
+    }
+
+    #[test]
+    fn test_questions_are_logged_and_matched_to_rep_answers() {
+        let mut log = QuestionLog::default();
+        say(&mut log, "We looked at a few vendors. How does your pricing work for larger teams?", false, 1_000);
+        say(&mut log, "what about the onboarding timeline", false, 2_000);
+        say(&mut log, "Okay.", false, 2_500);
+        assert_eq!(log.questions.len(), 2);
+        assert_eq!(log.questions[0].text, "How does your pricing work for larger teams?");
+
+        // Related rep speech answers the pricing question only
+        say(&mut log, "Sure. Pricing is per seat, with discounts above fifty seats", true, 10_000);
+        assert!(log.questions[0].answered);
+        assert_eq!(log.questions[0].answer.as_deref(), Some("Pricing is per seat, with discounts above fifty seats"));
+        assert!(!log.questions[1].answered);
+
+        // Too late to count as an answer
+        say(&mut log, "The onboarding usually takes two weeks", true, 40_000);
+        assert!(!log.questions[1].answered);
+    }
+}
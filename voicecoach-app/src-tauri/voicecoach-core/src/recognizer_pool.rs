@@ -0,0 +1,194 @@
+// Vosk recognizer pool - parallel decoding over a fixed set of worker threads
+// One recognizer decodes strictly in order, so a single one leaves every core but
+// one idle. The pool runs `size` workers sharing one model. Each source (a live
+// stream, or one chunk of a file) is bound to a worker on first use and keeps its own
+// recognizer there, so its decoding context is preserved across feeds; sources are
+// spread over the least-busy workers. Finishing a source flushes its final result
+// and resets the recognizer for reuse by the next source.
+
+use vosk::{Model, Recognizer, CompleteResult, DecodingState};
+use anyhow::{Result, anyhow};
+use log::warn;
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
+
+// Feed the recognizer 250ms at a time, same as the live 16kHz stream
+const FEED_CHUNK_MS: usize = 250;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    pub text: String,
+    pub start_ms: u64,  // Relative to the start of the source's audio
+    pub end_ms: u64,
+}
+
+pub type Reply = mpsc::Receiver<Result<Vec<Decoded>>>;
+
+enum Job {
+    Feed { source: String, samples: Vec<i16>, reply: mpsc::Sender<Result<Vec<Decoded>>> },
+    Finish { source: String, reply: mpsc::Sender<Result<Vec<Decoded>>> },
+}
+
+struct SourceState {
+    recognizer: Recognizer,
+    fed_samples: u64,
+    segment_start_ms: u64,
+}
+
+struct Worker {
+    model: Arc<Model>,
+    sample_rate: f32,
+    sources: HashMap<String, SourceState>,
+    idle: Vec<Recognizer>,  // Reset recognizers of finished sources
+}
+
+impl Worker {
+    fn source(&mut self, source: &str) -> Result<&mut SourceState> {
+        if !self.sources.contains_key(source) {
+            let recognizer = match self.idle.pop() {
+                Some(recognizer) => recognizer,
+                None => {
+                    let mut recognizer = Recognizer::new(&self.model, self.sample_rate)
+                        .ok_or_else(|| anyhow!("Failed to create recognizer"))?;
+                    recognizer.set_words(true);
+                    recognizer.set_partial_words(false);
+                    recognizer
+                }
+            };
+            self.sources.insert(source.to_string(), SourceState { recognizer, fed_samples: 0, segment_start_ms: 0 });
+        }
+        Ok(self.sources.get_mut(source).expect("source was just inserted"))
+    }
+
+    fn feed(&mut self, source: &str, samples: &[i16]) -> Result<Vec<Decoded>> {
+        let sample_rate = self.sample_rate as u64;
+        let chunk_samples = (sample_rate as usize * FEED_CHUNK_MS / 1000).max(1);
+        let state = self.source(source)?;
+        let mut decoded = Vec::new();
+        for chunk in samples.chunks(chunk_samples) {
+            state.fed_samples += chunk.len() as u64;
+            let decoding = state.recognizer.accept_waveform(chunk)
+                .map_err(|e| anyhow!("Failed to accept waveform: {:?}", e))?;
+            if decoding == DecodingState::Finalized {
+                let end_ms = state.fed_samples * 1000 / sample_rate;
+                decoded.extend(collect(state.recognizer.result(), state.segment_start_ms, end_ms));
+                state.segment_start_ms = end_ms;
+            }
+        }
+        Ok(decoded)
+    }
+
+    fn finish(&mut self, source: &str) -> Vec<Decoded> {
+        match self.sources.remove(source) {
+            Some(mut state) => {
+                let end_ms = state.fed_samples * 1000 / self.sample_rate as u64;
+                let decoded = collect(state.recognizer.final_result(), state.segment_start_ms, end_ms);
+                state.recognizer.reset();
+                self.idle.push(state.recognizer);
+                decoded.into_iter().collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn run(mut self, jobs: mpsc::Receiver<Job>) {
+        for job in jobs {
+            match job {
+                Job::Feed { source, samples, reply } => {
+                    let _ = reply.send(self.feed(&source, &samples));
+                }
+                Job::Finish { source, reply } => {
+                    let _ = reply.send(Ok(self.finish(&source)));
+                }
+            }
+        }
+    }
+}
+
+fn collect(result: CompleteResult, fallback_start_ms: u64, fallback_end_ms: u64) -> Option<Decoded> {
+    match result {
+        CompleteResult::Single(res) if !res.text.is_empty() => {
+            // Prefer word timings when the recognizer reports them
+            let (start_ms, end_ms) = match (res.result.first(), res.result.last()) {
+                (Some(first), Some(last)) => ((first.start * 1000.0) as u64, (last.end * 1000.0) as u64),
+                _ => (fallback_start_ms, fallback_end_ms),
+            };
+            Some(Decoded { text: res.text.to_string(), start_ms, end_ms })
+        }
+        CompleteResult::Single(_) => None,
+        CompleteResult::Multiple(_) => {
+            warn!("⚠️ LED 8103: Unexpected multiple-alternative result, skipping");
+            None
+        }
+    }
+}
+
+pub struct RecognizerPool {
+    workers: Vec<mpsc::Sender<Job>>,
+    affinity: Mutex<HashMap<String, usize>>,  // Source -> worker index
+}
+
+impl RecognizerPool {
+    /// Start `size` workers decoding with `model` (at least one)
+    pub fn new(model: Arc<Model>, size: usize, sample_rate: f32) -> Self {
+        let workers = (0..size.max(1)).map(|index| {
+            let (tx, rx) = mpsc::channel();
+            let worker = Worker { model: model.clone(), sample_rate, sources: HashMap::new(), idle: Vec::new() };
+            std::thread::Builder::new()
+                .name(format!("vosk-pool-{}", index))
+                .spawn(move || worker.run(rx))
+                .expect("failed to spawn recognizer worker");
+            tx
+        }).collect();
+        Self { workers, affinity: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    // Worker the source is bound to, binding it to the least-busy worker on first use
+    fn worker_for(&self, source: &str) -> &mpsc::Sender<Job> {
+        let mut affinity = self.affinity.lock().unwrap();
+        let index = match affinity.get(source) {
+            Some(&index) => index,
+            None => {
+                let mut bound = vec![0usize; self.workers.len()];
+                for &index in affinity.values() {
+                    bound[index] += 1;
+                }
+                let index = (0..bound.len()).min_by_key(|&i| bound[i]).unwrap_or(0);
+                affinity.insert(source.to_string(), index);
+                index
+            }
+        };
+        &self.workers[index]
+    }
+
+    fn send(&self, source: &str, job: Job, reply: mpsc::Receiver<Result<Vec<Decoded>>>) -> Reply {
+        if self.worker_for(source).send(job).is_err() {
+            // Worker gone (a decode panicked): the caller sees the reply channel closed
+            warn!("⚠️ Recognizer worker for {} is not running", source);
+        }
+        reply
+    }
+
+    /// Decode more audio of a source; the reply carries segments finalized by it
+    pub fn feed(&self, source: &str, samples: Vec<i16>) -> Reply {
+        let (tx, rx) = mpsc::channel();
+        self.send(source, Job::Feed { source: source.to_string(), samples, reply: tx }, rx)
+    }
+
+    /// Flush a source's final segment and release its recognizer
+    pub fn finish(&self, source: &str) -> Reply {
+        let (tx, rx) = mpsc::channel();
+        let reply = self.send(source, Job::Finish { source: source.to_string(), reply: tx }, rx);
+        self.affinity.lock().unwrap().remove(source);
+        reply
+    }
+}
+
+/// Wait for a reply (a closed channel means the worker failed)
+pub fn wait(reply: Reply) -> Result<Vec<Decoded>> {
+    reply.recv().map_err(|_| anyhow!("Recognizer worker stopped"))?
+}
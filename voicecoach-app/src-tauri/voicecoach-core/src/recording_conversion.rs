@@ -0,0 +1,223 @@
+// Recording Conversion - small MP3/Opus sharing copies of call recordings
+// Recordings are kept as WAV, the archival original, which is too large to email and
+// which many managers' mail and phone clients won't play. convert_recording writes a
+// sharing copy beside the original (call.wav -> call.low.mp3) at a speech bitrate,
+// downmixed and downsampled by quality: low is mono 16 kHz, small enough to email an
+// hour of call; high keeps the channels at 44.1/48 kHz. The original is never touched.
+// Encoding is done by ffmpeg (on the PATH, or the binary VOICECOACH_FFMPEG names),
+// which has both encoders; its progress is reported as "recording_conversion_progress"
+// events (to the caller's EventSink) on the recording's clock. A failed conversion leaves no partial copy behind.
+
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use log::info;
+
+use crate::events::EventSink;
+
+const DEFAULT_FFMPEG: &str = "ffmpeg";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SharingFormat {
+    Mp3,
+    Opus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SharingQuality {
+    Low,      // Mono 16 kHz, for email
+    Medium,   // Mono 22-24 kHz
+    High,     // The original's channels at 44.1/48 kHz
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SharingCopy {
+    pub session_id: String,
+    pub path: String,
+    pub format: SharingFormat,
+    pub quality: SharingQuality,
+    pub bytes: u64,
+    pub original_bytes: u64,
+}
+
+// Payload of "recording_conversion_progress"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ConversionProgress {
+    pub session_id: String,
+    pub format: SharingFormat,
+    pub quality: SharingQuality,
+    pub done_ms: u64,
+    pub duration_ms: Option<u64>,   // None when the original's length is unknown
+    pub finished: bool,
+}
+
+/// Encoder settings of a format at a quality
+struct Encoding {
+    codec: &'static str,
+    extension: &'static str,
+    bitrate_kbps: u32,
+    sample_rate: u32,
+    mono: bool,
+}
+
+fn encoding(format: SharingFormat, quality: SharingQuality) -> Encoding {
+    // Opus only encodes at 8/12/16/24/48 kHz; MP3 at 16/22.05/44.1 kHz and friends
+    let (codec, extension, bitrate_kbps, sample_rate) = match (format, quality) {
+        (SharingFormat::Mp3, SharingQuality::Low) => ("libmp3lame", "mp3", 24, 16_000),
+        (SharingFormat::Mp3, SharingQuality::Medium) => ("libmp3lame", "mp3", 48, 22_050),
+        (SharingFormat::Mp3, SharingQuality::High) => ("libmp3lame", "mp3", 128, 44_100),
+        (SharingFormat::Opus, SharingQuality::Low) => ("libopus", "opus", 16, 16_000),
+        (SharingFormat::Opus, SharingQuality::Medium) => ("libopus", "opus", 32, 24_000),
+        (SharingFormat::Opus, SharingQuality::High) => ("libopus", "opus", 64, 48_000),
+    };
+    Encoding { codec, extension, bitrate_kbps, sample_rate, mono: quality != SharingQuality::High }
+}
+
+/// Where the sharing copy of `original` goes: beside it, named by quality
+fn copy_path(original: &Path, format: SharingFormat, quality: SharingQuality) -> PathBuf {
+    let stem = original.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let label = match quality {
+        SharingQuality::Low => "low",
+        SharingQuality::Medium => "medium",
+        SharingQuality::High => "high",
+    };
+    original.with_file_name(format!("{}.{}.{}", stem, label, encoding(format, quality).extension))
+}
+
+fn ffmpeg_args(input: &Path, output: &Path, encoding: &Encoding) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-hide_banner", "-nostdin", "-loglevel", "error", "-nostats", "-progress", "pipe:1", "-y", "-i"]
+        .into_iter().map(String::from).collect();
+    args.push(input.to_string_lossy().into_owned());
+    args.extend(["-vn", "-c:a", encoding.codec, "-b:a"].iter().map(|a| a.to_string()));
+    args.push(format!("{}k", encoding.bitrate_kbps));
+    args.push("-ar".to_string());
+    args.push(encoding.sample_rate.to_string());
+    if encoding.mono {
+        args.extend(["-ac", "1"].iter().map(|a| a.to_string()));
+    }
+    if encoding.codec == "libopus" {
+        // Tuned for speech
+        args.extend(["-application", "voip"].iter().map(|a| a.to_string()));
+    }
+    args.push(output.to_string_lossy().into_owned());
+    args
+}
+
+/// Milliseconds encoded so far, from an ffmpeg -progress line. out_time_ms is in
+/// microseconds despite its name; every ffmpeg writes it (out_time_us is newer)
+fn progress_ms(line: &str) -> Option<u64> {
+    let (key, value) = line.trim().split_once('=')?;
+    if key != "out_time_ms" {
+        return None;
+    }
+    value.parse::<i64>().ok().map(|us| us.max(0) as u64 / 1000)
+}
+
+fn wav_duration_ms(path: &Path) -> Option<u64> {
+    let reader = hound::WavReader::open(path).ok()?;
+    let spec = reader.spec();
+    Some(reader.duration() as u64 * 1000 / spec.sample_rate.max(1) as u64)
+}
+
+fn ffmpeg() -> String {
+    std::env::var("VOICECOACH_FFMPEG").unwrap_or_else(|_| DEFAULT_FFMPEG.to_string())
+}
+
+/// Write the sharing copy of `original`, reporting progress to `events`
+pub fn convert(events: &dyn EventSink, session_id: &str, original: &Path, format: SharingFormat, quality: SharingQuality) -> Result<SharingCopy> {
+    let original_bytes = std::fs::metadata(original)
+        .context(format!("Recording {} not found", original.display()))?
+        .len();
+    let output = copy_path(original, format, quality);
+    let encoding = encoding(format, quality);
+    let duration_ms = wav_duration_ms(original);
+
+    let mut command = Command::new(ffmpeg());
+    command.args(ffmpeg_args(original, &output, &encoding))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let mut child = command.spawn()
+        .context("ffmpeg was not found - install it, or set VOICECOACH_FFMPEG to its path")?;
+
+    // Errors are read on the side so a chatty ffmpeg never blocks on a full pipe
+    let mut stderr = child.stderr.take().context("ffmpeg stderr unavailable")?;
+    let errors = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    let progress = |done_ms: u64, finished: bool| {
+        let _ = events.emit("recording_conversion_progress", ConversionProgress {
+            session_id: session_id.to_string(),
+            format,
+            quality,
+            done_ms,
+            duration_ms,
+            finished,
+        });
+    };
+    let stdout = child.stdout.take().context("ffmpeg stdout unavailable")?;
+    for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+        if let Some(done_ms) = progress_ms(&line) {
+            progress(duration_ms.map_or(done_ms, |d| done_ms.min(d)), false);
+        }
+    }
+
+    let status = child.wait().context("ffmpeg did not finish")?;
+    let errors = errors.join().unwrap_or_default();
+    if !status.success() {
+        let _ = std::fs::remove_file(&output);
+        let reason = errors.lines().last().unwrap_or("no error output").to_string();
+        anyhow::bail!("ffmpeg failed ({}): {}", status, reason);
+    }
+    progress(duration_ms.unwrap_or(0), true);
+
+    let bytes = std::fs::metadata(&output).map(|m| m.len()).unwrap_or(0);
+    info!("🎧 Sharing copy {} written ({} KB from {} KB)", output.display(), bytes / 1024, original_bytes / 1024);
+    Ok(SharingCopy {
+        session_id: session_id.to_string(),
+        path: output.to_string_lossy().into_owned(),
+        format,
+        quality,
+        bytes,
+        original_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sharing_copy_is_encoded_beside_the_original() {
+        let original = Path::new("calls").join("acme.wav");
+        assert_eq!(copy_path(&original, SharingFormat::Mp3, SharingQuality::Low), Path::new("calls").join("acme.low.mp3"));
+        assert_eq!(copy_path(&original, SharingFormat::Opus, SharingQuality::High), Path::new("calls").join("acme.high.opus"));
+
+        let output = copy_path(&original, SharingFormat::Opus, SharingQuality::Low);
+        let args = ffmpeg_args(&original, &output, &encoding(SharingFormat::Opus, SharingQuality::Low)).join(" ");
+        assert!(args.contains("-c:a libopus -b:a 16k -ar 16000 -ac 1 -application voip"), "{}", args);
+        assert!(args.ends_with("acme.low.opus"));
+        // High quality keeps the original's channels
+        let args = ffmpeg_args(&original, &output, &encoding(SharingFormat::Mp3, SharingQuality::High)).join(" ");
+        assert!(args.contains("-b:a 128k -ar 44100") && !args.contains("-ac 1"), "{}", args);
+
+        assert_eq!(progress_ms("out_time_ms=2500000"), Some(2_500));
+        assert_eq!(progress_ms("out_time_us=2500000"), None);
+        assert_eq!(progress_ms("out_time_ms=-9223372036854775807"), Some(0));
+        assert_eq!(progress_ms("total_size=1024"), None);
+        assert_eq!(progress_ms("progress=continue"), None);
+    }
+}
@@ -0,0 +1,301 @@
+// Sales Stage - conversation stage detection and stage-specific vocabulary biasing
+// Final transcripts are scored against per-stage cue phrases over a short rolling
+// window; when another stage clearly dominates, the stage changes and its
+// vocabulary (e.g. pricing terms during negotiation) is handed to the active
+// transcription backend: Deepgram reconnects with keyword boosting, Vosk can
+// optionally swap in a grammar-biased recognizer between utterances. Settings come
+// from the caller (the app keeps them in preferences) and stage changes go to an
+// EventSink, so detection runs the same in the app and in tests.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use log::{info, error};
+
+use crate::events::EventSink;
+use crate::phrases::count_phrases;
+
+// Final transcript lines considered when scoring stages
+const STAGE_WINDOW: usize = 6;
+// Cue hits a stage needs within the window before the call moves to it
+const MIN_CUE_HITS: usize = 2;
+const DEFAULT_KEYWORD_BOOST: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SalesStage {
+    Opening,
+    Discovery,
+    Presentation,
+    ObjectionHandling,
+    Negotiation,
+    Closing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct StageVocabulary {
+    pub stage: SalesStage,
+    /// Phrases that indicate the call is in this stage (whole words, case-insensitive)
+    pub cues: Vec<String>,
+    /// Terms the recognizer should favour while in this stage
+    pub terms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct StageBiasSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Also bias Vosk via a recognizer grammar. Grammars constrain recognition to the
+    /// listed phrases plus [unk], so this only suits small (dynamic graph) models.
+    #[serde(default)]
+    pub vosk_grammar: bool,
+    #[serde(default = "default_boost")]
+    pub keyword_boost: f32,
+    #[serde(default)]
+    pub stages: Vec<StageVocabulary>,  // Empty = built-in vocabularies
+}
+
+fn default_true() -> bool { true }
+fn default_boost() -> f32 { DEFAULT_KEYWORD_BOOST }
+
+impl Default for StageBiasSettings {
+    fn default() -> Self {
+        Self { enabled: true, vosk_grammar: false, keyword_boost: DEFAULT_KEYWORD_BOOST, stages: Vec::new() }
+    }
+}
+
+// Payload of "sales_stage_changed" and result of get_sales_stage
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct StageStatus {
+    pub stage: SalesStage,
+    pub previous: Option<SalesStage>,
+    pub terms: Vec<String>,   // Vocabulary currently fed to the recognizer (empty if disabled)
+    pub generation: u32,
+    pub manual: bool,         // Set via set_sales_stage rather than detected
+}
+
+fn vocabulary(stage: SalesStage, cues: &[&str], terms: &[&str]) -> StageVocabulary {
+    StageVocabulary {
+        stage,
+        cues: cues.iter().map(|c| c.to_string()).collect(),
+        terms: terms.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+pub fn default_vocabularies() -> Vec<StageVocabulary> {
+    vec![
+        vocabulary(SalesStage::Opening,
+            &["how are you", "thanks for taking", "nice to meet", "agenda", "quick intro", "introduce myself"],
+            &["agenda", "introductions"]),
+        vocabulary(SalesStage::Discovery,
+            &["tell me about", "how do you currently", "what challenges", "pain point", "walk me through", "what happens when"],
+            &["workflow", "pain points", "stakeholders", "requirements", "integration", "current process"]),
+        vocabulary(SalesStage::Presentation,
+            &["let me show", "demo", "share my screen", "this feature", "dashboard", "as you can see"],
+            &["dashboard", "analytics", "onboarding", "integration", "API", "reporting"]),
+        vocabulary(SalesStage::ObjectionHandling,
+            &["too expensive", "not sure", "concern", "competitor", "we already use", "not the right time"],
+            &["ROI", "payback", "security review", "compliance", "risk", "competitor"]),
+        vocabulary(SalesStage::Negotiation,
+            &["discount", "pricing", "contract", "per seat", "annual", "quote", "payment terms"],
+            &["discount", "per seat", "annual contract", "net thirty", "multi year", "procurement", "invoice", "SLA", "MSRP"]),
+        vocabulary(SalesStage::Closing,
+            &["next steps", "sign", "send over the contract", "start date", "kick off", "purchase order"],
+            &["purchase order", "DocuSign", "signature", "kickoff", "start date", "onboarding"]),
+    ]
+}
+
+struct StageDetector {
+    stage: SalesStage,
+    manual: bool,
+    window: VecDeque<Vec<(SalesStage, usize)>>,  // Cue hits per stage for recent lines
+}
+
+impl StageDetector {
+    fn new() -> Self {
+        Self { stage: SalesStage::Opening, manual: false, window: VecDeque::new() }
+    }
+
+    fn score(&self, stage: SalesStage) -> usize {
+        self.window.iter()
+            .flat_map(|hits| hits.iter())
+            .filter(|(s, _)| *s == stage)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Add a final transcript line; returns the previous stage if the stage changed
+    fn observe(&mut self, text: &str, vocabularies: &[StageVocabulary]) -> Option<SalesStage> {
+        let hits: Vec<(SalesStage, usize)> = vocabularies.iter()
+            .map(|v| (v.stage, count_phrases(text, &v.cues)))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        self.window.push_back(hits);
+        if self.window.len() > STAGE_WINDOW {
+            self.window.pop_front();
+        }
+
+        // Highest scoring stage wins; the current stage keeps ties
+        let current_score = self.score(self.stage);
+        let (best, best_score) = vocabularies.iter()
+            .map(|v| (v.stage, self.score(v.stage)))
+            .max_by_key(|(_, score)| *score)?;
+        if best != self.stage && best_score >= MIN_CUE_HITS && best_score > current_score {
+            let previous = self.stage;
+            self.stage = best;
+            self.manual = false;
+            Some(previous)
+        } else {
+            None
+        }
+    }
+}
+
+static DETECTOR: Lazy<Mutex<StageDetector>> = Lazy::new(|| Mutex::new(StageDetector::new()));
+// Bumped on every stage change; backends compare it to re-apply their biasing
+static GENERATION: AtomicU32 = AtomicU32::new(0);
+
+fn vocabularies(settings: &StageBiasSettings) -> Vec<StageVocabulary> {
+    if settings.stages.is_empty() { default_vocabularies() } else { settings.stages.clone() }
+}
+
+fn terms_for(stage: SalesStage, settings: &StageBiasSettings) -> Vec<String> {
+    if !settings.enabled {
+        return Vec::new();
+    }
+    vocabularies(settings).into_iter()
+        .find(|v| v.stage == stage)
+        .map(|v| v.terms)
+        .unwrap_or_default()
+}
+
+/// The current stage, with the vocabulary `settings` bias it towards
+pub fn status(settings: &StageBiasSettings, previous: Option<SalesStage>) -> StageStatus {
+    let (stage, manual) = {
+        let detector = DETECTOR.lock().unwrap();
+        (detector.stage, detector.manual)
+    };
+    StageStatus {
+        stage,
+        previous,
+        terms: terms_for(stage, settings),
+        generation: GENERATION.load(Ordering::SeqCst),
+        manual,
+    }
+}
+
+fn announce(sink: &dyn EventSink, settings: &StageBiasSettings, previous: SalesStage) {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    let status = status(settings, Some(previous));
+    info!("🧭 Sales stage: {:?} -> {:?} ({} bias terms)", previous, status.stage, status.terms.len());
+    if let Err(e) = sink.emit("sales_stage_changed", status) {
+        error!("Failed to emit sales_stage_changed: {:?}", e);
+    }
+}
+
+/// Start of a new call: back to the opening stage
+pub fn begin_call() {
+    *DETECTOR.lock().unwrap() = StageDetector::new();
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Feed a final transcript line; emits "sales_stage_changed" when the stage moves
+pub fn process_final_transcript(sink: &dyn EventSink, text: &str, settings: &StageBiasSettings) {
+    let vocabularies = vocabularies(settings);
+    let changed = DETECTOR.lock().unwrap().observe(text, &vocabularies);
+    if let Some(previous) = changed {
+        announce(sink, settings, previous);
+    }
+}
+
+/// Manual override; detection continues from `stage`
+pub fn set_stage(sink: &dyn EventSink, stage: SalesStage, settings: &StageBiasSettings) -> StageStatus {
+    let previous = {
+        let mut detector = DETECTOR.lock().unwrap();
+        let previous = detector.stage;
+        detector.stage = stage;
+        detector.manual = true;
+        detector.window.clear();
+        previous
+    };
+    if previous != stage {
+        announce(sink, settings, previous);
+    }
+    status(settings, Some(previous))
+}
+
+/// Cue phrases of the objection-handling stage
+pub fn objection_cues(settings: &StageBiasSettings) -> Vec<String> {
+    vocabularies(settings).into_iter()
+        .find(|v| v.stage == SalesStage::ObjectionHandling)
+        .map(|v| v.cues)
+        .unwrap_or_default()
+}
+
+/// Whether a line carries objection-handling cues (counted per call by call_analytics)
+pub fn is_objection(text: &str, settings: &StageBiasSettings) -> bool {
+    count_phrases(text, &objection_cues(settings)) > 0
+}
+
+/// Changes whenever the bias vocabulary may have changed
+pub fn generation() -> u32 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+/// The bias settings changed; backends re-apply on the next generation check
+pub fn settings_changed() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Grammar for a biased Vosk recognizer, or None to use the unrestricted model
+pub fn vosk_grammar(settings: &StageBiasSettings) -> Option<Vec<String>> {
+    if !settings.vosk_grammar {
+        return None;
+    }
+    let stage = DETECTOR.lock().unwrap().stage;
+    let mut grammar: Vec<String> = terms_for(stage, settings).iter().map(|t| t.to_lowercase()).collect();
+    if grammar.is_empty() {
+        return None;
+    }
+    grammar.push("[unk]".to_string());
+    Some(grammar)
+}
+
+/// Deepgram query parameters boosting the current stage's terms ("" when disabled)
+pub fn deepgram_keywords_query(settings: &StageBiasSettings) -> String {
+    let stage = DETECTOR.lock().unwrap().stage;
+    let mut words: Vec<String> = Vec::new();
+    for term in terms_for(stage, settings) {
+        // Keywords are single words; phrases are boosted word by word
+        for word in term.split_whitespace() {
+            let word: String = word.chars().filter(|c| c.is_alphanumeric() || *c == '\'').collect();
+            if !word.is_empty() && !words.contains(&word) {
+                words.push(word);
+            }
+        }
+    }
+    words.iter()
+        .map(|word| format!("&keywords={}:{}", word, settings.keyword_boost))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_moves_only_after_repeated_cues() {
+        let vocabularies = default_vocabularies();
+        let mut detector = StageDetector::new();
+
+        assert_eq!(detector.observe("Tell me about how you handle renewals today", &vocabularies), None);
+        assert_eq!(detector.observe("So what challenges come up with pricing?", &vocabularies), Some(SalesStage::Opening));
+        assert_eq!(detector.stage, SalesStage::Discovery);
+        // Negotiation ties discovery: the current stage keeps it
+        assert_eq!(detector.observe("Okay, is there a discount?", &vocabularies), None);
+        assert_eq!(detector.observe("And the per seat quote?", &vocabularies), Some(SalesStage::Discovery));
+        assert_eq!(detector.stage, SalesStage::Negotiation);
+    }
+}
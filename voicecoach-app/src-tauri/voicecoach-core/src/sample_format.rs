@@ -0,0 +1,203 @@
+// Sample Format - streams in whatever sample format the device speaks
+// Every stream in the app works in f32, and a stream was built for f32 (or, on the
+// output side, F32/I16/U16) only. cpal builds a stream in a format the device
+// supports, so interfaces offering I32 or F64 alone - pro audio interfaces, some
+// ASIO/WASAPI drivers, 24-bit devices reporting I32 - failed with "Unsupported sample
+// format". Streams are now built here for the device's own format, any of cpal's
+// (I8-I64, U8-U64, F32, F64), and each buffer is converted to or from f32 with cpal's
+// sample conversions (unsigned formats are centred on their midpoint, integers scaled
+// to -1.0..1.0). The callbacks keep seeing f32; conversion buffers are reused.
+
+use cpal::traits::DeviceTrait;
+use cpal::{BuildStreamError, FromSample, InputCallbackInfo, OutputCallbackInfo, Sample, SampleFormat, SizedSample, StreamConfig, StreamError};
+
+/// Reusable buffer converting device samples to and from f32
+#[derive(Default)]
+pub struct SampleFormatConverter {
+    buffer: Vec<f32>,
+}
+
+impl SampleFormatConverter {
+    /// Device samples as f32 (valid until the next call)
+    pub fn read<T>(&mut self, data: &[T]) -> &[f32]
+    where
+        T: Sample,
+        f32: FromSample<T>,
+    {
+        self.buffer.clear();
+        self.buffer.extend(data.iter().map(|&s| s.to_sample::<f32>()));
+        &self.buffer
+    }
+
+    /// A silent f32 buffer of `len` samples to fill before `write`
+    pub fn output(&mut self, len: usize) -> &mut [f32] {
+        self.buffer.clear();
+        self.buffer.resize(len, 0.0);
+        &mut self.buffer
+    }
+
+    /// The filled output buffer into device samples
+    pub fn write<T>(&self, data: &mut [T])
+    where
+        T: Sample + FromSample<f32>,
+    {
+        for (out, &sample) in data.iter_mut().zip(&self.buffer) {
+            *out = T::from_sample(sample);
+        }
+    }
+}
+
+/// Format of the device's default input configuration (F32 when it has none)
+pub fn input_format(device: &cpal::Device) -> SampleFormat {
+    device.default_input_config().map_or(SampleFormat::F32, |c| c.sample_format())
+}
+
+fn input<T, D, E>(device: &cpal::Device, config: &StreamConfig, mut data_callback: D, error_callback: E) -> Result<cpal::Stream, BuildStreamError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+    D: FnMut(&[f32], &InputCallbackInfo) + Send + 'static,
+    E: FnMut(StreamError) + Send + 'static,
+{
+    let mut converter = SampleFormatConverter::default();
+    device.build_input_stream(
+        config,
+        move |data: &[T], info: &InputCallbackInfo| data_callback(converter.read(data), info),
+        error_callback,
+        None,
+    )
+}
+
+/// Input stream in the device's `format`, delivering f32 samples
+pub fn build_input_stream<D, E>(device: &cpal::Device, config: &StreamConfig, format: SampleFormat, data_callback: D, error_callback: E) -> Result<cpal::Stream, BuildStreamError>
+where
+    D: FnMut(&[f32], &InputCallbackInfo) + Send + 'static,
+    E: FnMut(StreamError) + Send + 'static,
+{
+    match format {
+        SampleFormat::F32 => device.build_input_stream(config, data_callback, error_callback, None),
+        SampleFormat::F64 => input::<f64, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::I8 => input::<i8, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::I16 => input::<i16, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::I32 => input::<i32, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::I64 => input::<i64, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::U8 => input::<u8, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::U16 => input::<u16, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::U32 => input::<u32, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::U64 => input::<u64, _, _>(device, config, data_callback, error_callback),
+        _ => Err(BuildStreamError::StreamConfigNotSupported),
+    }
+}
+
+fn output<T, D, E>(device: &cpal::Device, config: &StreamConfig, mut data_callback: D, error_callback: E) -> Result<cpal::Stream, BuildStreamError>
+where
+    T: SizedSample + FromSample<f32>,
+    D: FnMut(&mut [f32], &OutputCallbackInfo) + Send + 'static,
+    E: FnMut(StreamError) + Send + 'static,
+{
+    let mut converter = SampleFormatConverter::default();
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], info: &OutputCallbackInfo| {
+            data_callback(converter.output(data.len()), info);
+            converter.write(data);
+        },
+        error_callback,
+        None,
+    )
+}
+
+/// Output stream in the device's `format`, filled in f32 (the buffer starts silent)
+pub fn build_output_stream<D, E>(device: &cpal::Device, config: &StreamConfig, format: SampleFormat, data_callback: D, error_callback: E) -> Result<cpal::Stream, BuildStreamError>
+where
+    D: FnMut(&mut [f32], &OutputCallbackInfo) + Send + 'static,
+    E: FnMut(StreamError) + Send + 'static,
+{
+    match format {
+        SampleFormat::F32 => output::<f32, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::F64 => output::<f64, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::I8 => output::<i8, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::I16 => output::<i16, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::I32 => output::<i32, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::I64 => output::<i64, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::U8 => output::<u8, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::U16 => output::<u16, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::U32 => output::<u32, _, _>(device, config, data_callback, error_callback),
+        SampleFormat::U64 => output::<u64, _, _>(device, config, data_callback, error_callback),
+        _ => Err(BuildStreamError::StreamConfigNotSupported),
+    }
+}
+
+/// Average interleaved frames down to mono (shared-mode formats are usually stereo)
+pub fn downmix_to_mono(data: &[f32], channels: usize) -> Vec<f32> {
+    data.chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Apply a linear gain in place, hard-limited to full scale
+pub fn apply_gain(samples: &mut [f32], gain: f32) {
+    for sample in samples.iter_mut() {
+        *sample = (*sample * gain).clamp(-1.0, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Full scale, silence and half scale of a format read back as f32
+    fn input_levels<T>(min: T, equilibrium: T, max: T) -> Vec<f32>
+    where
+        T: Sample,
+        f32: FromSample<T>,
+    {
+        SampleFormatConverter::default().read(&[min, equilibrium, max]).to_vec()
+    }
+
+    fn assert_levels(levels: Vec<f32>) {
+        assert!((levels[0] + 1.0).abs() < 1e-3, "min {:?}", levels);
+        assert!(levels[1].abs() < 1e-3, "equilibrium {:?}", levels);
+        assert!((levels[2] - 1.0).abs() < 1e-2, "max {:?}", levels);
+    }
+
+    // f32 written out in a format and read back
+    fn round_trip<T>(samples: &[f32]) -> Vec<f32>
+    where
+        T: Sample + FromSample<f32>,
+        f32: FromSample<T>,
+    {
+        let mut converter = SampleFormatConverter::default();
+        converter.output(samples.len()).copy_from_slice(samples);
+        let mut device = vec![T::EQUILIBRIUM; samples.len()];
+        converter.write(&mut device);
+        converter.read(&device).to_vec()
+    }
+
+    #[test]
+    fn test_every_sample_format_converts_to_and_from_f32() {
+        assert_levels(input_levels(i8::MIN, 0, i8::MAX));
+        assert_levels(input_levels(i16::MIN, 0, i16::MAX));
+        assert_levels(input_levels(i32::MIN, 0, i32::MAX));
+        assert_levels(input_levels(i64::MIN, 0, i64::MAX));
+        assert_levels(input_levels(u8::MIN, 128, u8::MAX));
+        assert_levels(input_levels(u16::MIN, 32_768, u16::MAX));
+        assert_levels(input_levels(u32::MIN, 1 << 31, u32::MAX));
+        assert_levels(input_levels(u64::MIN, 1 << 63, u64::MAX));
+        assert_levels(input_levels(-1.0f64, 0.0, 1.0));
+
+        let samples = [-1.0, -0.5, 0.0, 0.25, 0.999];
+        let close = |tolerance: f32, got: Vec<f32>| {
+            assert!(got.iter().zip(&samples).all(|(g, s)| (g - s).abs() <= tolerance), "{:?}", got);
+        };
+        close(1.0 / 64.0, round_trip::<i8>(&samples));
+        close(1.0 / 64.0, round_trip::<u8>(&samples));
+        close(1e-4, round_trip::<i16>(&samples));
+        close(1e-4, round_trip::<u16>(&samples));
+        close(1e-6, round_trip::<i32>(&samples));
+        close(1e-6, round_trip::<u32>(&samples));
+        close(1e-6, round_trip::<i64>(&samples));
+        close(1e-6, round_trip::<u64>(&samples));
+        close(0.0, round_trip::<f64>(&samples));
+    }
+}
@@ -0,0 +1,183 @@
+// Sentences - the punctuated transcript as timed sentences
+// Engines finalize a line when the speaker pauses, not when a sentence ends: one final
+// can hold a statement and a question, and a monologue can run across a summary
+// window. Analytics that reason about sentences - question detection, speaking pace,
+// summary windows - work on the records made here instead. Each final line (punctuated
+// by the engine, or by punctuation.rs for Vosk) is split after '.', '?' and '!' - not
+// after abbreviations such as "Mr." or "e.g.", and never inside a number such as
+// "2.5" - and each sentence is timed by its words' timings, or, when the engine gave
+// none, by spreading the line's speech time over its words. Times are offsets from the
+// session start, like the transcript's.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use crate::transcript::{TranscriptLine, TranscriptWord};
+
+// Speaking time assumed per word of a stored line without word timings (~150 wpm)
+const MS_PER_WORD: u64 = 400;
+
+// Words ending in '.' that don't end a sentence (besides "U.S."-style initialisms)
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "etc", "approx", "inc", "ltd", "corp", "dept", "est",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Sentence {
+    pub line: usize,       // Index of the transcript line it is part of
+    pub is_user: bool,
+    pub text: String,
+    pub start_ms: u64,     // Time since the call started
+    pub end_ms: u64,
+    pub words: usize,
+}
+
+impl Sentence {
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms.saturating_sub(self.start_ms)
+    }
+}
+
+#[derive(Default)]
+struct CallSentences {
+    lines: usize,
+    sentences: Vec<Sentence>,
+}
+
+static CALL: Lazy<Mutex<CallSentences>> = Lazy::new(|| Mutex::new(CallSentences::default()));
+
+/// Whether a word closes its sentence
+fn ends_sentence(word: &str) -> bool {
+    let word = word.trim_end_matches(['"', '\'', ')', ']']);
+    if word.ends_with('?') || word.ends_with('!') {
+        return true;
+    }
+    let stem = match word.strip_suffix('.') {
+        Some(stem) => stem,
+        None => return false,
+    };
+    let stem = stem.trim_start_matches(['"', '\'', '(', '[']);
+    // "e.g.", "U.S." and friends; a bare "." still ends
+    !stem.contains('.') && !ABBREVIATIONS.contains(&stem.to_lowercase().as_str())
+}
+
+/// Sentences of a line, as their words
+fn split(text: &str) -> Vec<Vec<&str>> {
+    let mut sentences = Vec::new();
+    let mut sentence = Vec::new();
+    for word in text.split_whitespace() {
+        sentence.push(word);
+        if ends_sentence(word) {
+            sentences.push(std::mem::take(&mut sentence));
+        }
+    }
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+    sentences
+}
+
+/// Sentences of one line starting at `start_ms`: timed by `words` when they match the
+/// text word for word, otherwise spread over the words' span (or `speech_ms` without any)
+fn timed(line: usize, is_user: bool, text: &str, words: &[TranscriptWord], start_ms: u64, speech_ms: u64) -> Vec<Sentence> {
+    let split = split(text);
+    let total: usize = split.iter().map(Vec::len).sum();
+    let matched = words.len() == total;
+    let span_start = words.first().map_or(start_ms, |w| w.start_ms);
+    let span_ms = words.last().map_or(speech_ms, |w| w.end_ms.saturating_sub(span_start));
+    let at = |count: usize| span_start + span_ms * count as u64 / total.max(1) as u64;
+
+    let mut before = 0;
+    split.into_iter().map(|sentence| {
+        let after = before + sentence.len();
+        let (start_ms, end_ms) = if matched {
+            (words[before].start_ms, words[after - 1].end_ms)
+        } else {
+            (at(before), at(after))
+        };
+        before = after;
+        Sentence { line, is_user, text: sentence.join(" "), start_ms, end_ms, words: sentence.len() }
+    }).collect()
+}
+
+/// Sentences of a transcript line (`speech_ms` of audio, where known)
+pub fn of_line(index: usize, line: &TranscriptLine, speech_ms: u64) -> Vec<Sentence> {
+    timed(index, line.is_user, &line.text, &line.words, line.offset_ms, speech_ms)
+}
+
+/// Sentences of a finished transcript; lines without word timings are taken to run at
+/// MS_PER_WORD a word, up to the next line
+pub fn segment(transcript: &[TranscriptLine]) -> Vec<Sentence> {
+    transcript.iter().enumerate().flat_map(|(index, line)| {
+        let estimate = line.text.split_whitespace().count() as u64 * MS_PER_WORD;
+        let speech_ms = transcript.get(index + 1)
+            .map_or(estimate, |next| estimate.min(next.offset_ms.saturating_sub(line.offset_ms)));
+        of_line(index, line, speech_ms)
+    }).collect()
+}
+
+/// Start of a new call
+pub fn begin_call() {
+    *CALL.lock().unwrap() = CallSentences::default();
+}
+
+/// Split a final line of the call in progress (`speech_ms` of audio first captured at
+/// `capture_ms`, words on the capture clock, which `offset_of` turns into session
+/// offsets) and keep its sentences
+pub fn observe(text: &str, is_user: bool, speech_ms: u64, capture_ms: u64, words: &[TranscriptWord], offset_of: impl Fn(u64) -> u64) -> Vec<Sentence> {
+    let words: Vec<TranscriptWord> = words.iter()
+        .map(|w| TranscriptWord {
+            start_ms: offset_of(w.start_ms),
+            end_ms: offset_of(w.end_ms),
+            ..w.clone()
+        })
+        .collect();
+    let mut call = CALL.lock().unwrap();
+    let sentences = timed(call.lines, is_user, text, &words, offset_of(capture_ms), speech_ms);
+    call.lines += 1;
+    call.sentences.extend(sentences.iter().cloned());
+    sentences
+}
+
+/// Read the sentences of the call in progress
+pub fn with_current<T>(read: impl FnOnce(&[Sentence]) -> T) -> T {
+    read(&CALL.lock().unwrap().sentences)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, start_ms: u64, end_ms: u64) -> TranscriptWord {
+        TranscriptWord { word: word.to_string(), start_ms, end_ms, confidence: None }
+    }
+
+    #[test]
+    fn test_lines_split_into_timed_sentences() {
+        let texts = |sentences: Vec<Sentence>| sentences.into_iter().map(|s| s.text).collect::<Vec<_>>();
+        assert_eq!(
+            texts(timed(0, false, "Mr. Smith said it costs $2.5 million, e.g. the U.S. license. Is that right? Yes", &[], 0, 0)),
+            vec!["Mr. Smith said it costs $2.5 million, e.g. the U.S. license.", "Is that right?", "Yes"],
+        );
+
+        // Word timings time each sentence
+        let words = vec![word("Sounds", 1_000, 1_300), word("good.", 1_300, 1_600), word("What's", 2_400, 2_700),
+            word("the", 2_700, 2_800), word("price?", 2_800, 3_200)];
+        let sentences = timed(3, false, "Sounds good. What's the price?", &words, 1_000, 2_200);
+        assert_eq!(sentences.iter().map(|s| (s.line, s.start_ms, s.end_ms, s.words)).collect::<Vec<_>>(),
+            vec![(3, 1_000, 1_600, 2), (3, 2_400, 3_200, 3)]);
+
+        // Without them the line's speech time is spread by word count
+        let sentences = timed(0, true, "One two three. Four two.", &[], 10_000, 2_500);
+        assert_eq!(sentences.iter().map(|s| (s.start_ms, s.end_ms)).collect::<Vec<_>>(),
+            vec![(10_000, 11_500), (11_500, 12_500)]);
+        assert_eq!(sentences[0].duration_ms() + sentences[1].duration_ms(), 2_500);
+
+        // Stored lines without timings run at MS_PER_WORD, up to the next line
+        let line = |offset_ms: u64, text: &str| TranscriptLine { offset_ms, is_user: false, text: text.to_string(), words: Vec::new() };
+        let sentences = segment(&[line(0, "One two. Three four five six."), line(1_000, "Okay.")]);
+        assert_eq!(sentences.iter().map(|s| (s.line, s.start_ms, s.end_ms)).collect::<Vec<_>>(),
+            vec![(0, 0, 333), (0, 333, 1_000), (1, 1_000, 1_400)]);
+    }
+}
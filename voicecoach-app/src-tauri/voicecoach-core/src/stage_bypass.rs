@@ -0,0 +1,81 @@
+// Stage Bypass - take single audio pipeline stages out at runtime to bisect problems
+// "No transcription" on one rep's machine can't always be reproduced elsewhere, so
+// support switches stages off one at a time in the shipped build and watches what
+// changes:
+//   vad              every buffer counts as speech: the adaptive threshold and Vosk's
+//                    minimum-speech filtering of finals, the cloud silence gate, and the
+//                    hold / headset-mute pauses let all audio through
+//   resampler        Vosk is given the device's own rate and resamples internally
+//                    (applies from the next start of the stream)
+//   preprocessing    no input gain (calibrated or auto-adjusted), no noise suppression
+//   loopback_silence the system / app audio (the prospect's side) is replaced by silence
+// The audio callbacks read one atomic bitmask. Bypasses are never stored: a restart
+// puts every stage back.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use log::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Vad,
+    Resampler,
+    Preprocessing,
+    LoopbackSilence,
+}
+
+const STAGES: [PipelineStage; 4] = [PipelineStage::Vad, PipelineStage::Resampler, PipelineStage::Preprocessing, PipelineStage::LoopbackSilence];
+
+static BYPASSED: AtomicU32 = AtomicU32::new(0);
+
+fn bit(stage: PipelineStage) -> u32 {
+    1 << stage as u32
+}
+
+fn stages(mask: u32) -> Vec<PipelineStage> {
+    STAGES.iter().copied().filter(|&stage| mask & bit(stage) != 0).collect()
+}
+
+/// Whether a stage is switched off (audio-thread safe)
+pub fn bypassed(stage: PipelineStage) -> bool {
+    BYPASSED.load(Ordering::Relaxed) & bit(stage) != 0
+}
+
+/// Switch a stage off (or back on); returns every stage now bypassed
+pub fn set(stage: PipelineStage, bypass: bool) -> Vec<PipelineStage> {
+    let previous = if bypass {
+        BYPASSED.fetch_or(bit(stage), Ordering::Relaxed)
+    } else {
+        BYPASSED.fetch_and(!bit(stage), Ordering::Relaxed)
+    };
+    let mask = if bypass { previous | bit(stage) } else { previous & !bit(stage) };
+    warn!("🔧 Pipeline stage {:?} {}", stage, if bypass { "BYPASSED" } else { "restored" });
+    stages(mask)
+}
+
+/// Every stage bypassed now
+pub fn current() -> Vec<PipelineStage> {
+    stages(BYPASSED.load(Ordering::Relaxed))
+}
+
+/// Put every stage back
+pub fn clear() {
+    if BYPASSED.swap(0, Ordering::Relaxed) != 0 {
+        warn!("🔧 Every pipeline stage restored");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages_map_to_distinct_bits() {
+        let mask = bit(PipelineStage::Vad) | bit(PipelineStage::LoopbackSilence);
+        assert_eq!(stages(mask), vec![PipelineStage::Vad, PipelineStage::LoopbackSilence]);
+        assert_eq!(stages(mask & !bit(PipelineStage::Vad)), vec![PipelineStage::LoopbackSilence]);
+        assert_eq!(STAGES.iter().map(|&s| bit(s)).fold(0, |all, b| all | b).count_ones(), 4);
+        assert!(stages(0).is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+// Talk Over - the rep starting to speak while the prospect is mid-sentence
+// Cutting prospects off is one of the habits reps most want coached out of them, and it
+// can only be coached while it happens. Both sides' capture callbacks own a tracker that
+// turns their audio into 20 ms voiced/unvoiced frames on the shared capture clock (the
+// prospect side also when it is only metered under one-party consent). The rep talks
+// over the prospect when they start speaking while the prospect is still speaking,
+// the prospect has been at it for a while (mid-sentence, not a pause between sentences),
+// and the rep keeps going long enough that it isn't a "mm-hm". Sensitivity sets those
+// durations. Every interruption is counted for the call's metrics; the rep gets a gentle
+// "talk_over_nudge" (sent to the tracker's EventSink) unless one was shown within the
+// cooldown.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use log::{info, error};
+
+use crate::events::EventSink;
+
+const FRAME_MS: u64 = 20;
+// Unvoiced time that still belongs to the same stretch of speech (between words)
+const SPEECH_GAP_MS: u64 = 350;
+// Threshold when a source has no level calibration and no learned threshold yet
+const DEFAULT_THRESHOLD: f32 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TalkOverSensitivity {
+    Low,      // Only clear, sustained interruptions
+    Medium,
+    High,     // Short overlaps count too
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TalkOverSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_sensitivity")]
+    pub sensitivity: TalkOverSensitivity,
+    /// Seconds after a nudge before the next one (interruptions are still counted)
+    #[serde(default = "default_cooldown_seconds")]
+    pub cooldown_seconds: u32,
+}
+
+fn default_true() -> bool { true }
+fn default_sensitivity() -> TalkOverSensitivity { TalkOverSensitivity::Medium }
+fn default_cooldown_seconds() -> u32 { 30 }
+
+impl Default for TalkOverSettings {
+    fn default() -> Self {
+        Self { enabled: true, sensitivity: default_sensitivity(), cooldown_seconds: default_cooldown_seconds() }
+    }
+}
+
+// Payload of "talk_over_nudge"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct TalkOverNudge {
+    pub offset_ms: u64,              // Time since the call started (where the rep came in)
+    pub prospect_speaking_ms: u64,   // How long the prospect had been speaking
+    pub interruptions: usize,        // In this call so far
+}
+
+/// (rep speech, prospect speech before the rep came in) that make an interruption
+fn thresholds(sensitivity: TalkOverSensitivity) -> (u64, u64) {
+    match sensitivity {
+        TalkOverSensitivity::Low => (800, 1_500),
+        TalkOverSensitivity::Medium => (500, 1_000),
+        TalkOverSensitivity::High => (300, 600),
+    }
+}
+
+/// A side's current stretch of speech on the capture clock
+#[derive(Default)]
+struct Speech {
+    start_ms: Option<u64>,
+    last_voiced_ms: u64,
+}
+
+impl Speech {
+    fn frame(&mut self, capture_ms: u64, voiced: bool) {
+        if !voiced {
+            return;
+        }
+        if self.start_ms.is_none() || capture_ms > self.last_voiced_ms + SPEECH_GAP_MS {
+            self.start_ms = Some(capture_ms);
+        }
+        self.last_voiced_ms = capture_ms + FRAME_MS;
+    }
+
+    /// Start of the stretch of speech still going at `at_ms`
+    fn speaking_since(&self, at_ms: u64) -> Option<u64> {
+        self.start_ms.filter(|_| at_ms <= self.last_voiced_ms + SPEECH_GAP_MS)
+    }
+}
+
+/// An interruption found: (where the rep came in, prospect speech before it)
+type Interruption = (u64, u64);
+
+#[derive(Default)]
+struct Detector {
+    rep: Speech,
+    prospect: Speech,
+    // Start of the rep stretch already counted (one interruption per stretch)
+    counted: Option<u64>,
+    interruptions: usize,
+    last_nudge_ms: Option<u64>,
+}
+
+impl Detector {
+    fn prospect_frame(&mut self, capture_ms: u64, voiced: bool) {
+        self.prospect.frame(capture_ms, voiced);
+    }
+
+    fn rep_frame(&mut self, capture_ms: u64, voiced: bool, sensitivity: TalkOverSensitivity) -> Option<Interruption> {
+        self.rep.frame(capture_ms, voiced);
+        let rep_start = self.rep.speaking_since(capture_ms)?;
+        if !voiced || self.counted == Some(rep_start) {
+            return None;
+        }
+        let (min_rep_ms, min_prospect_ms) = thresholds(sensitivity);
+        // The prospect was speaking when the rep came in, and still is
+        let prospect_start = self.prospect.speaking_since(rep_start)?;
+        self.prospect.speaking_since(capture_ms)?;
+        let prospect_ms = rep_start.saturating_sub(prospect_start);
+        if prospect_ms < min_prospect_ms || self.rep.last_voiced_ms.saturating_sub(rep_start) < min_rep_ms {
+            return None;
+        }
+        self.counted = Some(rep_start);
+        self.interruptions += 1;
+        Some((rep_start, prospect_ms))
+    }
+
+    /// Whether an interruption at `capture_ms` gets a nudge (outside the cooldown)
+    fn nudge(&mut self, capture_ms: u64, cooldown_ms: u64) -> bool {
+        if self.last_nudge_ms.map_or(false, |last| capture_ms < last + cooldown_ms) {
+            return false;
+        }
+        self.last_nudge_ms = Some(capture_ms);
+        true
+    }
+}
+
+static DETECTOR: Lazy<Mutex<Detector>> = Lazy::new(|| Mutex::new(Detector::default()));
+
+/// Frames one side's capture stream (one tracker per stream)
+pub struct TalkOverTracker {
+    sink: Arc<dyn EventSink>,
+    // Capture time to the call's transcript clock, for the nudge's offset
+    offset_of: fn(u64) -> u64,
+    is_user: bool,
+    settings: TalkOverSettings,
+    threshold: f32,
+    frame_len: usize,
+    sum_squares: f32,
+    filled: usize,
+    frame_start_ms: Option<u64>,
+}
+
+impl TalkOverTracker {
+    /// A tracker for the rep's (`is_user`) or the prospect's stream, voiced above the
+    /// source's calibrated `threshold` when it has one
+    pub fn new(sink: Arc<dyn EventSink>, offset_of: fn(u64) -> u64, is_user: bool, sample_rate: u32,
+        settings: TalkOverSettings, threshold: Option<f32>) -> Self {
+        Self {
+            sink,
+            offset_of,
+            is_user,
+            settings,
+            threshold: threshold.unwrap_or(DEFAULT_THRESHOLD),
+            frame_len: (sample_rate as u64 * FRAME_MS / 1000).max(1) as usize,
+            sum_squares: 0.0,
+            filled: 0,
+            frame_start_ms: None,
+        }
+    }
+
+    /// Frame a mono buffer whose first sample was captured at `capture_ms`, voiced
+    /// above `threshold` (the stream's learned VAD threshold, when it has one)
+    pub fn observe(&mut self, samples: &[f32], capture_ms: u64, threshold: Option<f32>) {
+        let threshold = threshold.unwrap_or(self.threshold);
+        let sample_ms = |i: usize| capture_ms + i as u64 * FRAME_MS / self.frame_len as u64;
+        let mut frames = Vec::new();
+        for (i, &sample) in samples.iter().enumerate() {
+            let at = *self.frame_start_ms.get_or_insert_with(|| sample_ms(i));
+            self.sum_squares += sample * sample;
+            self.filled += 1;
+            if self.filled < self.frame_len {
+                continue;
+            }
+            frames.push((at, (self.sum_squares / self.filled as f32).sqrt() >= threshold));
+            self.sum_squares = 0.0;
+            self.filled = 0;
+            self.frame_start_ms = None;
+        }
+        if frames.is_empty() {
+            return;
+        }
+
+        let mut found = Vec::new();
+        {
+            let mut detector = DETECTOR.lock().unwrap();
+            for (at, voiced) in frames {
+                if !self.is_user {
+                    detector.prospect_frame(at, voiced);
+                    continue;
+                }
+                if let Some((rep_start, prospect_ms)) = detector.rep_frame(at, voiced, self.settings.sensitivity) {
+                    let nudge = detector.nudge(rep_start, self.settings.cooldown_seconds as u64 * 1000);
+                    found.push((rep_start, prospect_ms, detector.interruptions, nudge));
+                }
+            }
+        }
+        for (rep_start, prospect_ms, interruptions, nudge) in found {
+            interrupted(self.sink.clone(), (self.offset_of)(rep_start), prospect_ms, interruptions, nudge);
+        }
+    }
+}
+
+/// Nudge the rep about an interruption (off the audio thread)
+fn interrupted(sink: Arc<dyn EventSink>, offset_ms: u64, prospect_ms: u64, interruptions: usize, nudge: bool) {
+    std::thread::spawn(move || {
+        let event = TalkOverNudge {
+            offset_ms,
+            prospect_speaking_ms: prospect_ms,
+            interruptions,
+        };
+        info!("🗣️ Rep talked over the prospect at {} s ({} this call){}", event.offset_ms / 1000, interruptions, if nudge { "" } else { " - nudge cooling down" });
+        if nudge {
+            if let Err(e) = sink.emit("talk_over_nudge", event) {
+                error!("Failed to emit talk_over_nudge: {:?}", e);
+            }
+        }
+    });
+}
+
+/// Start of a new call
+pub fn begin_call() {
+    *DETECTOR.lock().unwrap() = Detector::default();
+}
+
+/// Interruptions in the call so far
+pub fn interruptions() -> usize {
+    DETECTOR.lock().unwrap().interruptions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Voiced frames of one side from `from_ms` to `to_ms`
+    fn speak(detector: &mut Detector, rep: bool, from_ms: u64, to_ms: u64) -> Vec<Interruption> {
+        (from_ms..to_ms).step_by(FRAME_MS as usize)
+            .filter_map(|at| if rep { detector.rep_frame(at, true, TalkOverSensitivity::Medium) } else { detector.prospect_frame(at, true); None })
+            .collect()
+    }
+
+    #[test]
+    fn test_rep_coming_in_mid_sentence_is_counted_once() {
+        let mut detector = Detector::default();
+        // Prospect speaks 0-3 s; the rep comes in at 1.5 s and keeps talking
+        speak(&mut detector, false, 0, 1_500);
+        let mut found = Vec::new();
+        for at in (1_500..3_000).step_by(FRAME_MS as usize) {
+            detector.prospect_frame(at, true);
+            found.extend(detector.rep_frame(at, true, TalkOverSensitivity::Medium));
+        }
+        assert_eq!(found, vec![(1_500, 1_500)]);
+        assert_eq!(detector.interruptions, 1);
+
+        // A short "mm-hm" over the prospect is not an interruption
+        let mut detector = Detector::default();
+        speak(&mut detector, false, 0, 3_000);
+        assert!(speak(&mut detector, true, 1_500, 1_800).is_empty());
+
+        // Answering after the prospect stopped is not one either
+        let mut detector = Detector::default();
+        speak(&mut detector, false, 0, 2_000);
+        assert!(speak(&mut detector, true, 2_500, 4_000).is_empty());
+
+        // Nudges respect the cooldown
+        assert!(detector.nudge(10_000, 30_000));
+        assert!(!detector.nudge(20_000, 30_000));
+        assert!(detector.nudge(40_000, 30_000));
+    }
+}
@@ -0,0 +1,25 @@
+// Transcript - the timed lines a call's transcript is kept as
+// Sessions store these, and the sentence, question and confidence analytics work on
+// them. Live lines are timed on the capture clock until they're recorded; stored ones
+// are offsets from the session start.
+
+use serde::{Deserialize, Serialize};
+
+/// A transcript word, timed from the session start
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TranscriptWord {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    #[serde(default)]
+    pub confidence: Option<f32>,     // 0-1, when the engine gave one
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TranscriptLine {
+    pub offset_ms: u64,              // Time since the call started
+    pub is_user: bool,
+    pub text: String,
+    #[serde(default)]
+    pub words: Vec<TranscriptWord>,  // Empty when the engine gave no word timings
+}
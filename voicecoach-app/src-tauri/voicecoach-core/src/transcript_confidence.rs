@@ -0,0 +1,140 @@
+// Transcript Confidence - how far each part of the transcript can be trusted
+// Before a rep quotes the transcript back to a customer ("you said the fifteenth"),
+// they need to know whether the engine actually heard it. Every final
+// "voice_transcription" event carries the segment's confidence and each word's, and
+// get_transcript_confidence returns the same for a whole session, so the UI can shade
+// the transcript as a heatmap. A segment's confidence is the mean of its words' (the
+// figure audio_snippets and transcript_quality use too); each word and segment also
+// gets a level - high, medium, low, or unknown where the engine gave no confidence
+// (Vosk with word timing off, imported transcripts, partials).
+
+use serde::{Deserialize, Serialize};
+
+use crate::transcript::{TranscriptLine, TranscriptWord};
+
+// At or above: shown as trustworthy
+const HIGH_CONFIDENCE: f32 = 0.85;
+// Below: shown as doubtful (audio_snippets' default threshold)
+const LOW_CONFIDENCE: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfidenceLevel {
+    High,
+    Medium,
+    Low,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct WordConfidence {
+    pub word: String,
+    pub start_ms: u64,               // Time since the call started
+    pub end_ms: u64,
+    pub confidence: Option<f32>,
+    pub level: ConfidenceLevel,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct LineConfidence {
+    pub index: usize,
+    pub offset_ms: u64,
+    pub is_user: bool,
+    pub text: String,
+    pub confidence: Option<f32>,     // Mean of the words'
+    pub level: ConfidenceLevel,
+    pub words: Vec<WordConfidence>,  // Empty without word timings
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct TranscriptConfidence {
+    pub session_id: String,
+    pub mean_confidence: Option<f32>,
+    pub low_lines: usize,
+    pub lines: Vec<LineConfidence>,
+}
+
+fn level(confidence: Option<f32>) -> ConfidenceLevel {
+    match confidence {
+        Some(c) if c >= HIGH_CONFIDENCE => ConfidenceLevel::High,
+        Some(c) if c >= LOW_CONFIDENCE => ConfidenceLevel::Medium,
+        Some(_) => ConfidenceLevel::Low,
+        None => ConfidenceLevel::Unknown,
+    }
+}
+
+/// Mean confidence of the words that have one
+pub fn segment_confidence(words: &[TranscriptWord]) -> Option<f32> {
+    let scores: Vec<f32> = words.iter().filter_map(|w| w.confidence).collect();
+    (!scores.is_empty()).then(|| scores.iter().sum::<f32>() / scores.len() as f32)
+}
+
+fn word_confidence(word: &TranscriptWord, offset_ms: impl Fn(u64) -> u64) -> WordConfidence {
+    WordConfidence {
+        word: word.word.clone(),
+        start_ms: offset_ms(word.start_ms),
+        end_ms: offset_ms(word.end_ms),
+        confidence: word.confidence,
+        level: level(word.confidence),
+    }
+}
+
+/// Words of a live segment (timed on the capture clock, which `offset_of` turns into
+/// session offsets) for its event
+pub fn live_words(words: &[TranscriptWord], offset_of: impl Fn(u64) -> u64) -> Vec<WordConfidence> {
+    words.iter().map(|w| word_confidence(w, &offset_of)).collect()
+}
+
+fn line_confidence(index: usize, line: &TranscriptLine) -> LineConfidence {
+    let confidence = segment_confidence(&line.words);
+    LineConfidence {
+        index,
+        offset_ms: line.offset_ms,
+        is_user: line.is_user,
+        text: line.text.clone(),
+        confidence,
+        level: level(confidence),
+        words: line.words.iter().map(|w| word_confidence(w, |ms| ms)).collect(),
+    }
+}
+
+/// Confidence report of a session's transcript
+pub fn transcript_confidence(session_id: String, transcript: &[TranscriptLine]) -> TranscriptConfidence {
+    let lines: Vec<LineConfidence> = transcript.iter().enumerate().map(|(i, line)| line_confidence(i, line)).collect();
+    let words: Vec<TranscriptWord> = transcript.iter().flat_map(|l| l.words.iter().cloned()).collect();
+    TranscriptConfidence {
+        session_id,
+        mean_confidence: segment_confidence(&words),
+        low_lines: lines.iter().filter(|l| l.level == ConfidenceLevel::Low).count(),
+        lines,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, confidence: Option<f32>) -> TranscriptWord {
+        TranscriptWord { word: word.to_string(), start_ms: 1_000, end_ms: 1_400, confidence }
+    }
+
+    #[test]
+    fn test_lines_and_words_are_graded_by_confidence() {
+        let transcript = vec![
+            TranscriptLine { offset_ms: 1_000, is_user: false, text: "by the fifteenth".to_string(),
+                words: vec![word("by", Some(0.9)), word("the", Some(0.7)), word("fifteenth", Some(0.3))] },
+            TranscriptLine { offset_ms: 3_000, is_user: true, text: "great".to_string(), words: vec![word("great", Some(0.95))] },
+            TranscriptLine { offset_ms: 5_000, is_user: true, text: "imported line".to_string(), words: Vec::new() },
+        ];
+        let report = transcript_confidence("s1".to_string(), &transcript);
+
+        let levels: Vec<ConfidenceLevel> = report.lines[0].words.iter().map(|w| w.level).collect();
+        assert_eq!(levels, vec![ConfidenceLevel::High, ConfidenceLevel::Medium, ConfidenceLevel::Low]);
+        assert!((report.lines[0].confidence.unwrap() - 0.633_333).abs() < 1e-5);
+        assert_eq!(report.lines[0].level, ConfidenceLevel::Medium);
+        assert_eq!(report.lines[1].level, ConfidenceLevel::High);
+        assert_eq!((report.lines[2].confidence, report.lines[2].level), (None, ConfidenceLevel::Unknown));
+        assert_eq!(report.low_lines, 0);
+        assert!((report.mean_confidence.unwrap() - 0.7125).abs() < 1e-6);
+    }
+}
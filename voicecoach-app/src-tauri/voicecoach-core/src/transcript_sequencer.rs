@@ -0,0 +1,125 @@
+// Transcript Sequencer - keeps final segments in capture order
+// Engines finish segments on their own threads and connections (Deepgram stage
+// re-biasing briefly runs two sockets), so finals can complete out of order. Audio
+// is stamped on a process-wide monotonic capture clock when it is captured; each
+// final is submitted with the capture time of its first audio and held for a short
+// window, then released in capture order with a gap-free segment index and a
+// monotonic timestamp (wall clock from the session's anchor). A segment arriving after a later one was already released
+// is emitted immediately (counted as late) rather than dropped.
+
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use log::warn;
+
+// How long a final may wait for earlier-captured segments
+const HOLD_MS: u64 = 400;
+const TICK_MS: u64 = 50;
+
+/// Runs the emission for a released segment: (segment_index, timestamp_ms)
+pub type Release = Box<dyn FnOnce(u64, u64) + Send>;
+
+struct Pending<T> {
+    capture_ms: u64,
+    arrived: Instant,
+    item: T,
+}
+
+struct Sequencer<T> {
+    pending: Vec<Pending<T>>,
+    next_index: u64,
+    last_released_ms: u64,
+    late: u64,
+}
+
+impl<T> Sequencer<T> {
+    fn new() -> Self {
+        Self { pending: Vec::new(), next_index: 0, last_released_ms: 0, late: 0 }
+    }
+
+    fn submit(&mut self, capture_ms: u64, arrived: Instant, item: T) {
+        // Stable sort keeps submission order for segments captured at the same time
+        let position = self.pending.iter().position(|p| p.capture_ms > capture_ms).unwrap_or(self.pending.len());
+        self.pending.insert(position, Pending { capture_ms, arrived, item });
+    }
+
+    /// Segments ready at `now`, in capture order: everything up to the last one
+    /// whose hold expired (earlier captures are released with it)
+    fn release_due(&mut self, now: Instant) -> Vec<(u64, u64, T)> {
+        let hold = Duration::from_millis(HOLD_MS);
+        let due = match self.pending.iter().rposition(|p| now.duration_since(p.arrived) >= hold) {
+            Some(index) => index + 1,
+            None => return Vec::new(),
+        };
+        self.pending.drain(..due)
+            .map(|p| {
+                if p.capture_ms < self.last_released_ms {
+                    self.late += 1;
+                }
+                self.last_released_ms = self.last_released_ms.max(p.capture_ms);
+                let index = self.next_index;
+                self.next_index += 1;
+                (index, self.last_released_ms, p.item)
+            })
+            .collect()
+    }
+}
+
+static CLOCK_START: Lazy<Instant> = Lazy::new(Instant::now);
+static SEQUENCER: Lazy<Mutex<Sequencer<Release>>> = Lazy::new(|| Mutex::new(Sequencer::new()));
+static RELEASE_THREAD: Once = Once::new();
+
+/// Monotonic capture clock (ms); stamp audio with this when it is captured
+pub fn capture_ms() -> u64 {
+    CLOCK_START.elapsed().as_millis() as u64
+}
+
+/// Start of a new call: segment indices restart at 0
+pub fn begin_call() {
+    let mut sequencer = SEQUENCER.lock().unwrap();
+    sequencer.next_index = 0;
+    if sequencer.late > 0 {
+        warn!("⚠️ {} transcript segment(s) arrived too late to reorder last call", sequencer.late);
+        sequencer.late = 0;
+    }
+}
+
+/// Queue a final segment captured at `capture_ms`; `release` runs on the sequencer
+/// thread once the segment is next in capture order. `wall_ms` turns capture time into
+/// the session's wall clock (the first caller's is used for the process).
+pub fn submit(capture_ms: u64, wall_ms: fn(u64) -> u64, release: Release) {
+    RELEASE_THREAD.call_once(|| {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(Duration::from_millis(TICK_MS));
+            let due = SEQUENCER.lock().unwrap().release_due(Instant::now());
+            for (index, released_ms, release) in due {
+                release(index, wall_ms(released_ms));
+            }
+        });
+    });
+    SEQUENCER.lock().unwrap().submit(capture_ms, Instant::now(), release);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments_release_in_capture_order() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut sequencer = Sequencer::new();
+
+        // Captured 2000 arrives first, 1000 shortly after
+        sequencer.submit(2000, at(0), "second");
+        sequencer.submit(1000, at(100), "first");
+        assert!(sequencer.release_due(at(300)).is_empty());
+        let released: Vec<(u64, &str)> = sequencer.release_due(at(450)).into_iter().map(|(i, _, t)| (i, t)).collect();
+        assert_eq!(released, vec![(0, "first"), (1, "second")]);
+
+        // Too late to reorder: released with a timestamp that does not go backwards
+        sequencer.submit(1500, at(500), "late");
+        let (index, released_ms, _) = sequencer.release_due(at(900)).remove(0);
+        assert_eq!((index, released_ms, sequencer.late), (2, 2000, 1));
+    }
+}
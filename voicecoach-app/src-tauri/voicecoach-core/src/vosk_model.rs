@@ -0,0 +1,149 @@
+// Vosk Model - what an installed model directory holds, and loading it
+// Vosk loads any Kaldi model directory: the large models with a static HCLG graph and
+// language model rescoring, and pruned builds (the small models, "lgraph" variants)
+// that compose a lookahead graph from HCLr.fst and Gr.fst while decoding and may skip
+// rescoring. Quantized builds load the same way - Vosk reads whatever am/final.mdl
+// holds - so they are only told apart by the "int8"/"quant" their publishers put in
+// the name. vosk::Model::new only answers yes or no, so a directory is checked here
+// first: an archive extracted with an extra folder level (the model inside a folder of
+// the same name) is followed, and a missing acoustic model, feature config or graph is
+// named in the error.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use log::info;
+
+const ACOUSTIC_MODEL: &str = "am/final.mdl";
+const FEATURE_CONFIG: &str = "conf/mfcc.conf";
+const STATIC_GRAPH: &str = "graph/HCLG.fst";
+const LOOKAHEAD_GRAPH: [&str; 2] = ["graph/HCLr.fst", "graph/Gr.fst"];
+const RESCORING: [&str; 2] = ["rescore", "rnnlm"];
+// Name markers of quantized builds
+const QUANTIZED_MARKERS: [&str; 2] = ["int8", "quant"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodingGraph {
+    Static,     // HCLG.fst built ahead of time
+    Lookahead,  // HCLr.fst + Gr.fst composed while decoding (pruned builds)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ModelLayout {
+    pub path: String,           // The directory Vosk loads (an extra folder level followed)
+    pub graph: DecodingGraph,
+    pub rescoring: bool,        // rescore/ or rnnlm/ language models present
+    pub quantized: bool,
+    pub size_mb: u64,
+}
+
+/// The directory under `path` that holds the model: itself, or its only subfolder
+pub fn resolve_dir(path: &str) -> Result<PathBuf> {
+    let dir = Path::new(path);
+    if !dir.is_dir() {
+        return Err(anyhow!("Vosk model not found at: {}", path));
+    }
+    if dir.join(ACOUSTIC_MODEL).exists() {
+        return Ok(dir.to_path_buf());
+    }
+    let subfolders: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir())
+        .collect();
+    match subfolders.as_slice() {
+        [nested] if nested.join(ACOUSTIC_MODEL).exists() => Ok(nested.clone()),
+        _ => Err(anyhow!("Not a Vosk model (no {}): {}", ACOUSTIC_MODEL, path)),
+    }
+}
+
+fn size_bytes(path: &Path) -> u64 {
+    match std::fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| size_bytes(&e.path())).sum(),
+        Err(_) => std::fs::metadata(path).map_or(0, |m| m.len()),
+    }
+}
+
+/// Check a model directory and describe it
+pub fn inspect(path: &str) -> Result<ModelLayout> {
+    let dir = resolve_dir(path)?;
+    if !dir.join(FEATURE_CONFIG).exists() {
+        return Err(anyhow!("Incomplete Vosk model (no {}): {}", FEATURE_CONFIG, dir.display()));
+    }
+    let graph = if dir.join(STATIC_GRAPH).exists() {
+        DecodingGraph::Static
+    } else if LOOKAHEAD_GRAPH.iter().all(|file| dir.join(file).exists()) {
+        DecodingGraph::Lookahead
+    } else {
+        return Err(anyhow!("Incomplete Vosk model (no {} or {}): {}",
+            STATIC_GRAPH, LOOKAHEAD_GRAPH.join(" + "), dir.display()));
+    };
+    let name = dir.to_string_lossy().to_lowercase();
+
+    Ok(ModelLayout {
+        path: dir.to_string_lossy().to_string(),
+        graph,
+        rescoring: RESCORING.iter().any(|folder| dir.join(folder).is_dir()),
+        quantized: QUANTIZED_MARKERS.iter().any(|marker| name.contains(marker)),
+        size_mb: size_bytes(&dir) / (1024 * 1024),
+    })
+}
+
+/// Check and load a model, static, pruned or quantized
+pub fn load(path: &str) -> Result<(vosk::Model, ModelLayout)> {
+    let layout = inspect(path)?;
+    let start = Instant::now();
+    let model = vosk::Model::new(layout.path.as_str())
+        .ok_or_else(|| anyhow!("Failed to load Vosk model at: {}", layout.path))?;
+    info!("📦 Loaded Vosk model {} ({:?} graph{}{}, {} MB) in {:.2}s", layout.path, layout.graph,
+        if layout.rescoring { ", rescoring" } else { "" }, if layout.quantized { ", quantized" } else { "" },
+        layout.size_mb, start.elapsed().as_secs_f32());
+    Ok((model, layout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(dir: &Path, file: &str) {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"x").unwrap();
+    }
+
+    #[test]
+    fn test_layouts_are_checked_and_nested_folders_followed() {
+        let root = std::env::temp_dir().join("voicecoach_vosk_model_test");
+        let _ = std::fs::remove_dir_all(&root);
+
+        // Pruned, quantized build extracted with an extra folder level
+        let pruned = root.join("vosk-model-en-int8").join("vosk-model-en-int8");
+        for file in [ACOUSTIC_MODEL, FEATURE_CONFIG, LOOKAHEAD_GRAPH[0], LOOKAHEAD_GRAPH[1]] {
+            touch(&pruned, file);
+        }
+        let layout = inspect(root.join("vosk-model-en-int8").to_str().unwrap()).unwrap();
+        assert_eq!(Path::new(&layout.path), pruned.as_path());
+        assert_eq!(layout.graph, DecodingGraph::Lookahead);
+        assert!(layout.quantized && !layout.rescoring);
+
+        // Static graph with rescoring
+        let large = root.join("vosk-model-en-large");
+        for file in [ACOUSTIC_MODEL, FEATURE_CONFIG, STATIC_GRAPH, "rescore/G.carpa"] {
+            touch(&large, file);
+        }
+        let layout = inspect(large.to_str().unwrap()).unwrap();
+        assert_eq!(layout.graph, DecodingGraph::Static);
+        assert!(layout.rescoring && !layout.quantized);
+
+        // A missing graph is named
+        let broken = root.join("vosk-model-broken");
+        for file in [ACOUSTIC_MODEL, FEATURE_CONFIG, LOOKAHEAD_GRAPH[0]] {
+            touch(&broken, file);
+        }
+        assert!(inspect(broken.to_str().unwrap()).unwrap_err().to_string().contains("graph/HCLG.fst"));
+        assert!(inspect(root.to_str().unwrap()).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
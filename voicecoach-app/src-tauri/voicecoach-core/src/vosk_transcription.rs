@@ -0,0 +1,979 @@
+// Simple, working Vosk transcription for VoiceCoach
+// Based on the AI input recommendations for fast, accurate transcription
+// The mic stream, recognizer and warm standby live here; the app passes in the loaded
+// model and its vosk-config sections, and everything around the stream (device,
+// capture registry, detectors, where finals go) comes through CaptureHost.
+
+use vosk::{Model, Recognizer, CompleteResult};
+use cpal::traits::DeviceTrait;
+use std::sync::{Arc, Mutex};
+use serde::{Serialize, Deserialize};
+use log::{info, error, warn};
+use anyhow::{Result, anyhow};
+
+use crate::breadcrumb_system::BreadcrumbTrail;
+use crate::capture_host::{CaptureHost, Engine};
+use crate::events::EventSink;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RecognizerSettings {
+    pub sample_rate: u32,
+    pub partial_words: bool,
+    pub words: bool,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AudioProcessing {
+    pub min_buffer_size: usize,
+    pub silence_threshold: f32,
+    pub silence_buffers_for_pause: u32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BehaviorSettings {
+    pub emit_partials: bool,
+    pub reset_on_finalization: bool,
+    pub force_finalize_on_silence: bool,
+    #[serde(default = "default_pre_roll_seconds")]
+    pub pre_roll_seconds: u32,
+}
+
+fn default_pre_roll_seconds() -> u32 { 30 }
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DebuggingSettings {
+    pub enable_breadcrumbs: bool,
+    pub audio_level_log_frequency: u32,
+    pub log_processing_stats: bool,
+}
+
+// Utterance segmentation parameters (vosk-config "endpointing" section or set_endpointing);
+// keys left out of the section keep their defaults
+#[derive(Serialize, Deserialize, Clone, Debug, specta::Type)]
+#[serde(default)]
+pub struct EndpointingSettings {
+    pub min_silence_ms: u32,     // Trailing silence before Vosk finalizes an utterance
+    pub max_utterance_ms: u32,   // Force finalization after this much continuous speech
+    pub min_speech_ms: u32,      // Drop finals with less voiced audio than this (coughs, clicks)
+}
+
+impl Default for EndpointingSettings {
+    fn default() -> Self {
+        EndpointingSettings {
+            min_silence_ms: 500,
+            max_utterance_ms: 20000,
+            min_speech_ms: 250,
+        }
+    }
+}
+
+impl EndpointingSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.min_silence_ms < 100 || self.min_silence_ms > 5000 {
+            return Err(anyhow!("min_silence_ms must be between 100 and 5000 (got {})", self.min_silence_ms));
+        }
+        if self.max_utterance_ms < 1000 || self.max_utterance_ms > 120000 {
+            return Err(anyhow!("max_utterance_ms must be between 1000 and 120000 (got {})", self.max_utterance_ms));
+        }
+        if self.min_speech_ms >= self.max_utterance_ms {
+            return Err(anyhow!("min_speech_ms must be smaller than max_utterance_ms"));
+        }
+        Ok(())
+    }
+
+    // Push the delays into the recognizer's endpointer (seconds)
+    fn apply_to(&self, recognizer: &mut Recognizer) {
+        const MAX_LEADING_SILENCE_S: f32 = 5.0;  // Vosk default for t_start_max
+        recognizer.set_endpointer_delays(
+            MAX_LEADING_SILENCE_S,
+            self.min_silence_ms as f32 / 1000.0,
+            self.max_utterance_ms as f32 / 1000.0,
+        );
+    }
+}
+
+/// What a stream is opened with: the vosk-config sections and the machine's calibration
+#[derive(Clone, Debug)]
+pub struct StreamSettings {
+    pub recognizer: RecognizerSettings,
+    /// silence_threshold already replaced by the calibrated VAD threshold, if any
+    pub audio_processing: AudioProcessing,
+    pub behavior: BehaviorSettings,
+    pub debugging: DebuggingSettings,
+    /// As configured (a set_endpointing override takes precedence)
+    pub endpointing: EndpointingSettings,
+    /// Device buffer size (calibrated for the machine, else 250ms)
+    pub chunk_samples: u32,
+    /// Calibrated input gain
+    pub mic_gain: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize, specta::Type)]
+pub struct TranscriptionPayload {
+    pub text: String,
+    pub is_final: bool,
+    pub timestamp: u64,
+    pub is_user: bool,  // Identify if transcription is from user (true) or prospect (false)
+    pub led_number: u32,  // LED tracking number to identify event source
+    pub source: String,   // Source identifier (e.g., "vosk_final", "vosk_partial")
+    // Start of the speech on the session's audio clock (see audio_clock)
+    #[serde(default)]
+    pub audio_ms: u64,
+    // Finals only: position in the chronological transcript (see transcript_sequencer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_index: Option<u64>,
+    // Finals: mean word confidence and each word's, for the confidence heatmap (see transcript_confidence)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<crate::transcript_confidence::WordConfidence>,
+}
+
+// Global state for managing the transcription status (stream stored separately)
+static TRANSCRIPTION_RUNNING: once_cell::sync::Lazy<Arc<Mutex<bool>>> =
+    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(false)));
+
+// Simple stream ID to prevent duplicates (working solution)
+static CURRENT_STREAM_ID: once_cell::sync::Lazy<Arc<Mutex<u32>>> =
+    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(0)));
+
+// Audio buffer to accumulate samples before processing
+static AUDIO_BUFFER: once_cell::sync::Lazy<Arc<Mutex<Vec<i16>>>> =
+    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(Vec::new())));
+
+// Track last partial result to avoid duplicates
+static LAST_PARTIAL: once_cell::sync::Lazy<Arc<Mutex<String>>> =
+    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(String::new())));
+
+// Track silence for pause detection (counts consecutive silent buffers)
+static SILENCE_COUNTER: once_cell::sync::Lazy<Arc<Mutex<u32>>> =
+    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(0)));
+
+// Runtime endpointing override from set_endpointing (None = use vosk-config values)
+static ENDPOINTING_OVERRIDE: once_cell::sync::Lazy<Arc<Mutex<Option<EndpointingSettings>>>> =
+    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
+
+// Bumped on every set_endpointing so a running stream re-applies the settings
+static ENDPOINTING_VERSION: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+// Warm standby: the stream (and recognizer) of STANDBY is live, but while the gate
+// is closed every buffer is dropped at the top of the callback - nothing reaches the
+// recognizer, the audio tap, breadcrumbs or the transcript pipeline. The one exception
+// is the pre-roll: the last `pre_roll_seconds` of audio are kept in memory (never on
+// disk) so a call started a few seconds late is still transcribed from its beginning.
+static GATE_OPEN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+static DISCARDED_BUFFERS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+static STANDBY: once_cell::sync::Lazy<Arc<Mutex<Option<StandbyState>>>> =
+    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
+
+pub const DEFAULT_STANDBY_MINUTES: u32 = 30;
+
+struct StandbyState {
+    stream_id: u32,
+    capture: u64,  // The standby stream in the capture registry; closed (dropped) on release
+    entered_at: u64,
+    idle_since: u64,  // Last time the gate closed; drives auto-exit
+    auto_exit_minutes: u32,
+    pre_roll_seconds: u32,
+}
+
+// Pre-roll is replayed at this multiple of real time until the recognizer catches up
+const PRE_ROLL_CATCH_UP: usize = 2;
+// Owner name of the mic stream in the capture registry (privacy mute)
+pub const STREAM_OWNER: &str = "vosk";
+
+// Payload of "transcription_standby" and result of the standby commands.
+// The UI must show a persistent "mic warm - not recording" indicator while active.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct StandbyStatus {
+    pub active: bool,
+    pub listening: bool,  // Gate open = a call is being transcribed
+    pub entered_at: Option<u64>,
+    pub auto_exit_minutes: Option<u32>,
+    pub discarded_buffers: u64,
+    pub pre_roll_seconds: u32,  // Audio held in memory while the gate is closed (0 = none)
+}
+
+/// Effective endpointing settings: runtime override first, then the configured ones
+pub fn current_endpointing(configured: &EndpointingSettings) -> EndpointingSettings {
+    ENDPOINTING_OVERRIDE.lock().unwrap()
+        .clone()
+        .unwrap_or_else(|| configured.clone())
+}
+
+/// Override the configured endpointing (applies live if a stream is running)
+pub fn set_endpointing(settings: EndpointingSettings) -> Result<EndpointingSettings> {
+    settings.validate()?;
+
+    info!("🔧 set_endpointing: {:?}", settings);
+    *ENDPOINTING_OVERRIDE.lock().unwrap() = Some(settings.clone());
+    ENDPOINTING_VERSION.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+    Ok(settings)
+}
+
+// We'll manage the stream lifetime differently - just keep it running
+// The stream will be dropped when the app closes
+
+/// Word timings (with `words` on) moved from recognizer time onto the capture clock,
+/// anchored at the capture time of the utterance
+fn timed_words(host: &dyn CaptureHost, words: &[vosk::Word], utterance_capture_ms: u64) -> Vec<crate::transcript::TranscriptWord> {
+    let first = match words.first() {
+        Some(word) => word.start,
+        None => return Vec::new(),
+    };
+    let capture_ms = |seconds: f32| utterance_capture_ms + ((seconds - first) * 1000.0).max(0.0) as u64;
+    words.iter()
+        .map(|w| crate::transcript::TranscriptWord {
+            word: host.clean_text(w.word),
+            start_ms: capture_ms(w.start),
+            end_ms: capture_ms(w.end),
+            confidence: Some(w.conf),
+        })
+        .collect()
+}
+
+// Recognizer for the moment: the active recognition grammar, else the sales stage's
+// biased grammar when enabled, else unrestricted
+fn build_recognizer(host: &dyn CaptureHost, model: &Model, settings: &RecognizerSettings, endpointing: &EndpointingSettings) -> Option<Recognizer> {
+    let grammar = host.recognition_grammar()
+        .or_else(|| crate::sales_stage::vosk_grammar(&host.stage_bias()));
+    let mut recognizer = match grammar {
+        Some(grammar) => Recognizer::new_with_grammar(model, settings.sample_rate as f32, &grammar)?,
+        None => Recognizer::new(model, settings.sample_rate as f32)?,
+    };
+    recognizer.set_partial_words(settings.partial_words);
+    recognizer.set_words(settings.words);
+    endpointing.apply_to(&mut recognizer);
+    Some(recognizer)
+}
+
+/// Size of the buffered audio (memory budget)
+pub fn audio_buffer_bytes() -> usize {
+    AUDIO_BUFFER.lock().unwrap().len() * std::mem::size_of::<i16>()
+}
+
+/// Keep only the newest `max_bytes` of buffered audio; returns bytes dropped
+pub fn trim_audio_buffer(max_bytes: usize) -> usize {
+    let mut buffer = AUDIO_BUFFER.lock().unwrap();
+    let max_samples = max_bytes / std::mem::size_of::<i16>();
+    if buffer.len() <= max_samples {
+        return 0;
+    }
+    let excess = buffer.len() - max_samples;
+    buffer.drain(..excess);
+    buffer.shrink_to_fit();
+    excess * std::mem::size_of::<i16>()
+}
+
+/// Open the mic stream + recognizer on a loaded model, returning the stream ID and the
+/// stream's handle in the capture registry. With `gated` the stream starts in warm
+/// standby (audio discarded until the gate opens); otherwise it supersedes standby.
+pub fn open_stream(events: Arc<dyn EventSink>, host: Arc<dyn CaptureHost>, model: Arc<Model>, settings: StreamSettings, gated: bool) -> Result<(u32, u64), String> {
+    // Increment stream ID to invalidate any existing streams
+    let stream_id = {
+        let mut id = CURRENT_STREAM_ID.lock().unwrap();
+        *id += 1;
+        info!("📌 Starting new transcription stream with ID: {}", *id);
+        *id
+    };
+    if !gated {
+        STANDBY.lock().unwrap().take();
+    }
+    // Superseded streams are closed, not just ignored
+    host.close_streams(STREAM_OWNER);
+
+    // Create recognizer with configured sample rate, settings and utterance segmentation (endpointing)
+    let endpointing = current_endpointing(&settings.endpointing);
+    let recognizer = build_recognizer(&*host, &model, &settings.recognizer, &endpointing)
+        .ok_or_else(|| "Failed to create recognizer".to_string())?;
+    info!("Endpointing: {:?}", endpointing);
+
+    // Get audio input device
+    let device = host.input_device()
+        .ok_or("No input device available")?;
+
+    info!("Using audio device: {}", device.name().unwrap_or_default());
+
+    // Log supported configurations
+    if let Ok(configs) = device.supported_input_configs() {
+        info!("Supported audio configurations:");
+        for (i, config) in configs.enumerate() {
+            info!("  Config {}: channels={}, sample_rate={}-{}",
+                i,
+                config.channels(),
+                config.min_sample_rate().0,
+                config.max_sample_rate().0
+            );
+        }
+    }
+
+    // CRITICAL: Force 16kHz mono PCM configuration for Vosk
+    let config = cpal::StreamConfig {
+        channels: 1,  // MUST be mono for Vosk
+        sample_rate: cpal::SampleRate(16000),  // MUST be 16kHz for Vosk
+        buffer_size: cpal::BufferSize::Fixed(settings.chunk_samples),  // 250ms buffer at 16kHz unless calibrated
+    };
+
+    info!("Forcing optimal Vosk config: 16kHz mono PCM");
+
+    // Test if device supports this config
+    // The device's own sample format (converted to f32 for the pipeline)
+    let sample_format = crate::sample_format::input_format(&device);
+    let test_stream = crate::sample_format::build_input_stream(
+        &device,
+        &config,
+        sample_format,
+        |_: &[f32], _: &_| {},
+        |_| {},
+    );
+
+    let needs_resampling = match test_stream {
+        Ok(_) => {
+            info!("✅ Device supports 16kHz mono natively!");
+            false
+        }
+        Err(_) => {
+            // Device doesn't support 16kHz, use default and resample
+            warn!("Device doesn't support 16kHz, will use default rate and resample");
+            true
+        }
+    };
+
+    // If we need resampling, get the default config instead
+    let config = if needs_resampling {
+        let default_config = device.default_input_config()
+            .map_err(|e| format!("Failed to get default config: {}", e))?;
+        info!("Using device default: {} Hz, {} channels - will resample to 16kHz mono",
+            default_config.sample_rate().0, default_config.channels());
+
+        // CRITICAL: Force mono - Vosk ONLY works with mono audio!
+        // We were right the first time - force mono here
+        let mono_config = cpal::StreamConfig {
+            channels: 1,  // MUST be mono for Vosk
+            sample_rate: default_config.sample_rate(),
+            buffer_size: cpal::BufferSize::Default,
+        };
+
+        // Exclusive-mode conflicts: fall back to the device's shared format (may be stereo)
+        host.negotiate_input_config(STREAM_OWNER, &device, mono_config)?
+    } else {
+        config
+    };
+    // Shared-mode fallback can hand us multi-channel audio - downmixed in the callback
+    let input_channels = config.channels as usize;
+
+    // Get the actual sample rate we're using
+    let actual_sample_rate = config.sample_rate.0;
+    // Resampler bypassed (stage_bypass): Vosk gets the device rate and resamples internally
+    let mut recognizer_settings = settings.recognizer.clone();
+    let (recognizer, pipeline_rate) = if actual_sample_rate != 16000 && crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::Resampler) {
+        warn!("🔧 Resampler bypassed: Vosk takes {} Hz directly", actual_sample_rate);
+        recognizer_settings.sample_rate = actual_sample_rate;
+        (build_recognizer(&*host, &model, &recognizer_settings, &endpointing).ok_or("Failed to create recognizer")?, actual_sample_rate)
+    } else {
+        (recognizer, 16000)
+    };
+    let needs_resampling = actual_sample_rate != pipeline_rate;
+    // Tap files are 16kHz
+    let tap_compatible = pipeline_rate == 16000;
+    let recognizer = Arc::new(Mutex::new(recognizer));
+    let recognizer_clone = recognizer.clone();
+
+    // Use configuration values (the threshold is the calibrated one when the level wizard ran)
+    let _min_buffer_size = settings.audio_processing.min_buffer_size;
+    let silence_threshold = settings.audio_processing.silence_threshold;
+    let mic_gain = settings.mic_gain;
+    let _silence_buffers_for_pause = settings.audio_processing.silence_buffers_for_pause;
+    let emit_partials = settings.behavior.emit_partials;
+    let reset_on_finalization = settings.behavior.reset_on_finalization;
+    let _force_finalize_on_silence = settings.behavior.force_finalize_on_silence;
+    let enable_breadcrumbs = settings.debugging.enable_breadcrumbs;
+    let audio_level_log_frequency = settings.debugging.audio_level_log_frequency;
+    let _log_processing_stats = settings.debugging.log_processing_stats;
+
+    // Clone for the audio callback
+    let current_id = Arc::clone(&CURRENT_STREAM_ID);
+
+    // Stage vocabulary biasing: the recognizer is rebuilt between utterances when the stage changes
+    let bias_model = model.clone();
+    let mut applied_stage_generation = crate::sales_stage::generation();
+    // Recognition grammar mode: switched in when no utterance is in progress
+    let mut applied_grammar_generation = host.grammar_generation();
+    let mut grammar_mode = host.recognition_grammar().is_some();
+
+    // Endpointing state owned by the callback (re-applied when set_endpointing bumps the version)
+    let mut min_speech_ms = endpointing.min_speech_ms;
+    let mut current_endpointing = endpointing.clone();
+    let mut applied_endpointing_version = ENDPOINTING_VERSION.load(std::sync::atomic::Ordering::Relaxed);
+    let mut voiced_ms: u32 = 0;
+    // The app's processors on the rep's audio: mic quality, headset mute, the learned
+    // VAD threshold, talk-over, noise removal, snippets, the call recording and the
+    // wake-word command recognizer
+    let mut audio = host.stream_audio(true, pipeline_rate, Some(&model));
+    // Capture time of the current utterance's first buffer (orders finals across engines)
+    let mut utterance_capture_ms: Option<u64> = None;
+    // Buffers are stamped by the samples the device delivered
+    let mut sample_clock = crate::audio_clock::SampleClock::new(actual_sample_rate);
+    // Standby pre-roll (raw interleaved input), then the backlog while it is replayed
+    let pre_roll_capacity = if gated { settings.behavior.pre_roll_seconds as usize * actual_sample_rate as usize * input_channels } else { 0 };
+    let mut pre_roll: std::collections::VecDeque<f32> = std::collections::VecDeque::new();
+    let mut gate_was_open = !gated;
+
+    // Build the audio stream on a keeper thread (held open until closed)
+    GATE_OPEN.store(!gated, std::sync::atomic::Ordering::SeqCst);
+    let callback_host = host.clone();
+    let capture = host.open_stream(STREAM_OWNER, actual_sample_rate, true, Box::new(move || crate::sample_format::build_input_stream(
+        &device,
+        &config,
+        sample_format,
+        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+            let host = &*callback_host;
+            let _timing = crate::pipeline_stats::time_callback(crate::pipeline_stats::PipelineStream::VoskCapture, data.len() / input_channels.max(1), actual_sample_rate);
+            // Log that we received audio data
+            static CALLBACK_COUNT: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+            let count = CALLBACK_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            if count == 0 {
+                info!("🎙️ AUDIO CALLBACK FIRST CALL - Stream is working! Data length: {}", data.len());
+            }
+
+            // Check if this is still the current stream
+            {
+                let current = current_id.lock().unwrap();
+                if *current != stream_id {
+                    return; // This stream has been superseded
+                }
+            }
+
+            // Warm standby: drop the buffer before anything processes it (only the pre-roll is kept)
+            if !GATE_OPEN.load(std::sync::atomic::Ordering::Relaxed) {
+                if gate_was_open {
+                    // Call ended: never carry an unreplayed backlog into the next pre-roll
+                    pre_roll.clear();
+                    gate_was_open = false;
+                }
+                if pre_roll_capacity > 0 {
+                    pre_roll.extend(data.iter().copied());
+                    let excess = pre_roll.len().saturating_sub(pre_roll_capacity);
+                    pre_roll.drain(..excess);
+                }
+                DISCARDED_BUFFERS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                return;
+            }
+            gate_was_open = true;
+
+            // Sidetone monitors the live mic (never the replayed pre-roll)
+            host.sidetone(STREAM_OWNER, data, input_channels);
+
+            // Replay the pre-roll ahead of live audio, a few buffers' worth per callback
+            let replayed;
+            let (data, backlog_ms) = if pre_roll.is_empty() {
+                (data, 0)
+            } else {
+                pre_roll.extend(data.iter().copied());
+                let take = (data.len() * PRE_ROLL_CATCH_UP).min(pre_roll.len());
+                replayed = pre_roll.drain(..take).collect::<Vec<f32>>();
+                let backlog_ms = pre_roll.len() as u64 * 1000 / (actual_sample_rate as u64 * input_channels as u64);
+                (&replayed[..], backlog_ms)
+            };
+            let frames = data.len() / input_channels.max(1);
+            let captured_ms = sample_clock.stamp(crate::transcript_sequencer::capture_ms().saturating_sub(backlog_ms), frames);
+            let captured_end_ms = captured_ms + frames as u64 * 1000 / actual_sample_rate.max(1) as u64;
+
+            // Saturated pipeline: drop the buffer rather than block the audio thread
+            if crate::chaos::inject(crate::chaos::Fault::ChannelSaturation) {
+                return;
+            }
+
+            let downmixed;
+            let data = if input_channels > 1 {
+                downmixed = crate::sample_format::downmix_to_mono(data, input_channels);
+                &downmixed[..]
+            } else {
+                data
+            };
+
+            // Resample if needed (we're already in mono from the config)
+            let mut samples = if needs_resampling {
+                // Simple decimation for 48kHz -> 16kHz (ratio of 3:1)
+                // This is what was working before!
+                let ratio = actual_sample_rate / 16000;
+                if ratio == 3 {
+                    // Fast path for common 48kHz -> 16kHz conversion
+                    let mut resampled = Vec::with_capacity(data.len() / 3);
+                    for i in (0..data.len()).step_by(3) {
+                        resampled.push(data[i]);
+                    }
+
+                    // Log occasionally
+                    use std::sync::atomic::{AtomicU32, Ordering};
+                    static RESAMPLE_LOG_COUNTER: AtomicU32 = AtomicU32::new(0);
+                    let count = RESAMPLE_LOG_COUNTER.fetch_add(1, Ordering::Relaxed);
+                    if count % 100 == 0 {
+                        info!("Decimated {} samples to {} samples (48kHz->16kHz)",
+                            data.len(), resampled.len());
+                    }
+                    resampled
+                } else {
+                    // Linear interpolation for other ratios
+                    let ratio_f = actual_sample_rate as f32 / 16000.0;
+                    let output_len = (data.len() as f32 / ratio_f) as usize;
+                    let mut resampled = Vec::with_capacity(output_len);
+
+                    for i in 0..output_len {
+                        let src_idx = i as f32 * ratio_f;
+                        let idx_floor = src_idx.floor() as usize;
+                        let idx_ceil = (idx_floor + 1).min(data.len() - 1);
+                        let frac = src_idx - idx_floor as f32;
+
+                        let sample = if idx_floor < data.len() {
+                            data[idx_floor] * (1.0 - frac) + data[idx_ceil] * frac
+                        } else {
+                            0.0
+                        };
+                        resampled.push(sample);
+                    }
+                    resampled
+                }
+            } else {
+                data.to_vec()
+            };
+
+            // Calibrated gain, stepped live by the mic quality watch when auto-adjust is on
+            let preprocess = !crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::Preprocessing);
+            let vad = !crate::stage_bypass::bypassed(crate::stage_bypass::PipelineStage::Vad);
+            let gain = mic_gain * audio.gain();
+            if gain != 1.0 && preprocess {
+                crate::sample_format::apply_gain(&mut samples, gain);
+            }
+            audio.check_quality(&samples);
+            // Muted on the headset: nothing to transcribe until it is unmuted
+            if audio.pausing(&samples, captured_ms) && vad {
+                return;
+            }
+            if preprocess {
+                if let Some(cleaned) = audio.denoised(&samples) {
+                    samples = cleaned;
+                }
+            }
+            let learned = audio.learn_threshold(&samples).unwrap_or(silence_threshold);
+            audio.track_talk(&samples, captured_ms, Some(learned));
+            let threshold = if vad { learned } else { 0.0 };
+
+            // Calculate RMS for monitoring only
+            let rms = (samples.iter().map(|&s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+            // DISABLED VAD - Process ALL audio like Python
+            let is_silent = false;
+
+            // Voiced audio in the current utterance (for min_speech_ms filtering)
+            if rms >= threshold {
+                voiced_ms += (samples.len() as u32 * 1000) / pipeline_rate;
+                utterance_capture_ms.get_or_insert(captured_ms);
+            }
+
+            // LED 720: Audio level monitoring (configurable frequency)
+            if enable_breadcrumbs {
+                // Use atomic counter for thread safety and proper initialization
+                use std::sync::atomic::{AtomicU32, Ordering};
+                static AUDIO_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+                let count = AUDIO_COUNTER.fetch_add(1, Ordering::Relaxed);
+                if count % audio_level_log_frequency == 0 {
+                        let trail = BreadcrumbTrail::new("VoskAudio");
+                        trail.light(720, Some(serde_json::json!({
+                            "operation": "VOSK_AUDIO_LEVELS",
+                            "rms": rms,
+                            "silent": is_silent,
+                            "threshold": threshold,
+                            "samples": samples.len()
+                        })));
+                }
+            }
+
+            // CRITICAL FIX: Proper f32 to i16 conversion with clamping to prevent clipping
+            let i16_data: Vec<i16> = samples.iter()
+                .map(|&sample| {
+                    // Clamp to [-1.0, 1.0] range first to prevent overflow
+                    let clamped = sample.clamp(-1.0, 1.0);
+                    // Scale to i16 range
+                    (clamped * 32767.0) as i16
+                })
+                .collect();
+
+            // Debug tap: tee exactly what Vosk receives (no-op unless enabled)
+            if tap_compatible {
+                crate::audio_tap::write_samples(&i16_data);
+            }
+            audio.record(&i16_data, captured_ms);
+
+            // TEMPORARILY DISABLED: Skip processing if VAD says no speech (save CPU)
+            // if is_silent && LAST_PARTIAL.lock().unwrap().is_empty() {
+            //     // No speech detected and no partial result to finalize - skip processing
+            //     return;
+            // }
+
+            // MATCH PYTHON: Process immediately, no buffering!
+            {
+                // Log first audio reception
+                use std::sync::Once;
+                static FIRST_AUDIO: Once = Once::new();
+                FIRST_AUDIO.call_once(|| {
+                    info!("🎤 VOSK: First audio data received! Sample count: {}, RMS: {:.4}", i16_data.len(), rms);
+                });
+
+                // DIRECT PROCESSING LIKE PYTHON - NO BUFFERING
+                // LED 730: Vosk processing
+                if enable_breadcrumbs && i16_data.len() % 100 == 0 {  // Log less frequently
+                    let trail = BreadcrumbTrail::new("VoskProcessing");
+                    trail.light(730, Some(serde_json::json!({
+                        "operation": "VOSK_PROCESSING_AUDIO",
+                        "samples": i16_data.len(),
+                        "rms": rms
+                    })));
+                }
+
+                // PYTHON-LIKE SIMPLE PROCESSING
+                let mut rec = recognizer_clone.lock().unwrap();
+
+                // Pick up endpointing changes made while the stream is running
+                let endpointing_version = ENDPOINTING_VERSION.load(std::sync::atomic::Ordering::Relaxed);
+                if endpointing_version != applied_endpointing_version {
+                    if let Some(settings) = ENDPOINTING_OVERRIDE.lock().unwrap().clone() {
+                        settings.apply_to(&mut rec);
+                        min_speech_ms = settings.min_speech_ms;
+                        info!("🔧 Applied new endpointing settings: {:?}", settings);
+                        current_endpointing = settings;
+                    }
+                    applied_endpointing_version = endpointing_version;
+                }
+
+                // Recognition grammar switched on or off (or timed out) between utterances
+                let grammar_generation = host.grammar_generation();
+                if grammar_generation != applied_grammar_generation && utterance_capture_ms.is_none() {
+                    applied_grammar_generation = grammar_generation;
+                    match build_recognizer(host, &bias_model, &recognizer_settings, &current_endpointing) {
+                        Some(switched) => {
+                            *rec = switched;
+                            grammar_mode = host.recognition_grammar().is_some();
+                        }
+                        None => warn!("⚠️ Failed to rebuild Vosk recognizer for the recognition grammar, keeping current one"),
+                    }
+                }
+
+                // Just call accept_waveform directly with the audio data - exactly like Python!
+                match rec.accept_waveform(&i16_data) {
+                        Ok(state) => {
+                            use vosk::DecodingState;
+                            let state = if crate::chaos::inject(crate::chaos::Fault::VoskFailed) { DecodingState::Failed } else { state };
+
+                            if state == DecodingState::Failed {
+                                // Decoder failure: drop the current utterance and keep the stream running
+                                warn!("⚠️ Vosk decoding failed, resetting recognizer");
+                                host.engine_error("vosk");
+                                rec.reset();
+                                LAST_PARTIAL.lock().unwrap().clear();
+                                voiced_ms = 0;
+                                utterance_capture_ms = None;
+                            } else if state == DecodingState::Finalized {
+                                // Get final result
+                                let result = rec.final_result();
+                        if let CompleteResult::Single(res) = result {
+                                if !res.text.is_empty() && voiced_ms < min_speech_ms {
+                                    info!("🔇 Dropping '{}' - only {}ms voiced (min_speech_ms {})", res.text, voiced_ms, min_speech_ms);
+                                } else if !res.text.is_empty() && grammar_mode {
+                                    // Recognized against a grammar: a result for its purpose, not transcript
+                                    let confidence = (!res.result.is_empty())
+                                        .then(|| res.result.iter().map(|w| w.conf).sum::<f32>() / res.result.len() as f32);
+                                    host.grammar_finished(res.text, confidence);
+                                } else if !res.text.is_empty() {
+                                    // LED 740: Vosk final result
+                                    if enable_breadcrumbs {
+                                        let trail = BreadcrumbTrail::new("VoskResults");
+                                        trail.light(740, Some(serde_json::json!({
+                                            "operation": "VOSK_FINAL_RESULT",
+                                            "text": res.text,
+                                            "length": res.text.len()
+                                        })));
+                                    }
+
+                                    // Decode latency of the buffer that completed the segment
+                                    let latency_ms = crate::transcript_sequencer::capture_ms().saturating_sub(captured_end_ms);
+                                    host.transcribed("vosk", latency_ms);
+
+                                    let text = host.clean_text(&host.punctuate(res.text));
+                                    let text = host.apply_plugins(&text);
+
+                                    // Clear last partial since we finalized
+                                    LAST_PARTIAL.lock().unwrap().clear();
+
+                                    // Emitted once it is next in capture order (provisional until reconciled in two-pass mode)
+                                    let (release_events, release_host) = (events.clone(), callback_host.clone());
+                                    let submitted = text.clone();
+                                    let start_ms = utterance_capture_ms.unwrap_or(captured_ms);
+                                    let audio_ms = host.offset_of(start_ms);
+                                    let speech_ms = voiced_ms as u64;
+                                    let words = timed_words(host, &res.result, start_ms);
+                                    let confidence = crate::transcript_confidence::segment_confidence(&words);
+                                    let word_confidences = crate::transcript_confidence::live_words(&words, |ms| host.offset_of(ms));
+                                    host.submit_final(Engine::Vosk, true, start_ms, captured_end_ms, &submitted, Box::new(move |segment_index, timestamp| {
+                                        let (events, host) = (&*release_events, &*release_host);
+                                        let payload = TranscriptionPayload {
+                                            text: text.clone(),
+                                            is_final: true,
+                                            timestamp,
+                                            is_user: true,  // Microphone input is always from user
+                                            led_number: 8001,  // LED tracking for final transcriptions
+                                            source: "vosk_final".to_string(),
+                                            audio_ms,
+                                            segment_index: Some(segment_index),
+                                            confidence,
+                                            words: word_confidences,
+                                        };
+
+                                        // Emit to frontend with LED tracking
+                                        info!("🎯 LED 8001 - VOSK EMITTING FINAL TRANSCRIPTION: '{}'", text);
+                                        match events.emit("voice_transcription", payload) {
+                                            Ok(_) => info!("✅ LED 8001 - Transcription event emitted successfully"),
+                                            Err(e) => error!("❌ LED 8001 - Failed to emit transcription: {:?}", e),
+                                        }
+                                        host.publish_final(&text, true, None);
+                                        crate::call_analytics::process_final_transcript(events, host, &text, true, speech_ms, start_ms, words);
+                                    }));
+                                }
+                        }
+                        voiced_ms = 0;
+                        utterance_capture_ms = None;
+
+                        // CRITICAL: Reset recognizer state after finalization (if configured)
+                        // This ensures consistent behavior for subsequent speech
+                        if reset_on_finalization {
+                            rec.reset();
+                        }
+
+                        // Sales stage changed: swap in a recognizer biased to the new vocabulary
+                        // (or the recognition grammar was switched, or used up by this utterance)
+                        let stage_generation = crate::sales_stage::generation();
+                        let grammar_generation = host.grammar_generation();
+                        if stage_generation != applied_stage_generation || grammar_generation != applied_grammar_generation {
+                            applied_stage_generation = stage_generation;
+                            applied_grammar_generation = grammar_generation;
+                            match build_recognizer(host, &bias_model, &recognizer_settings, &current_endpointing) {
+                                Some(biased) => {
+                                    *rec = biased;
+                                    grammar_mode = host.recognition_grammar().is_some();
+                                    info!("🧭 Vosk recognizer rebuilt for the current sales stage or recognition grammar");
+                                }
+                                None => warn!("⚠️ Failed to rebuild Vosk recognizer for stage vocabulary, keeping current one"),
+                            }
+                        }
+                    } else {
+                        // Partial result - check if we should emit it
+                        if emit_partials && host.emits_partials(Engine::Vosk, true) {
+                            let partial = rec.partial_result();
+                            let partial_text = partial.partial;
+
+                            let mut last_partial = LAST_PARTIAL.lock().unwrap();
+                            if !partial_text.is_empty() && partial_text != *last_partial {
+                                // LED 750: Vosk partial result
+                                if enable_breadcrumbs {
+                                    let trail = BreadcrumbTrail::new("VoskResults");
+                                    trail.light(750, Some(serde_json::json!({
+                                        "operation": "VOSK_PARTIAL_RESULT",
+                                        "text": partial_text,
+                                        "length": partial_text.len()
+                                    })));
+                                }
+
+                                let start_ms = utterance_capture_ms.unwrap_or(captured_ms);
+                                let payload = TranscriptionPayload {
+                                    text: host.clean_text(partial_text),
+                                    is_final: false,
+                                    timestamp: host.wall_ms(start_ms),
+                                    is_user: true,
+                                    led_number: 8002,  // LED tracking for partial transcriptions
+                                    source: "vosk_partial".to_string(),
+                                    audio_ms: host.offset_of(start_ms),
+                                    segment_index: None,
+                                    confidence: None,
+                                    words: Vec::new(),
+                                };
+
+                                // Update last partial
+                                *last_partial = partial_text.to_string();
+
+                                // Emit partial to frontend with LED tracking
+                                info!("🎙️ LED 8002 - VOSK PARTIAL: '{}'", partial_text);
+                                match events.emit("voice_transcription", payload) {
+                                    Ok(_) => info!("✅ LED 8002 - Partial event emitted"),
+                                    Err(e) => error!("❌ LED 8002 - Failed to emit partial: {:?}", e),
+                            }
+                        }
+                    }
+                    }
+                },
+                Err(e) => {
+                    error!("Failed to accept waveform: {:?}", e);
+                }
+            }
+        }
+        },
+        |err| {
+            error!("Audio stream error: {:?}", err);
+        },
+    ).map_err(|e| format!("Failed to build audio stream: {}", e))))?;
+
+    // Store running state
+    {
+        let mut running = TRANSCRIPTION_RUNNING.lock().unwrap();
+        *running = !gated;
+    }
+
+    info!("✅ Vosk stream {} started successfully{}", stream_id, if gated { " (warm standby)" } else { "" });
+    Ok((stream_id, capture))
+}
+
+pub fn standby_status() -> StandbyStatus {
+    let standby = STANDBY.lock().unwrap();
+    let current = *CURRENT_STREAM_ID.lock().unwrap();
+    let active = standby.as_ref().map_or(false, |s| s.stream_id == current);
+    StandbyStatus {
+        active,
+        listening: active && GATE_OPEN.load(std::sync::atomic::Ordering::SeqCst),
+        entered_at: standby.as_ref().filter(|_| active).map(|s| s.entered_at),
+        auto_exit_minutes: standby.as_ref().filter(|_| active).map(|s| s.auto_exit_minutes),
+        discarded_buffers: DISCARDED_BUFFERS.load(std::sync::atomic::Ordering::Relaxed),
+        pre_roll_seconds: standby.as_ref().filter(|_| active).map_or(0, |s| s.pre_roll_seconds),
+    }
+}
+
+pub fn emit_standby_status(sink: &dyn EventSink) -> StandbyStatus {
+    let status = standby_status();
+    if let Err(e) = sink.emit("transcription_standby", status.clone()) {
+        error!("Failed to emit transcription_standby: {:?}", e);
+    }
+    status
+}
+
+/// Close the gate and drop the standby stream, releasing the microphone
+pub fn release_standby(host: &dyn CaptureHost) {
+    GATE_OPEN.store(false, std::sync::atomic::Ordering::SeqCst);
+    let standby = STANDBY.lock().unwrap().take();
+    if let Some(standby) = standby {
+        *CURRENT_STREAM_ID.lock().unwrap() += 1;
+        *TRANSCRIPTION_RUNNING.lock().unwrap() = false;
+        if !host.close_stream(standby.capture) {
+            warn!("⚠️ Warm standby stream {} was already closed", standby.stream_id);
+        }
+        info!("💤 Warm standby released");
+    }
+}
+
+/// Whether a transcription session is live (standby doesn't count)
+pub fn is_running() -> bool {
+    *TRANSCRIPTION_RUNNING.lock().unwrap()
+}
+
+/// Privacy mute: end transcription and standby and close the mic stream
+pub fn close_capture(sink: &dyn EventSink, host: &dyn CaptureHost) {
+    let had_standby = STANDBY.lock().unwrap().is_some();
+    release_standby(host);
+    GATE_OPEN.store(false, std::sync::atomic::Ordering::SeqCst);
+    *TRANSCRIPTION_RUNNING.lock().unwrap() = false;
+    *CURRENT_STREAM_ID.lock().unwrap() += 1;
+    host.close_streams(STREAM_OWNER);
+    if had_standby {
+        emit_standby_status(sink);
+    }
+}
+
+/// Hold the stream opened (gated) by open_stream as warm standby, left automatically
+/// after `auto_exit_minutes` idle
+pub fn enter_standby(events: Arc<dyn EventSink>, host: Arc<dyn CaptureHost>, stream_id: u32, capture: u64, auto_exit_minutes: u32, pre_roll_seconds: u32) -> StandbyStatus {
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    *STANDBY.lock().unwrap() = Some(StandbyState { stream_id, capture, entered_at: now, idle_since: now, auto_exit_minutes, pre_roll_seconds });
+    DISCARDED_BUFFERS.store(0, std::sync::atomic::Ordering::Relaxed);
+    info!("🔥 Warm standby active (auto-exit after {} idle minutes, {}s in-memory pre-roll)", auto_exit_minutes, pre_roll_seconds);
+
+    let status = emit_standby_status(&*events);
+    spawn_standby_timeout(events, host, stream_id);
+    status
+}
+
+// Leave standby after it has sat idle (gate closed) for too long so the mic isn't held open indefinitely
+fn spawn_standby_timeout(events: Arc<dyn EventSink>, host: Arc<dyn CaptureHost>, stream_id: u32) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+            let expired = {
+                let standby = STANDBY.lock().unwrap();
+                match standby.as_ref() {
+                    Some(s) if s.stream_id == stream_id => {
+                        let idle_ms = (chrono::Utc::now().timestamp_millis() as u64).saturating_sub(s.idle_since);
+                        !GATE_OPEN.load(std::sync::atomic::Ordering::SeqCst)
+                            && idle_ms >= s.auto_exit_minutes as u64 * 60_000
+                    }
+                    _ => return,  // Standby ended or replaced
+                }
+            };
+            if expired {
+                info!("⏲️ Warm standby idle timeout reached");
+                release_standby(&*host);
+                emit_standby_status(&*events);
+                return;
+            }
+        }
+    });
+}
+
+/// Start transcribing on the warm standby stream
+pub fn open_gate(sink: &dyn EventSink) -> StandbyStatus {
+    LAST_PARTIAL.lock().unwrap().clear();
+    GATE_OPEN.store(true, std::sync::atomic::Ordering::SeqCst);
+    *TRANSCRIPTION_RUNNING.lock().unwrap() = true;
+    info!("⚡ Opened warm standby gate - transcription live");
+    emit_standby_status(sink)
+}
+
+/// Stop transcribing: back to warm standby (stream stays live, audio discarded) if it
+/// was entered. Returns whether the stream is kept; otherwise its owner closes it.
+pub fn stop() -> bool {
+    // Force stop all transcription processing
+    {
+        let mut running = TRANSCRIPTION_RUNNING.lock().unwrap();
+        *running = false;
+    }
+
+    GATE_OPEN.store(false, std::sync::atomic::Ordering::SeqCst);
+    let standby = STANDBY.lock().unwrap().as_mut().map(|standby| {
+        standby.idle_since = chrono::Utc::now().timestamp_millis() as u64;
+        info!("💤 Returning to warm standby");
+    });
+
+    // Clear all state immediately
+    {
+        let mut buffer = AUDIO_BUFFER.lock().unwrap();
+        buffer.clear();
+
+        let mut last = LAST_PARTIAL.lock().unwrap();
+        last.clear();
+
+        let mut silence = SILENCE_COUNTER.lock().unwrap();
+        *silence = 0;
+    }
+    standby.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_endpointing_section_keeps_defaults() {
+        let settings: EndpointingSettings = serde_json::from_str(r#"{ "min_silence_ms": 800 }"#).unwrap();
+        assert_eq!(settings.min_silence_ms, 800);
+        assert_eq!(settings.max_utterance_ms, EndpointingSettings::default().max_utterance_ms);
+        assert_eq!(settings.min_speech_ms, EndpointingSettings::default().min_speech_ms);
+        assert!(settings.validate().is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+// Cloud Results Watchdog - half-open detection for streaming transcription sockets
+// On flaky Wi-Fi a WebSocket can go half-open: audio keeps being written but no
+// results come back and no error is raised. The watchdog follows both directions on
+// the session's sent-audio timeline (silence that was skipped is not on it, so only
+// voiced audio counts): what was sent, and how far the engine has answered. When
+// STALL_MS of sent audio goes unanswered the engine reconnects, and the audio sent
+// since the last final result is replayed into the new connection so nothing said
+// while the socket was dead is lost.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+
+// Voiced audio sent without any result before the connection counts as dead
+pub const STALL_MS: u64 = 8_000;
+// Most audio kept for replay
+const REPLAY_MAX_MS: u64 = 30_000;
+
+// Payload of "cloud_reconnected"
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CloudReconnect {
+    pub engine: String,
+    pub unanswered_ms: u64,  // Audio sent without results before the reconnect
+    pub replayed_ms: u64,    // Audio re-sent to the new connection
+}
+
+struct SentChunk {
+    sent_end_ms: u64,
+    capture_ms: u64,
+    samples: Vec<i16>,
+}
+
+/// Sent audio and the results received for it, on the session's sent timeline
+#[derive(Default)]
+pub struct ResultsWatchdog {
+    chunks: VecDeque<SentChunk>,  // Sent since the last final result
+    sent_ms: u64,
+    heard_through_ms: u64,        // End of the latest result of any kind
+    finalized_through_ms: u64,    // End of the latest final result
+}
+
+impl ResultsWatchdog {
+    /// A chunk was sent; `sent_end_ms` is the timeline's sent total after it
+    pub fn sent(&mut self, sent_end_ms: u64, capture_ms: u64, samples: Vec<i16>) {
+        self.sent_ms = sent_end_ms;
+        self.chunks.push_back(SentChunk { sent_end_ms, capture_ms, samples });
+        while self.chunks.front().map_or(false, |c| c.sent_end_ms + REPLAY_MAX_MS <= sent_end_ms) {
+            self.chunks.pop_front();
+        }
+    }
+
+    /// A result covering sent audio up to `end_ms` arrived
+    pub fn result(&mut self, end_ms: u64, is_final: bool) {
+        self.heard_through_ms = self.heard_through_ms.max(end_ms);
+        if is_final {
+            self.finalized_through_ms = self.finalized_through_ms.max(end_ms);
+            let finalized = self.finalized_through_ms;
+            while self.chunks.front().map_or(false, |c| c.sent_end_ms <= finalized) {
+                self.chunks.pop_front();
+            }
+        }
+    }
+
+    /// Sent audio the engine hasn't answered
+    pub fn unanswered_ms(&self) -> u64 {
+        self.sent_ms.saturating_sub(self.heard_through_ms)
+    }
+
+    pub fn stalled(&self) -> bool {
+        self.unanswered_ms() >= STALL_MS
+    }
+
+    /// A new connection starts at `sent_ms`: the audio to replay into it, as
+    /// (capture_ms, samples). Replayed chunks are tracked again as they are re-sent.
+    pub fn reconnected(&mut self, sent_ms: u64) -> Vec<(u64, Vec<i16>)> {
+        self.sent_ms = sent_ms;
+        self.heard_through_ms = sent_ms;
+        self.finalized_through_ms = sent_ms;
+        self.chunks.drain(..).map(|c| (c.capture_ms, c.samples)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_detection_and_replay_from_last_final() {
+        let mut watchdog = ResultsWatchdog::default();
+        for i in 0..5u64 {
+            watchdog.sent((i + 1) * 1_000, 10_000 + i * 1_000, vec![i as i16; 4]);
+        }
+        // Interim results keep the connection alive; the final covers the first 2s
+        watchdog.result(1_500, false);
+        watchdog.result(2_000, true);
+        watchdog.result(4_800, false);
+        assert!(!watchdog.stalled());
+
+        // Half-open: audio keeps going out, nothing comes back
+        for i in 5..13u64 {
+            watchdog.sent((i + 1) * 1_000, 10_000 + i * 1_000, vec![i as i16; 4]);
+        }
+        assert_eq!(watchdog.unanswered_ms(), 13_000 - 4_800);
+        assert!(watchdog.stalled());
+
+        // Everything after the last final is replayed, in capture order
+        let replay = watchdog.reconnected(13_000);
+        assert_eq!(replay.len(), 11);
+        assert_eq!(replay[0].0, 12_000);
+        assert!(!watchdog.stalled());
+    }
+}
@@ -3,14 +3,11 @@ import { smartInvoke, getEnvironmentInfo, isTauriEnvironment } from '../lib/taur
 import { BreadcrumbTrail } from '../lib/breadcrumb-system';
 import { testAudioSimulator } from '../lib/test-audio-simulator';
 import { wavTestMode } from '../lib/wav-test-mode';
+import type { AudioLevels, PerformanceMetrics as BackendPerformanceMetrics } from '../types/bindings';
 // Removed useSystemAudio import - Electron dependency removed
 
-// Types for audio processing data
-export interface AudioLevels {
-  user: number;      // 0-100 percentage
-  prospect: number;  // 0-100 percentage
-  timestamp: number; // milliseconds since start
-}
+// Types for audio processing data (backend payloads come from the generated bindings)
+export type { AudioLevels };
 
 export interface AudioDevice {
   name: string;
@@ -48,12 +45,7 @@ export interface AudioMixerStatus {
   dual_source_mixing: boolean;
 }
 
-export interface PerformanceMetrics {
-  average_latency_ms: number;
-  uptime_seconds: number;
-  total_transcriptions: number;
-  status: string;
-  target_latency_ms: number;
+export interface PerformanceMetrics extends BackendPerformanceMetrics {
   ollama_tokens?: number;
   ollama_over_limit?: boolean;
 }
@@ -0,0 +1,946 @@
+// Source: src-tauri/src/bindings.rs (regenerated by debug builds; `cargo test bindings` checks it)
+// This file has been generated by Specta. DO NOT EDIT.
+
+export type AccelerationBackend = "cuda" | "metal" | "directml" | "cpu"
+
+export type AccelerationReport = { backends: AccelerationBackend[]; gpus: GpuDevice[]; models: WhisperFeasibility[]; recommended_model: WhisperModelSize; recommended_backend: AccelerationBackend; rationale: string[] }
+
+export type Activation = { key_hint: string; activated_at: number; claims?: string; signature?: string }
+
+export type AdaptiveVadSettings = { enabled?: boolean; 
+/**
+ * Audio heard before the learned threshold replaces the fixed one
+ */
+learn_seconds?: number; 
+/**
+ * Threshold above the noise floor
+ */
+margin_db?: number }
+
+export type AnalyticsExport = { path: string; format: ExportFormat; sessions: number }
+
+export type AppAudioTarget = { pid: number; name: string }
+
+export type ArbiterState = "idle" | "starting" | "active"
+
+export type ArbiterStatus = { state: ArbiterState; engine: Engine | null; session_id: string | null; since: number | null }
+
+export type AudioDevice = { name: string; is_input: boolean; is_default: boolean; sample_rate: number; channels: number }
+
+export type AudioLevels = { user: number; prospect: number; timestamp: number }
+
+/**
+ * Raw audio kept for a transcript line
+ */
+export type AudioSnippet = { line: number; offset_ms: number; duration_ms: number; confidence: number; path: string }
+
+export type AudioStatus = { is_recording: boolean; is_processing: boolean; audio_level: number; prospect_level: number; status: string; timestamp: number; sample_rate: number; channels: number; buffer_size: number }
+
+export type AudioTapStatus = { enabled: boolean; directory: string | null; current_file: string | null; file_seconds: number; max_files: number; samples_written: number }
+
+export type BenchmarkContribution = { schema: number; contributor_id: string; industry: string | null; team: string | null; period_days: number; calls: number; talk_ratio: number | null; prospect_questions_per_call: number | null; objections_per_call: number | null; rep_wpm: number | null }
+
+export type BenchmarkOptOut = { deleted_remotely: boolean; message: string | null }
+
+export type BenchmarkPreview = { opted_in: boolean; contribution: BenchmarkContribution | null; payload: string | null; withheld_reason: string | null }
+
+export type BenchmarkSettings = { opted_in?: boolean; 
+/**
+ * One of INDUSTRIES
+ */
+industry?: string | null; 
+/**
+ * Shared within a team for team benchmarks; only its hash is sent
+ */
+team_code?: string | null; 
+/**
+ * Random id of this contributor, created on opt-in
+ */
+contributor_id?: string | null; last_upload_at?: number | null; endpoint?: string }
+
+export type Bookmark = { id: number; offset_ms: number; label: string | null; created_at: number; source: string; prompt_id?: number | null }
+
+/**
+ * Individual breadcrumb entry representing a traced operation
+ */
+export type Breadcrumb = { id: number; name: string; component: string; timestamp: number; duration_ms: number; data: JsonValue | null; success: boolean; error: string | null; stack_trace: string | null }
+
+/**
+ * One page of matching breadcrumbs, newest first
+ */
+export type BreadcrumbPage = { breadcrumbs: Breadcrumb[]; total: number; next_offset: number | null }
+
+/**
+ * Filter for the debug panel: LED range, component, time window, failures only
+ */
+export type BreadcrumbQuery = { led_from?: number | null; led_to?: number | null; component?: string | null; since?: number | null; until?: number | null; last_minutes?: number | null; failures_only?: boolean; offset?: number; limit?: number | null }
+
+export type BriefSource = { provider: string; url: string; excerpt: string }
+
+export type CalendarEvent = { title: string; attendees?: string[] }
+
+export type CalibrationResult = { model: string; model_path: string; chunk_ms: number; chunk_samples: number; estimated_latency_ms: number; target_latency_ms: number; meets_target: boolean; measurements: ChunkMeasurement[]; calibrated_at: string }
+
+export type CalibrationSettings = { target_latency_ms?: number; auto_calibrate_on_first_run?: boolean }
+
+/**
+ * What is known about the call about to start
+ */
+export type CallContext = { calendar_event?: CalendarEvent | null; contact?: CallerInfo | null }
+
+export type CallMetrics = { talk_ratio: TalkRatio; rep_wpm: number | null; prospect_wpm: number | null; objections: number; prospect_questions: number; unanswered_questions: number; checklist_completed: number; checklist_total: number; updated_at: number; interruptions?: number }
+
+export type CallOutcome = "won" | "lost" | "follow_up" | "no_decision"
+
+export type CallRecordingSettings = { enabled?: boolean; 
+/**
+ * Where recordings are written (recordings/ in the app data directory by default)
+ */
+directory?: string | null; 
+/**
+ * Where a recording continues when that directory is full or failing
+ */
+fallback_directory?: string | null; 
+/**
+ * Free space a recording leaves on its disk
+ */
+min_free_mb?: number }
+
+export type CallSummary = { checklist: ChecklistStatus; talk_ratio: TalkRatio; prospect_questions: ProspectQuestion[]; unanswered_questions: number; chapters: TopicChapter[]; key_moments: KeyMoment[] }
+
+export type CallbackStats = { stream: PipelineStream; callbacks: number; overruns: number; last_overrun_at: number | null; buffer_us: number; duration: HistogramSnapshot; jitter: HistogramSnapshot }
+
+/**
+ * Caller metadata a dialer passes with start_session
+ */
+export type CallerInfo = { name?: string | null; phone?: string | null; company?: string | null; crm_id?: string | null; campaign?: string | null; extra?: Partial<{ [key in string]: string }> }
+
+export type CaptureState = { muted: boolean; auto_rearm: boolean; open_streams: number; streams: string[]; changed_at: number | null }
+
+export type CatchUp = { session_id: string; elapsed_ms: number; summary: string | null; summarized_by: string | null; recent: CatchUpTurn[] }
+
+export type CatchUpTurn = { is_user: boolean; offset_ms: number; text: string }
+
+export type ChangelogEntry = { version: string; released_at: string | null; notes: string[] }
+
+export type ChecklistItemCompleted = { item: ChecklistItemStatus; completed: number; total: number }
+
+export type ChecklistItemDef = { id: string; label: string; 
+/**
+ * Phrases that indicate the item was covered (case-insensitive, whole words)
+ */
+phrases: string[] }
+
+export type ChecklistItemStatus = { id: string; label: string; completed: boolean; completed_at: number | null; matched_phrase: string | null; evidence: string | null }
+
+export type ChecklistStatus = { items: ChecklistItemStatus[]; completed: number; total: number; progress: number }
+
+export type ChunkEntry = { index: number; sha256: string; nonce?: string | null }
+
+export type ChunkMeasurement = { model: string; chunk_ms: number; avg_decode_ms: number; p90_decode_ms: number; real_time_factor: number; estimated_latency_ms: number }
+
+export type ClipBenchmark = { name: string; audio_ms: number; decode_ms: number; real_time_factor: number; reference_words: number | null; word_errors: number | null; transcript: string }
+
+/**
+ * Where a session starts on the capture clock and on the wall clock
+ */
+export type ClockAnchor = { capture_ms: number; wall_ms: number }
+
+export type CloudReconnect = { engine: string; unanswered_ms: number; replayed_ms: number }
+
+/**
+ * A transcribed post-call voice note
+ */
+export type CoachNote = { id: number; recorded_at: number; duration_ms: number; text: string }
+
+export type CoachingHistoryEntry = { timestamp: number; transcription: string; suggestion: CoachingSuggestion; source: string; preview_id?: number | null }
+
+export type CoachingProfile = { 
+/**
+ * Seconds before the same rule may prompt again
+ */
+rule_cooldown_secs?: number; 
+/**
+ * Per-rule overrides of rule_cooldown_secs, e.g. "fallback:price" = 120
+ */
+rule_cooldowns?: Partial<{ [key in string]: number }>; 
+/**
+ * Shortest gap between any two prompts
+ */
+min_gap_secs?: number; max_prompts_per_minute?: number; dedup_window_secs?: number; 
+/**
+ * Cosine similarity at which two suggestions count as the same advice
+ */
+dedup_similarity?: number }
+
+export type CoachingSuggestion = { suggestion: string; confidence: number; reasoning: string | null; action_items: string[]; 
+/**
+ * Knowledge passages the suggestion was grounded on (empty when no RAG content was used)
+ */
+citations?: KnowledgeCitation[] }
+
+export type Competitor = { name: string; 
+/**
+ * Other ways the competitor is referred to (product names, abbreviations)
+ */
+aliases?: string[]; 
+/**
+ * Knowledge base document with the battlecard; None = found by name
+ */
+battlecard?: string | null }
+
+export type CompetitorMention = { competitor: string; matched: string; transcript: string; timestamp: number; battlecard: string | null; points: string[] }
+
+export type CompetitorWatchlist = { enabled?: boolean; competitors?: Competitor[]; 
+/**
+ * Counter-positioning points sent with a mention
+ */
+max_points?: number }
+
+export type ConfidenceLevel = "high" | "medium" | "low" | "unknown"
+
+export type ConflictKind = "exclusive_mode_conflict" | "device_unavailable" | "format_not_supported" | "other"
+
+export type ControlInterfaceSettings = { enabled?: boolean; port?: number; token?: string | null }
+
+export type ControlInterfaceStatus = { settings: ControlInterfaceSettings; running: boolean; url: string | null; last_command_at: number | null }
+
+export type ControlSessionStarted = { session_id: string | null; caller: CallerInfo; template_id: string | null }
+
+export type ConversionProgress = { session_id: string; format: SharingFormat; quality: SharingQuality; done_ms: number; duration_ms: number | null; finished: boolean }
+
+export type DecodingGraph = "static" | "lookahead"
+
+export type DeepgramTranscriptionPayload = { text: string; is_final: boolean; timestamp: number; is_user: boolean; audio_ms?: number; speaker?: string | null; speaker_id?: number | null; segment_index?: number | null; confidence?: number | null; words: WordConfidence[] }
+
+export type DeviceConflictEvent = { engine: string; device: string; kind: ConflictKind; requested: StreamConfigInfo; error: string; renegotiated: boolean; fallback: StreamConfigInfo | null; guidance: string }
+
+export type DeviceKind = "usb" | "headset" | "webcam" | "bluetooth" | "bluetooth_hands_free" | "built_in" | "virtual"
+
+export type DeviceRule = { kind?: DeviceKind | null; name_contains?: string | null; action: RuleAction }
+
+export type DeviceSelectionEvent = { device: string | null; previous: string | null; reason: string }
+
+export type DocumentCoachingSuggestion = { suggestion_type: string; confidence: number; content: string; source_document: string; methodology: string | null }
+
+export type DocumentProcessingStats = { total_documents: number; total_chunks: number; processing_time_ms: number; success_rate: number; knowledge_base_size: number }
+
+export type EmailFormat = "markdown" | "plaintext"
+
+export type EmailTemplateSettings = { tone?: string; sender_name?: string | null; sign_off?: string; format?: EmailFormat; max_words?: number; 
+/**
+ * Extra guidance for the model (e.g. "always offer two meeting slots")
+ */
+instructions?: string | null }
+
+export type EndpointingSettings = { min_silence_ms: number; max_utterance_ms: number; min_speech_ms: number }
+
+/**
+ * The live transcription engines
+ */
+export type Engine = "vosk" | "deepgram"
+
+export type EngineHealth = { hardware: HardwareProfile; decision: ModelDecision; model_path: string; model_loaded: boolean; memory_fallback: boolean }
+
+export type EnrichmentProvider = { name: string; 
+/**
+ * Request URL; "{company}" is replaced with the URL-encoded company name
+ */
+url: string; 
+/**
+ * JSON pointer to the text in the response (e.g. "/extract"); None = whole body
+ */
+text_pointer?: string | null; 
+/**
+ * Extra request headers (e.g. a search API key)
+ */
+headers?: Partial<{ [key in string]: string }> }
+
+export type EnrichmentSettings = { enabled?: boolean; providers?: EnrichmentProvider[] }
+
+export type Experiment = { id: string; name: string; variants: ExperimentVariant[]; created_at?: number }
+
+/**
+ * The variant a session was coached with
+ */
+export type ExperimentAssignment = { experiment_id: string; variant_id: string }
+
+export type ExperimentResults = { experiment_id: string; name: string; running: boolean; variants: VariantResults[] }
+
+export type ExperimentSettings = { experiments?: Experiment[]; 
+/**
+ * Experiment new sessions are assigned to
+ */
+running?: string | null }
+
+export type ExperimentVariant = { id: string; name: string; 
+/**
+ * Share of sessions, relative to the other variants
+ */
+weight?: number; 
+/**
+ * Cooldowns and rate limits; None = the install's coaching profile
+ */
+coaching_profile?: CoachingProfile | null; 
+/**
+ * Guidance added to every coaching prompt
+ */
+coaching_focus?: string | null }
+
+export type ExportFormat = "csv" | "parquet"
+
+export type ExportKeyInfo = { public_key: string; signer_fingerprint: string; encryption_key_id: string; decryption_key_ids: string[]; trusted_signers: TrustedSigner[] }
+
+export type ExportManifest = { file_name: string; created_at: number; total_bytes: number; chunk_size: number; plaintext_sha256: string; encryption_key_id?: string | null; signer_public_key?: string | null; chunks: ChunkEntry[] }
+
+export type ExportSecuritySettings = { sign: boolean; encrypt: boolean }
+
+export type ExportVerification = { valid: boolean; signed: boolean; signature_valid: boolean | null; signer_fingerprint: string | null; trusted_signer: string | null; encrypted: boolean; intact: boolean; chunks_total: number; tampered_chunks: number[]; decrypted_to: string | null; problems: string[] }
+
+export type Fault = "device_disappearance" | "channel_saturation" | "vosk_failed" | "network_timeout"
+
+export type FaultInjection = { fault: Fault; 
+/**
+ * Chance that each check fails (0.0 - 1.0)
+ */
+probability?: number; 
+/**
+ * Failures left before the fault disarms itself; None = until cleared
+ */
+remaining?: number | null }
+
+export type FaultStatus = { available: boolean; armed: FaultInjection[]; triggered: ([Fault, number])[] }
+
+export type Feature = "cloud_engines" | "live_docs" | "crm_sync"
+
+export type FileTranscript = { file_path: string; duration_ms: number; processing_time_ms: number; segments: FileTranscriptSegment[] }
+
+export type FileTranscriptSegment = { text: string; start_ms: number; end_ms: number }
+
+export type FilterMode = "mask" | "tag"
+
+export type FollowupEmailDraft = { subject: string; body: string; format: EmailFormat; source: string }
+
+export type GpuDevice = { backend: AccelerationBackend; name: string; vram_mb: number | null; free_vram_mb: number | null }
+
+export type GrammarPurpose = "voice_command" | "practice" | "custom"
+
+export type GrammarResult = { purpose: GrammarPurpose; text: string; matched: string | null; confidence: number | null; timestamp: number }
+
+export type HardwareMuteSettings = { enabled?: boolean; 
+/**
+ * Stop transcribing the rep while muted
+ */
+pause_transcription?: boolean; 
+/**
+ * Digital silence that counts as a mute (stream starts and dropouts are shorter)
+ */
+min_mute_ms?: number }
+
+export type HardwareProfile = { arch: string; cpu_features: string[]; logical_cores: number; total_memory_mb: number | null; available_memory_mb: number | null }
+
+export type HistogramBucket = { upper_us: number; count: number }
+
+export type HistogramSnapshot = { count: number; mean_us: number; max_us: number; p50_us: number; p99_us: number; buckets: HistogramBucket[] }
+
+export type HoldDetectionSettings = { enabled?: boolean; 
+/**
+ * Stop streaming to cloud engines during hold music and dead air
+ */
+pause_cloud?: boolean; min_hold_seconds?: number; min_dead_air_seconds?: number }
+
+export type ImportedSession = { session_id: string; lines: number; duration_ms: number; speakers: string[]; rep_speaker: string | null; recording: string | null; knowledge_document: string | null; warnings: string[] }
+
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+
+export type KeyMoment = { kind: KeyMomentKind; label: string; line: number; offset_ms: number; is_user: boolean; text: string; score: number }
+
+export type KeyMomentKind = "pricing_mentioned" | "decision_maker" | "verbal_commitment" | "negative_spike" | "competitor_mentioned"
+
+export type KnowledgeAnswer = { question: string; answer: string; citations: KnowledgeCitation[]; source: string }
+
+export type KnowledgeBaseStats = { total_documents: number; total_chunks: number; collection_size: number; last_updated: string; health_status: string }
+
+export type KnowledgeCitation = { document: string; section: string | null; chunk_index: number; similarity: number; excerpt: string; source_url?: string | null }
+
+export type KnowledgeDocument = { filename: string; content: string; chunks: string[]; timestamp: number; type: string | null; isAIGenerated: boolean; sourceUrl?: string | null }
+
+export type KnowledgePassage = { document: string; section: string | null; chunk_index: number; text: string; source_url: string | null }
+
+export type KnowledgeSearchResult = { content: string; similarity_score: number; source_document: string; metadata: Partial<{ [key in string]: string }> }
+
+export type KnowledgeValidation = { is_valid: boolean; errors?: string[]; warnings?: string[] }
+
+export type LearnedThreshold = { source: VadSource; seconds_heard: number; noise_floor: number | null; threshold: number | null }
+
+export type LevelCalibration = { microphone?: SourceCalibration | null; system_audio?: SourceCalibration | null }
+
+export type LevelProgress = { source: LevelSource; elapsed_ms: number; total_ms: number; rms: number }
+
+export type LevelSource = "microphone" | "system_audio"
+
+export type LicenseState = "trial" | "trial_expired" | "active" | "grace" | "expired"
+
+export type LicenseStatus = { state: LicenseState; tier: Tier; features: Feature[]; trial_days_left: number | null; grace_days_left: number | null; expires_at: number | null; licensee: string | null; key_hint: string | null; machine_id: string; message: string | null }
+
+export type LineConfidence = { index: number; offset_ms: number; is_user: boolean; text: string; confidence: number | null; level: ConfidenceLevel; words: WordConfidence[] }
+
+export type LiveDocProvider = "google_docs" | "notion"
+
+export type LiveDocSettings = { provider: LiveDocProvider; 
+/**
+ * Google Doc ID, or the Notion page/block ID to append under
+ */
+document_id: string; batch_seconds?: number; include_coaching?: boolean }
+
+export type LiveDocStatus = { configured: boolean; streaming: boolean; provider: LiveDocProvider | null; document_id: string | null; queued_lines: number; sent_lines: number; last_error: string | null }
+
+export type LiveListenStatus = { active: boolean; join_url: string | null; token: string | null; relay_url: string | null; relay_connected: boolean; listeners: number; started_at: number | null }
+
+export type LocaleStatus = { locale: string; preference: string | null; system: string | null; available: string[] }
+
+export type LogConfig = { default_level: string; 
+/**
+ * Per-target overrides, e.g. "voicecoach::vosk_transcription" -> "debug"
+ */
+modules: Partial<{ [key in string]: string }>; log_file: string | null }
+
+export type ManagerSuggestion = { text: string; from: string | null; received_at: number }
+
+export type MemoryBudget = { enabled?: boolean; 
+/**
+ * Whole-process cap (resident memory)
+ */
+process_cap_mb?: number; ring_buffer_kb?: number; transcript_cache_kb?: number; knowledge_index_mb?: number; 
+/**
+ * Fraction of process_cap_mb at which degradation starts
+ */
+pressure_ratio?: number; fallback_to_small_model?: boolean }
+
+export type MemoryItem = { kind: MemoryKind; text: string; session_id: string; said_at: number }
+
+export type MemoryKind = "objection" | "promise" | "preference"
+
+export type MemoryUsage = { process_rss_bytes: number | null; ring_buffer_bytes: number; transcript_cache_bytes: number; knowledge_index_bytes: number; level: PressureLevel; small_model_active: boolean; actions: string[]; budget: MemoryBudget }
+
+export type MetricBenchmark = { metric: string; yours: number | null; team: PeerStats | null; industry: PeerStats | null; everyone: PeerStats | null }
+
+export type MicAdjustment = { issue: MicIssue; advice: string; levels: MicLevels; gain_factor: number; timestamp: number }
+
+export type MicIssue = "clipping" | "quiet_voice" | "noisy"
+
+export type MicLevels = { seconds: number; clipped_share: number; noise_floor: number; speech_level: number; snr_db: number }
+
+export type MicQualitySettings = { enabled?: boolean; 
+/**
+ * Step the live input gain on clipping / a quiet voice
+ */
+auto_adjust_gain?: boolean; min_snr_db?: number; 
+/**
+ * How long a problem must last before it is suggested
+ */
+persist_seconds?: number }
+
+export type MicQualityStatus = { settings: MicQualitySettings; levels: MicLevels | null; gain_factor: number; vad_thresholds: LearnedThreshold[] }
+
+export type ModelBenchmark = { layout: ModelLayout; load_ms: number; memory_mb: number | null; test_set: string | null; audio_ms: number; decode_ms: number; real_time_factor: number; word_error_rate: number | null; accuracy: number | null; clips: ClipBenchmark[]; benchmarked_at: string }
+
+export type ModelDecision = { model_size: ModelSize; recognizer_threads: number; rationale: string[] }
+
+export type ModelLayout = { path: string; graph: DecodingGraph; rescoring: boolean; quantized: boolean; size_mb: number }
+
+export type ModelSize = "small" | "large"
+
+/**
+ * A muted interval (payload of "mic_mute_state" too: no end yet = muted now)
+ */
+export type MutedInterval = { start_ms: number; end_ms: number | null }
+
+export type NoCoachOverride = { at: number; zone_id: string; zone_name: string; matched: string; reason: string; context: CallContext }
+
+export type NoCoachZone = { id: string; name: string; enabled?: boolean; 
+/**
+ * Words or phrases in a calendar event title (whole words, case-insensitive)
+ */
+calendar_keywords?: string[]; 
+/**
+ * Names, phone numbers, companies, CRM ids or e-mail addresses
+ */
+contacts?: string[]; windows?: TimeWindow[] }
+
+export type NoiseProfile = { device: string; bin_hz: number; power: number[]; noise_rms: number; captured_at: number }
+
+export type NoiseSuppressionSettings = { 
+/**
+ * Suppress noise on new calls (needs a profile)
+ */
+enabled?: boolean; 
+/**
+ * Multiple of the noise magnitude subtracted from each bin
+ */
+over_subtraction?: number; 
+/**
+ * Share of a bin's magnitude always kept
+ */
+spectral_floor?: number; profile?: NoiseProfile | null }
+
+export type NotesExport = { session_id: string; path: string | null; text: string; notes: number }
+
+export type ObsSettings = { host?: string; port?: number; password?: string | null; 
+/**
+ * Name of the OBS text source that receives live captions
+ */
+caption_source?: string; 
+/**
+ * Name of the OBS text source that receives the current coaching tip
+ */
+tip_source?: string }
+
+export type ObsStatus = { connected: boolean; host: string | null; port: number | null; caption_source: string | null; tip_source: string | null }
+
+export type Outcome = "replaced" | "confirmed" | "dropped"
+
+export type OutcomeCount = { outcome: CallOutcome; count: number }
+
+export type OutputDeviceInfo = { name: string; is_default: boolean; carries_call: boolean }
+
+export type PastCall = { session_id: string; started_at: number; outcome: CallOutcome | null; topics: string[] }
+
+export type PeerBenchmarks = { fetched_at: number; metrics: MetricBenchmark[] }
+
+export type PeerStats = { contributors: number; p25: number; median: number; p75: number }
+
+export type PerformanceMetrics = { average_latency_ms: number; uptime_seconds: number; total_transcriptions: number; status: string; target_latency_ms: number }
+
+export type PipelineStage = "vad" | "resampler" | "preprocessing" | "loopback_silence"
+
+export type PipelineStream = "vosk_capture" | "deepgram_capture" | "prospect_meter" | "sidetone_output"
+
+export type PlaybackStatus = { path: string | null; playing: boolean; position_ms: number; duration_ms: number; rate: number }
+
+export type Preferences = { calibration?: CalibrationResult | null; url_sources?: UrlSource[]; checklist?: ChecklistItemDef[]; device_rules?: DeviceRule[] | null; export_security?: ExportSecuritySettings; level_calibration?: LevelCalibration; live_doc?: LiveDocSettings | null; profanity_filter?: ProfanitySettings; updates?: UpdateSettings; stage_bias?: StageBiasSettings; memory_budget?: MemoryBudget; punctuation?: PunctuationSettings; silence_skipping?: SilenceSkipSettings; followup_email?: EmailTemplateSettings; privacy?: PrivacySettings; two_pass?: TwoPassMode; enrichment?: EnrichmentSettings; sidetone?: SidetoneSettings; session_templates?: SessionTemplateSettings; competitors?: CompetitorWatchlist; playback_rate?: number | null; locale?: string | null; mic_quality?: MicQualitySettings; coaching_profile?: CoachingProfile; control_interface?: ControlInterfaceSettings; adaptive_vad?: AdaptiveVadSettings; voice_commands?: VoiceCommandSettings; whisper_channel?: WhisperChannelSettings; startup?: StartupOptions; hold_detection?: HoldDetectionSettings; transcript_quality?: TranscriptQualitySettings; benchmarks?: BenchmarkSettings; hardware_mute?: HardwareMuteSettings; snippets?: SnippetSettings; noise_suppression?: NoiseSuppressionSettings; no_coach_zones?: NoCoachZone[]; window_layouts?: WindowPlacement[]; whisper_backend?: WhisperBackendSettings; transcript_plugins?: TranscriptPluginSettings; experiments?: ExperimentSettings; talk_over?: TalkOverSettings; call_recording?: CallRecordingSettings }
+
+export type PressureLevel = "normal" | "elevated" | "critical"
+
+export type PrivacySettings = { 
+/**
+ * Unmute automatically when the next transcription session starts
+ */
+auto_rearm?: boolean; 
+/**
+ * Transcribe and store only the rep's side (one_party)
+ */
+one_party_consent?: boolean }
+
+export type ProcessingStats = { total_documents: number; total_chunks: number; processing_time_ms: number; success_rate: number; knowledge_base_size: number }
+
+export type ProfanitySettings = { enabled?: boolean; mode?: FilterMode; 
+/**
+ * Words never filtered for this workspace (e.g. "damn", product names)
+ */
+allowlist?: string[]; 
+/**
+ * Additional words to filter (whole-word match)
+ */
+extra_words?: string[] }
+
+export type PromptCancelled = { id: number; reason: string }
+
+export type PromptIncoming = { id: number; category: string; urgency: PromptUrgency; timestamp: number }
+
+export type PromptRating = "helpful" | "unhelpful"
+
+export type PromptUrgency = "low" | "medium" | "high"
+
+export type ProspectBrief = { company: string; summary: string; sources: BriefSource[]; generated_at: number; summarized_by: string }
+
+export type ProspectLevel = { rms: number; speaking: boolean; speech_ms: number; timestamp: number }
+
+export type ProspectMemory = { prospect: string; identifiers: string[]; calls: PastCall[]; objections: MemoryItem[]; promises: MemoryItem[]; preferences: MemoryItem[] }
+
+export type ProspectProfile = { name?: string | null; company?: string | null; role?: string | null; notes?: string | null }
+
+export type ProspectQuestion = { id: number; text: string; asked_at: number; answered: boolean; answered_at: number | null; answer: string | null }
+
+export type ProvisionalSegment = { segment_id: number; text: string; start_ms: number; end_ms: number; engine: Engine }
+
+export type PunctuationSettings = { enabled?: boolean; 
+/**
+ * Use the ONNX model instead of the rules (needs the `onnx-punctuation` build)
+ */
+use_model?: boolean; 
+/**
+ * Directory holding model.onnx, vocab.txt (WordPiece) and labels.json
+ */
+model_dir?: string | null }
+
+export type QuietKind = "hold" | "dead_air"
+
+/**
+ * A hold or dead-air period (payload of "quiet_period" too: no end yet = it started)
+ */
+export type QuietPeriod = { kind: QuietKind; start_ms: number; end_ms: number | null }
+
+export type RankedDevice = { name: string; kinds: DeviceKind[]; score: number; excluded: boolean; is_system_default: boolean; selected: boolean; matched_rules: number[] }
+
+export type ReadAloudStatus = { active: boolean; document: string | null; periods: ScriptedPeriod[] }
+
+export type RecognitionGrammar = { phrases: string[]; purpose: GrammarPurpose; utterances_left: number; expires_at: number }
+
+export type Reconciled = { segment_ids: number[]; outcome: Outcome; text: string | null; engine: Engine }
+
+export type RecordingState = "complete" | "degraded" | "incomplete" | "not_recorded"
+
+export type RecordingStorage = { state: RecordingState; sample_rate: number; lost_ms: number; parts: string[]; warnings: RecordingStorageWarning[] }
+
+export type RecordingStorageWarning = { kind: RecordingWarningKind; offset_ms: number; directory: string | null; message: string }
+
+export type RecordingWarningKind = "bitrate_reduced" | "failed_over" | "stopped"
+
+export type Release = { version: string; released_at?: string | null; notes?: string[]; rollout_percentage?: number; download_url?: string | null; sha256?: string | null }
+
+export type RepScorecard = { rep: string; sessions: number; calls: number; talk_minutes: number; avg_rubric_score: number | null; checklist_completion: number | null; avg_talk_ratio: number | null; objections: number; objections_per_call: number | null; calls_with_objections: number; won_with_objections: number; question_answer_rate: number | null; helpful_prompt_share: number | null; outcomes: OutcomeCount[] }
+
+export type RollingSummary = { session_id: string; summary: string; covered_until_ms: number; windows: WindowSummary[]; summarized_by: string; updated_at: number }
+
+export type RubricCriterion = { label: string; metric: RubricMetric; min?: number | null; max?: number | null; weight?: number }
+
+export type RubricMetric = "talk_ratio" | "rep_wpm" | "prospect_wpm" | "checklist_progress" | "prospect_questions" | "unanswered_questions" | "objections"
+
+export type RuleAction = "prefer" | "avoid" | "never"
+
+export type RuleEvaluation = { offset_ms: number; text: string; rule: string; fired: boolean; suppressed_by: string | null }
+
+export type RuleTestReport = { name: string; evaluations: RuleEvaluation[]; failures: string[]; passed: boolean }
+
+export type SalesStage = "opening" | "discovery" | "presentation" | "objection_handling" | "negotiation" | "closing"
+
+export type ScratchNote = { id: number; offset_ms: number; text: string; created_at: number; edited_at?: number | null }
+
+export type ScreenCorner = "top_left" | "top_right" | "bottom_left" | "bottom_right"
+
+export type ScriptedPeriod = { document: string; started_at: number; ended_at: number | null }
+
+export type SealedExport = { bundle_path: string; signed: boolean; encrypted: boolean; chunks: number; signer_fingerprint: string | null; encryption_key_id: string | null }
+
+export type Sentence = { line: number; is_user: boolean; text: string; start_ms: number; end_ms: number; words: number }
+
+export type Session = { id: string; started_at: number; prompts?: SessionPrompt[]; company?: string | null; brief?: ProspectBrief | null; chapters?: TopicChapter[]; metrics?: CallMetrics | null; outcome?: CallOutcome | null; template?: string | null; rubric?: RubricCriterion[]; transcript?: TranscriptLine[]; caller?: CallerInfo | null; bookmarks?: Bookmark[]; notes?: CoachNote[]; recording?: string | null; scratchpad?: ScratchNote[]; summary?: RollingSummary | null; imported_from?: string | null; quiet_periods?: QuietPeriod[]; retranscribed_with?: string | null; muted_intervals?: MutedInterval[]; snippets?: AudioSnippet[]; clock?: ClockAnchor | null; experiment?: ExperimentAssignment | null; recording_storage?: RecordingStorage | null }
+
+export type SessionIndexStatus = { indexed: number; embedded: number; updated: number; unsummarized: number }
+
+export type SessionListing = { id: string; started_at: number; company: string | null; outcome: CallOutcome | null; duration_ms: number; lines: number; has_recording: boolean; imported: boolean; quality: TranscriptQuality | null; retranscribe_suggested: boolean; retranscribe_queued: boolean; retranscribed_with: string | null }
+
+export type SessionPrompt = { id: number; timestamp: number; offset_ms: number; trigger: string; context?: string | null; rule: string; suggestion: CoachingSuggestion; rating?: PromptRating | null }
+
+export type SessionTakeover = { previous_session_id: string | null; previous_engine: Engine; engine: Engine }
+
+export type SessionTemplate = { id: string; name: string; 
+/**
+ * Checklist for the call; empty = the regular checklist
+ */
+checklist?: ChecklistItemDef[]; 
+/**
+ * Guidance added to every coaching prompt
+ */
+coaching_focus?: string | null; 
+/**
+ * Knowledge documents (filenames) coaching draws on; empty = all of them
+ */
+knowledge_documents?: string[]; rubric?: RubricCriterion[] }
+
+export type SessionTemplateSettings = { 
+/**
+ * Saved templates; empty = the built-in ones
+ */
+templates?: SessionTemplate[]; active?: string | null }
+
+export type SettingsProfileReport = { name: string; path: string; created_at: number; app_version: string; sections: string[]; engine_config: boolean; excluded: string[]; signer_fingerprint: string; trusted_signer: string | null }
+
+export type ShareOptions = { pseudonymize: boolean; names: string[]; include_audio: boolean; anonymize_voice: boolean; pitch_semitones: number }
+
+export type SharedSessionExport = { session_id: string; session_file: string; audio_file: string | null; pseudonyms: number; voice_shift_semitones: number | null }
+
+export type SharingCopy = { session_id: string; path: string; format: SharingFormat; quality: SharingQuality; bytes: number; original_bytes: number }
+
+export type SharingFormat = "mp_3" | "opus"
+
+export type SharingQuality = "low" | "medium" | "high"
+
+export type SidetoneSettings = { enabled?: boolean; 
+/**
+ * Playback level of the monitored mic (1.0 = as captured)
+ */
+gain?: number; muted?: boolean; 
+/**
+ * Output device name; None = the default output device
+ */
+output_device?: string | null; 
+/**
+ * Most mic audio queued for playback before the backlog is dropped
+ */
+max_latency_ms?: number }
+
+export type SidetoneStatus = { settings: SidetoneSettings; active: boolean; source: string | null; output_device: string | null }
+
+export type SilenceSkipSettings = { enabled?: boolean; 
+/**
+ * RMS threshold override; None = the source's level calibration
+ */
+threshold?: number | null; 
+/**
+ * Silence streamed after speech before the gate closes
+ */
+hangover_ms?: number; 
+/**
+ * Silence sent ahead of speech when the gate opens
+ */
+pad_ms?: number }
+
+export type SimilarSession = { session_id: string; started_at: number; company: string | null; score: number; summary: string; moment_ms: number | null; matched_by: string }
+
+export type SnippetSettings = { enabled?: boolean; 
+/**
+ * Longest snippet kept per line
+ */
+snippet_seconds?: number; 
+/**
+ * Lines whose mean word confidence is below this keep their audio
+ */
+below_confidence?: number; 
+/**
+ * Audio retained over all sessions; the oldest snippets go first
+ */
+max_total_seconds?: number }
+
+export type SourceCalibration = { device: string; noise_floor: number; speech_level: number; peak: number; clipped_ratio: number; gain: number; vad_threshold: number; warnings: string[]; calibrated_at: number }
+
+export type SpeakerInfo = { speaker_id: number; label: string; name: string | null; display_name: string; talk_seconds: number; segments: number; counted: boolean }
+
+export type SpeakersUpdate = { estimated_count: number; speakers: SpeakerInfo[] }
+
+export type StageBiasSettings = { enabled?: boolean; 
+/**
+ * Also bias Vosk via a recognizer grammar. Grammars constrain recognition to the
+ * listed phrases plus [unk], so this only suits small (dynamic graph) models.
+ */
+vosk_grammar?: boolean; keyword_boost?: number; stages?: StageVocabulary[] }
+
+export type StageStatus = { stage: SalesStage; previous: SalesStage | null; terms: string[]; generation: number; manual: boolean }
+
+export type StageVocabulary = { stage: SalesStage; 
+/**
+ * Phrases that indicate the call is in this stage (whole words, case-insensitive)
+ */
+cues: string[]; 
+/**
+ * Terms the recognizer should favour while in this stage
+ */
+terms: string[] }
+
+export type StandbyStatus = { active: boolean; listening: boolean; entered_at: number | null; auto_exit_minutes: number | null; discarded_buffers: number; pre_roll_seconds: number }
+
+export type StartupDeviceCheck = { input_device: string | null; system_audio_device: string | null; output_device: string | null; problems: string[] }
+
+export type StartupOptions = { 
+/**
+ * Start hidden in the tray (the window opens from the tray menu)
+ */
+start_minimized?: boolean; 
+/**
+ * Enter warm transcription standby with the preloaded model
+ */
+standby_capture?: boolean; auto_check_devices?: boolean; 
+/**
+ * Registered with the OS to start at login
+ */
+launch_at_login?: boolean }
+
+export type StoredKey = { id: string; key: string; created_at: number; imported?: boolean }
+
+export type StoredKnowledgeDocument = { filename: string; content: string; chunks: string[]; timestamp: number; doc_type: string | null; is_ai_generated: boolean }
+
+export type StreamConfigInfo = { sample_rate: number; channels: number; buffer_size: number | null }
+
+export type TalkOverNudge = { offset_ms: number; prospect_speaking_ms: number; interruptions: number }
+
+export type TalkOverSensitivity = "low" | "medium" | "high"
+
+export type TalkOverSettings = { enabled?: boolean; sensitivity?: TalkOverSensitivity; 
+/**
+ * Seconds after a nudge before the next one (interruptions are still counted)
+ */
+cooldown_seconds?: number }
+
+export type TalkRatio = { rep_words: number; prospect_words: number; scripted_words: number; rep_share: number; rep_speech_ms?: number; prospect_speech_ms?: number; held_words?: number }
+
+export type TeamMember = { name: string; 
+/**
+ * Folder of the rep's session files; None = this machine's sessions
+ */
+sessions_dir?: string | null }
+
+export type TeamReport = { generated_at: number; from: number | null; to: number | null; team: RepScorecard; reps: RepScorecard[]; json_path: string; markdown_path: string }
+
+export type TelemetrySettings = { enabled?: boolean; 
+/**
+ * OTLP/HTTP base URL; /v1/metrics and /v1/traces are appended
+ */
+endpoint?: string; export_interval_secs?: number; traces?: boolean; 
+/**
+ * Extra request headers (e.g. collector auth)
+ */
+headers?: Partial<{ [key in string]: string }>; 
+/**
+ * Reported as service.instance.id (defaults to the host name)
+ */
+instance_id?: string | null }
+
+export type TelemetryStatus = { enabled: boolean; endpoint: string; exports: number; last_error: string | null }
+
+export type Tier = "free" | "pro" | "team"
+
+export type TimeWindow = { 
+/**
+ * Days the window applies, 0 = Monday; every day when empty
+ */
+days?: number[]; start: string; end: string }
+
+export type TimelineEntry = { kind: TimelineKind; offset_ms: number; text: string; is_user: boolean | null; note_id: number | null; end_ms: number | null; key_moment?: KeyMomentKind | null }
+
+export type TimelineKind = "transcript" | "note" | "hold" | "dead_air" | "muted" | "key_moment"
+
+export type TopicChapter = { index: number; label: string; start_ms: number; end_ms: number; first_line: number; last_line: number }
+
+export type TranscriptConfidence = { session_id: string; mean_confidence: number | null; low_lines: number; lines: LineConfidence[] }
+
+export type TranscriptFormat = "text" | "json" | "vtt"
+
+export type TranscriptLine = { offset_ms: number; is_user: boolean; text: string; words?: TranscriptWord[] }
+
+export type TranscriptMatch = { line: number; is_user: boolean; text: string; matched: string; start_ms: number; end_ms: number; word_level: boolean; note?: number | null }
+
+export type TranscriptPlugin = { name: string; size_bytes: number; enabled: boolean; error: string | null }
+
+export type TranscriptPluginSettings = { 
+/**
+ * Plugin files run on final segments, in this order
+ */
+enabled?: string[]; 
+/**
+ * Plugins (in order) per workspace, by session template id; others use `enabled`
+ */
+workspaces?: Partial<{ [key in string]: string[] }>; 
+/**
+ * Time budget of each plugin per segment
+ */
+budget_ms?: number }
+
+export type TranscriptQuality = { score: number; dictionary_ratio: number; mean_confidence: number | null; fragmentation: number; poor: boolean }
+
+export type TranscriptQualitySettings = { 
+/**
+ * Score below which a transcript is poor (0-1)
+ */
+poor_below?: number; 
+/**
+ * Queue poor finished calls that have a recording for re-transcription
+ */
+auto_retranscribe?: boolean }
+
+export type TranscriptStreamFormat = "txt" | "jsonl"
+
+export type TranscriptStreamStatus = { session_id: string; path: string; format: TranscriptStreamFormat; lines_written: number; live: boolean }
+
+/**
+ * A transcript word, timed from the session start
+ */
+export type TranscriptWord = { word: string; start_ms: number; end_ms: number; confidence?: number | null }
+
+export type TranscriptionPayload = { text: string; is_final: boolean; timestamp: number; is_user: boolean; led_number: number; source: string; audio_ms?: number; segment_index?: number | null; confidence?: number | null; words: WordConfidence[] }
+
+export type TrustedSigner = { name: string; public_key: string; fingerprint: string }
+
+export type TwoPassMode = { enabled?: boolean; 
+/**
+ * Engine whose partials (and provisional finals) are shown immediately
+ */
+partials?: Engine; 
+/**
+ * Engine whose finals replace the provisional segments
+ */
+finals?: Engine; 
+/**
+ * How long a provisional segment waits for the finals engine before it is kept
+ */
+final_wait_ms?: number }
+
+export type UpdateSettings = { auto_check?: boolean; deferred_version?: string | null; deferred_until?: number | null }
+
+export type UpdateState = "up_to_date" | "available" | "deferred" | "error"
+
+export type UpdateStatus = { state: UpdateState; current_version: string; latest: Release | null; changelog: ChangelogEntry[]; held_back: string | null; deferred_until: number | null; checked_at: number; error: string | null }
+
+export type UrlSource = { url: string; title: string; refresh_hours: number | null; last_fetched: number; last_error: string | null }
+
+export type UsagePeriod = { streamed_seconds: number; skipped_seconds: number; estimated_cost_usd: number; estimated_savings_usd: number; savings_percent: number }
+
+export type UsageReport = { silence_skipping: boolean; session_started_at: number | null; session: UsagePeriod; since_launch: UsagePeriod }
+
+export type VadSource = "microphone" | "system_audio"
+
+export type VariantResults = { variant_id: string; name: string; sessions: number; prompts: number; prompts_per_session: number; rated_prompts: number; helpful_prompts: number; helpful_rate: number | null; outcomes: number; won: number; lost: number; follow_up: number; no_decision: number; win_rate: number | null; helpful_rate_z: number | null; win_rate_z: number | null }
+
+export type VoiceAction = "bookmark" | "show_battlecard" | "pause_coaching" | "resume_coaching"
+
+export type VoiceCommand = { action: VoiceAction; phrase: string; competitor: string | null; ok: boolean; detail: string | null; timestamp: number }
+
+export type VoiceCommandInfo = { settings: VoiceCommandSettings; phrases: string[] }
+
+export type VoiceCommandSettings = { enabled?: boolean; wake_word?: string }
+
+export type VoiceNoteProgress = { session_id: string; elapsed_ms: number; max_ms: number; rms: number }
+
+export type Waveform = { session_id: string; start_ms: number; end_ms: number; duration_ms: number; bucket_ms: number; min: number[]; max: number[] }
+
+export type WhisperBackendChoice = { settings: WhisperBackendSettings; model: WhisperModelSize; device: AccelerationBackend; realtime: boolean; fallback: string | null }
+
+export type WhisperBackendSettings = { 
+/**
+ * None = the recommended size
+ */
+model?: WhisperModelSize | null; 
+/**
+ * None = the fastest device the model fits on
+ */
+device?: AccelerationBackend | null }
+
+export type WhisperChannelSettings = { enabled?: boolean; 
+/**
+ * Output device name; must not be a device carrying the call
+ */
+output_device?: string | null; volume?: number; 
+/**
+ * Chime when a coaching prompt is about to appear
+ */
+prompt_cues?: boolean; 
+/**
+ * Chime when a manager sends a private suggestion (live listen)
+ */
+manager_cues?: boolean }
+
+export type WhisperFeasibility = { model: WhisperModelSize; required_mb: number; backend: AccelerationBackend | null; realtime: boolean }
+
+export type WhisperModelSize = "tiny" | "base" | "small" | "medium" | "large"
+
+/**
+ * Where a window was in one monitor arrangement
+ */
+export type WindowPlacement = { window: string; arrangement: string; monitor: string; x: number; y: number; rel_x: number; rel_y: number; stored_at: number }
+
+export type WindowSummary = { start_ms: number; end_ms: number; summary: string }
+
+export type WordConfidence = { word: string; start_ms: number; end_ms: number; confidence: number | null; level: ConfidenceLevel }
+
+export type ZoneMatch = { zone_id: string; zone_name: string; matched: string }
+